@@ -47,6 +47,7 @@ impl RdfFusionBenchContext {
                 verbose_results: false,
                 target_partitions: Some(target_partitions),
                 memory_size: None,
+                rand_seed: None,
             },
             data_dir: Mutex::new(data_dir),
             bench_files_dir: PathBuf::from("./bench_files"),
@@ -83,7 +84,9 @@ impl RdfFusionBenchContext {
                 .build_arc()
                 .expect("Only setting memory limit"),
         };
-        Store::new_with_datafusion_config(config, runtime_enc)
+        let store = Store::new_with_datafusion_config(config, runtime_enc);
+        store.set_rand_seed(self.options.rand_seed);
+        store
     }
 
     /// Creates a new folder in the results directory and uses it until [Self::pop_dir] is
@@ -37,6 +37,9 @@ pub struct BenchmarkingOptions {
     pub target_partitions: Option<usize>,
     /// The number of MiBs that DataFusion is allowed to Suse.
     pub memory_size: Option<usize>,
+    /// The seed of the SPARQL `RAND` function. If set, `RAND` produces the same values in each
+    /// run, which makes the results of different runs comparable.
+    pub rand_seed: Option<u64>,
 }
 
 /// Executes an `operation` of a given `benchmark`.
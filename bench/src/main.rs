@@ -13,6 +13,7 @@ async fn main() -> anyhow::Result<()> {
         verbose_results: args.verbose_results,
         target_partitions: args.target_partitions,
         memory_size: args.memory_limit.map(|val| 1024 * 1024 * val),
+        rand_seed: args.rand_seed,
     };
     execute_benchmark_operation(options, args.operation, args.benchmark).await?;
     Ok(())
@@ -33,6 +34,9 @@ pub struct RdfFusionBenchArgs {
     /// Defines how much memory DataFusion is allowed to use. In MiB.
     #[arg(short, long)]
     pub memory_limit: Option<usize>,
+    /// Seeds the SPARQL `RAND` function such that it produces the same values in each run.
+    #[arg(long)]
+    pub rand_seed: Option<u64>,
     /// Indicates which benchmark should be executed.
     #[clap(subcommand)]
     pub benchmark: BenchmarkName,
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand, ValueHint};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug, Clone)]
@@ -37,6 +38,50 @@ pub enum Command {
         /// This is equivalent as setting the union-default-graph option in all SPARQL queries
         #[arg(long)]
         union_default_graph: bool,
+        /// Number of seconds that in-flight requests may take to finish after a shutdown signal
+        /// (SIGINT or SIGTERM) has been received
+        #[arg(long, default_value_t = 30)]
+        shutdown_timeout: u64,
+        /// Enables the administrative endpoints under `/admin` (e.g., storage diagnostics)
+        ///
+        /// These endpoints expose internals of the store and should only be reachable by
+        /// operators.
+        #[arg(long)]
+        admin: bool,
+        /// Maximum length (in bytes) of a SPARQL query that is sent via GET
+        ///
+        /// Longer queries are rejected with "414 URI Too Long" and must be sent via POST.
+        #[arg(long, default_value_t = 8 * 1024)]
+        max_get_query_length: usize,
+        /// Maximum size (in bytes) of a SPARQL update that is sent to the update endpoint
+        ///
+        /// Larger updates are rejected with "413 Payload Too Large". The size of other request
+        /// bodies (e.g., RDF data) is not limited.
+        #[arg(long, default_value_t = 128 * 1024 * 1024)]
+        max_update_size: usize,
+        /// Directory from which SPARQL updates may LOAD files
+        ///
+        /// Can be repeated. Clients can read any file within these directories. By default,
+        /// loading files is rejected.
+        #[arg(long = "load-directory", value_hint = ValueHint::DirPath)]
+        load_directories: Vec<PathBuf>,
+        /// Maximum number of SPARQL queries that are evaluated concurrently
+        ///
+        /// By default, the number of concurrent queries is not limited. Queries that exceed the
+        /// limit wait for a running query to finish (see --query-queue-timeout) unless
+        /// --reject-excess-queries is set.
+        #[arg(long)]
+        max_concurrent_queries: Option<NonZeroUsize>,
+        /// Number of seconds that a query waits for a running query to finish if the
+        /// --max-concurrent-queries limit is reached
+        ///
+        /// Queries that cannot start in time are rejected with "503 Service Unavailable".
+        #[arg(long, default_value_t = 60)]
+        query_queue_timeout: u64,
+        /// Immediately rejects queries that exceed the --max-concurrent-queries limit with
+        /// "503 Service Unavailable" instead of queuing them
+        #[arg(long, requires = "max_concurrent_queries")]
+        reject_excess_queries: bool,
     },
     /// Convert RDF serializations from one format into another
     Convert {
@@ -48,6 +93,7 @@ pub enum Command {
         /// The format of the file(s) to convert from
         ///
         /// It can be an extension like "nt" or a MIME type like "application/n-triples".
+        /// JSON-LD input ("jsonld" or "application/ld+json") may only use embedded contexts.
         ///
         /// By default the format is guessed from the input file extension.
         #[arg(long, required_unless_present = "from_file")]
@@ -73,6 +119,13 @@ pub enum Command {
         /// Attempt to keep converting even if the data file is invalid
         #[arg(long)]
         lenient: bool,
+        /// Skip the validation of IRIs and other syntax checks while parsing for a higher
+        /// throughput
+        ///
+        /// Only use this option for trusted data. Invalid IRIs are not detected and end up in
+        /// the output.
+        #[arg(long)]
+        lenient_iris: bool,
         /// Only load the given named graph from the input file
         ///
         /// By default all graphs are loaded.
@@ -86,5 +139,23 @@ pub enum Command {
         /// By default the default graph is used.
         #[arg(long, value_hint = ValueHint::Url)]
         to_graph: Option<String>,
+        /// Write human-readable RDF/XML
+        ///
+        /// The triples of each subject are grouped into a single node element that is named after
+        /// the type of the subject, and prefixes are declared for all used namespaces. This
+        /// requires buffering the entire graph in memory. Only supported for RDF/XML output.
+        #[arg(long)]
+        pretty: bool,
+    },
+    /// Print the logical and the physical plan of a SPARQL query
+    Explain {
+        /// The SPARQL query to explain
+        query: String,
+        /// Files to load into the store before planning the query
+        ///
+        /// The format of the files is guessed from their extensions. Some plans (e.g., the
+        /// chosen index of a scan) depend on the data in the store.
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        file: Vec<PathBuf>,
     },
 }
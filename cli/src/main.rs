@@ -4,15 +4,16 @@ use anyhow::{Context, bail};
 use clap::Parser;
 use datafusion::execution::runtime_env::RuntimeEnvBuilder;
 use datafusion::prelude::SessionConfig;
-use rdf_fusion::io::{RdfFormat, RdfParser, RdfSerializer};
-use rdf_fusion::model::{GraphName, NamedNode};
+use rdf_fusion::io::{PrettyRdfXmlSerializer, RdfFormat, RdfParser, RdfSerializer};
+use rdf_fusion::model::{GraphName, NamedNode, Quad};
 use rdf_fusion::store::Store;
-use rdf_fusion_web::ServerConfig;
+use rdf_fusion_web::{ExcessQueryBehavior, QueryConcurrencyLimit, ServerConfig};
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{self, BufWriter, Read, Write, stdin, stdout};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
+use std::time::Duration;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
@@ -37,6 +38,14 @@ pub async fn main() -> anyhow::Result<()> {
             bind,
             cors,
             union_default_graph,
+            shutdown_timeout,
+            admin,
+            max_get_query_length,
+            max_update_size,
+            load_directories,
+            max_concurrent_queries,
+            query_queue_timeout,
+            reject_excess_queries,
         } => {
             let runtime_env = match matches.runtime.memory_limit {
                 None => RuntimeEnvBuilder::default().build_arc()?,
@@ -48,7 +57,33 @@ pub async fn main() -> anyhow::Result<()> {
                 SessionConfig::from_env()?,
                 runtime_env,
             );
-            serve(store, &bind, false, cors, union_default_graph).await
+            let query_concurrency_limit =
+                max_concurrent_queries.map(|max_concurrent_queries| {
+                    QueryConcurrencyLimit {
+                        max_concurrent_queries,
+                        excess_queries: if reject_excess_queries {
+                            ExcessQueryBehavior::Reject
+                        } else {
+                            ExcessQueryBehavior::Queue {
+                                timeout: Duration::from_secs(query_queue_timeout),
+                            }
+                        },
+                    }
+                });
+            serve(
+                store,
+                &bind,
+                false,
+                cors,
+                union_default_graph,
+                Duration::from_secs(shutdown_timeout),
+                admin,
+                max_get_query_length,
+                max_update_size,
+                load_directories,
+                query_concurrency_limit,
+            )
+            .await
         }
         Command::Convert {
             from_file,
@@ -58,9 +93,11 @@ pub async fn main() -> anyhow::Result<()> {
             to_format,
             to_base,
             lenient,
+            lenient_iris,
             from_graph,
             from_default_graph,
             to_graph,
+            pretty,
         } => {
             let from_format = if let Some(format) = from_format {
                 rdf_format_from_name(&format)?
@@ -75,6 +112,9 @@ pub async fn main() -> anyhow::Result<()> {
                     .with_base_iri(&base)
                     .with_context(|| format!("Invalid base IRI {base}"))?;
             }
+            if lenient_iris {
+                parser = parser.lenient();
+            }
 
             let to_format = if let Some(format) = to_format {
                 rdf_format_from_name(&format)?
@@ -83,6 +123,9 @@ pub async fn main() -> anyhow::Result<()> {
             } else {
                 bail!("The --to-format option must be set when writing to stdout")
             };
+            if pretty && to_format != RdfFormat::RdfXml {
+                bail!("The --pretty option is only supported for RDF/XML output")
+            }
             let serializer = RdfSerializer::from_format(to_format);
 
             let from_graph = if let Some(from_graph) = from_graph {
@@ -118,6 +161,7 @@ pub async fn main() -> anyhow::Result<()> {
                     &from_graph,
                     &to_graph,
                     to_base.as_deref(),
+                    pretty,
                 )?),
                 (Some(from_file), None) => do_convert(
                     parser,
@@ -128,6 +172,7 @@ pub async fn main() -> anyhow::Result<()> {
                     &from_graph,
                     &to_graph,
                     to_base.as_deref(),
+                    pretty,
                 )?
                 .flush(),
                 (None, Some(to_file)) => close_file_writer(do_convert(
@@ -139,6 +184,7 @@ pub async fn main() -> anyhow::Result<()> {
                     &from_graph,
                     &to_graph,
                     to_base.as_deref(),
+                    pretty,
                 )?),
                 (None, None) => do_convert(
                     parser,
@@ -149,11 +195,25 @@ pub async fn main() -> anyhow::Result<()> {
                     &from_graph,
                     &to_graph,
                     to_base.as_deref(),
+                    pretty,
                 )?
                 .flush(),
             }?;
             Ok(())
         }
+        Command::Explain { query, file } => {
+            let store = Store::default();
+            for file in file {
+                let format = rdf_format_from_path(&file)?;
+                store
+                    .load_from_reader(format, File::open(&file)?)
+                    .await
+                    .with_context(|| format!("Failed to load {}", file.display()))?;
+            }
+            let explanation = store.context().explain_query(&query).await?;
+            writeln!(stdout().lock(), "{explanation}")?;
+            Ok(())
+        }
     }
 }
 
@@ -167,15 +227,55 @@ fn do_convert<R: Read, W: Write>(
     from_graph: &Option<GraphName>,
     default_graph: &GraphName,
     to_base: Option<&str>,
+    pretty: bool,
 ) -> anyhow::Result<W> {
     let mut parser = parser.for_reader(reader);
     let first = parser.next(); // We read the first element to get prefixes and the base IRI
-    if let Some(base_iri) = to_base.or_else(|| parser.base_iri()) {
+    let base_iri = to_base.or_else(|| parser.base_iri()).map(str::to_owned);
+    let prefixes = parser
+        .prefixes()
+        .map(|(prefix_name, prefix_iri)| (prefix_name.to_owned(), prefix_iri.to_owned()))
+        .collect::<Vec<_>>();
+    let quads =
+        first
+            .into_iter()
+            .chain(parser)
+            .filter_map(|quad_result| match quad_result {
+                Ok(quad) => select_graph(quad, from_graph, default_graph).map(Ok),
+                Err(e) => {
+                    if lenient {
+                        eprintln!("Parsing error: {e}");
+                        None
+                    } else {
+                        Some(Err(e))
+                    }
+                }
+            });
+
+    if pretty {
+        let mut serializer = PrettyRdfXmlSerializer::new();
+        if let Some(base_iri) = &base_iri {
+            serializer = serializer
+                .with_base_iri(base_iri)
+                .with_context(|| format!("Invalid base IRI: {base_iri}"))?;
+        }
+        for (prefix_name, prefix_iri) in &prefixes {
+            serializer = serializer
+                .with_prefix(prefix_name, prefix_iri)
+                .with_context(|| {
+                    format!("Invalid IRI for prefix {prefix_name}: {prefix_iri}")
+                })?;
+        }
+        let quads = quads.collect::<Result<Vec<_>, _>>()?;
+        return Ok(serializer.serialize_to_writer(quads, writer)?);
+    }
+
+    if let Some(base_iri) = &base_iri {
         serializer = serializer
             .with_base_iri(base_iri)
             .with_context(|| format!("Invalid base IRI: {base_iri}"))?;
     }
-    for (prefix_name, prefix_iri) in parser.prefixes() {
+    for (prefix_name, prefix_iri) in &prefixes {
         serializer = serializer
             .with_prefix(prefix_name, prefix_iri)
             .with_context(|| {
@@ -183,33 +283,32 @@ fn do_convert<R: Read, W: Write>(
             })?;
     }
     let mut serializer = serializer.for_writer(writer);
-    for quad_result in first.into_iter().chain(parser) {
-        match quad_result {
-            Ok(mut quad) => {
-                if let Some(from_graph) = from_graph {
-                    if quad.graph_name == *from_graph {
-                        quad.graph_name = GraphName::DefaultGraph;
-                    } else {
-                        continue;
-                    }
-                }
-                if quad.graph_name.is_default_graph() {
-                    quad.graph_name = default_graph.clone();
-                }
-                serializer.serialize_quad(&quad)?;
-            }
-            Err(e) => {
-                if lenient {
-                    eprintln!("Parsing error: {e}");
-                } else {
-                    return Err(e.into());
-                }
-            }
-        }
+    for quad in quads {
+        serializer.serialize_quad(&quad?)?;
     }
     Ok(serializer.finish()?)
 }
 
+/// Applies the `--from-graph` and `--to-graph` options to `quad`. Returns [None] if the quad is
+/// not in the selected graph.
+fn select_graph(
+    mut quad: Quad,
+    from_graph: &Option<GraphName>,
+    default_graph: &GraphName,
+) -> Option<Quad> {
+    if let Some(from_graph) = from_graph {
+        if quad.graph_name == *from_graph {
+            quad.graph_name = GraphName::DefaultGraph;
+        } else {
+            return None;
+        }
+    }
+    if quad.graph_name.is_default_graph() {
+        quad.graph_name = default_graph.clone();
+    }
+    Some(quad)
+}
+
 fn format_from_path<T>(
     path: &Path,
     from_extension: impl FnOnce(&str) -> anyhow::Result<T>,
@@ -245,12 +344,19 @@ fn rdf_format_from_name(name: &str) -> anyhow::Result<RdfFormat> {
     bail!("The file format '{name}' is unknown")
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn serve(
     store: Store,
     bind: &str,
     read_only: bool,
     cors: bool,
     union_default_graph: bool,
+    shutdown_timeout: Duration,
+    admin: bool,
+    max_get_query_length: usize,
+    max_update_size: usize,
+    load_directories: Vec<PathBuf>,
+    query_concurrency_limit: Option<QueryConcurrencyLimit>,
 ) -> anyhow::Result<()> {
     let server_config = ServerConfig {
         store,
@@ -258,6 +364,12 @@ async fn serve(
         read_only,
         cors,
         union_default_graph,
+        shutdown_timeout,
+        admin,
+        max_get_query_length,
+        max_update_size: Some(max_update_size),
+        query_concurrency_limit,
+        load_directories,
     };
     rdf_fusion_web::serve(server_config).await
 }
@@ -269,3 +381,40 @@ fn close_file_writer(writer: BufWriter<File>) -> io::Result<()> {
     file.flush()?;
     file.sync_all()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_web::{
+        DEFAULT_MAX_GET_QUERY_LENGTH, DEFAULT_MAX_UPDATE_SIZE,
+        DEFAULT_QUERY_QUEUE_TIMEOUT, DEFAULT_SHUTDOWN_TIMEOUT,
+    };
+
+    /// The defaults of the arguments are duplicated, as the build script cannot depend on the web
+    /// crate. This test ensures that they do not diverge.
+    #[test]
+    fn serve_defaults_match_web_defaults() {
+        let args = Args::try_parse_from(["rdf-fusion", "serve"]).unwrap();
+        let Command::Serve {
+            shutdown_timeout,
+            max_get_query_length,
+            max_update_size,
+            query_queue_timeout,
+            ..
+        } = args.command
+        else {
+            panic!("Expected the serve command");
+        };
+
+        assert_eq!(
+            Duration::from_secs(shutdown_timeout),
+            DEFAULT_SHUTDOWN_TIMEOUT
+        );
+        assert_eq!(max_get_query_length, DEFAULT_MAX_GET_QUERY_LENGTH);
+        assert_eq!(max_update_size, DEFAULT_MAX_UPDATE_SIZE);
+        assert_eq!(
+            Duration::from_secs(query_queue_timeout),
+            DEFAULT_QUERY_QUEUE_TIMEOUT
+        );
+    }
+}
@@ -119,7 +119,7 @@ impl ScalarSparqlOp for ContainsSpidermanSparqlOp {
                         };
                         Ok(TypedValueRef::BooleanLiteral(result.into()))
                     },
-                    || ThinError::expected(),
+                    ThinError::expected,
                 )
             },
         ))
@@ -12,7 +12,7 @@ use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
 use datafusion::prelude::SessionConfig;
 use rdf_fusion::api::RdfFusionContextView;
-use rdf_fusion::api::storage::QuadStorage;
+use rdf_fusion::api::storage::{QuadStorage, StorageOptimizationReport};
 use rdf_fusion::encoding::object_id::ObjectIdMapping;
 use rdf_fusion::encoding::plain_term::{PlainTermArrayElementBuilder, PlainTermEncoding};
 use rdf_fusion::encoding::{EncodingArray, QuadStorageEncoding};
@@ -107,7 +107,7 @@ impl VecQuadStorage {
             }
             subject.append_term(quad.subject.as_ref().into());
             predicate.append_term(quad.predicate.as_ref().into());
-            object.append_term(quad.object.as_ref().into());
+            object.append_term(quad.object.as_ref());
         }
 
         let graph_name = graph_name.finish();
@@ -211,8 +211,8 @@ impl QuadStorage for VecQuadStorage {
         Ok(self.0.len())
     }
 
-    async fn optimize(&self) -> Result<(), StorageError> {
-        Ok(())
+    async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError> {
+        Ok(StorageOptimizationReport::default())
     }
 
     async fn validate(&self) -> Result<(), StorageError> {
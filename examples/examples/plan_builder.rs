@@ -17,7 +17,7 @@ use std::sync::Arc;
 pub async fn main() -> anyhow::Result<()> {
     // Create a new in-memory instance
     let config = SessionConfig::default();
-    let storage = create_storage(&config);
+    let storage = create_storage(&config)?;
     let engine =
         RdfFusionContext::new(config, RuntimeEnvBuilder::default().build_arc()?, storage);
 
@@ -67,10 +67,14 @@ pub async fn main() -> anyhow::Result<()> {
 }
 
 /// Creates a new in-memory storage.
-fn create_storage(config: &SessionConfig) -> Arc<MemQuadStorage> {
+fn create_storage(config: &SessionConfig) -> anyhow::Result<Arc<MemQuadStorage>> {
     let mapping = Arc::new(MemObjectIdMapping::default());
     let encoding = Arc::new(ObjectIdEncoding::new(
         Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
     ));
-    Arc::new(MemQuadStorage::new(mapping, encoding, config.batch_size()))
+    Ok(Arc::new(MemQuadStorage::new(
+        mapping,
+        encoding,
+        config.batch_size(),
+    )?))
 }
@@ -1,7 +1,7 @@
 use crate::TermEncoding;
 use crate::encoding::EncodingArray;
 use crate::object_id::ObjectIdEncoding;
-use datafusion::arrow::array::{Array, ArrayRef, UInt32Array};
+use datafusion::arrow::array::{Array, ArrayRef};
 use datafusion::common::exec_err;
 use rdf_fusion_model::DFResult;
 use std::sync::Arc;
@@ -18,10 +18,16 @@ impl ObjectIdArray {
     ///
     /// # Errors
     ///
-    /// Returns an error if the data type of `value` is unexpected.
+    /// Returns an error if the data type of `value` does not match the object id width of the
+    /// `encoding`.
     pub fn try_new(encoding: Arc<ObjectIdEncoding>, array: ArrayRef) -> DFResult<Self> {
         if array.data_type() != encoding.data_type() {
-            return exec_err!("Expected array with ObjectIdEncoding, got {:?}", array);
+            return exec_err!(
+                "Expected array with ObjectIdEncoding ({}, {}), got {}",
+                encoding.object_id_size(),
+                encoding.data_type(),
+                array.data_type()
+            );
         }
         Ok(Self::new_unchecked(encoding, array))
     }
@@ -31,12 +37,10 @@ impl ObjectIdArray {
         Self { encoding, inner }
     }
 
-    /// Returns a reference to the inner [UInt32Array].
-    pub fn object_ids(&self) -> &UInt32Array {
-        self.inner
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .expect("Checked in constructor")
+    /// Returns the inner array of object ids. Its data type is the data type of the
+    /// [ObjectIdEncoding] (e.g., `UInt32` for 4-byte object ids).
+    pub fn object_ids(&self) -> &dyn Array {
+        self.inner.as_ref()
     }
 }
 
@@ -1,8 +1,8 @@
 use crate::EncodingName;
 use crate::encoding::TermEncoding;
 use crate::object_id::{
-    ObjectIdArray, ObjectIdMapping, ObjectIdMappingError, ObjectIdMappingRef,
-    ObjectIdScalar, ObjectIdSize,
+    ObjectIdArray, ObjectIdCreationError, ObjectIdMapping, ObjectIdMappingError,
+    ObjectIdMappingRef, ObjectIdScalar, ObjectIdSize,
 };
 use crate::plain_term::{PlainTermArray, PlainTermScalar};
 use datafusion::arrow::array::ArrayRef;
@@ -56,26 +56,47 @@ pub type ObjectIdEncodingRef = Arc<ObjectIdEncoding>;
 ///
 /// The equality and hashing functions check for pointer equality of the underlying mapping.
 ///
-/// # Current Limitation
+/// # Object ID Size
 ///
-/// Currently, this id is fixed to being a 32-bit integer. However, we have an
-/// [issue](https://github.com/tobixdev/rdf-fusion/issues/50) that tracks the progress on limiting
-/// this limitation.
+/// The width of the object ids is taken from the [`ObjectIdSize`] of the mapping and arrays or
+/// scalars with a different width are rejected. Currently, 4-byte (`UInt32`) and 8-byte
+/// (`UInt64`) object ids are supported.
 #[derive(Debug, Clone)]
 pub struct ObjectIdEncoding {
     /// The number of bytes in a single object id.
     object_id_size: ObjectIdSize,
+    /// The Arrow data type of a single object id. Derived from `object_id_size`.
+    data_type: DataType,
     /// The mapping that is used to encode and decode object ids.
     mapping: Arc<dyn ObjectIdMapping>,
 }
 
 impl ObjectIdEncoding {
     /// Creates a new [ObjectIdEncoding].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`ObjectIdSize`] of the `mapping` is not supported. See [Self::try_new] for
+    /// a fallible version.
     pub fn new(mapping: Arc<dyn ObjectIdMapping>) -> Self {
-        Self {
-            object_id_size: mapping.object_id_size(),
+        Self::try_new(mapping).expect("Unsupported object id size.")
+    }
+
+    /// Tries to create a new [ObjectIdEncoding].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [`ObjectIdSize`] of the `mapping` is not supported.
+    pub fn try_new(
+        mapping: Arc<dyn ObjectIdMapping>,
+    ) -> Result<Self, ObjectIdCreationError> {
+        let object_id_size = mapping.object_id_size();
+        let data_type = object_id_size.data_type().ok_or(ObjectIdCreationError)?;
+        Ok(Self {
+            object_id_size,
+            data_type,
             mapping,
-        }
+        })
     }
 
     /// Returns the size of the object id.
@@ -96,15 +117,8 @@ impl ObjectIdEncoding {
         scalar: &PlainTermScalar,
     ) -> Result<ObjectIdScalar, ObjectIdMappingError> {
         let object_id = self.mapping.encode_scalar(scalar)?;
-        let bytes = object_id
-            .as_bytes()
-            .try_into()
-            .expect("Currently only 4-byte object ids are supported.");
-        Ok(ObjectIdScalar::try_new(
-            Arc::clone(self),
-            ScalarValue::UInt32(Some(u32::from_be_bytes(bytes))),
-        )
-        .unwrap())
+        Ok(ObjectIdScalar::from_object_id(Arc::clone(self), object_id)
+            .expect("Mapping returns object ids of the configured size."))
     }
 
     /// Encodes a [`PlainTermArray`] into an [`ObjectIdArray`].
@@ -115,7 +129,8 @@ impl ObjectIdEncoding {
         array: &PlainTermArray,
     ) -> Result<ObjectIdArray, ObjectIdMappingError> {
         self.mapping.encode_array(array).map(|oids| {
-            ObjectIdArray::try_new(Arc::clone(self), Arc::new(oids) as ArrayRef).unwrap()
+            ObjectIdArray::try_new(Arc::clone(self), oids)
+                .expect("Mapping returns object ids of the configured size.")
         })
     }
 }
@@ -129,7 +144,7 @@ impl TermEncoding for ObjectIdEncoding {
     }
 
     fn data_type(&self) -> &DataType {
-        &DataType::UInt32
+        &self.data_type
     }
 
     fn try_new_array(self: &Arc<Self>, array: ArrayRef) -> DFResult<Self::Array> {
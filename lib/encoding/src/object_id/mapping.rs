@@ -2,7 +2,7 @@ use crate::object_id::{ObjectId, ObjectIdScalar, ObjectIdSize};
 use crate::plain_term::{PlainTermArray, PlainTermScalar};
 use crate::typed_value::{TypedValueArray, TypedValueEncodingRef, TypedValueScalar};
 use crate::{EncodingArray, EncodingScalar};
-use datafusion::arrow::array::UInt32Array;
+use datafusion::arrow::array::{Array, ArrayRef};
 use datafusion::arrow::error::ArrowError;
 use datafusion::error::DataFusionError;
 use rdf_fusion_model::{CorruptionError, StorageError};
@@ -20,6 +20,8 @@ pub enum ObjectIdMappingError {
     LiteralAsGraphName,
     #[error("An unknown object ID was encountered in an unexpected place.")]
     UnknownObjectId,
+    #[error("All object IDs of the configured size have been allocated.")]
+    ObjectIdsExhausted,
     #[error("An error occurred while accessing the object id storage.")]
     Storage(Box<dyn Error + Sync + Send>),
 }
@@ -81,12 +83,15 @@ pub trait ObjectIdMapping: Debug + Send + Sync {
         scalar: &PlainTermScalar,
     ) -> Result<Option<ObjectId>, ObjectIdMappingError>;
 
-    /// Encodes the entire `array` as an [`UInt32Array`]. Automatically creates a mapping for a
+    /// Encodes the entire `array` as an array of object ids. Automatically creates a mapping for a
     /// fresh object id if a term is not yet mapped.
+    ///
+    /// The data type of the result is given by the [`ObjectIdSize`] of the mapping (e.g., `UInt32`
+    /// for 4-byte object ids).
     fn encode_array(
         &self,
         array: &PlainTermArray,
-    ) -> Result<UInt32Array, ObjectIdMappingError>;
+    ) -> Result<ArrayRef, ObjectIdMappingError>;
 
     /// Encodes a single `scalar` as an [ObjectIdScalar]. Automatically creates a mapping for a
     /// fresh object id if the term is not yet mapped.
@@ -98,22 +103,22 @@ pub trait ObjectIdMapping: Debug + Send + Sync {
             .to_array(1)
             .expect("Data type is supported for to_array");
         let encoded = self.encode_array(&array)?;
-        let object_id = ObjectId::try_new_from_array(&encoded, 0)
+        let object_id = ObjectId::try_new_from_array(encoded.as_ref(), 0)
             .expect("Encoding does not return null values");
         Ok(object_id)
     }
 
-    /// Decodes the entire `array` as a [PlainTermArray].
+    /// Decodes the entire `array` of object ids as a [PlainTermArray].
     fn decode_array(
         &self,
-        array: &UInt32Array,
+        array: &dyn Array,
     ) -> Result<PlainTermArray, ObjectIdMappingError>;
 
-    /// Decodes the entire `array` as a [TypedValueArray].
+    /// Decodes the entire `array` of object ids as a [TypedValueArray].
     fn decode_array_to_typed_value(
         &self,
         encoding: &TypedValueEncodingRef,
-        array: &UInt32Array,
+        array: &dyn Array,
     ) -> Result<TypedValueArray, ObjectIdMappingError>;
 
     /// Decodes a single `scalar` as a [PlainTermScalar].
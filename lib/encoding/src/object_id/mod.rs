@@ -4,7 +4,8 @@ mod mapping;
 mod scalar;
 
 pub use array::*;
-use datafusion::arrow::array::{Array, UInt32Array};
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{DataType, UInt32Type, UInt64Type};
 pub use encoding::*;
 pub use mapping::*;
 pub use scalar::*;
@@ -22,6 +23,20 @@ pub struct ObjectIdSize(i32);
 #[error("Invalid object id size.")]
 pub struct ObjectIdCreationError;
 
+impl ObjectIdSize {
+    /// Returns the Arrow [`DataType`] that is used for representing object ids of this size.
+    ///
+    /// Returns [`None`] if object ids of this size are not supported. Currently, 4-byte
+    /// (`UInt32`) and 8-byte (`UInt64`) object ids are supported.
+    pub fn data_type(self) -> Option<DataType> {
+        match self.0 {
+            4 => Some(DataType::UInt32),
+            8 => Some(DataType::UInt64),
+            _ => None,
+        }
+    }
+}
+
 impl TryFrom<i32> for ObjectIdSize {
     type Error = ObjectIdCreationError;
 
@@ -77,12 +92,35 @@ impl ObjectId {
         })
     }
 
-    /// Creates a new [`ObjectId`].
-    pub fn try_new_from_array(array: &UInt32Array, index: usize) -> Option<Self> {
-        array.is_valid(index).then(|| ObjectId {
-            size: 4,
-            slice: Box::new(array.value(index).to_be_bytes()),
-        })
+    /// Creates a new [`ObjectId`] from the element at `index` of an `UInt32` or `UInt64` array.
+    ///
+    /// Returns [`None`] if the element is null or if the array has a different data type.
+    pub fn try_new_from_array(array: &dyn Array, index: usize) -> Option<Self> {
+        if array.is_null(index) {
+            return None;
+        }
+
+        match array.data_type() {
+            DataType::UInt32 => Some(ObjectId {
+                size: 4,
+                slice: Box::new(
+                    array
+                        .as_primitive::<UInt32Type>()
+                        .value(index)
+                        .to_be_bytes(),
+                ),
+            }),
+            DataType::UInt64 => Some(ObjectId {
+                size: 8,
+                slice: Box::new(
+                    array
+                        .as_primitive::<UInt64Type>()
+                        .value(index)
+                        .to_be_bytes(),
+                ),
+            }),
+            _ => None,
+        }
     }
 
     /// Returns the length of the object id in bytes.
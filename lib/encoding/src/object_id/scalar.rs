@@ -16,14 +16,16 @@ impl ObjectIdScalar {
     ///
     /// # Errors
     ///
-    /// Returns an error if the data type of `value` is unexpected.
+    /// Returns an error if the data type of `value` does not match the object id width of the
+    /// `encoding`.
     pub fn try_new(
         encoding: Arc<ObjectIdEncoding>,
         value: ScalarValue,
     ) -> DFResult<Self> {
         if &value.data_type() != encoding.data_type() {
             return exec_err!(
-                "Expected scalar value with ObjectID encoding. Expected: {:?}, got {:?}",
+                "Expected scalar value with ObjectID encoding ({}). Expected: {:?}, got {:?}",
+                encoding.object_id_size(),
                 encoding.data_type(),
                 value.data_type()
             );
@@ -36,9 +38,10 @@ impl ObjectIdScalar {
         Self { encoding, inner }
     }
 
-    /// Creates a new [ObjectIdScalar] from the given `object_id`.
+    /// Creates a new null [ObjectIdScalar].
     pub fn null(encoding: Arc<ObjectIdEncoding>) -> Self {
-        let scalar = ScalarValue::UInt32(None);
+        let scalar = ScalarValue::try_from(encoding.data_type())
+            .expect("Object id data types have a null scalar");
         Self::new_unchecked(encoding, scalar)
     }
 
@@ -47,12 +50,20 @@ impl ObjectIdScalar {
         encoding: Arc<ObjectIdEncoding>,
         object_id: ObjectId,
     ) -> Result<Self, ObjectIdCreationError> {
-        let bytes = object_id
-            .as_bytes()
-            .try_into()
-            .map_err(|_| ObjectIdCreationError)?;
-        let value = u32::from_be_bytes(bytes);
-        let scalar = ScalarValue::UInt32(Some(value));
+        if object_id.size() != i32::from(encoding.object_id_size()) {
+            return Err(ObjectIdCreationError);
+        }
+
+        let bytes = object_id.as_bytes();
+        let scalar = match bytes.len() {
+            4 => ScalarValue::UInt32(Some(u32::from_be_bytes(
+                bytes.try_into().map_err(|_| ObjectIdCreationError)?,
+            ))),
+            8 => ScalarValue::UInt64(Some(u64::from_be_bytes(
+                bytes.try_into().map_err(|_| ObjectIdCreationError)?,
+            ))),
+            _ => return Err(ObjectIdCreationError),
+        };
         Ok(Self::new_unchecked(encoding, scalar))
     }
 }
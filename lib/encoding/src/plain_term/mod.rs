@@ -4,10 +4,12 @@ pub mod decoders;
 mod element_builder;
 pub mod encoders;
 mod encoding;
+mod quads;
 mod scalar;
 
 pub use array::*;
 pub use builder::*;
 pub use element_builder::*;
 pub use encoding::*;
+pub use quads::*;
 pub use scalar::*;
@@ -0,0 +1,218 @@
+use crate::QuadStorageEncoding;
+use crate::encoding::TermDecoder;
+use crate::plain_term::PlainTermArray;
+use crate::plain_term::decoders::DefaultPlainTermDecoder;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::common::{exec_datafusion_err, exec_err, plan_err};
+use rdf_fusion_model::quads::{COL_GRAPH, COL_OBJECT, COL_PREDICATE, COL_SUBJECT};
+use rdf_fusion_model::{
+    DFResult, GraphNameRef, NamedNodeRef, NamedOrBlankNodeRef, QuadRef, TermRef,
+};
+use std::sync::Arc;
+
+/// A [RecordBatch] of quads where each component is stored in the
+/// [PlainTermEncoding](crate::plain_term::PlainTermEncoding).
+///
+/// The schema of the batch must match the quad schema of [QuadStorageEncoding::PlainTerm]. The
+/// default graph is encoded as a null value in the graph column.
+#[derive(Debug, Clone)]
+pub struct PlainTermQuadBatch {
+    /// The graph names.
+    graph: PlainTermArray,
+    /// The subjects.
+    subject: PlainTermArray,
+    /// The predicates.
+    predicate: PlainTermArray,
+    /// The objects.
+    object: PlainTermArray,
+}
+
+impl PlainTermQuadBatch {
+    /// Tries to create a new [PlainTermQuadBatch] from the given `batch`.
+    ///
+    /// Returns an error if the schema of the batch does not match the plain term quad schema.
+    pub fn try_new(batch: &RecordBatch) -> DFResult<Self> {
+        let expected = QuadStorageEncoding::PlainTerm.quad_schema();
+        let actual = batch.schema();
+
+        if actual.fields().len() != expected.fields().len() {
+            return plan_err!(
+                "Expected {} columns for a quad batch, got {}.",
+                expected.fields().len(),
+                actual.fields().len()
+            );
+        }
+
+        for (expected, actual) in expected.fields().iter().zip(actual.fields().iter()) {
+            if expected.name() != actual.name()
+                || expected.data_type() != actual.data_type()
+            {
+                return plan_err!(
+                    "Expected column '{}' with the plain term encoding, got column '{}' of type {}.",
+                    expected.name(),
+                    actual.name(),
+                    actual.data_type()
+                );
+            }
+        }
+
+        let column = |name: &str| -> DFResult<PlainTermArray> {
+            let column = batch
+                .column_by_name(name)
+                .ok_or_else(|| exec_datafusion_err!("Missing column '{name}'."))?;
+            PlainTermArray::try_from(Arc::clone(column))
+        };
+
+        Ok(Self {
+            graph: column(COL_GRAPH)?,
+            subject: column(COL_SUBJECT)?,
+            predicate: column(COL_PREDICATE)?,
+            object: column(COL_OBJECT)?,
+        })
+    }
+
+    /// Returns an iterator over the quads in this batch.
+    ///
+    /// The quads borrow from the underlying arrays. Hence, no model terms are allocated. Returns an
+    /// error for rows that do not represent a valid quad (e.g., a literal in the subject position).
+    pub fn quads(&self) -> impl Iterator<Item = DFResult<QuadRef<'_>>> {
+        DefaultPlainTermDecoder::decode_terms(&self.graph)
+            .zip(DefaultPlainTermDecoder::decode_terms(&self.subject))
+            .zip(DefaultPlainTermDecoder::decode_terms(&self.predicate))
+            .zip(DefaultPlainTermDecoder::decode_terms(&self.object))
+            .map(|(((graph, subject), predicate), object)| {
+                let graph_name = match graph {
+                    Err(_) => GraphNameRef::DefaultGraph,
+                    Ok(TermRef::NamedNode(node)) => GraphNameRef::NamedNode(node),
+                    Ok(TermRef::BlankNode(node)) => GraphNameRef::BlankNode(node),
+                    Ok(TermRef::Literal(_)) => {
+                        return exec_err!("A literal cannot be used as a graph name.");
+                    }
+                };
+                let subject: NamedOrBlankNodeRef<'_> = match subject {
+                    Ok(TermRef::NamedNode(node)) => node.into(),
+                    Ok(TermRef::BlankNode(node)) => node.into(),
+                    Ok(TermRef::Literal(_)) => {
+                        return exec_err!("A literal cannot be used as a subject.");
+                    }
+                    Err(_) => return exec_err!("The subject of a quad cannot be null."),
+                };
+                let predicate: NamedNodeRef<'_> = match predicate {
+                    Ok(TermRef::NamedNode(node)) => node,
+                    Ok(_) => return exec_err!("The predicate must be a named node."),
+                    Err(_) => {
+                        return exec_err!("The predicate of a quad cannot be null.");
+                    }
+                };
+                let Ok(object) = object else {
+                    return exec_err!("The object of a quad cannot be null.");
+                };
+
+                Ok(QuadRef::new(subject, predicate, object, graph_name))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EncodingArray;
+    use crate::plain_term::PlainTermArrayElementBuilder;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rdf_fusion_model::LiteralRef;
+
+    #[test]
+    fn test_quads_from_batch() {
+        let ex = NamedNodeRef::new_unchecked("http://example.com/ex");
+        let graph = NamedNodeRef::new_unchecked("http://example.com/graph");
+        let literal = LiteralRef::new_simple_literal("value");
+
+        let mut graphs = PlainTermArrayElementBuilder::default();
+        graphs.append_null();
+        graphs.append_named_node(graph);
+        let batch = create_batch(
+            graphs.finish(),
+            named_nodes(&[ex, ex]),
+            named_nodes(&[ex, ex]),
+            {
+                let mut builder = PlainTermArrayElementBuilder::default();
+                builder.append_literal(literal);
+                builder.append_named_node(ex);
+                builder.finish()
+            },
+        );
+
+        let quad_batch = PlainTermQuadBatch::try_new(&batch).unwrap();
+        let quads = quad_batch.quads().collect::<DFResult<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            quads,
+            vec![
+                QuadRef::new(ex, ex, literal, GraphNameRef::DefaultGraph),
+                QuadRef::new(ex, ex, ex, graph),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_literal_subject_is_error() {
+        let ex = NamedNodeRef::new_unchecked("http://example.com/ex");
+
+        let mut graphs = PlainTermArrayElementBuilder::default();
+        graphs.append_null();
+        let mut subjects = PlainTermArrayElementBuilder::default();
+        subjects.append_literal(LiteralRef::new_simple_literal("value"));
+        let batch = create_batch(
+            graphs.finish(),
+            subjects.finish(),
+            named_nodes(&[ex]),
+            named_nodes(&[ex]),
+        );
+
+        let quad_batch = PlainTermQuadBatch::try_new(&batch).unwrap();
+        let result = quad_batch.quads().collect::<DFResult<Vec<_>>>();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_wrong_schema_is_error() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            COL_SUBJECT,
+            DataType::Utf8,
+            false,
+        )]));
+        let batch = RecordBatch::new_empty(schema);
+
+        let result = PlainTermQuadBatch::try_new(&batch);
+
+        assert!(result.is_err());
+    }
+
+    fn named_nodes(nodes: &[NamedNodeRef<'_>]) -> PlainTermArray {
+        let mut builder = PlainTermArrayElementBuilder::default();
+        for node in nodes {
+            builder.append_named_node(*node);
+        }
+        builder.finish()
+    }
+
+    fn create_batch(
+        graph: PlainTermArray,
+        subject: PlainTermArray,
+        predicate: PlainTermArray,
+        object: PlainTermArray,
+    ) -> RecordBatch {
+        let schema = QuadStorageEncoding::PlainTerm.quad_schema();
+        RecordBatch::try_new(
+            Arc::clone(schema.inner()),
+            vec![
+                graph.into_array_ref(),
+                subject.into_array_ref(),
+                predicate.into_array_ref(),
+                object.into_array_ref(),
+            ],
+        )
+        .unwrap()
+    }
+}
@@ -54,6 +54,20 @@ impl SortableTermArrayBuilder {
         self.append(SortableTermType::String, None, value.as_bytes())
     }
 
+    /// Appends a language-tagged string.
+    ///
+    /// Language-tagged strings are ordered like simple literals by their lexical value. The
+    /// language tag is only used as a tiebreak for equal lexical values. To this end, the language
+    /// tag is appended to the lexical value, separated by a NUL byte. As a result, a simple
+    /// literal sorts before language-tagged strings with the same lexical value.
+    pub fn append_language_string(&mut self, value: &str, language: &str) {
+        let mut bytes = Vec::with_capacity(value.len() + language.len() + 1);
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(language.as_bytes());
+        self.append(SortableTermType::String, None, &bytes)
+    }
+
     pub fn append_date_time(&mut self, value: DateTime) {
         self.append(
             SortableTermType::DateTime,
@@ -31,7 +31,7 @@ impl TermEncoder<SortableTermEncoding> for TypedValueRefSortableTermEncoder {
                     }
                     TypedValueRef::SimpleLiteral(v) => builder.append_string(v.value),
                     TypedValueRef::LanguageStringLiteral(v) => {
-                        builder.append_string(v.value)
+                        builder.append_language_string(v.value, v.language)
                     }
                     TypedValueRef::DateTimeLiteral(v) => builder.append_date_time(v),
                     TypedValueRef::TimeLiteral(v) => builder.append_time(v),
@@ -60,3 +60,48 @@ impl TermEncoder<SortableTermEncoding> for TypedValueRefSortableTermEncoder {
         self.encode_terms([term])?.try_as_scalar(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sortable_term::encoding::SortableTermEncodingField;
+    use datafusion::arrow::array::AsArray;
+    use rdf_fusion_model::{LanguageStringRef, SimpleLiteralRef};
+
+    #[test]
+    fn test_language_strings_sort_by_value_then_language() {
+        let terms = [
+            TypedValueRef::LanguageStringLiteral(LanguageStringRef {
+                value: "b",
+                language: "de",
+            }),
+            TypedValueRef::LanguageStringLiteral(LanguageStringRef {
+                value: "a",
+                language: "en",
+            }),
+            TypedValueRef::LanguageStringLiteral(LanguageStringRef {
+                value: "a",
+                language: "de",
+            }),
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef { value: "a" }),
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef { value: "ab" }),
+        ];
+
+        let array = TypedValueRefSortableTermEncoder
+            .encode_terms(terms.into_iter().map(Ok))
+            .unwrap();
+        let bytes = array
+            .array()
+            .as_struct()
+            .column(SortableTermEncodingField::Bytes.index())
+            .as_binary::<i32>()
+            .iter()
+            .map(Option::unwrap)
+            .collect::<Vec<_>>();
+
+        // "a" < "a"@de < "a"@en < "ab" < "b"@de
+        let mut order = (0..bytes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| bytes[*i]);
+        assert_eq!(order, vec![3, 2, 1, 4, 0]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::TermEncoding;
 use crate::encoding::EncodingArray;
+use crate::typed_value::encoding::validate_type_ids;
 use crate::typed_value::{
     TypedValueEncoding, TypedValueEncodingField, TypedValueEncodingRef,
 };
@@ -8,7 +9,7 @@ use datafusion::arrow::array::{
     GenericStringArray, Int16Array, Int32Array, Int64Array, UnionArray,
 };
 use datafusion::arrow::datatypes::{
-    Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    DataType, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
 };
 use datafusion::common::exec_err;
 use rdf_fusion_model::DFResult;
@@ -24,7 +25,17 @@ pub struct TypedValueArray {
 }
 
 impl TypedValueArray {
+    /// Tries to create a new [`TypedValueArray`] from a regular [`ArrayRef`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array contains a type family that is unknown to the encoding or if
+    /// the data type of `array` is unexpected.
     pub fn try_new(encoding: TypedValueEncodingRef, array: ArrayRef) -> DFResult<Self> {
+        if let DataType::Union(fields, _) = array.data_type() {
+            validate_type_ids(fields)?;
+        }
+
         if array.data_type() != encoding.data_type() {
             return exec_err!(
                 "Expected scalar value with TypedValueEncoding, got {:?}",
@@ -186,3 +197,50 @@ pub struct OtherLiteralParts<'data> {
     pub value: &'data GenericStringArray<i32>,
     pub datatype: &'data GenericStringArray<i32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{StringArray, new_empty_array};
+    use datafusion::arrow::buffer::ScalarBuffer;
+    use datafusion::arrow::datatypes::{Field, UnionFields};
+
+    #[test]
+    fn test_try_new_with_unknown_type_family() {
+        let unknown_type_id = 15;
+        let known_fields = TypedValueEncoding::fields();
+        let mut type_ids = known_fields.iter().map(|(id, _)| id).collect::<Vec<_>>();
+        let mut fields = known_fields
+            .iter()
+            .map(|(_, field)| field.as_ref().clone())
+            .collect::<Vec<_>>();
+        type_ids.push(unknown_type_id);
+        fields.push(Field::new("geo_point", DataType::Utf8, false));
+
+        let mut children = known_fields
+            .iter()
+            .map(|(_, field)| new_empty_array(field.data_type()))
+            .collect::<Vec<_>>();
+        children.push(Arc::new(StringArray::from(vec!["POINT(1 2)"])));
+
+        let array = UnionArray::try_new(
+            UnionFields::new(type_ids, fields),
+            ScalarBuffer::from(vec![unknown_type_id]),
+            Some(ScalarBuffer::from(vec![0])),
+            children,
+        )
+        .unwrap();
+
+        let encoding = Arc::new(TypedValueEncoding::new());
+        let error = TypedValueArray::try_new(encoding, Arc::new(array))
+            .err()
+            .unwrap();
+        assert!(
+            error.to_string().contains(
+                "Cannot decode field 'geo_point' with the TypedValueEncoding: \
+                 Unknown type family with type_id 15"
+            ),
+            "Unexpected error: {error}"
+        );
+    }
+}
@@ -34,9 +34,12 @@ impl TermDecoder<TypedValueEncoding> for DefaultTypedValueDecoder {
     fn decode_term(
         scalar: &<TypedValueEncoding as TermEncoding>::Scalar,
     ) -> ThinResult<Self::Term<'_>> {
-        let ScalarValue::Union(Some((type_id, value)), _, _) = scalar.scalar_value()
-        else {
-            panic!("Unexpected type id");
+        let ScalarValue::Union(value, _, _) = scalar.scalar_value() else {
+            panic!("Unexpected scalar value");
+        };
+        // A null union (e.g., produced by constant folding a CASE) is treated like a null value.
+        let Some((type_id, value)) = value else {
+            return ThinError::expected();
         };
         let field =
             TypedValueEncodingField::try_from(*type_id).expect("Unexpected type id");
@@ -5,7 +5,7 @@ use crate::typed_value::scalar::TypedValueScalar;
 use crate::{EncodingArray, EncodingName, TermEncoder};
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::datatypes::{DataType, Field, Fields, UnionFields, UnionMode};
-use datafusion::common::ScalarValue;
+use datafusion::common::{ScalarValue, exec_err};
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::{Decimal, TermRef, ThinResult};
 use std::clone::Clone;
@@ -222,6 +222,21 @@ impl TypedValueEncoding {
     }
 }
 
+/// Checks whether all type ids in `fields` refer to a known [`TypedValueEncodingField`].
+///
+/// Returns an error that names the first unknown type id.
+pub(crate) fn validate_type_ids(fields: &UnionFields) -> DFResult<()> {
+    for (type_id, field) in fields.iter() {
+        if let Err(error) = TypedValueEncodingField::try_from(type_id) {
+            return exec_err!(
+                "Cannot decode field '{}' with the TypedValueEncoding: {error}",
+                field.name()
+            );
+        }
+    }
+    Ok(())
+}
+
 impl TermEncoding for TypedValueEncoding {
     type Array = TypedValueArray;
     type Scalar = TypedValueScalar;
@@ -333,12 +348,27 @@ impl Display for TypedValueEncodingField {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Error, PartialEq, Eq, Hash)]
-pub struct UnknownTypedValueEncodingFieldError;
+/// Indicates that a type id does not refer to a known [`TypedValueEncodingField`].
+///
+/// This can happen if Arrow data is exchanged between instances that support different type
+/// families (e.g., data produced by an instance with an additional domain-specific literal type).
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq, Hash)]
+pub struct UnknownTypedValueEncodingFieldError(pub i8);
+
+impl UnknownTypedValueEncodingFieldError {
+    /// Returns the unknown type id.
+    pub fn type_id(self) -> i8 {
+        self.0
+    }
+}
 
 impl Display for UnknownTypedValueEncodingFieldError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unexpected type_id for encoded RDF Term")
+        write!(
+            f,
+            "Unknown type family with type_id {} for encoded RDF Term",
+            self.0
+        )
     }
 }
 
@@ -362,7 +392,7 @@ impl TryFrom<i8> for TypedValueEncodingField {
             12 => TypedValueEncodingField::Date,
             13 => TypedValueEncodingField::Duration,
             14 => TypedValueEncodingField::OtherLiteral,
-            _ => return Err(UnknownTypedValueEncodingFieldError),
+            _ => return Err(UnknownTypedValueEncodingFieldError(value)),
         })
     }
 }
@@ -427,4 +457,14 @@ mod tests {
     fn test_type_id(term_field: TypedValueEncodingField) {
         assert_eq!(term_field, term_field.type_id().try_into().unwrap());
     }
+
+    #[test]
+    fn test_unknown_type_id() {
+        let error = TypedValueEncodingField::try_from(42i8).unwrap_err();
+        assert_eq!(error.type_id(), 42);
+        assert_eq!(
+            error.to_string(),
+            "Unknown type family with type_id 42 for encoded RDF Term"
+        );
+    }
 }
@@ -4,10 +4,12 @@ pub mod decoders;
 mod element_builder;
 pub mod encoders;
 mod encoding;
+mod normalization;
 mod scalar;
 
 pub use array::{TypedValueArray, TypedValueArrayParts};
 pub use builder::TypedValueArrayBuilder;
 pub use element_builder::TypedValueArrayElementBuilder;
 pub use encoding::*;
+pub use normalization::LiteralNormalization;
 pub use scalar::TypedValueScalar;
@@ -0,0 +1,133 @@
+use rdf_fusion_model::{Literal, LiteralRef, Quad, Term, TypedValueRef};
+
+/// Defines how the lexical forms of literals are treated when quads are inserted into a storage.
+///
+/// RDF distinguishes literals with different lexical forms, even if they represent the same value
+/// (e.g., `"01"^^xsd:integer` and `"1"^^xsd:integer`). By default, the lexical forms are
+/// preserved as-is. With [`LiteralNormalization::Canonical`], typed literals are replaced with
+/// the canonical lexical form of their value before storing them. This makes the storage more
+/// compact and allows joins and `DISTINCT` to match equal values, at the cost of losing the
+/// original lexical forms.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LiteralNormalization {
+    /// Literals are stored with their original lexical form.
+    #[default]
+    Preserve,
+    /// Typed literals are stored with the canonical lexical form of their value.
+    ///
+    /// The lexical form is canonicalized by decoding the literal into a [`TypedValueRef`] and
+    /// encoding the value again. The datatype of the literal is kept (e.g., `"007"^^xsd:byte`
+    /// becomes `"7"^^xsd:byte`). Literals with an invalid lexical form, an unsupported datatype,
+    /// or a language tag are not changed.
+    Canonical,
+}
+
+impl LiteralNormalization {
+    /// Applies the normalization to `quad`. Only the object of a quad can be a literal.
+    pub fn normalize_quad(self, quad: Quad) -> Quad {
+        Quad {
+            object: self.normalize_term(quad.object),
+            ..quad
+        }
+    }
+
+    /// Applies the normalization to `term`. Only literals are affected.
+    pub fn normalize_term(self, term: Term) -> Term {
+        match (self, term) {
+            (Self::Canonical, Term::Literal(literal)) => {
+                Term::Literal(canonical_literal(literal.as_ref()).unwrap_or(literal))
+            }
+            (_, term) => term,
+        }
+    }
+}
+
+/// Returns the canonical version of `literal`, if it differs from `literal`.
+fn canonical_literal(literal: LiteralRef<'_>) -> Option<Literal> {
+    if literal.language().is_some() {
+        return None;
+    }
+
+    let value = TypedValueRef::try_from(literal).ok()?;
+    let Term::Literal(canonical) = Term::from(value) else {
+        return None;
+    };
+
+    (canonical.value() != literal.value()).then(|| {
+        Literal::new_typed_literal(canonical.value(), literal.datatype().into_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::vocab::xsd;
+    use rdf_fusion_model::{GraphName, NamedNode, NamedNodeRef};
+
+    #[test]
+    fn test_canonical_lexical_forms() {
+        assert_canonical("01", xsd::INTEGER, "1");
+        assert_canonical("+5", xsd::INT, "5");
+        assert_canonical("007", xsd::BYTE, "7");
+        assert_canonical("1.50", xsd::DECIMAL, "1.5");
+        assert_canonical("true", xsd::BOOLEAN, "true");
+        assert_canonical("1", xsd::BOOLEAN, "true");
+        assert_canonical("abc", xsd::STRING, "abc");
+    }
+
+    #[test]
+    fn test_invalid_and_unknown_literals_are_kept() {
+        assert_canonical("one", xsd::INTEGER, "one");
+        assert_canonical(
+            "01",
+            NamedNodeRef::new_unchecked("http://example.com/datatype"),
+            "01",
+        );
+
+        let literal =
+            Term::from(Literal::new_language_tagged_literal_unchecked("01", "en"));
+        assert_eq!(
+            LiteralNormalization::Canonical.normalize_term(literal.clone()),
+            literal
+        );
+    }
+
+    #[test]
+    fn test_preserve_keeps_lexical_form() {
+        let literal = Term::from(Literal::new_typed_literal("01", xsd::INTEGER));
+        assert_eq!(
+            LiteralNormalization::Preserve.normalize_term(literal.clone()),
+            literal
+        );
+    }
+
+    #[test]
+    fn test_normalize_quad_only_changes_object() {
+        let ex = NamedNode::new_unchecked("http://example.com");
+        let quad = Quad::new(
+            ex.clone(),
+            ex.clone(),
+            Literal::new_typed_literal("01", xsd::INTEGER),
+            GraphName::DefaultGraph,
+        );
+
+        let normalized = LiteralNormalization::Canonical.normalize_quad(quad);
+        assert_eq!(
+            normalized,
+            Quad::new(
+                ex.clone(),
+                ex,
+                Literal::new_typed_literal("1", xsd::INTEGER),
+                GraphName::DefaultGraph,
+            )
+        );
+    }
+
+    fn assert_canonical(value: &str, datatype: NamedNodeRef<'_>, expected: &str) {
+        let literal = Term::from(Literal::new_typed_literal(value, datatype));
+        assert_eq!(
+            LiteralNormalization::Canonical.normalize_term(literal),
+            Term::from(Literal::new_typed_literal(expected, datatype))
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::encoding::EncodingScalar;
 use crate::typed_value::decoders::DefaultTypedValueDecoder;
+use crate::typed_value::encoding::validate_type_ids;
 use crate::typed_value::{TypedValueEncoding, TypedValueEncodingRef};
 use crate::{TermDecoder, TermEncoding};
 use datafusion::common::{ScalarValue, exec_err};
@@ -20,11 +21,16 @@ impl TypedValueScalar {
     ///
     /// # Errors
     ///
-    /// Returns an error if the data type of `value` is unexpected.
+    /// Returns an error if the scalar contains a type family that is unknown to the encoding or if
+    /// the data type of `value` is unexpected.
     pub fn try_new(
         encoding: TypedValueEncodingRef,
         value: ScalarValue,
     ) -> DFResult<Self> {
+        if let ScalarValue::Union(_, fields, _) = &value {
+            validate_type_ids(fields)?;
+        }
+
         if &value.data_type() != encoding.data_type() {
             return exec_err!(
                 "Expected scalar value with TypedValueEncoding, got {:?}",
@@ -2,9 +2,13 @@ use crate::planner::RdfFusionPlanner;
 use crate::results::QueryResults;
 use crate::sparql::error::QueryEvaluationError;
 use crate::sparql::{
-    OptimizationLevel, Query, QueryExplanation, QueryOptions, create_optimizer_rules,
-    create_pyhsical_optimizer_rules, evaluate_query,
+    HttpServiceResolver, OptimizationLevel, Query, QueryCancellation,
+    QueryCancellationToken, QueryExplanation, QueryOptions, QueryPlanCache,
+    ServiceResolver, ServiceResolverSlot, Update, UpdateOptions, create_optimizer_rules,
+    create_pyhsical_optimizer_rules, evaluate_query, evaluate_update,
 };
+use datafusion::arrow::array::RecordBatch;
+use datafusion::common::{internal_err, plan_err};
 use datafusion::dataframe::DataFrame;
 use datafusion::error::DataFusionError;
 use datafusion::execution::runtime_env::RuntimeEnv;
@@ -12,20 +16,38 @@ use datafusion::execution::{SendableRecordBatchStream, SessionStateBuilder};
 use datafusion::functions_aggregate::first_last::FirstValue;
 use datafusion::logical_expr::AggregateUDF;
 use datafusion::prelude::{SessionConfig, SessionContext};
-use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+use futures::TryStreamExt;
+use futures::future::{self, Either};
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdEncoding, ObjectIdScalar};
+use rdf_fusion_encoding::plain_term::decoders::DefaultPlainTermDecoder;
+use rdf_fusion_encoding::plain_term::{PLAIN_TERM_ENCODING, PlainTermQuadBatch};
 use rdf_fusion_encoding::sortable_term::SORTABLE_TERM_ENCODING;
-use rdf_fusion_encoding::typed_value::TypedValueEncoding;
-use rdf_fusion_encoding::{QuadStorageEncoding, RdfFusionEncodings};
+use rdf_fusion_encoding::typed_value::{LiteralNormalization, TypedValueEncoding};
+use rdf_fusion_encoding::{
+    QuadStorageEncoding, RdfFusionEncodings, TermDecoder, TermEncoding,
+};
 use rdf_fusion_extensions::RdfFusionContextView;
 use rdf_fusion_extensions::functions::{
     RdfFusionFunctionRegistry, RdfFusionFunctionRegistryRef,
 };
-use rdf_fusion_extensions::storage::QuadStorage;
-use rdf_fusion_functions::registry::DefaultRdfFusionFunctionRegistry;
+use rdf_fusion_extensions::storage::{QuadPosition, QuadStorage};
+use rdf_fusion_functions::datatypes::{CustomDatatype, CustomDatatypes};
+use rdf_fusion_functions::registry::{
+    DefaultRdfFusionFunctionRegistry, comparison_udfs, rand_udf,
+};
 use rdf_fusion_logical::{ActiveGraph, RdfFusionLogicalPlanBuilderContext};
-use rdf_fusion_model::{DFResult, NamedOrBlankNodeRef};
-use rdf_fusion_model::{GraphName, GraphNameRef, NamedNodeRef, QuadRef, TermRef};
-use std::sync::Arc;
+use rdf_fusion_model::quads::{COL_OBJECT, COL_PREDICATE};
+use rdf_fusion_model::{DFResult, NamedOrBlankNodeRef, StorageError, Variable};
+use rdf_fusion_model::{
+    GraphName, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNode, Quad, QuadRef,
+    Term, TermRef,
+};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::pin::pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 /// Represents a connection to an instance of an RDF Fusion engine.
 ///
@@ -33,6 +55,7 @@ use std::sync::Arc;
 /// - A [SessionContext]. This is the primary interaction point with DataFusion.
 /// - An [RdfFusionFunctionRegistry] that holds the currently registered RDF Fusion built-ins.
 /// - A reference to a quad storage.
+/// - A [QueryPlanCache] that holds the plans of recently executed queries.
 #[derive(Clone)]
 pub struct RdfFusionContext {
     /// The DataFusion [SessionContext].
@@ -43,6 +66,14 @@ pub struct RdfFusionContext {
     encodings: RdfFusionEncodings,
     /// The storage that backs this instance.
     storage: Arc<dyn QuadStorage>,
+    /// The cache for query plans. Shared between clones of this context.
+    query_plan_cache: Arc<QueryPlanCache>,
+    /// The registered custom datatypes. Shared between clones of this context.
+    custom_datatypes: Arc<RwLock<CustomDatatypes>>,
+    /// The resolver for `SERVICE` calls. Shared between clones of this context.
+    service_resolver: ServiceResolverSlot,
+    /// The normalization of literals on insert. Shared between clones of this context.
+    literal_normalization: Arc<RwLock<LiteralNormalization>>,
 }
 
 impl RdfFusionContext {
@@ -76,11 +107,14 @@ impl RdfFusionContext {
             create_optimizer_rules(context_view.clone(), OptimizationLevel::Full);
         let physical_optimizer_rules =
             create_pyhsical_optimizer_rules(OptimizationLevel::Full);
+        let service_resolver: ServiceResolverSlot =
+            Arc::new(RwLock::new(Arc::new(HttpServiceResolver::default())));
 
         let state = SessionStateBuilder::new()
             .with_query_planner(Arc::new(RdfFusionPlanner::new(
                 context_view,
                 Arc::clone(&storage),
+                Arc::clone(&service_resolver),
             )))
             .with_aggregate_functions(vec![AggregateUDF::from(FirstValue::new()).into()])
             .with_optimizer_rules(optimizer_rules)
@@ -95,9 +129,51 @@ impl RdfFusionContext {
             functions: registry,
             encodings,
             storage,
+            query_plan_cache: Arc::new(QueryPlanCache::default()),
+            custom_datatypes: Arc::new(RwLock::new(CustomDatatypes::default())),
+            service_resolver,
+            literal_normalization: Arc::new(RwLock::new(LiteralNormalization::default())),
         }
     }
 
+    /// Replaces the [QueryPlanCache] of this context with an empty cache holding at most
+    /// `capacity` plans. A capacity of zero disables caching query plans.
+    pub fn with_query_plan_cache_capacity(mut self, capacity: usize) -> Self {
+        self.query_plan_cache = Arc::new(QueryPlanCache::new(capacity));
+        self
+    }
+
+    /// Creates a new [RdfFusionContext] that evaluates all queries against a read-only snapshot of
+    /// the current storage.
+    ///
+    /// The returned context shares the configuration, the registered functions, and the
+    /// [QueryPlanCache] with this context. The snapshot is released once the returned context and
+    /// all of its clones are dropped. Returns an error if the storage does not support snapshots.
+    pub async fn snapshot(&self) -> Result<Self, StorageError> {
+        let storage = self.storage.snapshot().await?.ok_or_else(|| {
+            StorageError::Other("The storage does not support snapshots.".into())
+        })?;
+
+        let state = SessionStateBuilder::new_from_existing(self.ctx.state())
+            .with_query_planner(Arc::new(RdfFusionPlanner::new(
+                self.create_view(),
+                Arc::clone(&storage),
+                Arc::clone(&self.service_resolver),
+            )))
+            .build();
+
+        Ok(Self {
+            ctx: SessionContext::from(state),
+            functions: Arc::clone(&self.functions),
+            encodings: self.encodings.clone(),
+            storage,
+            query_plan_cache: Arc::clone(&self.query_plan_cache),
+            custom_datatypes: Arc::clone(&self.custom_datatypes),
+            service_resolver: Arc::clone(&self.service_resolver),
+            literal_normalization: Arc::clone(&self.literal_normalization),
+        })
+    }
+
     /// Creates a new [RdfFusionContextView] on this context. The resulting view should be passed
     /// around in the RDF Fusion ecosystem to access the current configuration without directly
     /// depending on the [RdfFusionContext].
@@ -124,6 +200,120 @@ impl RdfFusionContext {
         &self.encodings
     }
 
+    /// Returns a reference to the [QueryPlanCache] of this instance.
+    pub fn query_plan_cache(&self) -> &QueryPlanCache {
+        &self.query_plan_cache
+    }
+
+    /// Returns the custom datatypes that are registered in this instance.
+    pub fn custom_datatypes(&self) -> CustomDatatypes {
+        self.custom_datatypes.read().unwrap().clone()
+    }
+
+    /// Registers a custom RDF datatype in this instance. An existing registration for the same
+    /// datatype IRI is replaced.
+    ///
+    /// Afterward, the SPARQL comparison operators compare literals of this datatype with the
+    /// comparator of `datatype`. Literals with unregistered datatypes are not affected. The
+    /// registration only applies to this instance (and its clones).
+    pub fn register_custom_datatype(&self, datatype: CustomDatatype) {
+        let mut datatypes = self.custom_datatypes.write().unwrap();
+        *datatypes = datatypes.with_datatype(datatype);
+        for udf in comparison_udfs(&self.encodings, &datatypes) {
+            self.functions.register_udf(udf);
+        }
+    }
+
+    /// Sets the seed of the SPARQL `RAND` function in this instance.
+    ///
+    /// If a `seed` is given, `RAND` produces the same sequence of values in each execution of a
+    /// query. This is useful for reproducible benchmarks and tests. Passing [None] restores truly
+    /// random values. The setting only applies to this instance (and its clones).
+    pub fn set_rand_seed(&self, seed: Option<u64>) {
+        self.functions.register_udf(rand_udf(&self.encodings, seed));
+    }
+
+    /// Registers the [ServiceResolver] that evaluates the `SERVICE` calls of queries in this
+    /// instance. The previously registered resolver is replaced.
+    ///
+    /// By default, an [HttpServiceResolver] sends the calls to the SPARQL endpoints. The
+    /// registration only applies to this instance (and its clones).
+    pub fn register_service_resolver(&self, resolver: Arc<dyn ServiceResolver>) {
+        *self.service_resolver.write().unwrap() = resolver;
+    }
+
+    /// Returns the [LiteralNormalization] that is applied to inserted quads.
+    pub fn literal_normalization(&self) -> LiteralNormalization {
+        *self.literal_normalization.read().unwrap()
+    }
+
+    /// Sets the [LiteralNormalization] that is applied to quads inserted through this instance
+    /// (e.g., by [Self::insert_quads] or a SPARQL update). Quads that are already stored are not
+    /// changed. The setting only applies to this instance (and its clones).
+    ///
+    /// By default, literals keep their lexical form, as required by RDF. See
+    /// [LiteralNormalization::Canonical] for the tradeoffs of canonicalizing them.
+    pub fn set_literal_normalization(&self, normalization: LiteralNormalization) {
+        *self.literal_normalization.write().unwrap() = normalization;
+    }
+
+    /// Inserts `quads` into the storage after applying the configured [LiteralNormalization].
+    ///
+    /// Returns the number of quads that were not already in the storage.
+    pub async fn insert_quads(&self, quads: Vec<Quad>) -> Result<usize, StorageError> {
+        let normalization = self.literal_normalization();
+        let quads = quads
+            .into_iter()
+            .map(|quad| normalization.normalize_quad(quad))
+            .collect();
+        self.storage.extend(quads).await
+    }
+
+    /// Inserts `quads` into the storage after applying the configured [LiteralNormalization],
+    /// using up to `parallelism` threads for encoding them (see [QuadStorage::extend_parallel]).
+    ///
+    /// Returns the number of quads that were not already in the storage.
+    pub async fn insert_quads_parallel(
+        &self,
+        quads: Vec<Quad>,
+        parallelism: NonZeroUsize,
+    ) -> Result<usize, StorageError> {
+        let normalization = self.literal_normalization();
+        let quads = quads
+            .into_iter()
+            .map(|quad| normalization.normalize_quad(quad))
+            .collect();
+        self.storage.extend_parallel(quads, parallelism).await
+    }
+
+    /// Inserts the quads of `batch` into the storage after applying the configured
+    /// [LiteralNormalization].
+    ///
+    /// The batch must have the quad schema of [QuadStorageEncoding::PlainTerm]. If literals are
+    /// preserved, the batch is passed to the storage as-is. Otherwise, the batch is converted into
+    /// [Quad]s to normalize them. Returns the number of quads that were not already in the
+    /// storage.
+    pub async fn insert_batch(&self, batch: RecordBatch) -> Result<usize, StorageError> {
+        if self.literal_normalization() == LiteralNormalization::Preserve {
+            return self.storage.extend_from_batch(batch).await;
+        }
+
+        let quads = PlainTermQuadBatch::try_new(&batch)?
+            .quads()
+            .map(|quad| quad.map(QuadRef::into_owned))
+            .collect::<DFResult<Vec<_>>>()?;
+        self.insert_quads(quads).await
+    }
+
+    /// Removes `quad` from the storage after applying the configured [LiteralNormalization].
+    ///
+    /// This ensures that a quad can be removed with the same lexical forms that have been used
+    /// for inserting it. Returns `true` if the quad was in the storage.
+    pub async fn remove_quad(&self, quad: Quad) -> Result<bool, StorageError> {
+        let quad = self.literal_normalization().normalize_quad(quad);
+        self.storage.remove(quad.as_ref()).await
+    }
+
     /// Provides access to the [QuadStorage] of this instance for writing operations.
     pub fn storage(&self) -> &Arc<dyn QuadStorage> {
         &self.storage
@@ -163,7 +353,174 @@ impl RdfFusionContext {
             .map_err(|err| DataFusionError::External(Box::new(err)))
     }
 
+    /// Returns the number of quads that match the given pattern.
+    ///
+    /// If supported by the storage, the quads are counted directly by the storage layer.
+    /// Otherwise, the matching quads are counted with a query.
+    pub async fn count_matching(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> DFResult<usize> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .count_matching(graph_name, subject, predicate, object)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
+        let active_graph_info = graph_name_to_active_graph(graph_name);
+        let pattern_plan = self.plan_builder_context().create_matching_quads(
+            active_graph_info,
+            subject.map(NamedOrBlankNodeRef::into_owned),
+            predicate.map(NamedNodeRef::into_owned),
+            object.map(TermRef::into_owned),
+        );
+        DataFrame::new(self.ctx.state(), pattern_plan.build()?)
+            .count()
+            .await
+    }
+
+    /// Returns the distinct predicates of the quads with the given `subject`.
+    ///
+    /// If supported by the storage, the predicates are obtained directly from the storage layer.
+    /// Otherwise, the distinct predicates are computed with a query.
+    pub async fn predicates_for(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: NamedOrBlankNodeRef<'_>,
+    ) -> DFResult<Vec<NamedNode>> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .predicates_for(graph_name, subject)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
+        let active_graph_info = graph_name_to_active_graph(graph_name);
+        let pattern_plan = self
+            .plan_builder_context()
+            .create_matching_quads(
+                active_graph_info,
+                Some(subject.into_owned()),
+                None,
+                None,
+            )
+            .project(&[Variable::new_unchecked(COL_PREDICATE)])?
+            .distinct()?
+            .with_plain_terms()?;
+        let batches = DataFrame::new(self.ctx.state(), pattern_plan.build()?)
+            .collect()
+            .await?;
+
+        let mut predicates = Vec::new();
+        for batch in batches {
+            let terms = PLAIN_TERM_ENCODING.try_new_array(Arc::clone(batch.column(0)))?;
+            for term in DefaultPlainTermDecoder::decode_terms(&terms) {
+                match term {
+                    Ok(TermRef::NamedNode(predicate)) => {
+                        predicates.push(predicate.into_owned());
+                    }
+                    _ => return internal_err!("Predicate must be a named node."),
+                }
+            }
+        }
+        Ok(predicates)
+    }
+
+    /// Returns the distinct objects of the quads with the given `predicate`.
+    ///
+    /// If supported by the storage, the objects are obtained directly from the storage layer.
+    /// Otherwise, the distinct objects are computed with a query.
+    pub async fn distinct_objects_of(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        predicate: NamedNodeRef<'_>,
+    ) -> DFResult<Vec<Term>> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .distinct_objects_of(graph_name, predicate)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
+        let active_graph_info = graph_name_to_active_graph(graph_name);
+        let pattern_plan = self
+            .plan_builder_context()
+            .create_matching_quads(
+                active_graph_info,
+                None,
+                Some(predicate.into_owned()),
+                None,
+            )
+            .project(&[Variable::new_unchecked(COL_OBJECT)])?
+            .distinct()?
+            .with_plain_terms()?;
+        let batches = DataFrame::new(self.ctx.state(), pattern_plan.build()?)
+            .collect()
+            .await?;
+
+        let mut objects = Vec::new();
+        for batch in batches {
+            let terms = PLAIN_TERM_ENCODING.try_new_array(Arc::clone(batch.column(0)))?;
+            for term in DefaultPlainTermDecoder::decode_terms(&terms) {
+                match term {
+                    Ok(object) => objects.push(object.into_owned()),
+                    Err(_) => return internal_err!("Object must be a term."),
+                }
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Returns the `k` most frequent terms in the given `position`, together with the number of
+    /// quads that reference them. The terms are ordered by descending frequency.
+    ///
+    /// If supported by the storage, the frequencies are obtained directly from the storage layer.
+    /// Otherwise, they are computed by scanning all quads.
+    pub async fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> DFResult<Vec<(Term, usize)>> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .top_terms(position, k)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
+        let mut stream = self.quads_for_pattern(None, None, None, None).await?;
+        let mut frequencies = HashMap::<Term, usize>::new();
+        while let Some(batch) = stream.try_next().await? {
+            for quad in PlainTermQuadBatch::try_new(&batch)?.quads() {
+                let quad = quad?;
+                let term = match position {
+                    QuadPosition::GraphName => match quad.graph_name {
+                        GraphNameRef::NamedNode(node) => node.into(),
+                        GraphNameRef::BlankNode(node) => node.into(),
+                        GraphNameRef::DefaultGraph => continue,
+                    },
+                    QuadPosition::Subject => quad.subject.into(),
+                    QuadPosition::Predicate => quad.predicate.into(),
+                    QuadPosition::Object => quad.object,
+                };
+                *frequencies.entry(term.into_owned()).or_default() += 1;
+            }
+        }
+
+        let mut terms = frequencies.into_iter().collect::<Vec<_>>();
+        terms.sort_by_cached_key(|(term, count)| (Reverse(*count), term.to_string()));
+        terms.truncate(k);
+        Ok(terms)
+    }
+
     /// Returns a stream of all quads that match the given pattern.
+    ///
+    /// If supported by the storage, the quads are scanned directly from the storage layer.
+    /// Otherwise, the pattern is evaluated with a query.
     pub async fn quads_for_pattern(
         &self,
         graph_name: Option<GraphNameRef<'_>>,
@@ -171,6 +528,13 @@ impl RdfFusionContext {
         predicate: Option<NamedNodeRef<'_>>,
         object: Option<TermRef<'_>>,
     ) -> DFResult<SendableRecordBatchStream> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .quads_for_pattern(graph_name, subject, predicate, object)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
         let active_graph_info = graph_name_to_active_graph(graph_name);
         let pattern_plan = self
             .plan_builder_context()
@@ -188,6 +552,80 @@ impl RdfFusionContext {
         Ok(result)
     }
 
+    /// Returns the quads that match the given pattern of object ids. Unbound components match
+    /// any term.
+    ///
+    /// If supported by the storage, the quads are obtained directly from the storage layer
+    /// without encoding the bound components. Otherwise, the object ids are decoded and the
+    /// pattern is evaluated with a query. Object ids that are unknown to the storage do not match
+    /// any quad.
+    pub async fn quads_for_object_id_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+    ) -> DFResult<Vec<Quad>> {
+        if let Some(lookups) = self.storage.lookups() {
+            return lookups
+                .quads_for_object_id_pattern(graph_name, subject, predicate, object)
+                .await
+                .map_err(|err| DataFusionError::External(Box::new(err)));
+        }
+
+        let Some(mapping) = self.storage.object_id_mapping() else {
+            return plan_err!("The storage does not use object ids.");
+        };
+        // Returns None if the object id is unknown, and Some(None) if the component is unbound.
+        let decode = |object_id: Option<&ObjectId>| -> Option<Option<Term>> {
+            let Some(object_id) = object_id else {
+                return Some(None);
+            };
+            let encoding = Arc::new(ObjectIdEncoding::new(Arc::clone(&mapping)));
+            let scalar =
+                ObjectIdScalar::from_object_id(encoding, object_id.clone()).ok()?;
+            let term = mapping.decode_scalar(&scalar).ok()?;
+            let term = DefaultPlainTermDecoder::decode_term(&term).ok()?;
+            Some(Some(term.into_owned()))
+        };
+
+        let (Some(subject), Some(predicate), Some(object)) =
+            (decode(subject), decode(predicate), decode(object))
+        else {
+            return Ok(Vec::new());
+        };
+        let subject = match subject {
+            None => None,
+            Some(Term::NamedNode(node)) => Some(NamedOrBlankNode::NamedNode(node)),
+            Some(Term::BlankNode(node)) => Some(NamedOrBlankNode::BlankNode(node)),
+            Some(Term::Literal(_)) => return Ok(Vec::new()),
+        };
+        let predicate = match predicate {
+            None => None,
+            Some(Term::NamedNode(node)) => Some(node),
+            Some(_) => return Ok(Vec::new()),
+        };
+
+        let batches = self
+            .quads_for_pattern(
+                graph_name,
+                subject.as_ref().map(NamedOrBlankNode::as_ref),
+                predicate.as_ref().map(NamedNode::as_ref),
+                object.as_ref().map(Term::as_ref),
+            )
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut quads = Vec::new();
+        for batch in batches {
+            for quad in PlainTermQuadBatch::try_new(&batch)?.quads() {
+                quads.push(quad?.into_owned());
+            }
+        }
+        Ok(quads)
+    }
+
     /// Evaluates a SPARQL [Query] over the instance.
     pub async fn execute_query(
         &self,
@@ -202,6 +640,86 @@ impl RdfFusionContext {
         ))
         .await
     }
+
+    /// Plans the SPARQL query in `query` and returns its optimized logical plan and its physical
+    /// plan (see [QueryExplanation]).
+    ///
+    /// The query may, but does not have to be, prefixed with `EXPLAIN`. The results of the query
+    /// are not consumed. However, as in [Self::execute_query], `ASK` queries are evaluated up to
+    /// their first solution.
+    pub async fn explain_query(
+        &self,
+        query: &str,
+    ) -> Result<String, QueryEvaluationError> {
+        let query = Query::parse(query, None)?.without_explain();
+        let (_, explanation) =
+            self.execute_query(&query, QueryOptions::default()).await?;
+        Ok(explanation.to_string())
+    }
+
+    /// Evaluates a SPARQL [Query] over the instance and aborts the evaluation once `timeout` has
+    /// elapsed.
+    ///
+    /// The timeout covers planning the query and consuming the returned results. Once the
+    /// timeout has elapsed, the underlying DataFusion stream is dropped, which stops the
+    /// execution, and the next poll of the results returns [QueryEvaluationError::Timeout].
+    /// Results that have already been returned are unaffected.
+    pub async fn execute_query_with_timeout(
+        &self,
+        query: &Query,
+        options: QueryOptions,
+        timeout: Duration,
+    ) -> Result<(QueryResults, QueryExplanation), QueryEvaluationError> {
+        let cancellation = QueryCancellation::new(Some(timeout), None);
+        self.execute_query_with_cancellation_impl(query, options, cancellation)
+            .await
+    }
+
+    /// Evaluates a SPARQL [Query] over the instance and aborts the evaluation once `token` has
+    /// been cancelled or the optional `timeout` has elapsed.
+    ///
+    /// This allows, for example, an HTTP endpoint to stop the evaluation of a query once the
+    /// client has disconnected. A cancelled query returns [QueryEvaluationError::Cancelled],
+    /// while a query that exceeds its timeout returns [QueryEvaluationError::Timeout].
+    pub async fn execute_query_with_cancellation(
+        &self,
+        query: &Query,
+        options: QueryOptions,
+        token: QueryCancellationToken,
+        timeout: Option<Duration>,
+    ) -> Result<(QueryResults, QueryExplanation), QueryEvaluationError> {
+        let cancellation = QueryCancellation::new(timeout, Some(token));
+        self.execute_query_with_cancellation_impl(query, options, cancellation)
+            .await
+    }
+
+    async fn execute_query_with_cancellation_impl(
+        &self,
+        query: &Query,
+        options: QueryOptions,
+        cancellation: QueryCancellation,
+    ) -> Result<(QueryResults, QueryExplanation), QueryEvaluationError> {
+        let evaluation = self.execute_query(query, options);
+        let stopped = cancellation.clone().stopped();
+        match future::select(pin!(evaluation), pin!(stopped)).await {
+            Either::Left((result, _)) => {
+                let (results, explanation) = result?;
+                Ok((cancellation.wrap_results(results), explanation))
+            }
+            Either::Right((error, _)) => Err(error),
+        }
+    }
+
+    /// Evaluates a SPARQL [Update] over the instance.
+    ///
+    /// See [evaluate_update] for the guarantees of the evaluation.
+    pub async fn execute_update(
+        &self,
+        update: &Update,
+        options: UpdateOptions,
+    ) -> Result<(), QueryEvaluationError> {
+        Box::pin(evaluate_update(self, update, &options)).await
+    }
 }
 
 fn graph_name_to_active_graph(graph_name: Option<GraphNameRef<'_>>) -> ActiveGraph {
@@ -1,3 +1,4 @@
+use crate::sparql::{ServicePlanner, ServiceResolverSlot};
 use async_trait::async_trait;
 use datafusion::execution::SessionState;
 use datafusion::execution::context::QueryPlanner;
@@ -8,6 +9,7 @@ use datafusion::physical_planner::{
 };
 use rdf_fusion_extensions::RdfFusionContextView;
 use rdf_fusion_extensions::storage::QuadStorage;
+use rdf_fusion_physical::minus::MinusPlanner;
 use rdf_fusion_physical::paths::KleenePlusPathPlanner;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -17,12 +19,22 @@ pub struct RdfFusionPlanner {
     context: RdfFusionContextView,
     /// The storage layer that is used to execute the query.
     storage: Arc<dyn QuadStorage>,
+    /// The resolver for `SERVICE` calls.
+    service_resolver: ServiceResolverSlot,
 }
 
 impl RdfFusionPlanner {
     /// Creates a new [RdfFusionPlanner].
-    pub fn new(context: RdfFusionContextView, storage: Arc<dyn QuadStorage>) -> Self {
-        Self { context, storage }
+    pub fn new(
+        context: RdfFusionContextView,
+        storage: Arc<dyn QuadStorage>,
+        service_resolver: ServiceResolverSlot,
+    ) -> Self {
+        Self {
+            context,
+            storage,
+            service_resolver,
+        }
     }
 }
 
@@ -39,8 +51,11 @@ impl QueryPlanner for RdfFusionPlanner {
         logical_plan: &LogicalPlan,
         session_state: &SessionState,
     ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
-        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> =
-            vec![Arc::new(KleenePlusPathPlanner)];
+        let mut planners: Vec<Arc<dyn ExtensionPlanner + Send + Sync>> = vec![
+            Arc::new(KleenePlusPathPlanner),
+            Arc::new(MinusPlanner),
+            Arc::new(ServicePlanner::new(Arc::clone(&self.service_resolver))),
+        ];
         planners.extend(self.storage.planners(&self.context).await);
 
         let planner = DefaultPhysicalPlanner::with_extension_planners(planners);
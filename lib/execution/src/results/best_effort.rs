@@ -0,0 +1,82 @@
+use crate::results::{QuerySolution, QuerySolutionStream};
+use crate::sparql::error::QueryEvaluationError;
+use futures::StreamExt;
+use rdf_fusion_model::Variable;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The solutions of a query that have been computed within a time budget.
+///
+/// Contrary to a timeout that aborts the query with an error, exceeding the time budget is not an
+/// error. Instead, the solutions computed so far are returned and [Self::is_truncated] indicates
+/// that the query has been stopped before all solutions were computed. This is useful for
+/// applications (e.g., dashboards) that prefer showing partial results over showing no results.
+#[derive(Debug)]
+pub struct BestEffortQuerySolutions {
+    /// The variables used in the solutions.
+    variables: Arc<[Variable]>,
+    /// The solutions computed within the time budget.
+    solutions: Vec<QuerySolution>,
+    /// Whether the evaluation has been stopped before the stream was exhausted.
+    truncated: bool,
+}
+
+impl BestEffortQuerySolutions {
+    /// Reads solutions from `stream` until it is exhausted or `timeout` has elapsed.
+    ///
+    /// Once the `timeout` has elapsed, the stream is dropped, which stops the evaluation of the
+    /// query. Errors of the query are still reported as errors.
+    ///
+    /// The time budget is enforced cooperatively. An operator that does not yield back to the
+    /// runtime can delay the return beyond the `timeout`.
+    pub async fn collect_with_timeout(
+        mut stream: QuerySolutionStream,
+        timeout: Duration,
+    ) -> Result<Self, QueryEvaluationError> {
+        let deadline = Instant::now() + timeout;
+        let variables = Arc::from(stream.variables());
+
+        let mut solutions = Vec::new();
+        let truncated = loop {
+            // The timeout only elapses while the stream is pending. Streams that are always ready
+            // must therefore be checked against the deadline explicitly.
+            if Instant::now() >= deadline {
+                break true;
+            }
+
+            match tokio::time::timeout_at(deadline, stream.next()).await {
+                Ok(Some(solution)) => solutions.push(solution?),
+                Ok(None) => break false,
+                Err(_) => break true,
+            }
+        };
+
+        Ok(Self {
+            variables,
+            solutions,
+            truncated,
+        })
+    }
+
+    /// The variables used in the solutions.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Returns the solutions that have been computed within the time budget.
+    pub fn solutions(&self) -> &[QuerySolution] {
+        &self.solutions
+    }
+
+    /// Consumes `self` and returns the solutions that have been computed within the time budget.
+    pub fn into_solutions(self) -> Vec<QuerySolution> {
+        self.solutions
+    }
+
+    /// Returns true if the time budget has been exceeded. In this case, [Self::solutions] may
+    /// only contain a subset of the solutions of the query.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
@@ -0,0 +1,103 @@
+use crate::results::{QuerySolution, QuerySolutionStream};
+use crate::sparql::error::QueryEvaluationError;
+use futures::TryStreamExt;
+use rdf_fusion_model::{BlankNode, Term, Variable};
+use std::collections::HashMap;
+
+/// The solutions that differ between two executions of the same query (e.g., against two
+/// versions of a store).
+///
+/// The solutions are compared as multisets. Hence, a solution that is returned twice by the new
+/// execution but only once by the old execution is reported as added once. Blank nodes are only
+/// meaningful within a single result. Therefore, two solutions are considered equal if they are
+/// equal up to a renaming of the blank nodes within each solution. Note that this does not take
+/// blank nodes that are shared between different solutions into account.
+#[derive(Debug)]
+pub struct QuerySolutionsDiff {
+    /// The solutions that are only returned by the new execution.
+    added: Vec<QuerySolution>,
+    /// The solutions that are only returned by the old execution.
+    removed: Vec<QuerySolution>,
+}
+
+impl QuerySolutionsDiff {
+    /// Computes the difference between the solutions of `old` and `new`.
+    ///
+    /// Both streams are consumed completely. The added and removed solutions retain their order
+    /// in the respective stream.
+    pub async fn try_compute(
+        old: QuerySolutionStream,
+        new: QuerySolutionStream,
+    ) -> Result<Self, QueryEvaluationError> {
+        let old = old.try_collect::<Vec<_>>().await?;
+        let new = new.try_collect::<Vec<_>>().await?;
+
+        // The indices are reversed such that equal solutions are matched in order.
+        let mut unmatched = HashMap::<_, Vec<_>>::new();
+        for (index, solution) in old.iter().enumerate().rev() {
+            unmatched
+                .entry(normalize_solution(solution))
+                .or_default()
+                .push(index);
+        }
+
+        let mut matched = vec![false; old.len()];
+        let mut added = Vec::new();
+        for solution in new {
+            let index = unmatched
+                .get_mut(&normalize_solution(&solution))
+                .and_then(Vec::pop);
+            match index {
+                Some(index) => matched[index] = true,
+                None => added.push(solution),
+            }
+        }
+
+        let removed = old
+            .into_iter()
+            .zip(matched)
+            .filter_map(|(solution, matched)| (!matched).then_some(solution))
+            .collect();
+        Ok(Self { added, removed })
+    }
+
+    /// Returns the solutions that are only returned by the new execution.
+    pub fn added(&self) -> &[QuerySolution] {
+        &self.added
+    }
+
+    /// Returns the solutions that are only returned by the old execution.
+    pub fn removed(&self) -> &[QuerySolution] {
+        &self.removed
+    }
+
+    /// Returns true if both executions return the same solutions.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Consumes `self` and returns the added and removed solutions.
+    pub fn into_parts(self) -> (Vec<QuerySolution>, Vec<QuerySolution>) {
+        (self.added, self.removed)
+    }
+}
+
+/// Returns the bindings of `solution`, ordered by variable name. The blank nodes are renamed in
+/// the order of their first occurrence.
+fn normalize_solution(solution: &QuerySolution) -> Vec<(Variable, Term)> {
+    let mut bindings = solution
+        .iter()
+        .map(|(variable, term)| (variable.clone(), term.clone()))
+        .collect::<Vec<_>>();
+    bindings.sort_by(|(lhs, _), (rhs, _)| lhs.as_str().cmp(rhs.as_str()));
+
+    let mut blank_nodes = HashMap::new();
+    for (_, term) in &mut bindings {
+        if let Term::BlankNode(node) = term {
+            let next_id = blank_nodes.len();
+            let id = *blank_nodes.entry(node.clone()).or_insert(next_id);
+            *term = BlankNode::new_unchecked(format!("b{id}")).into();
+        }
+    }
+    bindings
+}
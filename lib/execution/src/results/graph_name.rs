@@ -13,6 +13,12 @@ use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 
 /// An iterator yielding graph names.
+///
+/// # Cancellation
+///
+/// The stream can be dropped at any time (e.g., after [take](StreamExt::take)). Dropping the
+/// stream drops the underlying execution plan, which releases the scanned storage snapshot and
+/// all locks held by it. See [QuerySolutionStream] for details.
 pub struct GraphNameStream {
     stream: QuerySolutionStream,
 }
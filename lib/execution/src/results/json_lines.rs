@@ -0,0 +1,154 @@
+use crate::results::QuerySolutionStream;
+use crate::sparql::error::QueryEvaluationError;
+use futures::{Stream, StreamExt};
+use rdf_fusion_model::vocab::xsd;
+use rdf_fusion_model::{Term, VariableRef};
+use sparesults::QuerySolution;
+use std::fmt::Write;
+
+/// The media type of the [JSON Lines](https://jsonlines.org/) result format.
+pub const JSON_LINES_MEDIA_TYPE: &str = "application/x-ndjson";
+
+/// Serializes `solutions` as [JSON Lines](https://jsonlines.org/). Each item of the returned
+/// stream is a single line (including the trailing line break) that holds one solution.
+///
+/// Each line is a JSON object that maps the names of the bound variables to a term object. Unbound
+/// variables are omitted. The term objects use the same schema as the
+/// [SPARQL 1.1 Query Results JSON Format](https://www.w3.org/TR/sparql11-results-json/#select-encode-terms).
+/// For example:
+///
+/// ```json
+/// {"s":{"type":"uri","value":"http://example.com/s"},"o":{"type":"literal","value":"1","datatype":"http://www.w3.org/2001/XMLSchema#integer"}}
+/// ```
+///
+/// The solutions are serialized lazily while the stream is polled.
+pub fn json_lines_stream(
+    solutions: QuerySolutionStream,
+) -> impl Stream<Item = Result<String, QueryEvaluationError>> + Send {
+    solutions.map(|solution| solution.map(|solution| solution_to_json_line(&solution)))
+}
+
+/// Serializes a single `solution` into a JSON object followed by a line break.
+pub fn solution_to_json_line(solution: &QuerySolution) -> String {
+    let mut line = String::from("{");
+    for (idx, (variable, term)) in solution.iter().enumerate() {
+        if idx > 0 {
+            line.push(',');
+        }
+        write_variable(&mut line, variable.as_ref());
+        write_term(&mut line, term);
+    }
+    line.push_str("}\n");
+    line
+}
+
+fn write_variable(output: &mut String, variable: VariableRef<'_>) {
+    write_json_string(output, variable.as_str());
+    output.push(':');
+}
+
+fn write_term(output: &mut String, term: &Term) {
+    match term {
+        Term::NamedNode(node) => {
+            output.push_str(r#"{"type":"uri","value":"#);
+            write_json_string(output, node.as_str());
+        }
+        Term::BlankNode(node) => {
+            output.push_str(r#"{"type":"bnode","value":"#);
+            write_json_string(output, node.as_str());
+        }
+        Term::Literal(literal) => {
+            output.push_str(r#"{"type":"literal","value":"#);
+            write_json_string(output, literal.value());
+            if let Some(language) = literal.language() {
+                output.push_str(r#","xml:lang":"#);
+                write_json_string(output, language);
+            } else if literal.datatype() != xsd::STRING {
+                output.push_str(r#","datatype":"#);
+                write_json_string(output, literal.datatype().as_str());
+            }
+        }
+    }
+    output.push('}');
+}
+
+fn write_json_string(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => {
+                write!(output, "\\u{:04x}", u32::from(c)).expect("Writing to a String");
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::{BlankNode, Literal, NamedNode, Variable};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_solution_to_json_line() {
+        let variables: Arc<[Variable]> = Arc::new([
+            Variable::new_unchecked("s"),
+            Variable::new_unchecked("unbound"),
+            Variable::new_unchecked("o"),
+        ]);
+        let solution = QuerySolution::from((
+            variables,
+            vec![
+                Some(NamedNode::new_unchecked("http://example.com/s").into()),
+                None,
+                Some(Literal::new_language_tagged_literal_unchecked("a\"b", "en").into()),
+            ],
+        ));
+
+        assert_eq!(
+            solution_to_json_line(&solution),
+            "{\"s\":{\"type\":\"uri\",\"value\":\"http://example.com/s\"},\
+            \"o\":{\"type\":\"literal\",\"value\":\"a\\\"b\",\"xml:lang\":\"en\"}}\n"
+        );
+    }
+
+    #[test]
+    fn test_solution_to_json_line_typed_literal_and_blank_node() {
+        let variables: Arc<[Variable]> =
+            Arc::new([Variable::new_unchecked("b"), Variable::new_unchecked("n")]);
+        let solution = QuerySolution::from((
+            variables,
+            vec![
+                Some(BlankNode::new_unchecked("b1").into()),
+                Some(Literal::from(1).into()),
+            ],
+        ));
+
+        assert_eq!(
+            solution_to_json_line(&solution),
+            "{\"b\":{\"type\":\"bnode\",\"value\":\"b1\"},\
+            \"n\":{\"type\":\"literal\",\"value\":\"1\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#integer\"}}\n"
+        );
+    }
+
+    #[test]
+    fn test_solution_to_json_line_escapes_control_characters() {
+        let variables: Arc<[Variable]> = Arc::new([Variable::new_unchecked("o")]);
+        let solution = QuerySolution::from((
+            variables,
+            vec![Some(Literal::new_simple_literal("a\nb\u{1}").into())],
+        ));
+
+        assert_eq!(
+            solution_to_json_line(&solution),
+            "{\"o\":{\"type\":\"literal\",\"value\":\"a\\nb\\u0001\"}}\n"
+        );
+    }
+}
@@ -10,13 +10,22 @@ use std::error::Error;
 use std::io::{Read, Write};
 use std::sync::Arc;
 
+mod best_effort;
+mod diff;
 mod graph_name;
+mod json_lines;
+mod partial;
 mod quads;
 mod query_solution;
+mod string_batches;
 mod triples;
 
 use crate::sparql::error::QueryEvaluationError;
+pub use best_effort::BestEffortQuerySolutions;
+pub use diff::QuerySolutionsDiff;
 pub use graph_name::GraphNameStream;
+pub use json_lines::{JSON_LINES_MEDIA_TYPE, json_lines_stream, solution_to_json_line};
+pub use partial::PartialQuerySolutions;
 pub use quads::QuadStream;
 pub use query_solution::QuerySolutionStream;
 use rdf_fusion_encoding::plain_term::{
@@ -28,6 +37,7 @@ pub use sparesults::{
     QueryResultsSerializer, QuerySolution, ReaderQueryResultsParserOutput,
     WriterSolutionsSerializer,
 };
+pub use string_batches::string_batch_schema;
 pub use triples::QueryTripleStream;
 
 /// Results of a [SPARQL query](https://www.w3.org/TR/sparql11-query/).
@@ -0,0 +1,75 @@
+use crate::results::{QuerySolution, QuerySolutionStream};
+use crate::sparql::error::QueryEvaluationError;
+use futures::StreamExt;
+use rdf_fusion_model::Variable;
+use std::sync::Arc;
+
+/// The first solutions of a query together with an indicator whether more solutions exist.
+///
+/// This is useful for showing a preview of the results (e.g., "first 100 results, more
+/// available") without computing all solutions of a query.
+#[derive(Debug)]
+pub struct PartialQuerySolutions {
+    /// The variables used in the solutions.
+    variables: Arc<[Variable]>,
+    /// The first solutions of the query.
+    solutions: Vec<QuerySolution>,
+    /// Whether the query has more solutions than contained in `solutions`.
+    has_more: bool,
+}
+
+impl PartialQuerySolutions {
+    /// Reads the first `limit` solutions from `stream`.
+    ///
+    /// One additional solution is read to determine whether more solutions exist. The stream is
+    /// dropped afterward, which stops the evaluation of the query. To avoid computing solutions
+    /// that are never read, the query should be limited to `limit + 1` solutions (see
+    /// [Query::with_solution_limit](crate::sparql::Query::with_solution_limit)).
+    pub async fn try_collect(
+        mut stream: QuerySolutionStream,
+        limit: usize,
+    ) -> Result<Self, QueryEvaluationError> {
+        let variables = Arc::from(stream.variables());
+
+        let mut solutions = Vec::new();
+        while solutions.len() < limit {
+            match stream.next().await {
+                None => {
+                    return Ok(Self {
+                        variables,
+                        solutions,
+                        has_more: false,
+                    });
+                }
+                Some(solution) => solutions.push(solution?),
+            }
+        }
+
+        let has_more = stream.next().await.transpose()?.is_some();
+        Ok(Self {
+            variables,
+            solutions,
+            has_more,
+        })
+    }
+
+    /// The variables used in the solutions.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Returns the first solutions of the query.
+    pub fn solutions(&self) -> &[QuerySolution] {
+        &self.solutions
+    }
+
+    /// Consumes `self` and returns the first solutions of the query.
+    pub fn into_solutions(self) -> Vec<QuerySolution> {
+        self.solutions
+    }
+
+    /// Returns true if the query has more solutions than returned by [Self::solutions].
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+}
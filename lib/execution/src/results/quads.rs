@@ -9,6 +9,12 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 /// An iterator returning quads
+///
+/// # Cancellation
+///
+/// The stream can be dropped at any time (e.g., after [take](StreamExt::take)). Dropping the
+/// stream drops the underlying execution plan, which releases the scanned storage snapshot and
+/// all locks held by it. See [QuerySolutionStream] for details.
 pub struct QuadStream {
     inner: QuerySolutionStream,
 }
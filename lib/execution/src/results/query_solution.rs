@@ -1,3 +1,4 @@
+use crate::results::string_batches::string_record_batch_stream;
 use crate::sparql::error::QueryEvaluationError;
 use datafusion::arrow::array::RecordBatch;
 use datafusion::common::exec_err;
@@ -14,6 +15,15 @@ use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 
 /// A stream over [`QuerySolution`]s.
+///
+/// # Cancellation
+///
+/// The stream owns the DataFusion stream that drives the query execution. The execution plan, in
+/// turn, holds the storage snapshot that is scanned by the query (for the in-memory storage, a
+/// version of the indexes). The snapshot is released as soon as the stream is exhausted or dropped,
+/// whichever happens first. Consumers that stop early (e.g., after [take](StreamExt::take))
+/// should drop the stream instead of keeping it around, as a pending stream retains the resources
+/// of its snapshot (e.g., an outdated copy of the indexes).
 pub struct QuerySolutionStream {
     /// The variables used in the query solutions.
     variables: Arc<[Variable]>,
@@ -72,6 +82,36 @@ impl QuerySolutionStream {
         }
     }
 
+    /// Returns the solutions as a [SendableRecordBatchStream] with a flat, string-typed schema.
+    ///
+    /// Each variable is mapped to a nullable [Utf8](datafusion::arrow::datatypes::DataType::Utf8)
+    /// column that holds the [N-Triples](https://www.w3.org/TR/n-triples/) representation of the
+    /// bound terms (e.g., `<http://example.com/s>`, `_:b1`, or `"1"^^<http://www.w3.org/2001/XMLSchema#integer>`).
+    /// Unbound values are null. Contrary to [Self::into_record_batch_stream], the batches do not
+    /// depend on the internal term encoding and can be directly loaded into dataframe libraries
+    /// (e.g., pandas or polars via pyarrow). See [string_batch_schema](crate::results::string_batch_schema)
+    /// for the schema of the batches.
+    ///
+    /// # Errors
+    ///
+    /// If the stream has been fully consumed.
+    pub fn into_string_record_batch_stream(self) -> DFResult<SendableRecordBatchStream> {
+        let variables = Arc::clone(&self.variables);
+        let inner = self.into_record_batch_stream()?;
+        Ok(string_record_batch_stream(&variables, inner))
+    }
+
+    /// Replaces the underlying [SendableRecordBatchStream] with the result of `f`.
+    ///
+    /// Does nothing if the stream has already been consumed.
+    pub(crate) fn map_record_batch_stream(
+        mut self,
+        f: impl FnOnce(SendableRecordBatchStream) -> SendableRecordBatchStream,
+    ) -> Self {
+        self.inner = self.inner.map(f);
+        self
+    }
+
     fn poll_inner(
         &mut self,
         ctx: &mut Context<'_>,
@@ -186,6 +226,7 @@ fn to_query_solution(
 mod tests {
     use super::*;
     use crate::results::{QueryResults, query_result_for_iterator};
+    use rdf_fusion_model::vocab::xsd;
     use rdf_fusion_model::{BlankNode, Literal, NamedNode};
     use sparesults::QueryResultsFormat;
     use std::error::Error;
@@ -281,4 +322,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_csv_serialization_w3c_example() -> Result<(), Box<dyn Error>> {
+        let buffer = w3c_example_results()?
+            .write(Vec::new(), QueryResultsFormat::Csv)
+            .await?;
+
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            "x,literal\r\n\
+            http://example/x,String\r\n\
+            http://example/x,\"String-with-dquote\"\"\"\r\n\
+            _:b0,Blank node\r\n\
+            ,Missing 'x'\r\n\
+            ,\r\n\
+            http://example/x,\r\n\
+            _:b1,String-with-lang\r\n\
+            _:b1,123\r\n\
+            http://example/x,2011-01-10\r\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tsv_serialization_w3c_example() -> Result<(), Box<dyn Error>> {
+        let buffer = w3c_example_results()?
+            .write(Vec::new(), QueryResultsFormat::Tsv)
+            .await?;
+
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            "?x\t?literal\n\
+            <http://example/x>\t\"String\"\n\
+            <http://example/x>\t\"String-with-dquote\\\"\"\n\
+            _:b0\t\"Blank node\"\n\
+            \t\"Missing 'x'\"\n\
+            \t\n\
+            <http://example/x>\t\n\
+            _:b1\t\"String-with-lang\"@en\n\
+            _:b1\t123\n\
+            <http://example/x>\t\"2011-01-10\"^^<http://www.w3.org/2001/XMLSchema#date>\n"
+        );
+        Ok(())
+    }
+
+    /// The example from the [W3C recommendation](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+    /// on the CSV and TSV formats, extended with a typed literal.
+    fn w3c_example_results() -> Result<QueryResults, Box<dyn Error>> {
+        let variables: Arc<[Variable]> = Arc::new([
+            Variable::new_unchecked("x"),
+            Variable::new_unchecked("literal"),
+        ]);
+        let x = NamedNode::new_unchecked("http://example/x");
+
+        let terms = vec![
+            vec![
+                Some(x.clone().into()),
+                Some(Literal::new_simple_literal("String").into()),
+            ],
+            vec![
+                Some(x.clone().into()),
+                Some(Literal::new_simple_literal("String-with-dquote\"").into()),
+            ],
+            vec![
+                Some(BlankNode::new_unchecked("b0").into()),
+                Some(Literal::new_simple_literal("Blank node").into()),
+            ],
+            vec![
+                None,
+                Some(Literal::new_simple_literal("Missing 'x'").into()),
+            ],
+            vec![None, None],
+            vec![Some(x.clone().into()), None],
+            vec![
+                Some(BlankNode::new_unchecked("b1").into()),
+                Some(
+                    Literal::new_language_tagged_literal_unchecked(
+                        "String-with-lang",
+                        "en",
+                    )
+                    .into(),
+                ),
+            ],
+            vec![
+                Some(BlankNode::new_unchecked("b1").into()),
+                Some(Literal::from(123).into()),
+            ],
+            vec![
+                Some(x.into()),
+                Some(Literal::new_typed_literal("2011-01-10", xsd::DATE).into()),
+            ],
+        ]
+        .into_iter()
+        .map(|ts| Ok(QuerySolution::from((Arc::clone(&variables), ts))));
+
+        Ok(query_result_for_iterator(Arc::clone(&variables), terms)?)
+    }
 }
@@ -0,0 +1,128 @@
+use datafusion::arrow::array::{
+    ArrayRef, RecordBatch, RecordBatchOptions, StringBuilder,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::StreamExt;
+use rdf_fusion_encoding::TermDecoder;
+use rdf_fusion_encoding::TermEncoding;
+use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+use rdf_fusion_encoding::plain_term::decoders::DefaultPlainTermDecoder;
+use rdf_fusion_model::{DFResult, Variable};
+use std::sync::Arc;
+
+/// Creates the schema of the string batches for the given `variables`.
+///
+/// Each variable is mapped to a nullable [DataType::Utf8] column with the same name.
+pub fn string_batch_schema(variables: &[Variable]) -> SchemaRef {
+    let fields = variables
+        .iter()
+        .map(|v| Field::new(v.as_str(), DataType::Utf8, true))
+        .collect::<Vec<_>>();
+    Arc::new(Schema::new(fields))
+}
+
+/// Converts a stream of query solutions in the plain term encoding into a stream of string
+/// batches.
+///
+/// See [QuerySolutionStream::into_string_record_batch_stream](crate::results::QuerySolutionStream::into_string_record_batch_stream)
+/// for details.
+pub(crate) fn string_record_batch_stream(
+    variables: &[Variable],
+    inner: SendableRecordBatchStream,
+) -> SendableRecordBatchStream {
+    let schema = string_batch_schema(variables);
+    let stream_schema = Arc::clone(&schema);
+    let stream =
+        inner.map(move |batch| batch.and_then(|batch| to_string_batch(&schema, &batch)));
+    Box::pin(RecordBatchStreamAdapter::new(stream_schema, stream))
+}
+
+/// Converts each column of `batch` into a column with the N-Triples representation of its terms.
+fn to_string_batch(schema: &SchemaRef, batch: &RecordBatch) -> DFResult<RecordBatch> {
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| {
+            let array = PLAIN_TERM_ENCODING.try_new_array(Arc::clone(column))?;
+            let mut builder = StringBuilder::with_capacity(batch.num_rows(), 0);
+            for term in DefaultPlainTermDecoder::decode_terms(&array) {
+                match term {
+                    Ok(term) => builder.append_value(term.to_string()),
+                    Err(_) => builder.append_null(),
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    Ok(RecordBatch::try_new_with_options(
+        Arc::clone(schema),
+        columns,
+        &options,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::results::{QueryResults, query_result_for_iterator};
+    use datafusion::arrow::array::{Array, AsArray};
+    use datafusion::arrow::datatypes::DataType;
+    use futures::TryStreamExt;
+    use rdf_fusion_model::{BlankNode, Literal, NamedNode, Variable};
+    use sparesults::QuerySolution;
+    use std::error::Error;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_string_record_batch_stream() -> Result<(), Box<dyn Error>> {
+        let variables: Arc<[Variable]> =
+            Arc::new([Variable::new_unchecked("s"), Variable::new_unchecked("o")]);
+        let terms = vec![
+            vec![
+                Some(NamedNode::new_unchecked("http://example.com/s").into()),
+                Some(Literal::new_language_tagged_literal_unchecked("a\"b", "en").into()),
+            ],
+            vec![
+                Some(BlankNode::new_unchecked("b1").into()),
+                Some(Literal::from(1).into()),
+            ],
+            vec![None, Some(Literal::new_simple_literal("c").into())],
+        ]
+        .into_iter()
+        .map(|ts| Ok(QuerySolution::from((Arc::clone(&variables), ts))));
+        let QueryResults::Solutions(solutions) =
+            query_result_for_iterator(Arc::clone(&variables), terms)?
+        else {
+            panic!("Expected solutions");
+        };
+
+        let stream = solutions.into_string_record_batch_stream()?;
+        let schema = stream.schema();
+        assert_eq!(schema.field(0).name(), "s");
+        assert_eq!(schema.field(1).name(), "o");
+        assert!(
+            schema
+                .fields()
+                .iter()
+                .all(|f| f.data_type() == &DataType::Utf8 && f.is_nullable())
+        );
+
+        let batches = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(batches.len(), 1);
+        let subjects = batches[0].column(0).as_string::<i32>();
+        let objects = batches[0].column(1).as_string::<i32>();
+        assert_eq!(subjects.value(0), "<http://example.com/s>");
+        assert_eq!(subjects.value(1), "_:b1");
+        assert!(subjects.is_null(2));
+        assert_eq!(objects.value(0), "\"a\\\"b\"@en");
+        assert_eq!(
+            objects.value(1),
+            "\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+        );
+        assert_eq!(objects.value(2), "\"c\"");
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::results::QuerySolutionStream;
 use crate::sparql::error::QueryEvaluationError;
 use futures::{Stream, StreamExt};
-use rdf_fusion_model::{BlankNode, Graph, Term, Triple};
+use rdf_fusion_model::{BlankNode, Graph, GraphName, Quad, Term, Triple};
 use sparesults::QuerySolution;
 use spargebra::term::{TermPattern, TriplePattern};
 use std::collections::{HashMap, HashSet};
@@ -29,6 +29,15 @@ impl QueryTripleStream {
         }
     }
 
+    /// Replaces the underlying [QuerySolutionStream] with the result of `f`.
+    pub(crate) fn map_solutions(
+        mut self,
+        f: impl FnOnce(QuerySolutionStream) -> QuerySolutionStream,
+    ) -> Self {
+        self.inner = f(self.inner);
+        self
+    }
+
     pub async fn collect_as_graph(&mut self) -> Result<Graph, QueryEvaluationError> {
         let mut graph = Graph::new();
         while let Some(triple) = self.next().await {
@@ -38,6 +47,20 @@ impl QueryTripleStream {
         Ok(graph)
     }
 
+    /// Places all constructed triples into the graph `graph_name`.
+    ///
+    /// This allows materializing the results of a `CONSTRUCT` query into a named graph. The
+    /// resulting quads are the same as the ones inserted by
+    /// `INSERT { GRAPH <graph_name> { template } } WHERE { pattern }`, except that duplicate
+    /// triples are already removed.
+    pub fn into_quads(
+        self,
+        graph_name: impl Into<GraphName>,
+    ) -> impl Stream<Item = Result<Quad, QueryEvaluationError>> + Send {
+        let graph_name = graph_name.into();
+        self.map(move |triple| triple.map(|triple| triple.in_graph(graph_name.clone())))
+    }
+
     fn poll_inner(
         &mut self,
         cx: &mut Context<'_>,
@@ -2,8 +2,11 @@
 //!
 //! The root type for SPARQL queries is [`Query`] and the root type for updates is [`Update`].
 
-use rdf_fusion_model::{GraphName, NamedOrBlankNode};
-use spargebra::{GraphUpdateOperation, SparqlSyntaxError};
+use crate::sparql::path_repetition::rewrite_path_repetitions;
+use rdf_fusion_logical::paths::PathRepetitions;
+use rdf_fusion_model::{GraphName, NamedNode, NamedOrBlankNode};
+use spargebra::algebra::GraphPattern;
+use spargebra::{GraphUpdateOperation, SparqlParser, SparqlSyntaxError};
 use std::fmt;
 use std::str::FromStr;
 
@@ -32,19 +35,87 @@ use std::str::FromStr;
 pub struct Query {
     pub(super) inner: spargebra::Query,
     pub(super) dataset: QueryDataset,
+    /// Whether the query plans are returned instead of the results (`EXPLAIN` prefix).
+    pub(super) explain: bool,
+    /// The bounded repetitions of the property paths in the query.
+    pub(super) path_repetitions: PathRepetitions,
 }
 
 impl Query {
     /// Parses a SPARQL query with an optional base IRI to resolve relative IRIs in the query.
+    ///
+    /// # Explaining Queries
+    ///
+    /// As a non-standard extension, a query can be prefixed with the `EXPLAIN` keyword (e.g.,
+    /// `EXPLAIN SELECT * { ?s ?p ?o }`). Instead of the results of the query, the evaluation then
+    /// returns the optimized logical plan and the physical plan of the query as solutions with
+    /// the variables `?plan_type` and `?plan`.
     pub fn parse(query: &str, base_iri: Option<&str>) -> Result<Self, SparqlSyntaxError> {
+        let (explain, query) = strip_explain_keyword(query);
         #[allow(deprecated, reason = "Converting to SparqlSyntaxError")]
         let query = Self::from(spargebra::Query::parse(query, base_iri)?);
+        Ok(Self { explain, ..query })
+    }
+
+    /// Parses a SPARQL query like [Self::parse] and additionally supports bounded repetitions of
+    /// property paths.
+    ///
+    /// # Bounded Property Paths
+    ///
+    /// As a non-standard extension, property paths can be repeated a bounded number of times
+    /// (e.g., `?s :p{2,4} ?o` matches two to four steps of `:p`). The forms `{n}`, `{n,}`, and
+    /// `{n,m}` are supported. The solutions of a repeated path are deduplicated. Repetitions with
+    /// more than [MAX_PATH_REPETITION_STEPS](rdf_fusion_logical::paths::MAX_PATH_REPETITION_STEPS)
+    /// steps are rejected when the query is evaluated.
+    ///
+    /// The repetitions are rewritten before the query is passed to the SPARQL 1.1 parser. Hence,
+    /// the positions in syntax errors may not match the original query.
+    pub fn parse_with_path_repetitions(
+        query: &str,
+        base_iri: Option<&str>,
+    ) -> Result<Self, SparqlSyntaxError> {
+        let (query, path_repetitions) = rewrite_path_repetitions(query);
         Ok(Self {
-            dataset: query.dataset,
-            inner: query.inner,
+            path_repetitions,
+            ..Self::parse(&query, base_iri)?
         })
     }
 
+    /// Parses a SPARQL query in which calls to one of the functions in `custom_aggregates` are
+    /// treated as calls to custom aggregate functions (e.g., `SELECT (<urn:median>(?x) AS ?m)`).
+    ///
+    /// The aggregate functions must be registered in the function registry of the engine that
+    /// evaluates the query.
+    pub fn parse_with_custom_aggregates(
+        query: &str,
+        custom_aggregates: impl IntoIterator<Item = NamedNode>,
+    ) -> Result<Self, SparqlSyntaxError> {
+        let parser = custom_aggregates.into_iter().fold(
+            SparqlParser::new(),
+            SparqlParser::with_custom_aggregate_function,
+        );
+        Ok(Self::from(parser.parse_query(query)?))
+    }
+
+    /// Returns whether the query has been prefixed with `EXPLAIN` (see [Self::parse]).
+    pub fn is_explain(&self) -> bool {
+        self.explain
+    }
+
+    /// Returns a copy of this query without the `EXPLAIN` prefix.
+    pub fn without_explain(&self) -> Self {
+        Self {
+            explain: false,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the bounded repetitions of the property paths in the query (see
+    /// [Self::parse_with_path_repetitions]).
+    pub fn path_repetitions(&self) -> &PathRepetitions {
+        &self.path_repetitions
+    }
+
     /// Returns [the query dataset specification](https://www.w3.org/TR/sparql11-query/#specifyingDataset)
     pub fn dataset(&self) -> &QueryDataset {
         &self.dataset
@@ -54,10 +125,61 @@ impl Query {
     pub fn dataset_mut(&mut self) -> &mut QueryDataset {
         &mut self.dataset
     }
+
+    /// Returns a copy of this query that produces at most the first `limit` solutions.
+    ///
+    /// The limit is applied on top of the solution modifiers (e.g., `LIMIT` and `OFFSET`) of the
+    /// query. Returns [None] if this is not a `SELECT` query.
+    pub fn with_solution_limit(&self, limit: usize) -> Option<Self> {
+        let spargebra::Query::Select {
+            dataset,
+            pattern,
+            base_iri,
+        } = &self.inner
+        else {
+            return None;
+        };
+
+        Some(Self {
+            inner: spargebra::Query::Select {
+                dataset: dataset.clone(),
+                pattern: GraphPattern::Slice {
+                    inner: Box::new(pattern.clone()),
+                    start: 0,
+                    length: Some(limit),
+                },
+                base_iri: base_iri.clone(),
+            },
+            dataset: self.dataset.clone(),
+            explain: self.explain,
+            path_repetitions: self.path_repetitions.clone(),
+        })
+    }
+}
+
+/// Removes a leading `EXPLAIN` keyword from `query`.
+///
+/// Returns whether the keyword was present and the remaining query.
+fn strip_explain_keyword(query: &str) -> (bool, &str) {
+    const KEYWORD: &str = "EXPLAIN";
+
+    let trimmed = query.trim_start();
+    let is_explain = trimmed
+        .get(..KEYWORD.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(KEYWORD))
+        && trimmed[KEYWORD.len()..].starts_with(char::is_whitespace);
+    if is_explain {
+        (true, &trimmed[KEYWORD.len()..])
+    } else {
+        (false, query)
+    }
 }
 
 impl fmt::Display for Query {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.explain {
+            write!(f, "EXPLAIN ")?;
+        }
         self.inner.fmt(f) // TODO: override
     }
 }
@@ -96,6 +218,8 @@ impl From<spargebra::Query> for Query {
                 | spargebra::Query::Ask { dataset, .. } => dataset,
             }),
             inner: query,
+            explain: false,
+            path_repetitions: PathRepetitions::default(),
         }
     }
 }
@@ -117,6 +241,8 @@ impl From<spargebra::Query> for Query {
 pub struct Update {
     pub(super) inner: spargebra::Update,
     pub(super) using_datasets: Vec<Option<QueryDataset>>,
+    /// The bounded repetitions of the property paths in the update.
+    pub(super) path_repetitions: PathRepetitions,
 }
 
 impl Update {
@@ -129,6 +255,25 @@ impl Update {
         Ok(spargebra::Update::parse(update, base_iri)?.into())
     }
 
+    /// Parses a SPARQL update like [Self::parse] and additionally supports bounded repetitions
+    /// of property paths in the `WHERE` clauses (see [Query::parse_with_path_repetitions]).
+    pub fn parse_with_path_repetitions(
+        update: &str,
+        base_iri: Option<&str>,
+    ) -> Result<Self, SparqlSyntaxError> {
+        let (update, path_repetitions) = rewrite_path_repetitions(update);
+        Ok(Self {
+            path_repetitions,
+            ..Self::parse(&update, base_iri)?
+        })
+    }
+
+    /// Returns the bounded repetitions of the property paths in the update (see
+    /// [Self::parse_with_path_repetitions]).
+    pub fn path_repetitions(&self) -> &PathRepetitions {
+        &self.path_repetitions
+    }
+
     /// Returns [the query dataset specification](https://www.w3.org/TR/sparql11-query/#specifyingDataset) in [DELETE/INSERT operations](https://www.w3.org/TR/sparql11-update/#deleteInsert).
     pub fn using_datasets(&self) -> impl Iterator<Item = &QueryDataset> {
         self.using_datasets.iter().filter_map(Option::as_ref)
@@ -185,6 +330,7 @@ impl From<spargebra::Update> for Update {
                 })
                 .collect(),
             inner: update,
+            path_repetitions: PathRepetitions::default(),
         }
     }
 }
@@ -309,4 +455,18 @@ mod tests {
         is_send_sync::<Query>();
         is_send_sync::<Update>();
     }
+
+    #[test]
+    fn test_parse_explain() {
+        let query =
+            Query::parse("  explain\nSELECT ?s WHERE { ?s ?p ?o }", None).unwrap();
+        assert!(query.is_explain());
+        assert_eq!(query.to_string(), "EXPLAIN SELECT ?s WHERE { ?s ?p ?o . }");
+        assert!(!query.without_explain().is_explain());
+
+        let query =
+            Query::parse("SELECT ?explain WHERE { ?s ?p ?explain }", None).unwrap();
+        assert!(!query.is_explain());
+        assert!(Query::parse("EXPLAINSELECT * WHERE { ?s ?p ?o }", None).is_err());
+    }
 }
@@ -0,0 +1,200 @@
+use crate::results::QueryResults;
+use crate::sparql::error::QueryEvaluationError;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::DataFusionError;
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream};
+use futures::future::{BoxFuture, Either};
+use futures::{FutureExt, Stream, StreamExt, future};
+use rdf_fusion_model::DFResult;
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// A token that allows cancelling the evaluation of queries from another task.
+///
+/// For example, an HTTP endpoint can cancel the evaluation of a query once the client has
+/// disconnected. The token can be cloned and all clones refer to the same cancellation state.
+/// Once cancelled, a token cannot be reset.
+#[derive(Debug, Clone)]
+pub struct QueryCancellationToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl QueryCancellationToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Cancels all queries that are evaluated with this token.
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Returns true if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Completes once the token has been cancelled.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender is owned by self and cannot be dropped while waiting.
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for QueryCancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Defines when the evaluation of a query is stopped.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryCancellation {
+    /// The point in time at which the evaluation fails with [QueryEvaluationError::Timeout].
+    deadline: Option<Instant>,
+    /// The token that stops the evaluation with [QueryEvaluationError::Cancelled].
+    token: Option<QueryCancellationToken>,
+}
+
+impl QueryCancellation {
+    /// Creates a new [QueryCancellation] that starts counting the `timeout` immediately.
+    pub(crate) fn new(
+        timeout: Option<Duration>,
+        token: Option<QueryCancellationToken>,
+    ) -> Self {
+        Self {
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            token,
+        }
+    }
+
+    /// Returns the corresponding error if the query must be stopped at this point in time.
+    fn check(&self) -> Option<QueryEvaluationError> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(QueryCancellationToken::is_cancelled)
+        {
+            return Some(QueryEvaluationError::Cancelled);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Some(QueryEvaluationError::Timeout);
+        }
+        None
+    }
+
+    /// Completes with the corresponding error once the query must be stopped.
+    pub(crate) async fn stopped(self) -> QueryEvaluationError {
+        let timeout = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let cancelled = async {
+            match &self.token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+
+        match future::select(pin!(cancelled), pin!(timeout)).await {
+            Either::Left(_) => QueryEvaluationError::Cancelled,
+            Either::Right(_) => QueryEvaluationError::Timeout,
+        }
+    }
+
+    /// Wraps the streams of `results` such that they fail once the query must be stopped.
+    pub(crate) fn wrap_results(self, results: QueryResults) -> QueryResults {
+        match results {
+            QueryResults::Solutions(solutions) => QueryResults::Solutions(
+                solutions.map_record_batch_stream(|stream| self.wrap_stream(stream)),
+            ),
+            QueryResults::Graph(triples) => {
+                QueryResults::Graph(triples.map_solutions(|solutions| {
+                    solutions.map_record_batch_stream(|stream| self.wrap_stream(stream))
+                }))
+            }
+            QueryResults::Boolean(value) => QueryResults::Boolean(value),
+        }
+    }
+
+    fn wrap_stream(self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        Box::pin(CancellableRecordBatchStream {
+            schema: stream.schema(),
+            inner: Some(stream),
+            cancellation: self.clone(),
+            stopped: self.stopped().boxed(),
+        })
+    }
+}
+
+/// A stream that stops the wrapped stream once the query must be stopped.
+///
+/// Stopping the stream drops the wrapped stream. This stops the execution of the query and
+/// releases all resources (e.g., storage snapshots) held by it. The error that stopped the query
+/// is returned as an external [DataFusionError], which is converted back into the original
+/// [QueryEvaluationError].
+///
+/// The cancellation is also checked synchronously on every poll. Otherwise, an inner stream that
+/// is always ready could prevent the timer from firing, as the cooperative scheduling budget of
+/// the runtime is exhausted before the task yields.
+struct CancellableRecordBatchStream {
+    schema: SchemaRef,
+    inner: Option<SendableRecordBatchStream>,
+    cancellation: QueryCancellation,
+    stopped: BoxFuture<'static, QueryEvaluationError>,
+}
+
+impl Stream for CancellableRecordBatchStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+
+        let error = match self.cancellation.check() {
+            Some(error) => Some(error),
+            None => match self.stopped.poll_unpin(cx) {
+                Poll::Ready(error) => Some(error),
+                Poll::Pending => None,
+            },
+        };
+        if let Some(error) = error {
+            self.inner = None;
+            return Poll::Ready(Some(Err(DataFusionError::External(Box::new(error)))));
+        }
+
+        let Some(inner) = self.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let result = inner.poll_next_unpin(cx);
+        if let Poll::Ready(None) = result {
+            self.inner = None;
+        }
+        result
+    }
+}
+
+impl RecordBatchStream for CancellableRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
@@ -80,12 +80,25 @@ pub enum QueryEvaluationError {
     /// The given content media type returned from an HTTP response is not supported (`SERVICE` and `LOAD`)
     #[error("The content media type {0} is not supported")]
     UnsupportedContentType(String),
+    /// The source of `LOAD` is a file outside of the allowed directories (see
+    /// [UpdateOptions::load_directories](crate::sparql::UpdateOptions::load_directories)).
+    #[error("Loading {0} is not allowed")]
+    LoadNotAllowed(NamedNode),
     /// The `SERVICE` call has not returns solutions
     #[error("The service is not returning solutions but a boolean or a graph")]
     ServiceDoesNotReturnSolutions,
     /// The results are not a RDF graph
     #[error("The query results are not a RDF graph")]
     NotAGraph,
+    /// The results are not solutions of a `SELECT` query
+    #[error("The query results are not solutions")]
+    NotSolutions,
+    /// The query evaluation has exceeded its timeout.
+    #[error("The query evaluation has exceeded its timeout")]
+    Timeout,
+    /// The query evaluation has been cancelled.
+    #[error("The query evaluation has been cancelled")]
+    Cancelled,
     #[error("An error returned from the query engine: {0}")]
     Engine(DataFusionError),
     #[error("A feature has not yet been implemented: {0}")]
@@ -123,6 +136,14 @@ impl From<SparqlEvaluationError> for QueryEvaluationError {
 
 impl From<DataFusionError> for QueryEvaluationError {
     fn from(error: DataFusionError) -> Self {
-        Self::Engine(error)
+        match error {
+            // Errors raised by RdfFusion within a DataFusion stream (e.g., timeouts) are passed
+            // through as external errors.
+            DataFusionError::External(error) => match error.downcast() {
+                Ok(error) => *error,
+                Err(error) => Self::Engine(DataFusionError::External(error)),
+            },
+            error => Self::Engine(error),
+        }
     }
 }
@@ -1,18 +1,29 @@
 use crate::RdfFusionContext;
-use crate::results::{QueryResults, QuerySolutionStream, QueryTripleStream};
+use crate::results::{
+    QueryResults, QuerySolution, QuerySolutionStream, QueryTripleStream,
+    query_result_for_iterator,
+};
 use crate::sparql::error::QueryEvaluationError;
 use crate::sparql::optimizer::{create_optimizer_rules, create_pyhsical_optimizer_rules};
+use crate::sparql::plan_cache::{CachedQueryPlans, QueryPlanCache, QueryPlanCacheKey};
 use crate::sparql::rewriting::GraphPatternRewriter;
-use crate::sparql::{Query, QueryDataset, QueryExplanation, QueryOptions};
+use crate::sparql::{
+    OptimizationLevel, Query, QueryDataset, QueryExplanation, QueryOptions,
+};
 use datafusion::arrow::datatypes::Schema;
 use datafusion::common::instant::Instant;
 use datafusion::execution::{SessionState, SessionStateBuilder};
-use datafusion::physical_plan::{ExecutionPlan, execute_stream};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::{ExecutionPlan, ExecutionPlanProperties, execute_stream};
 use futures::StreamExt;
 use itertools::izip;
 use rdf_fusion_logical::RdfFusionLogicalPlanBuilderContext;
-use rdf_fusion_model::Iri;
-use rdf_fusion_model::Variable;
+use rdf_fusion_logical::entailment::{EntailmentRegime, SameAsClasses};
+use rdf_fusion_logical::paths::PathRepetitions;
+use rdf_fusion_model::vocab::owl;
+use rdf_fusion_model::{DFResult, Literal, Variable};
+use rdf_fusion_model::{Iri, Term};
 use spargebra::algebra::GraphPattern;
 use spargebra::term::TriplePattern;
 use std::sync::Arc;
@@ -27,6 +38,19 @@ pub async fn evaluate_query(
     query: &Query,
     options: QueryOptions,
 ) -> Result<(QueryResults, QueryExplanation), QueryEvaluationError> {
+    if query.is_explain() {
+        // The results of the explained query are dropped without consuming them.
+        let (_, explanation) = Box::pin(evaluate_query(
+            ctx,
+            builder_context,
+            &query.without_explain(),
+            options,
+        ))
+        .await?;
+        let results = explanation_to_results(&explanation)?;
+        return Ok((results, explanation));
+    }
+
     let session_state = SessionStateBuilder::from(ctx.session_context().state())
         .with_optimizer_rules(create_optimizer_rules(
             ctx.create_view(),
@@ -36,6 +60,32 @@ pub async fn evaluate_query(
             options.optimization_level,
         ))
         .build();
+    let plan_cache = ctx.query_plan_cache();
+    let cache_key = QueryPlanCacheKey::new(
+        query,
+        options.optimization_level,
+        options.entailment,
+        options.deterministic_order,
+        options.path_depth_limit,
+        ctx.functions().version(),
+    );
+    let builder_context = match options.entailment {
+        EntailmentRegime::OwlSameAs => {
+            let classes = compute_same_as_classes(
+                ctx,
+                session_state.clone(),
+                builder_context.clone(),
+                query,
+                options.optimization_level,
+            )
+            .await?;
+            builder_context.with_same_as_classes(Arc::new(classes))
+        }
+        _ => builder_context,
+    }
+    .with_entailment(options.entailment)
+    .with_path_depth_limit(options.path_depth_limit)
+    .with_path_repetitions(query.path_repetitions().clone());
 
     match &query.inner {
         spargebra::Query::Select {
@@ -44,9 +94,12 @@ pub async fn evaluate_query(
             let (stream, explanation) = Box::pin(graph_pattern_to_stream(
                 session_state,
                 builder_context,
+                plan_cache,
+                cache_key,
                 query,
                 pattern,
                 base_iri,
+                options.deterministic_order,
             ))
             .await?;
             Ok((QueryResults::Solutions(stream), explanation))
@@ -60,9 +113,12 @@ pub async fn evaluate_query(
             let (stream, explanation) = Box::pin(graph_pattern_to_stream(
                 session_state,
                 builder_context,
+                plan_cache,
+                cache_key,
                 query,
                 pattern,
                 base_iri,
+                options.deterministic_order,
             ))
             .await?;
             Ok((
@@ -73,12 +129,23 @@ pub async fn evaluate_query(
         spargebra::Query::Ask {
             pattern, base_iri, ..
         } => {
+            // Only the existence of a single solution is relevant. Limiting the pattern to one
+            // solution allows the limit to be pushed into the scans such that they stop early.
+            // The order of the solutions is irrelevant.
+            let pattern = GraphPattern::Slice {
+                inner: Box::new(pattern.clone()),
+                start: 0,
+                length: Some(1),
+            };
             let (mut stream, explanation) = Box::pin(graph_pattern_to_stream(
                 session_state,
                 builder_context,
+                plan_cache,
+                cache_key,
                 query,
-                pattern,
+                &pattern,
                 base_iri,
+                false,
             ))
             .await?;
             let count = stream.next().await;
@@ -118,9 +185,12 @@ pub async fn evaluate_query(
             let (stream, explanation) = Box::pin(graph_pattern_to_stream(
                 session_state,
                 builder_context,
+                plan_cache,
+                cache_key,
                 query,
                 &pattern,
                 base_iri,
+                options.deterministic_order,
             ))
             .await?;
 
@@ -132,19 +202,118 @@ pub async fn evaluate_query(
     }
 }
 
+/// Returns the plans in `explanation` as the solutions of an `EXPLAIN` query.
+///
+/// Similar to the `EXPLAIN` statement of DataFusion, there is one solution per plan with the
+/// variables `?plan_type` and `?plan`.
+fn explanation_to_results(
+    explanation: &QueryExplanation,
+) -> Result<QueryResults, QueryEvaluationError> {
+    let variables: Arc<[Variable]> = Arc::new([
+        Variable::new_unchecked("plan_type"),
+        Variable::new_unchecked("plan"),
+    ]);
+    let solutions = [
+        ("logical_plan", explanation.logical_plan_string()),
+        ("physical_plan", explanation.physical_plan_string()),
+    ]
+    .into_iter()
+    .map(|(plan_type, plan)| {
+        let values = vec![
+            Some(Literal::new_simple_literal(plan_type).into()),
+            Some(Literal::new_simple_literal(plan).into()),
+        ];
+        Ok(QuerySolution::from((Arc::clone(&variables), values)))
+    });
+    query_result_for_iterator(Arc::clone(&variables), solutions)
+        .map_err(|error| QueryEvaluationError::InternalError(error.to_string()))
+}
+
+/// Computes the [SameAsClasses] from the `owl:sameAs` statements in the default graph of `query`.
+async fn compute_same_as_classes(
+    ctx: &RdfFusionContext,
+    state: SessionState,
+    builder_context: RdfFusionLogicalPlanBuilderContext,
+    query: &Query,
+    optimization_level: OptimizationLevel,
+) -> Result<SameAsClasses, QueryEvaluationError> {
+    let lhs = Variable::new_unchecked("lhs");
+    let rhs = Variable::new_unchecked("rhs");
+    let pattern = GraphPattern::Bgp {
+        patterns: vec![TriplePattern {
+            subject: lhs.clone().into(),
+            predicate: owl::SAME_AS.into_owned().into(),
+            object: rhs.clone().into(),
+        }],
+    };
+    let same_as_query = Query {
+        inner: spargebra::Query::Select {
+            dataset: None,
+            pattern: pattern.clone(),
+            base_iri: None,
+        },
+        dataset: query.dataset.clone(),
+        explain: false,
+        path_repetitions: PathRepetitions::default(),
+    };
+    let cache_key = QueryPlanCacheKey::new(
+        &same_as_query,
+        optimization_level,
+        EntailmentRegime::Simple,
+        false,
+        None,
+        ctx.functions().version(),
+    );
+
+    let (mut solutions, _) = Box::pin(graph_pattern_to_stream(
+        state,
+        builder_context.with_entailment(EntailmentRegime::Simple),
+        ctx.query_plan_cache(),
+        cache_key,
+        &same_as_query,
+        &pattern,
+        &None,
+        false,
+    ))
+    .await?;
+
+    let mut pairs = Vec::new();
+    while let Some(solution) = solutions.next().await {
+        let solution = solution?;
+        if let (Some(Term::NamedNode(lhs)), Some(Term::NamedNode(rhs))) =
+            (solution.get(&lhs), solution.get(&rhs))
+        {
+            pairs.push((lhs.clone(), rhs.clone()));
+        }
+    }
+    Ok(SameAsClasses::from_pairs(pairs))
+}
+
 /// Converts a SPARQL graph pattern to a stream of query solutions.
+#[allow(clippy::too_many_arguments)]
 async fn graph_pattern_to_stream(
     state: SessionState,
     builder_context: RdfFusionLogicalPlanBuilderContext,
+    plan_cache: &QueryPlanCache,
+    cache_key: QueryPlanCacheKey,
     query: &Query,
     pattern: &GraphPattern,
     base_iri: &Option<Iri<String>>,
+    deterministic_order: bool,
 ) -> Result<(QuerySolutionStream, QueryExplanation), QueryEvaluationError> {
     let task = state.task_ctx();
 
-    let (execution_plan, explanation) =
-        create_execution_plan(state, builder_context, &query.dataset, pattern, base_iri)
-            .await?;
+    let (execution_plan, explanation) = create_execution_plan(
+        state,
+        builder_context,
+        plan_cache,
+        cache_key,
+        &query.dataset,
+        pattern,
+        base_iri,
+        deterministic_order,
+    )
+    .await?;
     let variables = create_variables(&execution_plan.schema());
 
     let batch_record_stream = execute_stream(execution_plan, task)?;
@@ -154,23 +323,52 @@ async fn graph_pattern_to_stream(
 
 /// Creates a physical execution plan from a SPARQL graph pattern, doing further processing on the
 /// resulting query plan (e.g., optimization).
+///
+/// The logical plans are taken from `plan_cache` if available. Otherwise, they are computed and,
+/// if reusable, inserted into the cache.
+#[allow(clippy::too_many_arguments)]
 async fn create_execution_plan(
     state: SessionState,
     builder_context: RdfFusionLogicalPlanBuilderContext,
+    plan_cache: &QueryPlanCache,
+    cache_key: QueryPlanCacheKey,
     dataset: &QueryDataset,
     pattern: &GraphPattern,
     base_iri: &Option<Iri<String>>,
+    deterministic_order: bool,
 ) -> Result<(Arc<dyn ExecutionPlan>, QueryExplanation), QueryEvaluationError> {
     let planning_time_start = Instant::now();
-    let logical_plan =
-        GraphPatternRewriter::new(builder_context, dataset.clone(), base_iri.clone())
-            .rewrite(pattern)
-            .map_err(|e| e.context("Cannot rewrite SPARQL query"))?;
-    let optimized_plan = state.optimize(&logical_plan)?;
+    let CachedQueryPlans {
+        initial_logical_plan: logical_plan,
+        optimized_logical_plan: optimized_plan,
+    } = match plan_cache.get(&cache_key) {
+        Some(plans) => plans,
+        None => {
+            let rewriter = GraphPatternRewriter::new(
+                builder_context,
+                dataset.clone(),
+                base_iri.clone(),
+            )
+            .with_deterministic_order(deterministic_order);
+            let logical_plan = rewriter
+                .rewrite(pattern)
+                .map_err(|e| e.context("Cannot rewrite SPARQL query"))?;
+            let optimized_plan = state.optimize(&logical_plan)?;
+            let plans = CachedQueryPlans {
+                initial_logical_plan: logical_plan,
+                optimized_logical_plan: optimized_plan,
+            };
+            if rewriter.is_reusable() {
+                plan_cache.insert(cache_key, plans.clone());
+            }
+            plans
+        }
+    };
     let physical_plan = state
         .query_planner()
         .create_physical_plan(&optimized_plan, &state)
         .await?;
+    let physical_plan = merge_sorted_partitions(physical_plan)?;
     let planning_time = planning_time_start.elapsed();
 
     let explanation = QueryExplanation {
@@ -182,6 +380,36 @@ async fn create_execution_plan(
     Ok((Arc::clone(&physical_plan), explanation))
 }
 
+/// Merges the partitions of `plan` while preserving their order, if the partitions are sorted.
+///
+/// The physical optimizer may repartition the output of a sort (e.g., to parallelize the
+/// projection of the results). Consuming such a plan with [execute_stream] would interleave the
+/// sorted partitions. As the final projection into the plain term encoding does not report an
+/// output ordering, the sorted partitions are also merged below a projection.
+fn merge_sorted_partitions(
+    plan: Arc<dyn ExecutionPlan>,
+) -> DFResult<Arc<dyn ExecutionPlan>> {
+    if plan.output_partitioning().partition_count() <= 1 {
+        return Ok(plan);
+    }
+
+    if let Some(ordering) = plan.output_ordering() {
+        return Ok(Arc::new(SortPreservingMergeExec::new(
+            ordering.clone(),
+            plan,
+        )));
+    }
+
+    if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+        let input = merge_sorted_partitions(Arc::clone(projection.input()))?;
+        if input.output_partitioning().partition_count() <= 1 {
+            return plan.with_new_children(vec![input]);
+        }
+    }
+
+    Ok(plan)
+}
+
 #[allow(clippy::expect_used)]
 fn create_variables(schema: &Schema) -> Arc<[Variable]> {
     schema
@@ -1,5 +1,6 @@
 use datafusion::logical_expr::LogicalPlan;
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::{ExecutionPlan, displayable};
+use std::fmt;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -14,3 +15,30 @@ pub struct QueryExplanation {
     /// A reference to the root node of the plan that was actually executed.
     pub execution_plan: Arc<dyn ExecutionPlan>,
 }
+
+impl QueryExplanation {
+    /// Renders the optimized logical plan as an indented tree.
+    pub fn logical_plan_string(&self) -> String {
+        self.optimized_logical_plan.display_indent().to_string()
+    }
+
+    /// Renders the physical plan as an indented tree.
+    ///
+    /// The scans of the storage layer include their chosen index (e.g., `[GPOS]`) and the
+    /// predicates that have been pushed into the scan (`additional_filters`).
+    pub fn physical_plan_string(&self) -> String {
+        displayable(self.execution_plan.as_ref())
+            .indent(true)
+            .to_string()
+    }
+}
+
+impl fmt::Display for QueryExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Logical Plan:")?;
+        writeln!(f, "{}", self.logical_plan_string())?;
+        writeln!(f)?;
+        writeln!(f, "Physical Plan:")?;
+        write!(f, "{}", self.physical_plan_string())
+    }
+}
@@ -1,25 +1,39 @@
 //! [SPARQL](https://www.w3.org/TR/sparql11-overview/) implementation.
 
 mod algebra;
+mod cancellation;
 pub mod error;
 mod eval;
 mod explanation;
 mod optimizer;
+mod path_repetition;
+mod plan_cache;
 mod rewriting;
+mod service;
+mod update;
 
 pub use crate::sparql::algebra::{Query, QueryDataset, Update};
+pub(crate) use crate::sparql::cancellation::QueryCancellation;
+pub use crate::sparql::cancellation::QueryCancellationToken;
 pub use crate::sparql::explanation::QueryExplanation;
 pub use eval::evaluate_query;
 pub use optimizer::{create_optimizer_rules, create_pyhsical_optimizer_rules};
+pub use plan_cache::{DEFAULT_QUERY_PLAN_CACHE_CAPACITY, QueryPlanCache};
+pub use rdf_fusion_logical::entailment::EntailmentRegime;
+pub use rdf_fusion_logical::paths::{PathDepthExceeded, PathDepthLimit};
 pub use rdf_fusion_model::{Variable, VariableNameParseError};
+pub use service::{HttpServiceResolver, ServiceExec, ServiceResolver};
+pub(crate) use service::{ServicePlanner, ServiceResolverSlot};
 pub use spargebra::SparqlSyntaxError;
+use std::path::PathBuf;
+pub use update::evaluate_update;
 
 /// Defines how many optimizations the query optimizer should apply.
 ///
 /// Currently, the default value is [OptimizationLevel::Full], as we are still searching for a
 /// subset that performs well on many queries. Once this subset has been identified, the default
 /// value will be [OptimizationLevel::Default].
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum OptimizationLevel {
     /// No optimizations, except rewrites that are necessary for a working query.
     None,
@@ -36,15 +50,49 @@ pub enum OptimizationLevel {
 pub struct QueryOptions {
     /// The defined optimization level
     pub optimization_level: OptimizationLevel,
+    /// The entailment regime used for matching basic graph patterns.
+    ///
+    /// Entailment is implemented by rewriting the query. Inferred triples are not materialized.
+    pub entailment: EntailmentRegime,
+    /// Whether the solutions of queries without an explicit `ORDER BY` are sorted into a
+    /// deterministic order.
+    ///
+    /// The order is based on the encoded terms (e.g., object ids) and has no meaning in SPARQL.
+    /// However, it is stable across executions on the same data, which is useful for snapshot
+    /// testing query results. The sort is applied before `LIMIT` and `OFFSET`. Defaults to
+    /// `false`, as sorting the solutions incurs additional costs.
+    pub deterministic_order: bool,
+    /// The maximum number of steps that transitive property paths (`p+` and `p*`) follow from a
+    /// single start node.
+    ///
+    /// Transitive paths over large graphs can be expensive. A limit bounds the cost of such
+    /// queries, either by failing the query or by truncating the paths (see [PathDepthLimit]).
+    /// Defaults to `None`, which follows paths of arbitrary length as required by the
+    /// specification.
+    pub path_depth_limit: Option<PathDepthLimit>,
 }
 
 /// Options for SPARQL update evaluation.
 #[derive(Clone, Default)]
-pub struct UpdateOptions;
+pub struct UpdateOptions {
+    /// The options used for evaluating the `WHERE` clauses of the update.
+    pub query_options: QueryOptions,
+    /// The directories from which `LOAD` may read `file:` IRIs.
+    ///
+    /// `LOAD` reads files from the machine that evaluates the update. Files outside of these
+    /// directories are rejected with [QueryEvaluationError::LoadNotAllowed]. Defaults to an empty
+    /// list, which rejects loading any file.
+    ///
+    /// [QueryEvaluationError::LoadNotAllowed]: error::QueryEvaluationError::LoadNotAllowed
+    pub load_directories: Vec<PathBuf>,
+}
 
 impl From<QueryOptions> for UpdateOptions {
     #[inline]
-    fn from(_query_options: QueryOptions) -> Self {
-        Self {}
+    fn from(query_options: QueryOptions) -> Self {
+        Self {
+            query_options,
+            ..Self::default()
+        }
     }
 }
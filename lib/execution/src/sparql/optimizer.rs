@@ -7,12 +7,18 @@ use datafusion::optimizer::{Optimizer, OptimizerRule};
 use datafusion::physical_optimizer::PhysicalOptimizerRule;
 use datafusion::physical_optimizer::optimizer::PhysicalOptimizer;
 use rdf_fusion_extensions::RdfFusionContextView;
-use rdf_fusion_logical::expr::SimplifySparqlExpressionsRule;
+use rdf_fusion_logical::expr::{
+    OrderFilterPredicatesRule, SimplifySparqlExpressionsRule,
+};
 use rdf_fusion_logical::extend::ExtendLoweringRule;
 use rdf_fusion_logical::join::SparqlJoinLoweringRule;
-use rdf_fusion_logical::minus::MinusLoweringRule;
 use rdf_fusion_logical::paths::PropertyPathLoweringRule;
 use rdf_fusion_logical::patterns::PatternLoweringRule;
+use rdf_fusion_logical::quad_pattern::{
+    AggregateOrderHintRule, JoinOrderHintRule, QuadPatternLimitRule, ValuesInliningRule,
+};
+use rdf_fusion_logical::union::UnionReductionRule;
+use rdf_fusion_physical::join::MergeJoinSelection;
 use std::sync::Arc;
 
 /// Creates a list of optimizer rules based on the given `optimization_level`.
@@ -21,7 +27,6 @@ pub fn create_optimizer_rules(
     optimization_level: OptimizationLevel,
 ) -> Vec<Arc<dyn OptimizerRule + Send + Sync>> {
     let lowering_rules: Vec<Arc<dyn OptimizerRule + Send + Sync>> = vec![
-        Arc::new(MinusLoweringRule::new(context.clone())),
         Arc::new(ExtendLoweringRule::new()),
         Arc::new(PropertyPathLoweringRule::new(context.clone())),
         Arc::new(SparqlJoinLoweringRule::new(context.clone())),
@@ -38,7 +43,12 @@ pub fn create_optimizer_rules(
         OptimizationLevel::Default => {
             let mut rules: Vec<Arc<dyn OptimizerRule + Send + Sync>> = Vec::new();
 
+            // Must run before the joins are lowered.
+            rules.push(Arc::new(ValuesInliningRule::new(
+                context.encodings().clone(),
+            )));
             rules.extend(lowering_rules);
+            rules.push(Arc::new(UnionReductionRule::new()));
             rules.push(Arc::new(SimplifySparqlExpressionsRule::new(
                 context.encodings().clone(),
                 Arc::clone(context.functions()),
@@ -52,12 +62,21 @@ pub fn create_optimizer_rules(
                 context.encodings().clone(),
                 Arc::clone(context.functions()),
             )));
+            rules.push(Arc::new(OrderFilterPredicatesRule::default()));
+            rules.push(Arc::new(AggregateOrderHintRule::new()));
+            rules.push(Arc::new(JoinOrderHintRule::new()));
+            rules.push(Arc::new(QuadPatternLimitRule::new()));
             rules
         }
         OptimizationLevel::Full => {
             let mut rules: Vec<Arc<dyn OptimizerRule + Send + Sync>> = Vec::new();
 
+            // Must run before the joins are lowered.
+            rules.push(Arc::new(ValuesInliningRule::new(
+                context.encodings().clone(),
+            )));
             rules.extend(lowering_rules);
+            rules.push(Arc::new(UnionReductionRule::new()));
             rules.push(Arc::new(SimplifySparqlExpressionsRule::new(
                 context.encodings().clone(),
                 Arc::clone(context.functions()),
@@ -69,6 +88,10 @@ pub fn create_optimizer_rules(
                 context.encodings().clone(),
                 Arc::clone(context.functions()),
             )));
+            rules.push(Arc::new(OrderFilterPredicatesRule::default()));
+            rules.push(Arc::new(AggregateOrderHintRule::new()));
+            rules.push(Arc::new(JoinOrderHintRule::new()));
+            rules.push(Arc::new(QuadPatternLimitRule::new()));
             rules
         }
     }
@@ -88,5 +111,14 @@ pub fn create_pyhsical_optimizer_rules(
     _optimization_level: OptimizationLevel,
 ) -> Vec<Arc<dyn PhysicalOptimizerRule + Send + Sync>> {
     // TODO: build based on optimization level
-    PhysicalOptimizer::default().rules
+    let mut rules = PhysicalOptimizer::default().rules;
+
+    // The merge join must be selected before the distribution and ordering requirements are
+    // enforced.
+    let position = rules
+        .iter()
+        .position(|rule| rule.name() == "join_selection")
+        .map_or(0, |position| position + 1);
+    rules.insert(position, Arc::new(MergeJoinSelection::new()));
+    rules
 }
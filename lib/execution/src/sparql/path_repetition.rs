@@ -0,0 +1,320 @@
+use rdf_fusion_logical::paths::{PathRepetition, PathRepetitions};
+use rdf_fusion_model::NamedNode;
+use std::borrow::Cow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+
+/// The prefix of the IRIs that mark a [PathRepetition].
+const PATH_REPETITION_PREFIX: &str = "urn:rdf-fusion:path-repetition:";
+
+/// Rewrites the bounded repetitions of property paths (e.g., `:p{2,4}`) in `query` to their
+/// encoding in [PathRepetitions], such that the query can be parsed by a SPARQL 1.1 parser.
+///
+/// The markers of the repetitions are derived from a hash of `query`. Hence, they cannot be
+/// written in the query itself and do not change the meaning of IRIs used in the query.
+///
+/// Only repetitions that directly follow an IRI, a prefixed name, `a`, or a parenthesized path
+/// are rewritten. Strings, IRIs, and comments are left untouched. Invalid ranges (e.g., `{4,2}`)
+/// are not rewritten and cause a syntax error in the parser.
+pub(super) fn rewrite_path_repetitions(query: &str) -> (Cow<'_, str>, PathRepetitions) {
+    let occurrences = find_path_repetitions(query.as_bytes());
+    let mut repetitions = PathRepetitions::default();
+    if occurrences.is_empty() {
+        return (Cow::Borrowed(query), repetitions);
+    }
+
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    let query_hash = hasher.finish();
+
+    let mut edits = Vec::new();
+    for (index, occurrence) in occurrences.into_iter().enumerate() {
+        let marker = NamedNode::new_unchecked(format!(
+            "{PATH_REPETITION_PREFIX}{query_hash:016x}:{index}"
+        ));
+        edits.push((occurrence.path_start..occurrence.path_start, "(".to_owned()));
+        edits.push((occurrence.range, format!("|{marker})")));
+        repetitions.insert(marker, occurrence.repetition);
+    }
+    edits.sort_by_key(|(range, _)| range.start);
+
+    let mut result = String::with_capacity(query.len());
+    let mut position = 0;
+    for (range, replacement) in edits {
+        result.push_str(&query[position..range.start]);
+        result.push_str(&replacement);
+        position = range.end;
+    }
+    result.push_str(&query[position..]);
+    (Cow::Owned(result), repetitions)
+}
+
+/// A bounded repetition of a property path within a query string.
+struct PathRepetitionOccurrence {
+    /// The start of the repeated path.
+    path_start: usize,
+    /// The range of the repetition (e.g., `{2,4}`), including the whitespace that separates it
+    /// from the repeated path.
+    range: Range<usize>,
+    /// The parsed repetition.
+    repetition: PathRepetition,
+}
+
+fn find_path_repetitions(query: &[u8]) -> Vec<PathRepetitionOccurrence> {
+    let mut result = Vec::new();
+    let mut open_parentheses = Vec::new();
+    // The path that directly precedes the current position (ignoring whitespace).
+    let mut path: Option<Range<usize>> = None;
+
+    let mut i = 0;
+    while i < query.len() {
+        match query[i] {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                i += 1;
+                continue;
+            }
+            b'#' => {
+                i = query[i..]
+                    .iter()
+                    .position(|c| *c == b'\n')
+                    .map_or(query.len(), |end| i + end);
+                path = None;
+            }
+            b'"' | b'\'' => {
+                i = string_end(query, i);
+                path = None;
+            }
+            b'<' => match iri_end(query, i) {
+                Some(end) => {
+                    path = Some(i..end);
+                    i = end;
+                }
+                None => {
+                    path = None;
+                    i += 1;
+                }
+            },
+            b'(' => {
+                open_parentheses.push(i);
+                path = None;
+                i += 1;
+            }
+            b')' => {
+                path = open_parentheses.pop().map(|start| start..i + 1);
+                i += 1;
+            }
+            b'{' => {
+                let repetition = path.take().and_then(|path| {
+                    let (end, repetition) = parse_repetition(query, i)?;
+                    Some(PathRepetitionOccurrence {
+                        path_start: path.start,
+                        range: path.end..end,
+                        repetition,
+                    })
+                });
+                match repetition {
+                    Some(repetition) => {
+                        i = repetition.range.end;
+                        result.push(repetition);
+                    }
+                    None => i += 1,
+                }
+            }
+            c if is_name_start(c) => {
+                let end = name_end(query, i);
+                let name = &query[i..end];
+                let is_path = name == b"a" || name.contains(&b':');
+                path = is_path.then_some(i..end);
+                i = end;
+            }
+            _ => {
+                path = None;
+                i += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the end of the string literal that starts at `start`.
+fn string_end(query: &[u8], start: usize) -> usize {
+    let quote = query[start];
+    let long_delimiter = [quote; 3];
+    let is_long = query[start..].starts_with(&long_delimiter);
+    let delimiter_len = if is_long { 3 } else { 1 };
+
+    let mut i = start + delimiter_len;
+    while i < query.len() {
+        match query[i] {
+            b'\\' => i += 2,
+            c if c == quote && (!is_long || query[i..].starts_with(&long_delimiter)) => {
+                return i + delimiter_len;
+            }
+            _ => i += 1,
+        }
+    }
+    query.len()
+}
+
+/// Returns the end of the IRI that starts at `start`, or [None] if `<` is an operator.
+fn iri_end(query: &[u8], start: usize) -> Option<usize> {
+    for (i, c) in query.iter().enumerate().skip(start + 1) {
+        match *c {
+            b'>' => return Some(i + 1),
+            b'<' | b'"' | b'{' | b'}' | b'|' | b'^' | b'`' | b'\\' | 0..=b' ' => {
+                return None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_name_start(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b':') || !c.is_ascii()
+}
+
+/// Returns the end of the name (e.g., a prefixed name or keyword) that starts at `start`.
+fn name_end(query: &[u8], start: usize) -> usize {
+    let mut end = query[start..]
+        .iter()
+        .position(|c| !is_name_start(*c) && !matches!(*c, b'-' | b'.' | b'%' | b'\\'))
+        .map_or(query.len(), |end| start + end);
+    // Names cannot end with a dot (e.g., `?s :p :o.`).
+    while end > start + 1 && query[end - 1] == b'.' {
+        end -= 1;
+    }
+    end
+}
+
+/// Parses a repetition (`{n}`, `{n,}`, or `{n,m}`) that starts at `start`.
+fn parse_repetition(query: &[u8], start: usize) -> Option<(usize, PathRepetition)> {
+    let mut i = start + 1;
+    let skip_whitespace = |i: &mut usize| {
+        while query.get(*i).is_some_and(u8::is_ascii_whitespace) {
+            *i += 1;
+        }
+    };
+    let parse_number = |i: &mut usize| {
+        let digits_start = *i;
+        while query.get(*i).is_some_and(u8::is_ascii_digit) {
+            *i += 1;
+        }
+        std::str::from_utf8(&query[digits_start..*i])
+            .ok()?
+            .parse::<u32>()
+            .ok()
+    };
+
+    skip_whitespace(&mut i);
+    let min = parse_number(&mut i)?;
+    skip_whitespace(&mut i);
+    let max = match query.get(i)? {
+        b'}' => Some(min),
+        b',' => {
+            i += 1;
+            skip_whitespace(&mut i);
+            let max_start = i;
+            let max = parse_number(&mut i);
+            // An omitted maximum is unbounded, while an invalid maximum is not rewritten.
+            if max.is_none() && i != max_start {
+                return None;
+            }
+            skip_whitespace(&mut i);
+            if query.get(i) != Some(&b'}') {
+                return None;
+            }
+            max
+        }
+        _ => return None,
+    };
+
+    Some((i + 1, PathRepetition::try_new(min, max)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrite_repetition_of_prefixed_name() {
+        let (query, repetitions) =
+            rewrite_path_repetitions("SELECT * { ?s ex:p{2,4} ?o }");
+        let marker = single_marker(&query);
+        assert_eq!(query, format!("SELECT * {{ ?s (ex:p|{marker}) ?o }}"));
+        assert_repetition(&repetitions, &marker, 2, Some(4));
+    }
+
+    #[test]
+    fn rewrite_repetition_of_iri_and_group() {
+        let (query, repetitions) = rewrite_path_repetitions(
+            "SELECT * { ?s ^<http://e/p> { 3 } / (a|ex:q){1,} ?o }",
+        );
+        let markers = markers(&query);
+        assert_eq!(
+            query,
+            format!(
+                "SELECT * {{ ?s ^(<http://e/p>|{}) / ((a|ex:q)|{}) ?o }}",
+                markers[0], markers[1]
+            )
+        );
+        assert_repetition(&repetitions, &markers[0], 3, Some(3));
+        assert_repetition(&repetitions, &markers[1], 1, None);
+    }
+
+    #[test]
+    fn markers_depend_on_the_query() {
+        let (first, _) = rewrite_path_repetitions("SELECT * { ?s ex:p{2} ?o }");
+        let (second, _) = rewrite_path_repetitions("SELECT * { ?s ex:q{2} ?o }");
+        assert_ne!(single_marker(&first), single_marker(&second));
+    }
+
+    #[test]
+    fn ignore_strings_and_groups() {
+        let query = "SELECT * { ?s ex:p ?o FILTER(REGEX(?o, \"a{2,4}\")) \
+            GRAPH ex:g { ?s ?p ?o } VALUES ?x { 1 } } # ex:p{2}";
+        let (rewritten, repetitions) = rewrite_path_repetitions(query);
+        assert_eq!(rewritten, query);
+        assert!(repetitions.is_empty());
+    }
+
+    #[test]
+    fn ignore_invalid_range() {
+        let query = "SELECT * { ?s ex:p{4,2} ?o }";
+        assert_eq!(rewrite_path_repetitions(query).0, query);
+    }
+
+    fn markers(query: &str) -> Vec<NamedNode> {
+        query
+            .match_indices(&format!("<{PATH_REPETITION_PREFIX}"))
+            .map(|(start, _)| {
+                let end = start + query[start..].find('>').unwrap();
+                NamedNode::new_unchecked(&query[start + 1..end])
+            })
+            .collect()
+    }
+
+    fn single_marker(query: &str) -> NamedNode {
+        let [marker] = markers(query).try_into().unwrap();
+        marker
+    }
+
+    fn assert_repetition(
+        repetitions: &PathRepetitions,
+        marker: &NamedNode,
+        min: u32,
+        max: Option<u32>,
+    ) {
+        let path = rdf_fusion_model::PropertyPathExpression::Alternative(
+            Box::new(rdf_fusion_model::PropertyPathExpression::NamedNode(
+                NamedNode::new_unchecked("http://e/p"),
+            )),
+            Box::new(rdf_fusion_model::PropertyPathExpression::NamedNode(
+                marker.clone(),
+            )),
+        );
+        let (_, repetition) = repetitions.decode(&path).unwrap();
+        assert_eq!(repetition, PathRepetition::try_new(min, max).unwrap());
+    }
+}
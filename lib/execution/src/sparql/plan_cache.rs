@@ -0,0 +1,318 @@
+use crate::sparql::{
+    EntailmentRegime, OptimizationLevel, PathDepthLimit, Query, QueryDataset,
+};
+use datafusion::logical_expr::LogicalPlan;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The default number of query plans held by a [QueryPlanCache].
+pub const DEFAULT_QUERY_PLAN_CACHE_CAPACITY: usize = 128;
+
+/// A bounded cache of the logical plans of SPARQL queries.
+///
+/// The plans are keyed by the normalized query text (i.e., the serialization of the parsed query)
+/// and everything else that affects planning: the query dataset (e.g., whether the default graph
+/// is the union of all graphs), the optimization level, the entailment regime, whether a
+/// deterministic order is requested, the depth limit of property paths, and the version of the
+/// function registry.
+///
+/// Only planning is cached. Volatile functions (e.g., `RAND`) are evaluated anew during each
+/// execution. Queries whose plans depend on the time of planning (e.g., `NOW`) are never cached.
+///
+/// If the cache is full, the least recently used plan is evicted.
+#[derive(Debug)]
+pub struct QueryPlanCache {
+    /// The maximum number of cached plans. A capacity of zero disables the cache.
+    capacity: usize,
+    /// The cached plans.
+    state: Mutex<QueryPlanCacheState>,
+}
+
+/// The mutable state of a [QueryPlanCache].
+#[derive(Debug, Default)]
+struct QueryPlanCacheState {
+    /// The cached plans.
+    entries: HashMap<QueryPlanCacheKey, CacheEntry>,
+    /// A logical clock that is used for tracking the least recently used entry.
+    clock: u64,
+}
+
+/// An entry in the [QueryPlanCache].
+#[derive(Debug)]
+struct CacheEntry {
+    /// The cached plans.
+    plans: CachedQueryPlans,
+    /// The logical time of the last access.
+    last_used: u64,
+}
+
+/// The plans of a single query.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedQueryPlans {
+    /// The logical plan before optimization.
+    pub initial_logical_plan: LogicalPlan,
+    /// The optimized logical plan.
+    pub optimized_logical_plan: LogicalPlan,
+}
+
+/// Identifies a cached plan.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct QueryPlanCacheKey {
+    /// The normalized query text.
+    query: String,
+    /// The dataset of the query.
+    dataset: QueryDataset,
+    /// The optimization level used for planning.
+    optimization_level: OptimizationLevel,
+    /// The entailment regime used for planning.
+    entailment: EntailmentRegime,
+    /// Whether the solutions are sorted into a deterministic order.
+    deterministic_order: bool,
+    /// The depth limit of property paths.
+    path_depth_limit: Option<PathDepthLimit>,
+    /// The version of the function registry.
+    registry_version: u64,
+}
+
+impl QueryPlanCacheKey {
+    /// Creates a new [QueryPlanCacheKey].
+    pub fn new(
+        query: &Query,
+        optimization_level: OptimizationLevel,
+        entailment: EntailmentRegime,
+        deterministic_order: bool,
+        path_depth_limit: Option<PathDepthLimit>,
+        registry_version: u64,
+    ) -> Self {
+        Self {
+            query: query.inner.to_string(),
+            dataset: query.dataset.clone(),
+            optimization_level,
+            entailment,
+            deterministic_order,
+            path_depth_limit,
+            registry_version,
+        }
+    }
+}
+
+impl QueryPlanCache {
+    /// Creates a new [QueryPlanCache] that holds at most `capacity` plans.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(QueryPlanCacheState::default()),
+        }
+    }
+
+    /// Returns the maximum number of cached plans.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached plans.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached plans.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    /// Returns the cached plans for `key`, if available.
+    pub(crate) fn get(&self, key: &QueryPlanCacheKey) -> Option<CachedQueryPlans> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(key)?;
+        entry.last_used = clock;
+        Some(entry.plans.clone())
+    }
+
+    /// Caches `plans` for `key`, evicting the least recently used plan if the cache is full.
+    pub(crate) fn insert(&self, key: QueryPlanCacheKey, plans: CachedQueryPlans) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            let least_recently_used = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                state.entries.remove(&least_recently_used);
+            }
+        }
+
+        state.entries.insert(
+            key,
+            CacheEntry {
+                plans,
+                last_used: clock,
+            },
+        );
+    }
+}
+
+impl Default for QueryPlanCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUERY_PLAN_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::logical_expr::LogicalPlanBuilder;
+
+    #[test]
+    fn test_get_returns_inserted_plan() {
+        let cache = QueryPlanCache::new(2);
+        let key = key("SELECT * WHERE { ?s ?p ?o }");
+
+        cache.insert(key.clone(), plans());
+
+        assert!(cache.get(&key).is_some());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_key_normalizes_whitespace() {
+        assert_eq!(
+            key("SELECT * WHERE { ?s ?p ?o }"),
+            key("SELECT *\nWHERE {\n    ?s   ?p ?o\n}")
+        );
+    }
+
+    #[test]
+    fn test_key_depends_on_registry_version() {
+        let query = Query::parse("SELECT * WHERE { ?s ?p ?o }", None).unwrap();
+
+        assert_ne!(
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::Simple,
+                false,
+                None,
+                0
+            ),
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::Simple,
+                false,
+                None,
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_depends_on_entailment() {
+        let query = Query::parse("SELECT * WHERE { ?s a ?o }", None).unwrap();
+
+        assert_ne!(
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::Simple,
+                false,
+                None,
+                0
+            ),
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::RdfsHierarchy,
+                false,
+                None,
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_depends_on_deterministic_order() {
+        let query = Query::parse("SELECT * WHERE { ?s ?p ?o }", None).unwrap();
+
+        assert_ne!(
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::Simple,
+                false,
+                None,
+                0
+            ),
+            QueryPlanCacheKey::new(
+                &query,
+                OptimizationLevel::Full,
+                EntailmentRegime::Simple,
+                true,
+                None,
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_plan() {
+        let cache = QueryPlanCache::new(2);
+        let first = key("SELECT * WHERE { ?a ?b ?c }");
+        let second = key("SELECT * WHERE { ?d ?e ?f }");
+        let third = key("SELECT * WHERE { ?g ?h ?i }");
+
+        cache.insert(first.clone(), plans());
+        cache.insert(second.clone(), plans());
+        cache.get(&first);
+        cache.insert(third.clone(), plans());
+
+        assert!(cache.get(&first).is_some());
+        assert!(cache.get(&second).is_none());
+        assert!(cache.get(&third).is_some());
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_cache() {
+        let cache = QueryPlanCache::new(0);
+        let key = key("SELECT * WHERE { ?s ?p ?o }");
+
+        cache.insert(key.clone(), plans());
+
+        assert!(cache.get(&key).is_none());
+    }
+
+    fn key(query: &str) -> QueryPlanCacheKey {
+        let query = Query::parse(query, None).unwrap();
+        QueryPlanCacheKey::new(
+            &query,
+            OptimizationLevel::Full,
+            EntailmentRegime::Simple,
+            false,
+            None,
+            0,
+        )
+    }
+
+    fn plans() -> CachedQueryPlans {
+        let plan = LogicalPlanBuilder::empty(false).build().unwrap();
+        CachedQueryPlans {
+            initial_logical_plan: plan.clone(),
+            optimized_logical_plan: plan,
+        }
+    }
+}
@@ -5,8 +5,8 @@ use rdf_fusion_extensions::functions::FunctionName;
 use rdf_fusion_logical::{RdfFusionExprBuilder, RdfFusionExprBuilderContext};
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::Iri;
+use rdf_fusion_model::TermRef;
 use rdf_fusion_model::vocab::xsd;
-use rdf_fusion_model::{DateTime, TermRef};
 use rdf_fusion_model::{Literal, NamedNode};
 use spargebra::algebra::{Expression, Function, GraphPattern};
 
@@ -220,7 +220,10 @@ impl<'rewriter> ExpressionRewriter<'rewriter> {
             Function::Round => self.unary_args(args)?.round(),
             Function::Ceil => self.unary_args(args)?.ceil(),
             Function::Floor => self.unary_args(args)?.floor(),
-            Function::Rand => self.expr_builder_root.rand(),
+            Function::Rand => match self.graph_rewriter.seeded_rand()? {
+                Some(udf) => self.expr_builder_root.try_create_builder(udf.call(vec![])),
+                None => self.expr_builder_root.rand(),
+            },
             // Dates & Durations
             Function::Year => self.unary_args(args)?.year(),
             Function::Month => self.unary_args(args)?.month(),
@@ -230,9 +233,13 @@ impl<'rewriter> ExpressionRewriter<'rewriter> {
             Function::Seconds => self.unary_args(args)?.seconds(),
             Function::Timezone => self.unary_args(args)?.timezone(),
             Function::Tz => self.unary_args(args)?.tz(),
+            Function::Adjust => {
+                let (lhs, rhs) = self.binary_args(args)?;
+                lhs.adjust(rhs)
+            }
             Function::Now => {
                 let literal = Literal::new_typed_literal(
-                    DateTime::now().to_string(),
+                    self.graph_rewriter.now().to_string(),
                     xsd::DATE_TIME,
                 );
                 self.expr_builder_root
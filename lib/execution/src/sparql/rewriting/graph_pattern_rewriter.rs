@@ -4,20 +4,23 @@ use datafusion::arrow::datatypes::DataType;
 use datafusion::common::{Column, DFSchema, not_impl_err, plan_err};
 use datafusion::functions_aggregate::count::{count, count_udaf};
 use datafusion::logical_expr::utils::COUNT_STAR_EXPANSION;
-use datafusion::logical_expr::{Expr, LogicalPlan, SortExpr};
+use datafusion::logical_expr::{Expr, LogicalPlan, ScalarUDF, SortExpr};
 use rdf_fusion_encoding::EncodingName;
+use rdf_fusion_extensions::functions::{BuiltinName, FunctionName};
+use rdf_fusion_functions::registry::restarted_seeded_rand_udf;
+use rdf_fusion_logical::entailment::EntailmentRegime;
 use rdf_fusion_logical::join::SparqlJoinType;
 use rdf_fusion_logical::{
     ActiveGraph, RdfFusionLogicalPlanBuilder, RdfFusionLogicalPlanBuilderContext,
 };
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::Iri;
-use rdf_fusion_model::{GraphName, Variable};
+use rdf_fusion_model::{DateTime, GraphName, Variable};
 use spargebra::algebra::{
     AggregateExpression, AggregateFunction, Expression, GraphPattern, OrderExpression,
 };
 use spargebra::term::NamedNodePattern;
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 use std::sync::Arc;
 
 /// A rewriter that transforms SPARQL graph patterns into a DataFusion logical plan.
@@ -32,6 +35,16 @@ pub struct GraphPatternRewriter {
     base_iri: Option<Iri<String>>,
     /// The current state of the rewriting process.
     state: RefCell<RewritingState>,
+    /// The result of `NOW` for this query. It is computed on the first use such that all
+    /// occurrences of `NOW` in a query evaluate to the same value. If set, the resulting plan
+    /// depends on the time of rewriting.
+    now: OnceCell<DateTime>,
+    /// The UDF of `RAND` for this query if the function is seeded. It is created on the first use
+    /// such that all occurrences of `RAND` in a query continue the same sequence of values. If
+    /// set, the resulting plan holds the state of the random number generator.
+    seeded_rand: OnceCell<Option<Arc<ScalarUDF>>>,
+    /// Whether solutions without an explicit order are sorted into a deterministic order.
+    deterministic_order: bool,
 }
 
 impl GraphPatternRewriter {
@@ -53,18 +66,94 @@ impl GraphPatternRewriter {
             dataset,
             base_iri,
             state: RefCell::new(state),
+            now: OnceCell::new(),
+            seeded_rand: OnceCell::new(),
+            deterministic_order: false,
         }
     }
 
+    /// Returns a new rewriter that sorts the solutions of queries without an explicit `ORDER BY`
+    /// into a deterministic order (see
+    /// [RdfFusionLogicalPlanBuilder::order_by_encoded_terms]).
+    ///
+    /// The sort is applied before a top-level `LIMIT` or `OFFSET` such that the sliced solutions
+    /// are deterministic as well.
+    #[must_use]
+    pub fn with_deterministic_order(mut self, deterministic_order: bool) -> Self {
+        self.deterministic_order = deterministic_order;
+        self
+    }
+
+    /// Returns whether the rewritten plans can be reused for later executions of the same query.
+    ///
+    /// This is not the case if the plan contains values computed during rewriting that must be
+    /// re-computed for each execution (e.g., the result of `NOW` or the state of a seeded `RAND`)
+    /// or if the plan depends on the data (e.g., the `owl:sameAs` classes of
+    /// [EntailmentRegime::OwlSameAs]).
+    pub fn is_reusable(&self) -> bool {
+        self.now.get().is_none()
+            && self.seeded_rand.get().is_none_or(Option::is_none)
+            && self.builder_context.entailment() != EntailmentRegime::OwlSameAs
+    }
+
+    /// Returns the result of `NOW` for the rewritten query and marks the rewritten plan as
+    /// depending on the time of rewriting.
+    ///
+    /// The time is only computed once per rewriter. Hence, all occurrences of `NOW` in a query
+    /// (e.g., in different sub-queries) evaluate to the same value.
+    pub(super) fn now(&self) -> DateTime {
+        *self.now.get_or_init(DateTime::now)
+    }
+
+    /// Returns the UDF of `RAND` for the rewritten query if the registered function is seeded.
+    ///
+    /// A seeded `RAND` advances its random number generator with each evaluated batch. Therefore,
+    /// each rewritten query obtains a new UDF whose sequence starts from the beginning, and the
+    /// rewritten plan is marked as depending on the state of the generator.
+    pub(super) fn seeded_rand(&self) -> DFResult<Option<Arc<ScalarUDF>>> {
+        if let Some(udf) = self.seeded_rand.get() {
+            return Ok(udf.clone());
+        }
+
+        let udf = self
+            .builder_context
+            .registry()
+            .udf(&FunctionName::Builtin(BuiltinName::Rand))?;
+        let udf = restarted_seeded_rand_udf(self.builder_context.encodings(), &udf)
+            .map(Arc::new);
+        Ok(self.seeded_rand.get_or_init(|| udf).clone())
+    }
+
     /// Rewrites a SPARQL graph pattern into a DataFusion logical plan.
     ///
     /// The method ensures that all results are encoded as plain terms and can be displayed to
     /// users.
     pub fn rewrite(&self, pattern: &GraphPattern) -> DFResult<LogicalPlan> {
-        let plan = self.rewrite_graph_pattern(pattern)?;
+        let plan = match pattern {
+            GraphPattern::Slice {
+                inner,
+                start,
+                length,
+            } if self.requires_deterministic_order(inner) => self
+                .rewrite_graph_pattern(inner)?
+                .order_by_encoded_terms()?
+                .slice(*start, *length)?,
+            _ if self.requires_deterministic_order(pattern) => self
+                .rewrite_graph_pattern(pattern)?
+                .order_by_encoded_terms()?,
+            _ => self.rewrite_graph_pattern(pattern)?,
+        };
         plan.with_plain_terms()?.build()
     }
 
+    /// Returns whether the solutions of `pattern` must be sorted into a deterministic order.
+    ///
+    /// This is the case if a deterministic order is requested and the query does not define an
+    /// explicit order.
+    fn requires_deterministic_order(&self, pattern: &GraphPattern) -> bool {
+        self.deterministic_order && !has_explicit_order(pattern)
+    }
+
     /// Similar to [Self::rewrite] but does not transform all columns into the plain term encoding.
     pub fn rewrite_with_existing_encoding(
         &self,
@@ -123,7 +212,7 @@ impl GraphPatternRewriter {
             } => self.builder_context.create_values(variables, bindings),
             GraphPattern::Join { left, right } => {
                 let left = self.rewrite_graph_pattern(left)?;
-                let right = self.rewrite_graph_pattern(right)?;
+                let right = self.rewrite_join_rhs(&left, right)?;
                 left.join(right.build()?, SparqlJoinType::Inner, None)
             }
             GraphPattern::LeftJoin {
@@ -132,7 +221,7 @@ impl GraphPatternRewriter {
                 expression,
             } => {
                 let lhs = self.rewrite_graph_pattern(left)?;
-                let rhs = self.rewrite_graph_pattern(right)?;
+                let rhs = self.rewrite_join_rhs(&lhs, right)?;
 
                 let mut join_schema = lhs.schema().as_ref().clone();
                 join_schema.merge(rhs.schema());
@@ -231,10 +320,49 @@ impl GraphPatternRewriter {
                 let aggregate_result = inner.group(variables, &aggregate_exprs)?;
                 self.ensure_all_columns_are_rdf_terms(aggregate_result)
             }
+            GraphPattern::Service {
+                name,
+                inner,
+                silent,
+            } => match name {
+                NamedNodePattern::NamedNode(name) => self.builder_context.create_service(
+                    name.clone(),
+                    inner.as_ref().clone(),
+                    *silent,
+                    None,
+                ),
+                NamedNodePattern::Variable(_) => {
+                    not_impl_err!("SERVICE with a variable as name is not supported")
+                }
+            },
             _ => not_impl_err!("rewrite_graph_pattern: {:?}", pattern),
         }
     }
 
+    /// Rewrites the right-hand side of a join with `lhs`.
+    ///
+    /// If the right-hand side is a `SERVICE` call, the solutions of `lhs` are passed to the
+    /// service such that it only returns compatible solutions (i.e., a bind join).
+    fn rewrite_join_rhs(
+        &self,
+        lhs: &RdfFusionLogicalPlanBuilder,
+        rhs: &GraphPattern,
+    ) -> DFResult<RdfFusionLogicalPlanBuilder> {
+        match rhs {
+            GraphPattern::Service {
+                name: NamedNodePattern::NamedNode(name),
+                inner,
+                silent,
+            } => self.builder_context.create_service(
+                name.clone(),
+                inner.as_ref().clone(),
+                *silent,
+                Some(lhs.clone()),
+            ),
+            _ => self.rewrite_graph_pattern(rhs),
+        }
+    }
+
     /// Checks whether a potential variable in the GRAPH pattern goes out of scope. This is the case
     /// if it either already is out of scope or if the variable is not projected to the outer
     /// query.
@@ -335,26 +463,20 @@ impl GraphPatternRewriter {
             } => {
                 let expr = expression_rewriter.rewrite(expr)?;
                 let expr = expr_builder.try_create_builder(expr)?;
+                // The built-in aggregates encode their arguments as typed values themselves. DISTINCT
+                // requires the original terms for identifying duplicates.
                 Ok(match name {
-                    AggregateFunction::Avg => {
-                        expr.with_encoding(EncodingName::TypedValue)?.avg(*distinct)
-                    }
+                    AggregateFunction::Avg => expr.avg(*distinct),
                     AggregateFunction::Count => expr.count(*distinct),
-                    AggregateFunction::Max => {
-                        expr.with_encoding(EncodingName::TypedValue)?.max()
-                    }
-                    AggregateFunction::Min => {
-                        expr.with_encoding(EncodingName::TypedValue)?.min()
-                    }
+                    AggregateFunction::Max => expr.max(),
+                    AggregateFunction::Min => expr.min(),
                     AggregateFunction::Sample => expr.sample(),
-                    AggregateFunction::Sum => {
-                        expr.with_encoding(EncodingName::TypedValue)?.sum(*distinct)
+                    AggregateFunction::Sum => expr.sum(*distinct),
+                    AggregateFunction::GroupConcat { separator } => {
+                        expr.group_concat(*distinct, separator.as_deref())
                     }
-                    AggregateFunction::GroupConcat { separator } => expr
-                        .with_encoding(EncodingName::TypedValue)?
-                        .group_concat(*distinct, separator.as_deref()),
                     AggregateFunction::Custom(name) => {
-                        plan_err!("Unsupported custom aggregate function: {name}")
+                        expr.custom_aggregate(name, *distinct)
                     }
                 }?
                 .build_any())
@@ -480,6 +602,18 @@ fn compute_active_graph_for_pattern(
     }
 }
 
+/// Returns whether the solution modifiers of `graph_pattern` contain an `ORDER BY`.
+fn has_explicit_order(graph_pattern: &GraphPattern) -> bool {
+    match graph_pattern {
+        GraphPattern::OrderBy { .. } => true,
+        GraphPattern::Project { inner, .. }
+        | GraphPattern::Distinct { inner }
+        | GraphPattern::Reduced { inner }
+        | GraphPattern::Slice { inner, .. } => has_explicit_order(inner),
+        _ => false,
+    }
+}
+
 /// Extracts sort expressions from possible solution modifiers.
 fn get_sort_expressions(graph_pattern: &GraphPattern) -> Option<&Vec<OrderExpression>> {
     match graph_pattern {
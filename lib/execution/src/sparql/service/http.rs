@@ -0,0 +1,68 @@
+use crate::results::{QueryResults, QueryResultsFormat};
+use crate::sparql::Query;
+use crate::sparql::error::QueryEvaluationError;
+use crate::sparql::service::ServiceResolver;
+use async_trait::async_trait;
+use rdf_fusion_model::NamedNode;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use std::io::Cursor;
+
+/// The media types that are accepted from SPARQL endpoints, in the order of preference.
+const ACCEPTED_MEDIA_TYPES: &str = "application/sparql-results+json, \
+    application/sparql-results+xml;q=0.9, text/tab-separated-values;q=0.8, text/csv;q=0.7";
+
+/// A [ServiceResolver] that sends the queries to the SPARQL endpoint at the name of the service.
+///
+/// The queries are sent as `POST` requests according to the
+/// [SPARQL 1.1 Protocol](https://www.w3.org/TR/sparql11-protocol/). The results can be in any of
+/// the formats supported by [QueryResultsFormat].
+#[derive(Debug, Clone, Default)]
+pub struct HttpServiceResolver {
+    /// The client used for sending the requests.
+    client: reqwest::Client,
+}
+
+impl HttpServiceResolver {
+    /// Creates a new [HttpServiceResolver] that uses `client` for sending the requests.
+    ///
+    /// This allows configuring, for example, timeouts or default headers.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ServiceResolver for HttpServiceResolver {
+    async fn evaluate(
+        &self,
+        name: &NamedNode,
+        query: Query,
+    ) -> Result<QueryResults, QueryEvaluationError> {
+        let response = self
+            .client
+            .post(name.as_str())
+            .header(CONTENT_TYPE, "application/sparql-query")
+            .header(ACCEPT, ACCEPTED_MEDIA_TYPES)
+            .body(query.to_string())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|error| QueryEvaluationError::Service(Box::new(error)))?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let format = QueryResultsFormat::from_media_type(&content_type)
+            .ok_or(QueryEvaluationError::UnsupportedContentType(content_type))?;
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|error| QueryEvaluationError::Service(Box::new(error)))?;
+        QueryResults::read(Cursor::new(body), format)
+            .map_err(|error| QueryEvaluationError::Service(Box::new(error)))
+    }
+}
@@ -0,0 +1,77 @@
+//! Evaluation of the SPARQL [`SERVICE`](https://www.w3.org/TR/sparql11-federated-query/) operator.
+
+mod http;
+mod physical;
+mod planner;
+
+use crate::results::QueryResults;
+use crate::sparql::Query;
+use crate::sparql::error::QueryEvaluationError;
+use async_trait::async_trait;
+pub use http::HttpServiceResolver;
+pub use physical::ServiceExec;
+pub(crate) use planner::ServicePlanner;
+use rdf_fusion_model::NamedNode;
+use std::sync::{Arc, RwLock};
+
+/// Evaluates the graph patterns of `SERVICE` calls.
+///
+/// Whenever a query contains a `SERVICE <name> { ... }` pattern, the engine does not evaluate the
+/// inner pattern itself. Instead, it passes the pattern as a `SELECT *` query to the registered
+/// resolver and joins the returned solutions with the rest of the query. By default, the
+/// [HttpServiceResolver] is used, which sends the query to the SPARQL endpoint at `name`.
+///
+/// Custom resolvers allow routing `SERVICE` calls through custom logic (e.g., authentication,
+/// caching, or mock endpoints in tests). They can be registered with
+/// [RdfFusionContext::register_service_resolver](crate::RdfFusionContext::register_service_resolver).
+///
+/// ```
+/// use async_trait::async_trait;
+/// use rdf_fusion_execution::results::{QueryResults, query_result_for_iterator};
+/// use rdf_fusion_execution::sparql::error::QueryEvaluationError;
+/// use rdf_fusion_execution::sparql::{Query, ServiceResolver};
+/// use rdf_fusion_model::{Literal, NamedNode, Variable};
+/// use std::sync::Arc;
+///
+/// /// Answers every `SERVICE` call with a single solution binding `?x` to `1`.
+/// struct MockResolver;
+///
+/// #[async_trait]
+/// impl ServiceResolver for MockResolver {
+///     async fn evaluate(
+///         &self,
+///         _name: &NamedNode,
+///         _query: Query,
+///     ) -> Result<QueryResults, QueryEvaluationError> {
+///         let variables: Arc<[Variable]> = Arc::new([Variable::new_unchecked("x")]);
+///         let solution = (Arc::clone(&variables), vec![Some(Literal::from(1).into())]);
+///         query_result_for_iterator(variables, [Ok(solution.into())].into_iter())
+///             .map_err(|error| QueryEvaluationError::Service(Box::new(error)))
+///     }
+/// }
+///
+/// # futures::executor::block_on(async {
+/// let name = NamedNode::new("http://example.com/sparql")?;
+/// let query = Query::parse("SELECT ?x WHERE { ?s ?p ?x }", None)?;
+/// let results = MockResolver.evaluate(&name, query).await?;
+/// assert!(matches!(results, QueryResults::Solutions(_)));
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// # }).unwrap();
+/// ```
+#[async_trait]
+pub trait ServiceResolver: Send + Sync {
+    /// Evaluates `query` at the service `name`.
+    ///
+    /// The query is always a `SELECT` query and the result must be
+    /// [QueryResults::Solutions]. Variables that are not part of the query are ignored.
+    async fn evaluate(
+        &self,
+        name: &NamedNode,
+        query: Query,
+    ) -> Result<QueryResults, QueryEvaluationError>;
+}
+
+/// A shared reference to the [ServiceResolver] that is currently registered.
+///
+/// Holding the resolver behind a lock allows replacing it without re-creating the planner.
+pub(crate) type ServiceResolverSlot = Arc<RwLock<Arc<dyn ServiceResolver>>>;
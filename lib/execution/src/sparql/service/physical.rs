@@ -0,0 +1,312 @@
+use crate::results::QueryResults;
+use crate::sparql::Query;
+use crate::sparql::error::QueryEvaluationError;
+use crate::sparql::service::ServiceResolver;
+use datafusion::arrow::array::{RecordBatch, RecordBatchOptions};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::{DataFusionError, internal_err, plan_err};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, PlanProperties,
+};
+use futures::{StreamExt, TryStreamExt};
+use rdf_fusion_encoding::plain_term::decoders::DefaultPlainTermDecoder;
+use rdf_fusion_encoding::plain_term::{
+    PLAIN_TERM_ENCODING, PlainTermArrayElementBuilder,
+};
+use rdf_fusion_encoding::{EncodingArray, TermDecoder, TermEncoding};
+use rdf_fusion_model::{
+    DFResult, GraphPattern, GroundTerm, NamedNode, TermRef, Variable,
+};
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Evaluates the graph pattern of a `SERVICE` call with a [ServiceResolver].
+///
+/// The resolver is called once the operator is executed. The solutions of the service are
+/// returned in a single record batch.
+///
+/// If the operator has an input, its solutions are collected first and sent to the service as a
+/// `VALUES` block that is joined with the graph pattern. If the input has no solutions, the
+/// service is not called at all.
+pub struct ServiceExec {
+    /// The execution properties of this operator.
+    plan_properties: PlanProperties,
+    /// The name of the service.
+    name: NamedNode,
+    /// The graph pattern that is evaluated by the service.
+    pattern: GraphPattern,
+    /// Whether errors of the service are ignored.
+    silent: bool,
+    /// The resolver that evaluates the graph pattern.
+    resolver: Arc<dyn ServiceResolver>,
+    /// Provides the bindings that are passed to the service.
+    bindings: Option<Arc<dyn ExecutionPlan>>,
+}
+
+impl ServiceExec {
+    /// Creates a new [ServiceExec] that produces the solutions in `schema`.
+    pub fn new(
+        schema: SchemaRef,
+        name: NamedNode,
+        pattern: GraphPattern,
+        silent: bool,
+        resolver: Arc<dyn ServiceResolver>,
+    ) -> Self {
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        Self {
+            plan_properties,
+            name,
+            pattern,
+            silent,
+            resolver,
+            bindings: None,
+        }
+    }
+
+    /// Returns a new [ServiceExec] that passes the solutions of `bindings` to the service.
+    ///
+    /// All columns of `bindings` must use the plain term encoding.
+    pub fn with_bindings(self, bindings: Arc<dyn ExecutionPlan>) -> Self {
+        Self {
+            bindings: Some(bindings),
+            ..self
+        }
+    }
+}
+
+impl Debug for ServiceExec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceExec")
+            .field("name", &self.name)
+            .field("pattern", &self.pattern)
+            .field("silent", &self.silent)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExecutionPlan for ServiceExec {
+    fn name(&self) -> &str {
+        "ServiceExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        self.bindings.iter().collect()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != self.bindings.iter().len() {
+            return plan_err!(
+                "ServiceExec expects {} children, got {}.",
+                self.bindings.iter().len(),
+                children.len()
+            );
+        }
+
+        let exec = Self::new(
+            self.schema(),
+            self.name.clone(),
+            self.pattern.clone(),
+            self.silent,
+            Arc::clone(&self.resolver),
+        );
+        match children.into_iter().next() {
+            None => Ok(Arc::new(exec)),
+            Some(bindings) => Ok(Arc::new(exec.with_bindings(bindings))),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!(
+                "ServiceExec does not support partitioning (got partition {partition})"
+            );
+        }
+
+        let schema = self.schema();
+        let resolver = Arc::clone(&self.resolver);
+        let name = self.name.clone();
+        let pattern = self.pattern.clone();
+        let silent = self.silent;
+        let bindings = self
+            .bindings
+            .as_ref()
+            .map(|bindings| bindings.execute(0, context))
+            .transpose()?;
+
+        let batch_schema = Arc::clone(&schema);
+        let stream = futures::stream::once(async move {
+            let pattern = match bindings {
+                None => pattern,
+                Some(bindings) => match bind_pattern(pattern, bindings).await? {
+                    None => return Ok(RecordBatch::new_empty(batch_schema)),
+                    Some(pattern) => pattern,
+                },
+            };
+            let query = Query::from(spargebra::Query::Select {
+                dataset: None,
+                pattern,
+                base_iri: None,
+            });
+
+            match evaluate_service(resolver.as_ref(), &name, query, &batch_schema).await {
+                Ok(batch) => Ok(batch),
+                Err(_) if silent => empty_solution(&batch_schema),
+                Err(error) => Err(DataFusionError::External(Box::new(error))),
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+}
+
+impl DisplayAs for ServiceExec {
+    fn fmt_as(&self, _: DisplayFormatType, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ServiceExec: name={}, silent={}", self.name, self.silent)?;
+        if self.bindings.is_some() {
+            write!(f, ", bindings=true")?;
+        }
+        Ok(())
+    }
+}
+
+/// Restricts `pattern` to the solutions of `bindings` by joining it with a `VALUES` block.
+///
+/// Returns [None] if there are no bindings, as the service cannot contribute any solution in this
+/// case. If a binding contains a blank node, `pattern` is returned unchanged, as blank nodes are
+/// scoped to a single store and cannot be part of a `VALUES` block.
+async fn bind_pattern(
+    pattern: GraphPattern,
+    mut bindings: SendableRecordBatchStream,
+) -> DFResult<Option<GraphPattern>> {
+    let variables = bindings
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| Variable::new_unchecked(field.name()))
+        .collect::<Vec<_>>();
+
+    let mut rows = Vec::new();
+    while let Some(batch) = bindings.try_next().await? {
+        let mut columns = Vec::new();
+        for column in batch.columns() {
+            let terms = PLAIN_TERM_ENCODING.try_new_array(Arc::clone(column))?;
+            let mut values = Vec::new();
+            for term in DefaultPlainTermDecoder::decode_terms(&terms) {
+                values.push(match term {
+                    Ok(TermRef::NamedNode(node)) => {
+                        Some(GroundTerm::NamedNode(node.into_owned()))
+                    }
+                    Ok(TermRef::Literal(literal)) => {
+                        Some(GroundTerm::Literal(literal.into_owned()))
+                    }
+                    Ok(TermRef::BlankNode(_)) => return Ok(Some(pattern)),
+                    Err(_) => None,
+                });
+            }
+            columns.push(values);
+        }
+
+        for row in 0..batch.num_rows() {
+            rows.push(columns.iter().map(|column| column[row].clone()).collect());
+        }
+    }
+
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(GraphPattern::Join {
+        left: Box::new(GraphPattern::Values {
+            variables,
+            bindings: rows,
+        }),
+        right: Box::new(pattern),
+    }))
+}
+
+/// Evaluates `query` at the service `name` and collects the solutions into a record batch with
+/// the given `schema`.
+async fn evaluate_service(
+    resolver: &dyn ServiceResolver,
+    name: &NamedNode,
+    query: Query,
+    schema: &SchemaRef,
+) -> Result<RecordBatch, QueryEvaluationError> {
+    let QueryResults::Solutions(mut solutions) = resolver.evaluate(name, query).await?
+    else {
+        return Err(QueryEvaluationError::ServiceDoesNotReturnSolutions);
+    };
+
+    let mut builders = schema
+        .fields()
+        .iter()
+        .map(|_| PlainTermArrayElementBuilder::default())
+        .collect::<Vec<_>>();
+    let mut count = 0;
+    while let Some(solution) = solutions.next().await {
+        let solution = solution?;
+        for (field, builder) in schema.fields().iter().zip(&mut builders) {
+            match solution.get(field.name().as_str()) {
+                Some(term) => builder.append_term(term.as_ref()),
+                None => builder.append_null(),
+            }
+        }
+        count += 1;
+    }
+
+    let columns = builders
+        .into_iter()
+        .map(|builder| builder.finish().into_array_ref())
+        .collect();
+    let options = RecordBatchOptions::new().with_row_count(Some(count));
+    RecordBatch::try_new_with_options(Arc::clone(schema), columns, &options)
+        .map_err(|error| QueryEvaluationError::Engine(error.into()))
+}
+
+/// Creates a record batch with a single solution that does not bind any variable.
+///
+/// This is the result of a `SERVICE SILENT` call that fails.
+fn empty_solution(schema: &SchemaRef) -> DFResult<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|_| {
+            let mut builder = PlainTermArrayElementBuilder::default();
+            builder.append_null();
+            builder.finish().into_array_ref()
+        })
+        .collect();
+    let options = RecordBatchOptions::new().with_row_count(Some(1));
+    Ok(RecordBatch::try_new_with_options(
+        Arc::clone(schema),
+        columns,
+        &options,
+    )?)
+}
@@ -0,0 +1,56 @@
+use crate::sparql::service::{ServiceExec, ServiceResolverSlot};
+use async_trait::async_trait;
+use datafusion::common::plan_err;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+use rdf_fusion_logical::service::ServiceNode;
+use rdf_fusion_model::DFResult;
+use std::sync::Arc;
+
+/// Planner for [ServiceNode]s.
+///
+/// The created [ServiceExec] uses the resolver that is registered at the time of planning.
+pub(crate) struct ServicePlanner {
+    /// The registered resolver.
+    resolver: ServiceResolverSlot,
+}
+
+impl ServicePlanner {
+    /// Creates a new [ServicePlanner].
+    pub(crate) fn new(resolver: ServiceResolverSlot) -> Self {
+        Self { resolver }
+    }
+}
+
+#[async_trait]
+impl ExtensionPlanner for ServicePlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(node) = node.as_any().downcast_ref::<ServiceNode>() else {
+            return Ok(None);
+        };
+
+        let resolver = Arc::clone(&*self.resolver.read().unwrap());
+        let exec = ServiceExec::new(
+            Arc::clone(UserDefinedLogicalNode::schema(node).inner()),
+            node.name().clone(),
+            node.pattern().clone(),
+            node.silent(),
+            resolver,
+        );
+        let exec = match physical_inputs {
+            [] => exec,
+            [bindings] => exec.with_bindings(Arc::clone(bindings)),
+            _ => return plan_err!("ServiceNode must have at most one input."),
+        };
+        Ok(Some(Arc::new(exec)))
+    }
+}
@@ -0,0 +1,628 @@
+use crate::RdfFusionContext;
+use crate::results::{QuadStream, QueryResults, QuerySolutionStream};
+use crate::sparql::error::QueryEvaluationError;
+use crate::sparql::{Query, QueryDataset, Update, UpdateOptions};
+use futures::TryStreamExt;
+use oxrdfio::{RdfFormat, RdfParseError, RdfParser};
+use rdf_fusion_model::quads::{COL_GRAPH, COL_OBJECT, COL_PREDICATE, COL_SUBJECT};
+use rdf_fusion_model::{
+    BlankNode, GraphName, GraphNameRef, NamedNode, NamedOrBlankNode, Quad, Term, Variable,
+};
+use sparesults::QuerySolution;
+use spargebra::GraphUpdateOperation;
+use spargebra::algebra::{GraphPattern, GraphTarget};
+use spargebra::term::{
+    GraphNamePattern, GroundQuad, GroundQuadPattern, GroundTerm, GroundTermPattern,
+    NamedNodePattern, QuadPattern, TermPattern,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Evaluates a SPARQL [Update] against the storage of `ctx`.
+///
+/// The operations of the update are applied in order and each operation observes the changes of
+/// the previous operations. The update is atomic with respect to failures: if an operation fails,
+/// the changes of all previous operations are undone before the error is returned. However, the
+/// update is not isolated from concurrent writers, which may observe intermediate states.
+///
+/// `LOAD` only supports `file:` IRIs of files within [UpdateOptions::load_directories]. The format
+/// of the file is derived from its extension.
+pub async fn evaluate_update(
+    ctx: &RdfFusionContext,
+    update: &Update,
+    options: &UpdateOptions,
+) -> Result<(), QueryEvaluationError> {
+    let mut evaluator = UpdateEvaluator {
+        ctx,
+        update,
+        options,
+        undo_log: Vec::new(),
+    };
+
+    for (operation, using_dataset) in
+        update.inner.operations.iter().zip(&update.using_datasets)
+    {
+        if let Err(error) = evaluator
+            .evaluate_operation(operation, using_dataset.as_ref())
+            .await
+        {
+            evaluator.rollback().await;
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// A change that must be applied to undo a previously applied change.
+enum UndoOperation {
+    /// Inserts quads that have been removed.
+    Insert(Vec<Quad>),
+    /// Removes quads that have been inserted.
+    Remove(Vec<Quad>),
+    /// Inserts a named graph that has been dropped.
+    InsertNamedGraph(NamedOrBlankNode),
+    /// Drops a named graph that has been created.
+    DropNamedGraph(NamedOrBlankNode),
+}
+
+/// Applies the operations of an update and records how to undo them.
+struct UpdateEvaluator<'a> {
+    ctx: &'a RdfFusionContext,
+    update: &'a Update,
+    options: &'a UpdateOptions,
+    /// The changes that undo the applied operations, in the order of their application.
+    undo_log: Vec<UndoOperation>,
+}
+
+impl UpdateEvaluator<'_> {
+    async fn evaluate_operation(
+        &mut self,
+        operation: &GraphUpdateOperation,
+        using_dataset: Option<&QueryDataset>,
+    ) -> Result<(), QueryEvaluationError> {
+        match operation {
+            GraphUpdateOperation::InsertData { data } => {
+                let mut bnodes = HashMap::new();
+                let quads = data
+                    .iter()
+                    .map(|quad| {
+                        Quad::new(
+                            convert_subject(&quad.subject, &mut bnodes),
+                            quad.predicate.clone(),
+                            convert_term(&quad.object, &mut bnodes),
+                            convert_graph_name(&quad.graph_name),
+                        )
+                    })
+                    .collect();
+                self.apply_changes(Vec::new(), quads).await
+            }
+            GraphUpdateOperation::DeleteData { data } => {
+                let quads = data.iter().map(convert_ground_quad).collect();
+                self.apply_changes(quads, Vec::new()).await
+            }
+            GraphUpdateOperation::DeleteInsert {
+                delete,
+                insert,
+                pattern,
+                ..
+            } => {
+                self.evaluate_delete_insert(delete, insert, pattern, using_dataset)
+                    .await
+            }
+            GraphUpdateOperation::Load {
+                silent,
+                source,
+                destination,
+            } => {
+                let result = self.load(source, convert_graph_name(destination)).await;
+                ignore_if_silent(result, *silent)
+            }
+            GraphUpdateOperation::Clear { silent, graph } => {
+                let result = self.clear(graph).await;
+                ignore_if_silent(result, *silent)
+            }
+            GraphUpdateOperation::Create { silent, graph } => {
+                let result = self.create(graph).await;
+                ignore_if_silent(result, *silent)
+            }
+            GraphUpdateOperation::Drop { silent, graph } => {
+                let result = self.drop(graph).await;
+                ignore_if_silent(result, *silent)
+            }
+        }
+    }
+
+    /// Evaluates `pattern` and applies the instantiated `delete` and `insert` templates.
+    ///
+    /// All solutions are computed before the storage is changed, such that the changes do not
+    /// influence the evaluation of `pattern`.
+    async fn evaluate_delete_insert(
+        &mut self,
+        delete: &[GroundQuadPattern],
+        insert: &[QuadPattern],
+        pattern: &GraphPattern,
+        using_dataset: Option<&QueryDataset>,
+    ) -> Result<(), QueryEvaluationError> {
+        let mut query = Query::from(spargebra::Query::Select {
+            dataset: None,
+            pattern: pattern.clone(),
+            base_iri: self.update.inner.base_iri.clone(),
+        });
+        if let Some(dataset) = using_dataset {
+            query.dataset = dataset.clone();
+        }
+        query.path_repetitions = self.update.path_repetitions.clone();
+
+        let (results, _) = self
+            .ctx
+            .execute_query(&query, self.options.query_options.clone())
+            .await?;
+        let QueryResults::Solutions(solutions) = results else {
+            return QueryEvaluationError::internal(
+                "The pattern of an update did not produce solutions.".to_owned(),
+            );
+        };
+        let solutions = solutions.try_collect::<Vec<_>>().await?;
+
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+        for solution in &solutions {
+            deleted.extend(
+                delete
+                    .iter()
+                    .filter_map(|template| instantiate_ground_quad(template, solution)),
+            );
+            let mut bnodes = HashMap::new();
+            inserted.extend(insert.iter().filter_map(|template| {
+                instantiate_quad(template, solution, &mut bnodes)
+            }));
+        }
+
+        self.apply_changes(deleted, inserted).await
+    }
+
+    /// Loads the document at `source` into `destination`.
+    async fn load(
+        &mut self,
+        source: &NamedNode,
+        destination: GraphName,
+    ) -> Result<(), QueryEvaluationError> {
+        let Some(path) = source.as_str().strip_prefix("file://") else {
+            return Err(QueryEvaluationError::NotImplemented(format!(
+                "LOAD of {source}. Only file: IRIs are supported."
+            )));
+        };
+        let path = self.allowed_load_path(source, Path::new(path))?;
+        let format = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(RdfFormat::from_extension)
+            .ok_or_else(|| {
+                QueryEvaluationError::UnsupportedContentType(source.as_str().to_owned())
+            })?;
+
+        let file = File::open(path).map_err(RdfParseError::from)?;
+        let parser = RdfParser::from_format(format)
+            .with_base_iri(source.as_str())
+            .map_err(|error| QueryEvaluationError::InternalError(error.to_string()))?
+            .without_named_graphs()
+            .with_default_graph(destination)
+            .rename_blank_nodes();
+        let quads = parser
+            .for_reader(BufReader::new(file))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.apply_changes(Vec::new(), quads).await
+    }
+
+    /// Returns the canonical form of `path` if the file is within one of the
+    /// [load directories](UpdateOptions::load_directories).
+    ///
+    /// The path is checked before accessing the file system, such that the errors do not reveal
+    /// whether files outside of the load directories exist.
+    fn allowed_load_path(
+        &self,
+        source: &NamedNode,
+        path: &Path,
+    ) -> Result<PathBuf, QueryEvaluationError> {
+        let not_allowed = || QueryEvaluationError::LoadNotAllowed(source.clone());
+        if path
+            .components()
+            .any(|component| component == Component::ParentDir)
+        {
+            return Err(not_allowed());
+        }
+
+        let directories = self
+            .options
+            .load_directories
+            .iter()
+            .filter_map(|directory| {
+                let canonical = directory.canonicalize().ok()?;
+                Some((directory, canonical))
+            })
+            .collect::<Vec<_>>();
+        if !directories.iter().any(|(directory, canonical)| {
+            path.starts_with(directory) || path.starts_with(canonical)
+        }) {
+            return Err(not_allowed());
+        }
+
+        // Symbolic links may point to files outside of the load directories.
+        let path = path.canonicalize().map_err(RdfParseError::from)?;
+        if !directories
+            .iter()
+            .any(|(_, canonical)| path.starts_with(canonical))
+        {
+            return Err(not_allowed());
+        }
+        Ok(path)
+    }
+
+    /// Removes all quads from the graphs identified by `target`.
+    async fn clear(&mut self, target: &GraphTarget) -> Result<(), QueryEvaluationError> {
+        match target {
+            GraphTarget::NamedNode(graph) => {
+                self.ensure_named_graph_exists(graph).await?;
+                self.clear_graph(GraphNameRef::NamedNode(graph.as_ref()))
+                    .await
+            }
+            GraphTarget::DefaultGraph => {
+                self.clear_graph(GraphNameRef::DefaultGraph).await
+            }
+            GraphTarget::NamedGraphs => {
+                for graph in self.ctx.storage().named_graphs().await? {
+                    self.clear_graph(graph.as_ref().into()).await?;
+                }
+                Ok(())
+            }
+            GraphTarget::AllGraphs => {
+                self.clear_graph(GraphNameRef::DefaultGraph).await?;
+                for graph in self.ctx.storage().named_graphs().await? {
+                    self.clear_graph(graph.as_ref().into()).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Creates the named graph `graph`.
+    async fn create(&mut self, graph: &NamedNode) -> Result<(), QueryEvaluationError> {
+        if !self
+            .ctx
+            .storage()
+            .insert_named_graph(graph.as_ref().into())
+            .await?
+        {
+            return Err(QueryEvaluationError::GraphAlreadyExists(graph.clone()));
+        }
+        self.undo_log
+            .push(UndoOperation::DropNamedGraph(graph.clone().into()));
+        Ok(())
+    }
+
+    /// Removes the graphs identified by `target`. The default graph is cleared instead.
+    async fn drop(&mut self, target: &GraphTarget) -> Result<(), QueryEvaluationError> {
+        match target {
+            GraphTarget::NamedNode(graph) => {
+                self.ensure_named_graph_exists(graph).await?;
+                self.drop_named_graph(graph.clone().into()).await
+            }
+            GraphTarget::DefaultGraph => {
+                self.clear_graph(GraphNameRef::DefaultGraph).await
+            }
+            GraphTarget::NamedGraphs => {
+                for graph in self.ctx.storage().named_graphs().await? {
+                    self.drop_named_graph(graph).await?;
+                }
+                Ok(())
+            }
+            GraphTarget::AllGraphs => {
+                self.clear_graph(GraphNameRef::DefaultGraph).await?;
+                for graph in self.ctx.storage().named_graphs().await? {
+                    self.drop_named_graph(graph).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn ensure_named_graph_exists(
+        &self,
+        graph: &NamedNode,
+    ) -> Result<(), QueryEvaluationError> {
+        if self
+            .ctx
+            .storage()
+            .contains_named_graph(graph.as_ref().into())
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(QueryEvaluationError::GraphDoesNotExist(graph.clone()))
+        }
+    }
+
+    /// Removes `deleted`, then inserts `inserted`, and records the changes to the storage.
+    ///
+    /// The configured [LiteralNormalization](rdf_fusion_encoding::typed_value::LiteralNormalization)
+    /// is applied before changing the storage. Quads that are both deleted and inserted are only
+    /// inserted, as the insertion would restore them anyway. Each list is applied with a single
+    /// storage operation and the undo log records the normalized quads that have been changed.
+    async fn apply_changes(
+        &mut self,
+        deleted: Vec<Quad>,
+        inserted: Vec<Quad>,
+    ) -> Result<(), QueryEvaluationError> {
+        let normalization = self.ctx.literal_normalization();
+        let inserted = inserted
+            .into_iter()
+            .map(|quad| normalization.normalize_quad(quad))
+            .collect::<Vec<_>>();
+        let deleted = {
+            let inserted = inserted.iter().collect::<HashSet<_>>();
+            deleted
+                .into_iter()
+                .map(|quad| normalization.normalize_quad(quad))
+                .filter(|quad| !inserted.contains(quad))
+                .collect::<Vec<_>>()
+        };
+
+        let storage = self.ctx.storage();
+        if !deleted.is_empty() {
+            let removed = storage.remove_quads(deleted).await?;
+            if !removed.is_empty() {
+                self.undo_log.push(UndoOperation::Insert(removed));
+            }
+        }
+
+        if !inserted.is_empty() {
+            let graphs = inserted
+                .iter()
+                .filter(|quad| !quad.graph_name.is_default_graph())
+                .map(|quad| graph_name_to_named_graph(&quad.graph_name))
+                .collect::<HashSet<_>>();
+            for graph in graphs {
+                if !storage.contains_named_graph(graph.as_ref()).await? {
+                    self.undo_log.push(UndoOperation::DropNamedGraph(graph));
+                }
+            }
+
+            let inserted = storage.insert_quads(inserted).await?;
+            if !inserted.is_empty() {
+                self.undo_log.push(UndoOperation::Remove(inserted));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all quads from `graph_name` and records the removed quads.
+    async fn clear_graph(
+        &mut self,
+        graph_name: GraphNameRef<'_>,
+    ) -> Result<(), QueryEvaluationError> {
+        let quads = self.quads_in_graph(graph_name).await?;
+        self.ctx.storage().clear_graph(graph_name).await?;
+        if !quads.is_empty() {
+            self.undo_log.push(UndoOperation::Insert(quads));
+        }
+        Ok(())
+    }
+
+    /// Drops the named graph `graph` and records the graph and its quads.
+    async fn drop_named_graph(
+        &mut self,
+        graph: NamedOrBlankNode,
+    ) -> Result<(), QueryEvaluationError> {
+        let quads = self.quads_in_graph(graph.as_ref().into()).await?;
+        if self.ctx.storage().drop_named_graph(graph.as_ref()).await? {
+            self.undo_log.push(UndoOperation::InsertNamedGraph(graph));
+        }
+        if !quads.is_empty() {
+            self.undo_log.push(UndoOperation::Insert(quads));
+        }
+        Ok(())
+    }
+
+    async fn quads_in_graph(
+        &self,
+        graph_name: GraphNameRef<'_>,
+    ) -> Result<Vec<Quad>, QueryEvaluationError> {
+        let variables: Arc<[Variable]> = Arc::new([
+            Variable::new_unchecked(COL_GRAPH),
+            Variable::new_unchecked(COL_SUBJECT),
+            Variable::new_unchecked(COL_PREDICATE),
+            Variable::new_unchecked(COL_OBJECT),
+        ]);
+        let stream = self
+            .ctx
+            .quads_for_pattern(Some(graph_name), None, None, None)
+            .await?;
+        let solutions = QuerySolutionStream::try_new(variables, stream)?;
+        QuadStream::try_new(solutions)
+            .map_err(QueryEvaluationError::InternalError)?
+            .try_collect_to_vec()
+            .await
+    }
+
+    /// Undoes all recorded changes in reverse order.
+    ///
+    /// Errors during the rollback are ignored, as the error that caused the rollback is reported.
+    async fn rollback(&mut self) {
+        let storage = self.ctx.storage();
+        while let Some(operation) = self.undo_log.pop() {
+            let _ = match operation {
+                UndoOperation::Insert(quads) => storage.extend(quads).await.map(|_| ()),
+                UndoOperation::Remove(quads) => {
+                    storage.remove_quads(quads).await.map(|_| ())
+                }
+                UndoOperation::InsertNamedGraph(graph) => {
+                    storage.insert_named_graph(graph.as_ref()).await.map(|_| ())
+                }
+                UndoOperation::DropNamedGraph(graph) => {
+                    storage.drop_named_graph(graph.as_ref()).await.map(|_| ())
+                }
+            };
+        }
+    }
+}
+
+fn ignore_if_silent(
+    result: Result<(), QueryEvaluationError>,
+    silent: bool,
+) -> Result<(), QueryEvaluationError> {
+    if silent { Ok(()) } else { result }
+}
+
+fn graph_name_to_named_graph(graph_name: &GraphName) -> NamedOrBlankNode {
+    match graph_name {
+        GraphName::NamedNode(nn) => nn.clone().into(),
+        GraphName::BlankNode(bnode) => bnode.clone().into(),
+        GraphName::DefaultGraph => unreachable!("Only called for named graphs"),
+    }
+}
+
+fn convert_graph_name(graph_name: &spargebra::term::GraphName) -> GraphName {
+    match graph_name {
+        spargebra::term::GraphName::NamedNode(nn) => nn.clone().into(),
+        spargebra::term::GraphName::DefaultGraph => GraphName::DefaultGraph,
+    }
+}
+
+/// Replaces the blank nodes of a template with fresh blank nodes. Within `bnodes`, the same blank
+/// node of the template is always mapped to the same fresh blank node.
+fn convert_blank_node(
+    bnode: &BlankNode,
+    bnodes: &mut HashMap<BlankNode, BlankNode>,
+) -> BlankNode {
+    bnodes.entry(bnode.clone()).or_default().clone()
+}
+
+fn convert_subject(
+    subject: &NamedOrBlankNode,
+    bnodes: &mut HashMap<BlankNode, BlankNode>,
+) -> NamedOrBlankNode {
+    match subject {
+        NamedOrBlankNode::NamedNode(nn) => nn.clone().into(),
+        NamedOrBlankNode::BlankNode(bnode) => convert_blank_node(bnode, bnodes).into(),
+    }
+}
+
+fn convert_term(term: &Term, bnodes: &mut HashMap<BlankNode, BlankNode>) -> Term {
+    match term {
+        Term::BlankNode(bnode) => convert_blank_node(bnode, bnodes).into(),
+        _ => term.clone(),
+    }
+}
+
+fn convert_ground_term(term: &GroundTerm) -> Term {
+    match term {
+        GroundTerm::NamedNode(nn) => nn.clone().into(),
+        GroundTerm::Literal(literal) => literal.clone().into(),
+    }
+}
+
+fn convert_ground_quad(quad: &GroundQuad) -> Quad {
+    Quad::new(
+        quad.subject.clone(),
+        quad.predicate.clone(),
+        convert_ground_term(&quad.object),
+        convert_graph_name(&quad.graph_name),
+    )
+}
+
+/// Instantiates the `template` with the bindings of `solution`.
+///
+/// Returns [None] if a variable is unbound or the instantiated quad is not valid RDF (e.g., a
+/// literal in the subject position). In this case, the template is skipped for this solution.
+fn instantiate_ground_quad(
+    template: &GroundQuadPattern,
+    solution: &QuerySolution,
+) -> Option<Quad> {
+    Some(Quad::new(
+        term_to_subject(instantiate_ground_term(&template.subject, solution)?)?,
+        instantiate_named_node(&template.predicate, solution)?,
+        instantiate_ground_term(&template.object, solution)?,
+        instantiate_graph_name(&template.graph_name, solution)?,
+    ))
+}
+
+/// Instantiates the `template` with the bindings of `solution`. Blank nodes of the template are
+/// replaced by fresh blank nodes.
+///
+/// See [instantiate_ground_quad] for when [None] is returned.
+fn instantiate_quad(
+    template: &QuadPattern,
+    solution: &QuerySolution,
+    bnodes: &mut HashMap<BlankNode, BlankNode>,
+) -> Option<Quad> {
+    Some(Quad::new(
+        term_to_subject(instantiate_term(&template.subject, solution, bnodes)?)?,
+        instantiate_named_node(&template.predicate, solution)?,
+        instantiate_term(&template.object, solution, bnodes)?,
+        instantiate_graph_name(&template.graph_name, solution)?,
+    ))
+}
+
+fn instantiate_term(
+    pattern: &TermPattern,
+    solution: &QuerySolution,
+    bnodes: &mut HashMap<BlankNode, BlankNode>,
+) -> Option<Term> {
+    match pattern {
+        TermPattern::NamedNode(nn) => Some(nn.clone().into()),
+        TermPattern::BlankNode(bnode) => Some(convert_blank_node(bnode, bnodes).into()),
+        TermPattern::Literal(literal) => Some(literal.clone().into()),
+        TermPattern::Variable(variable) => solution.get(variable).cloned(),
+    }
+}
+
+fn instantiate_ground_term(
+    pattern: &GroundTermPattern,
+    solution: &QuerySolution,
+) -> Option<Term> {
+    match pattern {
+        GroundTermPattern::NamedNode(nn) => Some(nn.clone().into()),
+        GroundTermPattern::Literal(literal) => Some(literal.clone().into()),
+        GroundTermPattern::Variable(variable) => solution.get(variable).cloned(),
+    }
+}
+
+fn instantiate_named_node(
+    pattern: &NamedNodePattern,
+    solution: &QuerySolution,
+) -> Option<NamedNode> {
+    match pattern {
+        NamedNodePattern::NamedNode(nn) => Some(nn.clone()),
+        NamedNodePattern::Variable(variable) => match solution.get(variable)? {
+            Term::NamedNode(nn) => Some(nn.clone()),
+            _ => None,
+        },
+    }
+}
+
+fn instantiate_graph_name(
+    pattern: &GraphNamePattern,
+    solution: &QuerySolution,
+) -> Option<GraphName> {
+    match pattern {
+        GraphNamePattern::NamedNode(nn) => Some(nn.clone().into()),
+        GraphNamePattern::DefaultGraph => Some(GraphName::DefaultGraph),
+        GraphNamePattern::Variable(variable) => match solution.get(variable)? {
+            Term::NamedNode(nn) => Some(nn.clone().into()),
+            Term::BlankNode(bnode) => Some(bnode.clone().into()),
+            Term::Literal(_) => None,
+        },
+    }
+}
+
+fn term_to_subject(term: Term) -> Option<NamedOrBlankNode> {
+    match term {
+        Term::NamedNode(nn) => Some(nn.into()),
+        Term::BlankNode(bnode) => Some(bnode.into()),
+        Term::Literal(_) => None,
+    }
+}
@@ -36,6 +36,7 @@ pub enum BuiltinName {
     Seconds,
     Timezone,
     Tz,
+    Adjust,
     Uuid,
     StrUuid,
     Md5,
@@ -132,6 +133,7 @@ impl fmt::Display for BuiltinName {
             Self::Seconds => "SECONDS",
             Self::Timezone => "TIMEZONE",
             Self::Tz => "TZ",
+            Self::Adjust => "ADJUST",
             Self::Uuid => "UUID",
             Self::StrUuid => "STRUUID",
             Self::Md5 => "MD5",
@@ -225,6 +227,7 @@ impl TryFrom<&str> for BuiltinName {
             "SECONDS" => Self::Seconds,
             "TIMEZONE" => Self::Timezone,
             "TZ" => Self::Tz,
+            "ADJUST" => Self::Adjust,
             "UUID" => Self::Uuid,
             "STRUUID" => Self::StrUuid,
             "MD5" => Self::Md5,
@@ -272,7 +275,7 @@ impl TryFrom<&str> for BuiltinName {
             "GROUP_CONCAT" => Self::GroupConcat,
             "ENC_SORT" => Self::WithSortableEncoding,
             "ENC_TV" => Self::WithTypedValueEncoding,
-            "ENV_PT" => Self::WithPlainTermEncoding,
+            "ENC_PT" => Self::WithPlainTermEncoding,
             _ => return Err(NotABuiltInError {}),
         })
     }
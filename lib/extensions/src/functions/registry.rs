@@ -37,4 +37,13 @@ pub trait RdfFusionFunctionRegistry: Debug + Send + Sync {
 
     /// Register a [AggregateUDF].
     fn register_udaf(&self, udaf: AggregateUDF);
+
+    /// Returns a number that changes whenever the set of registered functions changes.
+    ///
+    /// This is used for invalidating cached query plans that may refer to outdated functions.
+    /// The default implementation returns a constant, which is only correct for registries that
+    /// do not support registering functions after creation.
+    fn version(&self) -> u64 {
+        0
+    }
 }
@@ -1,12 +1,17 @@
 use crate::RdfFusionContextView;
 use async_trait::async_trait;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::execution::SendableRecordBatchStream;
 use datafusion::physical_planner::ExtensionPlanner;
 use rdf_fusion_encoding::QuadStorageEncoding;
-use rdf_fusion_encoding::object_id::ObjectIdMapping;
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdMapping};
+use rdf_fusion_encoding::plain_term::PlainTermQuadBatch;
 use rdf_fusion_model::StorageError;
 use rdf_fusion_model::{
-    GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef,
+    DFResult, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef,
 };
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 #[async_trait]
@@ -34,9 +39,71 @@ pub trait QuadStorage: Send + Sync {
     /// Loads the given quads into the storage.
     async fn extend(&self, quads: Vec<Quad>) -> Result<usize, StorageError>;
 
+    /// Loads the quads in the given `batch` into the storage. Returns the number of inserted
+    /// quads.
+    ///
+    /// The batch must have the quad schema of [QuadStorageEncoding::PlainTerm]. See
+    /// [PlainTermQuadBatch] for details.
+    ///
+    /// The default implementation converts the batch into [Quad]s and calls [Self::extend].
+    /// Storage layers should override this method if they can avoid this conversion.
+    async fn extend_from_batch(&self, batch: RecordBatch) -> Result<usize, StorageError> {
+        let batch = PlainTermQuadBatch::try_new(&batch)?;
+        let quads = batch
+            .quads()
+            .map(|quad| quad.map(QuadRef::into_owned))
+            .collect::<DFResult<Vec<_>>>()?;
+        self.extend(quads).await
+    }
+
+    /// Loads the given quads into the storage, using up to `parallelism` threads for encoding
+    /// them. Returns the number of inserted quads.
+    ///
+    /// This method is used for bulk loading large amounts of quads. The default implementation
+    /// ignores `parallelism` and calls [Self::extend]. Storage layers whose encoding (e.g., the
+    /// interning of object ids) supports concurrent access should override this method.
+    async fn extend_parallel(
+        &self,
+        quads: Vec<Quad>,
+        _parallelism: NonZeroUsize,
+    ) -> Result<usize, StorageError> {
+        self.extend(quads).await
+    }
+
     /// Removes the given quad from the storage.
     async fn remove(&self, quad: QuadRef<'_>) -> Result<bool, StorageError>;
 
+    /// Inserts the given quads into the storage. Returns the quads that were not contained in the
+    /// storage before, each only once.
+    ///
+    /// In contrast to [Self::extend], this allows callers to undo the insertion. The default
+    /// implementation calls [Self::extend] for each quad. Storage layers should override this
+    /// method if they can determine the inserted quads in a single operation.
+    async fn insert_quads(&self, quads: Vec<Quad>) -> Result<Vec<Quad>, StorageError> {
+        let mut inserted = Vec::new();
+        for quad in quads {
+            if self.extend(vec![quad.clone()]).await? > 0 {
+                inserted.push(quad);
+            }
+        }
+        Ok(inserted)
+    }
+
+    /// Removes the given quads from the storage. Returns the quads that were contained in the
+    /// storage, each only once.
+    ///
+    /// The default implementation calls [Self::remove] for each quad. Storage layers should
+    /// override this method if they can remove multiple quads in a single operation.
+    async fn remove_quads(&self, quads: Vec<Quad>) -> Result<Vec<Quad>, StorageError> {
+        let mut removed = Vec::new();
+        for quad in quads {
+            if self.remove(quad.as_ref()).await? {
+                removed.push(quad);
+            }
+        }
+        Ok(removed)
+    }
+
     /// Creates an empty named graph in the storage.
     async fn insert_named_graph<'a>(
         &self,
@@ -70,9 +137,153 @@ pub trait QuadStorage: Send + Sync {
     /// Returns the number of quads in the storage.
     async fn len(&self) -> Result<usize, StorageError>;
 
-    /// Optimizes the storage (e.g., building indices).
-    async fn optimize(&self) -> Result<(), StorageError>;
+    /// Returns the [QuadStorageLookups] of the storage, or [None] if the storage cannot answer
+    /// them directly. In the latter case, callers fall back to evaluating the lookups with
+    /// queries.
+    fn lookups(&self) -> Option<&dyn QuadStorageLookups> {
+        None
+    }
+
+    /// Returns a read-only view on the current state of the storage.
+    ///
+    /// All queries evaluated against the returned storage observe the same state, regardless of
+    /// concurrent modifications of this storage. Writing to the snapshot returns an error. The
+    /// snapshot is released once the last reference to it is dropped.
+    ///
+    /// Storage layers may retain resources (e.g., old versions or locks) for as long as a snapshot
+    /// is alive. Implementations should document these implications. The default implementation
+    /// returns [None], indicating that snapshots are not supported.
+    async fn snapshot(&self) -> Result<Option<Arc<dyn QuadStorage>>, StorageError> {
+        Ok(None)
+    }
+
+    /// Optimizes the storage (e.g., building indices or compacting existing ones).
+    ///
+    /// Storage layers must not block concurrent readers for the entire duration of the
+    /// optimization.
+    async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError>;
 
     /// Validates invariants in the store
     async fn validate(&self) -> Result<(), StorageError>;
+
+    /// Returns diagnostic information on the internal structure of the storage (e.g., its
+    /// indexes).
+    ///
+    /// The information is intended for operators that tune the storage and is not stable across
+    /// versions. The default implementation returns [None], indicating that the storage does not
+    /// provide such information.
+    async fn storage_info(&self) -> Result<Option<StorageInfo>, StorageError> {
+        Ok(None)
+    }
+}
+
+/// Lookups that a [QuadStorage] answers directly from its indexes instead of planning a query.
+///
+/// Storage layers expose these lookups via [QuadStorage::lookups]. Unbound components of a
+/// pattern match any term, and if `graph_name` is [None], quads in all graphs are considered.
+/// The order of the results is unspecified unless stated otherwise. Each lookup must be computed
+/// on a single snapshot of the storage.
+#[async_trait]
+pub trait QuadStorageLookups: Send + Sync {
+    /// Returns the number of quads that match the given pattern.
+    async fn count_matching(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<usize, StorageError>;
+
+    /// Returns the distinct predicates of the quads with the given `subject`.
+    async fn predicates_for(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: NamedOrBlankNodeRef<'_>,
+    ) -> Result<Vec<NamedNode>, StorageError>;
+
+    /// Returns the distinct objects of the quads with the given `predicate`.
+    async fn distinct_objects_of(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        predicate: NamedNodeRef<'_>,
+    ) -> Result<Vec<Term>, StorageError>;
+
+    /// Returns the `k` terms that are referenced by the most quads in the given `position`,
+    /// together with the number of these quads. The terms are ordered by descending frequency.
+    /// The default graph is not reported for [QuadPosition::GraphName].
+    async fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> Result<Vec<(Term, usize)>, StorageError>;
+
+    /// Returns the quads that match the given pattern of object ids (see [ObjectIdMapping]).
+    /// Object ids that are unknown to the storage do not match any quad.
+    async fn quads_for_object_id_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+    ) -> Result<Vec<Quad>, StorageError>;
+
+    /// Returns a stream of the quads that match the given pattern. The batches of the stream
+    /// have the quad schema of [QuadStorageEncoding::PlainTerm].
+    async fn quads_for_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<SendableRecordBatchStream, StorageError>;
+}
+
+/// Identifies a position of a quad (e.g., for [QuadStorageLookups::top_terms]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QuadPosition {
+    /// The graph name
+    GraphName,
+    /// The subject
+    Subject,
+    /// The predicate
+    Predicate,
+    /// The object
+    Object,
+}
+
+/// Summarizes the effects of [QuadStorage::optimize].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StorageOptimizationReport {
+    /// The approximate number of bytes that have been reclaimed by the optimization.
+    pub reclaimed_bytes: usize,
+}
+
+/// Diagnostic information on the internal structure of a [QuadStorage]. See
+/// [QuadStorage::storage_info].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageInfo {
+    /// The number of quads in the storage.
+    pub quad_count: usize,
+    /// Statistics of the indexes that are maintained by the storage.
+    pub indexes: Vec<IndexInfo>,
+}
+
+impl StorageInfo {
+    /// Returns the approximate number of bytes used by all indexes.
+    pub fn memory_size(&self) -> usize {
+        self.indexes.iter().map(|index| index.memory_size).sum()
+    }
+}
+
+/// Statistics of a single index of a [QuadStorage].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    /// The name of the index, usually the order of its components (e.g., `GSPO`).
+    pub name: String,
+    /// The predicate if the index only holds the quads with this predicate.
+    pub predicate: Option<NamedNode>,
+    /// The number of quads in the index.
+    pub quad_count: usize,
+    /// The approximate number of bytes used by the index.
+    pub memory_size: usize,
 }
@@ -0,0 +1,384 @@
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, ListArray, new_empty_array};
+use datafusion::arrow::buffer::OffsetBuffer;
+use datafusion::arrow::compute::concat;
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion::common::exec_err;
+use datafusion::logical_expr::function::{
+    AccumulatorArgs, AggregateFunctionSimplification, StateFieldsArgs,
+};
+use datafusion::logical_expr::utils::format_state_name;
+use datafusion::logical_expr::{AggregateUDF, AggregateUDFImpl, Expr, Signature};
+use datafusion::scalar::ScalarValue;
+use datafusion::{error::Result, physical_plan::Accumulator};
+use std::any::Any;
+use std::collections::HashSet;
+use std::slice;
+use std::sync::Arc;
+
+/// Wraps the aggregate function `udaf` such that it only aggregates distinct values.
+///
+/// Duplicates are removed per group before any value reaches the accumulator of `udaf`. The
+/// wrapped aggregate takes the arguments of `udaf`, followed by the aggregated term in the plain
+/// term encoding. Two values are considered duplicates if they are the same RDF term. For example,
+/// `"01"^^xsd:integer` and `"1"^^xsd:integer` are distinct, even though they have the same typed
+/// value.
+///
+/// Relevant Resources:
+/// - [SPARQL 1.1 - Aggregate Algebra](https://www.w3.org/TR/sparql11-query/#aggregateAlgebra)
+pub fn distinct_aggregate(udaf: Arc<AggregateUDF>) -> AggregateUDF {
+    AggregateUDF::new_from_impl(DistinctAggregate::new(udaf))
+}
+
+/// An aggregate function that removes duplicates before passing values to the inner aggregate.
+///
+/// The inner aggregate must take exactly one argument that is aggregated. Additional (literal)
+/// arguments must be removed by the simplification of the inner aggregate (e.g., the separator of
+/// `GROUP_CONCAT`). The last argument of this aggregate is the key that is used for identifying
+/// duplicates and is never passed to the inner aggregate.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct DistinctAggregate {
+    /// The aggregate that receives the distinct values.
+    inner: Arc<AggregateUDF>,
+    /// The signature of the inner aggregate, extended by the key argument.
+    signature: Signature,
+}
+
+impl DistinctAggregate {
+    /// Creates a new [DistinctAggregate] for the given `inner` aggregate.
+    pub fn new(inner: Arc<AggregateUDF>) -> Self {
+        let signature = Signature::variadic_any(inner.signature().volatility);
+        Self { inner, signature }
+    }
+}
+
+impl AggregateUDFImpl for DistinctAggregate {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let Some((_, inner_arg_types)) = arg_types.split_last() else {
+            return exec_err!("DISTINCT aggregate is missing its key argument.");
+        };
+        self.inner.return_type(inner_arg_types)
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let ([value, _], [value_field, key_field]) =
+            (acc_args.exprs, acc_args.expr_fields)
+        else {
+            return exec_err!(
+                "DISTINCT is only supported for aggregates with a single argument."
+            );
+        };
+        let data_type = value.data_type(acc_args.schema)?;
+        let key_data_type = key_field.data_type().clone();
+        let inner = self.inner.accumulator(AccumulatorArgs {
+            exprs: slice::from_ref(value),
+            expr_fields: slice::from_ref(value_field),
+            ..acc_args
+        })?;
+        Ok(Box::new(DistinctAccumulator::new(
+            inner,
+            data_type,
+            key_data_type,
+        )))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        let [input_field, key_field] = args.input_fields else {
+            return exec_err!(
+                "DISTINCT is only supported for aggregates with a single argument."
+            );
+        };
+        Ok(vec![
+            list_state_field(args.name, "distinct_values", input_field.data_type()),
+            list_state_field(args.name, "distinct_keys", key_field.data_type()),
+        ])
+    }
+
+    fn simplify(&self) -> Option<AggregateFunctionSimplification> {
+        let simplify = self.inner.simplify()?;
+        Some(Box::new(move |mut function, info| {
+            // The key is not an argument of the inner aggregate.
+            let Some(key) = function.params.args.pop() else {
+                return exec_err!("DISTINCT aggregate is missing its key argument.");
+            };
+            match simplify(function, info)? {
+                Expr::AggregateFunction(mut function) => {
+                    function.func =
+                        Arc::new(distinct_aggregate(Arc::clone(&function.func)));
+                    function.params.args.push(key);
+                    Ok(Expr::AggregateFunction(function))
+                }
+                _ => exec_err!("Simplified DISTINCT aggregate is no aggregate."),
+            }
+        }))
+    }
+}
+
+/// Creates the field of a state that holds a list of values with the given `data_type`.
+fn list_state_field(name: &str, state_name: &str, data_type: &DataType) -> FieldRef {
+    let item_field = Field::new_list_field(data_type.clone(), true);
+    Arc::new(Field::new(
+        format_state_name(name, state_name),
+        DataType::List(Arc::new(item_field)),
+        true,
+    ))
+}
+
+/// Collects the distinct values of a group and passes them to the `inner` accumulator once the
+/// aggregate is evaluated.
+#[derive(Debug)]
+struct DistinctAccumulator {
+    /// The accumulator that receives the distinct values.
+    inner: Box<dyn Accumulator>,
+    /// The data type of the aggregated values.
+    data_type: DataType,
+    /// The data type of the keys that identify duplicates.
+    key_data_type: DataType,
+    /// The keys that have been seen so far.
+    seen: HashSet<ScalarValue>,
+    /// Single-element slices of the distinct values, in the order they have been seen.
+    values: Vec<ArrayRef>,
+    /// Single-element slices of the keys of `values`.
+    keys: Vec<ArrayRef>,
+    /// The number of values in `values` that have already been passed to `inner`.
+    passed_to_inner: usize,
+}
+
+impl DistinctAccumulator {
+    /// Creates a new [DistinctAccumulator].
+    fn new(
+        inner: Box<dyn Accumulator>,
+        data_type: DataType,
+        key_data_type: DataType,
+    ) -> Self {
+        Self {
+            inner,
+            data_type,
+            key_data_type,
+            seen: HashSet::new(),
+            values: Vec::new(),
+            keys: Vec::new(),
+            passed_to_inner: 0,
+        }
+    }
+
+    /// Adds all values in `values` whose key in `keys` has not been seen yet.
+    fn insert_arrays(&mut self, values: &ArrayRef, keys: &ArrayRef) -> Result<()> {
+        for i in 0..values.len() {
+            let key = ScalarValue::try_from_array(keys, i)?;
+            if self.seen.insert(key) {
+                self.values.push(values.slice(i, 1));
+                self.keys.push(keys.slice(i, 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Concatenates the single-element `arrays` into a single array of `data_type`.
+    fn concat_arrays(arrays: &[ArrayRef], data_type: &DataType) -> Result<ArrayRef> {
+        if arrays.is_empty() {
+            return Ok(new_empty_array(data_type));
+        }
+
+        let arrays = arrays.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        Ok(concat(&arrays)?)
+    }
+}
+
+impl Accumulator for DistinctAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let [values, keys] = values else {
+            return exec_err!("DISTINCT aggregate expects a value and a key.");
+        };
+        self.insert_arrays(values, keys)
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let pending =
+            Self::concat_arrays(&self.values[self.passed_to_inner..], &self.data_type)?;
+        self.passed_to_inner = self.values.len();
+        if !pending.is_empty() {
+            self.inner.update_batch(&[pending])?;
+        }
+        self.inner.evaluate()
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + self.inner.size()
+            + self.seen.iter().map(ScalarValue::size).sum::<usize>()
+            + self
+                .values
+                .iter()
+                .chain(&self.keys)
+                .map(|array| array.get_array_memory_size())
+                .sum::<usize>()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        let values = Self::concat_arrays(&self.values, &self.data_type)?;
+        let keys = Self::concat_arrays(&self.keys, &self.key_data_type)?;
+        Ok(vec![
+            single_list_scalar(values, &self.data_type)?,
+            single_list_scalar(keys, &self.key_data_type)?,
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let [values, keys] = states else {
+            return exec_err!("Missing state of DISTINCT aggregate.");
+        };
+        for (values, keys) in values
+            .as_list::<i32>()
+            .iter()
+            .zip(keys.as_list::<i32>().iter())
+        {
+            let (Some(values), Some(keys)) = (values, keys) else {
+                continue;
+            };
+            self.insert_arrays(&values, &keys)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps `values` into a list scalar with a single element.
+fn single_list_scalar(values: ArrayRef, data_type: &DataType) -> Result<ScalarValue> {
+    let item_field = Field::new_list_field(data_type.clone(), true);
+    let list = ListArray::try_new(
+        Arc::new(item_field),
+        OffsetBuffer::from_lengths([values.len()]),
+        values,
+        None,
+    )?;
+    Ok(ScalarValue::List(Arc::new(list)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{Int64Array, StringArray};
+
+    #[test]
+    fn test_distinct_accumulator_removes_duplicates() {
+        let mut accumulator = distinct_count_accumulator();
+
+        update(&mut accumulator, &[1, 2, 2]);
+        update(&mut accumulator, &[1, 3]);
+
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::UInt64(Some(3))
+        );
+    }
+
+    #[test]
+    fn test_distinct_accumulator_merges_states() {
+        let mut first = distinct_count_accumulator();
+        let mut second = distinct_count_accumulator();
+        update(&mut first, &[1, 2]);
+        update(&mut second, &[2, 3]);
+
+        let state = second
+            .state()
+            .unwrap()
+            .into_iter()
+            .map(|value| value.to_array().unwrap())
+            .collect::<Vec<_>>();
+        first.merge_batch(&state).unwrap();
+
+        assert_eq!(first.evaluate().unwrap(), ScalarValue::UInt64(Some(3)));
+    }
+
+    #[test]
+    fn test_distinct_accumulator_evaluate_is_idempotent() {
+        let mut accumulator = distinct_count_accumulator();
+        update(&mut accumulator, &[1, 1]);
+
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::UInt64(Some(1))
+        );
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::UInt64(Some(1))
+        );
+    }
+
+    #[test]
+    fn test_distinct_accumulator_removes_duplicates_by_key() {
+        let mut accumulator = DistinctAccumulator::new(
+            Box::new(RowCount::default()),
+            DataType::Int64,
+            DataType::Utf8,
+        );
+
+        // Equal values with different keys (e.g., "01" and "1" as xsd:integer) are distinct.
+        let keys = Arc::new(StringArray::from(vec!["01", "1", "1"])) as ArrayRef;
+        accumulator
+            .update_batch(&[int64s(&[1, 1, 1]), keys])
+            .unwrap();
+
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::UInt64(Some(2))
+        );
+    }
+
+    fn distinct_count_accumulator() -> DistinctAccumulator {
+        DistinctAccumulator::new(
+            Box::new(RowCount::default()),
+            DataType::Int64,
+            DataType::Int64,
+        )
+    }
+
+    /// Passes `values` to the `accumulator`, using the values themselves as keys.
+    fn update(accumulator: &mut DistinctAccumulator, values: &[i64]) {
+        accumulator
+            .update_batch(&[int64s(values), int64s(values)])
+            .unwrap();
+    }
+
+    fn int64s(values: &[i64]) -> ArrayRef {
+        Arc::new(Int64Array::from(values.to_vec()))
+    }
+
+    /// Counts all rows that reach the accumulator, including duplicates.
+    #[derive(Debug, Default)]
+    struct RowCount(u64);
+
+    impl Accumulator for RowCount {
+        fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+            self.0 += values[0].len() as u64;
+            Ok(())
+        }
+
+        fn evaluate(&mut self) -> Result<ScalarValue> {
+            Ok(ScalarValue::UInt64(Some(self.0)))
+        }
+
+        fn size(&self) -> usize {
+            size_of_val(self)
+        }
+
+        fn state(&mut self) -> Result<Vec<ScalarValue>> {
+            Ok(vec![ScalarValue::UInt64(Some(self.0))])
+        }
+
+        fn merge_batch(&mut self, _states: &[ArrayRef]) -> Result<()> {
+            unreachable!("Not used in tests")
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use datafusion::arrow::array::{ArrayRef, AsArray};
-use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef};
 use datafusion::common::plan_err;
 use datafusion::logical_expr::expr::AggregateFunction;
 use datafusion::logical_expr::function::{
-    AccumulatorArgs, AggregateFunctionSimplification,
+    AccumulatorArgs, AggregateFunctionSimplification, StateFieldsArgs,
 };
+use datafusion::logical_expr::utils::format_state_name;
 use datafusion::logical_expr::{
     AggregateUDF, AggregateUDFImpl, Expr, Signature, Volatility,
 };
@@ -26,6 +27,13 @@ pub fn group_concat_typed_value(encoding: TypedValueEncodingRef) -> AggregateUDF
     AggregateUDF::new_from_impl(SparqlGroupConcat::new(encoding))
 }
 
+/// Creates a `GROUP_CONCAT` aggregate that sorts the lexical values before concatenating them.
+///
+/// See [SparqlGroupConcat::new_sorted] for details.
+pub fn group_concat_sorted_typed_value(encoding: TypedValueEncodingRef) -> AggregateUDF {
+    AggregateUDF::new_from_impl(SparqlGroupConcat::new_sorted(encoding))
+}
+
 /// Concatenates the strings in a set with a given separator.
 ///
 /// Relevant Resources:
@@ -35,11 +43,30 @@ pub struct SparqlGroupConcat {
     encoding: TypedValueEncodingRef,
     name: String,
     signature: Signature,
+    /// Whether the lexical values are sorted before concatenating them.
+    sorted: bool,
 }
 
 impl SparqlGroupConcat {
     /// Creates a new [SparqlGroupConcat] aggregate UDF.
+    ///
+    /// The order of the concatenated values is implementation-defined, as allowed by the SPARQL
+    /// specification. It may change between evaluations (e.g., if the aggregation is
+    /// parallelized).
     pub fn new(encoding: TypedValueEncodingRef) -> Self {
+        Self::new_with_sorting(encoding, false)
+    }
+
+    /// Creates a new [SparqlGroupConcat] aggregate UDF that sorts the lexical values
+    /// (lexicographically by their code points) before concatenating them.
+    ///
+    /// The result is deterministic, regardless of how the aggregation is parallelized. This comes
+    /// at the cost of buffering all values of a group until the aggregate is evaluated.
+    pub fn new_sorted(encoding: TypedValueEncodingRef) -> Self {
+        Self::new_with_sorting(encoding, true)
+    }
+
+    fn new_with_sorting(encoding: TypedValueEncodingRef, sorted: bool) -> Self {
         let name = BuiltinName::GroupConcat.to_string();
         let signature =
             Signature::uniform(2, vec![encoding.data_type().clone()], Volatility::Stable);
@@ -47,6 +74,7 @@ impl SparqlGroupConcat {
             encoding,
             name,
             signature,
+            sorted,
         }
     }
 }
@@ -74,6 +102,7 @@ impl AggregateUDFImpl for SparqlGroupConcat {
 
     fn simplify(&self) -> Option<AggregateFunctionSimplification> {
         let encoding = Arc::clone(&self.encoding);
+        let sorted = self.sorted;
         Some(Box::new(move |function, _info| {
             debug_assert!(
                 function.params.args.len() == 2,
@@ -100,6 +129,7 @@ impl AggregateUDFImpl for SparqlGroupConcat {
                 AggregateUDF::new_from_impl(SparqlGroupConcatWithSeparator::new(
                     Arc::clone(&encoding),
                     separator,
+                    sorted,
                 ))
                 .into(),
                 vec![function.params.args[0].clone()],
@@ -118,11 +148,12 @@ struct SparqlGroupConcatWithSeparator {
     name: String,
     signature: Signature,
     separator: String,
+    sorted: bool,
 }
 
 impl SparqlGroupConcatWithSeparator {
     /// Creates a new [SparqlGroupConcatWithSeparator] aggregate UDF.
-    pub fn new(encoding: TypedValueEncodingRef, separator: String) -> Self {
+    pub fn new(encoding: TypedValueEncodingRef, separator: String, sorted: bool) -> Self {
         let name = BuiltinName::GroupConcat.to_string();
         let signature =
             Signature::exact(vec![encoding.data_type().clone()], Volatility::Stable);
@@ -131,6 +162,7 @@ impl SparqlGroupConcatWithSeparator {
             name,
             signature,
             separator,
+            sorted,
         }
     }
 }
@@ -156,31 +188,92 @@ impl AggregateUDFImpl for SparqlGroupConcatWithSeparator {
         Ok(Box::new(SparqlGroupConcatAccumulator::new(
             Arc::clone(&self.encoding),
             self.separator.clone(),
+            self.sorted,
         )))
     }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        // If the values are sorted, the unsorted values are collected in a list.
+        let value_type = if self.sorted {
+            DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+        } else {
+            DataType::Utf8
+        };
+        Ok(vec![
+            Arc::new(Field::new(
+                format_state_name(args.name, "error"),
+                DataType::Boolean,
+                true,
+            )),
+            Arc::new(Field::new(
+                format_state_name(args.name, "value"),
+                value_type,
+                true,
+            )),
+            Arc::new(Field::new(
+                format_state_name(args.name, "language_error"),
+                DataType::Boolean,
+                true,
+            )),
+            Arc::new(Field::new(
+                format_state_name(args.name, "language"),
+                DataType::Utf8,
+                true,
+            )),
+        ])
+    }
 }
 
 #[derive(Debug)]
 struct SparqlGroupConcatAccumulator {
     encoding: TypedValueEncodingRef,
     separator: String,
+    sorted: bool,
     error: bool,
+    /// The concatenated values. Only used if the values are not sorted.
     value: Option<String>,
+    /// The values in the order they have been seen. Only used if the values are sorted.
+    values: Vec<String>,
     language_error: bool,
     language: Option<String>,
 }
 
 impl SparqlGroupConcatAccumulator {
-    pub fn new(encoding: TypedValueEncodingRef, separator: String) -> Self {
+    pub fn new(encoding: TypedValueEncodingRef, separator: String, sorted: bool) -> Self {
         SparqlGroupConcatAccumulator {
             encoding,
             separator,
+            sorted,
             error: false,
             value: None,
+            values: Vec::new(),
             language_error: false,
             language: None,
         }
     }
+
+    /// Adds a single lexical value.
+    fn push_value(&mut self, value: &str) {
+        if self.sorted {
+            self.values.push(value.to_owned());
+            return;
+        }
+
+        match &mut self.value {
+            None => self.value = Some(value.to_owned()),
+            Some(existing) => {
+                existing.push_str(&self.separator);
+                existing.push_str(value);
+            }
+        }
+    }
+
+    /// Discards all values after an error occurred.
+    fn set_error(&mut self) {
+        self.error = true;
+        self.value = None;
+        self.values.clear();
+    }
 }
 
 impl Accumulator for SparqlGroupConcatAccumulator {
@@ -189,17 +282,10 @@ impl Accumulator for SparqlGroupConcatAccumulator {
             return Ok(());
         }
 
-        let mut value_exists = self.value.is_some();
-        let mut value = self.value.take().unwrap_or_default();
-
         let arr = self.encoding.try_new_array(Arc::clone(&values[0]))?;
         for string in StringLiteralRefTermValueDecoder::decode_terms(&arr) {
             if let Ok(string) = string {
-                if value_exists {
-                    value += self.separator.as_str();
-                }
-                value += string.0;
-                value_exists = true;
+                self.push_value(string.0);
                 if let Some(lang) = &self.language {
                     if Some(lang.as_str()) != string.1 {
                         self.language_error = true;
@@ -209,13 +295,11 @@ impl Accumulator for SparqlGroupConcatAccumulator {
                     self.language = string.1.map(ToOwned::to_owned);
                 }
             } else {
-                self.error = true;
-                self.value = None;
+                self.set_error();
                 return Ok(());
             }
         }
 
-        self.value = Some(value);
         Ok(())
     }
 
@@ -226,7 +310,14 @@ impl Accumulator for SparqlGroupConcatAccumulator {
                 .map(rdf_fusion_encoding::EncodingScalar::into_scalar_value);
         }
 
-        let value = self.value.as_deref().unwrap_or("");
+        let sorted_value;
+        let value = if self.sorted {
+            self.values.sort_unstable();
+            sorted_value = self.values.join(&self.separator);
+            sorted_value.as_str()
+        } else {
+            self.value.as_deref().unwrap_or("")
+        };
         let literal = StringLiteralRef(value, self.language.as_deref());
         StringLiteralRefTermValueEncoder::new(Arc::clone(&self.encoding))
             .encode_term(Ok(literal))
@@ -235,12 +326,24 @@ impl Accumulator for SparqlGroupConcatAccumulator {
 
     fn size(&self) -> usize {
         size_of_val(self)
+            + self.value.as_ref().map_or(0, String::capacity)
+            + self.values.iter().map(String::capacity).sum::<usize>()
     }
 
     fn state(&mut self) -> DFResult<Vec<ScalarValue>> {
+        let value = if self.sorted {
+            let values = self
+                .values
+                .iter()
+                .map(|value| ScalarValue::Utf8(Some(value.clone())))
+                .collect::<Vec<_>>();
+            ScalarValue::List(ScalarValue::new_list_nullable(&values, &DataType::Utf8))
+        } else {
+            ScalarValue::Utf8(self.value.clone())
+        };
         Ok(vec![
             ScalarValue::Boolean(Some(self.error)),
-            ScalarValue::Utf8(self.value.clone()),
+            value,
             ScalarValue::Boolean(Some(self.language_error)),
             ScalarValue::Utf8(self.language.clone()),
         ])
@@ -250,18 +353,22 @@ impl Accumulator for SparqlGroupConcatAccumulator {
     fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
         let error = states[0].as_boolean().iter().any(|e| e == Some(true));
         if error {
-            self.error = true;
-            self.value = None;
+            self.set_error();
             self.language = None;
             return Ok(());
         }
 
-        let old_values = states[1].as_string::<i32>();
-        for old_value in old_values.iter().flatten() {
-            self.value = match self.value.take() {
-                None => Some(old_value.to_owned()),
-                Some(value) => Some(value + self.separator.as_str() + old_value),
-            };
+        if self.sorted {
+            for old_values in states[1].as_list::<i32>().iter().flatten() {
+                for old_value in old_values.as_string::<i32>().iter().flatten() {
+                    self.push_value(old_value);
+                }
+            }
+        } else {
+            let old_values = states[1].as_string::<i32>();
+            for old_value in old_values.iter().flatten() {
+                self.push_value(old_value);
+            }
         }
 
         let existing_language_error =
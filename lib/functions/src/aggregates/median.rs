@@ -0,0 +1,184 @@
+use datafusion::arrow::array::{ArrayRef, AsArray, ListArray};
+use datafusion::arrow::buffer::OffsetBuffer;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::exec_datafusion_err;
+use datafusion::logical_expr::{AggregateUDF, Volatility, create_udaf};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use rdf_fusion_encoding::typed_value::TypedValueEncodingRef;
+use rdf_fusion_encoding::typed_value::decoders::NumericTermValueDecoder;
+use rdf_fusion_encoding::typed_value::encoders::NumericTypedValueEncoder;
+use rdf_fusion_encoding::{
+    EncodingArray, EncodingScalar, TermDecoder, TermEncoder, TermEncoding,
+};
+use rdf_fusion_extensions::functions::FunctionName;
+use rdf_fusion_model::{DFResult, NamedNode};
+use rdf_fusion_model::{Decimal, Numeric, NumericPair, ThinError, ThinResult};
+use std::cmp::Ordering;
+use std::ops::Div;
+use std::sync::Arc;
+
+/// Creates a `MEDIAN` aggregate function that is identified by the IRI `name`.
+///
+/// `MEDIAN` is not part of SPARQL 1.1. Hence, the function is not registered by default and must
+/// be registered in the function registry of the engine under an IRI of the user's choosing.
+/// Queries must be parsed with the IRI as a custom aggregate function (see
+/// `Query::parse_with_custom_aggregates`).
+///
+/// The median of an even number of values is the average of the two middle values. Numeric type
+/// promotion follows the rules of `AVG`. Non-numeric values and errors are ignored, like in `SUM`.
+/// If a group does not contain any numeric value, the result is unbound.
+pub fn median_typed_value(
+    name: NamedNode,
+    encoding: TypedValueEncodingRef,
+) -> AggregateUDF {
+    let data_type = encoding.data_type().clone();
+    let state_type =
+        DataType::List(Arc::new(Field::new_list_field(data_type.clone(), true)));
+    create_udaf(
+        FunctionName::Custom(name).to_string().as_str(),
+        vec![data_type.clone()],
+        Arc::new(data_type),
+        Volatility::Immutable,
+        Arc::new(move |_| Ok(Box::new(SparqlMedian::new(Arc::clone(&encoding))))),
+        Arc::new(vec![state_type]),
+    )
+}
+
+#[derive(Debug)]
+struct SparqlMedian {
+    encoding: TypedValueEncodingRef,
+    values: Vec<Numeric>,
+}
+
+impl SparqlMedian {
+    pub fn new(encoding: TypedValueEncodingRef) -> Self {
+        SparqlMedian {
+            encoding,
+            values: Vec::new(),
+        }
+    }
+
+    fn insert_array(&mut self, array: &ArrayRef) -> DFResult<()> {
+        let arr = self.encoding.try_new_array(Arc::clone(array))?;
+        self.values
+            .extend(NumericTermValueDecoder::decode_terms(&arr).flatten());
+        Ok(())
+    }
+}
+
+impl Accumulator for SparqlMedian {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        let Some(array) = values.first() else {
+            return Ok(());
+        };
+        self.insert_array(array)
+    }
+
+    fn evaluate(&mut self) -> DFResult<ScalarValue> {
+        NumericTypedValueEncoder::new(Arc::clone(&self.encoding))
+            .encode_term(median(&mut self.values))
+            .map(EncodingScalar::into_scalar_value)
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.values.capacity() * size_of::<Numeric>()
+    }
+
+    fn state(&mut self) -> DFResult<Vec<ScalarValue>> {
+        let values = NumericTypedValueEncoder::new(Arc::clone(&self.encoding))
+            .encode_terms(self.values.iter().copied().map(Ok))?
+            .into_array_ref();
+        let item_field = Field::new_list_field(self.encoding.data_type().clone(), true);
+        let list = ListArray::try_new(
+            Arc::new(item_field),
+            OffsetBuffer::from_lengths([values.len()]),
+            values,
+            None,
+        )?;
+        Ok(vec![ScalarValue::List(Arc::new(list))])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        let state = states
+            .first()
+            .ok_or_else(|| exec_datafusion_err!("Missing state of MEDIAN aggregate."))?;
+        for values in state.as_list::<i32>().iter().flatten() {
+            self.insert_array(&values)?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the median of `values`. The slice is sorted in the process.
+fn median(values: &mut [Numeric]) -> ThinResult<Numeric> {
+    if values.is_empty() {
+        return ThinError::expected();
+    }
+
+    values.sort_unstable_by(|lhs, rhs| lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        return Ok(values[middle]);
+    }
+
+    let sum = match NumericPair::with_casts_from(values[middle - 1], values[middle]) {
+        NumericPair::Int(lhs, rhs) => lhs.checked_add(rhs).map(Numeric::Int),
+        NumericPair::Integer(lhs, rhs) => lhs.checked_add(rhs).map(Numeric::Integer),
+        NumericPair::Float(lhs, rhs) => Ok(Numeric::Float(lhs + rhs)),
+        NumericPair::Double(lhs, rhs) => Ok(Numeric::Double(lhs + rhs)),
+        NumericPair::Decimal(lhs, rhs) => lhs.checked_add(rhs).map(Numeric::Decimal),
+    }?;
+
+    // Like in AVG, dividing by an xsd:decimal promotes integers to xsd:decimal.
+    match NumericPair::with_casts_from(sum, Numeric::Decimal(Decimal::from(2))) {
+        NumericPair::Int(_, _) | NumericPair::Integer(_, _) => {
+            unreachable!("Divisor is a decimal")
+        }
+        NumericPair::Float(lhs, rhs) => Ok(Numeric::Float(lhs.div(rhs))),
+        NumericPair::Double(lhs, rhs) => Ok(Numeric::Double(lhs.div(rhs))),
+        NumericPair::Decimal(lhs, rhs) => lhs.checked_div(rhs).map(Numeric::Decimal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::{Double, Integer};
+
+    #[test]
+    fn test_median_odd_count() {
+        let mut values = integers(&[5, 1, 3]);
+        assert_eq!(median(&mut values), Ok(Numeric::Integer(Integer::from(3))));
+    }
+
+    #[test]
+    fn test_median_even_count_averages_middle_values() {
+        let mut values = integers(&[4, 1, 3, 2]);
+        assert_eq!(
+            median(&mut values),
+            Ok(Numeric::Decimal(Decimal::new(25, 1).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_median_promotes_to_double() {
+        let mut values = vec![
+            Numeric::Integer(Integer::from(1)),
+            Numeric::Double(Double::from(2.0)),
+        ];
+        assert_eq!(median(&mut values), Ok(Numeric::Double(Double::from(1.5))));
+    }
+
+    #[test]
+    fn test_median_of_empty_group_is_error() {
+        assert!(median(&mut []).is_err());
+    }
+
+    fn integers(values: &[i64]) -> Vec<Numeric> {
+        values
+            .iter()
+            .map(|value| Numeric::Integer(Integer::from(*value)))
+            .collect()
+    }
+}
@@ -1,11 +1,17 @@
 mod avg;
+mod distinct;
 mod group_concat;
 mod max;
+mod median;
 mod min;
 mod sum;
+mod variance;
 
 pub use avg::*;
+pub use distinct::*;
 pub use group_concat::*;
 pub use max::*;
+pub use median::*;
 pub use min::*;
 pub use sum::*;
+pub use variance::*;
@@ -0,0 +1,282 @@
+use datafusion::arrow::array::{ArrayRef, AsArray};
+use datafusion::arrow::datatypes::{DataType, Float64Type, UInt64Type};
+use datafusion::common::exec_datafusion_err;
+use datafusion::logical_expr::{AggregateUDF, Volatility, create_udaf};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use rdf_fusion_encoding::typed_value::TypedValueEncodingRef;
+use rdf_fusion_encoding::typed_value::decoders::NumericTermValueDecoder;
+use rdf_fusion_encoding::typed_value::encoders::DoubleTermValueEncoder;
+use rdf_fusion_encoding::{EncodingScalar, TermDecoder, TermEncoder, TermEncoding};
+use rdf_fusion_extensions::functions::FunctionName;
+use rdf_fusion_model::{DFResult, Double, NamedNode, ThinError};
+use std::sync::Arc;
+
+/// The statistic that is computed by an aggregate created with [variance_typed_value].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarianceStatistic {
+    /// The variance of the values, treating them as the entire population.
+    PopulationVariance,
+    /// The variance of the values, treating them as a sample of the population.
+    SampleVariance,
+    /// The standard deviation of the values, treating them as the entire population.
+    PopulationStandardDeviation,
+    /// The standard deviation of the values, treating them as a sample of the population.
+    SampleStandardDeviation,
+}
+
+impl VarianceStatistic {
+    /// Returns whether the statistic treats the values as a sample of the population.
+    fn is_sample(self) -> bool {
+        matches!(
+            self,
+            VarianceStatistic::SampleVariance
+                | VarianceStatistic::SampleStandardDeviation
+        )
+    }
+
+    /// Returns whether the statistic is a standard deviation.
+    fn is_standard_deviation(self) -> bool {
+        matches!(
+            self,
+            VarianceStatistic::PopulationStandardDeviation
+                | VarianceStatistic::SampleStandardDeviation
+        )
+    }
+}
+
+/// Creates an aggregate function that computes the given variance `statistic` and is identified
+/// by the IRI `name`.
+///
+/// Like `MEDIAN` (see [median_typed_value](super::median_typed_value)), these aggregates are not
+/// part of SPARQL 1.1 and must be registered in the function registry of the engine under an IRI
+/// of the user's choosing.
+///
+/// All numeric values are promoted to `xsd:double` and the result is an `xsd:double`.
+/// Non-numeric values and errors are ignored, like in `SUM`. If a group does not contain any
+/// numeric value, the result is unbound. The sample statistics are unbound for groups with a
+/// single numeric value, while the population statistics are `0`.
+///
+/// The accumulators use Welford's algorithm and can be merged. Hence, the aggregation can be
+/// parallelized.
+pub fn variance_typed_value(
+    name: NamedNode,
+    statistic: VarianceStatistic,
+    encoding: TypedValueEncodingRef,
+) -> AggregateUDF {
+    let data_type = encoding.data_type().clone();
+    create_udaf(
+        FunctionName::Custom(name).to_string().as_str(),
+        vec![data_type.clone()],
+        Arc::new(data_type),
+        Volatility::Immutable,
+        Arc::new(move |_| {
+            Ok(Box::new(SparqlVariance::new(
+                Arc::clone(&encoding),
+                statistic,
+            )))
+        }),
+        Arc::new(vec![DataType::UInt64, DataType::Float64, DataType::Float64]),
+    )
+}
+
+#[derive(Debug)]
+struct SparqlVariance {
+    encoding: TypedValueEncodingRef,
+    statistic: VarianceStatistic,
+    moments: Moments,
+}
+
+impl SparqlVariance {
+    pub fn new(encoding: TypedValueEncodingRef, statistic: VarianceStatistic) -> Self {
+        SparqlVariance {
+            encoding,
+            statistic,
+            moments: Moments::default(),
+        }
+    }
+}
+
+impl Accumulator for SparqlVariance {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        let Some(array) = values.first() else {
+            return Ok(());
+        };
+
+        let arr = self.encoding.try_new_array(Arc::clone(array))?;
+        for value in NumericTermValueDecoder::decode_terms(&arr).flatten() {
+            self.moments.insert(f64::from(Double::from(value)));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> DFResult<ScalarValue> {
+        let result = self
+            .moments
+            .statistic(self.statistic)
+            .map(Double::from)
+            .ok_or(ThinError::ExpectedError);
+        DoubleTermValueEncoder::new(Arc::clone(&self.encoding))
+            .encode_term(result)
+            .map(EncodingScalar::into_scalar_value)
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self)
+    }
+
+    fn state(&mut self) -> DFResult<Vec<ScalarValue>> {
+        Ok(vec![
+            ScalarValue::UInt64(Some(self.moments.count)),
+            ScalarValue::Float64(Some(self.moments.mean)),
+            ScalarValue::Float64(Some(self.moments.m2)),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        let [counts, means, m2s] = states else {
+            return Err(exec_datafusion_err!("Invalid state of variance aggregate."));
+        };
+
+        let counts = counts.as_primitive::<UInt64Type>();
+        let means = means.as_primitive::<Float64Type>();
+        let m2s = m2s.as_primitive::<Float64Type>();
+        for ((count, mean), m2) in
+            counts.values().iter().zip(means.values()).zip(m2s.values())
+        {
+            self.moments.merge(&Moments {
+                count: *count,
+                mean: *mean,
+                m2: *m2,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The running moments of a set of values, as maintained by Welford's algorithm.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Moments {
+    /// The number of values.
+    count: u64,
+    /// The mean of the values.
+    mean: f64,
+    /// The sum of the squared differences from the mean.
+    m2: f64,
+}
+
+impl Moments {
+    /// Adds a single value.
+    fn insert(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Merges the moments of another set of values (Chan et al.'s parallel algorithm).
+    fn merge(&mut self, other: &Moments) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2
+            + delta * delta * self.count as f64 * other.count as f64 / count as f64;
+        self.count = count;
+    }
+
+    /// Computes the given `statistic`, or [None] if it is not defined for the values.
+    fn statistic(&self, statistic: VarianceStatistic) -> Option<f64> {
+        let divisor = if statistic.is_sample() {
+            self.count.checked_sub(1)?
+        } else {
+            self.count
+        };
+        if divisor == 0 {
+            return None;
+        }
+
+        let variance = self.m2 / divisor as f64;
+        Some(if statistic.is_standard_deviation() {
+            variance.sqrt()
+        } else {
+            variance
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [f64; 8] = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+    #[test]
+    fn test_population_statistics() {
+        let moments = moments(&VALUES);
+        assert_eq!(
+            moments.statistic(VarianceStatistic::PopulationVariance),
+            Some(4.0)
+        );
+        assert_eq!(
+            moments.statistic(VarianceStatistic::PopulationStandardDeviation),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn test_sample_statistics() {
+        let moments = moments(&VALUES);
+        let variance = moments
+            .statistic(VarianceStatistic::SampleVariance)
+            .unwrap();
+        assert!((variance - 32.0 / 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_single_value() {
+        let moments = moments(&[42.0]);
+        assert_eq!(
+            moments.statistic(VarianceStatistic::PopulationVariance),
+            Some(0.0)
+        );
+        assert_eq!(moments.statistic(VarianceStatistic::SampleVariance), None);
+    }
+
+    #[test]
+    fn test_no_values() {
+        let moments = Moments::default();
+        assert_eq!(
+            moments.statistic(VarianceStatistic::PopulationVariance),
+            None
+        );
+        assert_eq!(moments.statistic(VarianceStatistic::SampleVariance), None);
+    }
+
+    #[test]
+    fn test_merge_equals_sequential_insertion() {
+        let mut merged = moments(&VALUES[..3]);
+        merged.merge(&moments(&VALUES[3..]));
+        merged.merge(&Moments::default());
+
+        let sequential = moments(&VALUES);
+        assert_eq!(merged.count, sequential.count);
+        assert!((merged.mean - sequential.mean).abs() < 1e-12);
+        assert!((merged.m2 - sequential.m2).abs() < 1e-12);
+    }
+
+    fn moments(values: &[f64]) -> Moments {
+        let mut moments = Moments::default();
+        for value in values {
+            moments.insert(*value);
+        }
+        moments
+    }
+}
@@ -179,7 +179,7 @@ mod tests {
         encoding: &TypedValueEncodingRef,
         input: ArrayRef,
     ) -> ColumnarValue {
-        let udf = NativeBooleanAsTerm::new(Arc::clone(&encoding));
+        let udf = NativeBooleanAsTerm::new(Arc::clone(encoding));
         let args = ScalarFunctionArgs {
             args: vec![ColumnarValue::Array(Arc::clone(&input))],
             arg_fields: vec![FieldRef::new(Field::new(
@@ -0,0 +1,242 @@
+//! Support for user-defined RDF datatypes.
+//!
+//! Literals with a datatype that RDF Fusion does not support natively are stored as "other"
+//! literals in the [typed value encoding](rdf_fusion_encoding::typed_value). By default, such
+//! literals can only be compared for equality based on their lexical form. Registering a
+//! [CustomDatatype] allows users to provide a validator for the lexical form and custom comparison
+//! semantics for these literals.
+//!
+//! Custom datatypes are registered per [RdfFusionContext](https://docs.rs/rdf-fusion-execution).
+//! Literals with unregistered datatypes retain the default behavior.
+
+use rdf_fusion_model::{NamedNode, NamedNodeRef, ThinError, ThinResult, TypedValueRef};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Validates the lexical form of a literal.
+pub type LexicalFormValidator = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Compares the lexical forms of two valid literals.
+pub type LexicalFormComparator =
+    Arc<dyn Fn(&str, &str) -> Option<Ordering> + Send + Sync>;
+
+/// A user-defined RDF datatype with a validator and comparison semantics.
+///
+/// Only datatypes that are not supported natively (i.e., that end up as "other" literals in the
+/// typed value encoding) can be customized. Registering a built-in datatype (e.g., `xsd:integer`)
+/// has no effect.
+///
+/// # Example
+///
+/// ```
+/// # use rdf_fusion_functions::datatypes::CustomDatatype;
+/// # use rdf_fusion_model::NamedNode;
+/// // Compares amounts of the form "12.50 EUR". Amounts in different currencies are incomparable.
+/// let currency = CustomDatatype::new(
+///     NamedNode::new_unchecked("http://example.com/currency"),
+///     |value| parse_amount(value).is_some(),
+///     |lhs, rhs| {
+///         let (lhs_amount, lhs_currency) = parse_amount(lhs)?;
+///         let (rhs_amount, rhs_currency) = parse_amount(rhs)?;
+///         if lhs_currency != rhs_currency {
+///             return None;
+///         }
+///         lhs_amount.partial_cmp(&rhs_amount)
+///     },
+/// );
+///
+/// fn parse_amount(value: &str) -> Option<(f64, &str)> {
+///     let (amount, currency) = value.split_once(' ')?;
+///     Some((amount.parse().ok()?, currency))
+/// }
+///
+/// assert!(currency.is_valid("12.50 EUR"));
+/// assert!(!currency.is_valid("twelve"));
+/// ```
+#[derive(Clone)]
+pub struct CustomDatatype {
+    /// The IRI of the datatype.
+    iri: NamedNode,
+    /// Checks whether a lexical form is valid.
+    validator: LexicalFormValidator,
+    /// Compares two valid lexical forms.
+    comparator: LexicalFormComparator,
+}
+
+impl CustomDatatype {
+    /// Creates a new [CustomDatatype].
+    ///
+    /// The `validator` decides whether a lexical form belongs to the lexical space of the datatype.
+    /// The `comparator` is only called with valid lexical forms. Returning [None] from the
+    /// `comparator` indicates that the values are incomparable.
+    pub fn new(
+        iri: NamedNode,
+        validator: impl Fn(&str) -> bool + Send + Sync + 'static,
+        comparator: impl Fn(&str, &str) -> Option<Ordering> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            iri,
+            validator: Arc::new(validator),
+            comparator: Arc::new(comparator),
+        }
+    }
+
+    /// Returns the IRI of the datatype.
+    pub fn iri(&self) -> NamedNodeRef<'_> {
+        self.iri.as_ref()
+    }
+
+    /// Returns whether `value` is a valid lexical form of this datatype.
+    pub fn is_valid(&self, value: &str) -> bool {
+        (self.validator)(value)
+    }
+
+    /// Compares two lexical forms of this datatype.
+    ///
+    /// Returns [None] if one of the values is invalid or if the values are incomparable.
+    pub fn compare(&self, lhs: &str, rhs: &str) -> Option<Ordering> {
+        if !self.is_valid(lhs) || !self.is_valid(rhs) {
+            return None;
+        }
+        (self.comparator)(lhs, rhs)
+    }
+}
+
+impl Debug for CustomDatatype {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomDatatype")
+            .field("iri", &self.iri)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An immutable set of [CustomDatatype]s, identified by their IRIs.
+///
+/// Two sets are considered equal if they contain the same datatype IRIs.
+#[derive(Debug, Clone, Default)]
+pub struct CustomDatatypes {
+    /// The datatypes by their IRI.
+    datatypes: Arc<BTreeMap<String, CustomDatatype>>,
+}
+
+impl CustomDatatypes {
+    /// Returns a new set that also contains `datatype`. An existing datatype with the same IRI is
+    /// replaced.
+    #[must_use]
+    pub fn with_datatype(&self, datatype: CustomDatatype) -> Self {
+        let mut datatypes = self.datatypes.as_ref().clone();
+        datatypes.insert(datatype.iri.as_str().to_owned(), datatype);
+        Self {
+            datatypes: Arc::new(datatypes),
+        }
+    }
+
+    /// Returns the datatype with the given `iri`, if registered.
+    pub fn get(&self, iri: NamedNodeRef<'_>) -> Option<&CustomDatatype> {
+        self.datatypes.get(iri.as_str())
+    }
+
+    /// Returns whether no datatype is registered.
+    pub fn is_empty(&self) -> bool {
+        self.datatypes.is_empty()
+    }
+
+    /// Compares `lhs` and `rhs` if both are literals of the same registered datatype.
+    ///
+    /// Returns [None] if the values are not literals of a registered datatype. In this case, the
+    /// default comparison semantics apply. Invalid lexical forms and incomparable values yield a
+    /// type error.
+    pub fn partial_cmp(
+        &self,
+        lhs: TypedValueRef<'_>,
+        rhs: TypedValueRef<'_>,
+    ) -> Option<ThinResult<Ordering>> {
+        let (TypedValueRef::OtherLiteral(lhs), TypedValueRef::OtherLiteral(rhs)) =
+            (lhs, rhs)
+        else {
+            return None;
+        };
+
+        if lhs.datatype() != rhs.datatype() {
+            return None;
+        }
+
+        let datatype = self.get(lhs.datatype())?;
+        Some(
+            datatype
+                .compare(lhs.value(), rhs.value())
+                .ok_or(ThinError::ExpectedError),
+        )
+    }
+}
+
+impl PartialEq for CustomDatatypes {
+    fn eq(&self, other: &Self) -> bool {
+        self.datatypes.keys().eq(other.datatypes.keys())
+    }
+}
+
+impl Eq for CustomDatatypes {}
+
+impl Hash for CustomDatatypes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for iri in self.datatypes.keys() {
+            iri.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::LiteralRef;
+
+    #[test]
+    fn test_registered_datatype_uses_comparator() {
+        let datatypes = CustomDatatypes::default().with_datatype(reversed_datatype());
+
+        let ordering = datatypes.partial_cmp(literal("1"), literal("2"));
+
+        assert_eq!(ordering, Some(Ok(Ordering::Greater)));
+    }
+
+    #[test]
+    fn test_invalid_lexical_form_is_type_error() {
+        let datatypes = CustomDatatypes::default().with_datatype(reversed_datatype());
+
+        let ordering = datatypes.partial_cmp(literal("1"), literal("a"));
+
+        assert_eq!(ordering, Some(Err(ThinError::ExpectedError)));
+    }
+
+    #[test]
+    fn test_unregistered_datatype_uses_default_semantics() {
+        let datatypes = CustomDatatypes::default();
+
+        let ordering = datatypes.partial_cmp(literal("1"), literal("2"));
+
+        assert_eq!(ordering, None);
+    }
+
+    fn reversed_datatype() -> CustomDatatype {
+        CustomDatatype::new(
+            NamedNode::new_unchecked("http://example.com/reversed"),
+            |value| value.parse::<i64>().is_ok(),
+            |lhs, rhs| {
+                let lhs = lhs.parse::<i64>().ok()?;
+                let rhs = rhs.parse::<i64>().ok()?;
+                Some(rhs.cmp(&lhs))
+            },
+        )
+    }
+
+    fn literal(value: &str) -> TypedValueRef<'_> {
+        TypedValueRef::OtherLiteral(LiteralRef::new_typed_literal(
+            value,
+            NamedNodeRef::new_unchecked("http://example.com/reversed"),
+        ))
+    }
+}
@@ -33,6 +33,7 @@
 
 pub mod aggregates;
 pub mod builtin;
+pub mod datatypes;
 pub mod registry;
 pub mod scalar;
 
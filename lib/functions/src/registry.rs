@@ -9,6 +9,7 @@ use crate::builtin::native::{
     effective_boolean_value, native_boolean_as_term, native_int64_as_term,
 };
 use crate::builtin::query::is_compatible;
+use crate::datatypes::CustomDatatypes;
 use crate::scalar::comparison::{
     EqualSparqlOp, GreaterOrEqualSparqlOp, GreaterThanSparqlOp, LessOrEqualSparqlOp,
     LessThanSparqlOp,
@@ -27,7 +28,7 @@ use crate::scalar::dates_and_times::MonthSparqlOp;
 use crate::scalar::dates_and_times::SecondsSparqlOp;
 use crate::scalar::dates_and_times::TimezoneSparqlOp;
 use crate::scalar::dates_and_times::YearSparqlOp;
-use crate::scalar::dates_and_times::{DaySparqlOp, TzSparqlOp};
+use crate::scalar::dates_and_times::{AdjustSparqlOp, DaySparqlOp, TzSparqlOp};
 use crate::scalar::functional_form::{BoundSparqlOp, IfSparqlOp};
 use crate::scalar::numeric::RoundSparqlOp;
 use crate::scalar::numeric::{AbsSparqlOp, UnaryMinusSparqlOp, UnaryPlusSparqlOp};
@@ -88,6 +89,8 @@ pub struct RegistryContent {
     udf_encodings: HashMap<String, Vec<EncodingName>>,
     /// The actual function registry.
     registry: MemoryFunctionRegistry,
+    /// Incremented each time a function is registered.
+    version: u64,
 }
 
 impl Debug for DefaultRdfFusionFunctionRegistry {
@@ -106,6 +109,7 @@ impl DefaultRdfFusionFunctionRegistry {
             inner: Arc::new(RwLock::new(RegistryContent {
                 udf_encodings: HashMap::default(),
                 registry: MemoryFunctionRegistry::default(),
+                version: 0,
             })),
         };
         register_functions(&mut registry);
@@ -156,15 +160,19 @@ impl RdfFusionFunctionRegistry for DefaultRdfFusionFunctionRegistry {
         lock.registry
             .register_udf(Arc::new(udf))
             .expect("Cannot fail");
+        lock.version += 1;
     }
 
     fn register_udaf(&self, udaf: AggregateUDF) {
-        self.inner
-            .write()
-            .unwrap()
-            .registry
+        let mut lock = self.inner.write().unwrap();
+        lock.registry
             .register_udaf(Arc::new(udaf))
             .expect("Cannot fail");
+        lock.version += 1;
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.read().unwrap().version
     }
 }
 
@@ -214,6 +222,70 @@ where
     ScalarUDF::new_from_impl(adapter)
 }
 
+/// Creates the UDFs of the SPARQL comparison operators (`=`, `<`, `<=`, `>`, `>=`).
+///
+/// Literals of the given custom `datatypes` are compared with the comparators of the datatypes.
+/// Registering the returned UDFs replaces the existing comparison operators.
+pub fn comparison_udfs(
+    encodings: &RdfFusionEncodings,
+    datatypes: &CustomDatatypes,
+) -> Vec<ScalarUDF> {
+    vec![
+        ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+            encodings.clone(),
+            EqualSparqlOp::new_with_custom_datatypes(datatypes.clone()),
+        )),
+        ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+            encodings.clone(),
+            GreaterThanSparqlOp::new_with_custom_datatypes(datatypes.clone()),
+        )),
+        ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+            encodings.clone(),
+            GreaterOrEqualSparqlOp::new_with_custom_datatypes(datatypes.clone()),
+        )),
+        ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+            encodings.clone(),
+            LessThanSparqlOp::new_with_custom_datatypes(datatypes.clone()),
+        )),
+        ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+            encodings.clone(),
+            LessOrEqualSparqlOp::new_with_custom_datatypes(datatypes.clone()),
+        )),
+    ]
+}
+
+/// Creates the UDF of the SPARQL `RAND` function.
+///
+/// If `seed` is given, the function produces a deterministic sequence of values (see
+/// [RandSparqlOp::new_with_seed]). Registering the returned UDF replaces the existing function.
+pub fn rand_udf(encodings: &RdfFusionEncodings, seed: Option<u64>) -> ScalarUDF {
+    let op = match seed {
+        None => RandSparqlOp::new(),
+        Some(seed) => RandSparqlOp::new_with_seed(seed),
+    };
+    ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(encodings.clone(), op))
+}
+
+/// Creates a new UDF for the seeded SPARQL `RAND` function in `udf` whose sequence of values
+/// starts from the beginning (see [RandSparqlOp::restarted]).
+///
+/// Returns [None] if `udf` is not a seeded `RAND` function created with [rand_udf].
+pub fn restarted_seeded_rand_udf(
+    encodings: &RdfFusionEncodings,
+    udf: &ScalarUDF,
+) -> Option<ScalarUDF> {
+    let op = udf
+        .inner()
+        .as_any()
+        .downcast_ref::<ScalarSparqlOpAdapter<RandSparqlOp>>()?
+        .op();
+    op.seed()?;
+    Some(ScalarUDF::new_from_impl(ScalarSparqlOpAdapter::new(
+        encodings.clone(),
+        op.restarted(),
+    )))
+}
+
 fn register_functions(registry: &mut DefaultRdfFusionFunctionRegistry) {
     let scalar_fns: Vec<ScalarUDF> = vec![
         create_scalar_udf::<StrSparqlOp>(registry.encodings.clone()),
@@ -221,7 +293,7 @@ fn register_functions(registry: &mut DefaultRdfFusionFunctionRegistry) {
         create_scalar_udf::<LangMatchesSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<DatatypeSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<BNodeSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<RandSparqlOp>(registry.encodings.clone()),
+        rand_udf(&registry.encodings, None),
         create_scalar_udf::<AbsSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<CeilSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<FloorSparqlOp>(registry.encodings.clone()),
@@ -246,6 +318,7 @@ fn register_functions(registry: &mut DefaultRdfFusionFunctionRegistry) {
         create_scalar_udf::<SecondsSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<TimezoneSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<TzSparqlOp>(registry.encodings.clone()),
+        create_scalar_udf::<AdjustSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<UuidSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<StrUuidSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<Md5SparqlOp>(registry.encodings.clone()),
@@ -275,11 +348,6 @@ fn register_functions(registry: &mut DefaultRdfFusionFunctionRegistry) {
             )),
         ),
         create_scalar_udf::<IfSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<EqualSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<GreaterThanSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<GreaterOrEqualSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<LessThanSparqlOp>(registry.encodings.clone()),
-        create_scalar_udf::<LessOrEqualSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<AddSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<DivSparqlOp>(registry.encodings.clone()),
         create_scalar_udf::<MulSparqlOp>(registry.encodings.clone()),
@@ -308,6 +376,10 @@ fn register_functions(registry: &mut DefaultRdfFusionFunctionRegistry) {
         registry.register_udf(udf);
     }
 
+    for udf in comparison_udfs(&registry.encodings, &CustomDatatypes::default()) {
+        registry.register_udf(udf);
+    }
+
     // Aggregate functions
     let aggregate_fns: Vec<AggregateUDF> = vec![
         sum_typed_value(Arc::clone(registry.encodings.typed_value())),
@@ -1,3 +1,5 @@
+use crate::datatypes::CustomDatatypes;
+use crate::scalar::comparison::sparql_equal;
 use crate::scalar::dispatch::dispatch_binary_typed_value;
 use crate::scalar::sparql_op_impl::{
     ScalarSparqlOpImpl, create_typed_value_sparql_op_impl,
@@ -10,11 +12,13 @@ use rdf_fusion_encoding::typed_value::TypedValueEncoding;
 use rdf_fusion_extensions::functions::BuiltinName;
 use rdf_fusion_extensions::functions::FunctionName;
 use rdf_fusion_model::{ThinError, TypedValueRef};
-use std::cmp::Ordering;
 
 /// Implementation of the SPARQL `=` operator.
 #[derive(Debug, Hash, PartialEq, Eq)]
-pub struct EqualSparqlOp;
+pub struct EqualSparqlOp {
+    /// The custom datatypes whose literals are compared with their own comparators.
+    datatypes: CustomDatatypes,
+}
 
 impl Default for EqualSparqlOp {
     fn default() -> Self {
@@ -27,7 +31,12 @@ impl EqualSparqlOp {
 
     /// Creates a new [EqualSparqlOp].
     pub fn new() -> Self {
-        Self {}
+        Self::new_with_custom_datatypes(CustomDatatypes::default())
+    }
+
+    /// Creates a new [EqualSparqlOp] that considers the given custom `datatypes`.
+    pub fn new_with_custom_datatypes(datatypes: CustomDatatypes) -> Self {
+        Self { datatypes }
     }
 }
 
@@ -44,20 +53,18 @@ impl ScalarSparqlOp for EqualSparqlOp {
         &self,
         encodings: &RdfFusionEncodings,
     ) -> Option<Box<dyn ScalarSparqlOpImpl<TypedValueEncoding>>> {
+        let datatypes = self.datatypes.clone();
         Some(create_typed_value_sparql_op_impl(
             encodings.typed_value(),
-            |args: ScalarSparqlOpArgs<TypedValueEncoding>| {
+            move |args: ScalarSparqlOpArgs<TypedValueEncoding>| {
                 dispatch_binary_typed_value(
                     &args.encoding,
                     &args.args[0],
                     &args.args[1],
                     |lhs_value, rhs_value| {
-                        lhs_value
-                            .partial_cmp(&rhs_value)
-                            .map(|o| o == Ordering::Equal)
+                        sparql_equal(&datatypes, lhs_value, rhs_value)
                             .map(Into::into)
                             .map(TypedValueRef::BooleanLiteral)
-                            .ok_or(ThinError::ExpectedError)
                     },
                     |_, _| ThinError::expected(),
                 )
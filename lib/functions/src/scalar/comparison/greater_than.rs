@@ -1,3 +1,5 @@
+use crate::datatypes::CustomDatatypes;
+use crate::scalar::comparison::sparql_compare;
 use crate::scalar::dispatch::dispatch_binary_typed_value;
 use crate::scalar::sparql_op_impl::{
     ScalarSparqlOpImpl, create_typed_value_sparql_op_impl,
@@ -12,7 +14,10 @@ use std::cmp::Ordering;
 
 /// Implementation of the SPARQL `>` operator.
 #[derive(Debug, Hash, PartialEq, Eq)]
-pub struct GreaterThanSparqlOp;
+pub struct GreaterThanSparqlOp {
+    /// The custom datatypes whose literals are compared with their own comparators.
+    datatypes: CustomDatatypes,
+}
 
 impl Default for GreaterThanSparqlOp {
     fn default() -> Self {
@@ -25,7 +30,12 @@ impl GreaterThanSparqlOp {
 
     /// Creates a new [GreaterThanSparqlOp].
     pub fn new() -> Self {
-        Self {}
+        Self::new_with_custom_datatypes(CustomDatatypes::default())
+    }
+
+    /// Creates a new [GreaterThanSparqlOp] that considers the given custom `datatypes`.
+    pub fn new_with_custom_datatypes(datatypes: CustomDatatypes) -> Self {
+        Self { datatypes }
     }
 }
 
@@ -42,20 +52,19 @@ impl ScalarSparqlOp for GreaterThanSparqlOp {
         &self,
         encodings: &RdfFusionEncodings,
     ) -> Option<Box<dyn ScalarSparqlOpImpl<TypedValueEncoding>>> {
+        let datatypes = self.datatypes.clone();
         Some(create_typed_value_sparql_op_impl(
             encodings.typed_value(),
-            |args| {
+            move |args| {
                 dispatch_binary_typed_value(
                     &args.encoding,
                     &args.args[0],
                     &args.args[1],
                     |lhs_value, rhs_value| {
-                        lhs_value
-                            .partial_cmp(&rhs_value)
+                        sparql_compare(&datatypes, lhs_value, rhs_value)
                             .map(|o| o == Ordering::Greater)
                             .map(Into::into)
                             .map(TypedValueRef::BooleanLiteral)
-                            .ok_or(ThinError::ExpectedError)
                     },
                     |_, _| ThinError::expected(),
                 )
@@ -1,3 +1,10 @@
+//! The SPARQL comparison operators (`=`, `<`, `<=`, `>`, `>=`).
+//!
+//! These operators implement the strict semantics of the SPARQL operator mapping: comparing
+//! incomparable values raises a type error. They must not be used for sorting. `ORDER BY` uses the
+//! total order of the [sortable term encoding](rdf_fusion_encoding::sortable_term) instead, which
+//! also orders values that cannot be compared with these operators (e.g., IRIs and literals).
+
 mod equal;
 mod greater_or_equal;
 mod greater_than;
@@ -9,3 +16,203 @@ pub use greater_or_equal::GreaterOrEqualSparqlOp;
 pub use greater_than::GreaterThanSparqlOp;
 pub use less_or_equal::LessOrEqualSparqlOp;
 pub use less_than::LessThanSparqlOp;
+
+use crate::datatypes::CustomDatatypes;
+use rdf_fusion_model::{ThinError, ThinResult, TypedValueRef};
+use std::cmp::Ordering;
+
+/// Compares two values with the SPARQL `=` operator.
+///
+/// Terms of different kinds (e.g., an IRI and a literal) are never equal. Comparing two literals
+/// that are not comparable (e.g., a string and a number) raises a type error. Literals of a
+/// registered custom datatype are compared with the comparator of the datatype.
+fn sparql_equal(
+    datatypes: &CustomDatatypes,
+    lhs: TypedValueRef<'_>,
+    rhs: TypedValueRef<'_>,
+) -> ThinResult<bool> {
+    if let Some(ordering) = datatypes.partial_cmp(lhs, rhs) {
+        return ordering.map(|o| o == Ordering::Equal);
+    }
+
+    lhs.partial_cmp(&rhs)
+        .map(|o| o == Ordering::Equal)
+        .ok_or(ThinError::ExpectedError)
+}
+
+/// Compares two values for the SPARQL `<`, `<=`, `>`, and `>=` operators.
+///
+/// Only literals can be ordered by these operators. Comparing IRIs, blank nodes, or literals that
+/// are not comparable (e.g., a string and a number) raises a type error instead of returning a
+/// definite result. Literals of a registered custom datatype are compared with the comparator of
+/// the datatype.
+///
+/// # Additional Resources
+/// - [SPARQL 1.1 - Operator Mapping](https://www.w3.org/TR/sparql11-query/#OperatorMapping)
+fn sparql_compare(
+    datatypes: &CustomDatatypes,
+    lhs: TypedValueRef<'_>,
+    rhs: TypedValueRef<'_>,
+) -> ThinResult<Ordering> {
+    if !is_literal(lhs) || !is_literal(rhs) {
+        return ThinError::expected();
+    }
+    if let Some(ordering) = datatypes.partial_cmp(lhs, rhs) {
+        return ordering;
+    }
+    lhs.partial_cmp(&rhs).ok_or(ThinError::ExpectedError)
+}
+
+fn is_literal(value: TypedValueRef<'_>) -> bool {
+    !matches!(
+        value,
+        TypedValueRef::NamedNode(_) | TypedValueRef::BlankNode(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatypes::CustomDatatype;
+    use rdf_fusion_model::{
+        BlankNodeRef, Boolean, Date, DateTime, LanguageStringRef, LiteralRef, NamedNode,
+        NamedNodeRef, Numeric, SimpleLiteralRef,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_incomparable_literals_are_type_errors() {
+        let string = TypedValueRef::SimpleLiteral(SimpleLiteralRef::new("a"));
+        let language_string =
+            TypedValueRef::LanguageStringLiteral(LanguageStringRef::new("a", "en"));
+        let number = TypedValueRef::NumericLiteral(Numeric::Int(1.into()));
+        let boolean = TypedValueRef::BooleanLiteral(Boolean::from(true));
+        let date_time = TypedValueRef::DateTimeLiteral(
+            DateTime::from_str("2020-01-01T00:00:00Z").unwrap(),
+        );
+        let date = TypedValueRef::DateLiteral(Date::from_str("2020-01-01Z").unwrap());
+        let other = TypedValueRef::OtherLiteral(LiteralRef::new_typed_literal(
+            "a",
+            NamedNodeRef::new_unchecked("http://example.com/type"),
+        ));
+
+        let pairs = [
+            (string, number),
+            (string, boolean),
+            (string, date_time),
+            (string, language_string),
+            (string, other),
+            (number, boolean),
+            (number, date_time),
+            (number, other),
+            (boolean, date_time),
+            (date_time, date),
+            (language_string, number),
+        ];
+
+        for (lhs, rhs) in pairs {
+            assert!(
+                sparql_compare(&none(), lhs, rhs).is_err(),
+                "{lhs:?} < {rhs:?}"
+            );
+            assert!(
+                sparql_compare(&none(), rhs, lhs).is_err(),
+                "{rhs:?} < {lhs:?}"
+            );
+            assert!(
+                sparql_equal(&none(), lhs, rhs).is_err(),
+                "{lhs:?} = {rhs:?}"
+            );
+            assert!(
+                sparql_equal(&none(), rhs, lhs).is_err(),
+                "{rhs:?} = {lhs:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordering_non_literals_is_type_error() {
+        let iri = TypedValueRef::NamedNode(NamedNodeRef::new_unchecked("http://a.org"));
+        let blank_node = TypedValueRef::BlankNode(BlankNodeRef::new_unchecked("b"));
+        let number = TypedValueRef::NumericLiteral(Numeric::Int(1.into()));
+
+        for (lhs, rhs) in [
+            (iri, iri),
+            (iri, blank_node),
+            (iri, number),
+            (blank_node, number),
+        ] {
+            assert!(
+                sparql_compare(&none(), lhs, rhs).is_err(),
+                "{lhs:?} < {rhs:?}"
+            );
+            assert!(
+                sparql_compare(&none(), rhs, lhs).is_err(),
+                "{rhs:?} < {lhs:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_equality_of_different_term_kinds_is_false() {
+        let iri = TypedValueRef::NamedNode(NamedNodeRef::new_unchecked("http://a.org"));
+        let number = TypedValueRef::NumericLiteral(Numeric::Int(1.into()));
+
+        assert_eq!(sparql_equal(&none(), iri, number), Ok(false));
+        assert_eq!(sparql_equal(&none(), iri, iri), Ok(true));
+    }
+
+    #[test]
+    fn test_comparable_literals() {
+        let one = TypedValueRef::NumericLiteral(Numeric::Int(1.into()));
+        let two = TypedValueRef::NumericLiteral(Numeric::Double(2.0.into()));
+
+        assert_eq!(sparql_compare(&none(), one, two), Ok(Ordering::Less));
+        assert_eq!(sparql_equal(&none(), one, two), Ok(false));
+    }
+
+    #[test]
+    fn test_custom_datatype_uses_comparator() {
+        let datatypes = CustomDatatypes::default().with_datatype(CustomDatatype::new(
+            NamedNode::new_unchecked("http://example.com/length"),
+            |value| value.ends_with("cm") || value.ends_with('m'),
+            |lhs, rhs| centimeters(lhs)?.partial_cmp(&centimeters(rhs)?),
+        ));
+        let length = |value| {
+            TypedValueRef::OtherLiteral(LiteralRef::new_typed_literal(
+                value,
+                NamedNodeRef::new_unchecked("http://example.com/length"),
+            ))
+        };
+
+        assert_eq!(
+            sparql_equal(&datatypes, length("1m"), length("100cm")),
+            Ok(true)
+        );
+        assert_eq!(
+            sparql_compare(&datatypes, length("2m"), length("150cm")),
+            Ok(Ordering::Greater)
+        );
+        assert!(sparql_compare(&datatypes, length("2m"), length("2 feet")).is_err());
+
+        // Without the registration, only the lexical forms are compared for equality.
+        assert_eq!(sparql_equal(&none(), length("1m"), length("1m")), Ok(true));
+        assert!(sparql_equal(&none(), length("1m"), length("100cm")).is_err());
+        assert!(sparql_compare(&none(), length("2m"), length("150cm")).is_err());
+    }
+
+    fn centimeters(value: &str) -> Option<f64> {
+        match value.strip_suffix("cm") {
+            Some(value) => value.parse().ok(),
+            None => value
+                .strip_suffix('m')?
+                .parse::<f64>()
+                .ok()
+                .map(|m| m * 100.0),
+        }
+    }
+
+    fn none() -> CustomDatatypes {
+        CustomDatatypes::default()
+    }
+}
@@ -0,0 +1,179 @@
+use crate::scalar::dispatch::dispatch_binary_typed_value;
+use crate::scalar::sparql_op_impl::{
+    ScalarSparqlOpImpl, create_typed_value_sparql_op_impl,
+};
+use crate::scalar::{ScalarSparqlOp, ScalarSparqlOpSignature, SparqlOpArity};
+use rdf_fusion_encoding::RdfFusionEncodings;
+use rdf_fusion_encoding::typed_value::TypedValueEncoding;
+use rdf_fusion_extensions::functions::BuiltinName;
+use rdf_fusion_extensions::functions::FunctionName;
+use rdf_fusion_model::{ThinError, ThinResult, TimezoneOffset, TypedValueRef};
+
+/// Implementation of the SPARQL 1.2 `ADJUST` function.
+///
+/// If the timezone is unbound, the timezone of the value is removed.
+///
+/// # Relevant Resources
+/// - [SPARQL 1.2 - ADJUST](https://www.w3.org/TR/sparql12-query/#func-adjust)
+/// - [XPath and XQuery Functions and Operators 3.1 - fn:adjust-dateTime-to-timezone](https://www.w3.org/TR/xpath-functions-31/#func-adjust-dateTime-to-timezone)
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct AdjustSparqlOp;
+
+impl Default for AdjustSparqlOp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdjustSparqlOp {
+    const NAME: FunctionName = FunctionName::Builtin(BuiltinName::Adjust);
+
+    /// Creates a new [AdjustSparqlOp].
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ScalarSparqlOp for AdjustSparqlOp {
+    fn name(&self) -> &FunctionName {
+        &Self::NAME
+    }
+
+    fn signature(&self) -> ScalarSparqlOpSignature {
+        ScalarSparqlOpSignature::default_with_arity(SparqlOpArity::Fixed(2))
+    }
+
+    fn typed_value_encoding_op(
+        &self,
+        encodings: &RdfFusionEncodings,
+    ) -> Option<Box<dyn ScalarSparqlOpImpl<TypedValueEncoding>>> {
+        Some(create_typed_value_sparql_op_impl(
+            encodings.typed_value(),
+            |args| {
+                dispatch_binary_typed_value(
+                    &args.encoding,
+                    &args.args[0],
+                    &args.args[1],
+                    |value, timezone| evaluate_adjust(value, Some(timezone)),
+                    |value, timezone| match (value, timezone) {
+                        (Ok(value), Err(_)) => evaluate_adjust(value, None),
+                        _ => ThinError::expected(),
+                    },
+                )
+            },
+        ))
+    }
+}
+
+/// Adjusts `value` to `timezone`. Removes the timezone of `value` if `timezone` is [None].
+fn evaluate_adjust<'a>(
+    value: TypedValueRef<'a>,
+    timezone: Option<TypedValueRef<'a>>,
+) -> ThinResult<TypedValueRef<'a>> {
+    let timezone = match timezone {
+        None => None,
+        Some(TypedValueRef::DayTimeDurationLiteral(duration)) => Some(
+            TimezoneOffset::try_from(duration).map_err(|_| ThinError::ExpectedError)?,
+        ),
+        Some(_) => return ThinError::expected(),
+    };
+
+    match value {
+        TypedValueRef::DateTimeLiteral(value) => {
+            Ok(TypedValueRef::DateTimeLiteral(value.adjust(timezone)?))
+        }
+        TypedValueRef::DateLiteral(value) => {
+            Ok(TypedValueRef::DateLiteral(value.adjust(timezone)?))
+        }
+        TypedValueRef::TimeLiteral(value) => {
+            Ok(TypedValueRef::TimeLiteral(value.adjust(timezone)?))
+        }
+        _ => ThinError::expected(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::{Date, DateTime, DayTimeDuration, Time};
+    use std::str::FromStr;
+
+    fn adjust(value: TypedValueRef<'_>, timezone: Option<&str>) -> ThinResult<String> {
+        let timezone = timezone.map(|timezone| {
+            TypedValueRef::DayTimeDurationLiteral(
+                DayTimeDuration::from_str(timezone).unwrap(),
+            )
+        });
+        evaluate_adjust(value, timezone).map(|result| match result {
+            TypedValueRef::DateTimeLiteral(value) => value.to_string(),
+            TypedValueRef::DateLiteral(value) => value.to_string(),
+            TypedValueRef::TimeLiteral(value) => value.to_string(),
+            _ => panic!("Unexpected result"),
+        })
+    }
+
+    fn date_time(value: &str) -> TypedValueRef<'static> {
+        TypedValueRef::DateTimeLiteral(DateTime::from_str(value).unwrap())
+    }
+
+    #[test]
+    fn test_adjust_date_time() {
+        assert_eq!(
+            adjust(date_time("2002-03-07T10:00:00-05:00"), Some("-PT10H")),
+            Ok("2002-03-07T05:00:00-10:00".to_owned())
+        );
+        assert_eq!(
+            adjust(date_time("2002-03-07T10:00:00"), Some("-PT10H")),
+            Ok("2002-03-07T10:00:00-10:00".to_owned())
+        );
+        assert_eq!(
+            adjust(date_time("2002-03-07T10:00:00-07:00"), Some("PT10H")),
+            Ok("2002-03-08T03:00:00+10:00".to_owned())
+        );
+        assert_eq!(
+            adjust(date_time("2002-03-07T00:00:00+01:00"), Some("-PT8H")),
+            Ok("2002-03-06T15:00:00-08:00".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_adjust_without_timezone_removes_timezone() {
+        assert_eq!(
+            adjust(date_time("2002-03-07T10:00:00"), None),
+            Ok("2002-03-07T10:00:00".to_owned())
+        );
+        assert_eq!(
+            adjust(date_time("2002-03-07T10:00:00-07:00"), None),
+            Ok("2002-03-07T10:00:00".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_adjust_date_and_time() {
+        let date =
+            TypedValueRef::DateLiteral(Date::from_str("2002-03-07-07:00").unwrap());
+        assert_eq!(
+            adjust(date, Some("-PT10H")),
+            Ok("2002-03-06-10:00".to_owned())
+        );
+
+        let time = TypedValueRef::TimeLiteral(Time::from_str("10:00:00-07:00").unwrap());
+        assert_eq!(
+            adjust(time, Some("-PT10H")),
+            Ok("07:00:00-10:00".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_adjust_with_invalid_timezone_is_error() {
+        let value = date_time("2002-03-07T10:00:00");
+        assert!(adjust(value, Some("PT15H")).is_err());
+        assert!(adjust(value, Some("-PT14H1M")).is_err());
+        assert!(adjust(value, Some("PT10H30S")).is_err());
+        assert!(
+            evaluate_adjust(value, Some(TypedValueRef::BooleanLiteral(true.into())))
+                .is_err()
+        );
+        assert!(adjust(TypedValueRef::BooleanLiteral(true.into()), None).is_err());
+    }
+}
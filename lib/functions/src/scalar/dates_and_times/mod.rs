@@ -1,3 +1,4 @@
+mod adjust;
 mod day;
 mod hours;
 mod minutes;
@@ -7,6 +8,7 @@ mod timezone;
 mod tz;
 mod year;
 
+pub use adjust::AdjustSparqlOp;
 pub use day::DaySparqlOp;
 pub use hours::HoursSparqlOp;
 pub use minutes::MinutesSparqlOp;
@@ -9,6 +9,11 @@ use rdf_fusion_extensions::functions::BuiltinName;
 use rdf_fusion_extensions::functions::FunctionName;
 use rdf_fusion_model::Boolean;
 
+/// Implementation of the SPARQL `IF` function.
+///
+/// As a scalar function receives all of its arguments evaluated, this implementation evaluates
+/// both branches for every row. The query rewriter therefore lowers `IF` to a `CASE` expression
+/// that only evaluates the selected branch (see `RdfFusionExprBuilder::sparql_if`).
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct IfSparqlOp;
 
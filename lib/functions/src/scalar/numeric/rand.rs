@@ -3,15 +3,47 @@ use crate::scalar::sparql_op_impl::{
 };
 use crate::scalar::{ScalarSparqlOp, ScalarSparqlOpSignature, SparqlOpArity};
 use datafusion::logical_expr::{ColumnarValue, Volatility};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rdf_fusion_encoding::typed_value::TypedValueEncoding;
 use rdf_fusion_encoding::{EncodingArray, RdfFusionEncodings, TermEncoder};
 use rdf_fusion_extensions::functions::BuiltinName;
 use rdf_fusion_extensions::functions::FunctionName;
-use rdf_fusion_model::{Numeric, TypedValueRef};
+use rdf_fusion_model::{Double, Numeric, TypedValueRef};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
 
-#[derive(Debug, Hash, PartialEq, Eq)]
-pub struct RandSparqlOp;
+/// Implementation of the SPARQL `RAND` function.
+///
+/// By default, the values are truly random. For reproducible results (e.g., in benchmarks), the
+/// op can be created with a seed (see [RandSparqlOp::new_with_seed]).
+#[derive(Debug)]
+pub struct RandSparqlOp {
+    /// The seed of the random number generator, if any.
+    seed: Option<u64>,
+    /// The random number generator of a seeded op. It is shared by all batches evaluated by the
+    /// op, such that each batch continues the sequence of values of the previous batches.
+    rng: Option<Arc<Mutex<StdRng>>>,
+}
+
+impl PartialEq for RandSparqlOp {
+    fn eq(&self, other: &Self) -> bool {
+        let same_rng = match (&self.rng, &other.rng) {
+            (Some(lhs), Some(rhs)) => Arc::ptr_eq(lhs, rhs),
+            (None, None) => true,
+            _ => false,
+        };
+        self.seed == other.seed && same_rng
+    }
+}
+
+impl Eq for RandSparqlOp {}
+
+impl Hash for RandSparqlOp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.seed.hash(state);
+    }
+}
 
 impl Default for RandSparqlOp {
     fn default() -> Self {
@@ -22,8 +54,42 @@ impl Default for RandSparqlOp {
 impl RandSparqlOp {
     const NAME: FunctionName = FunctionName::Builtin(BuiltinName::Rand);
 
+    /// Creates a new [RandSparqlOp] that produces truly random values.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            seed: None,
+            rng: None,
+        }
+    }
+
+    /// Creates a new [RandSparqlOp] that produces a deterministic sequence of values.
+    ///
+    /// The random number generator is seeded with `seed` once and advanced by each evaluated
+    /// batch. Hence, an op must not be shared between executions of a query (see
+    /// [Self::restarted]). Within an execution, the batches continue the sequence in the order in
+    /// which they are evaluated. DataFusion does not expose the partition of a batch to scalar
+    /// functions. Therefore, the values are only reproducible if the batches are evaluated in a
+    /// deterministic order (e.g., with a single target partition).
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            rng: Some(Arc::new(Mutex::new(StdRng::seed_from_u64(seed)))),
+        }
+    }
+
+    /// Returns the seed of this op, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Creates a new [RandSparqlOp] with the same seed whose sequence of values starts from the
+    /// beginning.
+    #[must_use]
+    pub fn restarted(&self) -> Self {
+        match self.seed {
+            None => Self::new(),
+            Some(seed) => Self::new_with_seed(seed),
+        }
     }
 }
 
@@ -43,13 +109,21 @@ impl ScalarSparqlOp for RandSparqlOp {
         &self,
         encodings: &RdfFusionEncodings,
     ) -> Option<Box<dyn ScalarSparqlOpImpl<TypedValueEncoding>>> {
+        let rng = self.rng.clone();
         Some(create_typed_value_sparql_op_impl(
             encodings.typed_value(),
-            |args| {
-                let mut rng = rand::rng();
-                let values = (0..args.number_rows).map(|_| {
-                    let value = rng.random::<f64>();
-                    Ok(TypedValueRef::NumericLiteral(Numeric::Double(value.into())))
+            move |args| {
+                let values = match &rng {
+                    None => random_doubles(&mut rand::rng(), args.number_rows),
+                    Some(rng) => {
+                        // A panic while holding the lock cannot leave the generator in an
+                        // invalid state.
+                        let mut rng = rng.lock().unwrap_or_else(PoisonError::into_inner);
+                        random_doubles(&mut *rng, args.number_rows)
+                    }
+                };
+                let values = values.into_iter().map(|value| {
+                    Ok(TypedValueRef::NumericLiteral(Numeric::Double(value)))
                 });
                 let array = args.encoding.default_encoder().encode_terms(values)?;
                 Ok(ColumnarValue::Array(array.into_array_ref()))
@@ -57,3 +131,8 @@ impl ScalarSparqlOp for RandSparqlOp {
         ))
     }
 }
+
+/// Generates `count` random doubles in the range `[0, 1)`.
+fn random_doubles(rng: &mut impl Rng, count: usize) -> Vec<Double> {
+    (0..count).map(|_| rng.random::<f64>().into()).collect()
+}
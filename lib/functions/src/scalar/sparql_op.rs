@@ -188,6 +188,11 @@ impl<TScalarSparqlOp: ScalarSparqlOp> ScalarSparqlOpAdapter<TScalarSparqlOp> {
         }
     }
 
+    /// Returns the instance of the [ScalarSparqlOp].
+    pub fn op(&self) -> &TScalarSparqlOp {
+        &self.op
+    }
+
     fn detect_input_encoding(
         &self,
         arg_types: &[DataType],
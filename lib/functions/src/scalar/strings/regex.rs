@@ -109,33 +109,93 @@ impl ScalarSparqlOp for RegexSparqlOp {
     }
 }
 
+/// Compiles a regex `pattern` with the given `flags` according to the rules of XPath.
+///
+/// - `i`: Case-insensitive matching.
+/// - `s`: The `.` also matches line breaks (dot-all mode).
+/// - `m`: `^` and `$` match at the start and end of each line.
+/// - `x`: Whitespace characters in the pattern are removed, except within character classes.
+/// - `q`: The pattern is matched literally. Only the `i` flag is honored in combination with `q`.
+///
+/// Any other flag is an error.
+///
+/// # Relevant Resources
+/// - [XPath and XQuery Functions and Operators 3.1 - Flags](https://www.w3.org/TR/xpath-functions-31/#flags)
 pub(super) fn compile_pattern(pattern: &str, flags: Option<&str>) -> ThinResult<Regex> {
     const REGEX_SIZE_LIMIT: usize = 1_000_000;
 
-    let mut pattern = Cow::Borrowed(pattern);
     let flags = flags.unwrap_or_default();
-    if flags.contains('q') {
-        pattern = regex::escape(&pattern).into();
+    if flags
+        .chars()
+        .any(|flag| !matches!(flag, 's' | 'm' | 'i' | 'x' | 'q'))
+    {
+        return ThinError::expected(); // invalid option
     }
+
+    let literal = flags.contains('q');
+    let pattern = if literal {
+        Cow::Owned(regex::escape(pattern))
+    } else if flags.contains('x') {
+        Cow::Owned(remove_whitespace(pattern))
+    } else {
+        Cow::Borrowed(pattern)
+    };
+
     let mut regex_builder = RegexBuilder::new(&pattern);
     regex_builder.size_limit(REGEX_SIZE_LIMIT);
-    for flag in flags.chars() {
-        match flag {
-            's' => {
-                regex_builder.dot_matches_new_line(true);
-            }
-            'm' => {
-                regex_builder.multi_line(true);
-            }
-            'i' => {
-                regex_builder.case_insensitive(true);
-            }
-            'x' => {
-                regex_builder.ignore_whitespace(true);
+    regex_builder.case_insensitive(flags.contains('i'));
+    if !literal {
+        regex_builder.dot_matches_new_line(flags.contains('s'));
+        regex_builder.multi_line(flags.contains('m'));
+    }
+    regex_builder.build().map_err(|_| ThinError::ExpectedError)
+}
+
+/// Removes the whitespace characters of `pattern` that are not part of a character class.
+fn remove_whitespace(pattern: &str) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut class_depth = 0_usize;
+    let mut escaped = false;
+    for c in pattern.chars() {
+        if class_depth == 0 && matches!(c, ' ' | '\t' | '\n' | '\r') {
+            continue;
+        }
+
+        if escaped {
+            escaped = false;
+        } else {
+            match c {
+                '\\' => escaped = true,
+                '[' => class_depth += 1,
+                ']' => class_depth = class_depth.saturating_sub(1),
+                _ => {}
             }
-            'q' => (),                         // Already supported
-            _ => return ThinError::expected(), // invalid option
         }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_pattern_extended_keeps_whitespace_in_classes() {
+        let regex = compile_pattern("a [ ] b # c", Some("x")).unwrap();
+        assert!(regex.is_match("a b#c"));
+        assert!(!regex.is_match("ab#c"));
+    }
+
+    #[test]
+    fn test_compile_pattern_literal_ignores_other_flags() {
+        let regex = compile_pattern("a .b", Some("qxi")).unwrap();
+        assert!(regex.is_match("A .B"));
+        assert!(!regex.is_match("a.b"));
+    }
+
+    #[test]
+    fn test_compile_pattern_invalid_flag() {
+        assert!(compile_pattern("a", Some("ig")).is_err());
     }
-    regex_builder.build().map_err(|_| ThinError::ExpectedError)
 }
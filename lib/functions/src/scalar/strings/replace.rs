@@ -6,17 +6,25 @@ use crate::scalar::sparql_op_impl::{
 };
 use crate::scalar::strings::regex::compile_pattern;
 use crate::scalar::{ScalarSparqlOp, ScalarSparqlOpSignature, SparqlOpArity};
-use rdf_fusion_encoding::RdfFusionEncodings;
-use rdf_fusion_encoding::typed_value::TypedValueEncoding;
+use rdf_fusion_encoding::typed_value::decoders::DefaultTypedValueDecoder;
+use rdf_fusion_encoding::typed_value::{TypedValueEncoding, TypedValueScalar};
+use rdf_fusion_encoding::{EncodingDatum, RdfFusionEncodings, TermDecoder};
 use rdf_fusion_extensions::functions::BuiltinName;
 use rdf_fusion_extensions::functions::FunctionName;
 use rdf_fusion_model::{
     LanguageString, SimpleLiteral, SimpleLiteralRef, StringLiteralRef, ThinError,
-    TypedValue, TypedValueRef,
+    ThinResult, TypedValue, TypedValueRef,
 };
+use regex::{Captures, Regex};
 use std::borrow::Cow;
 
-/// Implementation of the SPARQL `regex` function (binary version).
+/// Implementation of the SPARQL `replace` function.
+///
+/// The regex is compiled once per batch if the pattern and flags are constant. Otherwise, it is
+/// compiled for each row.
+///
+/// # Relevant Resources
+/// - [SPARQL 1.1 - REPLACE](https://www.w3.org/TR/sparql11-query/#func-replace)
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct ReplaceSparqlOp;
 
@@ -53,55 +61,324 @@ impl ScalarSparqlOp for ReplaceSparqlOp {
     ) -> Option<Box<dyn ScalarSparqlOpImpl<TypedValueEncoding>>> {
         Some(create_typed_value_sparql_op_impl(
             encodings.typed_value(),
-            |args| match args.args.len() {
-                3 => dispatch_ternary_owned_typed_value(
-                    &args.encoding,
-                    &args.args[0],
-                    &args.args[1],
-                    &args.args[2],
-                    |arg0, arg1, arg2| evaluate_replace(arg0, arg1, arg2, None)?,
-                    |_, _, _| ThinError::expected(),
-                ),
-                4 => dispatch_quaternary_owned_typed_value(
-                    &args.encoding,
-                    &args.args[0],
-                    &args.args[1],
-                    &args.args[2],
-                    &args.args[3],
-                    |arg0, arg1, arg2, arg3| {
-                        evaluate_replace(arg0, arg1, arg2, Some(arg3))?
-                    },
-                    |_, _, _, _| ThinError::expected(),
-                ),
-                _ => unreachable!("Invalid number of arguments"),
+            |args| {
+                // The regex only has to be compiled once if the pattern and flags are constant.
+                if let Some(regex) =
+                    compile_scalar_pattern(&args.args[1], args.args.get(3))
+                {
+                    return dispatch_ternary_owned_typed_value(
+                        &args.encoding,
+                        &args.args[0],
+                        &args.args[1],
+                        &args.args[2],
+                        |arg0, _, arg2| {
+                            let regex = regex.as_ref().map_err(|error| *error)?;
+                            evaluate_replace_with_regex(arg0, regex, arg2)
+                        },
+                        |_, _, _| ThinError::expected(),
+                    );
+                }
+
+                match args.args.len() {
+                    3 => dispatch_ternary_owned_typed_value(
+                        &args.encoding,
+                        &args.args[0],
+                        &args.args[1],
+                        &args.args[2],
+                        |arg0, arg1, arg2| evaluate_replace(arg0, arg1, arg2, None),
+                        |_, _, _| ThinError::expected(),
+                    ),
+                    4 => dispatch_quaternary_owned_typed_value(
+                        &args.encoding,
+                        &args.args[0],
+                        &args.args[1],
+                        &args.args[2],
+                        &args.args[3],
+                        |arg0, arg1, arg2, arg3| {
+                            evaluate_replace(arg0, arg1, arg2, Some(arg3))
+                        },
+                        |_, _, _, _| ThinError::expected(),
+                    ),
+                    _ => unreachable!("Invalid number of arguments"),
+                }
             },
         ))
     }
 }
 
+/// Compiles the regex if the `pattern` and the `flags` are scalars. Otherwise, returns [None] and
+/// the regex must be compiled for each row.
+fn compile_scalar_pattern(
+    pattern: &EncodingDatum<TypedValueEncoding>,
+    flags: Option<&EncodingDatum<TypedValueEncoding>>,
+) -> Option<ThinResult<Regex>> {
+    let EncodingDatum::Scalar(pattern, _) = pattern else {
+        return None;
+    };
+    let flags = match flags {
+        None => None,
+        Some(EncodingDatum::Scalar(flags, _)) => Some(flags),
+        Some(EncodingDatum::Array(_)) => return None,
+    };
+
+    Some(decode_and_compile_pattern(pattern, flags))
+}
+
+fn decode_and_compile_pattern(
+    pattern: &TypedValueScalar,
+    flags: Option<&TypedValueScalar>,
+) -> ThinResult<Regex> {
+    let pattern = DefaultTypedValueDecoder::decode_term(pattern)?;
+    let flags = flags
+        .map(DefaultTypedValueDecoder::decode_term)
+        .transpose()?;
+    compile_replace_pattern(pattern, flags)
+}
+
 fn evaluate_replace(
     arg0: TypedValueRef<'_>,
     arg1: TypedValueRef<'_>,
     arg2: TypedValueRef<'_>,
     arg3: Option<TypedValueRef<'_>>,
-) -> Result<Result<TypedValue, ThinError>, ThinError> {
+) -> ThinResult<TypedValue> {
+    let regex = compile_replace_pattern(arg1, arg3)?;
+    evaluate_replace_with_regex(arg0, &regex, arg2)
+}
+
+/// Compiles the pattern of `REPLACE`.
+///
+/// Contrary to `REGEX`, patterns that match the empty string (including the empty pattern) are
+/// an error (`err:FORX0003`), as they would lead to an infinite number of replacements.
+fn compile_replace_pattern(
+    pattern: TypedValueRef<'_>,
+    flags: Option<TypedValueRef<'_>>,
+) -> ThinResult<Regex> {
+    let pattern = SimpleLiteralRef::try_from(pattern)?;
+    let flags = flags.map(SimpleLiteralRef::try_from).transpose()?;
+
+    let regex = compile_pattern(pattern.value, flags.map(|lit| lit.value))?;
+    if regex.is_match("") {
+        return ThinError::expected();
+    }
+    Ok(regex)
+}
+
+fn evaluate_replace_with_regex(
+    arg0: TypedValueRef<'_>,
+    regex: &Regex,
+    arg2: TypedValueRef<'_>,
+) -> ThinResult<TypedValue> {
     let arg0 = StringLiteralRef::try_from(arg0)?;
-    let arg1 = SimpleLiteralRef::try_from(arg1)?;
     let arg2 = SimpleLiteralRef::try_from(arg2)?;
-    let arg3 = arg3.map(SimpleLiteralRef::try_from).transpose()?;
-
-    let regex = compile_pattern(arg1.value, arg3.map(|lit| lit.value))?;
 
-    let result = match regex.replace_all(arg0.0, arg2.value) {
+    let replacement = parse_replacement(arg2.value, regex.captures_len() - 1)?;
+    let result = match regex.replace_all(arg0.0, |captures: &Captures<'_>| {
+        replacement.expand(captures)
+    }) {
         Cow::Owned(replaced) => replaced,
         Cow::Borrowed(_) => arg0.0.to_owned(),
     };
 
-    Ok(Ok(match arg0.1 {
+    Ok(match arg0.1 {
         None => TypedValue::SimpleLiteral(SimpleLiteral { value: result }),
         Some(language) => TypedValue::LanguageStringLiteral(LanguageString {
             value: result,
             language: language.to_owned(),
         }),
-    }))
+    })
+}
+
+/// A part of a parsed replacement string.
+#[derive(Debug, PartialEq, Eq)]
+enum ReplacementPart {
+    /// A literal string that is copied into the result.
+    Literal(String),
+    /// A reference to a capturing group (`$N`).
+    Group(usize),
+}
+
+/// A parsed replacement string of `REPLACE`.
+#[derive(Debug, PartialEq, Eq)]
+struct Replacement(Vec<ReplacementPart>);
+
+impl Replacement {
+    /// Creates the replacement for a single match.
+    fn expand(&self, captures: &Captures<'_>) -> String {
+        let mut result = String::new();
+        for part in &self.0 {
+            match part {
+                ReplacementPart::Literal(literal) => result.push_str(literal),
+                ReplacementPart::Group(group) => {
+                    result.push_str(captures.get(*group).map_or("", |m| m.as_str()));
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Parses a replacement string according to the rules of `fn:replace`.
+///
+/// - `$N` refers to the N-th capturing group (`$0` refers to the entire match). If more digits
+///   follow, they are part of N as long as the group exists. A reference to a group that does
+///   not exist is an error.
+/// - `\$` and `\\` represent a literal `$` and `\`, respectively.
+/// - Any other use of `$` or `\` is an error (`err:FORX0004`).
+///
+/// # Relevant Resources
+/// - [XPath and XQuery Functions and Operators 3.1 - fn:replace](https://www.w3.org/TR/xpath-functions-31/#func-replace)
+fn parse_replacement(replacement: &str, group_count: usize) -> ThinResult<Replacement> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('\\' | '$')) => literal.push(c),
+                _ => return ThinError::expected(),
+            },
+            '$' => {
+                let Some(mut group) = chars
+                    .next()
+                    .and_then(|c| c.to_digit(10))
+                    .map(|digit| digit as usize)
+                else {
+                    return ThinError::expected();
+                };
+                if group > group_count {
+                    return ThinError::expected();
+                }
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    let candidate = group * 10 + digit as usize;
+                    if candidate > group_count {
+                        break;
+                    }
+                    group = candidate;
+                    chars.next();
+                }
+
+                if !literal.is_empty() {
+                    parts.push(ReplacementPart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(ReplacementPart::Group(group));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(ReplacementPart::Literal(literal));
+    }
+    Ok(Replacement(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::LanguageStringRef;
+
+    fn replace(
+        input: TypedValueRef<'_>,
+        pattern: &str,
+        replacement: &str,
+        flags: Option<&str>,
+    ) -> ThinResult<TypedValue> {
+        evaluate_replace(
+            input,
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef { value: pattern }),
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef { value: replacement }),
+            flags.map(|value| TypedValueRef::SimpleLiteral(SimpleLiteralRef { value })),
+        )
+    }
+
+    fn simple(value: &str) -> TypedValueRef<'_> {
+        TypedValueRef::SimpleLiteral(SimpleLiteralRef { value })
+    }
+
+    fn simple_result(value: &str) -> ThinResult<TypedValue> {
+        Ok(TypedValue::SimpleLiteral(SimpleLiteral {
+            value: value.to_owned(),
+        }))
+    }
+
+    #[test]
+    fn test_replace_with_backreferences() {
+        assert_eq!(
+            replace(simple("abcd"), "(b)(c)", "$2$1", None),
+            simple_result("acbd")
+        );
+        assert_eq!(
+            replace(simple("abcd"), "b", "[$0]", None),
+            simple_result("a[b]cd")
+        );
+    }
+
+    #[test]
+    fn test_replace_escapes_in_replacement() {
+        assert_eq!(
+            replace(simple("abc"), "b", "\\$\\\\", None),
+            simple_result("a$\\c")
+        );
+        assert!(replace(simple("abc"), "b", "$", None).is_err());
+        assert!(replace(simple("abc"), "b", "\\n", None).is_err());
+    }
+
+    #[test]
+    fn test_replace_non_existent_group_is_error() {
+        assert!(replace(simple("abc"), "(b)", "$2", None).is_err());
+    }
+
+    #[test]
+    fn test_replace_multi_digit_group_uses_longest_existing_group() {
+        // Only one group exists, so "$10" refers to group 1 followed by "0".
+        assert_eq!(
+            replace(simple("abc"), "(b)", "$10", None),
+            simple_result("ab0c")
+        );
+    }
+
+    #[test]
+    fn test_replace_pattern_matching_empty_string_is_error() {
+        assert!(replace(simple("abc"), "", "x", None).is_err());
+        assert!(replace(simple("abc"), "x*", "y", None).is_err());
+    }
+
+    #[test]
+    fn test_replace_flags() {
+        assert_eq!(
+            replace(simple("aBc"), "b", "x", Some("i")),
+            simple_result("axc")
+        );
+        assert_eq!(
+            replace(simple("a\nb"), "a.b", "x", Some("s")),
+            simple_result("x")
+        );
+        assert_eq!(
+            replace(simple("a\nb"), "^b", "x", Some("m")),
+            simple_result("a\nx")
+        );
+        assert_eq!(
+            replace(simple("a#b"), "a # b", "x", Some("x")),
+            simple_result("x")
+        );
+        assert_eq!(
+            replace(simple("a.b"), ".", "x", Some("q")),
+            simple_result("axb")
+        );
+        assert!(replace(simple("abc"), "b", "x", Some("z")).is_err());
+    }
+
+    #[test]
+    fn test_replace_keeps_language() {
+        let input = TypedValueRef::LanguageStringLiteral(LanguageStringRef {
+            value: "abc",
+            language: "en",
+        });
+        assert_eq!(
+            replace(input, "b", "x", None),
+            Ok(TypedValue::LanguageStringLiteral(LanguageString {
+                value: "axc".to_owned(),
+                language: "en".to_owned(),
+            }))
+        );
+    }
 }
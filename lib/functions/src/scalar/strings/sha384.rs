@@ -35,6 +35,7 @@ impl ScalarSparqlOp for Sha384SparqlOp {
     fn signature(&self) -> ScalarSparqlOpSignature {
         ScalarSparqlOpSignature::default_with_arity(SparqlOpArity::Fixed(1))
     }
+
     fn typed_value_encoding_op(
         &self,
         encodings: &RdfFusionEncodings,
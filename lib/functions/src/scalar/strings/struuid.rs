@@ -2,7 +2,7 @@ use crate::scalar::sparql_op_impl::{
     ScalarSparqlOpImpl, create_typed_value_sparql_op_impl,
 };
 use crate::scalar::{ScalarSparqlOp, ScalarSparqlOpSignature, SparqlOpArity};
-use datafusion::logical_expr::ColumnarValue;
+use datafusion::logical_expr::{ColumnarValue, Volatility};
 use rdf_fusion_encoding::typed_value::TypedValueEncoding;
 use rdf_fusion_encoding::{EncodingArray, RdfFusionEncodings, TermEncoder};
 use rdf_fusion_extensions::functions::BuiltinName;
@@ -34,7 +34,10 @@ impl ScalarSparqlOp for StrUuidSparqlOp {
     }
 
     fn signature(&self) -> ScalarSparqlOpSignature {
-        ScalarSparqlOpSignature::default_with_arity(SparqlOpArity::Nullary)
+        ScalarSparqlOpSignature {
+            volatility: Volatility::Volatile,
+            arity: SparqlOpArity::Nullary,
+        }
     }
 
     fn typed_value_encoding_op(
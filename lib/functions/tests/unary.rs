@@ -70,9 +70,9 @@ fn invoke_udf(
 
     let mut result = String::from("[\n");
     for term in terms {
-        result.push_str(&format!("  {},\n", term.unwrap().to_string()));
+        result.push_str(&format!("  {},\n", term.unwrap()));
     }
-    result.push_str("]");
+    result.push(']');
 
     result
 }
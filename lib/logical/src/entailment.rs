@@ -0,0 +1,485 @@
+//! Query-time entailment for basic graph patterns.
+//!
+//! Entailment is implemented by rewriting the triple patterns of a query such that they also match
+//! triples that are entailed by the data. No inferred triples are materialized in the storage.
+//! Therefore, updates to the class or property hierarchy are immediately visible to subsequent
+//! queries.
+
+use crate::join::SparqlJoinType;
+use crate::{
+    ActiveGraph, RdfFusionLogicalPlanBuilder, RdfFusionLogicalPlanBuilderContext,
+};
+use rdf_fusion_model::vocab::{rdf, rdfs};
+use rdf_fusion_model::{
+    DFResult, GroundTerm, NamedNode, NamedNodePattern, PropertyPathExpression,
+    TermPattern, TriplePattern, Variable,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The entailment regime that is used for matching basic graph patterns.
+///
+/// # Relevant Resources
+/// - [SPARQL 1.1 - Entailment Regimes](https://www.w3.org/TR/sparql11-entailment/)
+/// - [RDF 1.1 Semantics - RDFS Entailment](https://www.w3.org/TR/rdf11-mt/#rdfs-entailment)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EntailmentRegime {
+    /// Triple patterns only match triples that are stored explicitly.
+    #[default]
+    Simple,
+    /// Triple patterns also match triples that are entailed by the class and property hierarchy.
+    ///
+    /// This covers the transitivity of `rdfs:subClassOf` and `rdfs:subPropertyOf` (rules `rdfs5`
+    /// and `rdfs11`) and their effect on instances (rules `rdfs7` and `rdfs9`):
+    /// - `?x rdf:type :Animal` also matches instances of all (transitive) subclasses of `:Animal`.
+    /// - `?x :p ?y` also matches triples whose predicate is a (transitive) subproperty of `:p`.
+    ///
+    /// Patterns with a variable in the predicate position are not rewritten. The hierarchy is
+    /// looked up in the same active graph as the pattern itself. Other RDFS rules (e.g.,
+    /// `rdfs:domain` and `rdfs:range`) are not supported.
+    RdfsHierarchy,
+    /// Triple patterns treat resources that are linked via `owl:sameAs` as equal.
+    ///
+    /// The `owl:sameAs` statements are interpreted as a symmetric, reflexive, and transitive
+    /// relation that partitions the IRIs into [SameAsClasses]. Each binding of a subject or
+    /// object is expanded to all members of its class. For example, if `:a owl:sameAs :b` and
+    /// `:a :p :c` hold, then `:b :p ?x` matches `:c` and `?x :p :c` matches both `:a` and `:b`.
+    ///
+    /// The classes are computed from the `owl:sameAs` statements in the default graph of the
+    /// query before the query is planned. Only IRIs are considered. The predicate and graph name
+    /// positions are not expanded.
+    ///
+    /// # Performance
+    ///
+    /// Computing the classes requires scanning all `owl:sameAs` statements for each query. In
+    /// addition, the expansion multiplies the number of solutions by the size of the classes
+    /// involved and requires removing duplicates. As the plans depend on the data, they are not
+    /// stored in the query plan cache. Consider materializing the canonical resources if the
+    /// classes are large or queries are latency-sensitive.
+    OwlSameAs,
+}
+
+/// The equivalence classes of IRIs that are linked via `owl:sameAs`.
+///
+/// The classes are computed with a union-find data structure, which processes the statements in
+/// almost linear time. Resources that are not linked to any other resource are not stored.
+///
+/// ```
+/// use rdf_fusion_logical::entailment::SameAsClasses;
+/// use rdf_fusion_model::NamedNode;
+///
+/// let a = NamedNode::new_unchecked("http://example.com/a");
+/// let b = NamedNode::new_unchecked("http://example.com/b");
+/// let c = NamedNode::new_unchecked("http://example.com/c");
+///
+/// let classes = SameAsClasses::from_pairs([(a.clone(), b.clone()), (c.clone(), b.clone())]);
+/// assert_eq!(classes.class(&a), Some([a.clone(), b, c].as_slice()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SameAsClasses {
+    /// Maps each resource to the index of its class in `classes`.
+    class_ids: HashMap<NamedNode, usize>,
+    /// The members of each class, ordered by their IRI.
+    classes: Vec<Vec<NamedNode>>,
+}
+
+impl SameAsClasses {
+    /// Computes the equivalence classes from the given `owl:sameAs` statements.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (NamedNode, NamedNode)>) -> Self {
+        let mut nodes = Vec::new();
+        let mut ids = HashMap::new();
+        let mut union_find = UnionFind::default();
+        for (lhs, rhs) in pairs {
+            let lhs = *ids.entry(lhs).or_insert_with_key(|node: &NamedNode| {
+                nodes.push(node.clone());
+                union_find.add()
+            });
+            let rhs = *ids.entry(rhs).or_insert_with_key(|node: &NamedNode| {
+                nodes.push(node.clone());
+                union_find.add()
+            });
+            union_find.union(lhs, rhs);
+        }
+
+        let mut members = HashMap::<usize, Vec<NamedNode>>::new();
+        for (id, node) in nodes.into_iter().enumerate() {
+            members.entry(union_find.find(id)).or_default().push(node);
+        }
+
+        let mut classes = members
+            .into_values()
+            .filter(|class| class.len() > 1)
+            .collect::<Vec<_>>();
+        for class in &mut classes {
+            class.sort_unstable();
+        }
+        classes.sort_unstable();
+
+        let class_ids = classes
+            .iter()
+            .enumerate()
+            .flat_map(|(id, class)| class.iter().map(move |node| (node.clone(), id)))
+            .collect();
+        Self { class_ids, classes }
+    }
+
+    /// Returns whether no resource is linked to another resource.
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Returns the members of the class of `node` (including `node`), or [None] if `node` is not
+    /// linked to another resource.
+    pub fn class(&self, node: &NamedNode) -> Option<&[NamedNode]> {
+        self.class_ids
+            .get(node)
+            .map(|id| self.classes[*id].as_slice())
+    }
+
+    /// Returns an iterator over all classes with at least two members.
+    pub fn classes(&self) -> impl Iterator<Item = &[NamedNode]> {
+        self.classes.iter().map(Vec::as_slice)
+    }
+}
+
+/// A disjoint-set forest with path halving and union by size.
+#[derive(Default)]
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    /// Adds a new singleton set and returns its id.
+    fn add(&mut self) -> usize {
+        let id = self.parents.len();
+        self.parents.push(id);
+        self.sizes.push(1);
+        id
+    }
+
+    /// Returns the representative of the set that contains `id`.
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            self.parents[id] = self.parents[self.parents[id]];
+            id = self.parents[id];
+        }
+        id
+    }
+
+    /// Merges the sets that contain `lhs` and `rhs`.
+    fn union(&mut self, lhs: usize, rhs: usize) {
+        let (lhs, rhs) = (self.find(lhs), self.find(rhs));
+        if lhs == rhs {
+            return;
+        }
+
+        let (larger, smaller) = if self.sizes[lhs] >= self.sizes[rhs] {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+    }
+}
+
+/// Creates a plan for `pattern` that also matches the triples that are entailed by the class and
+/// property hierarchy. See [EntailmentRegime::RdfsHierarchy].
+///
+/// The resulting solutions are distinct, as an entailed triple is only part of the entailed graph
+/// once, even if it can be derived in multiple ways.
+pub(crate) fn create_rdfs_hierarchy_pattern(
+    context: &RdfFusionLogicalPlanBuilderContext,
+    active_graph: ActiveGraph,
+    graph_variable: Option<Variable>,
+    pattern: TriplePattern,
+) -> DFResult<RdfFusionLogicalPlanBuilder> {
+    let predicate = match &pattern.predicate {
+        NamedNodePattern::NamedNode(predicate) => predicate.clone(),
+        NamedNodePattern::Variable(_) => {
+            return Ok(context.create_pattern(active_graph, graph_variable, pattern));
+        }
+    };
+
+    if predicate == rdf::TYPE {
+        // ?x rdf:type/rdfs:subClassOf* ?class
+        let path = PropertyPathExpression::Sequence(
+            Box::new(PropertyPathExpression::NamedNode(predicate)),
+            Box::new(zero_or_more(rdfs::SUB_CLASS_OF.into_owned())),
+        );
+        let plan = context.create_property_path(
+            active_graph,
+            graph_variable,
+            path,
+            pattern.subject,
+            pattern.object,
+        );
+        return remove_duplicates(plan);
+    }
+
+    // ?x ?property ?y . ?property rdfs:subPropertyOf* <predicate>
+    let property = fresh_property_variable(&pattern);
+    let triples = context.create_pattern(
+        active_graph.clone(),
+        graph_variable.clone(),
+        TriplePattern {
+            subject: pattern.subject,
+            predicate: NamedNodePattern::Variable(property.clone()),
+            object: pattern.object,
+        },
+    );
+    let sub_properties = context.create_property_path(
+        active_graph,
+        graph_variable,
+        zero_or_more(rdfs::SUB_PROPERTY_OF.into_owned()),
+        TermPattern::Variable(property.clone()),
+        TermPattern::NamedNode(predicate),
+    );
+    let joined = triples.join(sub_properties.build()?, SparqlJoinType::Inner, None)?;
+
+    let variables = joined
+        .schema()
+        .fields()
+        .iter()
+        .filter(|field| field.name() != property.as_str())
+        .map(|field| Variable::new_unchecked(field.name()))
+        .collect::<Vec<_>>();
+    remove_duplicates(joined.project(&variables)?)
+}
+
+/// Creates a plan for `pattern` that treats the members of the `classes` as equal. See
+/// [EntailmentRegime::OwlSameAs].
+///
+/// Bound subjects and objects are replaced by their class, while the bindings of variables are
+/// expanded to the members of their class. The resulting solutions are distinct.
+pub(crate) fn create_same_as_pattern(
+    context: &RdfFusionLogicalPlanBuilderContext,
+    active_graph: ActiveGraph,
+    graph_variable: Option<Variable>,
+    pattern: TriplePattern,
+    classes: &SameAsClasses,
+) -> DFResult<RdfFusionLogicalPlanBuilder> {
+    if classes.is_empty() {
+        return Ok(context.create_pattern(active_graph, graph_variable, pattern));
+    }
+
+    let mut rewriter = SameAsRewriter::new(&pattern, graph_variable.as_ref(), classes);
+    let stored_pattern = TriplePattern {
+        subject: rewriter.rewrite_term(pattern.subject),
+        predicate: pattern.predicate,
+        object: rewriter.rewrite_term(pattern.object),
+    };
+
+    let mut plan = context.create_pattern(active_graph, graph_variable, stored_pattern);
+
+    // Restrict the replaced terms to the members of their class.
+    for (variable, class) in std::mem::take(&mut rewriter.constants) {
+        let bindings = class
+            .iter()
+            .map(|node| vec![Some(GroundTerm::NamedNode(node.clone()))])
+            .collect::<Vec<_>>();
+        let values = context.create_values(&[variable], &bindings)?;
+        plan = plan.join(values.build()?, SparqlJoinType::Inner, None)?;
+    }
+
+    // Expand the stored bindings to all members of their class. Bindings without a class are
+    // retained by the left join and the COALESCE.
+    if !rewriter.expansions.is_empty() {
+        let pairs = classes
+            .classes()
+            .flat_map(|class| {
+                class.iter().flat_map(move |lhs| {
+                    class.iter().map(move |rhs| {
+                        vec![
+                            Some(GroundTerm::NamedNode(lhs.clone())),
+                            Some(GroundTerm::NamedNode(rhs.clone())),
+                        ]
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (stored, variable) in std::mem::take(&mut rewriter.expansions) {
+            let alternative = rewriter.fresh_variable();
+            let values =
+                context.create_values(&[stored.clone(), alternative.clone()], &pairs)?;
+            plan = plan.join(values.build()?, SparqlJoinType::Left, None)?;
+
+            let expr_builder = plan.expr_builder_root();
+            let expansion = expr_builder
+                .coalesce(vec![
+                    expr_builder.variable(alternative.as_ref())?.build()?,
+                    expr_builder.variable(stored.as_ref())?.build()?,
+                ])?
+                .build()?;
+            plan = plan.extend(variable, expansion)?;
+        }
+    }
+
+    let variables = plan
+        .schema()
+        .fields()
+        .iter()
+        .filter(|field| !rewriter.fresh_variables.contains(field.name().as_str()))
+        .map(|field| Variable::new_unchecked(field.name()))
+        .collect::<Vec<_>>();
+    remove_duplicates(plan.project(&variables)?)
+}
+
+/// Replaces the subject and object of a triple pattern with fresh variables that bind the stored
+/// terms.
+struct SameAsRewriter<'classes> {
+    /// The classes of the resources.
+    classes: &'classes SameAsClasses,
+    /// Variables that must not be expanded, as they bind predicates or graph names.
+    unexpanded_variables: HashSet<Variable>,
+    /// All variables of the original pattern.
+    used_variables: HashSet<String>,
+    /// The fresh variables created by the rewriter.
+    fresh_variables: HashSet<String>,
+    /// The fresh variables that replace a bound term, together with the class of the term.
+    constants: Vec<(Variable, &'classes [NamedNode])>,
+    /// Pairs of a fresh variable that binds the stored term and the original variable.
+    expansions: Vec<(Variable, Variable)>,
+}
+
+impl<'classes> SameAsRewriter<'classes> {
+    fn new(
+        pattern: &TriplePattern,
+        graph_variable: Option<&Variable>,
+        classes: &'classes SameAsClasses,
+    ) -> Self {
+        let mut unexpanded_variables = HashSet::new();
+        if let NamedNodePattern::Variable(variable) = &pattern.predicate {
+            unexpanded_variables.insert(variable.clone());
+        }
+        if let Some(variable) = graph_variable {
+            unexpanded_variables.insert(variable.clone());
+        }
+
+        let used_variables = [&pattern.subject, &pattern.object]
+            .into_iter()
+            .filter_map(|term| match term {
+                TermPattern::Variable(variable) => Some(variable.as_str().to_owned()),
+                _ => None,
+            })
+            .chain(unexpanded_variables.iter().map(|v| v.as_str().to_owned()))
+            .collect();
+
+        Self {
+            classes,
+            unexpanded_variables,
+            used_variables,
+            fresh_variables: HashSet::new(),
+            constants: Vec::new(),
+            expansions: Vec::new(),
+        }
+    }
+
+    fn rewrite_term(&mut self, term: TermPattern) -> TermPattern {
+        match term {
+            TermPattern::NamedNode(node) => match self.classes.class(&node) {
+                Some(class) => {
+                    let variable = self.fresh_variable();
+                    self.constants.push((variable.clone(), class));
+                    TermPattern::Variable(variable)
+                }
+                None => TermPattern::NamedNode(node),
+            },
+            TermPattern::Variable(variable)
+                if !self.unexpanded_variables.contains(&variable) =>
+            {
+                // A variable that occurs twice binds the same stored term.
+                if let Some((stored, _)) =
+                    self.expansions.iter().find(|(_, v)| *v == variable)
+                {
+                    return TermPattern::Variable(stored.clone());
+                }
+
+                let stored = self.fresh_variable();
+                self.expansions.push((stored.clone(), variable));
+                TermPattern::Variable(stored)
+            }
+            term => term,
+        }
+    }
+
+    fn fresh_variable(&mut self) -> Variable {
+        let mut name = format!("_same_as_{}", self.fresh_variables.len());
+        while self.used_variables.contains(&name) {
+            name.push('_');
+        }
+        self.fresh_variables.insert(name.clone());
+        Variable::new_unchecked(name)
+    }
+}
+
+/// Creates the path `predicate*`.
+fn zero_or_more(predicate: NamedNode) -> PropertyPathExpression {
+    PropertyPathExpression::ZeroOrMore(Box::new(PropertyPathExpression::NamedNode(
+        predicate,
+    )))
+}
+
+/// Returns a variable that binds the sub-properties and does not clash with the variables of
+/// `pattern`.
+fn fresh_property_variable(pattern: &TriplePattern) -> Variable {
+    let is_used = |name: &str| {
+        [&pattern.subject, &pattern.object]
+            .into_iter()
+            .any(|term| matches!(term, TermPattern::Variable(v) if v.as_str() == name))
+    };
+
+    let mut name = String::from("_rdfs_sub_property");
+    while is_used(&name) {
+        name.push('_');
+    }
+    Variable::new_unchecked(name)
+}
+
+/// Removes duplicate solutions. If the plan has no columns, at most one solution is retained.
+fn remove_duplicates(
+    plan: RdfFusionLogicalPlanBuilder,
+) -> DFResult<RdfFusionLogicalPlanBuilder> {
+    if plan.schema().fields().is_empty() {
+        plan.slice(0, Some(1))
+    } else {
+        plan.distinct()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str) -> NamedNode {
+        NamedNode::new_unchecked(format!("http://example.com/{name}"))
+    }
+
+    #[test]
+    fn test_same_as_classes_are_transitive_and_symmetric() {
+        let classes = SameAsClasses::from_pairs([
+            (node("a"), node("b")),
+            (node("c"), node("d")),
+            (node("d"), node("b")),
+            (node("x"), node("y")),
+        ]);
+
+        let expected = [node("a"), node("b"), node("c"), node("d")];
+        assert_eq!(classes.class(&node("c")), Some(expected.as_slice()));
+        assert_eq!(classes.class(&node("a")), Some(expected.as_slice()));
+        assert_eq!(
+            classes.class(&node("y")),
+            Some([node("x"), node("y")].as_slice())
+        );
+        assert_eq!(classes.classes().count(), 2);
+    }
+
+    #[test]
+    fn test_same_as_classes_ignore_reflexive_statements() {
+        let classes = SameAsClasses::from_pairs([(node("a"), node("a"))]);
+
+        assert!(classes.is_empty());
+        assert_eq!(classes.class(&node("a")), None);
+    }
+}
@@ -0,0 +1,418 @@
+use crate::expr::unwrap_encoding_changes;
+use datafusion::common::tree_node::Transformed;
+use datafusion::logical_expr::utils::{conjunction, split_conjunction_owned};
+use datafusion::logical_expr::{BinaryExpr, Expr, Filter, LogicalPlan, Operator};
+use datafusion::optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use datafusion::scalar::ScalarValue;
+use rdf_fusion_extensions::functions::BuiltinName;
+use rdf_fusion_model::DFResult;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The selectivity of predicates that cannot be analyzed (e.g., `REGEX` or custom functions).
+pub const DEFAULT_SELECTIVITY: f64 = 0.5;
+/// The selectivity of an equality with a constant if the number of distinct values is unknown.
+pub const EQUALITY_SELECTIVITY: f64 = 0.1;
+/// The selectivity of a range comparison (e.g., `?x < 10`).
+pub const RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Estimates the selectivity of `FILTER` predicates, i.e., the fraction of rows that pass the
+/// filter.
+///
+/// The estimates are deliberately conservative. In other words, they rather overestimate the
+/// number of rows that pass a filter:
+/// - An equality with a constant passes `1 / distinct values` of the rows. If the number of
+///   distinct values of the column is unknown, [EQUALITY_SELECTIVITY] is used.
+/// - A range comparison passes [RANGE_SELECTIVITY] of the rows.
+/// - Other predicates (e.g., `REGEX`) pass [DEFAULT_SELECTIVITY] of the rows.
+/// - A conjunction passes at most as many rows as its most selective part, while a disjunction
+///   passes at most the sum of its parts. No independence of the parts is assumed.
+#[derive(Debug, Clone, Default)]
+pub struct FilterSelectivityEstimator {
+    /// The known number of distinct values per column.
+    distinct_values: HashMap<String, usize>,
+}
+
+impl FilterSelectivityEstimator {
+    /// Creates a new [FilterSelectivityEstimator] without any knowledge about the data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the number of distinct values of `column`. This refines the estimates of
+    /// equalities with this column.
+    #[must_use]
+    pub fn with_distinct_values(
+        mut self,
+        column: impl Into<String>,
+        count: usize,
+    ) -> Self {
+        self.distinct_values.insert(column.into(), count);
+        self
+    }
+
+    /// Estimates the fraction of rows that pass `predicate`. The result is in the range `[0, 1]`.
+    pub fn estimate(&self, predicate: &Expr) -> f64 {
+        let selectivity = match predicate {
+            Expr::Literal(ScalarValue::Boolean(Some(value)), _) => {
+                if *value {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Expr::Literal(ScalarValue::Boolean(None), _) => 0.0,
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And => self.estimate(left).min(self.estimate(right)),
+                Operator::Or => self.estimate(left) + self.estimate(right),
+                Operator::Eq => self.estimate_equality(left, right),
+                Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => {
+                    RANGE_SELECTIVITY
+                }
+                _ => DEFAULT_SELECTIVITY,
+            },
+            Expr::Not(inner) => (1.0 - self.estimate(inner)).max(DEFAULT_SELECTIVITY),
+            Expr::ScalarFunction(function) => {
+                match BuiltinName::try_from(function.func.name()) {
+                    // Both functions only convert the value of the inner predicate.
+                    Ok(
+                        BuiltinName::EffectiveBooleanValue
+                        | BuiltinName::NativeBooleanAsTerm,
+                    ) if function.args.len() == 1 => self.estimate(&function.args[0]),
+                    Ok(BuiltinName::Equal) if function.args.len() == 2 => {
+                        self.estimate_equality(&function.args[0], &function.args[1])
+                    }
+                    Ok(
+                        BuiltinName::LessThan
+                        | BuiltinName::LessOrEqual
+                        | BuiltinName::GreaterThan
+                        | BuiltinName::GreaterOrEqual,
+                    ) => RANGE_SELECTIVITY,
+                    _ => DEFAULT_SELECTIVITY,
+                }
+            }
+            _ => DEFAULT_SELECTIVITY,
+        };
+        selectivity.clamp(0.0, 1.0)
+    }
+
+    /// Estimates the selectivity of an equality between `lhs` and `rhs`.
+    fn estimate_equality(&self, lhs: &Expr, rhs: &Expr) -> f64 {
+        let column = match (unwrap_encoding_changes(lhs), unwrap_encoding_changes(rhs)) {
+            (Expr::Column(_), Expr::Column(_)) => return DEFAULT_SELECTIVITY,
+            (Expr::Column(column), _) | (_, Expr::Column(column)) => column,
+            _ => return DEFAULT_SELECTIVITY,
+        };
+
+        match self.distinct_values.get(column.name()) {
+            Some(count) if *count > 0 => 1.0 / *count as f64,
+            _ => EQUALITY_SELECTIVITY,
+        }
+    }
+
+    /// Orders the conjuncts of `predicate` such that the most selective conjuncts come first.
+    /// Conjuncts with the same selectivity retain their relative order.
+    ///
+    /// As the conjuncts are evaluated in order, this reduces the number of rows for which the
+    /// remaining conjuncts must be evaluated.
+    pub fn order_conjuncts(&self, predicate: Expr) -> Expr {
+        let mut conjuncts =
+            split_conjunction_owned(flatten_boolean_round_trips(predicate))
+                .into_iter()
+                .map(|conjunct| (self.estimate(&conjunct), conjunct))
+                .collect::<Vec<_>>();
+        conjuncts.sort_by(|(lhs, _), (rhs, _)| {
+            lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal)
+        });
+        conjunction(conjuncts.into_iter().map(|(_, conjunct)| conjunct))
+            .expect("There is at least one conjunct")
+    }
+}
+
+/// An optimizer rule that orders the conjuncts of filters by their estimated selectivity (see
+/// [FilterSelectivityEstimator::order_conjuncts]).
+#[derive(Debug, Default)]
+pub struct OrderFilterPredicatesRule {
+    estimator: FilterSelectivityEstimator,
+}
+
+impl OrderFilterPredicatesRule {
+    /// Creates a new [OrderFilterPredicatesRule].
+    pub fn new(estimator: FilterSelectivityEstimator) -> Self {
+        Self { estimator }
+    }
+}
+
+impl OptimizerRule for OrderFilterPredicatesRule {
+    fn name(&self) -> &str {
+        "order-filter-predicates"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Filter(filter) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let predicate = self.estimator.order_conjuncts(filter.predicate.clone());
+        if predicate == filter.predicate {
+            return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+        }
+
+        let filter = Filter::try_new(predicate, filter.input)?;
+        Ok(Transformed::yes(LogicalPlan::Filter(filter)))
+    }
+}
+
+/// Replaces `EBV(BOOLEAN_AS_TERM(X))` with `X` along a chain of conjunctions, such that SPARQL
+/// conjunctions (`A && B && C`) can be split into their conjuncts.
+fn flatten_boolean_round_trips(expr: Expr) -> Expr {
+    if let Some(inner) = as_boolean_round_trip(&expr) {
+        return flatten_boolean_round_trips(inner.clone());
+    }
+
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => flatten_boolean_round_trips(*left).and(flatten_boolean_round_trips(*right)),
+        expr => expr,
+    }
+}
+
+/// Returns `X` if `expr` is `EBV(BOOLEAN_AS_TERM(X))`.
+fn as_boolean_round_trip(expr: &Expr) -> Option<&Expr> {
+    let Expr::ScalarFunction(ebv) = expr else {
+        return None;
+    };
+    if BuiltinName::try_from(ebv.func.name()).ok()? != BuiltinName::EffectiveBooleanValue
+    {
+        return None;
+    }
+
+    let Expr::ScalarFunction(boolean_as_term) = ebv.args.first()? else {
+        return None;
+    };
+    if BuiltinName::try_from(boolean_as_term.func.name()).ok()?
+        != BuiltinName::NativeBooleanAsTerm
+    {
+        return None;
+    }
+    boolean_as_term.args.first()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RdfFusionExprBuilderContext;
+    use datafusion::arrow::datatypes::{Field, Schema};
+    use datafusion::common::{DFSchema, DFSchemaRef};
+    use datafusion::logical_expr::{EmptyRelation, LogicalPlanBuilder};
+    use datafusion::optimizer::OptimizerContext;
+    use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+    use rdf_fusion_encoding::sortable_term::SORTABLE_TERM_ENCODING;
+    use rdf_fusion_encoding::typed_value::TypedValueEncoding;
+    use rdf_fusion_encoding::{QuadStorageEncoding, RdfFusionEncodings, TermEncoding};
+    use rdf_fusion_extensions::RdfFusionContextView;
+    use rdf_fusion_functions::registry::DefaultRdfFusionFunctionRegistry;
+    use rdf_fusion_model::{Literal, VariableRef};
+    use std::ops::Not;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_equality_before_range() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let equality = equal(&builder, "column1", 5);
+        let range = less_than(&builder, "column2", 5);
+        let estimator = FilterSelectivityEstimator::new();
+
+        assert_eq!(estimator.estimate(&equality), EQUALITY_SELECTIVITY);
+        assert_eq!(estimator.estimate(&range), RANGE_SELECTIVITY);
+        assert_eq!(
+            estimator.order_conjuncts(range.clone().and(equality.clone())),
+            equality.and(range)
+        );
+    }
+
+    #[test]
+    fn test_range_before_regex() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let regex = builder
+            .variable(VariableRef::new_unchecked("column1"))
+            .unwrap()
+            .regex(literal(&builder, "^a"))
+            .unwrap()
+            .build_effective_boolean_value()
+            .unwrap();
+        let range = less_than(&builder, "column2", 5);
+        let estimator = FilterSelectivityEstimator::new();
+
+        assert_eq!(estimator.estimate(&regex), DEFAULT_SELECTIVITY);
+        assert_eq!(
+            estimator.order_conjuncts(regex.clone().and(range.clone())),
+            range.and(regex)
+        );
+    }
+
+    #[test]
+    fn test_equality_uses_distinct_values() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let few_values = equal(&builder, "column1", 5);
+        let many_values = equal(&builder, "column2", 5);
+        let estimator = FilterSelectivityEstimator::new()
+            .with_distinct_values("column1", 2)
+            .with_distinct_values("column2", 1000);
+
+        assert_eq!(estimator.estimate(&few_values), 0.5);
+        assert_eq!(estimator.estimate(&many_values), 0.001);
+        assert_eq!(
+            estimator.order_conjuncts(few_values.clone().and(many_values.clone())),
+            many_values.and(few_values)
+        );
+    }
+
+    #[test]
+    fn test_same_selectivity_retains_order() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let range_1 = less_than(&builder, "column1", 5);
+        let range_2 = less_than(&builder, "column2", 5);
+        let predicate = range_2.and(range_1);
+
+        assert_eq!(
+            FilterSelectivityEstimator::new().order_conjuncts(predicate.clone()),
+            predicate
+        );
+    }
+
+    #[test]
+    fn test_combined_predicates_are_conservative() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let equality = equal(&builder, "column1", 5);
+        let range = less_than(&builder, "column2", 5);
+        let estimator = FilterSelectivityEstimator::new();
+
+        assert_eq!(
+            estimator.estimate(&equality.clone().and(range.clone())),
+            EQUALITY_SELECTIVITY
+        );
+        assert_eq!(
+            estimator.estimate(&equality.or(range.clone())),
+            EQUALITY_SELECTIVITY + RANGE_SELECTIVITY
+        );
+        assert_eq!(estimator.estimate(&range.not()), 1.0 - RANGE_SELECTIVITY);
+    }
+
+    #[test]
+    fn test_rule_splits_sparql_conjunction() {
+        let context = create_context();
+        let schema = make_schema();
+        let builder = RdfFusionExprBuilderContext::new(&context, &schema);
+
+        let range = less_than(&builder, "column2", 5);
+        let equality = equal(&builder, "column1", 5);
+        let sparql_conjunction = builder
+            .and(range.clone(), equality.clone())
+            .unwrap()
+            .build_effective_boolean_value()
+            .unwrap();
+
+        let plan = LogicalPlanBuilder::new(LogicalPlan::EmptyRelation(EmptyRelation {
+            produce_one_row: false,
+            schema: Arc::clone(&schema),
+        }))
+        .filter(sparql_conjunction)
+        .unwrap()
+        .build()
+        .unwrap();
+        let rewritten = OrderFilterPredicatesRule::default()
+            .rewrite(plan, &OptimizerContext::new())
+            .unwrap();
+
+        assert!(rewritten.transformed);
+        let LogicalPlan::Filter(filter) = rewritten.data else {
+            panic!("Expected a filter");
+        };
+        assert_eq!(filter.predicate, equality.and(range));
+    }
+
+    fn equal(
+        builder: &RdfFusionExprBuilderContext<'_>,
+        column: &str,
+        value: i64,
+    ) -> Expr {
+        builder
+            .variable(VariableRef::new_unchecked(column))
+            .unwrap()
+            .equal(literal(builder, value))
+            .unwrap()
+            .build_effective_boolean_value()
+            .unwrap()
+    }
+
+    fn less_than(
+        builder: &RdfFusionExprBuilderContext<'_>,
+        column: &str,
+        value: i64,
+    ) -> Expr {
+        builder
+            .variable(VariableRef::new_unchecked(column))
+            .unwrap()
+            .less_than(literal(builder, value))
+            .unwrap()
+            .build_effective_boolean_value()
+            .unwrap()
+    }
+
+    fn literal(
+        builder: &RdfFusionExprBuilderContext<'_>,
+        value: impl Into<Literal>,
+    ) -> Expr {
+        let literal = value.into();
+        builder.literal(literal.as_ref()).unwrap().build().unwrap()
+    }
+
+    fn create_context() -> RdfFusionContextView {
+        let encodings = RdfFusionEncodings::new(
+            Arc::clone(&PLAIN_TERM_ENCODING),
+            Arc::new(TypedValueEncoding::new()),
+            None,
+            Arc::clone(&SORTABLE_TERM_ENCODING),
+        );
+        let registry = Arc::new(DefaultRdfFusionFunctionRegistry::new(encodings.clone()));
+        RdfFusionContextView::new(registry, encodings, QuadStorageEncoding::PlainTerm)
+    }
+
+    fn make_schema() -> DFSchemaRef {
+        let data_type = PLAIN_TERM_ENCODING.data_type().clone();
+        DFSchemaRef::new(
+            DFSchema::try_from(Schema::new(vec![
+                Field::new("column1", data_type.clone(), true),
+                Field::new("column2", data_type, true),
+            ]))
+            .unwrap(),
+        )
+    }
+}
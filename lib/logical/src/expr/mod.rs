@@ -1,8 +1,10 @@
 mod expression_simplifier;
-mod scalars;
+mod filter_selectivity;
+pub(crate) mod scalars;
 
 use datafusion::logical_expr::Expr;
 pub use expression_simplifier::*;
+pub use filter_selectivity::*;
 use rdf_fusion_extensions::functions::BuiltinName;
 
 /// Returns an inner expression by unwrapping all encoding changes.
@@ -2,13 +2,13 @@ use crate::RdfFusionExprBuilderContext;
 use datafusion::common::{plan_datafusion_err, plan_err};
 use datafusion::functions_aggregate::count::{count, count_distinct};
 use datafusion::functions_aggregate::first_last::first_value;
-use datafusion::logical_expr::{Expr, ExprSchemable, lit};
+use datafusion::logical_expr::{Expr, ExprSchemable, lit, when};
 use rdf_fusion_encoding::plain_term::{PLAIN_TERM_ENCODING, PlainTermScalar};
 use rdf_fusion_encoding::{EncodingName, EncodingScalar, TermEncoder};
 use rdf_fusion_extensions::functions::{BuiltinName, FunctionName};
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::{
-    Iri, LiteralRef, SimpleLiteralRef, TermRef, ThinError, TypedValueRef,
+    Iri, LiteralRef, NamedNode, SimpleLiteralRef, TermRef, ThinError, TypedValueRef,
 };
 use std::collections::HashSet;
 use std::ops::Not;
@@ -63,19 +63,48 @@ impl<'root> RdfFusionExprBuilder<'root> {
     /// Returns an expression that evaluates to either the value of `if_true´ or `if_false`
     /// depending on the effective boolean value of the inner expression.
     ///
+    /// The expression is lowered to a `CASE` expression. As a result, each branch is only
+    /// evaluated for the rows in which it is selected. Errors in the branch that is not taken
+    /// have no effect. If the effective boolean value of the inner expression is an error, the
+    /// result is an error.
+    ///
     /// # Relevant Resources
     /// - [SPARQL 1.1 IF](https://www.w3.org/TR/sparql11-query/#func-if)
     pub fn sparql_if(self, if_true: Expr, if_false: Expr) -> DFResult<Self> {
-        self.apply_builtin(BuiltinName::If, vec![if_true, if_false])
+        let context = self.context;
+        let test = self.build_effective_boolean_value()?;
+        let if_true = context
+            .try_create_builder(if_true)?
+            .with_encoding(EncodingName::TypedValue)?
+            .build()?;
+        let if_false = context
+            .try_create_builder(if_false)?
+            .with_encoding(EncodingName::TypedValue)?
+            .build()?;
+
+        // Rows without a WHEN match (i.e., an erroneous test) evaluate to an error. The error
+        // must be encoded explicitly, as the SQL null of a union is not a valid typed value.
+        let error = context
+            .encodings()
+            .typed_value()
+            .encode_term(ThinError::expected())?;
+        let expr = when(test.clone(), if_true)
+            .when(test.not(), if_false)
+            .otherwise(lit(error.into_scalar_value()))?;
+        context.try_create_builder(expr)
     }
 
     /// Creates a new expression that evaluates to the first argument that does not produce an
-    /// error.
+    /// error. The inner expression is the first argument.
+    ///
+    /// See [RdfFusionExprBuilderContext::coalesce] for details on the evaluation.
     ///
     /// # Relevant Resources
     /// - [SPARQL 1.1 - Coalesce](https://www.w3.org/TR/sparql11-query/#func-coalesce)
     pub fn coalesce(self, args: Vec<Expr>) -> DFResult<Self> {
-        self.apply_builtin(BuiltinName::Coalesce, args)
+        let mut all_args = vec![self.expr];
+        all_args.extend(args);
+        self.context.coalesce(all_args)
     }
 
     /// Creates an expression that checks for RDF term equality.
@@ -461,6 +490,16 @@ impl<'root> RdfFusionExprBuilder<'root> {
         self.apply_builtin(BuiltinName::Tz, vec![])
     }
 
+    /// Creates a new expression that adjusts the inner date/time expression to the timezone
+    /// given by the `xsd:dayTimeDuration` `timezone`. If `timezone` is unbound, the timezone is
+    /// removed.
+    ///
+    /// # Relevant Resources
+    /// - [SPARQL 1.2 - ADJUST](https://www.w3.org/TR/sparql12-query/#func-adjust)
+    pub fn adjust(self, timezone: Expr) -> DFResult<Self> {
+        self.apply_builtin(BuiltinName::Adjust, vec![timezone])
+    }
+
     //
     // Hash Functions
     //
@@ -688,6 +727,10 @@ impl<'root> RdfFusionExprBuilder<'root> {
 
     /// Creates an expression for the logical not operator.
     ///
+    /// Errors of the inner expression are propagated (i.e., the negation of an error is an error).
+    /// As SPARQL's `!=` is rewritten to `!(a = b)`, this ensures that `!=` raises a type error
+    /// whenever `=` does.
+    ///
     /// # Relevant Resources
     /// - [SPARQL 1.1 - Operator Mappings](https://www.w3.org/TR/sparql11-query/#OperatorMapping)
     #[allow(clippy::should_implement_trait)]
@@ -764,6 +807,8 @@ impl<'root> RdfFusionExprBuilder<'root> {
 
     /// Creates a new aggregate expression that computes the sum of the inner expression.
     ///
+    /// If `distinct` is true, only distinct values are considered.
+    ///
     /// # Relevant Resources
     /// - [SPARQL 1.1 - Sum](https://www.w3.org/TR/sparql11-query/#defn_aggSum)
     pub fn sum(self, distinct: bool) -> DFResult<Self> {
@@ -798,6 +843,18 @@ impl<'root> RdfFusionExprBuilder<'root> {
         )
     }
 
+    /// Creates a new aggregate expression that applies the custom aggregate function `name` to
+    /// the inner expression. The aggregate function must be registered in the function registry.
+    ///
+    /// If `distinct` is true, only distinct values are considered.
+    pub fn custom_aggregate(self, name: &NamedNode, distinct: bool) -> DFResult<Self> {
+        self.context.apply_udaf(
+            &FunctionName::Custom(name.clone()),
+            vec![self.expr],
+            distinct,
+        )
+    }
+
     //
     // Encodings
     //
@@ -8,7 +8,7 @@ use datafusion::logical_expr::expr::AggregateFunction;
 use datafusion::logical_expr::utils::COUNT_STAR_EXPANSION;
 use datafusion::logical_expr::{
     Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, ScalarUDF, Subquery, and,
-    exists, lit, not_exists,
+    exists, lit, not_exists, when,
 };
 use rdf_fusion_encoding::plain_term::encoders::DefaultPlainTermEncoder;
 use rdf_fusion_encoding::{
@@ -18,6 +18,7 @@ use rdf_fusion_extensions::RdfFusionContextView;
 use rdf_fusion_extensions::functions::{
     BuiltinName, FunctionName, RdfFusionFunctionRegistry,
 };
+use rdf_fusion_functions::aggregates::distinct_aggregate;
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::{TermRef, ThinError, VariableRef};
 use std::collections::HashSet;
@@ -104,10 +105,40 @@ impl<'context> RdfFusionExprBuilderContext<'context> {
     /// Creates a new expression that evaluates to the first argument that does not produce an
     /// error.
     ///
+    /// The expression is lowered to a `CASE` expression that checks the arguments in order. As a
+    /// result, an argument is only evaluated for the rows that have not been resolved by a
+    /// previous argument. Errors in later arguments have no effect on these rows. All arguments
+    /// are first converted to a common encoding.
+    ///
     /// # Relevant Resources
     /// - [SPARQL 1.1 - Coalesce](https://www.w3.org/TR/sparql11-query/#func-coalesce)
     pub fn coalesce(&self, args: Vec<Expr>) -> DFResult<RdfFusionExprBuilder<'context>> {
-        self.apply_builtin(BuiltinName::Coalesce, args)
+        let supported_encodings = self
+            .registry()
+            .udf_supported_encodings(&FunctionName::Builtin(BuiltinName::Coalesce))?;
+        let encodings = self.get_encodings(&args)?;
+        let encoding = decide_input_encoding(&supported_encodings, &encodings)?;
+        let mut args = args
+            .into_iter()
+            .map(|expr| {
+                self.try_create_builder(expr)?
+                    .with_encoding(encoding)?
+                    .build()
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+
+        // Errors and unbound values are encoded as nulls in all encodings.
+        let Some(otherwise) = args.pop() else {
+            return self.apply_builtin(BuiltinName::Coalesce, args);
+        };
+        let Some((first, rest)) = args.split_first() else {
+            return self.try_create_builder(otherwise);
+        };
+        let mut case = when(first.clone().is_not_null(), first.clone());
+        for arg in rest {
+            case = case.when(arg.clone().is_not_null(), arg.clone());
+        }
+        self.try_create_builder(case.otherwise(otherwise)?)
     }
 
     /// Creates an expression that generates a new blank node.
@@ -444,10 +475,30 @@ impl<'context> RdfFusionExprBuilderContext<'context> {
         args: Vec<Expr>,
         distinct: bool,
     ) -> DFResult<RdfFusionExprBuilder<'context>> {
-        let udaf = self.registry().udaf(&FunctionName::Builtin(name))?;
+        self.apply_udaf(&FunctionName::Builtin(name), args, distinct)
+    }
+
+    /// Applies the aggregate function `name` from the registry to a list of arguments.
+    pub(crate) fn apply_udaf(
+        &self,
+        name: &FunctionName,
+        args: Vec<Expr>,
+        distinct: bool,
+    ) -> DFResult<RdfFusionExprBuilder<'context>> {
+        let udaf = self.registry().udaf(name)?;
+
+        // DISTINCT compares RDF terms and not typed values (e.g., "01" and "1" are distinct).
+        let distinct_key = match args.first() {
+            Some(arg) if distinct => Some(
+                self.try_create_builder(arg.clone())?
+                    .with_encoding(EncodingName::PlainTerm)?
+                    .build()?,
+            ),
+            _ => None,
+        };
 
         // Currently, UDAFs are only supported for typed values
-        let args = args
+        let mut args = args
             .into_iter()
             .map(|e| {
                 self.try_create_builder(e)?
@@ -456,10 +507,20 @@ impl<'context> RdfFusionExprBuilderContext<'context> {
             })
             .collect::<DFResult<Vec<_>>>()?;
 
+        // DataFusion leaves DISTINCT to the aggregate implementation. Hence, we remove the
+        // duplicates in a wrapper before they reach the accumulator of the built-in.
+        let udaf = match distinct_key {
+            Some(distinct_key) => {
+                args.push(distinct_key);
+                Arc::new(distinct_aggregate(udaf))
+            }
+            None => udaf,
+        };
+
         let expr = Expr::AggregateFunction(AggregateFunction::new_udf(
             udaf,
             args,
-            distinct,
+            false,
             None,
             Vec::new(),
             None,
@@ -16,6 +16,7 @@
 extern crate core;
 
 mod active_graph;
+pub mod entailment;
 pub mod expr;
 mod expr_builder;
 mod expr_builder_context;
@@ -27,6 +28,8 @@ pub mod minus;
 pub mod paths;
 pub mod patterns;
 pub mod quad_pattern;
+pub mod service;
+pub mod union;
 
 pub use active_graph::{ActiveGraph, EnumeratedActiveGraph};
 use datafusion::common::{DFSchema, plan_err};
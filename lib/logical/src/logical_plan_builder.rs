@@ -202,7 +202,49 @@ impl RdfFusionLogicalPlanBuilder {
         })
     }
 
+    /// Sorts the solutions by the encoded terms of all columns.
+    ///
+    /// Columns in the [EncodingName::ObjectId] encoding are sorted by their object id, all other
+    /// columns by their plain term. Contrary to [Self::order_by], the resulting order has no
+    /// meaning in SPARQL. However, it is stable across executions on the same data, which is
+    /// useful for comparing query results (e.g., in snapshot tests).
+    pub fn order_by_encoded_terms(self) -> DFResult<RdfFusionLogicalPlanBuilder> {
+        let exprs = self
+            .schema()
+            .columns()
+            .into_iter()
+            .map(|c| {
+                let expr = self
+                    .expr_builder(col(c))?
+                    .with_any_encoding(&[
+                        EncodingName::ObjectId,
+                        EncodingName::PlainTerm,
+                    ])?
+                    .build()?;
+                Ok(SortExpr::new(expr, true, true))
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        if exprs.is_empty() {
+            return Ok(self);
+        }
+
+        let context = self.context.clone();
+        let plan = LogicalPlan::Sort(Sort {
+            input: Arc::new(self.build()?),
+            expr: exprs,
+            fetch: None,
+        });
+
+        Ok(Self {
+            context,
+            plan_builder: LogicalPlanBuilder::new(plan),
+        })
+    }
+
     /// Ensure that the [EncodingName::Sortable] is used.
+    ///
+    /// The sortable encoding provides a total order across all RDF terms. This is different from
+    /// the SPARQL comparison operators, which raise an error for incomparable values.
     fn ensure_sortable(&self, e: &SortExpr) -> DFResult<SortExpr> {
         let expr = self
             .expr_builder(e.expr.clone())?
@@ -224,9 +266,12 @@ impl RdfFusionLogicalPlanBuilder {
 
     /// Subtracts the results of another plan from the current plan.
     pub fn minus(self, rhs: LogicalPlan) -> DFResult<RdfFusionLogicalPlanBuilder> {
-        let minus_node = MinusNode::new(self.plan_builder.build()?, rhs);
+        let context = self.context.clone();
+
+        let (lhs, rhs) = self.align_encodings_of_common_columns(rhs)?;
+        let minus_node = MinusNode::new(lhs.plan_builder.build()?, rhs);
         Ok(Self {
-            context: self.context,
+            context,
             plan_builder: create_extension_plan(minus_node),
         })
     }
@@ -252,6 +297,29 @@ impl RdfFusionLogicalPlanBuilder {
         })
     }
 
+    /// Groups the current plan by a set of computed keys and applies aggregate expressions.
+    ///
+    /// Each key is bound to its variable with an [ExtendNode] before the aggregation. As a result,
+    /// the variables of the keys can be used like grouped variables (e.g., in the projection of
+    /// a `SELECT` clause).
+    ///
+    /// # Relevant Resources
+    /// - [SPARQL 1.1 - Grouping](https://www.w3.org/TR/sparql11-query/#groupby)
+    pub fn group_by_expressions(
+        self,
+        keys: &[(Variable, Expr)],
+        aggregates: &[(Variable, Expr)],
+    ) -> DFResult<RdfFusionLogicalPlanBuilder> {
+        let variables = keys
+            .iter()
+            .map(|(variable, _)| variable.clone())
+            .collect::<Vec<_>>();
+        let extended = keys.iter().try_fold(self, |builder, (variable, expr)| {
+            builder.extend(variable.clone(), expr.clone())
+        })?;
+        extended.group(&variables, aggregates)
+    }
+
     /// Creates an [Expr] that ensures that the grouped values uses an [EncodingName::PlainTerm]
     /// encoding.
     fn create_group_expr(&self, v: &Variable) -> DFResult<Expr> {
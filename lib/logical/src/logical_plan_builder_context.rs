@@ -1,7 +1,12 @@
 use crate::active_graph::ActiveGraph;
+use crate::entailment::{
+    EntailmentRegime, SameAsClasses, create_rdfs_hierarchy_pattern,
+    create_same_as_pattern,
+};
 use crate::join::SparqlJoinType;
-use crate::paths::PropertyPathNode;
+use crate::paths::{PathDepthLimit, PathRepetitions, PropertyPathNode};
 use crate::quad_pattern::QuadPatternNode;
+use crate::service::ServiceNode;
 use crate::{RdfFusionExprBuilderContext, RdfFusionLogicalPlanBuilder};
 use datafusion::arrow::datatypes::{Field, Fields};
 use datafusion::common::{DFSchema, DataFusionError};
@@ -21,8 +26,8 @@ use rdf_fusion_extensions::functions::RdfFusionFunctionRegistryRef;
 use rdf_fusion_model::quads::{COL_GRAPH, COL_OBJECT, COL_PREDICATE, COL_SUBJECT};
 use rdf_fusion_model::{DFResult, NamedOrBlankNode};
 use rdf_fusion_model::{
-    GroundTerm, NamedNode, NamedNodePattern, PropertyPathExpression, Term, TermPattern,
-    TermRef, ThinError, TriplePattern, Variable,
+    GraphPattern, GroundTerm, NamedNode, NamedNodePattern, PropertyPathExpression, Term,
+    TermPattern, TermRef, ThinError, TriplePattern, Variable,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -32,12 +37,82 @@ use std::sync::Arc;
 pub struct RdfFusionLogicalPlanBuilderContext {
     /// The RDF Fusion configuration.
     rdf_fusion_context: RdfFusionContextView,
+    /// The entailment regime used for basic graph patterns.
+    entailment: EntailmentRegime,
+    /// The `owl:sameAs` classes used by [EntailmentRegime::OwlSameAs].
+    same_as_classes: Arc<SameAsClasses>,
+    /// The maximum depth of the transitive closures in property paths.
+    path_depth_limit: Option<PathDepthLimit>,
+    /// The repetitions of the property paths in the query.
+    path_repetitions: PathRepetitions,
 }
 
 impl RdfFusionLogicalPlanBuilderContext {
     /// Creates a new [RdfFusionLogicalPlanBuilder].
     pub fn new(rdf_fusion_context: RdfFusionContextView) -> Self {
-        Self { rdf_fusion_context }
+        Self {
+            rdf_fusion_context,
+            entailment: EntailmentRegime::default(),
+            same_as_classes: Arc::new(SameAsClasses::default()),
+            path_depth_limit: None,
+            path_repetitions: PathRepetitions::default(),
+        }
+    }
+
+    /// Returns a new context that uses `entailment` for basic graph patterns created with
+    /// [Self::create_bgp].
+    ///
+    /// Other methods (e.g., [Self::create_pattern]) always match the stored triples.
+    #[must_use]
+    pub fn with_entailment(mut self, entailment: EntailmentRegime) -> Self {
+        self.entailment = entailment;
+        self
+    }
+
+    /// Returns the [EntailmentRegime] used for basic graph patterns.
+    pub fn entailment(&self) -> EntailmentRegime {
+        self.entailment
+    }
+
+    /// Returns a new context that bounds the transitive closures of property paths created with
+    /// [Self::create_property_path] by `path_depth_limit`.
+    #[must_use]
+    pub fn with_path_depth_limit(
+        mut self,
+        path_depth_limit: Option<PathDepthLimit>,
+    ) -> Self {
+        self.path_depth_limit = path_depth_limit;
+        self
+    }
+
+    /// Returns the maximum depth of the transitive closures in property paths.
+    pub fn path_depth_limit(&self) -> Option<PathDepthLimit> {
+        self.path_depth_limit
+    }
+
+    /// Returns a new context that interprets the markers in `path_repetitions` as repetitions in
+    /// property paths created with [Self::create_property_path].
+    #[must_use]
+    pub fn with_path_repetitions(mut self, path_repetitions: PathRepetitions) -> Self {
+        self.path_repetitions = path_repetitions;
+        self
+    }
+
+    /// Returns the repetitions of the property paths in the query.
+    pub fn path_repetitions(&self) -> &PathRepetitions {
+        &self.path_repetitions
+    }
+
+    /// Returns a new context that uses `classes` for [EntailmentRegime::OwlSameAs].
+    #[must_use]
+    pub fn with_same_as_classes(mut self, classes: Arc<SameAsClasses>) -> Self {
+        self.same_as_classes = classes;
+        self
+    }
+
+    /// Returns the `owl:sameAs` classes used by [EntailmentRegime::OwlSameAs].
+    pub fn same_as_classes(&self) -> &SameAsClasses {
+        &self.same_as_classes
     }
 
     /// Returns a reference to the [RdfFusionFunctionRegistry](rdf_fusion_functions::registry::DefaultRdfFusionFunctionRegistry)
@@ -228,6 +303,8 @@ impl RdfFusionLogicalPlanBuilderContext {
     /// Creates a new [RdfFusionLogicalPlanBuilder] that matches the given basic graph pattern
     /// and returns all solutions.
     ///
+    /// The patterns are matched according to the [EntailmentRegime] of this context.
+    ///
     /// # Relevant Specifications
     /// - [SPARQL 1.1 - Basic Graph Patterns](https://www.w3.org/TR/sparql11-query/#BasicGraphPatterns)
     pub fn create_bgp(
@@ -238,14 +315,26 @@ impl RdfFusionLogicalPlanBuilderContext {
     ) -> DFResult<RdfFusionLogicalPlanBuilder> {
         patterns
             .iter()
-            .map(|p| {
-                self.create_pattern(
+            .map(|p| match self.entailment {
+                EntailmentRegime::Simple => Ok(self.create_pattern(
                     active_graph.clone(),
                     graph_variables.cloned(),
                     p.clone(),
-                )
+                )),
+                EntailmentRegime::RdfsHierarchy => create_rdfs_hierarchy_pattern(
+                    self,
+                    active_graph.clone(),
+                    graph_variables.cloned(),
+                    p.clone(),
+                ),
+                EntailmentRegime::OwlSameAs => create_same_as_pattern(
+                    self,
+                    active_graph.clone(),
+                    graph_variables.cloned(),
+                    p.clone(),
+                    &self.same_as_classes,
+                ),
             })
-            .map(Ok)
             .reduce(|lhs, rhs| lhs?.join(rhs?.build()?, SparqlJoinType::Inner, None))
             .unwrap_or_else(|| Ok(self.create_empty_solution()))
     }
@@ -288,9 +377,57 @@ impl RdfFusionLogicalPlanBuilderContext {
         object: TermPattern,
     ) -> RdfFusionLogicalPlanBuilder {
         let node =
-            PropertyPathNode::new(active_graph, graph_variable, subject, path, object);
+            PropertyPathNode::new(active_graph, graph_variable, subject, path, object)
+                .with_depth_limit(self.path_depth_limit)
+                .with_repetitions(self.path_repetitions.clone());
         RdfFusionLogicalPlanBuilder::new(self.clone(), create_extension_plan(node))
     }
+
+    /// Creates a new [RdfFusionLogicalPlanBuilder] that evaluates `pattern` at the service
+    /// `name`.
+    ///
+    /// If `bindings` is given, the distinct bindings of the variables that `bindings` shares with
+    /// `pattern` are passed to the service. This is used for evaluating a `SERVICE` that is joined
+    /// with `bindings`, as the service then only returns compatible solutions. Variables that may
+    /// be unbound in `bindings` are not passed, as a solution that leaves them unbound would be
+    /// compatible with multiple bindings. This would duplicate the solutions of the join.
+    ///
+    /// # Relevant Resources
+    /// - [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/)
+    pub fn create_service(
+        &self,
+        name: NamedNode,
+        pattern: GraphPattern,
+        silent: bool,
+        bindings: Option<RdfFusionLogicalPlanBuilder>,
+    ) -> DFResult<RdfFusionLogicalPlanBuilder> {
+        let mut node = ServiceNode::try_new(name, pattern, silent)?;
+        if let Some(bindings) = bindings {
+            let shared_variables = bindings
+                .schema()
+                .fields()
+                .iter()
+                .filter(|f| {
+                    !f.is_nullable()
+                        && node.schema().has_column_with_unqualified_name(f.name())
+                })
+                .map(|f| Variable::new_unchecked(f.name()))
+                .collect::<Vec<_>>();
+            if !shared_variables.is_empty() {
+                let bindings = bindings
+                    .project(&shared_variables)?
+                    .distinct()?
+                    .with_plain_terms()?
+                    .build()?;
+                node = node.with_bindings(bindings)?;
+            }
+        }
+
+        Ok(RdfFusionLogicalPlanBuilder::new(
+            self.clone(),
+            create_extension_plan(node),
+        ))
+    }
 }
 
 /// Creates a `LogicalPlanBuilder` from a user-defined logical node.
@@ -1,5 +1,3 @@
 mod logical;
-mod rewrite;
 
 pub use logical::*;
-pub use rewrite::*;
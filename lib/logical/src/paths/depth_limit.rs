@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Bounds the number of steps that the transitive closure of a property path (`p+` and `p*`)
+/// follows from a single start node.
+///
+/// A step is a single match of the repeated path `p`. Hence, `max_depth: 1` only matches the
+/// paths of length one (and zero for `p*`). By default, no limit is applied, as required by the
+/// SPARQL specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PathDepthLimit {
+    /// The maximum number of steps.
+    pub max_depth: usize,
+    /// What happens if a node can only be reached with more than `max_depth` steps.
+    pub on_exceeded: PathDepthExceeded,
+}
+
+impl PathDepthLimit {
+    /// Creates a new [PathDepthLimit] that fails the query if `max_depth` is exceeded.
+    pub fn error(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            on_exceeded: PathDepthExceeded::Error,
+        }
+    }
+
+    /// Creates a new [PathDepthLimit] that drops the paths longer than `max_depth`.
+    pub fn truncate(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            on_exceeded: PathDepthExceeded::Truncate,
+        }
+    }
+}
+
+impl fmt::Display for PathDepthLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "max_depth={} on_exceeded={}",
+            self.max_depth, self.on_exceeded
+        )
+    }
+}
+
+/// Defines the behavior of a [PathDepthLimit] if a path exceeds the maximum depth.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PathDepthExceeded {
+    /// Fails the query with an error.
+    #[default]
+    Error,
+    /// Silently omits the nodes that are only reachable with more steps. The results of the
+    /// query are incomplete.
+    Truncate,
+}
+
+impl fmt::Display for PathDepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathDepthExceeded::Error => write!(f, "error"),
+            PathDepthExceeded::Truncate => write!(f, "truncate"),
+        }
+    }
+}
@@ -1,10 +1,35 @@
-use crate::paths::PATH_TABLE_DFSCHEMA;
+use crate::paths::{PATH_TABLE_DFSCHEMA, PathDepthLimit};
 use datafusion::common::{DFSchemaRef, plan_err};
 use datafusion::logical_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
-use rdf_fusion_model::DFResult;
+use rdf_fusion_model::{DFResult, Term};
 use std::cmp::Ordering;
 use std::fmt;
 
+/// The fixed endpoints of a [KleenePlusClosureNode].
+///
+/// If the start or the end of a path is known (e.g., `<a> :p+ ?x`), the closure only needs to be
+/// computed for paths that start or end at this term, instead of computing all paths of the
+/// graph and filtering the result afterward.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PathEndpoints {
+    /// The term that all paths must start at.
+    pub source: Option<Term>,
+    /// The term that all paths must end at.
+    pub target: Option<Term>,
+}
+
+impl fmt::Display for PathEndpoints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, " source={source}")?;
+        }
+        if let Some(target) = &self.target {
+            write!(f, " target={target}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Represents a Kleene-plus path closure node. This node computes the Kleene plus closure
 /// of the inner paths. This closure is the result of the `+` operator in SPARQL property paths.
 #[derive(PartialEq, Eq, Hash)]
@@ -17,13 +42,18 @@ pub struct KleenePlusClosureNode {
     /// is allowed (as the entire RDF dataset is queries), given a GRAPH ?x { ... } pattern, each
     /// named node is evaluated individually.
     disallow_cross_graph_paths: bool,
+    /// The fixed endpoints of the computed paths.
+    endpoints: PathEndpoints,
+    /// The maximum number of steps from a start node, if any.
+    depth_limit: Option<PathDepthLimit>,
 }
 
 impl KleenePlusClosureNode {
     /// Tries to create a new [KleenePlusClosureNode].
     ///
     /// See [KleenePlusClosureNode::disallow_cross_graph_paths] for details on
-    /// `allow_cross_graph_paths`.
+    /// `allow_cross_graph_paths`. Only paths between the given `endpoints` are computed. If a
+    /// `depth_limit` is given, the closure does not follow more steps from a start node.
     ///
     /// # Errors
     ///
@@ -31,6 +61,8 @@ impl KleenePlusClosureNode {
     pub fn try_new(
         inner: LogicalPlan,
         disallow_cross_graph_paths: bool,
+        endpoints: PathEndpoints,
+        depth_limit: Option<PathDepthLimit>,
     ) -> DFResult<Self> {
         let matches_path_schema = inner
             .schema()
@@ -47,6 +79,8 @@ impl KleenePlusClosureNode {
             inner,
             schema: PATH_TABLE_DFSCHEMA.clone(),
             disallow_cross_graph_paths,
+            endpoints,
+            depth_limit,
         })
     }
 
@@ -58,6 +92,16 @@ impl KleenePlusClosureNode {
     pub fn disallow_cross_graph_paths(&self) -> bool {
         self.disallow_cross_graph_paths
     }
+
+    /// Returns the fixed endpoints of the computed paths.
+    pub fn endpoints(&self) -> &PathEndpoints {
+        &self.endpoints
+    }
+
+    /// Returns the maximum number of steps from a start node, if any.
+    pub fn depth_limit(&self) -> Option<PathDepthLimit> {
+        self.depth_limit
+    }
 }
 
 impl fmt::Debug for KleenePlusClosureNode {
@@ -90,7 +134,11 @@ impl UserDefinedLogicalNodeCore for KleenePlusClosureNode {
     }
 
     fn fmt_for_explain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "KleenePlusPath:",)
+        write!(f, "KleenePlusPath:{}", self.endpoints)?;
+        if let Some(depth_limit) = &self.depth_limit {
+            write!(f, " {depth_limit}")?;
+        }
+        Ok(())
     }
 
     fn with_exprs_and_inputs(
@@ -104,6 +152,11 @@ impl UserDefinedLogicalNodeCore for KleenePlusClosureNode {
         if !exprs.is_empty() {
             return plan_err!("Expected 0 expressions but got {}", exprs.len());
         }
-        Self::try_new(inputs[0].clone(), self.disallow_cross_graph_paths())
+        Self::try_new(
+            inputs[0].clone(),
+            self.disallow_cross_graph_paths(),
+            self.endpoints.clone(),
+            self.depth_limit,
+        )
     }
 }
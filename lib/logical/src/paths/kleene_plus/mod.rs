@@ -1,3 +1,3 @@
 mod logical;
 
-pub use logical::KleenePlusClosureNode;
+pub use logical::{KleenePlusClosureNode, PathEndpoints};
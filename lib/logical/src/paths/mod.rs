@@ -1,11 +1,15 @@
+mod depth_limit;
 mod kleene_plus;
 mod path_node;
+mod repetition;
 
 use datafusion::arrow::datatypes::{Field, Fields, SchemaRef};
 use datafusion::common::{DFSchema, DFSchemaRef};
+pub use depth_limit::{PathDepthExceeded, PathDepthLimit};
 pub use kleene_plus::*;
 pub use path_node::*;
 use rdf_fusion_encoding::QuadStorageEncoding;
+pub use repetition::{MAX_PATH_REPETITION_STEPS, PathRepetition, PathRepetitions};
 use std::clone::Clone;
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
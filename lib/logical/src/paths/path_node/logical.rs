@@ -1,5 +1,5 @@
 use crate::ActiveGraph;
-use crate::paths::PATH_TABLE_DFSCHEMA;
+use crate::paths::{PATH_TABLE_DFSCHEMA, PathDepthLimit, PathRepetitions};
 use crate::patterns::compute_schema_for_pattern;
 use datafusion::common::{DFSchemaRef, plan_err};
 use datafusion::logical_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
@@ -15,6 +15,8 @@ pub struct PropertyPathNode {
     subject: TermPattern,
     path: PropertyPathExpression,
     object: TermPattern,
+    depth_limit: Option<PathDepthLimit>,
+    repetitions: PathRepetitions,
     schema: DFSchemaRef,
 }
 
@@ -33,10 +35,27 @@ impl PropertyPathNode {
             subject,
             path,
             object,
+            depth_limit: None,
+            repetitions: PathRepetitions::default(),
             schema,
         }
     }
 
+    /// Returns a new node whose transitive closures follow at most `depth_limit` steps.
+    #[must_use]
+    pub fn with_depth_limit(mut self, depth_limit: Option<PathDepthLimit>) -> Self {
+        self.depth_limit = depth_limit;
+        self
+    }
+
+    /// Returns a new node that interprets the markers in `repetitions` as repetitions of a
+    /// path.
+    #[must_use]
+    pub fn with_repetitions(mut self, repetitions: PathRepetitions) -> Self {
+        self.repetitions = repetitions;
+        self
+    }
+
     pub fn active_graph(&self) -> &ActiveGraph {
         &self.active_graph
     }
@@ -56,6 +75,14 @@ impl PropertyPathNode {
     pub fn object(&self) -> &TermPattern {
         &self.object
     }
+
+    pub fn depth_limit(&self) -> Option<PathDepthLimit> {
+        self.depth_limit
+    }
+
+    pub fn repetitions(&self) -> &PathRepetitions {
+        &self.repetitions
+    }
 }
 
 impl fmt::Debug for PropertyPathNode {
@@ -117,7 +144,9 @@ impl UserDefinedLogicalNodeCore for PropertyPathNode {
             self.subject.clone(),
             self.path.clone(),
             self.object.clone(),
-        ))
+        )
+        .with_depth_limit(self.depth_limit)
+        .with_repetitions(self.repetitions.clone()))
     }
 }
 
@@ -1,10 +1,13 @@
 use crate::logical_plan_builder_context::RdfFusionLogicalPlanBuilderContext;
-use crate::paths::kleene_plus::KleenePlusClosureNode;
-use crate::paths::{COL_PATH_GRAPH, COL_PATH_SOURCE, COL_PATH_TARGET, PropertyPathNode};
+use crate::paths::kleene_plus::{KleenePlusClosureNode, PathEndpoints};
+use crate::paths::{
+    COL_PATH_GRAPH, COL_PATH_SOURCE, COL_PATH_TARGET, MAX_PATH_REPETITION_STEPS,
+    PathDepthLimit, PathRepetition, PathRepetitions, PropertyPathNode,
+};
 use crate::patterns::PatternNode;
 use crate::{ActiveGraph, RdfFusionExprBuilderContext, check_same_schema};
 use datafusion::common::tree_node::{Transformed, TreeNode};
-use datafusion::common::{Column, JoinType, NullEquality, plan_datafusion_err};
+use datafusion::common::{Column, JoinType, NullEquality, plan_datafusion_err, plan_err};
 use datafusion::logical_expr::{
     Expr, Extension, LogicalPlan, LogicalPlanBuilder, UserDefinedLogicalNode, col,
 };
@@ -15,7 +18,7 @@ use rdf_fusion_extensions::RdfFusionContextView;
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::quads::{COL_GRAPH, COL_OBJECT, COL_PREDICATE, COL_SUBJECT};
 use rdf_fusion_model::{
-    NamedNode, NamedNodePattern, PropertyPathExpression, TermPattern, TermRef,
+    NamedNode, NamedNodePattern, PropertyPathExpression, Term, TermPattern, TermRef,
     TriplePattern, Variable,
 };
 use std::sync::Arc;
@@ -68,8 +71,23 @@ impl PropertyPathLoweringRule {
         let inf = PropertyPathLoweringInformation {
             disallow_cross_graph_paths: node.graph_name_var().is_some(),
             active_graph: node.active_graph().clone(),
+            depth_limit: node.depth_limit(),
+            repetitions: node.repetitions().clone(),
+        };
+        // Closures at the top of the path can directly start (or end) at constant terms.
+        let endpoints = PathEndpoints {
+            source: fixed_term(node.subject()),
+            target: fixed_term(node.object()),
+        };
+        let query = match node.path() {
+            PropertyPathExpression::OneOrMore(inner) => {
+                self.rewrite_one_or_more(&inf, inner, endpoints)?
+            }
+            PropertyPathExpression::ZeroOrMore(inner) => {
+                self.rewrite_zero_or_more(&inf, inner, endpoints)?
+            }
+            path => self.rewrite_property_path_expression(&inf, path)?,
         };
-        let query = self.rewrite_property_path_expression(&inf, node.path())?;
 
         let logical_plan = LogicalPlan::Extension(Extension {
             node: Arc::new(PatternNode::try_new(
@@ -92,6 +110,10 @@ impl PropertyPathLoweringRule {
         inf: &PropertyPathLoweringInformation,
         path: &PropertyPathExpression,
     ) -> DFResult<LogicalPlanBuilder> {
+        if let Some((inner, repetition)) = inf.repetitions.decode(path) {
+            return self.rewrite_repetition(inf, inner, repetition);
+        }
+
         match path {
             PropertyPathExpression::NamedNode(node) => self.rewrite_named_node(inf, node),
             PropertyPathExpression::Reverse(inner) => self.rewrite_reverse(inf, inner),
@@ -102,10 +124,10 @@ impl PropertyPathLoweringRule {
                 self.rewrite_alternative(inf, lhs, rhs)
             }
             PropertyPathExpression::ZeroOrMore(inner) => {
-                self.rewrite_zero_or_more(inf, inner)
+                self.rewrite_zero_or_more(inf, inner, PathEndpoints::default())
             }
             PropertyPathExpression::OneOrMore(inner) => {
-                self.rewrite_one_or_more(inf, inner)
+                self.rewrite_one_or_more(inf, inner, PathEndpoints::default())
             }
             PropertyPathExpression::ZeroOrOne(inner) => {
                 self.rewrite_zero_or_one(inf, inner)
@@ -201,22 +223,25 @@ impl PropertyPathLoweringRule {
         self.join_path_sequence(inf, lhs, rhs)?.distinct()
     }
 
-    /// Rewrites a zero or more to a CTE.
+    /// Rewrites a zero or more to the union of the zero-length paths and the one or more paths.
     fn rewrite_zero_or_more(
         &self,
         inf: &PropertyPathLoweringInformation,
         inner: &PropertyPathExpression,
+        endpoints: PathEndpoints,
     ) -> DFResult<LogicalPlanBuilder> {
         let zero = self.zero_length_paths(inf)?;
-        let repetition = self.rewrite_one_or_more(inf, inner)?;
+        let repetition = self.rewrite_one_or_more(inf, inner, endpoints)?;
         join_path_alternatives(zero, repetition)?.distinct()
     }
 
-    /// Rewrites a one or more by building a recursive query.
+    /// Rewrites a one or more to a [KleenePlusClosureNode] that only computes the paths between
+    /// the given `endpoints`.
     fn rewrite_one_or_more(
         &self,
         inf: &PropertyPathLoweringInformation,
         inner: &PropertyPathExpression,
+        endpoints: PathEndpoints,
     ) -> DFResult<LogicalPlanBuilder> {
         let inner = self.rewrite_property_path_expression(inf, inner)?;
 
@@ -227,7 +252,12 @@ impl PropertyPathLoweringRule {
             .create(Arc::new(inner.build()?))
             .with_plain_terms()?
             .build()?;
-        let node = KleenePlusClosureNode::try_new(inner, inf.disallow_cross_graph_paths)?;
+        let node = KleenePlusClosureNode::try_new(
+            inner,
+            inf.disallow_cross_graph_paths,
+            endpoints,
+            inf.depth_limit,
+        )?;
 
         let builder = LogicalPlanBuilder::from(LogicalPlan::Extension(Extension {
             node: Arc::new(node),
@@ -245,6 +275,69 @@ impl PropertyPathLoweringRule {
         join_path_alternatives(zero, one)
     }
 
+    /// Rewrites a bounded repetition `p{n,m}` to the fixed-length path `p{n}`, followed by
+    /// `m - n` optional steps of `p`. An unbounded repetition `p{n,}` is rewritten to `p{n}/p*`.
+    ///
+    /// The solutions are deduplicated after each step, such that the plan grows linearly with the
+    /// number of steps. Repetitions with more than [MAX_PATH_REPETITION_STEPS] steps are
+    /// rejected.
+    fn rewrite_repetition(
+        &self,
+        inf: &PropertyPathLoweringInformation,
+        inner: &PropertyPathExpression,
+        repetition: PathRepetition,
+    ) -> DFResult<LogicalPlanBuilder> {
+        let steps = repetition.max().unwrap_or_else(|| repetition.min());
+        if steps > MAX_PATH_REPETITION_STEPS {
+            return plan_err!(
+                "Property path repetitions are limited to {MAX_PATH_REPETITION_STEPS} steps, got {steps}."
+            );
+        }
+
+        let Some(max) = repetition.max() else {
+            let repeated =
+                self.rewrite_zero_or_more(inf, inner, PathEndpoints::default())?;
+            if repetition.min() == 0 {
+                return Ok(repeated);
+            }
+
+            let fixed = self.fixed_length_path(inf, inner, repetition.min())?;
+            return self.join_path_sequence(inf, fixed, repeated)?.distinct();
+        };
+
+        // p{n,m} = p{n}/(p?){m-n}
+        let mut result = self.fixed_length_path(inf, inner, repetition.min())?;
+        for length in repetition.min() + 1..=max {
+            let optional_step = self.rewrite_zero_or_one(inf, inner)?;
+            result = if length == 1 {
+                optional_step
+            } else {
+                self.join_path_sequence(inf, result, optional_step)?
+                    .distinct()?
+            };
+        }
+        result.distinct()
+    }
+
+    /// Creates a sequence of `length` times the `inner` path.
+    fn fixed_length_path(
+        &self,
+        inf: &PropertyPathLoweringInformation,
+        inner: &PropertyPathExpression,
+        length: u32,
+    ) -> DFResult<LogicalPlanBuilder> {
+        if length == 0 {
+            return self.zero_length_paths(inf);
+        }
+
+        let mut result = self.rewrite_property_path_expression(inf, inner)?;
+        for _ in 1..length {
+            let next = self.rewrite_property_path_expression(inf, inner)?;
+            result = self.join_path_sequence(inf, result, next)?;
+        }
+        Ok(result)
+    }
+
     /// Returns a list of all subjects and objects in the graph where they both are the source and
     /// the target of the path.
     fn zero_length_paths(
@@ -335,6 +428,8 @@ impl PropertyPathLoweringRule {
 struct PropertyPathLoweringInformation {
     active_graph: ActiveGraph,
     disallow_cross_graph_paths: bool,
+    depth_limit: Option<PathDepthLimit>,
+    repetitions: PathRepetitions,
 }
 
 /// Creates a filter [Expr] for joining a sequence of two paths.
@@ -362,6 +457,15 @@ fn create_path_sequence_join_filter(
         .expect("At least one expression must be present"))
 }
 
+/// Returns the term of `pattern` if it is a constant.
+fn fixed_term(pattern: &TermPattern) -> Option<Term> {
+    match pattern {
+        TermPattern::NamedNode(node) => Some(node.clone().into()),
+        TermPattern::Literal(literal) => Some(literal.clone().into()),
+        _ => None,
+    }
+}
+
 /// Creates a union that represents an alternative of two paths.
 fn join_path_alternatives(
     lhs: LogicalPlanBuilder,
@@ -0,0 +1,121 @@
+use rdf_fusion_model::{NamedNode, PropertyPathExpression};
+use std::collections::BTreeMap;
+
+/// The maximum number of steps of a [PathRepetition].
+///
+/// The lowered plan of a repetition grows with the number of steps. Larger repetitions are
+/// rejected when planning the query.
+pub const MAX_PATH_REPETITION_STEPS: u32 = 16;
+
+/// A repetition of a property path that matches between `min` and `max` steps of the path
+/// (e.g., `?s :p{2,4} ?o`). If `max` is [None], the number of steps is unbounded.
+///
+/// # Non-Standard Extension
+///
+/// Bounded repetitions are not part of SPARQL 1.1. They have been part of earlier drafts of the
+/// specification and are supported by some implementations. See [PathRepetitions] for how they
+/// are represented in a [PropertyPathExpression].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathRepetition {
+    min: u32,
+    max: Option<u32>,
+}
+
+impl PathRepetition {
+    /// Creates a new [PathRepetition].
+    ///
+    /// Returns [None] if `max` is smaller than `min`.
+    pub fn try_new(min: u32, max: Option<u32>) -> Option<Self> {
+        match max {
+            Some(max) if max < min => None,
+            _ => Some(Self { min, max }),
+        }
+    }
+
+    /// The minimum number of steps.
+    pub fn min(&self) -> u32 {
+        self.min
+    }
+
+    /// The maximum number of steps. [None] if the number of steps is unbounded.
+    pub fn max(&self) -> Option<u32> {
+        self.max
+    }
+}
+
+/// The [PathRepetition]s of a single query.
+///
+/// As [PropertyPathExpression] cannot represent repetitions, the parser encodes a repetition of
+/// `path` as the alternative `(path|marker)`, where `marker` is an IRI generated for this query.
+/// The marker is only interpreted as a repetition if it is registered in the [PathRepetitions] of
+/// the query. Other IRIs (e.g., the same marker used in another query) always match the
+/// predicate of the quads.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PathRepetitions {
+    markers: BTreeMap<NamedNode, PathRepetition>,
+}
+
+impl PathRepetitions {
+    /// Registers `marker` as the marker of `repetition`.
+    pub fn insert(&mut self, marker: NamedNode, repetition: PathRepetition) {
+        self.markers.insert(marker, repetition);
+    }
+
+    /// Returns whether no repetition is registered.
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    /// Returns the repeated path and the repetition if `path` encodes a registered repetition.
+    pub fn decode<'path>(
+        &self,
+        path: &'path PropertyPathExpression,
+    ) -> Option<(&'path PropertyPathExpression, PathRepetition)> {
+        let PropertyPathExpression::Alternative(inner, marker) = path else {
+            return None;
+        };
+        let PropertyPathExpression::NamedNode(marker) = marker.as_ref() else {
+            return None;
+        };
+        let repetition = self.markers.get(marker)?;
+        Some((inner.as_ref(), *repetition))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_registered_repetition() {
+        let path = named_node_path("http://example.com/p");
+        let marker = NamedNode::new_unchecked("urn:marker:0");
+        let repetition = PathRepetition::try_new(2, Some(4)).unwrap();
+        let mut repetitions = PathRepetitions::default();
+        repetitions.insert(marker.clone(), repetition);
+
+        let encoded = PropertyPathExpression::Alternative(
+            Box::new(path.clone()),
+            Box::new(PropertyPathExpression::NamedNode(marker)),
+        );
+        assert_eq!(repetitions.decode(&encoded), Some((&path, repetition)));
+    }
+
+    #[test]
+    fn decode_unregistered_marker() {
+        let path = PropertyPathExpression::Alternative(
+            Box::new(named_node_path("http://example.com/p")),
+            Box::new(named_node_path("urn:marker:0")),
+        );
+        assert_eq!(PathRepetitions::default().decode(&path), None);
+    }
+
+    #[test]
+    fn invalid_range() {
+        assert_eq!(PathRepetition::try_new(4, Some(2)), None);
+    }
+
+    fn named_node_path(iri: &str) -> PropertyPathExpression {
+        PropertyPathExpression::NamedNode(NamedNode::new_unchecked(iri))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::quad_pattern::QuadPatternNode;
+use crate::quad_pattern::order_hint::with_new_input;
+use datafusion::common::tree_node::Transformed;
+use datafusion::logical_expr::logical_plan::{FetchType, SkipType};
+use datafusion::logical_expr::{Extension, Limit, LogicalPlan};
+use datafusion::optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use rdf_fusion_model::DFResult;
+use std::sync::Arc;
+
+/// An optimizer rule that pushes the bound of a `LIMIT` into the [QuadPatternNode] below it.
+///
+/// If the input of a [Limit] is a [QuadPatternNode], at most `skip + fetch` results of the pattern
+/// are consumed. The rule sets this bound as the limit of the node (see
+/// [QuadPatternNode::with_limit]) such that the storage layer can stop scanning once enough
+/// results have been produced. The [Limit] itself remains in the plan.
+///
+/// Projections and aliases do not change the number of rows and are looked through. Any other
+/// operator (e.g., a filter that drops rows or a sort that reorders them) prevents the pushdown.
+#[derive(Debug, Default)]
+pub struct QuadPatternLimitRule;
+
+impl QuadPatternLimitRule {
+    /// Creates a new [QuadPatternLimitRule].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OptimizerRule for QuadPatternLimitRule {
+    fn name(&self) -> &str {
+        "quad-pattern-limit"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Limit(limit) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let (SkipType::Literal(skip), FetchType::Literal(Some(fetch))) =
+            (limit.get_skip_type()?, limit.get_fetch_type()?)
+        else {
+            return Ok(Transformed::no(LogicalPlan::Limit(limit)));
+        };
+        let Some(bound) = skip.checked_add(fetch) else {
+            return Ok(Transformed::no(LogicalPlan::Limit(limit)));
+        };
+
+        match with_limit(&limit.input, bound)? {
+            None => Ok(Transformed::no(LogicalPlan::Limit(limit))),
+            Some(input) => Ok(Transformed::yes(LogicalPlan::Limit(Limit {
+                skip: limit.skip,
+                fetch: limit.fetch,
+                input: Arc::new(input),
+            }))),
+        }
+    }
+}
+
+/// Sets the limit of the [QuadPatternNode] that produces the rows of `plan`.
+///
+/// Returns [None] if the rows are not directly produced by a [QuadPatternNode] or the node already
+/// has a limit that is at most `limit`.
+fn with_limit(plan: &LogicalPlan, limit: usize) -> DFResult<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Projection(projection) => {
+            with_new_input(plan, with_limit(&projection.input, limit)?)
+        }
+        LogicalPlan::SubqueryAlias(alias) => {
+            with_new_input(plan, with_limit(&alias.input, limit)?)
+        }
+        LogicalPlan::Extension(extension) => {
+            let Some(node) = extension.node.as_any().downcast_ref::<QuadPatternNode>()
+            else {
+                return Ok(None);
+            };
+
+            if node.limit().is_some_and(|existing| existing <= limit) {
+                return Ok(None);
+            }
+
+            Ok(Some(LogicalPlan::Extension(Extension {
+                node: Arc::new(node.with_limit(limit)),
+            })))
+        }
+        _ => Ok(None),
+    }
+}
@@ -11,6 +11,7 @@ use rdf_fusion_model::{
 use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Formatter;
+use std::sync::Arc;
 
 /// A logical node that represents a scan of quads matching a pattern.
 ///
@@ -29,6 +30,20 @@ use std::fmt::Formatter;
 /// storage layer. This is because the planner should consider storage-specific problems like
 /// sharing a transaction across multiple scans of the quads table in a single query. The built-in
 /// storage layers of RDF Fusion provide examples.
+///
+/// ### Order Hint
+///
+/// The `order_hint` requests that the results are ordered by a variable of the pattern (e.g.,
+/// to allow streaming the aggregation of a `GROUP BY`). Storage layers are free to ignore this
+/// hint. Operators that require an ordering must not rely on it and should inspect the
+/// properties of the physical plan instead.
+///
+/// ### Limit
+///
+/// The `limit` is an upper bound for the number of results that are consumed by the query (e.g.,
+/// because of a `LIMIT` clause directly above the pattern). Storage layers can use it to stop
+/// scanning early. As with the order hint, storage layers are free to ignore it. Hence, the
+/// operator that imposes the limit must remain in the plan.
 #[derive(PartialEq, Eq, Hash)]
 pub struct QuadPatternNode {
     /// The encoding of the storage layer.
@@ -41,6 +56,10 @@ pub struct QuadPatternNode {
     pattern: TriplePattern,
     /// How to handle blank nodes in the pattern.
     blank_node_mode: BlankNodeMatchingMode,
+    /// The variable that the results should be ordered by, if any.
+    order_hint: Option<Variable>,
+    /// The maximum number of results that are consumed, if any.
+    limit: Option<usize>,
     /// The schema of the result.
     schema: DFSchemaRef,
 }
@@ -65,6 +84,8 @@ impl QuadPatternNode {
             graph_variable,
             blank_node_mode: BlankNodeMatchingMode::Variable,
             pattern,
+            order_hint: None,
+            limit: None,
             schema,
         }
     }
@@ -91,6 +112,8 @@ impl QuadPatternNode {
             graph_variable,
             blank_node_mode: BlankNodeMatchingMode::Filter,
             pattern,
+            order_hint: None,
+            limit: None,
             schema,
         }
     }
@@ -112,6 +135,8 @@ impl QuadPatternNode {
                 object: TermPattern::Variable(Variable::new_unchecked(COL_OBJECT)),
             },
             blank_node_mode: BlankNodeMatchingMode::Filter, // Doesn't matter here
+            order_hint: None,
+            limit: None,
             schema: storage_encoding.quad_schema(),
             storage_encoding,
         }
@@ -136,6 +161,70 @@ impl QuadPatternNode {
     pub fn blank_node_mode(&self) -> BlankNodeMatchingMode {
         self.blank_node_mode
     }
+
+    /// Returns true if `variable` is bound by the subject, predicate, or object of the pattern.
+    pub fn binds_triple_variable(&self, variable: VariableRef<'_>) -> bool {
+        let predicate = match &self.pattern.predicate {
+            NamedNodePattern::Variable(v) => Some(v),
+            NamedNodePattern::NamedNode(_) => None,
+        };
+        [&self.pattern.subject, &self.pattern.object]
+            .into_iter()
+            .filter_map(|term| match term {
+                TermPattern::Variable(v) => Some(v),
+                _ => None,
+            })
+            .chain(predicate)
+            .any(|v| v.as_ref() == variable)
+    }
+
+    /// The variable that the results should be ordered by, if any.
+    pub fn order_hint(&self) -> Option<VariableRef<'_>> {
+        self.order_hint.as_ref().map(|v| v.as_ref())
+    }
+
+    /// Returns a new [QuadPatternNode] that requests its results to be ordered by `variable`.
+    ///
+    /// Returns an error if `variable` is not bound by the subject, predicate, or object of the
+    /// pattern.
+    pub fn with_order_hint(&self, variable: Variable) -> DFResult<Self> {
+        if !self.binds_triple_variable(variable.as_ref()) {
+            return plan_err!(
+                "Variable {variable} is not bound by the pattern {}.",
+                self.pattern
+            );
+        }
+
+        Ok(Self {
+            storage_encoding: self.storage_encoding.clone(),
+            active_graph: self.active_graph.clone(),
+            graph_variable: self.graph_variable.clone(),
+            pattern: self.pattern.clone(),
+            blank_node_mode: self.blank_node_mode,
+            order_hint: Some(variable),
+            limit: self.limit,
+            schema: Arc::clone(&self.schema),
+        })
+    }
+
+    /// The maximum number of results that are consumed, if any.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Returns a new [QuadPatternNode] of which at most `limit` results are consumed.
+    pub fn with_limit(&self, limit: usize) -> Self {
+        Self {
+            storage_encoding: self.storage_encoding.clone(),
+            active_graph: self.active_graph.clone(),
+            graph_variable: self.graph_variable.clone(),
+            pattern: self.pattern.clone(),
+            blank_node_mode: self.blank_node_mode,
+            order_hint: self.order_hint.clone(),
+            limit: Some(limit),
+            schema: Arc::clone(&self.schema),
+        }
+    }
 }
 
 impl fmt::Debug for QuadPatternNode {
@@ -179,6 +268,14 @@ impl UserDefinedLogicalNodeCore for QuadPatternNode {
             write!(f, ", active_graph: {} ", self.active_graph)?;
         }
 
+        if let Some(order_hint) = &self.order_hint {
+            write!(f, ", order_hint: {order_hint}")?;
+        }
+
+        if let Some(limit) = self.limit {
+            write!(f, ", limit: {limit}")?;
+        }
+
         Ok(())
     }
 
@@ -195,7 +292,7 @@ impl UserDefinedLogicalNodeCore for QuadPatternNode {
             return plan_err!("QuadPatternNode has no expressions, got {}.", exprs.len());
         }
 
-        let cloned = match self.blank_node_mode {
+        let mut cloned = match self.blank_node_mode {
             BlankNodeMatchingMode::Variable => Self::new(
                 self.storage_encoding.clone(),
                 self.active_graph.clone(),
@@ -209,6 +306,8 @@ impl UserDefinedLogicalNodeCore for QuadPatternNode {
                 self.pattern.clone(),
             ),
         };
+        cloned.order_hint = self.order_hint.clone();
+        cloned.limit = self.limit;
         Ok(cloned)
     }
 }
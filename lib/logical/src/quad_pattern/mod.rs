@@ -1,3 +1,9 @@
+mod limit;
 mod logical;
+mod order_hint;
+mod values;
 
+pub use limit::*;
 pub use logical::*;
+pub use order_hint::*;
+pub use values::*;
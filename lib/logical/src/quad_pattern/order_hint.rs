@@ -0,0 +1,228 @@
+use crate::ActiveGraph;
+use crate::expr::unwrap_encoding_changes;
+use crate::quad_pattern::QuadPatternNode;
+use datafusion::common::JoinType;
+use datafusion::common::tree_node::Transformed;
+use datafusion::logical_expr::{
+    Aggregate, Expr, Extension, Join, LogicalPlan, UserDefinedLogicalNodeCore,
+};
+use datafusion::optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use rdf_fusion_model::{DFResult, Variable, VariableRef};
+use std::sync::Arc;
+
+/// An optimizer rule that requests the input of a `GROUP BY` to be ordered by a grouped variable.
+///
+/// If the input of an aggregation is a [QuadPatternNode] that binds one of the grouped variables,
+/// the rule sets the order hint of the node to this variable (see
+/// [QuadPatternNode::with_order_hint]). Storage layers that can provide this order (e.g., by
+/// scanning an index in the order of the variable) allow DataFusion to aggregate the groups in a
+/// streaming fashion instead of building a hash table for all groups.
+///
+/// Filters and projections that pass the grouped variable through are looked through. Grouping
+/// keys that are computed (e.g., `GROUP BY (STR(?s))`) are not considered.
+#[derive(Debug, Default)]
+pub struct AggregateOrderHintRule;
+
+impl AggregateOrderHintRule {
+    /// Creates a new [AggregateOrderHintRule].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OptimizerRule for AggregateOrderHintRule {
+    fn name(&self) -> &str {
+        "aggregate-order-hint"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Aggregate(aggregate) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let grouped_columns = aggregate
+            .group_expr
+            .iter()
+            .filter_map(|expr| match unwrap_encoding_changes(unwrap_alias(expr)) {
+                Expr::Column(column) => Some(column.name().to_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for column in grouped_columns {
+            if let Some(input) = with_order_hint(&aggregate.input, &column, &|_| true)? {
+                let aggregate = Aggregate::try_new(
+                    Arc::new(input),
+                    aggregate.group_expr,
+                    aggregate.aggr_expr,
+                )?;
+                return Ok(Transformed::yes(LogicalPlan::Aggregate(aggregate)));
+            }
+        }
+
+        Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)))
+    }
+}
+
+/// An optimizer rule that requests both inputs of a join to be ordered by the join variable.
+///
+/// If both inputs of an inner or left join on a single variable are [QuadPatternNode]s in which
+/// the join variable is the only unbound component, the rule sets the order hint of both nodes to
+/// this variable. Such patterns are answered from the last level of an index and, hence, are
+/// already sorted by the join variable. This allows the physical planner to merge the inputs
+/// instead of building a hash table (see `MergeJoinSelection` in `rdf-fusion-physical`).
+///
+/// Patterns that bind further variables or span multiple graphs are not considered, as their
+/// results would have to be sorted explicitly.
+#[derive(Debug, Default)]
+pub struct JoinOrderHintRule;
+
+impl JoinOrderHintRule {
+    /// Creates a new [JoinOrderHintRule].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OptimizerRule for JoinOrderHintRule {
+    fn name(&self) -> &str {
+        "join-order-hint"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Join(join) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+
+        let column = match join.on.as_slice() {
+            [(Expr::Column(lhs), Expr::Column(rhs))]
+                if matches!(join.join_type, JoinType::Inner | JoinType::Left)
+                    && lhs.name() == rhs.name() =>
+            {
+                lhs.name().to_owned()
+            }
+            _ => return Ok(Transformed::no(LogicalPlan::Join(join))),
+        };
+
+        let is_trailing = |node: &QuadPatternNode| {
+            is_trailing_variable(node, Variable::new_unchecked(&column).as_ref())
+        };
+        let lhs = with_order_hint(&join.left, &column, &is_trailing)?;
+        let rhs = with_order_hint(&join.right, &column, &is_trailing)?;
+        let (Some(lhs), Some(rhs)) = (lhs, rhs) else {
+            return Ok(Transformed::no(LogicalPlan::Join(join)));
+        };
+
+        let join = Join::try_new(
+            Arc::new(lhs),
+            Arc::new(rhs),
+            join.on,
+            join.filter,
+            join.join_type,
+            join.join_constraint,
+            join.null_equality,
+        )?;
+        Ok(Transformed::yes(LogicalPlan::Join(join)))
+    }
+}
+
+/// Returns whether `variable` is the only unbound component of `node`.
+///
+/// The graph name must be fixed as well, as all indexes start with the graph name.
+fn is_trailing_variable(node: &QuadPatternNode, variable: VariableRef<'_>) -> bool {
+    let single_graph = match node.active_graph() {
+        ActiveGraph::DefaultGraph => true,
+        ActiveGraph::Union(graphs) => graphs.len() == 1,
+        ActiveGraph::AllGraphs | ActiveGraph::AnyNamedGraph => false,
+    };
+    single_graph
+        && node.graph_variable().is_none()
+        && node.schema().fields().len() == 1
+        && node.binds_triple_variable(variable)
+}
+
+/// Returns `expr` without its alias.
+fn unwrap_alias(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Alias(alias) => unwrap_alias(&alias.expr),
+        _ => expr,
+    }
+}
+
+/// Sets the order hint of the [QuadPatternNode] that produces `column` in `plan`.
+///
+/// Returns [None] if `column` is not produced by a [QuadPatternNode], the node already has an
+/// order hint, or the node is rejected by `is_candidate`.
+fn with_order_hint(
+    plan: &LogicalPlan,
+    column: &str,
+    is_candidate: &dyn Fn(&QuadPatternNode) -> bool,
+) -> DFResult<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Filter(filter) => {
+            with_new_input(plan, with_order_hint(&filter.input, column, is_candidate)?)
+        }
+        LogicalPlan::SubqueryAlias(alias) => {
+            with_new_input(plan, with_order_hint(&alias.input, column, is_candidate)?)
+        }
+        LogicalPlan::Projection(projection) => {
+            let passes_column = projection.expr.iter().any(|expr| {
+                matches!(unwrap_alias(expr), Expr::Column(c) if c.name() == column)
+                    && expr.schema_name().to_string() == column
+            });
+            if !passes_column {
+                return Ok(None);
+            }
+            with_new_input(
+                plan,
+                with_order_hint(&projection.input, column, is_candidate)?,
+            )
+        }
+        LogicalPlan::Extension(extension) => {
+            let Some(node) = extension.node.as_any().downcast_ref::<QuadPatternNode>()
+            else {
+                return Ok(None);
+            };
+
+            let variable = Variable::new_unchecked(column);
+            if node.order_hint().is_some()
+                || !node.binds_triple_variable(variable.as_ref())
+                || !is_candidate(node)
+            {
+                return Ok(None);
+            }
+
+            let node = node.with_order_hint(variable)?;
+            Ok(Some(LogicalPlan::Extension(Extension {
+                node: Arc::new(node),
+            })))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Replaces the single input of `plan` with `input`, if any.
+pub(super) fn with_new_input(
+    plan: &LogicalPlan,
+    input: Option<LogicalPlan>,
+) -> DFResult<Option<LogicalPlan>> {
+    input
+        .map(|input| plan.with_new_exprs(plan.expressions(), vec![input]))
+        .transpose()
+}
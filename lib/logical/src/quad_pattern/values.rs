@@ -0,0 +1,367 @@
+use crate::check_same_schema;
+use crate::expr::scalars::try_extract_scalar_term;
+use crate::expr::unwrap_encoding_changes;
+use crate::join::{SparqlJoinNode, SparqlJoinType};
+use crate::quad_pattern::QuadPatternNode;
+use crate::quad_pattern::order_hint::with_new_input;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::tree_node::Transformed;
+use datafusion::common::{Column, ScalarValue, plan_err};
+use datafusion::logical_expr::{
+    Expr, Extension, Filter, LogicalPlan, LogicalPlanBuilder, UserDefinedLogicalNode, lit,
+};
+use datafusion::optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use rdf_fusion_encoding::object_id::ObjectIdMappingError;
+use rdf_fusion_encoding::plain_term::encoders::DefaultPlainTermEncoder;
+use rdf_fusion_encoding::{
+    EncodingName, EncodingScalar, RdfFusionEncodings, TermEncoder,
+};
+use rdf_fusion_model::{DFResult, Term};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// An optimizer rule that inlines the rows of a `VALUES` block into the quad patterns that it is
+/// joined with.
+///
+/// For each variable of the `VALUES` block that is bound in every row, a filter that restricts
+/// the variable to the given terms is placed on the [QuadPatternNode]s that bind it. The storage
+/// layer can then push these filters into the index scans instead of scanning all quads and
+/// joining them with the `VALUES` afterward. This is similar to comparing a variable to a
+/// constant with `sameTerm`.
+///
+/// If the `VALUES` block only consists of a single variable with distinct terms, the join is
+/// removed entirely. Otherwise, the join is retained, as it is still necessary for the remaining
+/// variables and duplicate rows.
+#[derive(Debug)]
+pub struct ValuesInliningRule {
+    /// Used for encoding the terms of the `VALUES` block.
+    encodings: RdfFusionEncodings,
+}
+
+impl ValuesInliningRule {
+    /// Creates a new [ValuesInliningRule].
+    pub fn new(encodings: RdfFusionEncodings) -> Self {
+        Self { encodings }
+    }
+}
+
+impl OptimizerRule for ValuesInliningRule {
+    fn name(&self) -> &str {
+        "values-inlining"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        let LogicalPlan::Extension(Extension { node }) = &plan else {
+            return Ok(Transformed::no(plan));
+        };
+        let Some(join) = node.as_any().downcast_ref::<SparqlJoinNode>() else {
+            return Ok(Transformed::no(plan));
+        };
+        if join.join_type() != SparqlJoinType::Inner || join.filter().is_some() {
+            return Ok(Transformed::no(plan));
+        }
+
+        let new_plan = match extract_values(&self.encodings, join.lhs())? {
+            Some(values) => self.inline_values(join, &values, join.rhs(), true)?,
+            None => match extract_values(&self.encodings, join.rhs())? {
+                Some(values) => self.inline_values(join, &values, join.lhs(), false)?,
+                None => None,
+            },
+        };
+
+        match new_plan {
+            None => Ok(Transformed::no(plan)),
+            Some(new_plan) => {
+                check_same_schema(join.schema(), new_plan.schema())?;
+                Ok(Transformed::yes(new_plan))
+            }
+        }
+    }
+}
+
+impl ValuesInliningRule {
+    /// Pushes the `values` into `other`, the input of `join` that is not the `VALUES` block.
+    fn inline_values(
+        &self,
+        join: &SparqlJoinNode,
+        values: &ValuesColumns,
+        other: &LogicalPlan,
+        values_is_lhs: bool,
+    ) -> DFResult<Option<LogicalPlan>> {
+        let mut new_other = other.clone();
+        let mut pushed_columns = Vec::new();
+        for (column, terms) in &values.columns {
+            if other.schema().field_with_unqualified_name(column).is_err() {
+                continue;
+            }
+            let Some(terms) = terms.iter().cloned().collect::<Option<Vec<_>>>() else {
+                // An unbound value is compatible with every term.
+                continue;
+            };
+
+            if let Some(plan) = self.push_terms(&new_other, column, &terms)? {
+                new_other = plan;
+                pushed_columns.push(column.as_str());
+            }
+        }
+
+        if pushed_columns.is_empty() {
+            return Ok(None);
+        }
+
+        if values.is_single_distinct_column()
+            && pushed_columns.len() == 1
+            && is_bound(&new_other, pushed_columns[0])
+        {
+            let projections = join
+                .schema()
+                .columns()
+                .into_iter()
+                .map(|c| Expr::Column(Column::new_unqualified(c.name())))
+                .collect::<Vec<_>>();
+            let plan = LogicalPlanBuilder::from(new_other)
+                .project(projections)?
+                .build()?;
+            return Ok(Some(plan));
+        }
+
+        let (lhs, rhs) = if values_is_lhs {
+            (join.lhs().clone(), new_other)
+        } else {
+            (new_other, join.rhs().clone())
+        };
+        let join = SparqlJoinNode::try_new(
+            self.encodings.clone(),
+            lhs,
+            rhs,
+            None,
+            SparqlJoinType::Inner,
+        )?;
+        Ok(Some(LogicalPlan::Extension(Extension {
+            node: Arc::new(join),
+        })))
+    }
+
+    /// Restricts the `column` of the [QuadPatternNode]s in `plan` to `terms`.
+    ///
+    /// Returns [None] if no quad pattern that binds `column` can be reached.
+    fn push_terms(
+        &self,
+        plan: &LogicalPlan,
+        column: &str,
+        terms: &[Term],
+    ) -> DFResult<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Projection(projection) => {
+                let Some(expr) = projection
+                    .expr
+                    .iter()
+                    .zip(projection.schema.fields())
+                    .find(|(_, field)| field.name() == column)
+                    .map(|(expr, _)| expr.clone().unalias())
+                else {
+                    return Ok(None);
+                };
+                let Expr::Column(input_column) = unwrap_encoding_changes(&expr) else {
+                    return Ok(None);
+                };
+
+                let input =
+                    self.push_terms(&projection.input, &input_column.name, terms)?;
+                with_new_input(plan, input)
+            }
+            LogicalPlan::SubqueryAlias(alias) => {
+                with_new_input(plan, self.push_terms(&alias.input, column, terms)?)
+            }
+            LogicalPlan::Filter(filter) => {
+                with_new_input(plan, self.push_terms(&filter.input, column, terms)?)
+            }
+            LogicalPlan::Extension(Extension { node }) => {
+                if let Some(join) = node.as_any().downcast_ref::<SparqlJoinNode>() {
+                    return self.push_terms_into_join(join, column, terms);
+                }
+                if node.as_any().downcast_ref::<QuadPatternNode>().is_none() {
+                    return Ok(None);
+                }
+
+                let Ok(field) = plan.schema().field_with_unqualified_name(column) else {
+                    return Ok(None);
+                };
+                if field.is_nullable() {
+                    return Ok(None);
+                }
+                let Some(scalars) =
+                    encode_terms(&self.encodings, field.data_type(), terms)?
+                else {
+                    return Ok(None);
+                };
+
+                let predicate = scalars
+                    .into_iter()
+                    .map(|scalar| {
+                        Expr::Column(Column::new_unqualified(column)).eq(lit(scalar))
+                    })
+                    .reduce(Expr::or)
+                    .unwrap_or_else(|| lit(false));
+                let filter = Filter::try_new(predicate, Arc::new(plan.clone()))?;
+                Ok(Some(LogicalPlan::Filter(filter)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Restricts the `column` in all inputs of an inner `join` that bind it.
+    fn push_terms_into_join(
+        &self,
+        join: &SparqlJoinNode,
+        column: &str,
+        terms: &[Term],
+    ) -> DFResult<Option<LogicalPlan>> {
+        if join.join_type() != SparqlJoinType::Inner {
+            return Ok(None);
+        }
+
+        let lhs = self.push_terms(join.lhs(), column, terms)?;
+        let rhs = self.push_terms(join.rhs(), column, terms)?;
+        if lhs.is_none() && rhs.is_none() {
+            return Ok(None);
+        }
+
+        let join = SparqlJoinNode::try_new(
+            self.encodings.clone(),
+            lhs.unwrap_or_else(|| join.lhs().clone()),
+            rhs.unwrap_or_else(|| join.rhs().clone()),
+            join.filter().cloned(),
+            SparqlJoinType::Inner,
+        )?;
+        Ok(Some(LogicalPlan::Extension(Extension {
+            node: Arc::new(join),
+        })))
+    }
+}
+
+/// The terms of a `VALUES` block, grouped by column. Unbound values are [None].
+struct ValuesColumns {
+    columns: Vec<(String, Vec<Option<Term>>)>,
+}
+
+impl ValuesColumns {
+    /// Returns true if there is a single column that is bound in every row and does not contain
+    /// duplicates. Joining with such a block is equivalent to filtering the other input.
+    fn is_single_distinct_column(&self) -> bool {
+        let [(_, terms)] = self.columns.as_slice() else {
+            return false;
+        };
+
+        let mut seen = HashSet::new();
+        terms
+            .iter()
+            .all(|term| term.as_ref().is_some_and(|term| seen.insert(term)))
+    }
+}
+
+/// Extracts the terms of `plan` if it is a `VALUES` block. Projections that only rename the
+/// columns are looked through.
+fn extract_values(
+    encodings: &RdfFusionEncodings,
+    plan: &LogicalPlan,
+) -> DFResult<Option<ValuesColumns>> {
+    match plan {
+        LogicalPlan::Projection(projection) => {
+            let Some(input) = extract_values(encodings, &projection.input)? else {
+                return Ok(None);
+            };
+
+            let mut columns = Vec::new();
+            for (expr, field) in projection.expr.iter().zip(projection.schema.fields()) {
+                let Expr::Column(column) = expr.clone().unalias() else {
+                    return Ok(None);
+                };
+                let Some((_, terms)) =
+                    input.columns.iter().find(|(name, _)| *name == column.name)
+                else {
+                    return Ok(None);
+                };
+                columns.push((field.name().clone(), terms.clone()));
+            }
+            Ok(Some(ValuesColumns { columns }))
+        }
+        LogicalPlan::Values(values) => {
+            let mut columns = values
+                .schema
+                .fields()
+                .iter()
+                .map(|field| (field.name().clone(), Vec::new()))
+                .collect::<Vec<_>>();
+
+            for row in &values.values {
+                for ((_, terms), expr) in columns.iter_mut().zip(row) {
+                    let term = match expr {
+                        Expr::Literal(scalar, _) if scalar.is_null() => None,
+                        expr => match try_extract_scalar_term(encodings, expr) {
+                            Some(term) => Some(term),
+                            None => return Ok(None),
+                        },
+                    };
+                    terms.push(term);
+                }
+            }
+            Ok(Some(ValuesColumns { columns }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns true if `column` is bound in every solution of `plan`.
+fn is_bound(plan: &LogicalPlan, column: &str) -> bool {
+    plan.schema()
+        .field_with_unqualified_name(column)
+        .is_ok_and(|field| !field.is_nullable())
+}
+
+/// Encodes `terms` such that they can be compared to a column of `data_type`.
+///
+/// Terms that are unknown to the object id mapping are omitted, as they cannot match any quad.
+/// Returns [None] if the encoding does not support comparing terms for equality.
+fn encode_terms(
+    encodings: &RdfFusionEncodings,
+    data_type: &DataType,
+    terms: &[Term],
+) -> DFResult<Option<Vec<ScalarValue>>> {
+    let Some(encoding) = encodings.try_get_encoding_name(data_type) else {
+        return Ok(None);
+    };
+
+    let mut result = Vec::new();
+    for term in terms {
+        match encoding {
+            EncodingName::PlainTerm => result.push(
+                encodings
+                    .plain_term()
+                    .encode_term(Ok(term.as_ref()))?
+                    .into_scalar_value(),
+            ),
+            EncodingName::ObjectId => {
+                let Some(object_ids) = encodings.object_id() else {
+                    return Ok(None);
+                };
+                let scalar = DefaultPlainTermEncoder.encode_term(Ok(term.as_ref()))?;
+                match object_ids.encode_scalar(&scalar) {
+                    Ok(scalar) => result.push(scalar.into_scalar_value()),
+                    Err(ObjectIdMappingError::UnknownObjectId) => {}
+                    Err(err) => plan_err!("Failed to encode term: {}", err)?,
+                }
+            }
+            EncodingName::TypedValue | EncodingName::Sortable => return Ok(None),
+        }
+    }
+    Ok(Some(result))
+}
@@ -0,0 +1,262 @@
+use datafusion::arrow::datatypes::{Field, Fields};
+use datafusion::common::{DFSchema, DFSchemaRef, plan_err};
+use datafusion::logical_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
+use rdf_fusion_encoding::TermEncoding;
+use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+use rdf_fusion_model::{DFResult, GraphPattern, NamedNode, Variable};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A logical node that represents the SPARQL `SERVICE` operator.
+///
+/// The inner graph pattern is not evaluated by the engine. Instead, it is sent to the service
+/// `name` during execution. The node has one column for each variable that is in scope in the
+/// inner pattern. All columns use the plain term encoding.
+///
+/// Optionally, the node has a single input that provides bindings for some of the variables of
+/// the inner pattern (e.g., the solutions of the left-hand side of a join). These bindings are
+/// sent to the service as a `VALUES` block, which allows the service to only compute the
+/// solutions that are compatible with them. Note that the bindings only restrict the solutions of
+/// the service. The solutions must still be joined with the bindings afterward.
+///
+/// # Relevant Resources
+/// - [SPARQL 1.1 Federated Query](https://www.w3.org/TR/sparql11-federated-query/)
+#[derive(PartialEq, Eq, Hash)]
+pub struct ServiceNode {
+    /// The name of the service.
+    name: NamedNode,
+    /// The graph pattern that is evaluated by the service.
+    pattern: GraphPattern,
+    /// Whether errors of the service are ignored.
+    silent: bool,
+    /// The bindings that are passed to the service.
+    bindings: Option<LogicalPlan>,
+    /// The schema of this node.
+    schema: DFSchemaRef,
+}
+
+impl ServiceNode {
+    /// Tries to create a new [ServiceNode].
+    pub fn try_new(
+        name: NamedNode,
+        pattern: GraphPattern,
+        silent: bool,
+    ) -> DFResult<Self> {
+        let mut variables = Vec::<&Variable>::new();
+        pattern.on_in_scope_variable(|variable| {
+            if !variables.contains(&variable) {
+                variables.push(variable);
+            }
+        });
+
+        let fields = variables
+            .iter()
+            .map(|v| {
+                Field::new(v.as_str(), PLAIN_TERM_ENCODING.data_type().clone(), true)
+            })
+            .collect::<Fields>();
+        let schema = DFSchema::from_unqualified_fields(fields, HashMap::new())?;
+
+        Ok(Self {
+            name,
+            pattern,
+            silent,
+            bindings: None,
+            schema: Arc::new(schema),
+        })
+    }
+
+    /// Returns a new [ServiceNode] that passes the solutions of `bindings` to the service.
+    ///
+    /// Each column of `bindings` must correspond to a variable of the inner pattern and use the
+    /// plain term encoding.
+    pub fn with_bindings(self, bindings: LogicalPlan) -> DFResult<Self> {
+        for field in bindings.schema().fields() {
+            let Ok(own_field) = self.schema.field_with_unqualified_name(field.name())
+            else {
+                return plan_err!(
+                    "The binding {} is not a variable of the SERVICE pattern.",
+                    field.name()
+                );
+            };
+            if own_field.data_type() != field.data_type() {
+                return plan_err!(
+                    "The binding {} must use the plain term encoding.",
+                    field.name()
+                );
+            }
+        }
+
+        Ok(Self {
+            bindings: Some(bindings),
+            ..self
+        })
+    }
+
+    /// Returns the name of the service.
+    pub fn name(&self) -> &NamedNode {
+        &self.name
+    }
+
+    /// Returns the graph pattern that is evaluated by the service.
+    pub fn pattern(&self) -> &GraphPattern {
+        &self.pattern
+    }
+
+    /// Returns whether errors of the service are ignored.
+    ///
+    /// If `true`, a failing service produces a single solution without any bindings.
+    pub fn silent(&self) -> bool {
+        self.silent
+    }
+
+    /// Returns the plan that provides the bindings that are passed to the service, if any.
+    pub fn bindings(&self) -> Option<&LogicalPlan> {
+        self.bindings.as_ref()
+    }
+}
+
+impl fmt::Debug for ServiceNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        UserDefinedLogicalNodeCore::fmt_for_explain(self, f)
+    }
+}
+
+impl PartialOrd for ServiceNode {
+    fn partial_cmp(&self, _other: &Self) -> Option<Ordering> {
+        None
+    }
+}
+
+impl UserDefinedLogicalNodeCore for ServiceNode {
+    fn name(&self) -> &str {
+        "Service"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        self.bindings.iter().collect()
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        Vec::new()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Service: {}", self.name)?;
+        if self.silent {
+            write!(f, " (silent)")?;
+        }
+        Ok(())
+    }
+
+    fn with_exprs_and_inputs(
+        &self,
+        exprs: Vec<Expr>,
+        inputs: Vec<LogicalPlan>,
+    ) -> DFResult<Self> {
+        if inputs.len() != self.bindings.iter().len() {
+            return plan_err!(
+                "ServiceNode expects {} inputs, got {}.",
+                self.bindings.iter().len(),
+                inputs.len()
+            );
+        }
+        if !exprs.is_empty() {
+            return plan_err!("ServiceNode must not have any expression.");
+        }
+
+        let node = Self {
+            name: self.name.clone(),
+            pattern: self.pattern.clone(),
+            silent: self.silent,
+            bindings: None,
+            schema: Arc::clone(&self.schema),
+        };
+        match inputs.into_iter().next() {
+            None => Ok(node),
+            Some(bindings) => node.with_bindings(bindings),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::logical_expr::EmptyRelation;
+    use rdf_fusion_model::{TermPattern, TriplePattern};
+
+    #[test]
+    fn test_schema_contains_in_scope_variables() {
+        let s = Variable::new_unchecked("s");
+        let o = Variable::new_unchecked("o");
+        let pattern = GraphPattern::Bgp {
+            patterns: vec![
+                TriplePattern {
+                    subject: TermPattern::Variable(s.clone()),
+                    predicate: NamedNode::new_unchecked("http://example.com/p").into(),
+                    object: TermPattern::Variable(o.clone()),
+                },
+                TriplePattern {
+                    subject: TermPattern::Variable(o),
+                    predicate: NamedNode::new_unchecked("http://example.com/p").into(),
+                    object: TermPattern::Variable(s),
+                },
+            ],
+        };
+
+        let node = ServiceNode::try_new(
+            NamedNode::new_unchecked("http://example.com/sparql"),
+            pattern,
+            false,
+        )
+        .unwrap();
+
+        let columns = node
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec!["s", "o"]);
+    }
+
+    #[test]
+    fn test_bindings_must_be_variables_of_pattern() {
+        let node = || {
+            let pattern = GraphPattern::Bgp {
+                patterns: vec![TriplePattern {
+                    subject: TermPattern::Variable(Variable::new_unchecked("s")),
+                    predicate: NamedNode::new_unchecked("http://example.com/p").into(),
+                    object: TermPattern::Variable(Variable::new_unchecked("o")),
+                }],
+            };
+            ServiceNode::try_new(
+                NamedNode::new_unchecked("http://example.com/sparql"),
+                pattern,
+                false,
+            )
+            .unwrap()
+        };
+
+        let bindings = |name: &str| {
+            let field = Field::new(name, PLAIN_TERM_ENCODING.data_type().clone(), false);
+            let schema =
+                DFSchema::from_unqualified_fields(vec![field].into(), HashMap::new())
+                    .unwrap();
+            LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                schema: Arc::new(schema),
+            })
+        };
+
+        let node_with_bindings = node().with_bindings(bindings("s")).unwrap();
+        assert_eq!(node_with_bindings.inputs().len(), 1);
+        assert!(node().with_bindings(bindings("x")).is_err());
+    }
+}
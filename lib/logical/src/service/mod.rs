@@ -0,0 +1,3 @@
+mod logical;
+
+pub use logical::*;
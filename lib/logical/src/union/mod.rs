@@ -0,0 +1,3 @@
+mod rewrite;
+
+pub use rewrite::*;
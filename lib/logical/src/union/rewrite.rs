@@ -0,0 +1,286 @@
+use datafusion::common::tree_node::Transformed;
+use datafusion::logical_expr::{Aggregate, Distinct, Expr, LogicalPlan, Union};
+use datafusion::optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use rdf_fusion_model::DFResult;
+use std::sync::Arc;
+
+/// An optimizer rule that removes equivalent branches of a `UNION` whose duplicates are
+/// irrelevant.
+///
+/// SPARQL's `UNION` has bag semantics. Hence, `{ A } UNION { A }` produces every solution of `A`
+/// twice and the branches cannot be collapsed in general. However, if the union feeds into an
+/// operator that eliminates duplicates (e.g., `SELECT DISTINCT`), evaluating equivalent branches
+/// more than once is redundant. This is common in generated queries that repeat the same
+/// boilerplate pattern in multiple branches.
+///
+/// Two branches are considered equivalent if their logical plans are equal. Nested unions are
+/// flattened before comparing the branches. Projections, filters, and aliases between the
+/// duplicate-eliminating operator and the union are looked through, as they do not change
+/// whether duplicates matter.
+#[derive(Debug, Default)]
+pub struct UnionReductionRule;
+
+impl UnionReductionRule {
+    /// Creates a new [UnionReductionRule].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OptimizerRule for UnionReductionRule {
+    fn name(&self) -> &str {
+        "union-reduction"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> DFResult<Transformed<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Distinct(Distinct::All(input)) => {
+                Ok(match reduce_unions(&input)? {
+                    Some(input) => Transformed::yes(LogicalPlan::Distinct(
+                        Distinct::All(Arc::new(input)),
+                    )),
+                    None => Transformed::no(LogicalPlan::Distinct(Distinct::All(input))),
+                })
+            }
+            // An aggregation without aggregate expressions only produces the distinct groups.
+            LogicalPlan::Aggregate(aggregate) if aggregate.aggr_expr.is_empty() => {
+                let Some(input) = reduce_unions(&aggregate.input)? else {
+                    return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
+                };
+                let aggregate = Aggregate::try_new(
+                    Arc::new(input),
+                    aggregate.group_expr,
+                    aggregate.aggr_expr,
+                )?;
+                Ok(Transformed::yes(LogicalPlan::Aggregate(aggregate)))
+            }
+            _ => Ok(Transformed::no(plan)),
+        }
+    }
+}
+
+/// Removes the equivalent branches of the unions in `plan`. The caller must ensure that
+/// duplicate solutions of `plan` are irrelevant.
+///
+/// Returns [None] if no union has been changed.
+fn reduce_unions(plan: &LogicalPlan) -> DFResult<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::SubqueryAlias(_) => {
+            let [input] = plan.inputs()[..] else {
+                return Ok(None);
+            };
+            reduce_unions(input)?
+                .map(|input| plan.with_new_exprs(plan.expressions(), vec![input]))
+                .transpose()
+        }
+        LogicalPlan::Union(union) => reduce_union(union),
+        _ => Ok(None),
+    }
+}
+
+/// Removes the equivalent branches of `union`.
+fn reduce_union(union: &Union) -> DFResult<Option<LogicalPlan>> {
+    let mut branches = Vec::new();
+    collect_branches(union, &mut branches);
+
+    let mut changed = branches.len() != union.inputs.len();
+    let mut distinct_branches: Vec<LogicalPlan> = Vec::new();
+    for branch in branches {
+        let branch = match reduce_unions(&branch)? {
+            Some(reduced) => {
+                changed = true;
+                reduced
+            }
+            None => branch,
+        };
+
+        if distinct_branches.contains(&branch) {
+            changed = true;
+        } else {
+            distinct_branches.push(branch);
+        }
+    }
+
+    if !changed {
+        return Ok(None);
+    }
+
+    if let [branch] = distinct_branches.as_slice() {
+        // The branch replaces the union, so it must produce the same columns.
+        if !union
+            .schema
+            .logically_equivalent_names_and_types(branch.schema())
+        {
+            return Ok(None);
+        }
+        return Ok(distinct_branches.pop());
+    }
+
+    Ok(Some(LogicalPlan::Union(Union {
+        inputs: distinct_branches.into_iter().map(Arc::new).collect(),
+        schema: Arc::clone(&union.schema),
+    })))
+}
+
+/// Collects the branches of `union`. Nested unions with the same schema are flattened.
+fn collect_branches(union: &Union, branches: &mut Vec<LogicalPlan>) {
+    for input in &union.inputs {
+        match as_union(input) {
+            Some(nested)
+                if union
+                    .schema
+                    .logically_equivalent_names_and_types(&nested.schema) =>
+            {
+                collect_branches(nested, branches);
+            }
+            _ => branches.push(input.as_ref().clone()),
+        }
+    }
+}
+
+/// Returns the union of `plan`, looking through projections that only pass through the columns
+/// of the union.
+fn as_union(plan: &LogicalPlan) -> Option<&Union> {
+    match plan {
+        LogicalPlan::Union(union) => Some(union),
+        LogicalPlan::Projection(projection) => {
+            let input_columns = projection.input.schema().columns();
+            let is_pass_through =
+                projection.expr.len() == input_columns.len()
+                    && projection.expr.iter().zip(&input_columns).all(
+                        |(expr, column)| matches!(expr, Expr::Column(c) if c == column),
+                    );
+            if is_pass_through {
+                as_union(&projection.input)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ActiveGraph, RdfFusionLogicalPlanBuilder, RdfFusionLogicalPlanBuilderContext,
+    };
+    use datafusion::optimizer::OptimizerContext;
+    use insta::assert_snapshot;
+    use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+    use rdf_fusion_encoding::sortable_term::SORTABLE_TERM_ENCODING;
+    use rdf_fusion_encoding::typed_value::TypedValueEncoding;
+    use rdf_fusion_encoding::{QuadStorageEncoding, RdfFusionEncodings};
+    use rdf_fusion_extensions::RdfFusionContextView;
+    use rdf_fusion_functions::registry::DefaultRdfFusionFunctionRegistry;
+    use rdf_fusion_model::NamedNode;
+
+    #[test]
+    fn distinct_union_of_equivalent_branches_is_collapsed() {
+        let plan = matching_quads("p")
+            .union(matching_quads("p").build().unwrap())
+            .unwrap()
+            .distinct()
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let result = rewrite_plan(plan);
+        assert_snapshot!(&result, @r"
+        Distinct:
+          Projection: graph, subject, predicate, object
+            Projection: graph, subject, Struct({term_type:0,value:http://example.com/p,data_type:,language_tag:}) AS predicate, object
+              QuadPattern (?graph ?subject <http://example.com/p> ?object)
+        ");
+    }
+
+    #[test]
+    fn distinct_union_keeps_different_branches() {
+        let nested = matching_quads("q")
+            .union(matching_quads("p").build().unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = matching_quads("p")
+            .union(nested)
+            .unwrap()
+            .distinct()
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let result = rewrite_plan(plan);
+        assert_snapshot!(&result, @r"
+        Distinct:
+          Union
+            Projection: graph, subject, predicate, object
+              Projection: graph, subject, Struct({term_type:0,value:http://example.com/p,data_type:,language_tag:}) AS predicate, object
+                QuadPattern (?graph ?subject <http://example.com/p> ?object)
+            Projection: graph, subject, predicate, object
+              Projection: graph, subject, Struct({term_type:0,value:http://example.com/q,data_type:,language_tag:}) AS predicate, object
+                QuadPattern (?graph ?subject <http://example.com/q> ?object)
+        ");
+    }
+
+    #[test]
+    fn union_without_distinct_is_not_changed() {
+        let plan = matching_quads("p")
+            .union(matching_quads("p").build().unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let result = rewrite_plan(plan);
+        assert_snapshot!(&result, @r"
+        Union
+          Projection: graph, subject, predicate, object
+            Projection: graph, subject, Struct({term_type:0,value:http://example.com/p,data_type:,language_tag:}) AS predicate, object
+              QuadPattern (?graph ?subject <http://example.com/p> ?object)
+          Projection: graph, subject, predicate, object
+            Projection: graph, subject, Struct({term_type:0,value:http://example.com/p,data_type:,language_tag:}) AS predicate, object
+              QuadPattern (?graph ?subject <http://example.com/p> ?object)
+        ");
+    }
+
+    fn matching_quads(predicate: &str) -> RdfFusionLogicalPlanBuilder {
+        let encodings = RdfFusionEncodings::new(
+            Arc::clone(&PLAIN_TERM_ENCODING),
+            Arc::new(TypedValueEncoding::default()),
+            None,
+            Arc::clone(&SORTABLE_TERM_ENCODING),
+        );
+        let registry = Arc::new(DefaultRdfFusionFunctionRegistry::new(encodings.clone()));
+        let context = RdfFusionContextView::new(
+            registry,
+            encodings,
+            QuadStorageEncoding::PlainTerm,
+        );
+        RdfFusionLogicalPlanBuilderContext::new(context).create_matching_quads(
+            ActiveGraph::DefaultGraph,
+            None,
+            Some(NamedNode::new_unchecked(format!(
+                "http://example.com/{predicate}"
+            ))),
+            None,
+        )
+    }
+
+    fn rewrite_plan(plan: LogicalPlan) -> LogicalPlan {
+        let config = OptimizerContext::new();
+        UnionReductionRule::new()
+            .rewrite(plan, &config)
+            .unwrap()
+            .data
+    }
+}
@@ -33,7 +33,7 @@ pub use oxrdf::{
     Quad, QuadRef, Term, TermParseError, TermRef, Triple, TripleRef, Variable,
     VariableNameParseError, VariableRef, dataset,
 };
-pub use spargebra::algebra::PropertyPathExpression;
+pub use spargebra::algebra::{GraphPattern, PropertyPathExpression};
 pub use spargebra::term::{GroundTerm, NamedNodePattern, TermPattern, TriplePattern};
 
 use datafusion::arrow::error::ArrowError;
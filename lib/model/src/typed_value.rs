@@ -1,8 +1,8 @@
 use crate::{
-    Boolean, Date, DateTime, DayTimeDuration, Decimal, Double, Duration, Float, Int,
-    Integer, LanguageString, LanguageStringRef, Numeric, ParseDateTimeError,
-    ParseDecimalError, ParseDurationError, SimpleLiteral, SimpleLiteralRef, Term, Time,
-    YearMonthDuration,
+    Boolean, Date, DateTime, DayTimeDuration, Decimal, Double, Duration, Float, GDay,
+    GMonth, GMonthDay, GYear, GYearMonth, Int, Integer, LanguageString,
+    LanguageStringRef, Numeric, ParseDateTimeError, ParseDecimalError,
+    ParseDurationError, SimpleLiteral, SimpleLiteralRef, Term, Time, YearMonthDuration,
 };
 use oxrdf::vocab::xsd;
 use oxrdf::{
@@ -10,7 +10,7 @@ use oxrdf::{
 };
 use std::cmp::Ordering;
 use std::num::{ParseFloatError, ParseIntError};
-use std::str::ParseBoolError;
+use std::str::{FromStr, ParseBoolError};
 use thiserror::Error;
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -77,6 +77,9 @@ pub enum TypedValue {
     DayTimeDurationLiteral(DayTimeDuration),
     /// A literal with a datatype not specifically handled by other variants.
     ///
+    /// This includes `xsd:anyURI` literals. They retain their datatype but are compared like
+    /// strings (see [TypedValueRef::partial_cmp]).
+    ///
     /// # Additional Resources
     /// - [RDF 1.1 Concepts - Datatypes](https://www.w3.org/TR/rdf11-concepts/#section-Datatypes)
     OtherLiteral(Literal),
@@ -183,13 +186,13 @@ impl PartialOrd for TypedValueRef<'_> {
 
 fn partial_cmp_literals(a: TypedValueRef<'_>, b: TypedValueRef<'_>) -> Option<Ordering> {
     match a {
-        TypedValueRef::SimpleLiteral(a) => {
-            if let TypedValueRef::SimpleLiteral(b) = b {
-                a.partial_cmp(&b)
-            } else {
-                None
+        TypedValueRef::SimpleLiteral(a) => match b {
+            TypedValueRef::SimpleLiteral(b) => a.partial_cmp(&b),
+            TypedValueRef::OtherLiteral(b) if b.datatype() == xsd::ANY_URI => {
+                Some(a.value.cmp(b.value()))
             }
-        }
+            _ => None,
+        },
         TypedValueRef::LanguageStringLiteral(a) => {
             if let TypedValueRef::LanguageStringLiteral(b) = b {
                 a.partial_cmp(&b)
@@ -252,7 +255,10 @@ fn partial_cmp_literals(a: TypedValueRef<'_>, b: TypedValueRef<'_>) -> Option<Or
         },
         TypedValueRef::OtherLiteral(a) => match b {
             TypedValueRef::OtherLiteral(b) if a.datatype() == b.datatype() => {
-                (a.value() == b.value()).then_some(Ordering::Equal)
+                partial_cmp_other_literals(a, b)
+            }
+            TypedValueRef::SimpleLiteral(b) if a.datatype() == xsd::ANY_URI => {
+                Some(a.value().cmp(b.value))
             }
             _ => None,
         },
@@ -260,6 +266,33 @@ fn partial_cmp_literals(a: TypedValueRef<'_>, b: TypedValueRef<'_>) -> Option<Or
     }
 }
 
+/// Compares two literals with the same datatype that have no dedicated typed value.
+///
+/// The partial date types (e.g., `xsd:gYear`) are compared by their value. Like in XPath,
+/// `xsd:anyURI` literals are compared like strings. Literals of other datatypes can only be
+/// compared for equality of their lexical form.
+fn partial_cmp_other_literals(a: LiteralRef<'_>, b: LiteralRef<'_>) -> Option<Ordering> {
+    let ordering = match a.datatype() {
+        xsd::G_YEAR => partial_cmp_values::<GYear>(a.value(), b.value()),
+        xsd::G_YEAR_MONTH => partial_cmp_values::<GYearMonth>(a.value(), b.value()),
+        xsd::G_MONTH => partial_cmp_values::<GMonth>(a.value(), b.value()),
+        xsd::G_MONTH_DAY => partial_cmp_values::<GMonthDay>(a.value(), b.value()),
+        xsd::G_DAY => partial_cmp_values::<GDay>(a.value(), b.value()),
+        xsd::ANY_URI => Some(a.value().cmp(b.value())),
+        _ => None,
+    };
+
+    // Fall back to the lexical form for other datatypes and ill-formed values.
+    ordering.or_else(|| (a.value() == b.value()).then_some(Ordering::Equal))
+}
+
+/// Parses both values as `T` and compares the results.
+fn partial_cmp_values<T: FromStr + PartialOrd>(a: &str, b: &str) -> Option<Ordering> {
+    let a = a.parse::<T>().ok()?;
+    let b = b.parse::<T>().ok()?;
+    a.partial_cmp(&b)
+}
+
 macro_rules! impl_from {
     ($TYPE: ty, $VARIANT: path) => {
         impl<'data> From<$TYPE> for TypedValueRef<'data> {
@@ -448,3 +481,88 @@ impl_for_parsing_error!(ParseBoolError);
 impl_for_parsing_error!(ParseDecimalError);
 impl_for_parsing_error!(ParseDurationError);
 impl_for_parsing_error!(ParseDateTimeError);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_g_year_equality() {
+        assert_eq!(cmp("2020", "2020", xsd::G_YEAR), Some(Ordering::Equal));
+        assert_eq!(
+            cmp("2020Z", "2020+00:00", xsd::G_YEAR),
+            Some(Ordering::Equal)
+        );
+        assert_ne!(cmp("2020", "2021", xsd::G_YEAR), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_g_year_ordering() {
+        assert_eq!(cmp("2020", "2021", xsd::G_YEAR), Some(Ordering::Less));
+        assert_eq!(cmp("-0300", "0100", xsd::G_YEAR), Some(Ordering::Less));
+        assert_eq!(cmp("10000", "9999", xsd::G_YEAR), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_g_year_month_equality() {
+        assert_eq!(
+            cmp("2020-05", "2020-05", xsd::G_YEAR_MONTH),
+            Some(Ordering::Equal)
+        );
+        assert_ne!(
+            cmp("2020-05", "2020-06", xsd::G_YEAR_MONTH),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn test_g_year_month_ordering() {
+        assert_eq!(
+            cmp("2020-05", "2020-06", xsd::G_YEAR_MONTH),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            cmp("2021-01", "2020-12", xsd::G_YEAR_MONTH),
+            Some(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn test_partial_dates_of_different_types_are_incomparable() {
+        let year = Literal::new_typed_literal("2020", xsd::G_YEAR);
+        let year_month = Literal::new_typed_literal("2020-01", xsd::G_YEAR_MONTH);
+
+        let year = TypedValueRef::try_from(year.as_ref()).unwrap();
+        let year_month = TypedValueRef::try_from(year_month.as_ref()).unwrap();
+
+        assert_eq!(year.partial_cmp(&year_month), None);
+    }
+
+    #[test]
+    fn test_any_uri_is_compared_like_a_string() {
+        let any_uri = Literal::new_typed_literal("http://example.com/b", xsd::ANY_URI);
+        let any_uri = TypedValueRef::try_from(any_uri.as_ref()).unwrap();
+        let string =
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef::new("http://example.com/b"));
+        let smaller =
+            TypedValueRef::SimpleLiteral(SimpleLiteralRef::new("http://example.com/a"));
+
+        assert!(matches!(any_uri, TypedValueRef::OtherLiteral(_)));
+        assert_eq!(any_uri.partial_cmp(&string), Some(Ordering::Equal));
+        assert_eq!(string.partial_cmp(&any_uri), Some(Ordering::Equal));
+        assert_eq!(any_uri.partial_cmp(&smaller), Some(Ordering::Greater));
+        assert_eq!(
+            cmp("http://example.com/a", "http://example.com/b", xsd::ANY_URI),
+            Some(Ordering::Less)
+        );
+    }
+
+    fn cmp(a: &str, b: &str, datatype: NamedNodeRef<'_>) -> Option<Ordering> {
+        let a = Literal::new_typed_literal(a, datatype);
+        let b = Literal::new_typed_literal(b, datatype);
+
+        let a = TypedValueRef::try_from(a.as_ref()).unwrap();
+        let b = TypedValueRef::try_from(b.as_ref()).unwrap();
+        a.partial_cmp(&b)
+    }
+}
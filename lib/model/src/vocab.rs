@@ -1 +1,10 @@
 pub use oxrdf::vocab::*;
+
+pub mod owl {
+    //! [OWL](https://www.w3.org/TR/owl2-overview/) vocabulary.
+    use oxrdf::NamedNodeRef;
+
+    /// The property that states that two individuals are the same.
+    pub const SAME_AS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#sameAs");
+}
@@ -0,0 +1,110 @@
+use datafusion::arrow::compute::SortOptions;
+use datafusion::common::JoinType;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::config::ConfigOptions;
+use datafusion::error::Result as DFResult;
+use datafusion::physical_expr::{PhysicalExprRef, PhysicalSortExpr};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::expressions::Column;
+use datafusion::physical_plan::joins::{HashJoinExec, SortMergeJoinExec};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::{ExecutionPlan, ExecutionPlanProperties};
+use std::sync::Arc;
+
+/// A physical optimizer rule that replaces a hash join with a sort-merge join if both inputs are
+/// already sorted on the join key.
+///
+/// Quad pattern scans that leave only the join variable unbound produce their results in the
+/// order of the last index level (see
+/// [QuadPatternNode::order_hint](rdf_fusion_logical::quad_pattern::QuadPatternNode::order_hint)).
+/// Merging two such inputs avoids building a hash table for one of them and preserves the order
+/// of the left input.
+///
+/// Only inner and left joins on a single key are considered. The rule must run before
+/// `EnforceDistribution` and `EnforceSorting`, such that these rules can satisfy the requirements
+/// of the sort-merge join if the number of partitions changes.
+#[derive(Debug, Default)]
+pub struct MergeJoinSelection;
+
+impl MergeJoinSelection {
+    /// Creates a new [MergeJoinSelection].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl PhysicalOptimizerRule for MergeJoinSelection {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(|plan| {
+            let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+                return Ok(Transformed::no(plan));
+            };
+
+            Ok(match try_create_merge_join(join)? {
+                Some(merge_join) => Transformed::yes(merge_join),
+                None => Transformed::no(plan),
+            })
+        })
+        .map(|transformed| transformed.data)
+    }
+
+    fn name(&self) -> &str {
+        "merge_join_selection"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Creates a [SortMergeJoinExec] that is equivalent to `join`, if both inputs are sorted on the
+/// join key.
+fn try_create_merge_join(
+    join: &HashJoinExec,
+) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+    if !matches!(join.join_type(), JoinType::Inner | JoinType::Left) {
+        return Ok(None);
+    }
+
+    let [(left_key, right_key)] = join.on() else {
+        return Ok(None);
+    };
+    if !is_sorted_by(join.left(), left_key)? || !is_sorted_by(join.right(), right_key)? {
+        return Ok(None);
+    }
+
+    let merge_join: Arc<dyn ExecutionPlan> = Arc::new(SortMergeJoinExec::try_new(
+        Arc::clone(join.left()),
+        Arc::clone(join.right()),
+        join.on().to_vec(),
+        join.filter().cloned(),
+        *join.join_type(),
+        vec![SortOptions::default()],
+        join.null_equality(),
+    )?);
+
+    // The hash join may have embedded a projection of its output.
+    let Some(projection) = &join.projection else {
+        return Ok(Some(merge_join));
+    };
+    let schema = merge_join.schema();
+    let exprs = projection
+        .iter()
+        .map(|index| {
+            let name = schema.field(*index).name().to_owned();
+            let column: PhysicalExprRef = Arc::new(Column::new(&name, *index));
+            (column, name)
+        })
+        .collect::<Vec<_>>();
+    Ok(Some(Arc::new(ProjectionExec::try_new(exprs, merge_join)?)))
+}
+
+/// Returns whether the output of `plan` is sorted by `key` in the default sort order.
+fn is_sorted_by(plan: &Arc<dyn ExecutionPlan>, key: &PhysicalExprRef) -> DFResult<bool> {
+    plan.equivalence_properties()
+        .ordering_satisfy([PhysicalSortExpr::new_default(Arc::clone(key))])
+}
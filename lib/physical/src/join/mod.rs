@@ -0,0 +1,3 @@
+mod merge_join;
+
+pub use merge_join::MergeJoinSelection;
@@ -10,4 +10,6 @@
 
 extern crate core;
 
+pub mod join;
+pub mod minus;
 pub mod paths;
@@ -0,0 +1,5 @@
+mod physical;
+mod planner;
+
+pub use physical::MinusExec;
+pub use planner::MinusPlanner;
@@ -0,0 +1,451 @@
+use datafusion::arrow::array::{ArrayRef, BooleanArray, RecordBatch};
+use datafusion::arrow::compute::{concat_batches, filter_record_batch};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::row::{RowConverter, Rows, SortField};
+use datafusion::common::{internal_err, plan_err};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_expr::{Distribution, Partitioning};
+use datafusion::physical_plan::execution_plan::EmissionType;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+    RecordBatchStream,
+};
+use futures::{Stream, StreamExt};
+use rdf_fusion_model::DFResult;
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Formatter;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+/// Computes the SPARQL `MINUS` of two inputs.
+///
+/// A solution of the left-hand side is removed if there is a solution of the right-hand side that
+/// is compatible with it *and* that shares at least one bound variable with it. Hence, this is not
+/// a plain left-anti join:
+/// - If the inputs do not share any variables, no solution is removed.
+/// - A shared variable that is unbound in either solution does not prevent compatibility, but it
+///   also does not count as a shared bound variable.
+///
+/// The right-hand side is collected before the left-hand side is streamed. Its solutions are
+/// grouped by the set of shared variables that are bound, such that the left-hand side can be
+/// probed with hash lookups instead of comparing every pair of solutions.
+#[derive(Debug)]
+pub struct MinusExec {
+    /// The execution properties of this operator.
+    plan_properties: PlanProperties,
+    /// The solutions that are filtered.
+    lhs: Arc<dyn ExecutionPlan>,
+    /// The solutions that are subtracted from `lhs`.
+    rhs: Arc<dyn ExecutionPlan>,
+    /// The column indices (lhs, rhs) of the variables that appear in both inputs.
+    shared_columns: Vec<(usize, usize)>,
+}
+
+impl MinusExec {
+    /// Creates a new [MinusExec] that removes the solutions of `rhs` from `lhs`.
+    ///
+    /// Variables are matched by their column names. Shared columns must have the same data type.
+    pub fn try_new(
+        lhs: Arc<dyn ExecutionPlan>,
+        rhs: Arc<dyn ExecutionPlan>,
+    ) -> DFResult<Self> {
+        let lhs_schema = lhs.schema();
+        let rhs_schema = rhs.schema();
+
+        let mut shared_columns = Vec::new();
+        for (lhs_index, lhs_field) in lhs_schema.fields().iter().enumerate() {
+            let Ok(rhs_index) = rhs_schema.index_of(lhs_field.name()) else {
+                continue;
+            };
+
+            let rhs_field = rhs_schema.field(rhs_index);
+            if lhs_field.data_type() != rhs_field.data_type() {
+                return internal_err!(
+                    "Column {} of MinusExec has different data types: {} and {}",
+                    lhs_field.name(),
+                    lhs_field.data_type(),
+                    rhs_field.data_type()
+                );
+            }
+            shared_columns.push((lhs_index, rhs_index));
+        }
+
+        // Removing solutions does not change the order of the left-hand side.
+        let plan_properties = PlanProperties::new(
+            lhs.equivalence_properties().clone(),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental, // Emits results after the right-hand side is collected
+            lhs.boundedness(),
+        );
+        Ok(Self {
+            plan_properties,
+            lhs,
+            rhs,
+            shared_columns,
+        })
+    }
+
+    /// Returns the names of the variables that are shared by both inputs.
+    fn shared_variables(&self) -> Vec<String> {
+        let schema = self.lhs.schema();
+        self.shared_columns
+            .iter()
+            .map(|(lhs_index, _)| schema.field(*lhs_index).name().clone())
+            .collect()
+    }
+}
+
+impl ExecutionPlan for MinusExec {
+    fn name(&self) -> &str {
+        "MinusExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition, Distribution::SinglePartition]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false, false]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.lhs, &self.rhs]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let Ok([lhs, rhs]) = TryInto::<[_; 2]>::try_into(children) else {
+            return plan_err!("MinusExec requires exactly two children");
+        };
+
+        Ok(Arc::new(MinusExec::try_new(lhs, rhs)?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            // This operator requires a single partition as input.
+            return internal_err!(
+                "MinusExec does not support partitioning (got partition {partition})"
+            );
+        }
+
+        let lhs = execute_all_partitions(&self.lhs, &context)?;
+        if self.shared_columns.is_empty() {
+            // Without shared variables, no solution can be removed.
+            return Ok(lhs);
+        }
+
+        let rhs = execute_all_partitions(&self.rhs, &context)?;
+        Ok(Box::pin(MinusStream {
+            schema: self.schema(),
+            shared_columns: self.shared_columns.clone(),
+            state: MinusStreamState::CollectingRhs {
+                lhs,
+                rhs,
+                batches: Vec::new(),
+            },
+        }))
+    }
+}
+
+impl DisplayAs for MinusExec {
+    fn fmt_as(&self, _: DisplayFormatType, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MinusExec: on=[{}]", self.shared_variables().join(", "))
+    }
+}
+
+/// Executes all partitions of `plan` and merges them into a single stream.
+fn execute_all_partitions(
+    plan: &Arc<dyn ExecutionPlan>,
+    context: &Arc<TaskContext>,
+) -> DFResult<SendableRecordBatchStream> {
+    let partition_count = plan.output_partitioning().partition_count();
+    if partition_count == 1 {
+        return plan.execute(0, Arc::clone(context));
+    }
+
+    let all_partitions = (0..partition_count)
+        .map(|i| plan.execute(i, Arc::clone(context)))
+        .collect::<DFResult<Vec<_>>>()?;
+    Ok(Box::pin(RecordBatchStreamAdapter::new(
+        plan.schema(),
+        futures::stream::select_all(all_partitions),
+    )))
+}
+
+/// The solutions of the right-hand side of a [MinusExec].
+///
+/// Each shared variable is converted into its own [Rows], such that the key of a solution can be
+/// built for any subset of the shared variables.
+struct RhsSolutions {
+    /// The converters used for the shared variables. The same converters must be used for the
+    /// left-hand side to obtain comparable keys.
+    converters: Vec<RowConverter>,
+    /// The converted values of the shared variables.
+    values: Vec<Rows>,
+    /// The solutions grouped by the set of shared variables that are bound.
+    groups: Vec<(Vec<bool>, Vec<usize>)>,
+    /// The keys of the solutions in a group, projected to the variables that are also bound in a
+    /// solution of the left-hand side. Built lazily as new combinations are encountered.
+    keys: HashMap<(usize, Vec<bool>), HashSet<Vec<u8>>>,
+}
+
+impl RhsSolutions {
+    /// Creates a new [RhsSolutions] from the shared `columns` of the right-hand side.
+    fn try_new(columns: &[ArrayRef]) -> DFResult<Self> {
+        let converters = columns
+            .iter()
+            .map(|column| {
+                RowConverter::new(vec![SortField::new(column.data_type().clone())])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = convert_columns(&converters, columns)?;
+
+        let num_rows = columns.first().map_or(0, |column| column.len());
+        let mut groups: HashMap<Vec<bool>, Vec<usize>> = HashMap::new();
+        for row in 0..num_rows {
+            let bound = columns.iter().map(|c| c.is_valid(row)).collect::<Vec<_>>();
+            // A solution without bound shared variables cannot remove any solution.
+            if bound.contains(&true) {
+                groups.entry(bound).or_default().push(row);
+            }
+        }
+
+        Ok(Self {
+            converters,
+            values,
+            groups: groups.into_iter().collect(),
+            keys: HashMap::new(),
+        })
+    }
+
+    /// Returns a mask of the solutions in `columns` that are not removed by any solution of the
+    /// right-hand side.
+    fn retain_mask(
+        &mut self,
+        columns: &[ArrayRef],
+        num_rows: usize,
+    ) -> DFResult<Vec<bool>> {
+        let values = convert_columns(&self.converters, columns)?;
+
+        let mut result = Vec::with_capacity(num_rows);
+        for row in 0..num_rows {
+            let bound = columns.iter().map(|c| c.is_valid(row)).collect::<Vec<_>>();
+            result.push(!self.is_removed(&values, &bound, row));
+        }
+        Ok(result)
+    }
+
+    /// Checks whether a compatible solution that shares a bound variable with the solution at
+    /// `row` exists.
+    fn is_removed(&mut self, values: &[Rows], bound: &[bool], row: usize) -> bool {
+        for group in 0..self.groups.len() {
+            let shared = self.groups[group]
+                .0
+                .iter()
+                .zip(bound)
+                .map(|(rhs, lhs)| *rhs && *lhs)
+                .collect::<Vec<_>>();
+            if !shared.contains(&true) {
+                continue;
+            }
+
+            let key = build_key(values, &shared, row);
+            let keys = match self.keys.entry((group, shared)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let shared = &entry.key().1;
+                    let keys = self.groups[group]
+                        .1
+                        .iter()
+                        .map(|rhs_row| build_key(&self.values, shared, *rhs_row))
+                        .collect();
+                    entry.insert(keys)
+                }
+            };
+            if keys.contains(&key) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Converts each column with its own converter.
+fn convert_columns(
+    converters: &[RowConverter],
+    columns: &[ArrayRef],
+) -> DFResult<Vec<Rows>> {
+    converters
+        .iter()
+        .zip(columns)
+        .map(|(converter, column)| {
+            converter
+                .convert_columns(&[Arc::clone(column)])
+                .map_err(Into::into)
+        })
+        .collect()
+}
+
+/// Builds the key of the solution at `row`, consisting of the variables that are `selected`.
+///
+/// The row format of each value is self-delimiting, so the concatenation is unambiguous.
+fn build_key(values: &[Rows], selected: &[bool], row: usize) -> Vec<u8> {
+    let mut key = Vec::new();
+    for (rows, _) in values
+        .iter()
+        .zip(selected)
+        .filter(|(_, selected)| **selected)
+    {
+        key.extend_from_slice(rows.row(row).as_ref());
+    }
+    key
+}
+
+/// The stream that computes the result of a [MinusExec].
+struct MinusStream {
+    /// The schema of the stream.
+    schema: SchemaRef,
+    /// See [MinusExec] for details.
+    shared_columns: Vec<(usize, usize)>,
+    /// The current state of the stream.
+    state: MinusStreamState,
+}
+
+/// Tracks the state of the [MinusStream].
+enum MinusStreamState {
+    /// Collecting the solutions of the right-hand side.
+    CollectingRhs {
+        lhs: SendableRecordBatchStream,
+        rhs: SendableRecordBatchStream,
+        batches: Vec<RecordBatch>,
+    },
+    /// Filtering the solutions of the left-hand side.
+    ProbingLhs {
+        lhs: SendableRecordBatchStream,
+        rhs: RhsSolutions,
+    },
+    /// Done - all solutions have been emitted
+    Done,
+}
+
+impl MinusStream {
+    fn poll_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<DFResult<RecordBatch>>> {
+        loop {
+            match &mut self.state {
+                MinusStreamState::CollectingRhs { rhs, batches, .. } => {
+                    match ready!(rhs.poll_next_unpin(cx)) {
+                        Some(Ok(batch)) => batches.push(batch),
+                        Some(Err(e)) => {
+                            self.state = MinusStreamState::Done;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                        None => {
+                            if let Err(e) = self.finish_rhs() {
+                                self.state = MinusStreamState::Done;
+                                return Poll::Ready(Some(Err(e)));
+                            }
+                        }
+                    }
+                }
+                MinusStreamState::ProbingLhs { lhs, rhs } => {
+                    match ready!(lhs.poll_next_unpin(cx)) {
+                        Some(Ok(batch)) => {
+                            let columns = self
+                                .shared_columns
+                                .iter()
+                                .map(|(lhs_index, _)| {
+                                    Arc::clone(batch.column(*lhs_index))
+                                })
+                                .collect::<Vec<_>>();
+                            let result = rhs
+                                .retain_mask(&columns, batch.num_rows())
+                                .and_then(|mask| {
+                                    let mask = BooleanArray::from(mask);
+                                    Ok(filter_record_batch(&batch, &mask)?)
+                                });
+                            match result {
+                                Ok(batch) if batch.num_rows() == 0 => {}
+                                Ok(batch) => return Poll::Ready(Some(Ok(batch))),
+                                Err(e) => {
+                                    self.state = MinusStreamState::Done;
+                                    return Poll::Ready(Some(Err(e)));
+                                }
+                            }
+                        }
+                        Some(Err(e)) => {
+                            self.state = MinusStreamState::Done;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                        None => {
+                            self.state = MinusStreamState::Done;
+                        }
+                    }
+                }
+                MinusStreamState::Done => return Poll::Ready(None),
+            }
+        }
+    }
+
+    /// Indexes the collected solutions of the right-hand side and starts probing the left-hand
+    /// side.
+    fn finish_rhs(&mut self) -> DFResult<()> {
+        let MinusStreamState::CollectingRhs { lhs, rhs, batches } =
+            std::mem::replace(&mut self.state, MinusStreamState::Done)
+        else {
+            return internal_err!("MinusStream is not collecting the right-hand side");
+        };
+
+        let rhs_batch = concat_batches(&rhs.schema(), &batches)?;
+        let columns = self
+            .shared_columns
+            .iter()
+            .map(|(_, rhs_index)| Arc::clone(rhs_batch.column(*rhs_index)))
+            .collect::<Vec<_>>();
+        self.state = MinusStreamState::ProbingLhs {
+            lhs,
+            rhs: RhsSolutions::try_new(&columns)?,
+        };
+        Ok(())
+    }
+}
+
+impl RecordBatchStream for MinusStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+impl Stream for MinusStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.poll_inner(cx)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::minus::MinusExec;
+use async_trait::async_trait;
+use datafusion::common::plan_err;
+use datafusion::error::Result as DFResult;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+use rdf_fusion_logical::minus::MinusNode;
+use std::sync::Arc;
+
+/// Planner for Minus nodes
+#[derive(Debug, Default)]
+pub struct MinusPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for MinusPlanner {
+    /// Converts a logical Minus node into its physical execution plan
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+        if node.as_any().downcast_ref::<MinusNode>().is_none() {
+            return Ok(None);
+        }
+
+        let ([_, _], [lhs, rhs]) = (logical_inputs, physical_inputs) else {
+            return plan_err!("Minus node must have exactly two inputs");
+        };
+
+        let physical_plan = MinusExec::try_new(Arc::clone(lhs), Arc::clone(rhs))?;
+        Ok(Some(Arc::new(physical_plan)))
+    }
+}
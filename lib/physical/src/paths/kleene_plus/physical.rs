@@ -1,7 +1,9 @@
 use datafusion::arrow::array::RecordBatchOptions;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::common::{SchemaExt, exec_datafusion_err, internal_err, plan_err};
+use datafusion::common::{
+    SchemaExt, exec_datafusion_err, exec_err, internal_err, plan_err,
+};
 use datafusion::execution::{SendableRecordBatchStream, TaskContext};
 use datafusion::physical_expr::{EquivalenceProperties, Partitioning};
 use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
@@ -19,33 +21,32 @@ use rdf_fusion_encoding::plain_term::{
     PLAIN_TERM_ENCODING, PlainTermArrayElementBuilder,
 };
 use rdf_fusion_encoding::{EncodingArray, TermDecoder, TermEncoding};
-use rdf_fusion_logical::paths::PATH_TABLE_SCHEMA;
+use rdf_fusion_logical::paths::{
+    PATH_TABLE_SCHEMA, PathDepthExceeded, PathDepthLimit, PathEndpoints,
+};
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::{GraphName, Term};
 use std::any::Any;
 use std::clone::Clone;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
-use std::hash::Hash;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 
-/// Represents a path in the closure.
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Path {
-    /// The graph name that this path belongs to.
-    ///
-    /// This is important if cross-graph paths are disabled.
-    graph: GraphName,
-    /// The start of the path.
-    start: Term,
-    /// The end of the path.
-    end: Term,
-}
-
 /// Represents a Kleene-plus path closure execution plan. This plan computes the Kleene-plus closure
 /// of the inner paths. This closure is the result of the `+` operator in SPARQL property paths.
+///
+/// The closure is computed by expanding the frontier of each start node until no new nodes are
+/// reachable. Only the nodes that have been visited from the current start node are kept in
+/// memory, which ensures termination on cyclic graphs. The results are emitted in batches while
+/// the remaining start nodes are expanded. Hence, the memory consumption is bounded by the inner
+/// paths and the nodes reachable from a single start node, instead of all paths in the closure.
+///
+/// If the [PathEndpoints] are fixed, only the paths that start (or end) at the given terms are
+/// computed. If a [PathDepthLimit] is given, the frontier of a start node is expanded at most
+/// `max_depth` times. Depending on the limit, the operator then either fails or omits the nodes
+/// that are further away.
 #[derive(Debug)]
 pub struct KleenePlusClosureExec {
     /// The execution properties of this operator.
@@ -54,16 +55,23 @@ pub struct KleenePlusClosureExec {
     inner: Arc<dyn ExecutionPlan>,
     /// See [rdf_fusion_logical::paths::KleenePlusClosureNode::disallow_cross_graph_paths] for details.
     allow_cross_graph_paths: bool,
+    /// The fixed endpoints of the computed paths.
+    endpoints: PathEndpoints,
+    /// The maximum number of steps from a start node, if any.
+    depth_limit: Option<PathDepthLimit>,
 }
 
 impl KleenePlusClosureExec {
     /// Creates a new [KleenePlusClosureExec] over the `inner` [ExecutionPlan].
     ///
     /// The `allow_cross_graph_paths` argument indicates whether paths are created across multiple
-    /// graphs.
+    /// graphs. Only paths between the given `endpoints` are computed. Paths that are longer than
+    /// the `depth_limit` are not followed.
     pub fn try_new(
         inner: Arc<dyn ExecutionPlan>,
         allow_cross_graph_paths: bool,
+        endpoints: PathEndpoints,
+        depth_limit: Option<PathDepthLimit>,
     ) -> DFResult<Self> {
         if !inner
             .schema()
@@ -80,13 +88,15 @@ impl KleenePlusClosureExec {
         let plan_properties = PlanProperties::new(
             EquivalenceProperties::new(inner.schema()),
             Partitioning::UnknownPartitioning(1), // Computation requires all data in one partition
-            EmissionType::Final, // Emits results only after full computation
-            Boundedness::Bounded, // Assumes the closure computation terminates
+            EmissionType::Incremental, // Emits results while expanding the start nodes
+            Boundedness::Bounded,      // The closure computation terminates
         );
         Ok(Self {
             plan_properties,
             inner,
             allow_cross_graph_paths,
+            endpoints,
+            depth_limit,
         })
     }
 }
@@ -122,6 +132,8 @@ impl ExecutionPlan for KleenePlusClosureExec {
         let exec = KleenePlusClosureExec::try_new(
             Arc::clone(&children[0]),
             self.allow_cross_graph_paths,
+            self.endpoints.clone(),
+            self.depth_limit,
         )?;
         Ok(Arc::new(exec))
     }
@@ -138,6 +150,7 @@ impl ExecutionPlan for KleenePlusClosureExec {
             );
         }
 
+        let batch_size = context.session_config().batch_size();
         let partition_count = self.inner.output_partitioning().partition_count();
         let all_partitions = (0..partition_count)
             .map(|i| self.inner.execute(i, Arc::clone(&context)))
@@ -152,13 +165,117 @@ impl ExecutionPlan for KleenePlusClosureExec {
             Box::pin(input_stream),
             Arc::clone(&schema),
             self.allow_cross_graph_paths,
+            self.endpoints.clone(),
+            self.depth_limit,
+            batch_size,
         )))
     }
 }
 
 impl DisplayAs for KleenePlusClosureExec {
     fn fmt_as(&self, _: DisplayFormatType, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "KleenePlusPathExec")
+        write!(f, "KleenePlusPathExec:{}", self.endpoints)?;
+        if let Some(depth_limit) = &self.depth_limit {
+            write!(f, " {depth_limit}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The inner paths of the closure, grouped by their graph and their start node.
+///
+/// If only the target of the closure is fixed, the inner paths are stored in reverse, such that
+/// the closure can be expanded from the target.
+#[derive(Default)]
+struct InnerPaths {
+    edges: HashMap<GraphName, HashMap<Term, Vec<Term>>>,
+}
+
+impl InnerPaths {
+    /// Returns the nodes that are reachable with a single inner path of `graph` from `node`.
+    fn successors<'a>(
+        &'a self,
+        graph: &GraphName,
+        node: &Term,
+    ) -> impl Iterator<Item = &'a Term> {
+        self.edges
+            .get(graph)
+            .and_then(|edges| edges.get(node))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the nodes that are reachable with a single inner path from `node`, regardless of
+    /// the graph of the path.
+    fn successors_in_any_graph<'a>(
+        &'a self,
+        node: &'a Term,
+    ) -> impl Iterator<Item = &'a Term> {
+        self.edges
+            .values()
+            .filter_map(move |edges| edges.get(node))
+            .flatten()
+    }
+
+    /// Returns all nodes that are reachable with one or more inner paths from `start`. The first
+    /// path must be part of `graph`. The following paths must also be part of `graph`, unless
+    /// `allow_cross_graph_paths` is set.
+    ///
+    /// Every node is visited at most once, which ensures termination on cyclic paths. The nodes
+    /// are visited in the order of their distance from `start`. If `max_depth` is set, only the
+    /// nodes that are reachable with at most `max_depth` inner paths are returned. The returned
+    /// flag indicates whether further nodes would have been reachable.
+    fn reachable<'a>(
+        &'a self,
+        graph: &GraphName,
+        start: &Term,
+        allow_cross_graph_paths: bool,
+        max_depth: Option<usize>,
+    ) -> (Vec<&'a Term>, bool) {
+        let mut visited = HashSet::new();
+        let mut reachable = Vec::new();
+        let mut frontier = self.successors(graph, start).collect::<Vec<_>>();
+        let mut depth = 1;
+
+        loop {
+            frontier.retain(|node| !visited.contains(node));
+            if frontier.is_empty() {
+                return (reachable, false);
+            }
+            if max_depth.is_some_and(|max_depth| depth > max_depth) {
+                return (reachable, true);
+            }
+
+            let mut next_frontier = Vec::new();
+            for node in frontier {
+                if !visited.insert(node) {
+                    continue;
+                }
+                reachable.push(node);
+
+                if allow_cross_graph_paths {
+                    next_frontier.extend(self.successors_in_any_graph(node));
+                } else {
+                    next_frontier.extend(self.successors(graph, node));
+                }
+            }
+            frontier = next_frontier;
+            depth += 1;
+        }
+    }
+
+    /// Returns the start nodes of the closure. If `fixed_start` is set, only this node is
+    /// considered.
+    fn start_nodes(&self, fixed_start: Option<&Term>) -> Vec<(GraphName, Term)> {
+        self.edges
+            .iter()
+            .flat_map(|(graph, edges)| {
+                edges
+                    .keys()
+                    .filter(|node| fixed_start.is_none_or(|start| *node == start))
+                    .map(|node| (graph.clone(), node.clone()))
+            })
+            .collect()
     }
 }
 
@@ -170,32 +287,27 @@ struct KleenePlusClosureStream {
     schema: SchemaRef,
     /// See [KleenePlusClosureExec] for details on `allow_cross_graph_paths`.
     allow_cross_graph_paths: bool,
-
-    // State for the transitive closure computation
-    /// A map from graph names to sets of direct (start, end) path pairs.
-    ///
-    /// This stores the initial paths grouped by graph name, which serves as the
-    /// base case for the transitive closure computation.
-    initial_paths_map: HashMap<GraphName, HashSet<(Term, Term)>>,
-    /// The set of all paths discovered so far in the transitive closure.
-    ///
-    /// This accumulates all paths (direct and transitive) during the computation
-    /// and is used to avoid duplicates.
-    all_paths: HashSet<Path>,
-    /// The current set of newly discovered paths in this iteration.
-    ///
-    /// This represents the "delta" or frontier of paths discovered in the current
-    /// iteration that will be used to find new paths in the next iteration.
-    current_delta: Vec<Path>,
+    /// The fixed endpoints of the computed paths.
+    endpoints: PathEndpoints,
+    /// The maximum number of steps from a start node, if any.
+    depth_limit: Option<PathDepthLimit>,
+    /// The number of paths after which a batch is emitted.
+    batch_size: usize,
+    /// The inner paths that are collected from the input.
+    inner_paths: InnerPaths,
+    /// The start nodes that have not been expanded yet.
+    pending_starts: Vec<(GraphName, Term)>,
+    /// The paths that have been computed but not emitted yet.
+    output: OutputBuilder,
 }
 
 /// Enum to track the state of our stream processing
 enum KleenePlusPathStreamState {
     /// Initial state - need to collect input batches
     CollectingInput { stream: SendableRecordBatchStream },
-    /// Computing the closure
-    Computing,
-    /// Done - either yielding the final batch or already finished
+    /// Expanding the start nodes and emitting the computed paths
+    Expanding,
+    /// Done - all paths have been emitted
     Done,
     /// Error state
     Error,
@@ -204,22 +316,34 @@ enum KleenePlusPathStreamState {
 impl KleenePlusClosureStream {
     /// Creates a new [KleenePlusClosureStream].
     ///
-    /// See [KleenePlusClosureExec] for details on `allow_cross_graph_paths`.
+    /// See [KleenePlusClosureExec] for details on `allow_cross_graph_paths`, `endpoints`, and
+    /// `depth_limit`.
     fn new(
         input: SendableRecordBatchStream,
         schema: SchemaRef,
         allow_cross_graph_paths: bool,
+        endpoints: PathEndpoints,
+        depth_limit: Option<PathDepthLimit>,
+        batch_size: usize,
     ) -> Self {
         Self {
             state: KleenePlusPathStreamState::CollectingInput { stream: input },
             schema,
             allow_cross_graph_paths,
-            initial_paths_map: HashMap::new(),
-            all_paths: HashSet::new(),
-            current_delta: Vec::new(),
+            endpoints,
+            depth_limit,
+            batch_size: batch_size.max(1),
+            inner_paths: InnerPaths::default(),
+            pending_starts: Vec::new(),
+            output: OutputBuilder::default(),
         }
     }
 
+    /// Indicates whether the closure is expanded from the fixed target instead of the sources.
+    fn is_backward(&self) -> bool {
+        self.endpoints.source.is_none() && self.endpoints.target.is_some()
+    }
+
     fn poll_inner(
         &mut self,
         cx: &mut Context<'_>,
@@ -239,54 +363,43 @@ impl KleenePlusClosureStream {
                             return Poll::Ready(Some(Err(e)));
                         }
                         None => {
-                            self.state = KleenePlusPathStreamState::Computing;
+                            let fixed_start = if self.is_backward() {
+                                self.endpoints.target.as_ref()
+                            } else {
+                                self.endpoints.source.as_ref()
+                            };
+                            self.pending_starts =
+                                self.inner_paths.start_nodes(fixed_start);
+                            self.state = KleenePlusPathStreamState::Expanding;
                         }
                     }
                 }
-                KleenePlusPathStreamState::Computing => {
-                    // All input collected, jump to Done state
-                    if self.all_paths.is_empty() {
-                        self.state = KleenePlusPathStreamState::Done;
-                        continue;
-                    }
-
-                    // Compute one iteration of the closure
-                    let mut next_delta = Vec::new();
-
-                    for current_path in &self.current_delta {
-                        if self.allow_cross_graph_paths {
-                            Self::compute_new_cross_graph_paths(
-                                &self.initial_paths_map,
-                                &mut self.all_paths,
-                                &mut next_delta,
-                                current_path,
-                            );
-                        } else {
-                            Self::compute_new_single_graph_paths(
-                                &self.initial_paths_map,
-                                &mut self.all_paths,
-                                &mut next_delta,
-                                &current_path.graph,
-                                current_path,
-                            );
+                KleenePlusPathStreamState::Expanding => {
+                    while self.output.len() < self.batch_size {
+                        let Some((graph, start)) = self.pending_starts.pop() else {
+                            break;
+                        };
+                        if let Err(e) = self.expand(&graph, &start) {
+                            self.state = KleenePlusPathStreamState::Error;
+                            return Poll::Ready(Some(Err(e)));
                         }
                     }
 
-                    if next_delta.is_empty() {
-                        // Closure computation complete, create the final batch
-                        return match self.create_output_batch() {
-                            Ok(batch) => {
-                                self.state = KleenePlusPathStreamState::Done;
-                                Poll::Ready(Some(Ok(batch)))
-                            }
-                            Err(e) => {
-                                self.state = KleenePlusPathStreamState::Error;
-                                Poll::Ready(Some(Err(e)))
-                            }
-                        };
+                    if self.pending_starts.is_empty() {
+                        self.state = KleenePlusPathStreamState::Done;
+                    }
+                    if self.output.len() == 0 {
+                        continue;
                     }
 
-                    self.current_delta = next_delta;
+                    let output = std::mem::take(&mut self.output);
+                    return match output.finish(&self.schema) {
+                        Ok(batch) => Poll::Ready(Some(Ok(batch))),
+                        Err(e) => {
+                            self.state = KleenePlusPathStreamState::Error;
+                            Poll::Ready(Some(Err(e)))
+                        }
+                    };
                 }
                 KleenePlusPathStreamState::Done => {
                     return Poll::Ready(None);
@@ -301,8 +414,6 @@ impl KleenePlusClosureStream {
     }
 
     /// Collects the inner paths of a single [RecordBatch].
-    ///
-    /// This adds all inner paths to the `initial_paths_map`, `all_paths`, and the `current_delta`.
     fn collect_next_batch(&mut self, batch: &RecordBatch) -> DFResult<()> {
         let graph_names =
             PLAIN_TERM_ENCODING.try_new_array(Arc::clone(batch.column(0)))?;
@@ -313,6 +424,7 @@ impl KleenePlusClosureStream {
         let starts = DefaultPlainTermDecoder::decode_terms(&starts);
         let ends = DefaultPlainTermDecoder::decode_terms(&ends);
 
+        let is_backward = self.is_backward();
         for (graph, start, end) in izip!(graph_names, starts, ends) {
             let graph = graph.map_err(|_| {
                 exec_datafusion_err!("Could not obtain graph value from inner paths.")
@@ -324,97 +436,100 @@ impl KleenePlusClosureStream {
                 exec_datafusion_err!("Could not obtain end value from inner paths.")
             })?;
 
-            let path_tuple = (start.into_owned(), end.into_owned());
-            self.initial_paths_map
+            let (from, to) = if is_backward {
+                (end, start)
+            } else {
+                (start, end)
+            };
+            self.inner_paths
+                .edges
                 .entry(graph.into_owned())
                 .or_default()
-                .insert(path_tuple);
-
-            let path = Path {
-                graph: graph.into_owned(),
-                start: start.into_owned(),
-                end: end.into_owned(),
-            };
-
-            self.all_paths.insert(path.clone()); // All inner paths are part of the closure.
-            self.current_delta.push(path); // All inner paths must be part of the next iteration.
+                .entry(from.into_owned())
+                .or_default()
+                .push(to.into_owned());
         }
         Ok(())
     }
 
-    fn compute_new_cross_graph_paths(
-        initial_paths_map: &HashMap<GraphName, HashSet<(Term, Term)>>,
-        all_paths: &mut HashSet<Path>,
-        next_delta: &mut Vec<Path>,
-        current_path: &Path,
-    ) {
-        for graph in initial_paths_map.keys() {
-            Self::compute_new_single_graph_paths(
-                initial_paths_map,
-                all_paths,
-                next_delta,
-                graph,
-                current_path,
+    /// Computes all paths of the closure that start at `start` and adds them to the output.
+    ///
+    /// Returns an error if the depth limit is exceeded and [PathDepthExceeded::Error] is used.
+    fn expand(&mut self, graph: &GraphName, start: &Term) -> DFResult<()> {
+        let is_backward = self.is_backward();
+        let (reachable, exceeded) = self.inner_paths.reachable(
+            graph,
+            start,
+            self.allow_cross_graph_paths,
+            self.depth_limit.map(|limit| limit.max_depth),
+        );
+
+        if let Some(limit) = self.depth_limit
+            && exceeded
+            && limit.on_exceeded == PathDepthExceeded::Error
+        {
+            return exec_err!(
+                "The transitive property path at {start} exceeds the maximum depth of {}",
+                limit.max_depth
             );
         }
-    }
-
-    fn compute_new_single_graph_paths(
-        initial_paths_map: &HashMap<GraphName, HashSet<(Term, Term)>>,
-        all_paths: &mut HashSet<Path>,
-        next_delta: &mut Vec<Path>,
-        graph_name: &GraphName,
-        current_path: &Path,
-    ) {
-        if let Some(initial_paths_from_b) = initial_paths_map.get(graph_name) {
-            for (initial_start_b, initial_end_c) in initial_paths_from_b {
-                if &current_path.end == initial_start_b {
-                    let path_ac = Path {
-                        graph: current_path.graph.clone(),
-                        start: current_path.start.clone(),
-                        end: initial_end_c.clone(),
-                    };
 
-                    if all_paths.insert(path_ac.clone()) {
-                        next_delta.push(path_ac);
-                    }
-                }
+        for node in reachable {
+            if is_backward {
+                self.output.append(graph, node, start);
+            } else if self
+                .endpoints
+                .target
+                .as_ref()
+                .is_none_or(|target| target == node)
+            {
+                self.output.append(graph, start, node);
             }
         }
+        Ok(())
     }
+}
 
-    /// Creates a [RecordBatch] from the internal state of `self`.
-    fn create_output_batch(&self) -> DFResult<RecordBatch> {
-        let mut graph_builder = PlainTermArrayElementBuilder::default();
-        let mut start_builder = PlainTermArrayElementBuilder::default();
-        let mut end_builder = PlainTermArrayElementBuilder::default();
+/// Builds the output batches of the closure.
+#[derive(Default)]
+struct OutputBuilder {
+    graph_builder: PlainTermArrayElementBuilder,
+    start_builder: PlainTermArrayElementBuilder,
+    end_builder: PlainTermArrayElementBuilder,
+    len: usize,
+}
 
-        for path in &self.all_paths {
-            match &path.graph {
-                GraphName::NamedNode(named) => {
-                    graph_builder.append_named_node(named.as_ref())
-                }
-                GraphName::BlankNode(bnode) => {
-                    graph_builder.append_blank_node(bnode.as_ref())
-                }
-                GraphName::DefaultGraph => graph_builder.append_null(),
+impl OutputBuilder {
+    /// Returns the number of paths in the builder.
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Adds a path from `start` to `end` in `graph`.
+    fn append(&mut self, graph: &GraphName, start: &Term, end: &Term) {
+        match graph {
+            GraphName::NamedNode(named) => {
+                self.graph_builder.append_named_node(named.as_ref())
+            }
+            GraphName::BlankNode(bnode) => {
+                self.graph_builder.append_blank_node(bnode.as_ref())
             }
-            start_builder.append_term(path.start.as_ref());
-            end_builder.append_term(path.end.as_ref());
+            GraphName::DefaultGraph => self.graph_builder.append_null(),
         }
+        self.start_builder.append_term(start.as_ref());
+        self.end_builder.append_term(end.as_ref());
+        self.len += 1;
+    }
 
-        let graph_array = graph_builder.finish();
-        let start_array = start_builder.finish();
-        let end_array = end_builder.finish();
-
-        let options =
-            RecordBatchOptions::new().with_row_count(Some(self.all_paths.len()));
+    /// Creates a [RecordBatch] from the paths in the builder.
+    fn finish(self, schema: &SchemaRef) -> DFResult<RecordBatch> {
+        let options = RecordBatchOptions::new().with_row_count(Some(self.len));
         RecordBatch::try_new_with_options(
-            Arc::clone(&self.schema),
+            Arc::clone(schema),
             vec![
-                graph_array.into_array_ref(),
-                start_array.into_array_ref(),
-                end_array.into_array_ref(),
+                self.graph_builder.finish().into_array_ref(),
+                self.start_builder.finish().into_array_ref(),
+                self.end_builder.finish().into_array_ref(),
             ],
             &options,
         )
@@ -40,6 +40,8 @@ impl ExtensionPlanner for KleenePlusPathPlanner {
             let physical_plan = KleenePlusClosureExec::try_new(
                 Arc::clone(&physical_inputs[0]),
                 !node.disallow_cross_graph_paths(),
+                node.endpoints().clone(),
+                node.depth_limit(),
             )?;
 
             Ok(Some(Arc::new(physical_plan)))
@@ -1,6 +1,6 @@
 #![allow(clippy::panic)]
 
-use codspeed_criterion_compat::{Criterion, criterion_group, criterion_main};
+use codspeed_criterion_compat::{BatchSize, Criterion, criterion_group, criterion_main};
 use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::prelude::SessionConfig;
 use futures::StreamExt;
@@ -9,7 +9,7 @@ use rand::prelude::{SliceRandom, SmallRng};
 use rdf_fusion::model::{NamedOrBlankNode, Term};
 use rdf_fusion::store::Store;
 use rdf_fusion_execution::results::QueryResults;
-use rdf_fusion_model::{GraphName, NamedNode, Quad};
+use rdf_fusion_model::{GraphName, GraphNameRef, NamedNode, Quad};
 use tokio::runtime::Builder;
 
 /// This benchmark measures transactionally inserting synthetic quads into the store.
@@ -51,6 +51,38 @@ fn store_load(c: &mut Criterion) {
                 .unwrap();
         });
     });
+
+    c.bench_function("Store::extend (new quads)", |b| {
+        let quads = generate_quads(10_500).collect::<Vec<_>>();
+        let (existing, new) = quads.split_at(10_000);
+        b.iter_batched(
+            || runtime.block_on(prepare_store_with_quads(existing)),
+            |store| {
+                runtime
+                    .block_on(store.extend(new.iter().map(Quad::as_ref)))
+                    .unwrap();
+            },
+            BatchSize::PerIteration,
+        );
+    });
+}
+
+/// This benchmark measures removing all quads of a graph.
+fn store_clear_graph(c: &mut Criterion) {
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+    c.bench_function("Store::clear_graph", |b| {
+        let quads = generate_quads(10_000).collect::<Vec<_>>();
+        b.iter_batched(
+            || runtime.block_on(prepare_store_with_quads(&quads)),
+            |store| {
+                runtime
+                    .block_on(store.clear_graph(GraphNameRef::DefaultGraph))
+                    .unwrap();
+            },
+            BatchSize::PerIteration,
+        );
+    });
 }
 
 /// These benchmarks measure the duration of running a simple query (1 triple pattern). Hopefully,
@@ -126,7 +158,7 @@ fn store_single_pattern_with_fixed_element(c: &mut Criterion) {
     );
 }
 
-criterion_group!(store_write, store_load);
+criterion_group!(store_write, store_load, store_clear_graph);
 criterion_group!(
     store_query,
     store_single_pattern,
@@ -135,11 +167,15 @@ criterion_group!(
 criterion_main!(store_write, store_query);
 
 async fn prepare_store_with_generated_triples(n: usize) -> Store {
+    let quads = generate_quads(n).collect::<Vec<_>>();
+    prepare_store_with_quads(&quads).await
+}
+
+async fn prepare_store_with_quads(quads: &[Quad]) -> Store {
     let store = Store::new_with_datafusion_config(
         SessionConfig::new().with_target_partitions(1),
         RuntimeEnv::default().into(),
     );
-    let quads = generate_quads(n).collect::<Vec<_>>();
     store.extend(quads.iter().map(Quad::as_ref)).await.unwrap();
     store
 }
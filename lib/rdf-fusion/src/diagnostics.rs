@@ -0,0 +1,57 @@
+//! Reports that help with understanding why a query does not return the expected results.
+//!
+//! See [Store::diagnose_pattern](crate::store::Store::diagnose_pattern) for the entry point.
+
+/// The result of diagnosing a quad pattern with
+/// [Store::diagnose_pattern](crate::store::Store::diagnose_pattern).
+///
+/// Each component of the pattern is diagnosed on its own. This allows pinpointing the component
+/// that is responsible for an empty result (e.g., a typo in a predicate IRI).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternDiagnosis {
+    /// The diagnosis of the subject.
+    pub subject: ComponentDiagnosis,
+    /// The diagnosis of the predicate.
+    pub predicate: ComponentDiagnosis,
+    /// The diagnosis of the object.
+    pub object: ComponentDiagnosis,
+    /// The diagnosis of the graph name.
+    pub graph_name: ComponentDiagnosis,
+    /// The number of quads that match the entire pattern.
+    pub matching_quads: usize,
+}
+
+impl PatternDiagnosis {
+    /// Returns whether at least one quad matches the entire pattern.
+    pub fn has_matches(&self) -> bool {
+        self.matching_quads > 0
+    }
+}
+
+/// The diagnosis of a single component of a quad pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentDiagnosis {
+    /// The component is not bound and matches any term.
+    Unbound,
+    /// The term has never been stored in the store. This usually indicates a typo in an IRI or
+    /// literal.
+    ///
+    /// Only reported by storage layers that expose an object id mapping. Note that terms are
+    /// never removed from the mapping, even if all quads that contain them have been removed.
+    UnknownTerm,
+    /// The term is known to the store but no quad contains it at the position of this
+    /// component (e.g., an IRI that is only used as an object is used as a predicate).
+    NotInPosition,
+    /// The given number of quads contain the term at the position of this component.
+    Present(usize),
+}
+
+impl ComponentDiagnosis {
+    /// Returns whether the component can match at least one quad on its own.
+    pub fn is_satisfiable(&self) -> bool {
+        matches!(
+            self,
+            ComponentDiagnosis::Unbound | ComponentDiagnosis::Present(_)
+        )
+    }
+}
@@ -1,9 +1,129 @@
 use oxrdfio::{RdfFormat, RdfParseError};
+use rdf_fusion_execution::sparql::SparqlSyntaxError;
 use rdf_fusion_execution::sparql::error::QueryEvaluationError;
-use rdf_fusion_model::IriParseError;
 use rdf_fusion_model::StorageError;
+use rdf_fusion_model::{IriParseError, NamedOrBlankNode};
 use std::io;
 
+/// An error raised by the [`Store`](crate::store::Store) API.
+///
+/// The individual operations of the store return more specific errors (e.g., [LoaderError]). All
+/// of them can be converted into a [StoreError], which groups the errors by their cause. This
+/// allows applications to handle all store errors in a single place while still distinguishing
+/// between invalid input (see [StoreError::is_client_error]) and internal errors.
+///
+/// ```
+/// use rdf_fusion::error::StoreError;
+/// use rdf_fusion::store::Store;
+///
+/// # tokio_test::block_on(async {
+/// async fn run(store: &Store, query: &str) -> Result<(), StoreError> {
+///     store.query(query).await?;
+///     Ok(())
+/// }
+///
+/// let error = run(&Store::default(), "SELECT * WHERE {").await.unwrap_err();
+/// assert!(matches!(error, StoreError::QueryParsing(_)));
+/// assert!(error.is_client_error());
+/// # })
+/// ```
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    /// An error raised while parsing a SPARQL query or update.
+    #[error(transparent)]
+    QueryParsing(#[from] SparqlSyntaxError),
+    /// An error raised while parsing RDF data.
+    #[error(transparent)]
+    DataParsing(#[from] RdfParseError),
+    /// The base IRI is invalid.
+    #[error("Invalid base IRI '{iri}': {error}")]
+    InvalidBaseIri {
+        /// The IRI itself.
+        iri: String,
+        /// The parsing error.
+        #[source]
+        error: IriParseError,
+    },
+    /// An error raised by the storage layer.
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    /// An error raised while evaluating a query or an update.
+    #[error(transparent)]
+    Evaluation(QueryEvaluationError),
+    /// An error raised while serializing RDF data.
+    #[error(transparent)]
+    Serialization(#[from] io::Error),
+    /// A format compatible with [RDF dataset](https://www.w3.org/TR/rdf11-concepts/#dfn-rdf-dataset) is required.
+    #[error("A RDF format supporting datasets was expected, {0} found")]
+    DatasetFormatExpected(RdfFormat),
+    /// An RDF collection is malformed.
+    #[error(transparent)]
+    MalformedList(ListError),
+}
+
+impl StoreError {
+    /// Returns true if the error was caused by invalid input of the caller (e.g., a query with a
+    /// syntax error). Otherwise, the error is caused by the store itself.
+    ///
+    /// For example, a web server can use this method to choose between a client error (4xx) and
+    /// a server error (5xx).
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            StoreError::QueryParsing(_)
+            | StoreError::DataParsing(_)
+            | StoreError::InvalidBaseIri { .. }
+            | StoreError::DatasetFormatExpected(_)
+            | StoreError::MalformedList(_) => true,
+            StoreError::Evaluation(error) => matches!(
+                error,
+                QueryEvaluationError::GraphAlreadyExists(_)
+                    | QueryEvaluationError::GraphDoesNotExist(_)
+                    | QueryEvaluationError::UnsupportedService(_)
+                    | QueryEvaluationError::UnboundService
+            ),
+            StoreError::Storage(_) | StoreError::Serialization(_) => false,
+        }
+    }
+}
+
+impl From<QueryEvaluationError> for StoreError {
+    #[inline]
+    fn from(error: QueryEvaluationError) -> Self {
+        match error {
+            QueryEvaluationError::Parsing(error) => Self::QueryParsing(error),
+            QueryEvaluationError::GraphParsing(error) => Self::DataParsing(error),
+            QueryEvaluationError::Storage(error) => Self::Storage(error),
+            error => Self::Evaluation(error),
+        }
+    }
+}
+
+impl From<LoaderError> for StoreError {
+    #[inline]
+    fn from(error: LoaderError) -> Self {
+        match error {
+            LoaderError::Parsing(error) => Self::DataParsing(error),
+            LoaderError::Storage(error) => Self::Storage(error),
+            LoaderError::InvalidBaseIri { iri, error } => {
+                Self::InvalidBaseIri { iri, error }
+            }
+        }
+    }
+}
+
+impl From<SerializerError> for StoreError {
+    #[inline]
+    fn from(error: SerializerError) -> Self {
+        match error {
+            SerializerError::Io(error) => Self::Serialization(error),
+            SerializerError::Evaluation(error) => error.into(),
+            SerializerError::DatasetFormatExpected(format) => {
+                Self::DatasetFormatExpected(format)
+            }
+        }
+    }
+}
+
 /// An error raised while loading a file into a [`Store`](crate::store::Store).
 #[derive(Debug, thiserror::Error)]
 pub enum LoaderError {
@@ -37,6 +157,35 @@ impl From<LoaderError> for io::Error {
     }
 }
 
+/// An error raised while reading an [RDF collection](https://www.w3.org/TR/rdf11-mt/#rdf-collections)
+/// with [`Store::list_members`](crate::store::Store::list_members).
+#[derive(Debug, thiserror::Error)]
+pub enum ListError {
+    /// A node of the list does not have exactly one `rdf:first` value.
+    #[error("The list node {0} does not have exactly one rdf:first value")]
+    InvalidFirst(NamedOrBlankNode),
+    /// A node of the list does not have exactly one `rdf:rest` value that is an IRI or a blank
+    /// node.
+    #[error("The list node {0} does not have exactly one valid rdf:rest value")]
+    InvalidRest(NamedOrBlankNode),
+    /// The list does not end with `rdf:nil` but refers back to one of its nodes.
+    #[error("The list contains a cycle at the node {0}")]
+    Cycle(NamedOrBlankNode),
+    /// An error raised while accessing the quads of the list.
+    #[error(transparent)]
+    Evaluation(#[from] QueryEvaluationError),
+}
+
+impl From<ListError> for StoreError {
+    #[inline]
+    fn from(error: ListError) -> Self {
+        match error {
+            ListError::Evaluation(error) => error.into(),
+            error => Self::MalformedList(error),
+        }
+    }
+}
+
 /// An error raised while writing a file from a [`Store`](crate::store::Store).
 
 #[derive(Debug, thiserror::Error)]
@@ -54,3 +203,50 @@ pub enum SerializerError {
     #[error("A RDF format supporting datasets was expected, {0} found")]
     DatasetFormatExpected(RdfFormat),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::NamedNode;
+
+    #[test]
+    fn test_graph_already_exists_is_client_error() {
+        let error = QueryEvaluationError::GraphAlreadyExists(NamedNode::new_unchecked(
+            "http://example.com/graph",
+        ));
+
+        let error = StoreError::from(error);
+
+        assert!(matches!(error, StoreError::Evaluation(_)));
+        assert!(error.is_client_error());
+    }
+
+    #[test]
+    fn test_storage_error_is_not_client_error() {
+        let error = StoreError::from(LoaderError::Storage(StorageError::Io(
+            io::Error::other("disk failure"),
+        )));
+
+        assert!(matches!(error, StoreError::Storage(_)));
+        assert!(!error.is_client_error());
+    }
+
+    #[test]
+    fn test_serializer_error_dataset_format_expected() {
+        let error =
+            StoreError::from(SerializerError::DatasetFormatExpected(RdfFormat::Turtle));
+
+        assert!(matches!(error, StoreError::DatasetFormatExpected(_)));
+        assert!(error.is_client_error());
+    }
+
+    #[test]
+    fn test_malformed_list_is_client_error() {
+        let error = StoreError::from(ListError::Cycle(
+            NamedNode::new_unchecked("http://example.com/list").into(),
+        ));
+
+        assert!(matches!(error, StoreError::MalformedList(_)));
+        assert!(error.is_client_error());
+    }
+}
@@ -0,0 +1,127 @@
+//! Materialized [RDFS](https://www.w3.org/TR/rdf11-mt/#rdfs-entailment) inference.
+//!
+//! As an alternative to rewriting queries at query time (see
+//! [EntailmentRegime](rdf_fusion_execution::sparql::EntailmentRegime)), the entailed triples can
+//! be materialized into the store. See [Store::materialize_rdfs](crate::store::Store::materialize_rdfs)
+//! for more information.
+
+use std::collections::BTreeSet;
+
+/// A single RDFS inference rule (or a group of closely related rules).
+///
+/// # Relevant Resources
+/// - [RDF 1.1 Semantics - RDFS Entailment Rules](https://www.w3.org/TR/rdf11-mt/#patterns-of-rdfs-entailment-informative)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RdfsRule {
+    /// The transitivity of `rdfs:subClassOf` (`rdfs11`) and the propagation of `rdf:type` to
+    /// super classes (`rdfs9`).
+    SubClassOf,
+    /// The transitivity of `rdfs:subPropertyOf` (`rdfs5`) and the propagation of triples to super
+    /// properties (`rdfs7`).
+    SubPropertyOf,
+    /// Infers the type of subjects from the `rdfs:domain` of a property (`rdfs2`).
+    Domain,
+    /// Infers the type of objects from the `rdfs:range` of a property (`rdfs3`). Literals are not
+    /// typed, as they cannot be the subject of a triple.
+    Range,
+}
+
+impl RdfsRule {
+    /// Returns the `CONSTRUCT` queries that compute a single application of the rule.
+    pub(crate) fn queries(self) -> &'static [&'static str] {
+        match self {
+            RdfsRule::SubClassOf => &[
+                "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?a rdfs:subClassOf ?c }
+                WHERE { ?a rdfs:subClassOf ?b . ?b rdfs:subClassOf ?c }",
+                "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?x a ?c }
+                WHERE { ?x a ?b . ?b rdfs:subClassOf ?c }",
+            ],
+            RdfsRule::SubPropertyOf => &[
+                "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?p rdfs:subPropertyOf ?r }
+                WHERE { ?p rdfs:subPropertyOf ?q . ?q rdfs:subPropertyOf ?r }",
+                "PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?x ?q ?y }
+                WHERE { ?x ?p ?y . ?p rdfs:subPropertyOf ?q }",
+            ],
+            RdfsRule::Domain => &["PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?x a ?c }
+                WHERE { ?x ?p ?y . ?p rdfs:domain ?c }"],
+            RdfsRule::Range => &["PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+                CONSTRUCT { ?y a ?c }
+                WHERE { ?x ?p ?y . ?p rdfs:range ?c FILTER(!isLiteral(?y)) }"],
+        }
+    }
+}
+
+/// The set of [RdfsRule]s that are used for materializing entailed triples.
+///
+/// By default, all rules are enabled.
+///
+/// ```
+/// use rdf_fusion::inference::{RdfsRule, RdfsRules};
+///
+/// let rules = RdfsRules::none()
+///     .with_rule(RdfsRule::SubClassOf)
+///     .with_rule(RdfsRule::SubPropertyOf);
+/// assert!(rules.contains(RdfsRule::SubClassOf));
+/// assert!(!rules.contains(RdfsRule::Domain));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RdfsRules {
+    /// The enabled rules.
+    rules: BTreeSet<RdfsRule>,
+}
+
+impl RdfsRules {
+    /// Returns a rule set with all supported rules.
+    pub fn all() -> Self {
+        Self {
+            rules: BTreeSet::from([
+                RdfsRule::SubClassOf,
+                RdfsRule::SubPropertyOf,
+                RdfsRule::Domain,
+                RdfsRule::Range,
+            ]),
+        }
+    }
+
+    /// Returns an empty rule set.
+    pub fn none() -> Self {
+        Self {
+            rules: BTreeSet::new(),
+        }
+    }
+
+    /// Returns a new rule set that also contains `rule`.
+    #[must_use]
+    pub fn with_rule(mut self, rule: RdfsRule) -> Self {
+        self.rules.insert(rule);
+        self
+    }
+
+    /// Returns a new rule set that does not contain `rule`.
+    #[must_use]
+    pub fn without_rule(mut self, rule: RdfsRule) -> Self {
+        self.rules.remove(&rule);
+        self
+    }
+
+    /// Returns whether `rule` is enabled.
+    pub fn contains(&self, rule: RdfsRule) -> bool {
+        self.rules.contains(&rule)
+    }
+
+    /// Returns an iterator over the enabled rules.
+    pub fn iter(&self) -> impl Iterator<Item = RdfsRule> + '_ {
+        self.rules.iter().copied()
+    }
+}
+
+impl Default for RdfsRules {
+    fn default() -> Self {
+        Self::all()
+    }
+}
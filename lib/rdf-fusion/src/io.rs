@@ -0,0 +1,298 @@
+//! Parsers and serializers for RDF formats.
+//!
+//! Most types are re-exported from [oxrdfio]. [PrettyRdfXmlSerializer] additionally provides a
+//! human-readable RDF/XML serialization.
+
+pub use oxrdfio::*;
+
+use rdf_fusion_model::vocab::rdf;
+use rdf_fusion_model::{IriParseError, NamedOrBlankNode, Quad, Term};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::Write;
+
+/// The namespace of the `rdf` prefix that is always declared by the RDF/XML serializer.
+const RDF_NAMESPACE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+/// Serializes quads in the default graph as [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/)
+/// that is easier to read than the output of a streaming [RdfSerializer].
+///
+/// Contrary to the streaming serializer, all quads are buffered before writing them, such that:
+/// - all triples of a subject are written in a single node element,
+/// - the node element of a subject with a type is a typed node element (e.g., `<ex:Person>`)
+///   instead of an `rdf:Description`, and
+/// - a prefix is declared for every namespace of the used predicates and types, instead of
+///   declaring the namespace on each element.
+///
+/// The output is still valid RDF/XML and parses to the same graph. Node elements are not nested
+/// into property elements (striped syntax). Blank nodes are referenced with `rdf:nodeID`.
+///
+/// Usage example:
+/// ```
+/// use rdf_fusion::io::PrettyRdfXmlSerializer;
+/// use rdf_fusion::model::vocab::rdf;
+/// use rdf_fusion::model::*;
+///
+/// let me = NamedNode::new("http://example.com/me")?;
+/// let person = NamedNode::new("http://schema.org/Person")?;
+/// let name = NamedNode::new("http://schema.org/name")?;
+/// let quads = vec![
+///     Quad::new(me.clone(), name, Literal::from("Foo"), GraphName::DefaultGraph),
+///     Quad::new(me, rdf::TYPE, person, GraphName::DefaultGraph),
+/// ];
+///
+/// let xml = PrettyRdfXmlSerializer::new().serialize_to_writer(quads, Vec::new())?;
+/// assert_eq!(
+///     String::from_utf8(xml)?,
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rdf:RDF xmlns:ns0=\"http://schema.org/\" xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n\t<ns0:Person rdf:about=\"http://example.com/me\">\n\t\t<ns0:name>Foo</ns0:name>\n\t</ns0:Person>\n</rdf:RDF>"
+/// );
+/// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct PrettyRdfXmlSerializer {
+    /// The underlying serializer.
+    serializer: RdfSerializer,
+    /// The names of the declared prefixes.
+    prefix_names: HashSet<String>,
+    /// The IRIs of the declared prefixes.
+    namespaces: HashSet<String>,
+}
+
+impl Default for PrettyRdfXmlSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrettyRdfXmlSerializer {
+    /// Creates a new [PrettyRdfXmlSerializer].
+    pub fn new() -> Self {
+        Self {
+            serializer: RdfSerializer::from_format(RdfFormat::RdfXml),
+            prefix_names: HashSet::from(["rdf".to_owned()]),
+            namespaces: HashSet::from([RDF_NAMESPACE.to_owned()]),
+        }
+    }
+
+    /// Declares a prefix. Prefixes are generated for the namespaces that are not declared.
+    ///
+    /// See [RdfSerializer::with_prefix] for details.
+    pub fn with_prefix(
+        mut self,
+        prefix_name: impl Into<String>,
+        prefix_iri: impl Into<String>,
+    ) -> Result<Self, IriParseError> {
+        let prefix_name = prefix_name.into();
+        let prefix_iri = prefix_iri.into();
+        self.serializer = self
+            .serializer
+            .with_prefix(prefix_name.clone(), prefix_iri.clone())?;
+        self.prefix_names.insert(prefix_name);
+        self.namespaces.insert(prefix_iri);
+        Ok(self)
+    }
+
+    /// Sets the base IRI that is used for writing relative IRIs.
+    ///
+    /// See [RdfSerializer::with_base_iri] for details.
+    pub fn with_base_iri(
+        mut self,
+        base_iri: impl Into<String>,
+    ) -> Result<Self, IriParseError> {
+        self.serializer = self.serializer.with_base_iri(base_iri)?;
+        Ok(self)
+    }
+
+    /// Writes the `quads` to `writer`.
+    ///
+    /// Fails if a quad is not in the default graph, as RDF/XML does not support named graphs.
+    pub fn serialize_to_writer<W: Write>(
+        mut self,
+        quads: impl IntoIterator<Item = Quad>,
+        writer: W,
+    ) -> io::Result<W> {
+        let quads = group_by_subject(quads);
+        for namespace in used_namespaces(&quads) {
+            self.declare_namespace(namespace);
+        }
+
+        let mut serializer = self.serializer.for_writer(writer);
+        for quad in &quads {
+            serializer.serialize_quad(quad)?;
+        }
+        serializer.finish()
+    }
+
+    /// Declares a generated prefix for `namespace` if it has not been declared yet.
+    fn declare_namespace(&mut self, namespace: &str) {
+        if self.namespaces.contains(namespace) {
+            return;
+        }
+
+        let prefix_name = (0..)
+            .map(|i| format!("ns{i}"))
+            .find(|name| !self.prefix_names.contains(name))
+            .expect("Infinite iterator");
+        // Namespaces that are not a valid IRI are declared on each element instead.
+        if let Ok(serializer) = self
+            .serializer
+            .clone()
+            .with_prefix(prefix_name.clone(), namespace)
+        {
+            self.serializer = serializer;
+            self.prefix_names.insert(prefix_name);
+            self.namespaces.insert(namespace.to_owned());
+        }
+    }
+}
+
+/// Groups the `quads` by their subject, retaining the order in which the subjects first appear.
+/// The first `rdf:type` of a subject is moved to the front of its group such that the serializer
+/// writes a typed node element.
+fn group_by_subject(quads: impl IntoIterator<Item = Quad>) -> Vec<Quad> {
+    let mut groups: Vec<Vec<Quad>> = Vec::new();
+    let mut group_of_subject = HashMap::<NamedOrBlankNode, usize>::new();
+    for quad in quads {
+        let index = *group_of_subject
+            .entry(quad.subject.clone())
+            .or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+
+        let group = &mut groups[index];
+        if is_node_type(&quad) && !group.first().is_some_and(is_node_type) {
+            group.insert(0, quad);
+        } else {
+            group.push(quad);
+        }
+    }
+    groups.into_iter().flatten().collect()
+}
+
+/// Returns the namespaces of the predicates and of the types that are used for typed node
+/// elements, in the order of their first occurrence.
+fn used_namespaces(quads: &[Quad]) -> Vec<&str> {
+    let mut seen = HashSet::new();
+    let mut namespaces = Vec::new();
+    let mut previous_subject = None;
+    for quad in quads {
+        let is_first_of_subject = previous_subject != Some(&quad.subject);
+        previous_subject = Some(&quad.subject);
+
+        let iri = match &quad.object {
+            Term::NamedNode(node) if is_first_of_subject && is_node_type(quad) => {
+                node.as_str()
+            }
+            _ => quad.predicate.as_str(),
+        };
+        let (namespace, local_name) = split_iri(iri);
+        if !local_name.is_empty() && seen.insert(namespace) {
+            namespaces.push(namespace);
+        }
+    }
+    namespaces
+}
+
+/// Returns true if `quad` assigns a type that can be used for a typed node element.
+fn is_node_type(quad: &Quad) -> bool {
+    quad.predicate == rdf::TYPE && matches!(quad.object, Term::NamedNode(_))
+}
+
+/// Splits `iri` into a namespace and a local name that is a valid XML name. Mirrors the
+/// splitting of the RDF/XML serializer such that the declared prefixes are picked up.
+fn split_iri(iri: &str) -> (&str, &str) {
+    let Some(position_base) = iri.rfind(|c| !is_name_char(c) || c == ':') else {
+        return (iri, "");
+    };
+    match iri[position_base..].find(|c| is_name_start_char(c) && c != ':') {
+        Some(position_add) => iri.split_at(position_base + position_add),
+        None => (iri, ""),
+    }
+}
+
+/// See <https://www.w3.org/TR/xml/#NT-NameStartChar>.
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        ':'
+        | 'A'..='Z'
+        | '_'
+        | 'a'..='z'
+        | '\u{00C0}'..='\u{00D6}'
+        | '\u{00D8}'..='\u{00F6}'
+        | '\u{00F8}'..='\u{02FF}'
+        | '\u{0370}'..='\u{037D}'
+        | '\u{037F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}')
+}
+
+/// See <https://www.w3.org/TR/xml/#NT-NameChar>.
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c, '-' | '.' | '0'..='9' | '\u{B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::dataset::CanonicalizationAlgorithm;
+    use rdf_fusion_model::{BlankNode, Dataset, GraphName, Literal, NamedNode};
+
+    #[test]
+    fn test_pretty_rdf_xml_round_trips() {
+        let ex =
+            |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+        let bnode = BlankNode::default();
+        let quads = vec![
+            Quad::new(
+                ex("a"),
+                ex("name"),
+                Literal::from("A"),
+                GraphName::DefaultGraph,
+            ),
+            Quad::new(ex("b"), rdf::TYPE, ex("Person"), GraphName::DefaultGraph),
+            Quad::new(ex("a"), ex("knows"), bnode.clone(), GraphName::DefaultGraph),
+            Quad::new(ex("a"), rdf::TYPE, ex("Person"), GraphName::DefaultGraph),
+            Quad::new(ex("a"), rdf::TYPE, ex("Agent"), GraphName::DefaultGraph),
+            Quad::new(bnode, ex("age"), Literal::from(42), GraphName::DefaultGraph),
+        ];
+
+        let xml = PrettyRdfXmlSerializer::new()
+            .with_prefix("ex", "http://example.com/")
+            .unwrap()
+            .serialize_to_writer(quads.clone(), Vec::new())
+            .unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+        assert_eq!(xml.matches("<ex:Person").count(), 2);
+        assert_eq!(xml.matches("<rdf:Description").count(), 1);
+        assert!(!xml.contains("xmlns:ns0"));
+        assert!(!xml.contains("xmlns=\""));
+
+        let mut parsed = RdfParser::from_format(RdfFormat::RdfXml)
+            .for_slice(xml.as_bytes())
+            .collect::<Result<Dataset, _>>()
+            .unwrap();
+        parsed.canonicalize(CanonicalizationAlgorithm::Unstable);
+        let mut expected = quads.into_iter().collect::<Dataset>();
+        expected.canonicalize(CanonicalizationAlgorithm::Unstable);
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_pretty_rdf_xml_rejects_named_graphs() {
+        let ex = NamedNode::new_unchecked("http://example.com");
+        let quad = Quad::new(ex.clone(), ex.clone(), ex.clone(), ex);
+        assert!(
+            PrettyRdfXmlSerializer::new()
+                .serialize_to_writer([quad], Vec::new())
+                .is_err()
+        );
+    }
+}
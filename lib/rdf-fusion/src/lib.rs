@@ -393,7 +393,11 @@
 //!   for RDF Fusion.
 //! - [rdf-fusion-web](https://docs.rs/rdf-fusion-web/): The web server for RDF Fusion.
 
+pub mod diagnostics;
 pub mod error;
+pub mod inference;
+pub mod io;
+pub mod schema;
 pub mod store;
 
 pub mod api {
@@ -408,10 +412,6 @@ pub mod functions {
     pub use rdf_fusion_functions::*;
 }
 
-pub mod io {
-    pub use oxrdfio::*;
-}
-
 pub mod model {
     pub use rdf_fusion_model::*;
 }
@@ -0,0 +1,44 @@
+//! Summaries of the vocabulary that is used by the data in a store.
+//!
+//! See [Store::schema_summary](crate::store::Store::schema_summary) for the entry point.
+
+use rdf_fusion_model::{NamedNode, NamedNodeRef, Term, TermRef};
+
+/// The classes and properties that are used by the data in a store, computed with
+/// [Store::schema_summary](crate::store::Store::schema_summary).
+///
+/// Both lists are ordered by descending usage count. Terms with the same count are ordered by
+/// their string representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaSummary {
+    /// The distinct objects of `rdf:type`, together with the number of quads that assign them to
+    /// a resource.
+    pub classes: Vec<(Term, usize)>,
+    /// The distinct predicates, together with the number of quads that use them.
+    pub properties: Vec<(NamedNode, usize)>,
+}
+
+impl SchemaSummary {
+    /// Returns the number of quads that assign the given `class` to a resource, or [None] if the
+    /// class is not used.
+    pub fn class_count<'a>(&self, class: impl Into<TermRef<'a>>) -> Option<usize> {
+        let class = class.into();
+        self.classes
+            .iter()
+            .find(|(term, _)| term.as_ref() == class)
+            .map(|(_, count)| *count)
+    }
+
+    /// Returns the number of quads that use the given `property`, or [None] if the property is
+    /// not used.
+    pub fn property_count<'a>(
+        &self,
+        property: impl Into<NamedNodeRef<'a>>,
+    ) -> Option<usize> {
+        let property = property.into();
+        self.properties
+            .iter()
+            .find(|(term, _)| term.as_ref() == property)
+            .map(|(_, count)| *count)
+    }
+}
@@ -30,26 +30,45 @@
 //! # }).unwrap();
 //! ```
 
-use crate::error::{LoaderError, SerializerError};
+use crate::diagnostics::{ComponentDiagnosis, PatternDiagnosis};
+use crate::error::{ListError, LoaderError, SerializerError, StoreError};
+use crate::inference::{RdfsRule, RdfsRules};
+use crate::schema::SchemaSummary;
+use datafusion::arrow::array::RecordBatch;
 use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
 use datafusion::prelude::SessionConfig;
-use futures::StreamExt;
-use oxrdfio::{RdfParser, RdfSerializer};
-use rdf_fusion_encoding::object_id::{ObjectIdEncoding, ObjectIdMapping};
+use futures::{StreamExt, TryStreamExt};
+use oxrdfio::{RdfFormat, RdfParser, RdfSerializer};
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdEncoding, ObjectIdMapping};
+use rdf_fusion_encoding::plain_term::PlainTermScalar;
+use rdf_fusion_encoding::typed_value::LiteralNormalization;
 use rdf_fusion_execution::RdfFusionContext;
-use rdf_fusion_execution::results::{QuadStream, QueryResults, QuerySolutionStream};
+use rdf_fusion_execution::results::{
+    BestEffortQuerySolutions, PartialQuerySolutions, QuadStream, QueryResults,
+    QuerySolutionStream, QuerySolutionsDiff,
+};
 use rdf_fusion_execution::sparql::error::QueryEvaluationError;
 use rdf_fusion_execution::sparql::{
-    Query, QueryExplanation, QueryOptions, Update, UpdateOptions,
+    Query, QueryExplanation, QueryOptions, ServiceResolver, Update, UpdateOptions,
+};
+use rdf_fusion_extensions::storage::{
+    QuadPosition, StorageInfo, StorageOptimizationReport,
 };
+use rdf_fusion_functions::datatypes::CustomDatatype;
 use rdf_fusion_model::StorageError;
+use rdf_fusion_model::dataset::CanonicalizationAlgorithm;
+use rdf_fusion_model::vocab::rdf;
 use rdf_fusion_model::{
-    GraphNameRef, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef,
-    TermRef, Variable,
+    Dataset, GraphName, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, QuadRef, Term, TermRef, Variable,
 };
 use rdf_fusion_storage::memory::{MemObjectIdMapping, MemQuadStorage};
+use std::cmp::Reverse;
+use std::collections::HashSet;
 use std::io::{Read, Write};
+use std::num::NonZeroUsize;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 static QUAD_VARIABLES: LazyLock<Arc<[Variable]>> = LazyLock::new(|| {
     Arc::new([
@@ -106,7 +125,8 @@ impl Default for Store {
         let encoding = Arc::new(ObjectIdEncoding::new(
             Arc::clone(&object_id_mapping) as Arc<dyn ObjectIdMapping>
         ));
-        let storage = MemQuadStorage::new(object_id_mapping, encoding, 8192);
+        let storage = MemQuadStorage::new(object_id_mapping, encoding, 8192)
+            .expect("The encoding is created from the mapping");
         let engine = RdfFusionContext::new(
             config,
             RuntimeEnvBuilder::default().build_arc().unwrap(),
@@ -132,7 +152,8 @@ impl Store {
         let encoding = Arc::new(ObjectIdEncoding::new(
             Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
         ));
-        let storage = MemQuadStorage::new(mapping, encoding, config.batch_size());
+        let storage = MemQuadStorage::new(mapping, encoding, config.batch_size())
+            .expect("The encoding is created from the mapping");
         let context = RdfFusionContext::new(config, runtime_env, Arc::new(storage));
         Self { context }
     }
@@ -142,6 +163,141 @@ impl Store {
         &self.context
     }
 
+    /// Registers a custom RDF datatype with a validator and a comparison function.
+    ///
+    /// Afterward, the SPARQL comparison operators (e.g., `<` in a `FILTER`) compare literals of
+    /// this datatype with the given comparator. Literals with an invalid lexical form raise a type
+    /// error. Literals with unregistered datatypes are not affected. The registration only
+    /// applies to this store (and its clones).
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::execution::results::QueryResults;
+    /// use rdf_fusion::functions::datatypes::CustomDatatype;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// store.register_custom_datatype(CustomDatatype::new(
+    ///     NamedNode::new("http://example.com/amount")?,
+    ///     |value| value.parse::<f64>().is_ok(),
+    ///     |lhs, rhs| lhs.parse::<f64>().ok()?.partial_cmp(&rhs.parse::<f64>().ok()?),
+    /// ));
+    ///
+    /// let query = r#"ASK {
+    ///     FILTER("10"^^<http://example.com/amount> = "10.0"^^<http://example.com/amount>)
+    /// }"#;
+    /// if let QueryResults::Boolean(result) = store.query(query).await? {
+    ///     assert!(result);
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub fn register_custom_datatype(&self, datatype: CustomDatatype) {
+        self.context.register_custom_datatype(datatype);
+    }
+
+    /// Sets the seed of the SPARQL `RAND` function.
+    ///
+    /// If a `seed` is given, `RAND` produces the same sequence of values in each execution of a
+    /// query. Passing [None] restores truly random values. The setting only applies to this store
+    /// (and its clones).
+    pub fn set_rand_seed(&self, seed: Option<u64>) {
+        self.context.set_rand_seed(seed);
+    }
+
+    /// Sets how the lexical forms of literals are treated when quads are inserted into this
+    /// store (e.g., with [Store::insert], [Store::load_from_reader], or a SPARQL update).
+    ///
+    /// By default ([LiteralNormalization::Preserve]), literals are stored exactly as given, as
+    /// required by the RDF semantics. With [LiteralNormalization::Canonical], typed literals are
+    /// stored with the canonical lexical form of their value (e.g., `"01"^^xsd:integer` is stored
+    /// as `"1"^^xsd:integer`). This makes equal values join and deduplicate, and avoids storing
+    /// several lexical forms of the same value. However, the original lexical forms are lost and
+    /// queries must use the canonical lexical form to match a literal in a triple pattern.
+    /// Removing a quad applies the same normalization.
+    ///
+    /// Quads that are already in the store are not changed. The setting only applies to this
+    /// store (and its clones).
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::encoding::typed_value::LiteralNormalization;
+    /// use rdf_fusion::model::vocab::xsd;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.set_literal_normalization(LiteralNormalization::Canonical);
+    ///
+    /// let value = Literal::new_typed_literal("01", xsd::INTEGER);
+    /// store.insert(QuadRef::new(ex, ex, &value, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// let canonical = Literal::new_typed_literal("1", xsd::INTEGER);
+    /// assert!(store.contains(QuadRef::new(ex, ex, &canonical, GraphNameRef::DefaultGraph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub fn set_literal_normalization(&self, normalization: LiteralNormalization) {
+        self.context.set_literal_normalization(normalization);
+    }
+
+    /// Registers the [ServiceResolver] that evaluates the `SERVICE` calls of queries.
+    ///
+    /// By default, the calls are sent to the SPARQL endpoints with an
+    /// [HttpServiceResolver](rdf_fusion_execution::sparql::HttpServiceResolver). A custom
+    /// resolver can, for example, add authentication, cache results, or answer the calls without
+    /// a live endpoint in tests. The registration only applies to this store (and its clones).
+    ///
+    /// Usage example with a resolver that evaluates the calls against another store:
+    /// ```
+    /// use async_trait::async_trait;
+    /// use rdf_fusion::execution::results::QueryResults;
+    /// use rdf_fusion::execution::sparql::error::QueryEvaluationError;
+    /// use rdf_fusion::execution::sparql::{Query, ServiceResolver};
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    /// use futures::StreamExt;
+    /// use std::sync::Arc;
+    ///
+    /// struct LocalResolver(Store);
+    ///
+    /// #[async_trait]
+    /// impl ServiceResolver for LocalResolver {
+    ///     async fn evaluate(
+    ///         &self,
+    ///         _name: &NamedNode,
+    ///         query: Query,
+    ///     ) -> Result<QueryResults, QueryEvaluationError> {
+    ///         self.0.query(query).await
+    ///     }
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let remote = Store::default();
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// remote.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// let store = Store::default();
+    /// store.register_service_resolver(Arc::new(LocalResolver(remote)));
+    ///
+    /// let query = "SELECT ?s WHERE { SERVICE <http://example.com/sparql> { ?s ?p ?o } }";
+    /// if let QueryResults::Solutions(mut solutions) = store.query(query).await? {
+    ///     assert_eq!(
+    ///         solutions.next().await.unwrap()?.get("s"),
+    ///         Some(&ex.into_owned().into())
+    ///     );
+    /// }
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub fn register_service_resolver(&self, resolver: Arc<dyn ServiceResolver>) {
+        self.context.register_service_resolver(resolver);
+    }
+
     /// Executes a [SPARQL](https://www.w3.org/TR/sparql11-query/) query.
     ///
     /// Usage example:
@@ -246,8 +402,136 @@ impl Store {
         }
     }
 
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) and returns
+    /// at most its first `limit` solutions, together with an indicator whether more solutions
+    /// exist.
+    ///
+    /// Internally, the query is limited to `limit + 1` solutions. The query engine can push this
+    /// limit down such that no solutions beyond the additional one are computed. The
+    /// additional solution is only used for determining [PartialQuerySolutions::has_more].
+    ///
+    /// Returns [QueryEvaluationError::NotSolutions] if the query is not a `SELECT` query.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let partial = store
+    ///     .query_partial("SELECT ?s WHERE { VALUES ?s { 1 2 3 } }", 2)
+    ///     .await?;
+    /// assert_eq!(partial.solutions().len(), 2);
+    /// assert!(partial.has_more());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn query_partial(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        limit: usize,
+    ) -> Result<PartialQuerySolutions, QueryEvaluationError> {
+        let query = query.try_into().map_err(Into::into)?;
+        let query = query
+            .with_solution_limit(limit.saturating_add(1))
+            .ok_or(QueryEvaluationError::NotSolutions)?;
+        let QueryResults::Solutions(solutions) = self.query(query).await? else {
+            return Err(QueryEvaluationError::NotSolutions);
+        };
+        PartialQuerySolutions::try_collect(solutions, limit).await
+    }
+
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) in a
+    /// best-effort mode that returns the solutions computed within `timeout`.
+    ///
+    /// This mode is opt-in and only available via this method. Once `timeout` has elapsed, the
+    /// evaluation is stopped and the solutions computed so far are returned with
+    /// [BestEffortQuerySolutions::is_truncated] set to true. Exceeding the `timeout` is therefore
+    /// not an error. Errors that occur during the evaluation are still reported. Note that the
+    /// `timeout` only limits the evaluation of the query and not its planning.
+    ///
+    /// Returns [QueryEvaluationError::NotSolutions] if the query is not a `SELECT` query.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::store::Store;
+    /// use std::time::Duration;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let query = "SELECT ?s WHERE { VALUES ?s { 1 2 3 } }";
+    /// let solutions = store
+    ///     .query_best_effort(query, Duration::from_secs(10))
+    ///     .await?;
+    /// assert_eq!(solutions.solutions().len(), 3);
+    /// assert!(!solutions.is_truncated());
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn query_best_effort(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        timeout: Duration,
+    ) -> Result<BestEffortQuerySolutions, QueryEvaluationError> {
+        let QueryResults::Solutions(solutions) = self.query(query).await? else {
+            return Err(QueryEvaluationError::NotSolutions);
+        };
+        BestEffortQuerySolutions::collect_with_timeout(solutions, timeout).await
+    }
+
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) against
+    /// this store and `other`, and returns the solutions that differ between the two.
+    ///
+    /// This store is treated as the old version. Hence, [QuerySolutionsDiff::added] contains the
+    /// solutions that are only returned by `other`, and [QuerySolutionsDiff::removed] contains the
+    /// solutions that are only returned by this store. Use [StoreSnapshot::query_diff] for
+    /// comparing two versions of the same store. Blank nodes in the solutions are compared up to
+    /// renaming. See [QuerySolutionsDiff] for details.
+    ///
+    /// Returns [QueryEvaluationError::NotSolutions] if the query is not a `SELECT` query.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let old = Store::default();
+    /// old.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    /// let one = Literal::from(1);
+    /// let new = Store::default();
+    /// new.insert(QuadRef::new(ex, ex, &one, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// let diff = old.query_diff(&new, "SELECT ?o WHERE { ?s ?p ?o }").await?;
+    /// assert_eq!(diff.added().len(), 1);
+    /// assert_eq!(diff.added()[0].get("o"), Some(&one.into()));
+    /// assert_eq!(diff.removed().len(), 1);
+    /// assert_eq!(diff.removed()[0].get("o"), Some(&ex.into_owned().into()));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn query_diff(
+        &self,
+        other: &Store,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+    ) -> Result<QuerySolutionsDiff, QueryEvaluationError> {
+        let query: Query = query.try_into().map_err(Into::into)?;
+        let QueryResults::Solutions(old) = self.query(query.clone()).await? else {
+            return Err(QueryEvaluationError::NotSolutions);
+        };
+        let QueryResults::Solutions(new) = other.query(query).await? else {
+            return Err(QueryEvaluationError::NotSolutions);
+        };
+        QuerySolutionsDiff::try_compute(old, new).await
+    }
+
     /// Retrieves quads with a filter on each quad component
     ///
+    /// Unbound components match any term. If `graph_name` is [None], quads in all graphs are
+    /// returned. If supported by the storage, the quads are streamed directly from its indexes
+    /// without planning a SPARQL query.
+    ///
     /// Usage example:
     /// ```
     /// use rdf_fusion::model::*;
@@ -310,6 +594,71 @@ impl Store {
         self.quads_for_pattern(None, None, None, None).await
     }
 
+    /// Returns the [ObjectId] of `term`, or [None] if the store has never encountered `term`.
+    ///
+    /// Applications that repeatedly look up the same term can encode it once and pass the
+    /// object id to [Self::quads_for_object_id_pattern]. This avoids encoding the term for each
+    /// lookup. Returns an error if the storage does not use object ids.
+    ///
+    /// # Lifetime
+    ///
+    /// An object id is only meaningful for the store that returned it and its snapshots. It
+    /// remains valid as long as the store is not cleared, even if all quads that contain the term
+    /// are removed. After the store has been cleared (e.g., with [Self::clear] or
+    /// [Self::restore]), the term must be encoded again.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// assert_eq!(store.encode_term(ex)?, None);
+    ///
+    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    /// store.insert(quad).await?;
+    ///
+    /// let ex_id = store.encode_term(ex)?.unwrap();
+    /// let results = store
+    ///     .quads_for_object_id_pattern(Some(&ex_id), None, None, None)
+    ///     .await?;
+    /// assert_eq!(vec![quad.into_owned()], results);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub fn encode_term<'a>(
+        &self,
+        term: impl Into<TermRef<'a>>,
+    ) -> Result<Option<ObjectId>, StorageError> {
+        let Some(mapping) = self.context.storage().object_id_mapping() else {
+            return Err(StorageError::Other(
+                "The storage does not use object ids.".into(),
+            ));
+        };
+        Ok(mapping.try_get_object_id(&PlainTermScalar::from(term.into()))?)
+    }
+
+    /// Returns all the quads that match the given pattern of object ids (see
+    /// [Self::encode_term]). Unbound components match any term. If `graph_name` is [None], the
+    /// quads of all graphs are returned. The order of the quads is unspecified.
+    ///
+    /// This is a lower-level alternative to [Self::quads_for_pattern] that does not encode the
+    /// bound components. Object ids that are not known to the store do not match any quad.
+    pub async fn quads_for_object_id_pattern(
+        &self,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<Quad>, QueryEvaluationError> {
+        self.context
+            .quads_for_object_id_pattern(graph_name, subject, predicate, object)
+            .await
+            .map_err(QueryEvaluationError::from)
+    }
+
     /// Checks if this store contains a given quad.
     ///
     /// Usage example:
@@ -342,7 +691,10 @@ impl Store {
 
     /// Returns the number of quads in the store.
     ///
-    /// <div class="warning">This function executes a full scan.</div>
+    /// The quads are counted by the storage layer without running a query. A quad is identified
+    /// by its subject, predicate, object, *and* graph name. Hence, the same triple in two graphs
+    /// is counted twice, while inserting the same quad twice does not change the count. Empty
+    /// named graphs do not contribute to the count.
     ///
     /// Usage example:
     /// ```
@@ -362,7 +714,11 @@ impl Store {
         self.context.len().await.map_err(QueryEvaluationError::from)
     }
 
-    /// Returns if the store is empty.
+    /// Returns the number of quads that match the given pattern. Unbound components match any
+    /// term.
+    ///
+    /// For the in-memory storage, the quads are counted with an index scan on a single snapshot
+    /// of the store. No terms are materialized.
     ///
     /// Usage example:
     /// ```
@@ -370,171 +726,1039 @@ impl Store {
     /// use rdf_fusion::store::Store;
     ///
     /// # tokio_test::block_on(async {
-    /// let store = Store::default();
-    /// assert!(store.is_empty().await?);
-    ///
     /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let other = NamedNodeRef::new("http://example.com/other")?;
+    /// let store = Store::default();
     /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
-    /// assert!(!store.is_empty().await?);
+    /// store.insert(QuadRef::new(ex, ex, other, GraphNameRef::DefaultGraph)).await?;
+    /// store.insert(QuadRef::new(other, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// assert_eq!(2, store.count_matching(Some(ex.into()), None, None, None).await?);
+    /// assert_eq!(
+    ///     1,
+    ///     store
+    ///         .count_matching(Some(ex.into()), None, None, Some(GraphNameRef::DefaultGraph))
+    ///         .await?
+    /// );
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// # }).unwrap();
     /// ```
-    pub async fn is_empty(&self) -> Result<bool, QueryEvaluationError> {
-        Ok(self.len().await? == 0)
+    pub async fn count_matching(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<usize, QueryEvaluationError> {
+        self.context
+            .count_matching(graph_name, subject, predicate, object)
+            .await
+            .map_err(QueryEvaluationError::from)
     }
 
-    /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
+    /// Returns the distinct predicates that are used by the given `subject`. If `graph_name` is
+    /// [None], the predicates of all graphs are returned. The order of the predicates is
+    /// unspecified.
+    ///
+    /// If supported by the storage, the predicates are obtained with a single index scan that
+    /// only decodes the distinct predicates. This is considerably cheaper than collecting the
+    /// predicates from [Store::quads_for_pattern] for subjects with many quads.
     ///
     /// Usage example:
     /// ```
-    /// // use rdf-fusion::model::*;
-    /// // use rdf-fusion::store::Store;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
     ///
     /// # tokio_test::block_on(async {
-    /// // TODO #7: Implement Update
-    /// // let store = Store::default();
-    /// // insertion
-    /// // store
-    /// //    .update("INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }").await?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
     ///
-    /// // we inspect the store contents
-    /// // let ex = NamedNodeRef::new("http://example.com")?;
-    /// // assert!(store.contains(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?);
+    /// assert_eq!(vec![ex.into_owned()], store.predicates_for(ex, None).await?);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// # }).unwrap();
     /// ```
-    #[allow(clippy::unimplemented, reason = "Not production ready")]
-    #[allow(clippy::unused_self, reason = "Not implemented")]
-    #[allow(clippy::unused_async, reason = "Not implemented")]
-    pub async fn update(
+    pub async fn predicates_for<'a>(
         &self,
-        _update: impl TryInto<Update, Error = impl Into<QueryEvaluationError>>,
-    ) -> Result<(), QueryEvaluationError> {
-        unimplemented!()
+        subject: impl Into<NamedOrBlankNodeRef<'a>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<NamedNode>, QueryEvaluationError> {
+        self.context
+            .predicates_for(graph_name, subject.into())
+            .await
+            .map_err(QueryEvaluationError::from)
     }
 
-    /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/) with some options.
+    /// Returns the distinct objects of the quads with the given `predicate`. If `graph_name` is
+    /// [None], the objects of all graphs are returned. The order of the objects is unspecified.
+    ///
+    /// This is useful for enumerating the values of a facet. If supported by the storage, the
+    /// objects are obtained with a single range scan over an index that starts with the
+    /// predicate. The objects are deduplicated before they are decoded, such that each distinct
+    /// object is only materialized once.
     ///
+    /// Usage example:
     /// ```
-    /// // use rdf-fusion::store::Store;
-    /// // use rdf-fusion::sparql::QueryOptions;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
     ///
     /// # tokio_test::block_on(async {
-    /// // TODO #7: Implement Update
-    /// // let store = Store::default();
-    /// // store.update_opt(
-    /// //    "INSERT { ?s <http://example.com/n-triples-representation> ?n } WHERE { ?s ?p ?o BIND(<http://www.w3.org/ns/formats/N-Triples>(?s) AS ?nt) }",
-    /// //    QueryOptions::default()
-    /// //).await?;
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    ///
+    /// assert_eq!(vec![Term::from(ex.into_owned())], store.distinct_objects_of(ex, None).await?);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// # }).unwrap();
     /// ```
-    #[allow(clippy::unimplemented, reason = "Not production ready")]
-    #[allow(clippy::unused_self, reason = "Not implemented")]
-    #[allow(clippy::unused_async, reason = "Not implemented")]
-    pub async fn update_opt(
+    pub async fn distinct_objects_of<'a>(
         &self,
-        _update: impl TryInto<Update, Error = impl Into<QueryEvaluationError>>,
-        _options: impl Into<UpdateOptions>,
-    ) -> Result<(), QueryEvaluationError> {
-        unimplemented!()
+        predicate: impl Into<NamedNodeRef<'a>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<Term>, QueryEvaluationError> {
+        self.context
+            .distinct_objects_of(graph_name, predicate.into())
+            .await
+            .map_err(QueryEvaluationError::from)
     }
 
-    /// Loads a RDF file under into the store.
+    /// Returns the `k` terms that occur most frequently in the given `position`, together with
+    /// the number of quads that reference them. The terms are ordered by descending frequency.
+    /// The default graph is not reported for [QuadPosition::GraphName].
     ///
-    /// This function is atomic, quite slow and memory hungry.
+    /// This is useful for profiling a dataset (e.g., finding the most common predicates). If
+    /// supported by the storage, the frequencies are computed from an index on the first call and
+    /// cached until the store is modified, such that repeated calls do not scan any quads.
+    /// Otherwise, the frequencies are computed by scanning the entire store.
     ///
     /// Usage example:
     /// ```
-    /// use rdf_fusion::store::Store;
+    /// use rdf_fusion::api::storage::QuadPosition;
     /// use rdf_fusion::model::*;
-    /// use rdf_fusion::io::{RdfParser, RdfFormat};
+    /// use rdf_fusion::store::Store;
     ///
     /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let name = NamedNodeRef::new("http://schema.org/name")?;
     /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, name, LiteralRef::new_simple_literal("Ex"), GraphNameRef::DefaultGraph)).await?;
+    /// store.insert(QuadRef::new(name, name, LiteralRef::new_simple_literal("Name"), GraphNameRef::DefaultGraph)).await?;
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
     ///
-    /// // insert a dataset file (former load_dataset method)
-    /// let file = b"<http://example.com> <http://example.com> <http://example.com> <http://example.com/g> .";
-    /// store.load_from_reader(RdfFormat::NQuads, file.as_ref()).await?;
-    ///
-    /// // insert a graph file (former load_graph method)
-    /// let file = b"<> <> <> .";
-    /// store.load_from_reader(
-    ///     RdfParser::from_format(RdfFormat::Turtle)
-    ///         .with_base_iri("http://example.com")?
-    ///         .without_named_graphs() // No named graphs allowed in the input
-    ///         .with_default_graph(NamedNodeRef::new("http://example.com/g2")?), // we put the file default graph inside of a named graph
-    ///     file.as_ref()
-    /// ).await?;
-    ///
-    /// // we inspect the store contents
-    /// let ex = NamedNodeRef::new("http://example.com")?;
-    /// assert!(store.contains(QuadRef::new(ex, ex, ex, NamedNodeRef::new("http://example.com/g")?)).await?);
-    /// assert!(store.contains(QuadRef::new(ex, ex, ex, NamedNodeRef::new("http://example.com/g2")?)).await?);
+    /// let top_predicates = store.top_terms(QuadPosition::Predicate, 1).await?;
+    /// assert_eq!(vec![(Term::from(name.into_owned()), 2)], top_predicates);
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// # }).unwrap();
     /// ```
-    pub async fn load_from_reader(
+    pub async fn top_terms(
         &self,
-        parser: impl Into<RdfParser>,
-        reader: impl Read,
-    ) -> Result<(), LoaderError> {
-        let quads = parser
-            .into()
-            .rename_blank_nodes()
-            .for_reader(reader)
-            .collect::<Result<Vec<_>, _>>()?;
+        position: QuadPosition,
+        k: usize,
+    ) -> Result<Vec<(Term, usize)>, QueryEvaluationError> {
         self.context
-            .storage()
-            .extend(quads)
+            .top_terms(position, k)
             .await
-            .map(|_| ())
-            .map_err(LoaderError::from)
+            .map_err(QueryEvaluationError::from)
     }
 
-    /// Adds a quad to this store.
+    /// Summarizes the vocabulary of the store: the classes (the distinct objects of `rdf:type`)
+    /// and the properties (the distinct predicates), together with their usage counts. All graphs
+    /// are considered. This is useful for exploring an unknown dataset or for auto-completion in
+    /// a query builder.
     ///
-    /// Returns `true` if the quad was not already in the store.
+    /// The summary is computed on a single snapshot of the store. If supported by the storage,
+    /// no quads are scanned for the properties, as their frequencies are maintained on every
+    /// update. The classes are obtained with a range scan over the `rdf:type` quads, followed by
+    /// one count per class.
     ///
     /// Usage example:
     /// ```
+    /// use rdf_fusion::io::RdfFormat;
     /// use rdf_fusion::model::*;
+    /// use rdf_fusion::model::vocab::rdf;
     /// use rdf_fusion::store::Store;
     ///
     /// # tokio_test::block_on(async {
-    /// let ex = NamedNodeRef::new("http://example.com")?;
-    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
-    ///
     /// let store = Store::default();
-    /// assert!(store.insert(quad).await?);
-    /// assert!(!store.insert(quad).await?);
-    ///
-    /// assert!(store.contains(quad).await?);
+    /// let file = "@prefix ex: <http://example.com/> .
+    ///     ex:a a ex:Person ; ex:name \"A\" .
+    ///     ex:b a ex:Person .";
+    /// store.load_from_reader(RdfFormat::Turtle, file.as_bytes()).await?;
+    ///
+    /// let person = NamedNodeRef::new("http://example.com/Person")?;
+    /// let summary = store.schema_summary().await?;
+    /// assert_eq!(Some(2), summary.class_count(person));
+    /// assert_eq!(Some(2), summary.property_count(rdf::TYPE));
+    /// assert_eq!(2, summary.properties.len());
     /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
     /// # }).unwrap();
     /// ```
-    pub async fn insert<'a>(
-        &self,
-        quad: impl Into<QuadRef<'a>>,
-    ) -> Result<bool, StorageError> {
-        let quad = vec![quad.into().into_owned()];
-        self.context
-            .storage()
-            .extend(quad)
-            .await
-            .map(|inserted| inserted > 0)
+    pub async fn schema_summary(&self) -> Result<SchemaSummary, QueryEvaluationError> {
+        let snapshot = self.snapshot().await?;
+        snapshot.schema_summary().await
     }
 
-    /// Atomically adds a set of quads to this store.
-    pub async fn extend(
+    /// Summarizes the vocabulary of the store without creating a snapshot.
+    async fn schema_summary_unsynchronized(
         &self,
-        quads: impl IntoIterator<Item = impl Into<Quad>>,
-    ) -> Result<(), StorageError> {
-        let quads = quads.into_iter().map(Into::into).collect::<Vec<_>>();
-        self.context.storage().extend(quads).await?;
+    ) -> Result<SchemaSummary, QueryEvaluationError> {
+        let properties = self
+            .top_terms(QuadPosition::Predicate, usize::MAX)
+            .await?
+            .into_iter()
+            .filter_map(|(term, count)| match term {
+                Term::NamedNode(property) => Some((property, count)),
+                _ => None,
+            })
+            .collect();
+
+        let mut classes = Vec::new();
+        for class in self.distinct_objects_of(rdf::TYPE, None).await? {
+            let count = self
+                .count_matching(None, Some(rdf::TYPE), Some(class.as_ref()), None)
+                .await?;
+            classes.push((class, count));
+        }
+        classes.sort_by_cached_key(|(class, count)| (Reverse(*count), class.to_string()));
+
+        Ok(SchemaSummary {
+            classes,
+            properties,
+        })
+    }
+
+    /// Diagnoses why the given quad pattern does (not) match any quads.
+    ///
+    /// Each bound component of the pattern is checked on its own across the entire dataset. The
+    /// report states whether the term is known to the store at all and how many quads contain it
+    /// at the position of the component. This helps with finding, for example, a typo in a
+    /// predicate IRI that causes an empty query result. All counts are computed on a single
+    /// snapshot of the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::diagnostics::ComponentDiagnosis;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let typo = NamedNodeRef::new("http://exmaple.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// let diagnosis = store.diagnose_pattern(Some(ex.into()), Some(typo), None, None).await?;
+    /// assert!(!diagnosis.has_matches());
+    /// assert_eq!(ComponentDiagnosis::Present(1), diagnosis.subject);
+    /// assert_eq!(ComponentDiagnosis::UnknownTerm, diagnosis.predicate);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn diagnose_pattern(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<PatternDiagnosis, QueryEvaluationError> {
+        let snapshot = self.snapshot().await?;
+        snapshot
+            .diagnose_pattern(subject, predicate, object, graph_name)
+            .await
+    }
+
+    /// Diagnoses a quad pattern without creating a snapshot.
+    async fn diagnose_pattern_unsynchronized(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<PatternDiagnosis, QueryEvaluationError> {
+        let subject_diagnosis = match subject {
+            None => ComponentDiagnosis::Unbound,
+            Some(subject) => {
+                let count = self.count_matching(Some(subject), None, None, None).await?;
+                self.diagnose_component(subject.into(), count)?
+            }
+        };
+        let predicate_diagnosis = match predicate {
+            None => ComponentDiagnosis::Unbound,
+            Some(predicate) => {
+                let count = self
+                    .count_matching(None, Some(predicate), None, None)
+                    .await?;
+                self.diagnose_component(predicate.into(), count)?
+            }
+        };
+        let object_diagnosis = match object {
+            None => ComponentDiagnosis::Unbound,
+            Some(object) => {
+                let count = self.count_matching(None, None, Some(object), None).await?;
+                self.diagnose_component(object, count)?
+            }
+        };
+        let graph_name_diagnosis = match graph_name {
+            None => ComponentDiagnosis::Unbound,
+            Some(graph_name) => {
+                let count = self
+                    .count_matching(None, None, None, Some(graph_name))
+                    .await?;
+                match graph_name {
+                    GraphNameRef::NamedNode(node) => {
+                        self.diagnose_component(node.into(), count)?
+                    }
+                    GraphNameRef::BlankNode(node) => {
+                        self.diagnose_component(node.into(), count)?
+                    }
+                    GraphNameRef::DefaultGraph if count == 0 => {
+                        ComponentDiagnosis::NotInPosition
+                    }
+                    GraphNameRef::DefaultGraph => ComponentDiagnosis::Present(count),
+                }
+            }
+        };
+        let matching_quads = self
+            .count_matching(subject, predicate, object, graph_name)
+            .await?;
+
+        Ok(PatternDiagnosis {
+            subject: subject_diagnosis,
+            predicate: predicate_diagnosis,
+            object: object_diagnosis,
+            graph_name: graph_name_diagnosis,
+            matching_quads,
+        })
+    }
+
+    /// Creates the [ComponentDiagnosis] of a bound `term` that occurs `count` times at the
+    /// position of the component.
+    fn diagnose_component(
+        &self,
+        term: TermRef<'_>,
+        count: usize,
+    ) -> Result<ComponentDiagnosis, StorageError> {
+        if count > 0 {
+            return Ok(ComponentDiagnosis::Present(count));
+        }
+
+        let Some(mapping) = self.context.storage().object_id_mapping() else {
+            return Ok(ComponentDiagnosis::NotInPosition);
+        };
+        match mapping.try_get_object_id(&PlainTermScalar::from(term))? {
+            None => Ok(ComponentDiagnosis::UnknownTerm),
+            Some(_) => Ok(ComponentDiagnosis::NotInPosition),
+        }
+    }
+
+    /// Returns the members of the [RDF collection](https://www.w3.org/TR/rdf11-mt/#rdf-collections)
+    /// (`rdf:first`/`rdf:rest` list) that starts at `list` in the given graph.
+    ///
+    /// The members are returned in list order, starting with the `rdf:first` value of `list` and
+    /// following the `rdf:rest` links until `rdf:nil` is reached. The list is read from a single
+    /// snapshot of the store. Hence, concurrent modifications do not lead to a partially updated
+    /// list. Each node of the list requires a separate lookup, which makes this method
+    /// unsuitable for very long lists.
+    ///
+    /// Malformed lists are reported with a [ListError] instead of returning a truncated list. A
+    /// list is malformed if one of its nodes does not have exactly one `rdf:first` and exactly
+    /// one `rdf:rest` value, or if the list contains a cycle.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::io::RdfFormat;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let file = "@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+    ///     <http://example.com/list> rdf:first 3 ; rdf:rest (1 2) .";
+    /// store.load_from_reader(RdfFormat::Turtle, file.as_bytes()).await?;
+    ///
+    /// let list = NamedNodeRef::new("http://example.com/list")?;
+    /// let members = store.list_members(list, GraphNameRef::DefaultGraph).await?;
+    /// assert_eq!(
+    ///     members,
+    ///     vec![Literal::from(3).into(), Literal::from(1).into(), Literal::from(2).into()]
+    /// );
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn list_members<'a>(
+        &self,
+        list: impl Into<NamedOrBlankNodeRef<'a>>,
+        graph_name: impl Into<GraphNameRef<'a>>,
+    ) -> Result<Vec<Term>, ListError> {
+        let snapshot = self.snapshot().await.map_err(QueryEvaluationError::from)?;
+        snapshot
+            .store
+            .list_members_unsynchronized(list.into(), graph_name.into())
+            .await
+    }
+
+    /// Reads the members of an RDF collection without creating a snapshot.
+    async fn list_members_unsynchronized(
+        &self,
+        list: NamedOrBlankNodeRef<'_>,
+        graph_name: GraphNameRef<'_>,
+    ) -> Result<Vec<Term>, ListError> {
+        let mut members = Vec::new();
+        let mut visited = HashSet::new();
+        let mut node = list.into_owned();
+        while node.as_ref() != NamedOrBlankNodeRef::from(rdf::NIL) {
+            if !visited.insert(node.clone()) {
+                return Err(ListError::Cycle(node));
+            }
+
+            let Some(first) = self.single_object(&node, rdf::FIRST, graph_name).await?
+            else {
+                return Err(ListError::InvalidFirst(node));
+            };
+            let rest = match self.single_object(&node, rdf::REST, graph_name).await? {
+                Some(Term::NamedNode(rest)) => NamedOrBlankNode::from(rest),
+                Some(Term::BlankNode(rest)) => NamedOrBlankNode::from(rest),
+                _ => return Err(ListError::InvalidRest(node)),
+            };
+
+            members.push(first);
+            node = rest;
+        }
+        Ok(members)
+    }
+
+    /// Returns the object of `subject` and `predicate` in `graph_name`, or [None] if there is no
+    /// or more than one such object.
+    async fn single_object(
+        &self,
+        subject: &NamedOrBlankNode,
+        predicate: NamedNodeRef<'_>,
+        graph_name: GraphNameRef<'_>,
+    ) -> Result<Option<Term>, QueryEvaluationError> {
+        let mut quads = self
+            .quads_for_pattern(
+                Some(subject.as_ref()),
+                Some(predicate),
+                None,
+                Some(graph_name),
+            )
+            .await?
+            .try_collect_to_vec()
+            .await?;
+        if quads.len() != 1 {
+            return Ok(None);
+        }
+        Ok(quads.pop().map(|quad| quad.object))
+    }
+
+    /// Returns if the store is empty.
+    ///
+    /// The store is empty if it contains no quads, even if it contains empty named graphs (see
+    /// [Store::len]).
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// assert!(store.is_empty().await?);
+    ///
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    /// assert!(!store.is_empty().await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn is_empty(&self) -> Result<bool, QueryEvaluationError> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// Checks whether the store contains the same quads as `other` up to a renaming of blank
+    /// nodes.
+    ///
+    /// Stores with a different number of quads are rejected without reading their quads.
+    /// Otherwise, the quads of both stores are collected into [Dataset]s whose blank nodes are
+    /// canonicalized before comparing them. This is useful for verifying that a backup has been
+    /// restored correctly (see [Store::backup]).
+    ///
+    /// <div class="warning">The quads of both stores are held in memory and the worst-case
+    /// complexity of the canonicalization is exponential in the number of blank nodes.</div>
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(BlankNodeRef::new("a")?, ex, ex, ex)).await?;
+    ///
+    /// let other = Store::default();
+    /// other.insert(QuadRef::new(BlankNodeRef::new("b")?, ex, ex, ex)).await?;
+    /// assert!(store.is_isomorphic_to(&other).await?);
+    ///
+    /// other.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    /// assert!(!store.is_isomorphic_to(&other).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn is_isomorphic_to(
+        &self,
+        other: &Store,
+    ) -> Result<bool, QueryEvaluationError> {
+        if self.len().await? != other.len().await? {
+            return Ok(false);
+        }
+
+        let mut dataset = self.collect_dataset().await?;
+        let mut other_dataset = other.collect_dataset().await?;
+        if dataset.len() != other_dataset.len() {
+            return Ok(false);
+        }
+
+        dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
+        other_dataset.canonicalize(CanonicalizationAlgorithm::Unstable);
+        Ok(dataset == other_dataset)
+    }
+
+    /// Collects all quads of the store into a [Dataset].
+    async fn collect_dataset(&self) -> Result<Dataset, QueryEvaluationError> {
+        let quads = self.stream().await?.try_collect_to_vec().await?;
+        Ok(quads.into_iter().collect())
+    }
+
+    /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/).
+    ///
+    /// All operations (`INSERT DATA`, `DELETE DATA`, `DELETE`/`INSERT ... WHERE`, `LOAD`, `CLEAR`,
+    /// `DROP`, and `CREATE`) are supported. The update is atomic with respect to failures: if one
+    /// of its operations fails, the changes of the previous operations are undone. `LOAD` only
+    /// supports `file:` IRIs and rejects all files, unless
+    /// [UpdateOptions::load_directories] is set via [Store::update_opt].
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// // insertion
+    /// store
+    ///     .update("INSERT DATA { <http://example.com> <http://example.com> <http://example.com> }")
+    ///     .await?;
+    ///
+    /// // we inspect the store contents
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// assert!(store.contains(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn update(
+        &self,
+        update: impl TryInto<Update, Error = impl Into<QueryEvaluationError>>,
+    ) -> Result<(), QueryEvaluationError> {
+        self.update_opt(update, UpdateOptions::default()).await
+    }
+
+    /// Executes a [SPARQL 1.1 update](https://www.w3.org/TR/sparql11-update/) with some options.
+    ///
+    /// The options are used for evaluating the `WHERE` clauses of the update and define the
+    /// directories from which `LOAD` may read files.
+    ///
+    /// ```
+    /// use rdf_fusion::store::Store;
+    /// use rdf_fusion::execution::sparql::QueryOptions;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// store.update_opt(
+    ///     "INSERT { ?s <http://example.com/p> ?s } WHERE { VALUES ?s { <http://example.com> } }",
+    ///     QueryOptions::default()
+    /// ).await?;
+    /// assert_eq!(store.len().await?, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn update_opt(
+        &self,
+        update: impl TryInto<Update, Error = impl Into<QueryEvaluationError>>,
+        options: impl Into<UpdateOptions>,
+    ) -> Result<(), QueryEvaluationError> {
+        let update = update.try_into().map_err(Into::into)?;
+        self.context.execute_update(&update, options.into()).await
+    }
+
+    /// Loads a RDF file under into the store.
+    ///
+    /// This function is atomic, quite slow and memory hungry. The file is parsed completely before
+    /// any quad is inserted, as streaming the quads like [Store::bulk_load] would make partially
+    /// loaded files visible and requires a `'static` reader. The terms of the quads are then
+    /// encoded in parallel, like in [Store::bulk_load] with the default [BulkLoadOptions].
+    ///
+    /// JSON-LD documents can be loaded using [RdfFormat::JsonLd](oxrdfio::RdfFormat::JsonLd).
+    /// The documents are expanded and converted to quads before being inserted. Only contexts that
+    /// are embedded in the document are resolved. Documents that reference remote contexts (e.g.,
+    /// `"@context": "https://schema.org/"`) cannot be loaded, as no document loader is configured.
+    ///
+    /// By default, the parser validates all IRIs. For large, trusted dumps, this validation can be
+    /// skipped by passing a [lenient](RdfParser::lenient) parser, which increases the throughput.
+    /// However, invalid IRIs are then stored as-is and may lead to unexpected query results or
+    /// invalid serializations.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::store::Store;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::io::{RdfParser, RdfFormat};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    ///
+    /// // insert a dataset file (former load_dataset method)
+    /// let file = b"<http://example.com> <http://example.com> <http://example.com> <http://example.com/g> .";
+    /// store.load_from_reader(RdfFormat::NQuads, file.as_ref()).await?;
+    ///
+    /// // insert a graph file (former load_graph method)
+    /// let file = b"<> <> <> .";
+    /// store.load_from_reader(
+    ///     RdfParser::from_format(RdfFormat::Turtle)
+    ///         .with_base_iri("http://example.com")?
+    ///         .without_named_graphs() // No named graphs allowed in the input
+    ///         .with_default_graph(NamedNodeRef::new("http://example.com/g2")?), // we put the file default graph inside of a named graph
+    ///     file.as_ref()
+    /// ).await?;
+    ///
+    /// // we inspect the store contents
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// assert!(store.contains(QuadRef::new(ex, ex, ex, NamedNodeRef::new("http://example.com/g")?)).await?);
+    /// assert!(store.contains(QuadRef::new(ex, ex, ex, NamedNodeRef::new("http://example.com/g2")?)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn load_from_reader(
+        &self,
+        parser: impl Into<RdfParser>,
+        reader: impl Read,
+    ) -> Result<(), LoaderError> {
+        let quads = parser
+            .into()
+            .rename_blank_nodes()
+            .for_reader(reader)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.context
+            .insert_quads_parallel(quads, BulkLoadOptions::default().parallelism)
+            .await
+            .map(|_| ())
+            .map_err(LoaderError::from)
+    }
+
+    /// Loads a large RDF file into the store.
+    ///
+    /// Contrary to [Store::load_from_reader], the quads are not collected before inserting them.
+    /// Instead, the file is parsed in a background thread while the already parsed quads are
+    /// inserted in batches of [BulkLoadOptions::batch_size] quads. The terms of each batch are
+    /// encoded by up to [BulkLoadOptions::parallelism] threads.
+    ///
+    /// <div class="warning">The load is not atomic. If the file cannot be parsed, the batches
+    /// before the error remain in the store. Concurrent queries may observe a partially loaded
+    /// file.</div>
+    ///
+    /// See [Store::load_from_reader] for more information on the supported formats.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::io::RdfFormat;
+    /// use rdf_fusion::store::{BulkLoadOptions, Store};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let file = b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .";
+    /// let options = BulkLoadOptions {
+    ///     batch_size: 1_000,
+    ///     ..BulkLoadOptions::default()
+    /// };
+    /// store.bulk_load(RdfFormat::NTriples, file.as_slice(), options).await?;
+    /// assert_eq!(store.len().await?, 1);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn bulk_load(
+        &self,
+        parser: impl Into<RdfParser>,
+        reader: impl Read + Send + 'static,
+        options: BulkLoadOptions,
+    ) -> Result<(), LoaderError> {
+        let parser = parser.into().rename_blank_nodes();
+        let batch_size = options.batch_size.max(1);
+        // Bounds the number of parsed batches that wait for their insertion.
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(2);
+        let parser_thread = std::thread::spawn(move || {
+            let mut quads = parser.for_reader(reader);
+            loop {
+                let batch = quads
+                    .by_ref()
+                    .take(batch_size)
+                    .collect::<Result<Vec<_>, _>>();
+                let is_last =
+                    !batch.as_ref().is_ok_and(|batch| batch.len() == batch_size);
+                if sender.blocking_send(batch).is_err() || is_last {
+                    return;
+                }
+            }
+        });
+
+        let mut result = Ok(());
+        while let Some(batch) = receiver.recv().await {
+            let inserted = match batch {
+                Ok(batch) if batch.is_empty() => continue,
+                Ok(batch) => self
+                    .context
+                    .insert_quads_parallel(batch, options.parallelism)
+                    .await
+                    .map_err(LoaderError::from),
+                Err(error) => Err(LoaderError::from(error)),
+            };
+            if let Err(error) = inserted {
+                result = Err(error);
+                break;
+            }
+        }
+
+        // Stops the parser thread if loading failed. Otherwise, the thread has already finished.
+        drop(receiver);
+        if let Err(panic) = parser_thread.join() {
+            std::panic::resume_unwind(panic);
+        }
+        result
+    }
+
+    /// Loads a RDF file into the store and places the triples of the file's default graph into
+    /// `target_graph`.
+    ///
+    /// Quads that already belong to a named graph (e.g., from N-Quads or TriG files) are kept in
+    /// their graph, unless `override_named_graphs` is set. In this case, all quads of the file are
+    /// placed into `target_graph`. This mirrors the `--to-graph` option of the CLI and allows
+    /// partitioning multiple files into separate named graphs.
+    ///
+    /// See [Store::load_from_reader] for more information on the supported formats.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::io::RdfFormat;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let file = b"<http://example.com/s> <http://example.com/p> <http://example.com/o> .";
+    /// let graph = NamedNodeRef::new("http://example.com/g")?;
+    /// store.load(RdfFormat::NTriples, file.as_ref(), graph, false).await?;
+    ///
+    /// let s = NamedNodeRef::new("http://example.com/s")?;
+    /// let p = NamedNodeRef::new("http://example.com/p")?;
+    /// let o = NamedNodeRef::new("http://example.com/o")?;
+    /// assert!(store.contains(QuadRef::new(s, p, o, graph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn load(
+        &self,
+        parser: impl Into<RdfParser>,
+        reader: impl Read,
+        target_graph: impl Into<GraphName>,
+        override_named_graphs: bool,
+    ) -> Result<(), LoaderError> {
+        let target_graph = target_graph.into();
+        let quads = parser
+            .into()
+            .rename_blank_nodes()
+            .with_default_graph(target_graph.clone())
+            .for_reader(reader)
+            .map(|quad| {
+                quad.map(|mut quad| {
+                    if override_named_graphs {
+                        quad.graph_name = target_graph.clone();
+                    }
+                    quad
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.context
+            .insert_quads(quads)
+            .await
+            .map(|_| ())
+            .map_err(LoaderError::from)
+    }
+
+    /// Loads a graph file (e.g., Turtle or N-Triples) into the graph `graph_name` of the store.
+    ///
+    /// All triples of the file are inserted in a single transaction. Files that contain named
+    /// graphs (e.g., TriG) are rejected. Use [Store::load] to load datasets into a graph.
+    ///
+    /// Returns the number of quads that were not already in the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::io::RdfFormat;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let store = Store::default();
+    /// let file = b"@prefix ex: <http://example.com/> . ex:s ex:p ex:o1, ex:o2 .";
+    /// let graph = NamedNodeRef::new("http://example.com/g")?;
+    /// assert_eq!(store.load_graph(file.as_ref(), RdfFormat::Turtle, graph).await?, 2);
+    ///
+    /// let s = NamedNodeRef::new("http://example.com/s")?;
+    /// let p = NamedNodeRef::new("http://example.com/p")?;
+    /// let o1 = NamedNodeRef::new("http://example.com/o1")?;
+    /// assert!(store.contains(QuadRef::new(s, p, o1, graph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn load_graph(
+        &self,
+        reader: impl Read,
+        parser: impl Into<RdfParser>,
+        graph_name: impl Into<GraphName>,
+    ) -> Result<usize, LoaderError> {
+        let quads = parser
+            .into()
+            .rename_blank_nodes()
+            .without_named_graphs()
+            .with_default_graph(graph_name)
+            .for_reader(reader)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.context
+            .insert_quads(quads)
+            .await
+            .map_err(LoaderError::from)
+    }
+
+    /// Loads a RDF file into the store and materializes the triples that are entailed by `rules`.
+    ///
+    /// This is a shorthand for [Store::load_from_reader] followed by [Store::materialize_rdfs].
+    /// Returns the number of inferred quads.
+    pub async fn load_from_reader_with_rdfs(
+        &self,
+        parser: impl Into<RdfParser>,
+        reader: impl Read,
+        rules: &RdfsRules,
+    ) -> Result<usize, StoreError> {
+        self.load_from_reader(parser, reader).await?;
+        Ok(self.materialize_rdfs(rules).await?)
+    }
+
+    /// Materializes the triples that are entailed by the given RDFS `rules` into the store.
+    ///
+    /// The rules are applied to each graph separately, and the inferred triples are inserted into
+    /// the graph of their premises. The rules are applied until a fixpoint is reached. As the store
+    /// has set semantics, running the inference again (e.g., after loading more data) only inserts
+    /// newly entailed triples.
+    ///
+    /// Returns the number of inferred quads that were not already in the store.
+    ///
+    /// Contrary to query-time entailment (see
+    /// [EntailmentRegime](rdf_fusion_execution::sparql::EntailmentRegime)), the inferred triples
+    /// are regular triples. Removing a premise does not remove the triples inferred from it.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::inference::RdfsRules;
+    /// use rdf_fusion::io::RdfFormat;
+    /// use rdf_fusion::model::vocab::rdf;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let file = br#"
+    ///     @prefix ex: <http://example.com/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///     ex:Dog rdfs:subClassOf ex:Animal .
+    ///     ex:rex a ex:Dog .
+    /// "#;
+    ///
+    /// let store = Store::default();
+    /// store.load_from_reader(RdfFormat::Turtle, file.as_ref()).await?;
+    /// assert_eq!(store.materialize_rdfs(&RdfsRules::all()).await?, 1);
+    /// assert_eq!(store.materialize_rdfs(&RdfsRules::all()).await?, 0);
+    ///
+    /// let rex = NamedNodeRef::new("http://example.com/rex")?;
+    /// let animal = NamedNodeRef::new("http://example.com/Animal")?;
+    /// assert!(store.contains(QuadRef::new(rex, rdf::TYPE, animal, GraphNameRef::DefaultGraph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn materialize_rdfs(
+        &self,
+        rules: &RdfsRules,
+    ) -> Result<usize, QueryEvaluationError> {
+        let mut graphs = vec![GraphName::DefaultGraph];
+        graphs.extend(self.named_graphs().await?.into_iter().map(GraphName::from));
+
+        let mut inferred = 0;
+        for graph in graphs {
+            loop {
+                let mut inferred_in_round = 0;
+                for query in rules.iter().flat_map(RdfsRule::queries) {
+                    let mut query = Query::parse(query, None)?;
+                    query.dataset_mut().set_default_graph(vec![graph.clone()]);
+                    inferred_in_round +=
+                        self.construct_into_graph(query, graph.clone()).await?;
+                }
+
+                if inferred_in_round == 0 {
+                    break;
+                }
+                inferred += inferred_in_round;
+            }
+        }
+        Ok(inferred)
+    }
+
+    /// Adds a quad to this store.
+    ///
+    /// Returns `true` if the quad was not already in the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let quad = QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph);
+    ///
+    /// let store = Store::default();
+    /// assert!(store.insert(quad).await?);
+    /// assert!(!store.insert(quad).await?);
+    ///
+    /// assert!(store.contains(quad).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn insert<'a>(
+        &self,
+        quad: impl Into<QuadRef<'a>>,
+    ) -> Result<bool, StorageError> {
+        self.context
+            .insert_quads(vec![quad.into().into_owned()])
+            .await
+            .map(|inserted| inserted > 0)
+    }
+
+    /// Atomically adds a set of quads to this store.
+    pub async fn extend(
+        &self,
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<(), StorageError> {
+        let quads = quads.into_iter().map(Into::into).collect::<Vec<_>>();
+        self.context.insert_quads(quads).await?;
         Ok(())
     }
 
+    /// Atomically adds the quads of an Arrow [RecordBatch] to this store.
+    ///
+    /// The batch must have the columns `graph`, `subject`, `predicate`, and `object` in the plain
+    /// term encoding (see [`PlainTermQuadBatch`]). A null value in the `graph` column represents
+    /// the default graph. This avoids creating a [Quad] for every row, which makes it suitable for
+    /// loading data from Arrow pipelines.
+    ///
+    /// Returns the number of quads that were not already in the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use datafusion::arrow::array::RecordBatch;
+    /// use rdf_fusion::encoding::EncodingArray;
+    /// use rdf_fusion::encoding::QuadStorageEncoding;
+    /// use rdf_fusion::encoding::plain_term::PlainTermArrayElementBuilder;
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let column = |term: Option<NamedNodeRef<'_>>| {
+    ///     let mut builder = PlainTermArrayElementBuilder::default();
+    ///     match term {
+    ///         None => builder.append_null(),
+    ///         Some(term) => builder.append_named_node(term),
+    ///     }
+    ///     builder.finish().into_array_ref()
+    /// };
+    /// let schema = QuadStorageEncoding::PlainTerm.quad_schema();
+    /// let batch = RecordBatch::try_new(
+    ///     schema.inner().clone(),
+    ///     vec![column(None), column(Some(ex)), column(Some(ex)), column(Some(ex))],
+    /// )?;
+    ///
+    /// let store = Store::default();
+    /// assert_eq!(store.insert_batch(batch).await?, 1);
+    ///
+    /// assert!(store.contains(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// [`PlainTermQuadBatch`]: rdf_fusion_encoding::plain_term::PlainTermQuadBatch
+    pub async fn insert_batch(&self, batch: RecordBatch) -> Result<usize, StorageError> {
+        self.context.insert_batch(batch).await
+    }
+
+    /// Evaluates a `CONSTRUCT` or `DESCRIBE` query and atomically inserts the resulting triples
+    /// into the graph `graph_name`.
+    ///
+    /// This is useful for materializing inferred triples into a dedicated graph. The result is the
+    /// same as evaluating `INSERT { GRAPH <graph_name> { template } } WHERE { pattern }`. Use
+    /// [QueryTripleStream::into_quads](rdf_fusion_execution::results::QueryTripleStream::into_quads)
+    /// for processing the quads without inserting them.
+    ///
+    /// The query is fully evaluated before any quad is inserted, and all quads are inserted in a
+    /// single write. Hence, if the evaluation fails, the store is left unchanged. Each solution
+    /// instantiates fresh blank nodes for the blank nodes in the template, also across repeated
+    /// calls, while blank nodes bound by the pattern keep their identity.
+    ///
+    /// Returns the number of quads that were not already in the store.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let inferred = NamedNodeRef::new("http://example.com/inferred")?;
+    ///
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    /// let inserted = store
+    ///     .construct_into_graph("CONSTRUCT { ?o ?p ?s } WHERE { ?s ?p ?o }", inferred)
+    ///     .await?;
+    ///
+    /// assert_eq!(inserted, 1);
+    /// assert!(store.contains(QuadRef::new(ex, ex, ex, inferred)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn construct_into_graph(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        graph_name: impl Into<GraphName>,
+    ) -> Result<usize, QueryEvaluationError> {
+        let QueryResults::Graph(triples) = self.query(query).await? else {
+            return Err(QueryEvaluationError::NotAGraph);
+        };
+        let quads = triples
+            .into_quads(graph_name)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(self.context.insert_quads(quads).await?)
+    }
+
     /// Removes a quad from this store.
     ///
     /// Returns `true` if the quad was in the store and has been removed.
@@ -561,7 +1785,7 @@ impl Store {
         &self,
         quad: impl Into<QuadRef<'a>>,
     ) -> Result<bool, StorageError> {
-        self.context.storage().remove(quad.into()).await
+        self.context.remove_quad(quad.into().into_owned()).await
     }
 
     /// Dumps the store into a file.
@@ -637,8 +1861,58 @@ impl Store {
         Ok(serializer.finish()?)
     }
 
+    /// Writes a backup of the entire store (all graphs) as N-Quads into `writer`.
+    ///
+    /// The backup is taken from a [snapshot](Store::snapshot) of the store. Hence, the backup is
+    /// consistent even if other tasks write to the store concurrently. Note that these writers
+    /// are blocked until the backup is complete. The writer can be wrapped (e.g., in an encoder of
+    /// a compression library) to produce a compressed archive.
+    ///
+    /// Named graphs that do not contain any quads cannot be represented in N-Quads and are
+    /// therefore not part of the backup. Use [Store::restore] to load the backup.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    ///
+    /// let backup = store.backup(Vec::new()).await?;
+    ///
+    /// let restored = Store::default();
+    /// restored.restore(backup.as_slice()).await?;
+    /// assert!(restored.contains(QuadRef::new(ex, ex, ex, ex)).await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn backup<W: Write>(&self, writer: W) -> Result<W, SerializerError> {
+        let snapshot = self.snapshot().await.map_err(QueryEvaluationError::from)?;
+        let writer = snapshot
+            .store
+            .dump_to_writer(RdfFormat::NQuads, writer)
+            .await?;
+        snapshot.release();
+        Ok(writer)
+    }
+
+    /// Restores a backup that has been created with [Store::backup].
+    ///
+    /// The quads of the backup are added to the current contents of the store. Restore the
+    /// backup into an empty store to reproduce the state of the store at the time of the backup.
+    pub async fn restore(&self, reader: impl Read) -> Result<(), LoaderError> {
+        self.load_from_reader(RdfFormat::NQuads, reader).await
+    }
+
     /// Returns all the store named graphs.
     ///
+    /// The named graphs are read from a single snapshot of the storage. Graphs that have been
+    /// created with [Store::insert_named_graph] or emptied with [Store::clear_graph] are included
+    /// even though they contain no quads. The default graph is never included.
+    ///
     /// Usage example:
     /// ```
     /// use rdf_fusion::model::*;
@@ -801,8 +2075,26 @@ impl Store {
 
     /// Optimizes the database for future workload.
     ///
-    /// Useful to call after a batch upload or another similar operation. Usually
-    pub async fn optimize(&self) -> Result<(), StorageError> {
+    /// Useful to call after a batch upload or many deletions. For the in-memory storage, this
+    /// rebuilds all index permutations compactly. Concurrent queries are not blocked while the
+    /// new indexes are being built. The returned report contains the approximate number of bytes
+    /// that have been reclaimed.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    /// store.optimize().await?;
+    /// assert_eq!(1, store.len().await?);
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError> {
         self.context.storage().optimize().await
     }
 
@@ -810,6 +2102,341 @@ impl Store {
     pub async fn validate(&self) -> Result<(), StorageError> {
         self.context.storage().validate().await
     }
+
+    /// Returns diagnostic information on the storage, such as the existing index permutations,
+    /// their number of quads, and their approximate memory usage.
+    ///
+    /// Returns [None] if the storage does not provide this information.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    ///
+    /// let info = store.storage_info().await?.expect("Supported by the in-memory storage");
+    /// assert_eq!(1, info.quad_count);
+    /// assert!(info.indexes.iter().all(|index| index.quad_count == 1));
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn storage_info(&self) -> Result<Option<StorageInfo>, StorageError> {
+        self.context.storage().storage_info().await
+    }
+
+    /// Creates a read-only [StoreSnapshot] of the current state of the store.
+    ///
+    /// All queries evaluated against the snapshot observe the same data, regardless of concurrent
+    /// modifications of the store. This is useful for evaluating a batch of queries that must be
+    /// consistent with each other.
+    ///
+    /// For the in-memory storage, writers do not wait for snapshots. Instead, the first write after
+    /// creating a snapshot copies the indexes, and the snapshot keeps the old version alive until it
+    /// is released. Release snapshots once they are no longer needed to free this memory.
+    ///
+    /// Usage example:
+    /// ```
+    /// use rdf_fusion::model::*;
+    /// use rdf_fusion::store::Store;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ex = NamedNodeRef::new("http://example.com")?;
+    /// let store = Store::default();
+    /// store.insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph)).await?;
+    ///
+    /// let snapshot = store.snapshot().await?;
+    /// store.insert(QuadRef::new(ex, ex, ex, ex)).await?;
+    /// assert_eq!(2, store.len().await?);
+    /// assert_eq!(1, snapshot.len().await?);
+    /// snapshot.release();
+    /// # Result::<_, Box<dyn std::error::Error>>::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn snapshot(&self) -> Result<StoreSnapshot, StorageError> {
+        let context = self.context.snapshot().await?;
+        Ok(StoreSnapshot {
+            store: Store::new(context),
+        })
+    }
+}
+
+/// A read-only view on a [Store] that is consistent across multiple queries.
+///
+/// Created with [Store::snapshot]. The snapshot is released when it is dropped or when
+/// [StoreSnapshot::release] is called. See [Store::snapshot] for the implications of holding a
+/// snapshot for a long time.
+#[derive(Clone)]
+pub struct StoreSnapshot {
+    /// A store that is backed by the snapshot of the storage.
+    store: Store,
+}
+
+impl StoreSnapshot {
+    /// Executes a [SPARQL](https://www.w3.org/TR/sparql11-query/) query against the snapshot.
+    ///
+    /// See [Store::query] for details.
+    pub async fn query(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+    ) -> Result<QueryResults, QueryEvaluationError> {
+        self.store.query(query).await
+    }
+
+    /// Executes a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) with some options
+    /// against the snapshot.
+    ///
+    /// See [Store::query_opt] for details.
+    pub async fn query_opt(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        options: QueryOptions,
+    ) -> Result<QueryResults, QueryEvaluationError> {
+        self.store.query_opt(query, options).await
+    }
+
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) against the
+    /// snapshot and returns at most its first `limit` solutions.
+    ///
+    /// See [Store::query_partial] for details.
+    pub async fn query_partial(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        limit: usize,
+    ) -> Result<PartialQuerySolutions, QueryEvaluationError> {
+        self.store.query_partial(query, limit).await
+    }
+
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) against the
+    /// snapshot and returns the solutions computed within `timeout`.
+    ///
+    /// See [Store::query_best_effort] for details.
+    pub async fn query_best_effort(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        timeout: Duration,
+    ) -> Result<BestEffortQuerySolutions, QueryEvaluationError> {
+        self.store.query_best_effort(query, timeout).await
+    }
+
+    /// Executes a [SPARQL 1.1 `SELECT` query](https://www.w3.org/TR/sparql11-query/) against
+    /// this snapshot and `other`, and returns the solutions that differ between the two.
+    ///
+    /// See [Store::query_diff] for details.
+    pub async fn query_diff(
+        &self,
+        other: &StoreSnapshot,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+    ) -> Result<QuerySolutionsDiff, QueryEvaluationError> {
+        self.store.query_diff(&other.store, query).await
+    }
+
+    /// Executes a [SPARQL 1.1 query](https://www.w3.org/TR/sparql11-query/) with some options
+    /// against the snapshot and returns a query explanation.
+    ///
+    /// See [Store::explain_query_opt] for details.
+    pub async fn explain_query_opt(
+        &self,
+        query: impl TryInto<Query, Error = impl Into<QueryEvaluationError> + std::fmt::Debug>,
+        options: QueryOptions,
+    ) -> Result<(QueryResults, QueryExplanation), QueryEvaluationError> {
+        self.store.explain_query_opt(query, options).await
+    }
+
+    /// Retrieves the quads of the snapshot with a filter on each quad component.
+    ///
+    /// See [Store::quads_for_pattern] for details.
+    pub async fn quads_for_pattern(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<QuadStream, QueryEvaluationError> {
+        self.store
+            .quads_for_pattern(subject, predicate, object, graph_name)
+            .await
+    }
+
+    /// Returns the [ObjectId] of `term`.
+    ///
+    /// See [Store::encode_term] for details.
+    pub fn encode_term<'a>(
+        &self,
+        term: impl Into<TermRef<'a>>,
+    ) -> Result<Option<ObjectId>, StorageError> {
+        self.store.encode_term(term)
+    }
+
+    /// Returns all the quads that match the given pattern of object ids.
+    ///
+    /// See [Store::quads_for_object_id_pattern] for details.
+    pub async fn quads_for_object_id_pattern(
+        &self,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<Quad>, QueryEvaluationError> {
+        self.store
+            .quads_for_object_id_pattern(subject, predicate, object, graph_name)
+            .await
+    }
+
+    /// Returns all the quads contained in the snapshot.
+    pub async fn stream(&self) -> Result<QuadStream, QueryEvaluationError> {
+        self.store.stream().await
+    }
+
+    /// Checks if the snapshot contains a given quad.
+    pub async fn contains<'a>(
+        &self,
+        quad: impl Into<QuadRef<'a>>,
+    ) -> Result<bool, QueryEvaluationError> {
+        self.store.contains(quad).await
+    }
+
+    /// Returns the number of quads in the snapshot.
+    pub async fn len(&self) -> Result<usize, QueryEvaluationError> {
+        self.store.len().await
+    }
+
+    /// Returns the number of quads in the snapshot that match the given pattern.
+    ///
+    /// See [Store::count_matching] for details.
+    pub async fn count_matching(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<usize, QueryEvaluationError> {
+        self.store
+            .count_matching(subject, predicate, object, graph_name)
+            .await
+    }
+
+    /// Returns the distinct predicates that are used by the given `subject` in the snapshot.
+    ///
+    /// See [Store::predicates_for] for details.
+    pub async fn predicates_for<'a>(
+        &self,
+        subject: impl Into<NamedOrBlankNodeRef<'a>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<NamedNode>, QueryEvaluationError> {
+        self.store.predicates_for(subject, graph_name).await
+    }
+
+    /// Returns the distinct objects of the quads with the given `predicate` in the snapshot.
+    ///
+    /// See [Store::distinct_objects_of] for details.
+    pub async fn distinct_objects_of<'a>(
+        &self,
+        predicate: impl Into<NamedNodeRef<'a>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<Vec<Term>, QueryEvaluationError> {
+        self.store.distinct_objects_of(predicate, graph_name).await
+    }
+
+    /// Returns the `k` most frequent terms in the given `position` in the snapshot.
+    ///
+    /// See [Store::top_terms] for details.
+    pub async fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> Result<Vec<(Term, usize)>, QueryEvaluationError> {
+        self.store.top_terms(position, k).await
+    }
+
+    /// Summarizes the classes and properties of the snapshot.
+    ///
+    /// See [Store::schema_summary] for details.
+    pub async fn schema_summary(&self) -> Result<SchemaSummary, QueryEvaluationError> {
+        self.store.schema_summary_unsynchronized().await
+    }
+
+    /// Diagnoses why the given quad pattern does (not) match any quads in the snapshot.
+    ///
+    /// See [Store::diagnose_pattern] for details.
+    pub async fn diagnose_pattern(
+        &self,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Result<PatternDiagnosis, QueryEvaluationError> {
+        self.store
+            .diagnose_pattern_unsynchronized(subject, predicate, object, graph_name)
+            .await
+    }
+
+    /// Returns the members of the RDF collection that starts at `list` in the snapshot.
+    ///
+    /// See [Store::list_members] for details.
+    pub async fn list_members<'a>(
+        &self,
+        list: impl Into<NamedOrBlankNodeRef<'a>>,
+        graph_name: impl Into<GraphNameRef<'a>>,
+    ) -> Result<Vec<Term>, ListError> {
+        self.store
+            .list_members_unsynchronized(list.into(), graph_name.into())
+            .await
+    }
+
+    /// Returns if the snapshot is empty.
+    pub async fn is_empty(&self) -> Result<bool, QueryEvaluationError> {
+        self.store.is_empty().await
+    }
+
+    /// Returns all the named graphs of the snapshot.
+    pub async fn named_graphs(&self) -> Result<Vec<NamedOrBlankNode>, StorageError> {
+        self.store.named_graphs().await
+    }
+
+    /// Checks if the snapshot contains a given graph.
+    pub async fn contains_named_graph<'a>(
+        &self,
+        graph_name: impl Into<NamedOrBlankNodeRef<'a>>,
+    ) -> Result<bool, QueryEvaluationError> {
+        self.store.contains_named_graph(graph_name).await
+    }
+
+    /// Releases the snapshot.
+    ///
+    /// This is equivalent to dropping the snapshot. Note that the underlying resources are only
+    /// freed once all clones of this snapshot have been released.
+    pub fn release(self) {
+        drop(self);
+    }
+}
+
+/// Options for bulk loading RDF files with [Store::bulk_load].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BulkLoadOptions {
+    /// The number of quads that are inserted into the store at once.
+    ///
+    /// Larger batches reduce the overhead of acquiring exclusive access to the storage, while
+    /// more quads are held in memory. Defaults to 100,000.
+    pub batch_size: usize,
+    /// The maximum number of threads that encode the quads of a batch.
+    ///
+    /// Encoding the terms of a quad (e.g., into object ids) is the most expensive part of
+    /// inserting them. Defaults to the available parallelism of the system.
+    pub parallelism: NonZeroUsize,
+}
+
+impl Default for BulkLoadOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100_000,
+            parallelism: std::thread::available_parallelism()
+                .unwrap_or(NonZeroUsize::MIN),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -825,6 +2452,7 @@ mod tests {
     fn test_send_sync() {
         fn is_send_sync<T: Send + Sync>() {}
         is_send_sync::<Store>();
+        is_send_sync::<StoreSnapshot>();
     }
 
     #[tokio::test]
@@ -892,7 +2520,7 @@ mod tests {
                 GraphName::DefaultGraph,
             ),
         ];
-        let all_quads = vec![
+        let all_quads = [
             named_quad.clone(),
             Quad::new(
                 main_s.clone(),
@@ -1,11 +1,54 @@
 #![cfg(test)]
 #![allow(clippy::panic_in_result_fn)]
 
-use rdf_fusion::io::RdfFormat;
+use async_trait::async_trait;
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::post;
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::concat_batches;
+use datafusion::common::runtime::SpawnedTask;
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::physical_plan::aggregates::AggregateExec;
+use datafusion::physical_plan::joins::HashJoinExec;
+use datafusion::physical_plan::metrics::MetricValue;
+use datafusion::physical_plan::{ExecutionPlan, InputOrderMode, displayable};
+use datafusion::prelude::SessionConfig;
+use futures::{StreamExt, TryStreamExt};
+use rdf_fusion::api::storage::{QuadPosition, QuadStorage};
+use rdf_fusion::diagnostics::ComponentDiagnosis;
+use rdf_fusion::encoding::object_id::{ObjectIdEncoding, ObjectIdMapping};
+use rdf_fusion::encoding::typed_value::LiteralNormalization;
+use rdf_fusion::error::{ListError, LoaderError};
+use rdf_fusion::execution::RdfFusionContext;
+use rdf_fusion::execution::results::{QueryResults, QuerySolution};
+use rdf_fusion::execution::sparql::error::QueryEvaluationError;
+use rdf_fusion::execution::sparql::{
+    EntailmentRegime, PathDepthLimit, Query, QueryCancellationToken, QueryOptions,
+    ServiceResolver, UpdateOptions,
+};
+use rdf_fusion::functions::aggregates::{
+    VarianceStatistic, group_concat_sorted_typed_value, median_typed_value,
+    variance_typed_value,
+};
+use rdf_fusion::functions::datatypes::CustomDatatype;
+use rdf_fusion::inference::{RdfsRule, RdfsRules};
+use rdf_fusion::io::{JsonLdProfileSet, RdfFormat, RdfParser};
 use rdf_fusion::model::vocab::{rdf, xsd};
-use rdf_fusion::model::{GraphNameRef, LiteralRef, NamedNodeRef, QuadRef};
-use rdf_fusion::store::Store;
+use rdf_fusion::model::{
+    BlankNode, GraphName, GraphNameRef, Literal, LiteralRef, NamedNode, NamedNodeRef,
+    NamedOrBlankNode, Quad, QuadRef, Term, Variable,
+};
+use rdf_fusion::storage::memory::{MemObjectIdMapping, MemQuadStorage};
+use rdf_fusion::store::{BulkLoadOptions, Store};
+use std::collections::{BTreeSet, HashSet};
 use std::error::Error;
+use std::fmt::Write;
+use std::io::Cursor;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
 
 #[allow(clippy::non_ascii_literal)]
 const DATA: &str = r#"
@@ -38,6 +81,40 @@ GRAPH <http://www.wikidata.org/wiki/Special:EntityData/Q90> {
         schema:postalCode "75001" .
 }
 "#;
+
+const JSON_LD_DATA: &str = r#"{
+    "@context": {
+        "schema": "http://schema.org/",
+        "name": "schema:name",
+        "population": {
+            "@id": "schema:population",
+            "@type": "http://www.w3.org/2001/XMLSchema#integer"
+        }
+    },
+    "@id": "http://example.com/g",
+    "@graph": [
+        {
+            "@id": "http://www.wikidata.org/entity/Q90",
+            "@type": "schema:City",
+            "name": { "@value": "Paris", "@language": "fr" },
+            "population": "2000000"
+        }
+    ]
+}"#;
+
+const RDFS_DATA: &str = r#"
+@prefix ex: <http://example.com/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+GRAPH ex:g {
+    ex:Dog rdfs:subClassOf ex:Mammal .
+    ex:Mammal rdfs:subClassOf ex:Animal .
+    ex:hasPuppy rdfs:subPropertyOf ex:hasChild .
+    ex:hasChild rdfs:domain ex:Parent ; rdfs:range ex:Offspring .
+    ex:rex a ex:Dog ; ex:hasPuppy ex:fido ; ex:name "Rex" .
+}
+"#;
+
 const NUMBER_OF_TRIPLES: usize = 8;
 
 fn quads(graph_name: impl Into<GraphNameRef<'static>>) -> Vec<QuadRef<'static>> {
@@ -124,6 +201,171 @@ async fn test_load_dataset() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_load_with_lenient_parser_skips_iri_validation() -> Result<(), Box<dyn Error>>
+{
+    let data = "<http://example.com/{s}> <http://example.com/p> <http://example.com/o> .";
+
+    let strict_store = Store::default();
+    let result = strict_store
+        .load_from_reader(RdfFormat::NTriples, data.as_bytes())
+        .await;
+    assert!(result.is_err());
+    assert!(strict_store.is_empty().await?);
+
+    let lenient_store = Store::default();
+    lenient_store
+        .load_from_reader(
+            RdfParser::from_format(RdfFormat::NTriples).lenient(),
+            data.as_bytes(),
+        )
+        .await?;
+    assert_eq!(lenient_store.len().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_json_ld_with_embedded_context() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::JsonLd {
+                profile: JsonLdProfileSet::empty(),
+            },
+            JSON_LD_DATA.as_bytes(),
+        )
+        .await?;
+
+    let graph = NamedNodeRef::new_unchecked("http://example.com/g");
+    let paris = NamedNodeRef::new_unchecked("http://www.wikidata.org/entity/Q90");
+    let schema_name = NamedNodeRef::new_unchecked("http://schema.org/name");
+    let schema_population = NamedNodeRef::new_unchecked("http://schema.org/population");
+    assert!(
+        store
+            .contains(QuadRef::new(
+                paris,
+                rdf::TYPE,
+                NamedNodeRef::new_unchecked("http://schema.org/City"),
+                graph,
+            ))
+            .await?
+    );
+    assert!(
+        store
+            .contains(QuadRef::new(
+                paris,
+                schema_name,
+                LiteralRef::new_language_tagged_literal_unchecked("Paris", "fr"),
+                graph,
+            ))
+            .await?
+    );
+    assert!(
+        store
+            .contains(QuadRef::new(
+                paris,
+                schema_population,
+                LiteralRef::new_typed_literal("2000000", xsd::INTEGER),
+                graph,
+            ))
+            .await?
+    );
+    assert_eq!(store.len().await?, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_json_ld_with_remote_context_fails() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let result = store
+        .load_from_reader(
+            RdfFormat::JsonLd {
+                profile: JsonLdProfileSet::empty(),
+            },
+            r#"{
+                "@context": "http://example.com/context.jsonld",
+                "@id": "http://example.com/s",
+                "name": "s"
+            }"#
+            .as_bytes(),
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert!(store.is_empty().await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bulk_load_matches_load_from_reader() -> Result<(), Box<dyn Error>> {
+    const NUMBER_OF_SUBJECTS: usize = 5_000;
+    let mut file = String::new();
+    for i in 0..NUMBER_OF_SUBJECTS {
+        writeln!(
+            file,
+            "<http://example.com/s{i}> <http://example.com/p{}> <http://example.com/s{}> .",
+            i % 7,
+            (i + 1) % NUMBER_OF_SUBJECTS
+        )?;
+        writeln!(
+            file,
+            "<http://example.com/s{i}> <http://example.com/value> \"{}\" .",
+            i % 100
+        )?;
+        writeln!(
+            file,
+            "<http://example.com/s{i}> <http://example.com/node> _:b{} .",
+            i % 50
+        )?;
+    }
+
+    let store = Store::default();
+    let options = BulkLoadOptions {
+        batch_size: 1_000,
+        parallelism: NonZeroUsize::new(4).unwrap(),
+    };
+    store
+        .bulk_load(RdfFormat::NTriples, Cursor::new(file.clone()), options)
+        .await?;
+    assert_eq!(store.len().await?, 3 * NUMBER_OF_SUBJECTS);
+
+    let expected = Store::default();
+    expected
+        .load_from_reader(RdfFormat::NTriples, file.as_bytes())
+        .await?;
+    assert!(store.is_isomorphic_to(&expected).await?);
+
+    // Every term is interned only once, even if it is encoded concurrently.
+    let value = LiteralRef::new_simple_literal("42");
+    assert_eq!(
+        store
+            .count_matching(None, None, Some(value.into()), None)
+            .await?,
+        NUMBER_OF_SUBJECTS / 100
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bulk_load_keeps_batches_before_parse_error() -> Result<(), Box<dyn Error>> {
+    let file = "<http://example.com/s> <http://example.com/p> <http://example.com/o1> .\n\
+        <http://example.com/s> <http://example.com/p> <http://example.com/o2> .\n\
+        <http://example.com/s> <http://example.com/p> .\n";
+    let store = Store::default();
+    let options = BulkLoadOptions {
+        batch_size: 2,
+        ..BulkLoadOptions::default()
+    };
+
+    let result = store
+        .bulk_load(RdfFormat::NTriples, file.as_bytes(), options)
+        .await;
+
+    assert!(matches!(result, Err(LoaderError::Parsing(_))));
+    assert_eq!(store.len().await?, 2);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_load_graph_generates_new_blank_nodes() -> Result<(), Box<dyn Error>> {
     let store = Store::default();
@@ -175,3 +417,3655 @@ async fn test_dump_dataset() -> Result<(), Box<dyn Error>> {
     );
     Ok(())
 }
+
+#[tokio::test]
+async fn test_query_plan_is_cached() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+
+    for query in [
+        "ASK { ?s a <http://schema.org/City> }",
+        "ASK {\n    ?s   a <http://schema.org/City>\n}",
+    ] {
+        let QueryResults::Boolean(result) = store.query(query).await? else {
+            panic!("Expected a boolean result");
+        };
+        assert!(result);
+    }
+
+    assert_eq!(store.context().query_plan_cache().len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_plan_with_now_is_not_cached() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    store.query("SELECT (NOW() AS ?now) WHERE {}").await?;
+
+    assert!(store.context().query_plan_cache().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_not_equal_propagates_type_errors() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    // "a" = 1 raises a type error. Hence, neither "a" != 1 nor its negation may hold.
+    for query in [
+        "ASK { FILTER(\"a\" = 1) }",
+        "ASK { FILTER(\"a\" != 1) }",
+        "ASK { FILTER(!(\"a\" != 1)) }",
+        "ASK { BIND(\"a\" != 1 AS ?result) FILTER(BOUND(?result)) }",
+    ] {
+        let QueryResults::Boolean(result) = store.query(query).await? else {
+            panic!("Expected a boolean result");
+        };
+        assert!(!result, "{query}");
+    }
+
+    let QueryResults::Boolean(result) = store.query("ASK { FILTER(1 != 2) }").await?
+    else {
+        panic!("Expected a boolean result");
+    };
+    assert!(result);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_order_by_mixed_types_with_strict_filter() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:s ex:p 3.5, "a", ex:o, _:b, "2020-01-01"^^xsd:date, 1 .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    // ORDER BY uses a total order across all types.
+    let sorted = select_objects(
+        &store,
+        "SELECT ?o WHERE { ?s <http://example.com/p> ?o } ORDER BY ?o",
+    )
+    .await?;
+    assert_eq!(sorted.len(), 6);
+    assert!(sorted[0].is_blank_node());
+    assert_eq!(
+        sorted[1],
+        Term::from(NamedNode::new("http://example.com/o")?)
+    );
+    let one = sorted
+        .iter()
+        .position(|t| *t == Term::from(Literal::from(1)));
+    let three_and_a_half = sorted
+        .iter()
+        .position(|t| *t == Term::from(Literal::new_typed_literal("3.5", xsd::DECIMAL)));
+    assert!(one.is_some() && three_and_a_half.is_some());
+    assert!(one < three_and_a_half);
+
+    // Comparisons in the FILTER of the same query are strict. Incomparable values raise a type
+    // error and are removed.
+    let filtered = select_objects(
+        &store,
+        "SELECT ?o WHERE { ?s <http://example.com/p> ?o FILTER(?o < 3 || ?o >= 3) } ORDER BY ?o",
+    )
+    .await?;
+    assert_eq!(
+        filtered,
+        vec![
+            Term::from(Literal::from(1)),
+            Term::from(Literal::new_typed_literal("3.5", xsd::DECIMAL))
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quads_for_object_id_pattern() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let s = NamedNodeRef::new("http://example.com/s")?;
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let q = NamedNodeRef::new("http://example.com/q")?;
+    let g = NamedNodeRef::new("http://example.com/g")?;
+    let o = LiteralRef::new_simple_literal("o");
+    let in_default = QuadRef::new(s, p, o, GraphNameRef::DefaultGraph);
+    let in_graph = QuadRef::new(s, q, o, g);
+    store
+        .extend([in_default, in_graph, QuadRef::new(p, p, s, g)])
+        .await?;
+
+    assert_eq!(
+        store.encode_term(NamedNodeRef::new("http://example.com/unknown")?)?,
+        None
+    );
+    let s_id = store.encode_term(s)?.expect("s is known");
+    let p_id = store.encode_term(p)?.expect("p is known");
+    let o_id = store.encode_term(o)?.expect("o is known");
+
+    let quads = store
+        .quads_for_object_id_pattern(Some(&s_id), None, None, None)
+        .await?;
+    assert_eq!(
+        quads.into_iter().collect::<HashSet<_>>(),
+        HashSet::from([in_default.into_owned(), in_graph.into_owned()])
+    );
+    let quads = store
+        .quads_for_object_id_pattern(Some(&s_id), Some(&p_id), Some(&o_id), None)
+        .await?;
+    assert_eq!(quads, vec![in_default.into_owned()]);
+    let quads = store
+        .quads_for_object_id_pattern(None, None, Some(&o_id), Some(g.into()))
+        .await?;
+    assert_eq!(quads, vec![in_graph.into_owned()]);
+
+    // The object id remains valid after the quads of the term have been removed.
+    store.remove(in_default).await?;
+    store.remove(in_graph).await?;
+    assert_eq!(store.encode_term(s)?, Some(s_id.clone()));
+    assert!(
+        store
+            .quads_for_object_id_pattern(Some(&s_id), None, None, None)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bounded_property_path() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let nodes = (0..6)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    // A chain s0 -> s1 -> ... -> s5 with a shortcut from s0 to s2.
+    store
+        .extend(
+            nodes
+                .windows(2)
+                .map(|w| QuadRef::new(&w[0], p, &w[1], GraphNameRef::DefaultGraph))
+                .chain([QuadRef::new(
+                    &nodes[0],
+                    p,
+                    &nodes[2],
+                    GraphNameRef::DefaultGraph,
+                )]),
+        )
+        .await?;
+
+    // s3 is reachable with two and three steps but must only be returned once.
+    let objects = select_repeated_objects(
+        &store,
+        "SELECT ?o WHERE { <http://example.com/s0> <http://example.com/p>{2,3} ?o }",
+    )
+    .await?;
+    assert_eq!(objects.len(), 3);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        HashSet::from([
+            Term::from(nodes[2].clone()),
+            Term::from(nodes[3].clone()),
+            Term::from(nodes[4].clone())
+        ])
+    );
+
+    let objects = select_repeated_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s0 ex:p{4} ?o }",
+    )
+    .await?;
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        HashSet::from([Term::from(nodes[4].clone()), Term::from(nodes[5].clone())])
+    );
+
+    let objects = select_repeated_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s5 ^ex:p{3,} ?o }",
+    )
+    .await?;
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        HashSet::from([
+            Term::from(nodes[0].clone()),
+            Term::from(nodes[1].clone()),
+            Term::from(nodes[2].clone())
+        ])
+    );
+
+    // Repetitions are a non-standard extension and must be enabled explicitly.
+    assert!(
+        Query::parse(
+            "SELECT ?o WHERE { <http://example.com/s0> <http://example.com/p>{2} ?o }",
+            None
+        )
+        .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bounded_property_path_marker_iri_is_regular_iri()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    store
+        .extend([QuadRef::new(p, p, o, GraphNameRef::DefaultGraph)])
+        .await?;
+
+    // The IRI looks like the marker of a repetition but has not been created by the parser.
+    let query = "SELECT ?o WHERE { \
+        <http://example.com/p> (<http://example.com/p>|<urn:rdf-fusion:path-repetition:0:0>) ?o }";
+    assert_eq!(select_objects(&store, query).await?, vec![Term::from(o)]);
+    assert_eq!(
+        select_repeated_objects(&store, query).await?,
+        vec![Term::from(o)]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bounded_property_path_with_too_many_steps() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let query = Query::parse_with_path_repetitions(
+        "SELECT * WHERE { ?s <http://example.com/p>{0,300} ?o }",
+        None,
+    )?;
+    let result = store
+        .context()
+        .execute_query(&query, QueryOptions::default())
+        .await;
+    assert!(result.is_err_and(|err| err.to_string().contains("limited to 16 steps")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transitive_property_path_on_cyclic_graph() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            "@prefix ex: <http://example.com/> .
+            ex:s0 ex:p ex:s1 .
+            ex:s1 ex:p ex:s2 .
+            ex:s2 ex:p ex:s0, ex:s3 ."
+                .as_bytes(),
+        )
+        .await?;
+    let node = |i: usize| {
+        Term::from(NamedNode::new_unchecked(format!("http://example.com/s{i}")))
+    };
+    let nodes = |ids: &[usize]| ids.iter().map(|i| node(*i)).collect::<HashSet<_>>();
+
+    // The cycle s0 -> s1 -> s2 -> s0 must be visited only once.
+    let objects = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s0 ex:p+ ?o }",
+    )
+    .await?;
+    assert_eq!(objects.len(), 4);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        nodes(&[0, 1, 2, 3])
+    );
+
+    let objects = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ?o ex:p+ ex:s3 }",
+    )
+    .await?;
+    assert_eq!(objects.len(), 3);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        nodes(&[0, 1, 2])
+    );
+
+    let objects = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s3 ex:p+ ?o }",
+    )
+    .await?;
+    assert!(objects.is_empty());
+
+    let objects = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s1 ex:p* ?o }",
+    )
+    .await?;
+    assert_eq!(objects.len(), 4);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        nodes(&[0, 1, 2, 3])
+    );
+
+    // Each of s0, s1, and s2 reaches all nodes.
+    let count = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT (COUNT(*) AS ?o) WHERE { ?s ex:p+ ?t }",
+    )
+    .await?;
+    assert_eq!(count, vec![Term::from(Literal::from(12))]);
+
+    // The fixed start is passed to the closure operator.
+    let (_, explanation) = store
+        .explain_query_opt(
+            "PREFIX ex: <http://example.com/>
+            SELECT ?o WHERE { ex:s0 ex:p+ ?o }",
+            QueryOptions::default(),
+        )
+        .await?;
+    assert!(
+        explanation
+            .physical_plan_string()
+            .contains("KleenePlusPathExec: source=<http://example.com/s0>"),
+        "{}",
+        explanation.physical_plan_string()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transitive_property_path_on_long_chain() -> Result<(), Box<dyn Error>> {
+    const LENGTH: usize = 500;
+
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let nodes = (0..=LENGTH)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            nodes
+                .windows(2)
+                .map(|w| QuadRef::new(&w[0], p, &w[1], GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let objects = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s0 ex:p+ ?o }",
+    )
+    .await?;
+    assert_eq!(objects.len(), LENGTH);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        nodes[1..].iter().cloned().map(Term::from).collect()
+    );
+
+    let objects = select_objects(
+        &store,
+        &format!(
+            "PREFIX ex: <http://example.com/>
+            SELECT ?o WHERE {{ ?o ex:p+ ex:s{LENGTH} }}"
+        ),
+    )
+    .await?;
+    assert_eq!(objects.len(), LENGTH);
+
+    // The closure of the chain contains a path from each node to all following nodes.
+    let count = select_objects(
+        &store,
+        "PREFIX ex: <http://example.com/>
+        SELECT (COUNT(*) AS ?o) WHERE { ?s ex:p+ ?t }",
+    )
+    .await?;
+    let expected = i64::try_from(LENGTH * (LENGTH + 1) / 2)?;
+    assert_eq!(count, vec![Term::from(Literal::from(expected))]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transitive_property_path_depth_limit() -> Result<(), Box<dyn Error>> {
+    const LENGTH: usize = 10;
+
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let nodes = (0..=LENGTH)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            nodes
+                .windows(2)
+                .map(|w| QuadRef::new(&w[0], p, &w[1], GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+    let options = |path_depth_limit| QueryOptions {
+        path_depth_limit,
+        ..QueryOptions::default()
+    };
+    let one_or_more = "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s0 ex:p+ ?o }";
+    let zero_or_more = "PREFIX ex: <http://example.com/>
+        SELECT ?o WHERE { ex:s0 ex:p* ?o }";
+
+    // By default, paths of arbitrary length are followed.
+    let objects = select_objects_opt(&store, one_or_more, options(None)).await?;
+    assert_eq!(objects.len(), LENGTH);
+
+    // Truncating only returns the nodes within the maximum depth.
+    let objects = select_objects_opt(
+        &store,
+        one_or_more,
+        options(Some(PathDepthLimit::truncate(3))),
+    )
+    .await?;
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        nodes[1..=3].iter().cloned().map(Term::from).collect()
+    );
+    let objects = select_objects_opt(
+        &store,
+        zero_or_more,
+        options(Some(PathDepthLimit::truncate(3))),
+    )
+    .await?;
+    assert_eq!(objects.len(), 4);
+
+    // Exceeding the maximum depth fails the query, while reaching it does not.
+    let result =
+        select_objects_opt(&store, one_or_more, options(Some(PathDepthLimit::error(3))))
+            .await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the maximum depth of 3")
+    );
+    let objects = select_objects_opt(
+        &store,
+        one_or_more,
+        options(Some(PathDepthLimit::error(LENGTH))),
+    )
+    .await?;
+    assert_eq!(objects.len(), LENGTH);
+
+    let (_, explanation) = store
+        .explain_query_opt(one_or_more, options(Some(PathDepthLimit::truncate(3))))
+        .await?;
+    assert!(
+        explanation
+            .physical_plan_string()
+            .contains("max_depth=3 on_exceeded=truncate")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_results_as_string_batches() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            "@prefix ex: <http://example.com/> .
+            ex:a ex:p 1 ; ex:q \"x\"@en .
+            ex:b ex:p 2 ."
+                .as_bytes(),
+        )
+        .await?;
+
+    let QueryResults::Solutions(solutions) = store
+        .query(
+            "PREFIX ex: <http://example.com/>
+            SELECT ?s ?n ?l WHERE { ?s ex:p ?n OPTIONAL { ?s ex:q ?l } } ORDER BY ?s",
+        )
+        .await?
+    else {
+        panic!("Expected solutions");
+    };
+    let batches = solutions
+        .into_string_record_batch_stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    let batch = concat_batches(&batches[0].schema(), &batches)?;
+
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_string::<i32>()
+            .iter()
+            .map(|value| value.map(str::to_owned))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        column("s"),
+        vec![
+            Some("<http://example.com/a>".to_owned()),
+            Some("<http://example.com/b>".to_owned())
+        ]
+    );
+    assert_eq!(
+        column("n"),
+        vec![
+            Some("\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>".to_owned()),
+            Some("\"2\"^^<http://www.w3.org/2001/XMLSchema#integer>".to_owned())
+        ]
+    );
+    assert_eq!(column("l"), vec![Some("\"x\"@en".to_owned()), None]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_order_by_language_tagged_strings() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:s ex:p "b"@de, "a"@en, "a"@de, "a", "ab" .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    // Language-tagged strings sort by their lexical value. The language tag breaks ties.
+    let sorted = select_objects(
+        &store,
+        "SELECT ?o WHERE { ?s <http://example.com/p> ?o } ORDER BY ?o",
+    )
+    .await?;
+    assert_eq!(
+        sorted,
+        vec![
+            Term::from(Literal::new_simple_literal("a")),
+            Term::from(Literal::new_language_tagged_literal("a", "de")?),
+            Term::from(Literal::new_language_tagged_literal("a", "en")?),
+            Term::from(Literal::new_simple_literal("ab")),
+            Term::from(Literal::new_language_tagged_literal("b", "de")?),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_distinct_aggregates() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:a ex:p 1, 2 ; ex:q "x", "y" .
+            ex:b ex:p 4 ; ex:q "x" .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    // The join duplicates each value of ?o and ?x within the group of ex:a.
+    assert_eq!(
+        select_aggregates(&store, "SUM(?o)").await?,
+        vec![Term::from(Literal::from(6)), Term::from(Literal::from(4))]
+    );
+    assert_eq!(
+        select_aggregates(&store, "SUM(DISTINCT ?o)").await?,
+        vec![Term::from(Literal::from(3)), Term::from(Literal::from(4))]
+    );
+
+    let concatenated =
+        select_aggregates(&store, "GROUP_CONCAT(DISTINCT ?x ; SEPARATOR = \",\")")
+            .await?;
+    let Term::Literal(first) = &concatenated[0] else {
+        panic!("Expected a literal");
+    };
+    let mut values = first.value().split(',').collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, vec!["x", "y"]);
+    assert_eq!(concatenated[1], Term::from(Literal::from("x")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_distinct_aggregates_compare_terms() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:a ex:p "01"^^xsd:integer, "1"^^xsd:integer ; ex:q "x" .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    // "01" and "1" have the same value but are different RDF terms.
+    assert_eq!(
+        select_aggregates(&store, "COUNT(DISTINCT ?o)").await?,
+        vec![Term::from(Literal::from(2))]
+    );
+    assert_eq!(
+        select_aggregates(&store, "SUM(DISTINCT ?o)").await?,
+        vec![Term::from(Literal::from(2))]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_is_consistent_across_queries() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let ex = NamedNodeRef::new("http://example.com")?;
+    store
+        .insert(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))
+        .await?;
+
+    let snapshot = store.snapshot().await?;
+    store
+        .insert(QuadRef::new(ex, ex, Literal::from(1).as_ref(), ex))
+        .await?;
+
+    // Writers do not wait for the snapshot, which still observes the old state.
+    for _ in 0..2 {
+        let QueryResults::Solutions(solutions) =
+            snapshot.query("SELECT * WHERE { ?s ?p ?o }").await?
+        else {
+            panic!("Expected solutions");
+        };
+        assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 1);
+        assert_eq!(snapshot.len().await?, 1);
+    }
+    assert_eq!(store.len().await?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_storage_is_read_only() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let ex = NamedNodeRef::new("http://example.com")?;
+
+    let snapshot = store.context().snapshot().await?;
+    let result = snapshot
+        .storage()
+        .extend(vec![QuadRef::new(ex, ex, ex, ex).into_owned()])
+        .await;
+
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dropping_stream_early_releases_snapshot() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+
+    let quads = store.stream().await?.take(2).collect::<Vec<_>>().await;
+    assert_eq!(quads.len(), 2);
+
+    let mut stream = store.stream().await?;
+    assert!(stream.next().await.is_some());
+    drop(stream);
+
+    // Compaction swaps in new indexes and must not be affected by the streams above.
+    store.optimize().await?;
+    assert_eq!(store.len().await?, NUMBER_OF_TRIPLES);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_diagnose_pattern() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+    let paris = NamedNodeRef::new("http://www.wikidata.org/entity/Q90")?;
+    let france = NamedNodeRef::new("http://www.wikidata.org/entity/Q142")?;
+    let country = NamedNodeRef::new("http://schema.org/country")?;
+    let typo = NamedNodeRef::new("http://schema.org/contry")?;
+
+    let diagnosis = store
+        .diagnose_pattern(Some(paris.into()), Some(typo), None, None)
+        .await?;
+    assert_eq!(
+        diagnosis.subject,
+        ComponentDiagnosis::Present(NUMBER_OF_TRIPLES)
+    );
+    assert_eq!(diagnosis.predicate, ComponentDiagnosis::UnknownTerm);
+    assert_eq!(diagnosis.object, ComponentDiagnosis::Unbound);
+    assert_eq!(diagnosis.graph_name, ComponentDiagnosis::Unbound);
+    assert!(!diagnosis.has_matches());
+
+    let diagnosis = store
+        .diagnose_pattern(
+            Some(france.into()),
+            Some(country),
+            None,
+            Some(GraphNameRef::DefaultGraph),
+        )
+        .await?;
+    assert_eq!(diagnosis.subject, ComponentDiagnosis::NotInPosition);
+    assert_eq!(diagnosis.predicate, ComponentDiagnosis::Present(1));
+    assert_eq!(
+        diagnosis.graph_name,
+        ComponentDiagnosis::Present(NUMBER_OF_TRIPLES)
+    );
+    assert_eq!(diagnosis.matching_quads, 0);
+
+    let diagnosis = store
+        .diagnose_pattern(None, Some(country), Some(france.into()), None)
+        .await?;
+    assert_eq!(diagnosis.object, ComponentDiagnosis::Present(1));
+    assert_eq!(diagnosis.matching_quads, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sha384() -> Result<(), Box<dyn Error>> {
+    // Expected value of the W3C test `sha384-01`.
+    const FOO_DIGEST: &str = "98c11ffdfdd540676b1a137cb1a22b2a70350c9a44171d6b\
+        1180c6be5cbb2ee3f79d532c8a1dd9ef2e8e08e752a3babb";
+    let store = Store::default();
+
+    let results = select_objects(
+        &store,
+        "SELECT (SHA384(?v) AS ?o) WHERE { VALUES ?v { \"foo\" \"foo\"^^<http://www.w3.org/2001/XMLSchema#string> } }",
+    )
+    .await?;
+    assert_eq!(
+        results,
+        vec![Literal::new_simple_literal(FOO_DIGEST).into(); 2]
+    );
+
+    let results = select_objects(
+        &store,
+        "SELECT (SHA384(?v) AS ?o) WHERE { VALUES ?v { <http://example.com/foo> 1 } }",
+    )
+    .await?;
+    assert!(results.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_adjust() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (ADJUST(?v, ?tz) AS ?o) WHERE {
+            VALUES ?v { \"2002-03-07T10:00:00-05:00\"^^xsd:dateTime }
+            VALUES ?tz { \"-PT10H\"^^xsd:dayTimeDuration }
+        }",
+    )
+    .await?;
+    assert_eq!(
+        results,
+        vec![
+            Literal::new_typed_literal("2002-03-07T05:00:00-10:00", xsd::DATE_TIME)
+                .into()
+        ]
+    );
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (ADJUST(?v, ?tz) AS ?o) WHERE {
+            VALUES ?v { \"2002-03-07T10:00:00-07:00\"^^xsd:dateTime }
+            OPTIONAL { VALUES ?tz { } }
+        }",
+    )
+    .await?;
+    assert_eq!(
+        results,
+        vec![Literal::new_typed_literal("2002-03-07T10:00:00", xsd::DATE_TIME).into()]
+    );
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (ADJUST(?v, \"PT15H\"^^xsd:dayTimeDuration) AS ?o) WHERE {
+            VALUES ?v { \"2002-03-07T10:00:00\"^^xsd:dateTime }
+        }",
+    )
+    .await?;
+    assert!(results.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_if_only_evaluates_selected_branch() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    let results = select_objects(&store, "SELECT (IF(true, 1, 1/0) AS ?o) {}").await?;
+    assert_eq!(results, vec![Literal::from(1).into()]);
+
+    let results = select_objects(
+        &store,
+        "SELECT (IF(?x != 0, 4/?x, \"zero\") AS ?o) { VALUES ?x { 0 2 } }",
+    )
+    .await?;
+    assert_eq!(
+        results,
+        vec![
+            Literal::from("zero").into(),
+            Literal::new_typed_literal("2", xsd::DECIMAL).into()
+        ]
+    );
+
+    let results = select_objects(&store, "SELECT (IF(1/0 = 1, 1, 2) AS ?o) {}").await?;
+    assert!(results.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalesce_only_evaluates_unresolved_arguments() -> Result<(), Box<dyn Error>>
+{
+    let store = Store::default();
+
+    let results = select_objects(
+        &store,
+        "SELECT (COALESCE(?a, ?b, 1/0) AS ?o) { VALUES (?a ?b) { (1 UNDEF) (UNDEF \"b\") } }",
+    )
+    .await?;
+    assert_eq!(
+        results,
+        vec![Literal::from(1).into(), Literal::from("b").into()]
+    );
+
+    let results = select_objects(
+        &store,
+        "SELECT (COALESCE(?a, 1/0) AS ?o) { VALUES ?a { UNDEF } }",
+    )
+    .await?;
+    assert!(results.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_seeded_rand_is_reproducible() -> Result<(), Box<dyn Error>> {
+    let query = "SELECT (RAND() AS ?o) { VALUES ?x { 1 2 3 } }";
+
+    let store = Store::default();
+    store.set_rand_seed(Some(42));
+    let first = select_objects(&store, query).await?;
+    let second = select_objects(&store, query).await?;
+    assert_eq!(first.len(), 3);
+    assert_eq!(first, second);
+
+    let other_store = Store::default();
+    other_store.set_rand_seed(Some(42));
+    assert_eq!(select_objects(&other_store, query).await?, first);
+
+    other_store.set_rand_seed(Some(7));
+    assert_ne!(select_objects(&other_store, query).await?, first);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_seeded_rand_continues_sequence_across_batches() -> Result<(), Box<dyn Error>>
+{
+    // More rows than the batch size of the store, such that RAND is evaluated for multiple
+    // batches.
+    let query = "SELECT (RAND() AS ?o) { ?s ?p ?v }";
+    let store = Store::default();
+    let predicate = NamedNodeRef::new("http://example.com/p")?;
+    let subjects = (0..10_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(subjects.iter().map(|subject| {
+            QuadRef::new(subject, predicate, predicate, GraphNameRef::DefaultGraph)
+        }))
+        .await?;
+    store.set_rand_seed(Some(42));
+
+    let first = select_objects(&store, query).await?;
+    assert_eq!(first.len(), 10_000);
+    let distinct = first
+        .iter()
+        .map(ToString::to_string)
+        .collect::<HashSet<_>>();
+    assert_eq!(distinct.len(), 10_000);
+
+    let second = select_objects(&store, query).await?;
+    assert_eq!(first, second);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_any_uri_is_value_equal_but_not_same_term_as_string()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (?uri = ?string AS ?o) {
+            BIND(\"https://www.paris.fr/\"^^xsd:anyURI AS ?uri)
+            BIND(\"https://www.paris.fr/\" AS ?string)
+        }",
+    )
+    .await?;
+    assert_eq!(results, vec![Literal::from(true).into()]);
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (sameTerm(?uri, ?string) AS ?o) {
+            BIND(\"https://www.paris.fr/\"^^xsd:anyURI AS ?uri)
+            BIND(\"https://www.paris.fr/\" AS ?string)
+        }",
+    )
+    .await?;
+    assert_eq!(results, vec![Literal::from(false).into()]);
+
+    let results = select_objects(
+        &store,
+        "PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+        SELECT (DATATYPE(\"https://www.paris.fr/\"^^xsd:anyURI) AS ?o) {}",
+    )
+    .await?;
+    assert_eq!(results, vec![xsd::ANY_URI.into_owned().into()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backup_and_restore() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+    store
+        .load_from_reader(RdfFormat::TriG, GRAPH_DATA.as_bytes())
+        .await?;
+
+    let backup = store.backup(Vec::new()).await?;
+    let restored = Store::default();
+    restored.restore(backup.as_slice()).await?;
+
+    assert_eq!(restored.len().await?, 2 * NUMBER_OF_TRIPLES);
+    assert_eq!(restored.named_graphs().await?, store.named_graphs().await?);
+    let query = "SELECT * WHERE { { ?s ?p ?o } UNION { GRAPH ?g { ?s ?p ?o } } } \
+                 ORDER BY ?g ?s ?p ?o";
+    assert_eq!(
+        all_solutions(&restored, query).await?,
+        all_solutions(&store, query).await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_is_isomorphic_to_after_backup_and_restore() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::TriG,
+            "@prefix ex: <http://example.com/> .
+            _:a ex:knows _:b .
+            _:b ex:knows _:a .
+            _:g { _:a ex:name \"a\" }"
+                .as_bytes(),
+        )
+        .await?;
+
+    let backup = store.backup(Vec::new()).await?;
+    let restored = Store::default();
+    restored.restore(backup.as_slice()).await?;
+
+    assert!(store.is_isomorphic_to(&restored).await?);
+    assert!(restored.is_isomorphic_to(&store).await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_is_isomorphic_to_renamed_blank_nodes() -> Result<(), Box<dyn Error>> {
+    let knows = NamedNodeRef::new("http://example.com/knows")?;
+    let (a, b, c, d) = (
+        BlankNode::new("a")?,
+        BlankNode::new("b")?,
+        BlankNode::new("c")?,
+        BlankNode::new("d")?,
+    );
+
+    let store = Store::default();
+    store
+        .extend([
+            QuadRef::new(&a, knows, &b, GraphNameRef::DefaultGraph),
+            QuadRef::new(&b, knows, &a, &a),
+        ])
+        .await?;
+
+    let renamed = Store::default();
+    renamed
+        .extend([
+            QuadRef::new(&c, knows, &d, GraphNameRef::DefaultGraph),
+            QuadRef::new(&d, knows, &c, &c),
+        ])
+        .await?;
+    assert!(store.is_isomorphic_to(&renamed).await?);
+
+    // Same number of quads, but the blank nodes are not connected in the same way.
+    let different = Store::default();
+    different
+        .extend([
+            QuadRef::new(&c, knows, &d, GraphNameRef::DefaultGraph),
+            QuadRef::new(&d, knows, &c, &d),
+        ])
+        .await?;
+    assert!(!store.is_isomorphic_to(&different).await?);
+
+    // A different number of quads.
+    renamed
+        .insert(QuadRef::new(&c, knows, &c, GraphNameRef::DefaultGraph))
+        .await?;
+    assert!(!store.is_isomorphic_to(&renamed).await?);
+    Ok(())
+}
+
+/// A [ServiceResolver] that evaluates the `SERVICE` calls against a local store and records the
+/// names of the called services.
+struct LocalServiceResolver {
+    store: Store,
+    called: Mutex<Vec<NamedNode>>,
+}
+
+#[async_trait]
+impl ServiceResolver for LocalServiceResolver {
+    async fn evaluate(
+        &self,
+        name: &NamedNode,
+        query: Query,
+    ) -> Result<QueryResults, QueryEvaluationError> {
+        self.called.lock().unwrap().push(name.clone());
+        self.store.query(query).await
+    }
+}
+
+/// A [ServiceResolver] that always fails.
+struct FailingServiceResolver;
+
+#[async_trait]
+impl ServiceResolver for FailingServiceResolver {
+    async fn evaluate(
+        &self,
+        name: &NamedNode,
+        _query: Query,
+    ) -> Result<QueryResults, QueryEvaluationError> {
+        Err(QueryEvaluationError::UnsupportedService(name.clone()))
+    }
+}
+
+#[tokio::test]
+async fn test_service_with_custom_resolver() -> Result<(), Box<dyn Error>> {
+    let name = NamedNodeRef::new("http://example.com/name")?;
+    let age = NamedNodeRef::new("http://example.com/age")?;
+    let alice = NamedNodeRef::new("http://example.com/alice")?;
+    let bob = NamedNodeRef::new("http://example.com/bob")?;
+
+    let remote = Store::default();
+    remote
+        .extend([
+            QuadRef::new(alice, age, &Literal::from(30), GraphNameRef::DefaultGraph),
+            QuadRef::new(bob, age, &Literal::from(40), GraphNameRef::DefaultGraph),
+        ])
+        .await?;
+    let resolver = Arc::new(LocalServiceResolver {
+        store: remote,
+        called: Mutex::new(Vec::new()),
+    });
+
+    let store = Store::default();
+    store
+        .insert(QuadRef::new(
+            alice,
+            name,
+            LiteralRef::from("Alice"),
+            GraphNameRef::DefaultGraph,
+        ))
+        .await?;
+    store.register_service_resolver(Arc::clone(&resolver) as Arc<dyn ServiceResolver>);
+
+    let solutions = all_solutions(
+        &store,
+        "SELECT ?name ?age WHERE {
+            ?s <http://example.com/name> ?name .
+            SERVICE <http://example.com/sparql> { ?s <http://example.com/age> ?age }
+        }",
+    )
+    .await?;
+
+    assert_eq!(
+        solutions,
+        vec![vec![
+            (Variable::new("name")?, Literal::from("Alice").into()),
+            (Variable::new("age")?, Literal::from(30).into()),
+        ]]
+    );
+    assert_eq!(
+        *resolver.called.lock().unwrap(),
+        vec![NamedNode::new("http://example.com/sparql")?]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_service_silent_ignores_resolver_errors() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store.register_service_resolver(Arc::new(FailingServiceResolver));
+
+    let solutions = all_solutions(
+        &store,
+        "SELECT ?x ?o WHERE {
+            BIND(1 AS ?x)
+            SERVICE SILENT <http://example.com/sparql> { ?s ?p ?o }
+        }",
+    )
+    .await?;
+    assert_eq!(
+        solutions,
+        vec![vec![(Variable::new("x")?, Literal::from(1).into())]]
+    );
+
+    let result = store
+        .query("SELECT * WHERE { SERVICE <http://example.com/sparql> { ?s ?p ?o } }")
+        .await;
+    let error = match result {
+        Ok(QueryResults::Solutions(solutions)) => {
+            solutions.try_collect::<Vec<_>>().await.err()
+        }
+        Ok(_) => panic!("Expected solutions"),
+        Err(error) => Some(error),
+    };
+    assert!(matches!(
+        error,
+        Some(QueryEvaluationError::UnsupportedService(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_service_http_resolver_with_unreachable_endpoint()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    let solutions = all_solutions(
+        &store,
+        "SELECT * WHERE { SERVICE SILENT <http://127.0.0.1:1/sparql> { ?s ?p ?o } }",
+    )
+    .await?;
+    assert_eq!(solutions, vec![Vec::new()]);
+
+    let result = store
+        .query("SELECT * WHERE { SERVICE <http://127.0.0.1:1/sparql> { ?s ?p ?o } }")
+        .await;
+    let error = match result {
+        Ok(QueryResults::Solutions(solutions)) => {
+            solutions.try_collect::<Vec<_>>().await.err()
+        }
+        Ok(_) => panic!("Expected solutions"),
+        Err(error) => Some(error),
+    };
+    assert!(matches!(error, Some(QueryEvaluationError::Service(_))));
+    Ok(())
+}
+
+/// The response of the mock SPARQL endpoint in [test_service_http_resolver_passes_bindings].
+const MOCK_SERVICE_RESULTS: &str = r#"{
+    "head": { "vars": ["s", "age"] },
+    "results": { "bindings": [
+        {
+            "s": { "type": "uri", "value": "http://example.com/alice" },
+            "age": {
+                "type": "literal",
+                "value": "30",
+                "datatype": "http://www.w3.org/2001/XMLSchema#integer"
+            }
+        },
+        {
+            "s": { "type": "uri", "value": "http://example.com/carol" },
+            "age": {
+                "type": "literal",
+                "value": "50",
+                "datatype": "http://www.w3.org/2001/XMLSchema#integer"
+            }
+        }
+    ] }
+}"#;
+
+#[tokio::test]
+async fn test_service_http_resolver_passes_bindings() -> Result<(), Box<dyn Error>> {
+    // A mock SPARQL endpoint that records the received queries.
+    let queries = Arc::new(Mutex::new(Vec::<String>::new()));
+    let app = Router::new().route(
+        "/sparql",
+        post({
+            let queries = Arc::clone(&queries);
+            move |query: String| async move {
+                queries.lock().unwrap().push(query);
+                (
+                    [(CONTENT_TYPE, "application/sparql-results+json")],
+                    MOCK_SERVICE_RESULTS,
+                )
+            }
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}/sparql", listener.local_addr()?);
+    let _server = SpawnedTask::spawn(async move { axum::serve(listener, app).await });
+
+    let store = Store::default();
+    let file = r#"
+        @prefix ex: <http://example.com/> .
+        ex:alice ex:name "Alice" .
+        ex:bob ex:name "Bob" .
+    "#;
+    store
+        .load_from_reader(RdfFormat::Turtle, file.as_bytes())
+        .await?;
+
+    let solutions = all_solutions(
+        &store,
+        &format!(
+            "SELECT ?name ?age WHERE {{
+                ?s <http://example.com/name> ?name .
+                SERVICE <{endpoint}> {{ ?s <http://example.com/age> ?age }}
+            }}"
+        ),
+    )
+    .await?;
+    assert_eq!(
+        solutions,
+        vec![vec![
+            (Variable::new("name")?, Literal::from("Alice").into()),
+            (Variable::new("age")?, Literal::from(30).into()),
+        ]]
+    );
+
+    // The subjects of the outer pattern are passed to the endpoint.
+    let query = queries.lock().unwrap().pop().ok_or("No query received")?;
+    assert!(query.contains("VALUES"));
+    assert!(query.contains("<http://example.com/alice>"));
+    assert!(query.contains("<http://example.com/bob>"));
+
+    // Without any bindings, the endpoint is not called.
+    let solutions = all_solutions(
+        &store,
+        &format!(
+            "SELECT * WHERE {{
+                ?s <http://example.com/unknown> ?name .
+                SERVICE <{endpoint}> {{ ?s <http://example.com/age> ?age }}
+            }}"
+        ),
+    )
+    .await?;
+    assert!(solutions.is_empty());
+    assert!(queries.lock().unwrap().is_empty());
+    Ok(())
+}
+
+async fn all_solutions(
+    store: &Store,
+    query: &str,
+) -> Result<Vec<Vec<(Variable, Term)>>, Box<dyn Error>> {
+    let QueryResults::Solutions(solutions) = store.query(query).await? else {
+        panic!("Expected solutions");
+    };
+    Ok(solutions
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .map(|solution| {
+            solution
+                .iter()
+                .map(|(variable, term)| (variable.clone(), term.clone()))
+                .collect()
+        })
+        .collect())
+}
+
+#[tokio::test]
+async fn test_now_is_evaluated_once_per_query() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let QueryResults::Solutions(solutions) = store
+        .query(
+            "SELECT ?x ?y WHERE { \
+                { SELECT ?x WHERE { BIND(NOW() AS ?x) } } \
+                { SELECT ?y WHERE { VALUES ?v { 1 2 3 } BIND(NOW() AS ?y) } } \
+             }",
+        )
+        .await?
+    else {
+        panic!("Expected solutions");
+    };
+
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    assert_eq!(solutions.len(), 3);
+    for solution in solutions {
+        let x = solution.get("x").expect("?x is bound");
+        assert_eq!(Some(x), solution.get("y"));
+        assert!(matches!(x, Term::Literal(l) if l.datatype() == xsd::DATE_TIME));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_uuid_is_fresh_for_each_row() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let values = (0..1000)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let query = format!(
+        "SELECT (UUID() AS ?uuid) (STRUUID() AS ?struuid) WHERE {{ VALUES ?i {{ {values} }} }}"
+    );
+    let QueryResults::Solutions(solutions) = store.query(query.as_str()).await? else {
+        panic!("Expected solutions");
+    };
+
+    let mut uuids = BTreeSet::new();
+    let mut str_uuids = BTreeSet::new();
+    for solution in solutions.try_collect::<Vec<_>>().await? {
+        let Some(Term::NamedNode(uuid)) = solution.get("uuid") else {
+            panic!("Expected an IRI for UUID()");
+        };
+        let Some(Term::Literal(str_uuid)) = solution.get("struuid") else {
+            panic!("Expected a literal for STRUUID()");
+        };
+        let uuid = uuid
+            .as_str()
+            .strip_prefix("urn:uuid:")
+            .expect("UUID() uses the urn:uuid: scheme");
+        assert!(is_uuid(uuid), "Malformed UUID: {uuid}");
+        assert_eq!(str_uuid.datatype(), xsd::STRING);
+        assert!(
+            is_uuid(str_uuid.value()),
+            "Malformed UUID: {}",
+            str_uuid.value()
+        );
+        uuids.insert(uuid.to_owned());
+        str_uuids.insert(str_uuid.value().to_owned());
+    }
+    assert_eq!(uuids.len(), 1000);
+    assert_eq!(str_uuids.len(), 1000);
+    Ok(())
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups = value.split('-').map(str::len).collect::<Vec<_>>();
+    groups == [8, 4, 4, 4, 12]
+        && value
+            .chars()
+            .all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+#[tokio::test]
+async fn test_ask_stops_scan_after_first_match() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..10_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "ASK { ?s <http://example.com/p> ?o }",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    assert!(matches!(result, QueryResults::Boolean(true)));
+    assert_eq!(scanned_rows(explanation.execution_plan.as_ref()), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_join_prunes_probe_scan_with_build_side_bounds() -> Result<(), Box<dyn Error>>
+{
+    let store = Store::default();
+    let value = NamedNodeRef::new("http://example.com/value")?;
+    let selected = NamedNodeRef::new("http://example.com/selected")?;
+    let yes = NamedNodeRef::new("http://example.com/yes")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..10_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, value, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+    store
+        .extend(
+            subjects[5_000..5_010]
+                .iter()
+                .map(|s| QuadRef::new(s, selected, yes, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT ?s WHERE {
+                ?s <http://example.com/selected> <http://example.com/yes> .
+                ?s <http://example.com/value> ?v
+            }",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 10);
+    // Without pruning, the scan of the second pattern would read all 10,000 quads.
+    assert!(scanned_rows(explanation.execution_plan.as_ref()) < 1_000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_partial() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let query = "SELECT ?s WHERE { VALUES ?s { 1 2 3 } } ORDER BY ?s";
+
+    let partial = store.query_partial(query, 2).await?;
+    assert_eq!(partial.variables(), [Variable::new("s")?]);
+    assert_eq!(
+        partial
+            .solutions()
+            .iter()
+            .map(|solution| solution.get("s").cloned())
+            .collect::<Vec<_>>(),
+        vec![Some(Literal::from(1).into()), Some(Literal::from(2).into())]
+    );
+    assert!(partial.has_more());
+
+    let partial = store.query_partial(query, 3).await?;
+    assert_eq!(partial.solutions().len(), 3);
+    assert!(!partial.has_more());
+
+    let partial = store.query_partial(query, 0).await?;
+    assert!(partial.solutions().is_empty());
+    assert!(partial.has_more());
+
+    let partial = store
+        .query_partial("SELECT ?s WHERE { VALUES ?s { 1 2 3 } } LIMIT 2", 5)
+        .await?;
+    assert_eq!(partial.solutions().len(), 2);
+    assert!(!partial.has_more());
+
+    assert!(store.query_partial("ASK { }", 1).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_best_effort() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..1_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let complete = store
+        .query_best_effort(
+            "SELECT ?s WHERE { ?s <http://example.com/p> ?o }",
+            Duration::from_secs(60),
+        )
+        .await?;
+    assert_eq!(complete.variables(), [Variable::new("s")?]);
+    assert_eq!(complete.solutions().len(), 1_000);
+    assert!(!complete.is_truncated());
+
+    // The cross product has 10^9 solutions and cannot be computed within the timeout.
+    let truncated = store
+        .query_best_effort(
+            "SELECT * WHERE { ?a ?p1 ?b . ?c ?p2 ?d . ?e ?p3 ?f }",
+            Duration::from_millis(50),
+        )
+        .await?;
+    assert!(truncated.is_truncated());
+    assert!(truncated.solutions().len() < 1_000_000_000);
+
+    assert!(
+        store
+            .query_best_effort("ASK { }", Duration::from_secs(1))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_timeout_and_cancellation() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..1_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    // The cross product has 10^9 solutions and cannot be computed within the timeout.
+    let query =
+        Query::parse("SELECT * WHERE { ?a ?p1 ?b . ?c ?p2 ?d . ?e ?p3 ?f }", None)?;
+    let (results, _) = store
+        .context()
+        .execute_query_with_timeout(
+            &query,
+            QueryOptions::default(),
+            Duration::from_millis(50),
+        )
+        .await?;
+    let QueryResults::Solutions(solutions) = results else {
+        panic!("Expected solutions");
+    };
+    let result = solutions.try_collect::<Vec<_>>().await;
+    assert!(matches!(result, Err(QueryEvaluationError::Timeout)));
+
+    let token = QueryCancellationToken::new();
+    let (results, _) = store
+        .context()
+        .execute_query_with_cancellation(
+            &query,
+            QueryOptions::default(),
+            token.clone(),
+            None,
+        )
+        .await?;
+    let QueryResults::Solutions(mut solutions) = results else {
+        panic!("Expected solutions");
+    };
+    assert!(solutions.next().await.transpose()?.is_some());
+    token.cancel();
+    let result = solutions.try_collect::<Vec<_>>().await;
+    assert!(matches!(result, Err(QueryEvaluationError::Cancelled)));
+
+    // Queries that complete within the timeout are unaffected.
+    let query = Query::parse("SELECT ?s WHERE { ?s <http://example.com/p> ?o }", None)?;
+    let (results, _) = store
+        .context()
+        .execute_query_with_timeout(
+            &query,
+            QueryOptions::default(),
+            Duration::from_secs(60),
+        )
+        .await?;
+    let QueryResults::Solutions(solutions) = results else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 1_000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_solution_limit_stops_scan() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..10_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let query = Query::parse("SELECT ?s WHERE { ?s <http://example.com/p> ?o }", None)?
+        .with_solution_limit(3)
+        .expect("SELECT query");
+    let (result, explanation) = store
+        .explain_query_opt(query, QueryOptions::default())
+        .await?;
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 3);
+    assert!(scanned_rows(explanation.execution_plan.as_ref()) <= 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dump_and_load_parquet() -> Result<(), Box<dyn Error>> {
+    let path = std::env::temp_dir()
+        .join(format!("rdf-fusion-store-dump-{}", std::process::id()));
+    let (store, storage) = store_with_mem_storage();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+    store
+        .load_from_reader(RdfFormat::TriG, GRAPH_DATA.as_bytes())
+        .await?;
+    storage.dump_to_parquet(&path).await?;
+
+    let (loaded_store, loaded_storage) = store_with_mem_storage();
+    loaded_storage.load_from_parquet(&path).await?;
+    std::fs::remove_dir_all(&path)?;
+
+    let query =
+        "SELECT ?g ?s ?p ?o WHERE { { ?s ?p ?o } UNION { GRAPH ?g { ?s ?p ?o } } }";
+    let expected = all_solutions(&store, query)
+        .await?
+        .into_iter()
+        .map(|solution| format!("{solution:?}"))
+        .collect::<BTreeSet<_>>();
+    let actual = all_solutions(&loaded_store, query)
+        .await?
+        .into_iter()
+        .map(|solution| format!("{solution:?}"))
+        .collect::<BTreeSet<_>>();
+    assert!(!expected.is_empty());
+    assert_eq!(actual, expected);
+    assert_eq!(
+        loaded_store
+            .named_graphs()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        store
+            .named_graphs()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>()
+    );
+    Ok(())
+}
+
+/// Creates a [Store] together with its [MemQuadStorage].
+fn store_with_mem_storage() -> (Store, Arc<MemQuadStorage>) {
+    let mapping = Arc::new(MemObjectIdMapping::new());
+    let encoding = Arc::new(ObjectIdEncoding::new(
+        Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
+    ));
+    let storage = Arc::new(MemQuadStorage::new(mapping, encoding, 8192).unwrap());
+    let context = RdfFusionContext::new(
+        SessionConfig::new(),
+        RuntimeEnv::default().into(),
+        Arc::clone(&storage) as Arc<dyn QuadStorage>,
+    );
+    (Store::new(context), storage)
+}
+
+/// Returns the number of rows produced by the data sources (i.e., scans) in `plan`.
+fn scanned_rows(plan: &dyn ExecutionPlan) -> usize {
+    let own_rows = match plan.name() {
+        "DataSourceExec" => plan
+            .metrics()
+            .and_then(|metrics| metrics.output_rows())
+            .unwrap_or_default(),
+        _ => 0,
+    };
+    own_rows
+        + plan
+            .children()
+            .into_iter()
+            .map(|child| scanned_rows(child.as_ref()))
+            .sum::<usize>()
+}
+
+#[tokio::test]
+async fn test_update_insert_and_delete_data() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let ex = NamedNodeRef::new("http://example.com/s")?;
+    let g = NamedNodeRef::new("http://example.com/g")?;
+
+    store
+        .update(
+            "INSERT DATA {
+                <http://example.com/s> <http://example.com/s> <http://example.com/s> .
+                GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/s> 1 }
+            }",
+        )
+        .await?;
+    assert_eq!(store.len().await?, 2);
+    assert!(store.contains_named_graph(g).await?);
+
+    store
+        .update("DELETE DATA { <http://example.com/s> <http://example.com/s> <http://example.com/s> }")
+        .await?;
+    assert!(
+        !store
+            .contains(QuadRef::new(ex, ex, ex, GraphNameRef::DefaultGraph))
+            .await?
+    );
+    assert_eq!(store.len().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_delete_insert_where() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .update(
+            "INSERT DATA {
+                <http://example.com/a> <http://example.com/p> 1 .
+                <http://example.com/b> <http://example.com/p> 2 .
+            }",
+        )
+        .await?;
+
+    store
+        .update(
+            "DELETE { ?s <http://example.com/p> ?o }
+             INSERT { ?s <http://example.com/q> ?o }
+             WHERE { ?s <http://example.com/p> ?o FILTER(?o > 1) }",
+        )
+        .await?;
+    assert_eq!(
+        select_objects(
+            &store,
+            "SELECT ?o WHERE { ?s <http://example.com/p> ?o } ORDER BY ?o"
+        )
+        .await?,
+        vec![Term::from(Literal::from(1))]
+    );
+    assert_eq!(
+        select_objects(
+            &store,
+            "SELECT ?o WHERE { ?s <http://example.com/q> ?o } ORDER BY ?o"
+        )
+        .await?,
+        vec![Term::from(Literal::from(2))]
+    );
+
+    store
+        .update("DELETE WHERE { ?s <http://example.com/p> ?o }")
+        .await?;
+    assert_eq!(store.len().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_graph_management() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let g1 = NamedNodeRef::new("http://example.com/g1")?;
+    let g2 = NamedNodeRef::new("http://example.com/g2")?;
+
+    store.update("CREATE GRAPH <http://example.com/g1>").await?;
+    assert!(store.contains_named_graph(g1).await?);
+    assert!(
+        store
+            .update("CREATE GRAPH <http://example.com/g1>")
+            .await
+            .is_err()
+    );
+    store
+        .update("CREATE SILENT GRAPH <http://example.com/g1>")
+        .await?;
+
+    store
+        .update(
+            "INSERT DATA {
+                <http://example.com/s> <http://example.com/p> 1 .
+                GRAPH <http://example.com/g1> { <http://example.com/s> <http://example.com/p> 1 }
+                GRAPH <http://example.com/g2> { <http://example.com/s> <http://example.com/p> 1 }
+            }",
+        )
+        .await?;
+
+    store.update("CLEAR GRAPH <http://example.com/g1>").await?;
+    assert!(store.contains_named_graph(g1).await?);
+    assert_eq!(store.len().await?, 2);
+
+    store.update("DROP GRAPH <http://example.com/g1>").await?;
+    assert!(!store.contains_named_graph(g1).await?);
+    assert!(
+        store
+            .update("DROP GRAPH <http://example.com/g1>")
+            .await
+            .is_err()
+    );
+    store
+        .update("DROP SILENT GRAPH <http://example.com/g1>")
+        .await?;
+
+    store.update("CLEAR DEFAULT").await?;
+    assert_eq!(store.len().await?, 1);
+
+    store.update("DROP ALL").await?;
+    assert!(!store.contains_named_graph(g2).await?);
+    assert!(store.is_empty().await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_load() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let path =
+        std::env::temp_dir().join(format!("rdf-fusion-load-{}.nt", std::process::id()));
+    std::fs::write(
+        &path,
+        "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n",
+    )?;
+
+    let load = format!(
+        "LOAD <file://{}> INTO GRAPH <http://example.com/g>",
+        path.display()
+    );
+    let options = UpdateOptions {
+        load_directories: vec![std::env::temp_dir()],
+        ..UpdateOptions::default()
+    };
+    let result = store.update_opt(load.as_str(), options).await;
+    std::fs::remove_file(&path)?;
+    result?;
+
+    let s = NamedNodeRef::new("http://example.com/s")?;
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let g = NamedNodeRef::new("http://example.com/g")?;
+    assert!(store.contains(QuadRef::new(s, p, o, g)).await?);
+
+    assert!(
+        store
+            .update("LOAD <http://example.com/missing.ttl>")
+            .await
+            .is_err()
+    );
+    store
+        .update("LOAD SILENT <http://example.com/missing.ttl>")
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_load_rejects_files_outside_of_load_directories()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let load = "LOAD <file:///etc/passwd>";
+
+    // Loading files is disabled by default.
+    assert!(matches!(
+        store.update(load).await,
+        Err(QueryEvaluationError::LoadNotAllowed(_))
+    ));
+
+    let options = UpdateOptions {
+        load_directories: vec![std::env::temp_dir()],
+        ..UpdateOptions::default()
+    };
+    assert!(matches!(
+        store.update_opt(load, options.clone()).await,
+        Err(QueryEvaluationError::LoadNotAllowed(_))
+    ));
+    let escape = format!(
+        "LOAD <file://{}/../etc/passwd>",
+        std::env::temp_dir().display()
+    );
+    assert!(matches!(
+        store.update_opt(escape.as_str(), options).await,
+        Err(QueryEvaluationError::LoadNotAllowed(_))
+    ));
+    assert!(store.is_empty().await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_update_is_rolled_back() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .update(
+            "INSERT DATA {
+                <http://example.com/s> <http://example.com/p> 1 .
+                GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 1 }
+            }",
+        )
+        .await?;
+    let before = store.stream().await?.try_collect_to_set().await?;
+
+    // The last operation fails, as the graph has already been dropped.
+    let result = store
+        .update(
+            "INSERT DATA { <http://example.com/s> <http://example.com/p> 2 } ;
+             DELETE DATA { <http://example.com/s> <http://example.com/p> 1 } ;
+             CREATE GRAPH <http://example.com/new> ;
+             DROP GRAPH <http://example.com/g> ;
+             DROP GRAPH <http://example.com/g>",
+        )
+        .await;
+    assert!(result.is_err());
+
+    let after = store.stream().await?.try_collect_to_set().await?;
+    assert_eq!(after, before);
+    assert!(
+        store
+            .contains_named_graph(NamedNodeRef::new("http://example.com/g")?)
+            .await?
+    );
+    assert!(
+        !store
+            .contains_named_graph(NamedNodeRef::new("http://example.com/new")?)
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_batched_update_only_undoes_changed_quads()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .update("INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }")
+        .await?;
+    let before = store.stream().await?.try_collect_to_set().await?;
+
+    // The quads that are both deleted and inserted are retained by the second operation. The last
+    // operation fails, as the graph does not exist.
+    let result = store
+        .update(
+            "INSERT DATA {
+                <http://example.com/s> <http://example.com/p> 1 .
+                <http://example.com/s> <http://example.com/p> 2 .
+                GRAPH <http://example.com/g> { <http://example.com/s> <http://example.com/p> 3 }
+             } ;
+             DELETE { ?s ?p ?o } INSERT { ?s ?p ?o } WHERE { ?s ?p ?o } ;
+             DROP GRAPH <http://example.com/missing>",
+        )
+        .await;
+    assert!(result.is_err());
+
+    let after = store.stream().await?.try_collect_to_set().await?;
+    assert_eq!(after, before);
+    assert!(
+        !store
+            .contains_named_graph(NamedNodeRef::new("http://example.com/g")?)
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_predicates_for() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+    let paris = NamedNodeRef::new("http://www.wikidata.org/entity/Q90")?;
+
+    let mut predicates = store.predicates_for(paris, None).await?;
+    predicates.sort();
+    let expected = store
+        .quads_for_pattern(Some(paris.into()), None, None, None)
+        .await?
+        .try_collect_to_vec()
+        .await?
+        .into_iter()
+        .map(|quad| quad.predicate)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(predicates, expected);
+    assert!(predicates.len() < NUMBER_OF_TRIPLES);
+
+    let snapshot = store.snapshot().await?;
+    store.clear().await?;
+    assert!(store.predicates_for(paris, None).await?.is_empty());
+    assert_eq!(
+        snapshot.predicates_for(paris, None).await?.len(),
+        expected.len()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_distinct_objects_of() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let file = "
+        @prefix ex: <http://example.com/> .
+        ex:a ex:color ex:red ; ex:size 1 .
+        ex:b ex:color ex:red ; ex:size 2 .
+        ex:c ex:color ex:blue ; ex:size 1 .
+        GRAPH ex:g { ex:d ex:color ex:green . ex:e ex:color ex:red . }
+    ";
+    store
+        .load_from_reader(RdfFormat::TriG, file.as_bytes())
+        .await?;
+    let color = NamedNodeRef::new("http://example.com/color")?;
+    let graph = NamedNodeRef::new("http://example.com/g")?;
+    let colors = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| {
+                NamedNode::new(format!("http://example.com/{name}")).map(Term::from)
+            })
+            .collect::<Result<HashSet<_>, _>>()
+    };
+
+    let objects = store.distinct_objects_of(color, None).await?;
+    assert_eq!(objects.len(), 3);
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        colors(&["blue", "green", "red"])?
+    );
+
+    let objects = store
+        .distinct_objects_of(color, Some(GraphNameRef::DefaultGraph))
+        .await?;
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        colors(&["blue", "red"])?
+    );
+
+    let objects = store.distinct_objects_of(color, Some(graph.into())).await?;
+    assert_eq!(
+        objects.into_iter().collect::<HashSet<_>>(),
+        colors(&["green", "red"])?
+    );
+
+    let unknown = NamedNodeRef::new("http://example.com/unknown")?;
+    assert!(store.distinct_objects_of(unknown, None).await?.is_empty());
+    assert!(
+        store
+            .distinct_objects_of(color, Some(unknown.into()))
+            .await?
+            .is_empty()
+    );
+
+    let snapshot = store.snapshot().await?;
+    store.clear().await?;
+    assert!(store.distinct_objects_of(color, None).await?.is_empty());
+    assert_eq!(snapshot.distinct_objects_of(color, None).await?.len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_top_terms() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let file = "
+        @prefix ex: <http://example.com/> .
+        ex:a ex:color ex:red ; ex:size 1 .
+        ex:b ex:color ex:red ; ex:size 2 .
+        ex:c ex:color ex:blue .
+        GRAPH ex:g { ex:d ex:color ex:red . }
+    ";
+    store
+        .load_from_reader(RdfFormat::TriG, file.as_bytes())
+        .await?;
+    let term =
+        |name: &str| NamedNode::new(format!("http://example.com/{name}")).map(Term::from);
+
+    assert_eq!(
+        store.top_terms(QuadPosition::Predicate, 2).await?,
+        vec![(term("color")?, 4), (term("size")?, 2)]
+    );
+    assert_eq!(
+        store.top_terms(QuadPosition::Object, 1).await?,
+        vec![(term("red")?, 3)]
+    );
+    assert_eq!(
+        store.top_terms(QuadPosition::GraphName, 10).await?,
+        vec![(term("g")?, 1)]
+    );
+    assert!(store.top_terms(QuadPosition::Subject, 0).await?.is_empty());
+
+    let snapshot = store.snapshot().await?;
+    assert_eq!(
+        snapshot.top_terms(QuadPosition::Object, 1).await?,
+        vec![(term("red")?, 3)]
+    );
+    snapshot.release();
+
+    // The frequencies follow updates.
+    store
+        .update("PREFIX ex: <http://example.com/> DELETE WHERE { ?s ex:color ex:red }")
+        .await?;
+    assert_eq!(
+        store
+            .top_terms(QuadPosition::Predicate, 10)
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        HashSet::from([(term("color")?, 2), (term("size")?, 2)])
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_schema_summary() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let file = "
+        @prefix ex: <http://example.com/> .
+        ex:a a ex:Person ; ex:name \"A\" ; ex:knows ex:b .
+        ex:b a ex:Person ; ex:name \"B\" .
+        ex:c a ex:Company .
+        GRAPH ex:g { ex:d a ex:Person , ex:Company . }
+    ";
+    store
+        .load_from_reader(RdfFormat::TriG, file.as_bytes())
+        .await?;
+    let node = |name: &str| NamedNode::new(format!("http://example.com/{name}"));
+
+    let summary = store.schema_summary().await?;
+    assert_eq!(
+        summary.classes,
+        vec![(node("Person")?.into(), 3), (node("Company")?.into(), 2)]
+    );
+    assert_eq!(
+        summary.properties,
+        vec![
+            (rdf::TYPE.into_owned(), 5),
+            (node("name")?, 2),
+            (node("knows")?, 1)
+        ]
+    );
+    assert_eq!(summary.class_count(&node("Company")?), Some(2));
+    assert_eq!(summary.class_count(&node("name")?), None);
+    assert_eq!(summary.property_count(&node("knows")?), Some(1));
+
+    let snapshot = store.snapshot().await?;
+    assert_eq!(snapshot.schema_summary().await?, summary);
+    snapshot.release();
+
+    store.clear().await?;
+    let summary = store.schema_summary().await?;
+    assert!(summary.classes.is_empty());
+    assert!(summary.properties.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_members() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let file = "
+        @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+        @prefix ex: <http://example.com/> .
+        ex:list rdf:first ex:c ; rdf:rest (ex:a ex:b) .
+        ex:missingRest rdf:first ex:a .
+        ex:cycle rdf:first ex:a ; rdf:rest ex:cycle .
+    ";
+    store
+        .load_from_reader(RdfFormat::Turtle, file.as_bytes())
+        .await?;
+    let ex = |name: &str| NamedNode::new(format!("http://example.com/{name}"));
+
+    let members = store
+        .list_members(&ex("list")?, GraphNameRef::DefaultGraph)
+        .await?;
+    assert_eq!(
+        members,
+        vec![ex("c")?.into(), ex("a")?.into(), ex("b")?.into()]
+    );
+
+    let members = store
+        .list_members(rdf::NIL, GraphNameRef::DefaultGraph)
+        .await?;
+    assert!(members.is_empty());
+
+    let result = store
+        .list_members(&ex("missingRest")?, GraphNameRef::DefaultGraph)
+        .await;
+    assert!(matches!(result, Err(ListError::InvalidRest(_))));
+
+    let result = store
+        .list_members(&ex("cycle")?, GraphNameRef::DefaultGraph)
+        .await;
+    assert!(matches!(result, Err(ListError::Cycle(_))));
+
+    let result = store
+        .list_members(&ex("unknown")?, GraphNameRef::DefaultGraph)
+        .await;
+    assert!(matches!(result, Err(ListError::InvalidFirst(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_into_target_graph() -> Result<(), Box<dyn Error>> {
+    let file = "
+        <http://example.com/s> <http://example.com/p> <http://example.com/o1> .
+        <http://example.com/g> { <http://example.com/s> <http://example.com/p> <http://example.com/o2> }
+    ";
+    let target = NamedNodeRef::new("http://example.com/target")?;
+    let named = NamedNodeRef::new("http://example.com/g")?;
+    let s = NamedNodeRef::new("http://example.com/s")?;
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o1 = NamedNodeRef::new("http://example.com/o1")?;
+    let o2 = NamedNodeRef::new("http://example.com/o2")?;
+
+    let store = Store::default();
+    store
+        .load(RdfFormat::TriG, file.as_bytes(), target, false)
+        .await?;
+    assert_eq!(store.len().await?, 2);
+    assert!(store.contains(QuadRef::new(s, p, o1, target)).await?);
+    assert!(store.contains(QuadRef::new(s, p, o2, named)).await?);
+
+    let store = Store::default();
+    store
+        .load(RdfFormat::TriG, file.as_bytes(), target, true)
+        .await?;
+    assert_eq!(store.len().await?, 2);
+    assert!(store.contains(QuadRef::new(s, p, o1, target)).await?);
+    assert!(store.contains(QuadRef::new(s, p, o2, target)).await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_load_graph_into_named_graph() -> Result<(), Box<dyn Error>> {
+    let graph = NamedNodeRef::new("http://example.com/g")?;
+    let store = Store::default();
+    let loaded = store
+        .load_graph(DATA.as_bytes(), RdfFormat::Turtle, graph)
+        .await?;
+    assert_eq!(loaded, quads(graph).len());
+    for q in quads(graph) {
+        assert!(store.contains(q).await?);
+    }
+
+    // Loading the same file again does not insert any new quads.
+    let loaded = store
+        .load_graph(DATA.as_bytes(), RdfFormat::Turtle, graph)
+        .await?;
+    assert_eq!(loaded, 0);
+
+    // Named graphs are rejected.
+    assert!(
+        store
+            .load_graph(GRAPH_DATA.as_bytes(), RdfFormat::TriG, graph)
+            .await
+            .is_err()
+    );
+    store.validate().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_construct_into_graph() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+    let graph = NamedNodeRef::new("http://example.com/inferred")?;
+    let query = "CONSTRUCT { ?s a <http://example.com/Thing> } WHERE { ?s ?p ?o }";
+
+    assert_eq!(store.construct_into_graph(query, graph).await?, 1);
+    assert_eq!(store.construct_into_graph(query, graph).await?, 0);
+
+    let paris = NamedNodeRef::new("http://www.wikidata.org/entity/Q90")?;
+    let thing = NamedNodeRef::new("http://example.com/Thing")?;
+    assert!(
+        store
+            .contains(QuadRef::new(paris, rdf::TYPE, thing, graph))
+            .await?
+    );
+    assert!(
+        !store
+            .contains(QuadRef::new(
+                paris,
+                rdf::TYPE,
+                thing,
+                GraphNameRef::DefaultGraph
+            ))
+            .await?
+    );
+    assert_eq!(store.len().await?, NUMBER_OF_TRIPLES + 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_construct_into_graph_generates_fresh_blank_nodes()
+-> Result<(), Box<dyn Error>> {
+    let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+    let store = Store::default();
+    let data_node = BlankNode::new("data")?;
+    store
+        .extend([
+            Quad::new(ex("a"), ex("p"), ex("b"), GraphName::DefaultGraph),
+            Quad::new(data_node.clone(), ex("p"), ex("c"), GraphName::DefaultGraph),
+        ])
+        .await?;
+    let graph = ex("inferred");
+    let query = "CONSTRUCT { _:e <http://example.com/of> ?s } WHERE { ?s ?p ?o }";
+
+    // Each solution and each evaluation instantiates new blank nodes for the template.
+    assert_eq!(store.construct_into_graph(query, graph.clone()).await?, 2);
+    assert_eq!(store.construct_into_graph(query, graph.clone()).await?, 2);
+    let subjects = store
+        .quads_for_pattern(None, None, None, Some(graph.as_ref().into()))
+        .await?
+        .try_collect_to_vec()
+        .await?
+        .into_iter()
+        .map(|quad| quad.subject)
+        .collect::<HashSet<_>>();
+    assert_eq!(subjects.len(), 4);
+
+    // Blank nodes that are bound by the pattern keep their identity.
+    assert_eq!(
+        store
+            .count_matching(
+                None,
+                Some(ex("of").as_ref()),
+                Some(data_node.as_ref().into()),
+                Some(graph.as_ref().into()),
+            )
+            .await?,
+        2
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_construct_into_graph_rejects_select() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let graph = NamedNodeRef::new("http://example.com/inferred")?;
+
+    let result = store
+        .construct_into_graph("SELECT * WHERE { ?s ?p ?o }", graph)
+        .await;
+
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rdfs_hierarchy_entailment() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+            ex:Dog rdfs:subClassOf ex:Mammal .
+            ex:Mammal rdfs:subClassOf ex:Animal .
+            ex:Cat rdfs:subClassOf ex:Animal .
+            ex:hasPuppy rdfs:subPropertyOf ex:hasChild .
+
+            ex:rex a ex:Dog, ex:Mammal ; ex:hasPuppy ex:fido .
+            ex:tom a ex:Cat ; ex:hasChild ex:tim .
+            ex:rock a ex:Stone .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+    let entailment = QueryOptions {
+        entailment: EntailmentRegime::RdfsHierarchy,
+        ..QueryOptions::default()
+    };
+    let animals = "SELECT ?o WHERE { ?o a <http://example.com/Animal> } ORDER BY ?o";
+    let children = "SELECT ?o WHERE { ?s <http://example.com/hasChild> ?o } ORDER BY ?o";
+
+    assert!(select_objects(&store, animals).await?.is_empty());
+    assert_eq!(
+        select_objects_opt(&store, animals, entailment.clone()).await?,
+        vec![ex_term("rex"), ex_term("tom")]
+    );
+    assert_eq!(
+        select_objects(&store, children).await?,
+        vec![ex_term("tim")]
+    );
+    assert_eq!(
+        select_objects_opt(&store, children, entailment).await?,
+        vec![ex_term("fido"), ex_term("tim")]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owl_same_as_entailment() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+            ex:alice owl:sameAs ex:alice2 .
+            ex:alice3 owl:sameAs ex:alice2 .
+            ex:alice ex:knows ex:bob .
+            ex:alice3 ex:knows ex:carol .
+            ex:dave ex:knows ex:alice2 .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+    let entailment = QueryOptions {
+        entailment: EntailmentRegime::OwlSameAs,
+        ..QueryOptions::default()
+    };
+    let known_by_alice = "SELECT ?o WHERE { <http://example.com/alice2> <http://example.com/knows> ?o } ORDER BY ?o";
+    let knows_bob = "SELECT ?o WHERE { ?o <http://example.com/knows> <http://example.com/bob> } ORDER BY ?o";
+    let known_by_dave = "SELECT ?o WHERE { <http://example.com/dave> <http://example.com/knows> ?o } ORDER BY ?o";
+
+    assert!(select_objects(&store, known_by_alice).await?.is_empty());
+    assert_eq!(
+        select_objects_opt(&store, known_by_alice, entailment.clone()).await?,
+        vec![ex_term("bob"), ex_term("carol")]
+    );
+    assert_eq!(
+        select_objects_opt(&store, knows_bob, entailment.clone()).await?,
+        vec![ex_term("alice"), ex_term("alice2"), ex_term("alice3")]
+    );
+    assert_eq!(
+        select_objects_opt(&store, known_by_dave, entailment).await?,
+        vec![ex_term("alice"), ex_term("alice2"), ex_term("alice3")]
+    );
+    Ok(())
+}
+
+fn ex_term(name: &str) -> Term {
+    NamedNode::new_unchecked(format!("http://example.com/{name}")).into()
+}
+
+#[tokio::test]
+async fn test_materialize_rdfs() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let inferred = store
+        .load_from_reader_with_rdfs(
+            RdfFormat::TriG,
+            RDFS_DATA.as_bytes(),
+            &RdfsRules::all(),
+        )
+        .await?;
+
+    let graph = NamedNodeRef::new("http://example.com/g")?;
+    let rex = NamedNodeRef::new("http://example.com/rex")?;
+    let parent = NamedNodeRef::new("http://example.com/Parent")?;
+    let offspring = NamedNodeRef::new("http://example.com/Offspring")?;
+    let fido = NamedNodeRef::new("http://example.com/fido")?;
+    for class in ["Dog", "Mammal", "Animal"] {
+        let class = NamedNode::new(format!("http://example.com/{class}"))?;
+        assert!(
+            store
+                .contains(QuadRef::new(rex, rdf::TYPE, class.as_ref(), graph))
+                .await?
+        );
+    }
+    assert!(
+        store
+            .contains(QuadRef::new(
+                rex,
+                NamedNodeRef::new("http://example.com/hasChild")?,
+                fido,
+                graph
+            ))
+            .await?
+    );
+    assert!(
+        store
+            .contains(QuadRef::new(rex, rdf::TYPE, parent, graph))
+            .await?
+    );
+    assert!(
+        store
+            .contains(QuadRef::new(fido, rdf::TYPE, offspring, graph))
+            .await?
+    );
+    assert!(
+        !store
+            .contains(QuadRef::new(
+                rex,
+                rdf::TYPE,
+                parent,
+                GraphNameRef::DefaultGraph
+            ))
+            .await?
+    );
+
+    let len = store.len().await?;
+    assert_eq!(store.materialize_rdfs(&RdfsRules::all()).await?, 0);
+    assert_eq!(store.len().await?, len);
+    assert!(inferred > 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_materialize_rdfs_only_uses_enabled_rules() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::TriG, RDFS_DATA.as_bytes())
+        .await?;
+
+    store
+        .materialize_rdfs(&RdfsRules::none().with_rule(RdfsRule::SubClassOf))
+        .await?;
+
+    let graph = NamedNodeRef::new("http://example.com/g")?;
+    let rex = NamedNodeRef::new("http://example.com/rex")?;
+    let animal = NamedNodeRef::new("http://example.com/Animal")?;
+    let parent = NamedNodeRef::new("http://example.com/Parent")?;
+    assert!(
+        store
+            .contains(QuadRef::new(rex, rdf::TYPE, animal, graph))
+            .await?
+    );
+    assert!(
+        !store
+            .contains(QuadRef::new(rex, rdf::TYPE, parent, graph))
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_group_by_expression() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:a ex:date "2020-01-01T00:00:00Z"^^xsd:dateTime .
+            ex:b ex:date "2020-06-01T00:00:00Z"^^xsd:dateTime .
+            ex:c ex:date "2021-01-01T00:00:00Z"^^xsd:dateTime .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    let QueryResults::Solutions(solutions) = store
+        .query(
+            "SELECT ?y (COUNT(*) AS ?c) WHERE {
+                ?s <http://example.com/date> ?date
+            } GROUP BY (YEAR(?date) AS ?y) ORDER BY ?y",
+        )
+        .await?
+    else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    let groups = solutions
+        .iter()
+        .map(|solution| (solution.get("y").cloned(), solution.get("c").cloned()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        groups,
+        vec![
+            (
+                Some(Literal::from(2020).into()),
+                Some(Literal::from(2).into())
+            ),
+            (
+                Some(Literal::from(2021).into()),
+                Some(Literal::from(1).into())
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_group_by_over_ordered_scan_uses_streaming_aggregate()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    for i in 0..20 {
+        let s = NamedNode::new(format!("http://example.com/s{}", i % 5))?;
+        let o = NamedNode::new(format!("http://example.com/o{i}"))?;
+        store
+            .insert(QuadRef::new(&s, p, &o, GraphNameRef::DefaultGraph))
+            .await?;
+    }
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT ?s (COUNT(?o) AS ?c) WHERE {
+                ?s <http://example.com/p> ?o
+            } GROUP BY ?s",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    let aggregate = find_aggregate(explanation.execution_plan.as_ref())
+        .expect("Expected an aggregation");
+    assert_eq!(aggregate.input_order_mode(), &InputOrderMode::Sorted);
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    let mut groups = solutions
+        .try_collect::<Vec<_>>()
+        .await?
+        .iter()
+        .map(|solution| (solution.get("s").cloned(), solution.get("c").cloned()))
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|(s, _)| s.as_ref().map(Term::to_string));
+    let expected = (0..5)
+        .map(|i| {
+            (
+                Some(NamedNode::new_unchecked(format!("http://example.com/s{i}")).into()),
+                Some(Literal::from(4).into()),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(groups, expected);
+    Ok(())
+}
+
+/// Returns the first [AggregateExec] in `plan`.
+fn find_aggregate(plan: &dyn ExecutionPlan) -> Option<&AggregateExec> {
+    if let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() {
+        return Some(aggregate);
+    }
+    plan.children()
+        .into_iter()
+        .find_map(|child| find_aggregate(child.as_ref()))
+}
+
+#[tokio::test]
+async fn test_join_uses_smaller_pattern_as_build_side() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let common = NamedNodeRef::new("http://example.com/common")?;
+    let rare = NamedNodeRef::new("http://example.com/rare")?;
+    for i in 0..200 {
+        let s = NamedNode::new(format!("http://example.com/s{i}"))?;
+        store
+            .insert(QuadRef::new(
+                &s,
+                common,
+                &Literal::from(i),
+                GraphNameRef::DefaultGraph,
+            ))
+            .await?;
+        if i % 100 == 0 {
+            store
+                .insert(QuadRef::new(
+                    &s,
+                    rare,
+                    &Literal::from(i),
+                    GraphNameRef::DefaultGraph,
+                ))
+                .await?;
+        }
+    }
+
+    // The selective pattern is written last. Without statistics, it would become the probe side.
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT ?s WHERE {
+                ?s <http://example.com/common> ?c .
+                ?s <http://example.com/rare> ?r
+            }",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    let join = find_hash_join(explanation.execution_plan.as_ref())
+        .expect("Expected a hash join");
+    let build_side = displayable(join.left().as_ref()).indent(true).to_string();
+    let probe_side = displayable(join.right().as_ref()).indent(true).to_string();
+    assert!(build_side.contains("predicate=<http://example.com/rare>"));
+    assert!(probe_side.contains("predicate=<http://example.com/common>"));
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 2);
+    Ok(())
+}
+
+/// Returns the first [HashJoinExec] in `plan`.
+fn find_hash_join(plan: &dyn ExecutionPlan) -> Option<&HashJoinExec> {
+    if let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+        return Some(join);
+    }
+    plan.children()
+        .into_iter()
+        .find_map(|child| find_hash_join(child.as_ref()))
+}
+
+#[tokio::test]
+async fn test_distinct_union_of_equivalent_branches() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let quads = (0..10)
+        .map(|i| {
+            let subject = NamedNode::new(format!("http://example.com/s{}", i % 5))?;
+            Ok(
+                QuadRef::new(&subject, p, &Literal::from(i), GraphNameRef::DefaultGraph)
+                    .into_owned(),
+            )
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    store.extend(quads).await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT DISTINCT ?s WHERE {
+                { ?s <http://example.com/p> ?o }
+                UNION
+                { ?s <http://example.com/p> ?o }
+            }",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    let plan = format!("{}", explanation.optimized_logical_plan.display_indent());
+    assert!(!plan.contains("Union"), "{plan}");
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    let subjects = solutions.try_collect::<Vec<_>>().await?;
+    assert_eq!(subjects.len(), 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_join_of_sorted_patterns_uses_merge_join() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let a = NamedNodeRef::new("http://example.com/a")?;
+    let b = NamedNodeRef::new("http://example.com/b")?;
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let quad = |s, i| {
+        QuadRef::new(s, p, &Literal::from(i), GraphNameRef::DefaultGraph).into_owned()
+    };
+    // The objects are encoded in ascending order, which is the order of the index.
+    let quads = (0..50)
+        .map(|i| quad(a, i))
+        .chain((0..50).rev().filter(|i| i % 2 == 0).map(|i| quad(b, i)))
+        .collect::<Vec<_>>();
+    store.extend(quads).await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT ?o WHERE {
+                <http://example.com/a> <http://example.com/p> ?o .
+                <http://example.com/b> <http://example.com/p> ?o
+            }",
+            QueryOptions::default(),
+        )
+        .await?;
+
+    assert!(find_hash_join(explanation.execution_plan.as_ref()).is_none());
+    assert!(
+        explanation
+            .physical_plan_string()
+            .contains("SortMergeJoinExec: join_type=Inner"),
+        "{}",
+        explanation.physical_plan_string()
+    );
+
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    let objects = solutions
+        .map_ok(|solution| solution.get("o").cloned())
+        .try_collect::<Vec<_>>()
+        .await?;
+    let expected = (0..50)
+        .filter(|i| i % 2 == 0)
+        .map(|i| Some(Term::from(Literal::from(i))))
+        .collect::<Vec<_>>();
+    assert_eq!(objects, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sorted_group_concat_is_deterministic() -> Result<(), Box<dyn Error>> {
+    let subject = NamedNodeRef::new("http://example.com/s")?;
+    let predicate = NamedNodeRef::new("http://example.com/p")?;
+    let objects = (0..100)
+        .rev()
+        .map(|i| Literal::new_simple_literal(format!("v{i:03}")))
+        .collect::<Vec<_>>();
+    let expected = (0..100)
+        .map(|i| format!("v{i:03}"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    for target_partitions in [1, 2, 4, 8] {
+        let store = Store::new_with_datafusion_config(
+            SessionConfig::new()
+                .with_target_partitions(target_partitions)
+                .with_batch_size(8),
+            RuntimeEnv::default().into(),
+        );
+        store
+            .context()
+            .functions()
+            .register_udaf(group_concat_sorted_typed_value(Arc::clone(
+                store.context().encodings().typed_value(),
+            )));
+        store
+            .extend(objects.iter().map(|object| {
+                QuadRef::new(subject, predicate, object, GraphNameRef::DefaultGraph)
+            }))
+            .await?;
+
+        let results = select_objects(
+            &store,
+            "SELECT (GROUP_CONCAT(?v; SEPARATOR=\",\") AS ?o) WHERE { ?s ?p ?v }",
+        )
+        .await?;
+        assert_eq!(
+            results,
+            vec![Literal::new_simple_literal(expected.as_str()).into()],
+            "target_partitions = {target_partitions}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deterministic_order() -> Result<(), Box<dyn Error>> {
+    let subject = NamedNodeRef::new("http://example.com/s")?;
+    let predicate = NamedNodeRef::new("http://example.com/p")?;
+    let objects = (0..100)
+        .rev()
+        .map(|i| Literal::new_simple_literal(format!("v{i:03}")))
+        .collect::<Vec<_>>();
+    let options = QueryOptions {
+        deterministic_order: true,
+        ..QueryOptions::default()
+    };
+
+    for target_partitions in [1, 2, 4, 8] {
+        let store = Store::new_with_datafusion_config(
+            SessionConfig::new()
+                .with_target_partitions(target_partitions)
+                .with_batch_size(8),
+            RuntimeEnv::default().into(),
+        );
+        store
+            .extend(objects.iter().map(|object| {
+                QuadRef::new(subject, predicate, object, GraphNameRef::DefaultGraph)
+            }))
+            .await?;
+
+        // Without an explicit order, the solutions are sorted by object id (i.e., the order of
+        // insertion in this case).
+        let results =
+            select_objects_opt(&store, "SELECT ?o WHERE { ?s ?p ?o }", options.clone())
+                .await?;
+        let expected = objects.iter().cloned().map(Term::from).collect::<Vec<_>>();
+        assert_eq!(results, expected, "target_partitions = {target_partitions}");
+
+        let results = select_objects_opt(
+            &store,
+            "SELECT ?o WHERE { ?s ?p ?o } LIMIT 3 OFFSET 2",
+            options.clone(),
+        )
+        .await?;
+        assert_eq!(
+            results,
+            expected[2..5],
+            "target_partitions = {target_partitions}"
+        );
+
+        // An explicit order is not overridden.
+        let results = select_objects_opt(
+            &store,
+            "SELECT ?o WHERE { ?s ?p ?o } ORDER BY ?o LIMIT 3",
+            options.clone(),
+        )
+        .await?;
+        assert_eq!(
+            results,
+            vec![
+                Literal::new_simple_literal("v000").into(),
+                Literal::new_simple_literal("v001").into(),
+                Literal::new_simple_literal("v002").into(),
+            ],
+            "target_partitions = {target_partitions}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_median_aggregate() -> Result<(), Box<dyn Error>> {
+    let median = NamedNode::new("http://example.com/median")?;
+    let store = Store::default();
+    store
+        .context()
+        .functions()
+        .register_udaf(median_typed_value(
+            median.clone(),
+            Arc::clone(store.context().encodings().typed_value()),
+        ));
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:a ex:price 5, 1, 3 .
+            ex:b ex:price 4, 1, 3, 2 .
+            ex:c ex:price "free" .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    let query = Query::parse_with_custom_aggregates(
+        "SELECT (<http://example.com/median>(?price) AS ?o) WHERE {
+            ?s <http://example.com/price> ?price
+        } GROUP BY ?s ORDER BY ?s",
+        [median],
+    )?;
+    let QueryResults::Solutions(solutions) = store.query(query).await? else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    let medians = solutions
+        .iter()
+        .map(|solution| solution.get("o").cloned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        medians,
+        vec![
+            Some(Literal::from(3).into()),
+            Some(Literal::new_typed_literal("2.5", xsd::DECIMAL).into()),
+            None
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_variance_aggregates() -> Result<(), Box<dyn Error>> {
+    let stdev = NamedNode::new("http://example.com/stdev")?;
+    let sample_variance = NamedNode::new("http://example.com/sampleVariance")?;
+    let store = Store::default();
+    let encoding = store.context().encodings().typed_value();
+    store
+        .context()
+        .functions()
+        .register_udaf(variance_typed_value(
+            stdev.clone(),
+            VarianceStatistic::PopulationStandardDeviation,
+            Arc::clone(encoding),
+        ));
+    store
+        .context()
+        .functions()
+        .register_udaf(variance_typed_value(
+            sample_variance.clone(),
+            VarianceStatistic::SampleVariance,
+            Arc::clone(encoding),
+        ));
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            r#"
+            @prefix ex: <http://example.com/> .
+            ex:a ex:v 2, 4, 4.0, "4"^^<http://www.w3.org/2001/XMLSchema#short>, 5, 5.0, 7, 9e0 .
+            ex:b ex:v 42 .
+            ex:c ex:v "forty-two" .
+            "#
+            .as_bytes(),
+        )
+        .await?;
+
+    let query = Query::parse_with_custom_aggregates(
+        "SELECT ?s (<http://example.com/stdev>(?v) AS ?stdev)
+            (<http://example.com/sampleVariance>(?v) AS ?variance)
+        WHERE { ?s <http://example.com/v> ?v } GROUP BY ?s ORDER BY ?s",
+        [stdev, sample_variance],
+    )?;
+    let QueryResults::Solutions(solutions) = store.query(query).await? else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    let double = |solution: &QuerySolution, variable: &str| match solution.get(variable) {
+        Some(Term::Literal(literal)) => {
+            assert_eq!(literal.datatype(), xsd::DOUBLE);
+            literal.value().parse::<f64>().ok()
+        }
+        _ => None,
+    };
+
+    let [a, b, c] = solutions.as_slice() else {
+        panic!("Expected three groups");
+    };
+    assert!((double(a, "stdev").unwrap() - 2.0).abs() < 1e-12);
+    assert!((double(a, "variance").unwrap() - 32.0 / 7.0).abs() < 1e-12);
+    assert_eq!(double(b, "stdev"), Some(0.0));
+    assert_eq!(b.get("variance"), None);
+    assert_eq!(c.get("stdev"), None);
+    assert_eq!(c.get("variance"), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_datatype_comparison() -> Result<(), Box<dyn Error>> {
+    let currency_store = Store::default();
+    currency_store.register_custom_datatype(CustomDatatype::new(
+        NamedNode::new("http://example.com/currency")?,
+        |value| parse_amount(value).is_some(),
+        |lhs, rhs| {
+            let (lhs_amount, lhs_currency) = parse_amount(lhs)?;
+            let (rhs_amount, rhs_currency) = parse_amount(rhs)?;
+            (lhs_currency == rhs_currency)
+                .then(|| lhs_amount.partial_cmp(&rhs_amount))
+                .flatten()
+        },
+    ));
+    let other_store = Store::default();
+
+    let less = r#""9.5 EUR"^^ex:currency < "10 EUR"^^ex:currency"#;
+    let equal = r#""10 EUR"^^ex:currency = "10.00 EUR"^^ex:currency"#;
+    let different_currencies = r#""9.5 EUR"^^ex:currency < "10 USD"^^ex:currency"#;
+    let invalid = r#""ten EUR"^^ex:currency = "ten EUR"^^ex:currency"#;
+    let unregistered = r#""10 EUR"^^ex:other = "10 EUR"^^ex:other"#;
+
+    assert!(ask_filter(&currency_store, less).await?);
+    assert!(ask_filter(&currency_store, equal).await?);
+    assert!(!ask_filter(&currency_store, different_currencies).await?);
+    assert!(!ask_filter(&currency_store, invalid).await?);
+    assert!(ask_filter(&currency_store, unregistered).await?);
+
+    // The registration is per store. Otherwise, literals of unknown datatypes are only equal if
+    // their lexical forms are equal.
+    assert!(!ask_filter(&other_store, less).await?);
+    assert!(!ask_filter(&other_store, equal).await?);
+    assert!(
+        ask_filter(
+            &other_store,
+            r#""10 EUR"^^ex:currency = "10 EUR"^^ex:currency"#
+        )
+        .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_literal_normalization_preserves_lexical_forms_by_default()
+-> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            br#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:a ex:value "01"^^xsd:integer .
+            ex:b ex:value "1"^^xsd:integer .
+            "#
+            .as_slice(),
+        )
+        .await?;
+
+    let values = select_objects(
+        &store,
+        "SELECT DISTINCT ?o WHERE { ?s <http://example.com/value> ?o }",
+    )
+    .await?;
+    assert_eq!(values.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_literal_normalization_canonical() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store.set_literal_normalization(LiteralNormalization::Canonical);
+    store
+        .load_from_reader(
+            RdfFormat::Turtle,
+            br#"
+            @prefix ex: <http://example.com/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            ex:a ex:value "01"^^xsd:integer .
+            ex:b ex:value "1"^^xsd:integer .
+            ex:c ex:value "1.50"^^xsd:decimal .
+            ex:d ex:value "01"@en .
+            "#
+            .as_slice(),
+        )
+        .await?;
+    store
+        .update(
+            r#"PREFIX ex: <http://example.com/>
+            PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+            INSERT DATA { ex:e ex:value "+0001"^^xsd:integer }"#,
+        )
+        .await?;
+
+    let values = select_objects(
+        &store,
+        "SELECT DISTINCT ?o WHERE { ?s <http://example.com/value> ?o }",
+    )
+    .await?;
+    assert_eq!(
+        values.into_iter().collect::<HashSet<_>>(),
+        HashSet::from([
+            Literal::new_typed_literal("1", xsd::INTEGER).into(),
+            Literal::new_typed_literal("1.5", xsd::DECIMAL).into(),
+            Literal::new_language_tagged_literal("01", "en")?.into(),
+        ])
+    );
+
+    // The values of ex:a, ex:b, and ex:e join on their canonical lexical form.
+    let joined = select_objects(
+        &store,
+        "SELECT ?o WHERE {
+            <http://example.com/a> <http://example.com/value> ?v .
+            ?o <http://example.com/value> ?v
+        }",
+    )
+    .await?;
+    assert_eq!(joined.len(), 3);
+
+    // Removing uses the same normalization as inserting.
+    let a = NamedNodeRef::new("http://example.com/a")?;
+    let value = NamedNodeRef::new("http://example.com/value")?;
+    let original = Literal::new_typed_literal("01", xsd::INTEGER);
+    assert!(
+        store
+            .remove(QuadRef::new(
+                a,
+                value,
+                &original,
+                GraphNameRef::DefaultGraph
+            ))
+            .await?
+    );
+    assert_eq!(store.len().await?, 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_explain_query() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+
+    let explanation = store
+        .context()
+        .explain_query(
+            "SELECT ?s ?o WHERE { ?s <http://schema.org/name> ?name . ?s ?p ?o FILTER(?o = 1) }",
+        )
+        .await?;
+    let (logical_plan, physical_plan) = explanation
+        .split_once("Physical Plan:")
+        .expect("Physical plan is missing");
+    assert!(logical_plan.contains("QuadPattern (?s <http://schema.org/name> ?name)"));
+    assert!(logical_plan.contains("Join"));
+    assert!(physical_plan.contains("HashJoinExec"));
+    assert!(physical_plan.contains("FilterExec"));
+    assert!(
+        physical_plan.contains("[GPOS] subject=?s, predicate=<http://schema.org/name>")
+    );
+    assert!(physical_plan.contains("[GSPO] subject=?s, predicate=?p, object=?o"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_explain_keyword() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    store
+        .load_from_reader(RdfFormat::Turtle, DATA.as_bytes())
+        .await?;
+
+    let QueryResults::Solutions(solutions) = store
+        .query("EXPLAIN SELECT ?s WHERE { ?s <http://schema.org/name> ?name }")
+        .await?
+    else {
+        panic!("Expected solutions");
+    };
+    let plans = solutions
+        .map(|solution| {
+            let solution = solution?;
+            let Some(Term::Literal(plan_type)) = solution.get("plan_type") else {
+                panic!("Expected a plan type");
+            };
+            let Some(Term::Literal(plan)) = solution.get("plan") else {
+                panic!("Expected a plan");
+            };
+            Ok::<_, QueryEvaluationError>((
+                plan_type.value().to_owned(),
+                plan.value().to_owned(),
+            ))
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    assert_eq!(plans.len(), 2);
+    assert_eq!(plans[0].0, "logical_plan");
+    assert!(plans[0].1.contains("QuadPattern"));
+    assert_eq!(plans[1].0, "physical_plan");
+    assert!(plans[1].1.contains("[GPOS]"));
+    Ok(())
+}
+
+async fn ask_filter(store: &Store, filter: &str) -> Result<bool, Box<dyn Error>> {
+    let query = format!("PREFIX ex: <http://example.com/> ASK {{ FILTER({filter}) }}");
+    let QueryResults::Boolean(result) = store.query(query.as_str()).await? else {
+        panic!("Expected a boolean result");
+    };
+    Ok(result)
+}
+
+fn parse_amount(value: &str) -> Option<(f64, &str)> {
+    let (amount, currency) = value.split_once(' ')?;
+    Some((amount.parse().ok()?, currency))
+}
+
+async fn select_objects(store: &Store, query: &str) -> Result<Vec<Term>, Box<dyn Error>> {
+    select_objects_opt(store, query, QueryOptions::default()).await
+}
+
+async fn select_objects_opt(
+    store: &Store,
+    query: &str,
+    options: QueryOptions,
+) -> Result<Vec<Term>, Box<dyn Error>> {
+    let QueryResults::Solutions(solutions) = store.query_opt(query, options).await?
+    else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    Ok(solutions
+        .iter()
+        .filter_map(|solution| solution.get("o").cloned())
+        .collect())
+}
+
+/// Parses `query` with support for bounded property paths and returns the bindings of `?o`.
+async fn select_repeated_objects(
+    store: &Store,
+    query: &str,
+) -> Result<Vec<Term>, Box<dyn Error>> {
+    let query = Query::parse_with_path_repetitions(query, None)?;
+    let (results, _) = store
+        .context()
+        .execute_query(&query, QueryOptions::default())
+        .await?;
+    let QueryResults::Solutions(solutions) = results else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    Ok(solutions
+        .iter()
+        .filter_map(|solution| solution.get("o").cloned())
+        .collect())
+}
+
+/// Evaluates `aggregate` for each subject in a join of `ex:p` and `ex:q`.
+async fn select_aggregates(
+    store: &Store,
+    aggregate: &str,
+) -> Result<Vec<Term>, Box<dyn Error>> {
+    let query = format!(
+        "SELECT ({aggregate} AS ?result) WHERE {{
+            ?s <http://example.com/p> ?o ; <http://example.com/q> ?x
+        }} GROUP BY ?s ORDER BY ?s"
+    );
+    let QueryResults::Solutions(solutions) = store.query(query.as_str()).await? else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    Ok(solutions
+        .iter()
+        .filter_map(|solution| solution.get("result").cloned())
+        .collect())
+}
+
+#[tokio::test]
+async fn test_limit_is_pushed_into_quad_pattern_scan() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let p = NamedNodeRef::new("http://example.com/p")?;
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let subjects = (0..1_000_000)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(
+            subjects
+                .iter()
+                .map(|s| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph)),
+        )
+        .await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT * WHERE { ?s ?p ?o } LIMIT 5",
+            QueryOptions::default(),
+        )
+        .await?;
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 5);
+    assert!(explanation.logical_plan_string().contains("limit: 5"));
+    assert_eq!(scanned_rows(explanation.execution_plan.as_ref()), 5);
+    assert_eq!(scanned_batches(explanation.execution_plan.as_ref()), 1);
+
+    // A filter may drop rows, so the limit must not reach the quad pattern.
+    let (_, explanation) = store
+        .explain_query_opt(
+            "SELECT * WHERE { ?s ?p ?o FILTER(?s != <http://example.com/s0>) } LIMIT 5",
+            QueryOptions::default(),
+        )
+        .await?;
+    assert!(!explanation.logical_plan_string().contains("limit: 5"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_values_are_inlined_into_quad_pattern_scan() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+    let o = NamedNodeRef::new("http://example.com/o")?;
+    let predicates = (0..100)
+        .map(|i| NamedNode::new(format!("http://example.com/p{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let subjects = (0..100)
+        .map(|i| NamedNode::new(format!("http://example.com/s{i}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    store
+        .extend(subjects.iter().flat_map(|s| {
+            predicates
+                .iter()
+                .map(move |p| QuadRef::new(s, p, o, GraphNameRef::DefaultGraph))
+        }))
+        .await?;
+
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT * WHERE { VALUES ?p { <http://example.com/p1> <http://example.com/p2> } ?s ?p ?o }",
+            QueryOptions::default(),
+        )
+        .await?;
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    assert_eq!(solutions.try_collect::<Vec<_>>().await?.len(), 200);
+    assert!(!explanation.logical_plan_string().contains("Join"));
+    assert!(explanation.physical_plan_string().contains(" in ("));
+    assert_eq!(scanned_rows(explanation.execution_plan.as_ref()), 200);
+
+    // Only ?p can be pushed into the scan. The join is still required for ?x.
+    let (result, explanation) = store
+        .explain_query_opt(
+            "SELECT * WHERE { VALUES (?p ?x) { (<http://example.com/p1> 1) (<http://example.com/p2> 2) } ?s ?p ?o }",
+            QueryOptions::default(),
+        )
+        .await?;
+    let QueryResults::Solutions(solutions) = result else {
+        panic!("Expected solutions");
+    };
+    let solutions = solutions.try_collect::<Vec<_>>().await?;
+    assert_eq!(solutions.len(), 200);
+    assert!(solutions.iter().all(|s| s.get("x").is_some()));
+    assert!(explanation.logical_plan_string().contains("Join"));
+    assert!(explanation.physical_plan_string().contains(" in ("));
+    assert_eq!(scanned_rows(explanation.execution_plan.as_ref()), 200);
+    Ok(())
+}
+
+fn scanned_batches(plan: &dyn ExecutionPlan) -> usize {
+    let own_batches = match plan.name() {
+        "DataSourceExec" => plan
+            .metrics()
+            .and_then(|metrics| {
+                metrics.sum(|m| matches!(m.value(), MetricValue::OutputBatches(_)))
+            })
+            .map(|value| value.as_usize())
+            .unwrap_or_default(),
+        _ => 0,
+    };
+    own_batches
+        + plan
+            .children()
+            .into_iter()
+            .map(|child| scanned_batches(child.as_ref()))
+            .sum::<usize>()
+}
+
+#[tokio::test]
+async fn test_query_diff() -> Result<(), Box<dyn Error>> {
+    let old = Store::default();
+    old.load_from_reader(
+        RdfFormat::Turtle,
+        "@prefix ex: <http://example.com/> .
+        _:a ex:name \"A\" ; ex:knows _:b .
+        _:b ex:name \"B\" ."
+            .as_bytes(),
+    )
+    .await?;
+    let new = Store::default();
+    new.load_from_reader(
+        RdfFormat::Turtle,
+        "@prefix ex: <http://example.com/> .
+        _:x ex:name \"A\" ; ex:knows _:y .
+        _:y ex:name \"C\" .
+        ex:c ex:name \"C\" ."
+            .as_bytes(),
+    )
+    .await?;
+
+    let query = "SELECT ?s ?name WHERE { ?s <http://example.com/name> ?name }";
+    let diff = old.query_diff(&new, query).await?;
+    let names = |solutions: &[QuerySolution]| {
+        solutions
+            .iter()
+            .map(|s| {
+                (
+                    s.get("s").unwrap().is_blank_node(),
+                    s.get("name").unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(diff.removed()), vec![(true, "\"B\"".to_owned())]);
+    let mut added = names(diff.added());
+    added.sort();
+    assert_eq!(
+        added,
+        vec![(false, "\"C\"".to_owned()), (true, "\"C\"".to_owned())]
+    );
+
+    // Blank nodes are compared up to renaming.
+    let query = "SELECT ?s ?o WHERE { ?s <http://example.com/knows> ?o }";
+    assert!(old.query_diff(&new, query).await?.is_empty());
+
+    // Solutions are compared as multisets.
+    let query = "SELECT ?name WHERE { ?s <http://example.com/name> ?name }";
+    let diff = new.query_diff(&new, query).await?;
+    assert!(diff.is_empty());
+    let diff = old.query_diff(&new, query).await?;
+    assert_eq!(diff.added().len(), 2);
+    assert_eq!(diff.removed().len(), 1);
+
+    let old_snapshot = old.snapshot().await?;
+    let new_snapshot = new.snapshot().await?;
+    let diff = old_snapshot.query_diff(&new_snapshot, query).await?;
+    assert_eq!(diff.added().len(), 2);
+    assert_eq!(diff.removed().len(), 1);
+
+    assert!(matches!(
+        old.query_diff(&new, "ASK { ?s ?p ?o }").await,
+        Err(QueryEvaluationError::NotSolutions)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quads_for_pattern_combinations() -> Result<(), Box<dyn Error>> {
+    let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+    let graph = GraphName::from(ex("g"));
+    let quads = vec![
+        Quad::new(ex("a"), ex("p"), ex("b"), GraphName::DefaultGraph),
+        Quad::new(ex("a"), ex("p"), ex("b"), graph.clone()),
+        Quad::new(ex("a"), ex("q"), Literal::from(1), GraphName::DefaultGraph),
+        Quad::new(BlankNode::new("x")?, ex("p"), ex("a"), graph.clone()),
+        Quad::new(ex("b"), ex("q"), ex("b"), graph.clone()),
+    ];
+    let store = Store::default();
+    store.extend(quads.clone()).await?;
+
+    let subject = NamedOrBlankNode::from(ex("a"));
+    let predicate = ex("p");
+    let object = Term::from(ex("b"));
+    for mask in 0..16 {
+        let subject = (mask & 1 != 0).then(|| subject.as_ref());
+        let predicate = (mask & 2 != 0).then(|| predicate.as_ref());
+        let object = (mask & 4 != 0).then(|| object.as_ref());
+        let graph_name = (mask & 8 != 0).then(|| graph.as_ref());
+
+        let mut expected = quads
+            .iter()
+            .filter(|q| subject.is_none_or(|s| q.subject.as_ref() == s))
+            .filter(|q| predicate.is_none_or(|p| q.predicate.as_ref() == p))
+            .filter(|q| object.is_none_or(|o| q.object.as_ref() == o))
+            .filter(|q| graph_name.is_none_or(|g| q.graph_name.as_ref() == g))
+            .cloned()
+            .collect::<Vec<_>>();
+        expected.sort_by_key(ToString::to_string);
+        let mut actual = store
+            .quads_for_pattern(subject, predicate, object, graph_name)
+            .await?
+            .try_collect_to_vec()
+            .await?;
+        actual.sort_by_key(ToString::to_string);
+        assert_eq!(actual, expected, "pattern {mask:04b}");
+    }
+
+    // The default graph only matches the quads in the default graph.
+    let default_graph = store
+        .quads_for_pattern(None, None, None, Some(GraphNameRef::DefaultGraph))
+        .await?
+        .try_collect_to_vec()
+        .await?;
+    assert_eq!(default_graph.len(), 2);
+    assert!(
+        default_graph
+            .iter()
+            .all(|q| q.graph_name.is_default_graph())
+    );
+
+    // Unknown terms do not match any quad.
+    let unknown = ex("unknown");
+    for (subject, predicate, graph_name) in [
+        (Some(unknown.as_ref().into()), None, None),
+        (None, Some(unknown.as_ref()), None),
+        (None, None, Some(unknown.as_ref().into())),
+    ] {
+        let count = store
+            .quads_for_pattern(subject, predicate, None, graph_name)
+            .await?
+            .try_collect_to_vec()
+            .await?
+            .len();
+        assert_eq!(count, 0);
+    }
+
+    let snapshot = store.snapshot().await?;
+    let named_graph = snapshot
+        .quads_for_pattern(None, None, None, Some(graph.as_ref()))
+        .await?
+        .try_collect_to_vec()
+        .await?;
+    assert_eq!(named_graph.len(), 3);
+    snapshot.release();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_len_and_is_empty() -> Result<(), Box<dyn Error>> {
+    let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+    let g1 = ex("g1");
+    let g2 = ex("g2");
+    let store = Store::default();
+    assert_eq!(store.len().await?, 0);
+    assert!(store.is_empty().await?);
+
+    // The same triple in different graphs is counted once per graph.
+    let in_default = Quad::new(ex("a"), ex("p"), ex("b"), GraphName::DefaultGraph);
+    let in_g1 = Quad::new(ex("a"), ex("p"), ex("b"), g1.clone());
+    let in_g2 = Quad::new(ex("a"), ex("p"), ex("b"), g2.clone());
+    store
+        .extend([in_default.clone(), in_g1.clone(), in_g2.clone()])
+        .await?;
+    store
+        .insert(QuadRef::new(&ex("c"), &ex("p"), &ex("d"), &g1))
+        .await?;
+    assert_eq!(store.len().await?, 4);
+    assert!(!store.is_empty().await?);
+
+    // Inserting an existing quad does not change the count.
+    store.insert(&in_g1).await?;
+    assert_eq!(store.len().await?, 4);
+
+    store.remove(&in_g2).await?;
+    assert_eq!(store.len().await?, 3);
+    store.remove(&in_g2).await?;
+    assert_eq!(store.len().await?, 3);
+
+    // Empty named graphs are not counted.
+    store.clear_graph(&g1).await?;
+    assert_eq!(store.len().await?, 1);
+    assert!(store.contains_named_graph(&g1).await?);
+
+    store.remove(&in_default).await?;
+    assert_eq!(store.len().await?, 0);
+    assert!(store.is_empty().await?);
+    assert!(!store.named_graphs().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_named_graphs_includes_empty_graphs() -> Result<(), Box<dyn Error>> {
+    let ex = |name: &str| NamedNode::new_unchecked(format!("http://example.com/{name}"));
+    let store = Store::default();
+    assert!(store.named_graphs().await?.is_empty());
+
+    let empty = NamedOrBlankNode::from(ex("empty"));
+    let blank = NamedOrBlankNode::from(BlankNode::new("empty")?);
+    assert!(store.insert_named_graph(&empty).await?);
+    assert!(store.insert_named_graph(&blank).await?);
+    assert!(store.is_empty().await?);
+
+    store
+        .insert(QuadRef::new(&ex("a"), &ex("p"), &ex("b"), &ex("full")))
+        .await?;
+    store
+        .insert(QuadRef::new(
+            &ex("a"),
+            &ex("p"),
+            &ex("b"),
+            GraphNameRef::DefaultGraph,
+        ))
+        .await?;
+
+    assert_eq!(
+        store
+            .named_graphs()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        HashSet::from([empty, blank, ex("full").into()])
+    );
+    Ok(())
+}
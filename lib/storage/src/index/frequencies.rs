@@ -0,0 +1,151 @@
+use crate::index::{EncodedQuad, EncodedTerm, IndexComponent};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Counts how many quads reference a term in each component of a quad.
+///
+/// The frequencies are computed from the quads of an index by the
+/// [IndexPermutations](crate::index::IndexPermutations). The default graph is not counted as a
+/// graph name.
+#[derive(Debug, Clone)]
+pub struct TermFrequencies<TTerm: EncodedTerm> {
+    /// The frequencies of the terms, indexed by [IndexComponent::gspo_index].
+    frequencies: [HashMap<TTerm, usize>; 4],
+}
+
+impl<TTerm: EncodedTerm> Default for TermFrequencies<TTerm> {
+    fn default() -> Self {
+        Self {
+            frequencies: Default::default(),
+        }
+    }
+}
+
+impl<TTerm: EncodedTerm> TermFrequencies<TTerm> {
+    /// Counts the terms of the distinct `quads`.
+    pub fn from_quads(quads: impl IntoIterator<Item = EncodedQuad<TTerm>>) -> Self {
+        let mut frequencies = Self::default();
+        for quad in quads {
+            frequencies.add(&quad);
+        }
+        frequencies
+    }
+
+    /// Returns the number of quads that reference `term` in the given `component`.
+    pub fn frequency(&self, component: IndexComponent, term: &TTerm) -> usize {
+        self.frequencies[component.gspo_index()]
+            .get(term)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of distinct terms in the given `component`.
+    pub fn distinct_terms(&self, component: IndexComponent) -> usize {
+        self.frequencies[component.gspo_index()].len()
+    }
+
+    /// Returns the `k` most frequent terms in the given `component`, ordered by descending
+    /// frequency. Terms with equal frequencies are ordered by their encoding.
+    pub fn top_k(&self, component: IndexComponent, k: usize) -> Vec<(TTerm, usize)> {
+        let mut terms = self.frequencies[component.gspo_index()]
+            .iter()
+            .map(|(term, count)| (*term, *count))
+            .collect::<Vec<_>>();
+        let order = |(term, count): &(TTerm, usize)| (Reverse(*count), *term);
+
+        if k < terms.len() {
+            terms.select_nth_unstable_by_key(k, order);
+            terms.truncate(k);
+        }
+        terms.sort_unstable_by_key(order);
+        terms
+    }
+
+    /// Counts the terms of `quad`.
+    fn add(&mut self, quad: &EncodedQuad<TTerm>) {
+        for (component, term) in Self::components(quad) {
+            *self.frequencies[component.gspo_index()]
+                .entry(term)
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns the components of `quad` that are counted.
+    fn components(
+        quad: &EncodedQuad<TTerm>,
+    ) -> impl Iterator<Item = (IndexComponent, TTerm)> {
+        [
+            (IndexComponent::GraphName, quad.graph_name),
+            (IndexComponent::Subject, quad.subject),
+            (IndexComponent::Predicate, quad.predicate),
+            (IndexComponent::Object, quad.object),
+        ]
+        .into_iter()
+        .filter(|(component, term)| {
+            *component != IndexComponent::GraphName || !term.is_default_graph()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_quads() {
+        let frequencies = TermFrequencies::from_quads([
+            quad(0, 1, 2, 3),
+            quad(0, 1, 2, 4),
+            quad(5, 4, 2, 3),
+        ]);
+
+        assert_eq!(frequencies.frequency(IndexComponent::Subject, &MyId(1)), 2);
+        assert_eq!(
+            frequencies.frequency(IndexComponent::Predicate, &MyId(2)),
+            3
+        );
+        assert_eq!(frequencies.frequency(IndexComponent::Object, &MyId(4)), 1);
+        assert_eq!(frequencies.frequency(IndexComponent::Object, &MyId(5)), 0);
+        assert_eq!(frequencies.distinct_terms(IndexComponent::Object), 2);
+        assert_eq!(frequencies.distinct_terms(IndexComponent::GraphName), 1);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let frequencies = TermFrequencies::from_quads([
+            quad(0, 1, 2, 3),
+            quad(0, 1, 2, 4),
+            quad(0, 1, 3, 4),
+            quad(0, 2, 3, 4),
+            quad(0, 3, 4, 4),
+        ]);
+
+        assert_eq!(
+            frequencies.top_k(IndexComponent::Predicate, 2),
+            vec![(MyId(2), 2), (MyId(3), 2)]
+        );
+        assert_eq!(
+            frequencies.top_k(IndexComponent::Subject, 10),
+            vec![(MyId(1), 3), (MyId(2), 1), (MyId(3), 1)]
+        );
+        assert!(frequencies.top_k(IndexComponent::GraphName, 10).is_empty());
+    }
+
+    fn quad(g: u32, s: u32, p: u32, o: u32) -> EncodedQuad<MyId> {
+        EncodedQuad {
+            graph_name: MyId(g),
+            subject: MyId(s),
+            predicate: MyId(p),
+            object: MyId(o),
+        }
+    }
+
+    #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+    struct MyId(u32);
+
+    impl EncodedTerm for MyId {
+        fn is_default_graph(&self) -> bool {
+            self.0 == 0
+        }
+    }
+}
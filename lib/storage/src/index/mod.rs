@@ -12,10 +12,12 @@
 
 mod components;
 mod error;
+mod frequencies;
 mod permutations;
 
 pub use components::*;
 pub use error::*;
+pub use frequencies::*;
 pub use permutations::*;
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -30,14 +32,28 @@ pub trait QuadIndex {
     type NamedGraphStorage: NamedGraphStorage<Term = Self::Term>;
 
     /// The [ScanInstructions] that are used to scan the index.
-    type ScanInstructions: ScanInstructions;
+    type ScanInstructions: ScanInstructions<Term = Self::Term>;
 
     /// Returns the components of the index.
     fn components(&self) -> IndexComponents;
 
+    /// Returns the predicate if this index is specialized for a single predicate.
+    ///
+    /// A specialized index only holds the quads with this predicate. Therefore, it can only be
+    /// used for scans that restrict the predicate to exactly this term.
+    fn specialized_predicate(&self) -> Option<Self::Term>;
+
+    /// Returns the [IndexId] that identifies this index within an [IndexPermutations].
+    fn id(&self) -> IndexId<Self::Term> {
+        IndexId::new(self.components(), self.specialized_predicate())
+    }
+
     /// Returns the total number of quads.
     fn len(&self) -> usize;
 
+    /// Returns the approximate number of bytes used by the index.
+    fn memory_size(&self) -> usize;
+
     /// Computes the "scan score" for the given `instructions`.
     ///
     /// The higher the scan score, the better is the index suited for scanning a particular pattern.
@@ -45,6 +61,9 @@ pub trait QuadIndex {
     /// a scan.
     fn compute_scan_score(&self, instructions: &Self::ScanInstructions) -> usize;
 
+    /// Returns whether `quad` is contained in the index.
+    fn contains(&self, quad: &IndexQuad<Self::Term>) -> bool;
+
     /// Inserts a list of quads.
     ///
     /// Quads that already exist in the index are ignored.
@@ -62,6 +81,17 @@ pub trait QuadIndex {
 
     /// Clears the given `graph_name`.
     fn clear_graph(&mut self, graph_name: Self::Term);
+
+    /// Returns all quads in this index, ordered according to [Self::components].
+    fn quads(&self) -> Vec<IndexQuad<Self::Term>>;
+
+    /// Creates a compacted copy of this index that contains the same quads.
+    ///
+    /// The index itself is not modified, allowing concurrent readers to continue using it while
+    /// the copy is being built.
+    fn compacted(&self) -> Self
+    where
+        Self: Sized;
 }
 
 /// The data structure that is used to represent a single RDF term. This can be either an object id
@@ -97,6 +127,36 @@ pub trait NamedGraphStorage {
 /// Scan instructions are used to implement a scan. The scan instructions should capture which parts
 /// of the quad are bound to variables and which parts should be filtered.
 pub trait ScanInstructions {
+    /// The data structure that is used to represent a single RDF term.
+    type Term;
+
     /// Reorders the [ScanInstructions] to the given `components`.
     fn reorder(&self, components: IndexComponents) -> Self;
+
+    /// Returns the term if the predicate of the scanned quads is restricted to exactly one term.
+    fn bound_predicate(&self) -> Option<Self::Term>;
+
+    /// Returns true if the given `component` is restricted to exactly one term.
+    fn is_fixed(&self, component: IndexComponent) -> bool;
+
+    /// Returns true if scanning an index with the given `components` produces results that are
+    /// ordered by `component`.
+    ///
+    /// This is the case if all components that precede `component` in the index are fixed.
+    fn is_ordered_by(
+        &self,
+        components: IndexComponents,
+        component: IndexComponent,
+    ) -> bool {
+        for current in components.inner() {
+            if *current == component {
+                return true;
+            }
+
+            if !self.is_fixed(*current) {
+                return false;
+            }
+        }
+        false
+    }
 }
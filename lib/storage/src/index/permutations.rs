@@ -1,10 +1,11 @@
 use crate::index::{
     EncodedTerm, IndexComponent, IndexComponents, NamedGraphStorage, QuadIndex,
-    ScanInstructions,
+    ScanInstructions, TermFrequencies,
 };
 use rdf_fusion_model::StorageError;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
+use std::sync::OnceLock;
 
 /// Represents a quad with encoded terms.
 pub struct EncodedQuad<TTerm: EncodedTerm> {
@@ -36,6 +37,75 @@ impl<TTerm: EncodedTerm> EncodedQuad<TTerm> {
     }
 }
 
+impl<TTerm: EncodedTerm> IndexQuad<TTerm> {
+    /// Creates a new [EncodedQuad] from a quad that is sorted for an index with the given
+    /// `components`. This is the inverse of [EncodedQuad::for_index].
+    pub fn to_encoded_quad(&self, components: IndexComponents) -> EncodedQuad<TTerm> {
+        let term = |component: IndexComponent| {
+            let position = components
+                .inner()
+                .iter()
+                .position(|c| *c == component)
+                .expect("Components always contain all components");
+            self.0[position]
+        };
+        EncodedQuad {
+            graph_name: term(IndexComponent::GraphName),
+            subject: term(IndexComponent::Subject),
+            predicate: term(IndexComponent::Predicate),
+            object: term(IndexComponent::Object),
+        }
+    }
+}
+
+/// Identifies an index within an [IndexPermutations].
+///
+/// General indexes are identified by their [IndexComponents]. Indexes that are specialized for a
+/// single predicate additionally carry this predicate, as there might be a general index with the
+/// same [IndexComponents].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexId<TTerm> {
+    /// The components of the index.
+    components: IndexComponents,
+    /// The predicate if the index is specialized.
+    predicate: Option<TTerm>,
+}
+
+impl<TTerm> IndexId<TTerm> {
+    /// Creates a new [IndexId].
+    pub fn new(components: IndexComponents, predicate: Option<TTerm>) -> Self {
+        Self {
+            components,
+            predicate,
+        }
+    }
+
+    /// Creates a new [IndexId] for a general index with the given `components`.
+    pub fn general(components: IndexComponents) -> Self {
+        Self::new(components, None)
+    }
+
+    /// Returns the components of the index.
+    pub fn components(&self) -> IndexComponents {
+        self.components
+    }
+
+    /// Returns the predicate if the index is specialized.
+    pub fn predicate(&self) -> Option<&TTerm> {
+        self.predicate.as_ref()
+    }
+}
+
+impl<TTerm: Display> Display for IndexId<TTerm> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.components)?;
+        if let Some(predicate) = &self.predicate {
+            write!(f, " predicate={predicate}")?;
+        }
+        Ok(())
+    }
+}
+
 /// A quad that is sorted for some index.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct IndexQuad<TTerm: EncodedTerm>(pub [TTerm; 4]);
@@ -50,12 +120,61 @@ pub struct IndexQuad<TTerm: EncodedTerm>(pub [TTerm; 4]);
 /// evaluated with an SPO index. For this pattern, the query engine should use an POS or OPS index.
 ///
 /// The [IndexPermutations] allows managing multiple such indices.
+///
+/// # Specialized Indexes
+///
+/// In addition to the general indexes, the [IndexPermutations] can hold indexes that are
+/// specialized for a single predicate (see [QuadIndex::specialized_predicate]). These indexes
+/// only hold a subset of all quads and are only considered for scans that bind the predicate to
+/// exactly this term. On equal scan scores, specialized indexes are preferred, as they are smaller.
+///
+/// # Deferred Indexes
+///
+/// Building all permutations can be slow for large datasets. Therefore, general indexes can be
+/// deferred (see [Self::defer_index]). A deferred index is not maintained until a scan chooses it
+/// for the first time. Then, the index is built from the quads of another general index and
+/// maintained from there on. As the index is built while holding a shared reference, concurrent
+/// scans can continue using the other indexes.
+///
+/// # Term Frequencies
+///
+/// The [IndexPermutations] provides how many quads reference each term in each component (see
+/// [TermFrequencies]). Maintaining the frequencies would slow down every modification. Therefore,
+/// they are computed from a general index once they are requested and cached until the next
+/// modification.
+///
+/// # Cloning
+///
+/// Cloning the [IndexPermutations] is used for copy-on-write snapshots. Index implementations
+/// should share their immutable data (e.g., row groups) between clones such that cloning is cheap.
 #[derive(Debug)]
 pub struct IndexPermutations<TIndex: QuadIndex> {
     /// The [NamedGraphStorage] that is used to separately store named graphs.
     named_graphs: TIndex::NamedGraphStorage,
     /// The index variations.
     indexes: Vec<TIndex>,
+    /// The general indexes whose construction is deferred until they are first used.
+    deferred: Vec<DeferredIndex<TIndex>>,
+    /// The number of quads that reference each term. Computed on first use and reset on every
+    /// modification.
+    frequencies: OnceLock<TermFrequencies<TIndex::Term>>,
+    /// Incremented on every modification. Can be used to detect concurrent modifications.
+    version: u64,
+}
+
+impl<TIndex: QuadIndex + Clone> Clone for IndexPermutations<TIndex>
+where
+    TIndex::NamedGraphStorage: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            named_graphs: self.named_graphs.clone(),
+            indexes: self.indexes.clone(),
+            deferred: self.deferred.clone(),
+            frequencies: self.frequencies.clone(),
+            version: self.version,
+        }
+    }
 }
 
 impl<TIndex: QuadIndex> IndexPermutations<TIndex> {
@@ -64,50 +183,283 @@ impl<TIndex: QuadIndex> IndexPermutations<TIndex> {
         Self {
             named_graphs,
             indexes,
+            deferred: Vec::new(),
+            frequencies: OnceLock::new(),
+            version: 0,
         }
     }
 
-    /// Finds an index with the given `configuration`.
-    pub fn find_index(&self, configuration: IndexComponents) -> Option<&TIndex> {
+    /// Returns all indexes that have been built, including the specialized ones.
+    pub fn indexes(&self) -> impl Iterator<Item = &TIndex> {
         self.indexes
             .iter()
-            .find(|index| index.components() == configuration)
+            .chain(self.deferred.iter().filter_map(|index| index.index.get()))
+    }
+
+    /// Finds the index with the given `id`.
+    ///
+    /// If the index is deferred and has not been built yet, it is built by this call.
+    pub fn find_index(&self, id: IndexId<TIndex::Term>) -> Option<&TIndex> {
+        if let Some(index) = self.indexes.iter().find(|index| index.id() == id) {
+            return Some(index);
+        }
+
+        let deferred = self
+            .deferred
+            .iter()
+            .find(|index| index.template.id() == id)?;
+        Some(
+            deferred
+                .index
+                .get_or_init(|| self.build_index(&deferred.template)),
+        )
+    }
+
+    /// Returns whether the index with the given `id` exists and has been built.
+    pub fn is_built(&self, id: IndexId<TIndex::Term>) -> bool {
+        self.indexes().any(|index| index.id() == id)
+    }
+
+    /// Defers the general index with the given `components` (see [IndexPermutations] for
+    /// deferred indexes). The current content of the index is dropped.
+    ///
+    /// Returns false if there is no such index that has been built, or if the index is the last
+    /// general index that is not deferred.
+    pub fn defer_index(&mut self, components: IndexComponents) -> bool {
+        let general_indexes = self
+            .indexes
+            .iter()
+            .filter(|index| index.specialized_predicate().is_none())
+            .count();
+        let Some(position) = self
+            .indexes
+            .iter()
+            .position(|index| index.id() == IndexId::general(components))
+        else {
+            return false;
+        };
+        if general_indexes <= 1 {
+            return false;
+        }
+
+        let mut template = self.indexes.remove(position);
+        template.clear();
+        self.version += 1;
+        self.deferred.push(DeferredIndex {
+            template,
+            index: OnceLock::new(),
+        });
+        true
     }
 
     /// Chooses the index for scanning the given `pattern`.
     ///
-    /// This returns an [IndexComponents] that identifies the chosen index. Use
+    /// This returns an [IndexId] that identifies the chosen index. Use
     /// [MemIndexSetScanIterator] for executing the scan operation.
-    pub fn choose_index(&self, pattern: &TIndex::ScanInstructions) -> IndexComponents {
+    ///
+    /// The index whose prefix binds the most components is preferred. For example, a pattern that
+    /// only leaves the predicate unbound (`<s> ?p <o>`) uses GOSP such that only the predicates
+    /// of a single subject-object pair are scanned.
+    ///
+    /// Specialized indexes are only considered if the pattern binds the predicate to the predicate
+    /// of the index. Otherwise, the general permutations are used.
+    pub fn choose_index(
+        &self,
+        pattern: &TIndex::ScanInstructions,
+    ) -> IndexId<TIndex::Term> {
+        self.choose_index_where(pattern, |_| true)
+            .expect("At least one general index must be available")
+    }
+
+    /// Chooses the index for scanning the given `pattern` such that the results are ordered by
+    /// `order`.
+    ///
+    /// Returns [None] if no index can provide this order for the given `pattern`. In this case,
+    /// callers must fall back to [Self::choose_index] and sort the results explicitly.
+    pub fn choose_index_with_order(
+        &self,
+        pattern: &TIndex::ScanInstructions,
+        order: IndexComponent,
+    ) -> Option<IndexId<TIndex::Term>> {
+        self.choose_index_where(pattern, |index| {
+            pattern.is_ordered_by(index.components(), order)
+        })
+    }
+
+    /// Chooses the index with the best scan score among the indexes that fulfill `is_candidate`.
+    fn choose_index_where(
+        &self,
+        pattern: &TIndex::ScanInstructions,
+        is_candidate: impl Fn(&TIndex) -> bool,
+    ) -> Option<IndexId<TIndex::Term>> {
+        let bound_predicate = pattern.bound_predicate();
         self.indexes
             .iter()
+            .chain(self.deferred.iter().map(|index| &index.template))
             .rev() // Prefer SPO (max by uses the last on equality)
-            .max_by(|lhs, rhs| {
-                let lhs_pattern = pattern.reorder(lhs.components());
-                let rhs_pattern = pattern.reorder(rhs.components());
+            .filter(|index| match index.specialized_predicate() {
+                None => true,
+                Some(predicate) => bound_predicate == Some(predicate),
+            })
+            .filter(|index| is_candidate(index))
+            .max_by_key(|index| {
+                let pattern = pattern.reorder(index.components());
+                let score = index.compute_scan_score(&pattern);
+                (score, index.specialized_predicate().is_some())
+            })
+            .map(QuadIndex::id)
+    }
 
-                let lhs_score = lhs.compute_scan_score(&lhs_pattern);
-                let rhs_score = rhs.compute_scan_score(&rhs_pattern);
+    /// Adds a new index that is specialized for a single predicate.
+    ///
+    /// The index is populated with the quads that are already contained in the permutations.
+    /// Returns false if an index with the same [IndexId] already exists.
+    pub fn add_specialized_index(&mut self, mut index: TIndex) -> bool {
+        let Some(predicate) = index.specialized_predicate() else {
+            return false;
+        };
+        if self.find_index(index.id()).is_some() {
+            return false;
+        }
 
-                lhs_score.cmp(&rhs_score)
-            })
-            .expect("At least one index must be available")
-            .components()
+        let source_components = self.any_index().components();
+        let target_components = index.components();
+        let quads = self
+            .any_index()
+            .quads()
+            .into_iter()
+            .map(|quad| quad.to_encoded_quad(source_components))
+            .filter(|quad| quad.predicate == predicate)
+            .map(|quad| quad.for_index(target_components))
+            .collect::<Vec<_>>();
+        index.insert(quads);
+
+        self.version += 1;
+        self.indexes.push(index);
+        true
     }
 
     pub fn len(&self) -> usize {
         self.any_index().len()
     }
 
+    /// Returns the number of quads that reference each term.
+    ///
+    /// The frequencies are computed by scanning a general index if they have not been requested
+    /// since the last modification.
+    pub fn term_frequencies(&self) -> &TermFrequencies<TIndex::Term> {
+        self.frequencies.get_or_init(|| {
+            let index = self.any_index();
+            let components = index.components();
+            TermFrequencies::from_quads(
+                index
+                    .quads()
+                    .into_iter()
+                    .map(|quad| quad.to_encoded_quad(components)),
+            )
+        })
+    }
+
+    /// Returns the current version of the permutations. The version changes with every
+    /// modification.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the approximate number of bytes used by all indexes.
+    pub fn memory_size(&self) -> usize {
+        self.indexes().map(|index| index.memory_size()).sum()
+    }
+
+    /// Creates a compacted copy of all index permutations.
+    ///
+    /// See [QuadIndex::compacted] for further details.
+    pub fn compacted(&self) -> Self
+    where
+        TIndex::NamedGraphStorage: Clone,
+    {
+        Self {
+            named_graphs: self.named_graphs.clone(),
+            indexes: self.indexes.iter().map(QuadIndex::compacted).collect(),
+            deferred: self
+                .deferred
+                .iter()
+                .map(|index| DeferredIndex {
+                    template: index.template.compacted(),
+                    index: index
+                        .index
+                        .get()
+                        .map(|index| OnceLock::from(index.compacted()))
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            frequencies: self.frequencies.clone(),
+            version: self.version,
+        }
+    }
+
     pub fn insert(
         &mut self,
         quads: &[EncodedQuad<TIndex::Term>],
     ) -> Result<usize, StorageError> {
+        self.insert_with_progress(quads, quads.len().max(1), |_| {})
+    }
+
+    /// Inserts `quads` into one index after another. Each index receives the quads in chunks of
+    /// `chunk_size` quads and `progress` is called after each chunk. Deferred indexes that have
+    /// not been built yet are skipped.
+    ///
+    /// Returns the number of inserted quads.
+    pub fn insert_with_progress(
+        &mut self,
+        quads: &[EncodedQuad<TIndex::Term>],
+        chunk_size: usize,
+        mut progress: impl FnMut(IndexBuildProgress<TIndex::Term>),
+    ) -> Result<usize, StorageError> {
+        self.version += 1;
+        self.frequencies.take();
         let mut count = 0;
-        for index in self.indexes.iter_mut() {
+        let total_indexes = self.indexes().count();
+        for (completed_indexes, index) in self.built_indexes_mut().enumerate() {
             let components = index.components();
-            let quads = quads.iter().map(|q| q.for_index(components));
-            count = index.insert(quads);
+            let predicate = index.specialized_predicate();
+            let quads = quads
+                .iter()
+                .filter(|q| predicate.is_none_or(|predicate| q.predicate == predicate))
+                .collect::<Vec<_>>();
+
+            let mut inserted_quads = 0;
+            let mut index_count = 0;
+            for chunk in quads.chunks(chunk_size.max(1)) {
+                index_count +=
+                    index.insert(chunk.iter().map(|q| q.for_index(components)));
+                inserted_quads += chunk.len();
+                progress(IndexBuildProgress {
+                    index: index.id(),
+                    inserted_quads,
+                    total_quads: quads.len(),
+                    completed_indexes: if inserted_quads == quads.len() {
+                        completed_indexes + 1
+                    } else {
+                        completed_indexes
+                    },
+                    total_indexes,
+                });
+            }
+
+            if quads.is_empty() {
+                progress(IndexBuildProgress {
+                    index: index.id(),
+                    inserted_quads: 0,
+                    total_quads: 0,
+                    completed_indexes: completed_indexes + 1,
+                    total_indexes,
+                });
+            }
+
+            if predicate.is_none() {
+                count = index_count;
+            }
         }
 
         for quad in quads.iter().filter(|q| !q.graph_name.is_default_graph()) {
@@ -117,17 +469,38 @@ impl<TIndex: QuadIndex> IndexPermutations<TIndex> {
         Ok(count)
     }
 
+    /// Returns whether `quad` is contained in the permutations.
+    pub fn contains(&self, quad: &EncodedQuad<TIndex::Term>) -> bool {
+        let index = self.any_index();
+        index.contains(&quad.for_index(index.components()))
+    }
+
     pub fn remove(&mut self, quads: &[EncodedQuad<TIndex::Term>]) -> usize {
+        self.version += 1;
+        self.frequencies.take();
+
         let mut count = 0;
-        for index in self.indexes.iter_mut() {
+        for index in self.built_indexes_mut() {
             let components = index.components();
-            let quads = quads.iter().map(|q| q.for_index(components));
-            count = index.remove(quads);
+            match index.specialized_predicate() {
+                None => {
+                    let quads = quads.iter().map(|q| q.for_index(components));
+                    count = index.remove(quads);
+                }
+                Some(predicate) => {
+                    let quads = quads
+                        .iter()
+                        .filter(|q| q.predicate == predicate)
+                        .map(|q| q.for_index(components));
+                    index.remove(quads);
+                }
+            }
         }
         count
     }
 
     pub fn insert_named_graph(&mut self, graph_name: TIndex::Term) -> bool {
+        self.version += 1;
         self.named_graphs.insert(graph_name)
     }
 
@@ -140,13 +513,17 @@ impl<TIndex: QuadIndex> IndexPermutations<TIndex> {
     }
 
     pub fn clear(&mut self) {
-        for index in self.indexes.iter_mut() {
+        self.version += 1;
+        self.frequencies.take();
+        for index in self.built_indexes_mut() {
             index.clear();
         }
     }
 
     pub fn clear_graph(&mut self, graph_name: &TIndex::Term) {
-        for index in self.indexes.iter_mut() {
+        self.version += 1;
+        self.frequencies.take();
+        for index in self.built_indexes_mut() {
             index.clear_graph(*graph_name);
         }
     }
@@ -156,11 +533,65 @@ impl<TIndex: QuadIndex> IndexPermutations<TIndex> {
         self.named_graphs.remove(graph_name)
     }
 
+    /// Returns a general index that holds all quads.
     fn any_index(&self) -> &TIndex {
-        self.indexes.first().unwrap()
+        self.indexes
+            .iter()
+            .find(|index| index.specialized_predicate().is_none())
+            .expect("At least one general index must be available")
+    }
+
+    /// Returns mutable references to all indexes that have been built.
+    fn built_indexes_mut(&mut self) -> impl Iterator<Item = &mut TIndex> {
+        self.indexes.iter_mut().chain(
+            self.deferred
+                .iter_mut()
+                .filter_map(|index| index.index.get_mut()),
+        )
+    }
+
+    /// Builds a new index with the same configuration as the (empty) `template` from the quads of
+    /// another general index.
+    fn build_index(&self, template: &TIndex) -> TIndex {
+        let source = self.any_index();
+        let source_components = source.components();
+        let target_components = template.components();
+
+        let mut index = template.compacted();
+        index.insert(source.quads().into_iter().map(|quad| {
+            quad.to_encoded_quad(source_components)
+                .for_index(target_components)
+        }));
+        index
     }
 }
 
+/// A general index whose construction is deferred until it is first used.
+#[derive(Debug, Clone)]
+struct DeferredIndex<TIndex> {
+    /// An empty index with the configuration of the deferred index. Used for choosing indexes and
+    /// as the starting point for building the index.
+    template: TIndex,
+    /// The index, once it has been built.
+    index: OnceLock<TIndex>,
+}
+
+/// Reports the progress of inserting quads into the [IndexPermutations] (see
+/// [IndexPermutations::insert_with_progress]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexBuildProgress<TTerm> {
+    /// The index that is currently built.
+    pub index: IndexId<TTerm>,
+    /// The number of quads that have been inserted into the current index.
+    pub inserted_quads: usize,
+    /// The number of quads that are inserted into the current index.
+    pub total_quads: usize,
+    /// The number of indexes that are completely built.
+    pub completed_indexes: usize,
+    /// The number of indexes that are built.
+    pub total_indexes: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +610,16 @@ mod tests {
         assert_eq!(reordered.0, [oid(1), oid(3), oid(4), oid(2)]);
     }
 
+    #[test]
+    fn test_to_encoded_quad_gosp() {
+        let reordered = dummy_quad().for_index(IndexComponents::GOSP);
+        let quad = reordered.to_encoded_quad(IndexComponents::GOSP);
+        assert_eq!(
+            [quad.graph_name, quad.subject, quad.predicate, quad.object],
+            [oid(1), oid(2), oid(3), oid(4)]
+        );
+    }
+
     fn dummy_quad() -> EncodedQuad<MyId> {
         EncodedQuad {
             graph_name: MyId(1),
@@ -10,3 +10,5 @@
 
 pub(crate) mod index;
 pub mod memory;
+#[cfg(feature = "rocksdb")]
+pub mod rocksdb;
@@ -1,11 +1,14 @@
 //! This module exposes the in-memory store from Oxigraph as table in RdfFusion.
 mod encoding;
-mod object_id;
+pub(crate) mod object_id;
 mod object_id_mapping;
 mod persistence;
 mod planner;
 mod storage;
 
+pub use object_id::ObjectIdWidth;
 pub use object_id_mapping::MemObjectIdMapping;
 pub use persistence::*;
-pub use storage::MemQuadStorage;
+pub use storage::{
+    BulkLoadProgress, MemQuadStorage, NamedGraphStorageKind, ScanOrderHint,
+};
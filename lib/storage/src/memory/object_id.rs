@@ -1,16 +1,23 @@
 #![allow(clippy::unreadable_literal)]
 
 use crate::index::EncodedTerm;
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, UInt32Type, UInt64Type};
+use datafusion::arrow::error::ArrowError;
+use datafusion::common::ScalarValue;
 use datafusion::parquet::data_type::AsBytes;
-use rdf_fusion_encoding::object_id::ObjectId;
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdSize};
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
+use std::sync::Arc;
 use thiserror::Error;
 
-const SIZE: u8 = 4;
-
 /// The encoded object id represents an [ObjectId] in the storage layer.
 ///
+/// The value of an encoded object id is independent of the [ObjectIdWidth] of the storage. The
+/// width only determines how many bytes are used for representing the id in Arrow arrays and
+/// which ids can be allocated by the [MemObjectIdMapping](crate::memory::MemObjectIdMapping).
+///
 /// # Default Graph
 ///
 /// The default graph is represented by the [DEFAULT_GRAPH_ID]. Use [EncodedGraphObjectId] to
@@ -18,19 +25,24 @@ const SIZE: u8 = 4;
 /// the system assumes that the id cannot represent the default graph and errors may be thrown
 /// during decoding.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
-pub struct EncodedObjectId(u32);
+pub struct EncodedObjectId(u64);
 
 impl EncodedObjectId {
-    pub const SIZE: u8 = 4;
-    pub const SIZE_I32: i32 = SIZE as i32;
     pub const MIN: EncodedObjectId = EncodedObjectId(0);
-    pub const MAX: EncodedObjectId = EncodedObjectId(u32::MAX);
+    pub const MAX: EncodedObjectId = EncodedObjectId(u64::MAX);
 
-    pub fn as_object_id(&self) -> ObjectId {
-        ObjectId::try_new(self.0.to_be_bytes()).expect("Object ID valid")
+    /// Returns the [ObjectId] of this id with the given `width`.
+    ///
+    /// Returns [None] if the id does not fit into `width`.
+    pub fn as_object_id(&self, width: ObjectIdWidth) -> Option<ObjectId> {
+        let bytes = match width {
+            ObjectIdWidth::U32 => u32::try_from(self.0).ok()?.to_be_bytes().to_vec(),
+            ObjectIdWidth::U64 => self.0.to_be_bytes().to_vec(),
+        };
+        Some(ObjectId::try_new(bytes).expect("Object ID valid"))
     }
 
-    pub fn as_u32(&self) -> u32 {
+    pub fn as_u64(&self) -> u64 {
         self.0
     }
 
@@ -51,6 +63,12 @@ impl EncodedTerm for EncodedObjectId {
 
 impl From<u32> for EncodedObjectId {
     fn from(value: u32) -> Self {
+        Self(u64::from(value))
+    }
+}
+
+impl From<u64> for EncodedObjectId {
+    fn from(value: u64) -> Self {
         Self(value)
     }
 }
@@ -68,11 +86,166 @@ pub struct InvalidObjectIdError;
 impl TryFrom<&[u8]> for EncodedObjectId {
     type Error = InvalidObjectIdError;
 
+    /// Converts the native-endian bytes of a 4-byte or 8-byte id.
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        TryInto::<[u8; 4]>::try_into(value.as_bytes())
-            .map(u32::from_ne_bytes)
-            .map(Self)
-            .map_err(|_| InvalidObjectIdError)
+        let value = value.as_bytes();
+        match value.len() {
+            4 => Ok(Self::from(u32::from_ne_bytes(
+                value.try_into().map_err(|_| InvalidObjectIdError)?,
+            ))),
+            8 => Ok(Self(u64::from_ne_bytes(
+                value.try_into().map_err(|_| InvalidObjectIdError)?,
+            ))),
+            _ => Err(InvalidObjectIdError),
+        }
+    }
+}
+
+impl TryFrom<&ObjectId> for EncodedObjectId {
+    type Error = InvalidObjectIdError;
+
+    /// Converts an [ObjectId] that has been created by [EncodedObjectId::as_object_id].
+    fn try_from(value: &ObjectId) -> Result<Self, Self::Error> {
+        let value = value.as_bytes();
+        match value.len() {
+            4 => Ok(Self::from(u32::from_be_bytes(
+                value.try_into().map_err(|_| InvalidObjectIdError)?,
+            ))),
+            8 => Ok(Self(u64::from_be_bytes(
+                value.try_into().map_err(|_| InvalidObjectIdError)?,
+            ))),
+            _ => Err(InvalidObjectIdError),
+        }
+    }
+}
+
+/// The number of bytes that are used for representing an [EncodedObjectId] in Arrow arrays.
+///
+/// 4-byte ids are represented as `UInt32` arrays, while 8-byte ids are represented as `UInt64`
+/// arrays. The smaller width reduces the memory footprint of the indexes but limits the number of
+/// distinct terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObjectIdWidth {
+    /// 4-byte object ids.
+    #[default]
+    U32,
+    /// 8-byte object ids.
+    U64,
+}
+
+impl ObjectIdWidth {
+    /// Returns the [ObjectIdSize] of this width.
+    pub fn object_id_size(self) -> ObjectIdSize {
+        let size = match self {
+            ObjectIdWidth::U32 => 4,
+            ObjectIdWidth::U64 => 8,
+        };
+        ObjectIdSize::try_from(size).expect("Size is valid")
+    }
+
+    /// Returns the Arrow [DataType] of the ids.
+    pub fn data_type(self) -> DataType {
+        match self {
+            ObjectIdWidth::U32 => DataType::UInt32,
+            ObjectIdWidth::U64 => DataType::UInt64,
+        }
+    }
+
+    /// Returns the largest id that can be represented with this width.
+    pub fn max(self) -> EncodedObjectId {
+        match self {
+            ObjectIdWidth::U32 => EncodedObjectId::from(u32::MAX),
+            ObjectIdWidth::U64 => EncodedObjectId::MAX,
+        }
+    }
+
+    /// Returns a [ScalarValue] that holds `object_id`.
+    ///
+    /// Returns [None] if `object_id` does not fit into this width.
+    pub fn scalar_value(self, object_id: EncodedObjectId) -> Option<ScalarValue> {
+        match self {
+            ObjectIdWidth::U32 => u32::try_from(object_id.0)
+                .ok()
+                .map(|id| ScalarValue::UInt32(Some(id))),
+            ObjectIdWidth::U64 => Some(ScalarValue::UInt64(Some(object_id.0))),
+        }
+    }
+
+    /// Creates an array that holds the given `object_ids`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an id does not fit into this width. The
+    /// [MemObjectIdMapping](crate::memory::MemObjectIdMapping) never allocates such ids.
+    pub fn create_array(
+        self,
+        object_ids: impl IntoIterator<Item = Option<EncodedObjectId>>,
+    ) -> ArrayRef {
+        match self {
+            ObjectIdWidth::U32 => Arc::new(
+                object_ids
+                    .into_iter()
+                    .map(|id| {
+                        id.map(|id| {
+                            u32::try_from(id.0).expect("Object id exceeds the width.")
+                        })
+                    })
+                    .collect::<UInt32Array>(),
+            ),
+            ObjectIdWidth::U64 => Arc::new(
+                object_ids
+                    .into_iter()
+                    .map(|id| id.map(|id| id.0))
+                    .collect::<UInt64Array>(),
+            ),
+        }
+    }
+}
+
+impl TryFrom<ObjectIdSize> for ObjectIdWidth {
+    type Error = InvalidObjectIdError;
+
+    fn try_from(value: ObjectIdSize) -> Result<Self, Self::Error> {
+        match i32::from(value) {
+            4 => Ok(ObjectIdWidth::U32),
+            8 => Ok(ObjectIdWidth::U64),
+            _ => Err(InvalidObjectIdError),
+        }
+    }
+}
+
+impl TryFrom<&DataType> for ObjectIdWidth {
+    type Error = InvalidObjectIdError;
+
+    fn try_from(value: &DataType) -> Result<Self, Self::Error> {
+        match value {
+            DataType::UInt32 => Ok(ObjectIdWidth::U32),
+            DataType::UInt64 => Ok(ObjectIdWidth::U64),
+            _ => Err(InvalidObjectIdError),
+        }
+    }
+}
+
+/// Returns the object ids of an `UInt32` or `UInt64` `array`.
+///
+/// Nulls are returned as [None].
+pub fn object_ids_of_array(
+    array: &dyn Array,
+) -> Result<Vec<Option<EncodedObjectId>>, ArrowError> {
+    match array.data_type() {
+        DataType::UInt32 => Ok(array
+            .as_primitive::<UInt32Type>()
+            .iter()
+            .map(|id| id.map(EncodedObjectId::from))
+            .collect()),
+        DataType::UInt64 => Ok(array
+            .as_primitive::<UInt64Type>()
+            .iter()
+            .map(|id| id.map(EncodedObjectId::from))
+            .collect()),
+        data_type => Err(ArrowError::InvalidArgumentError(format!(
+            "Expected an array of object ids, got {data_type}."
+        ))),
     }
 }
 
@@ -128,11 +301,45 @@ impl From<EncodedObjectId> for EncodedGraphObjectId {
 mod tests {
     use super::*;
 
+    #[test]
+    fn object_id_roundtrip() {
+        let id = EncodedObjectId::from(0x0102_0304u32);
+        for width in [ObjectIdWidth::U32, ObjectIdWidth::U64] {
+            let object_id = id.as_object_id(width).unwrap();
+            assert_eq!(object_id.size(), i32::from(width.object_id_size()));
+            assert_eq!(EncodedObjectId::try_from(&object_id).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn object_id_larger_than_width() {
+        let id = EncodedObjectId::from(u64::from(u32::MAX) + 1);
+        assert!(id.as_object_id(ObjectIdWidth::U32).is_none());
+        assert!(ObjectIdWidth::U32.scalar_value(id).is_none());
+        assert_eq!(
+            ObjectIdWidth::U64.scalar_value(id),
+            Some(ScalarValue::UInt64(Some(u64::from(u32::MAX) + 1)))
+        );
+    }
+
+    #[test]
+    fn object_ids_of_array_roundtrip() {
+        let ids = vec![Some(EncodedObjectId::from(1u32)), None];
+        for width in [ObjectIdWidth::U32, ObjectIdWidth::U64] {
+            let array = width.create_array(ids.clone());
+            assert_eq!(array.data_type(), &width.data_type());
+            assert_eq!(object_ids_of_array(array.as_ref()).unwrap(), ids);
+        }
+    }
+
     #[test]
     fn test_from_byte_slice_success() {
         let array: [u8; 4] = [0x56, 0x78, 0x9A, 0xBC];
         let id = EncodedObjectId::try_from(array.as_slice()).unwrap();
-        assert_eq!(id.0.to_ne_bytes(), [0x56, 0x78, 0x9A, 0xBC]);
+        assert_eq!(
+            u32::try_from(id.0).unwrap().to_ne_bytes(),
+            [0x56, 0x78, 0x9A, 0xBC]
+        );
     }
 
     #[test]
@@ -2,9 +2,14 @@
 
 use crate::index::EncodedQuad;
 use crate::memory::encoding::{EncodedTerm, EncodedTypedValue};
-use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedGraphObjectId, EncodedObjectId};
+use crate::memory::object_id::{
+    DEFAULT_GRAPH_ID, EncodedGraphObjectId, EncodedObjectId, ObjectIdWidth,
+    object_ids_of_array,
+};
+use dashmap::mapref::entry::Entry;
 use dashmap::{DashMap, DashSet};
-use datafusion::arrow::array::{UInt32Array, UInt32Builder};
+use datafusion::arrow::array::{Array, ArrayRef};
+use itertools::Itertools;
 use rdf_fusion_encoding::TermDecoder;
 use rdf_fusion_encoding::object_id::{
     ObjectId, ObjectIdMapping, ObjectIdMappingError, ObjectIdSize,
@@ -24,7 +29,7 @@ use rdf_fusion_model::{
 use rustc_hash::FxHasher;
 use std::hash::BuildHasherDefault;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Maintains a mapping between RDF terms and object IDs in memory.
 ///
@@ -33,8 +38,11 @@ use std::sync::atomic::{AtomicU32, Ordering};
 ///
 /// # Object IDs
 ///
-/// The encoded Object ID is a 32-bit unsigned integer used to uniquely identify RDF terms.
-/// Currently, we simply use a counter to allocate new object IDs.
+/// The encoded Object ID is an unsigned integer used to uniquely identify RDF terms. Currently, we
+/// simply use a counter to allocate new object IDs. The [ObjectIdWidth] of the mapping determines
+/// whether 4-byte or 8-byte ids are used and is reported as the
+/// [ObjectIdSize](rdf_fusion_encoding::object_id::ObjectIdSize) of the mapping. Once all ids of
+/// the width have been allocated, encoding new terms fails.
 ///
 /// # Typed Values
 ///
@@ -42,8 +50,10 @@ use std::sync::atomic::{AtomicU32, Ordering};
 /// speed-up queries working on typed values.
 #[derive(Debug)]
 pub struct MemObjectIdMapping {
+    /// The width of the object ids.
+    width: ObjectIdWidth,
     /// Contains the next free object id.
-    next_id: AtomicU32,
+    next_id: AtomicU64,
     /// A set for interning strings.
     str_interning: DashSet<Arc<str>>,
     /// Maps object ids to the encoded terms & typed values.
@@ -66,52 +76,61 @@ impl Default for MemObjectIdMapping {
 }
 
 impl MemObjectIdMapping {
-    /// Creates a new empty [MemObjectIdMapping].
-    ///
-    /// The given encodings are used for creating the outputs of the mapping.
+    /// Creates a new empty [MemObjectIdMapping] with 4-byte object ids.
     pub fn new() -> Self {
+        Self::new_with_width(ObjectIdWidth::U32)
+    }
+
+    /// Creates a new empty [MemObjectIdMapping] whose object ids have the given `width`.
+    pub fn new_with_width(width: ObjectIdWidth) -> Self {
         Self {
-            next_id: AtomicU32::new(1), // Start at 1 to account for Default Graph.
+            width,
+            next_id: AtomicU64::new(1), // Start at 1 to account for Default Graph.
             str_interning: DashSet::new(),
             id2term: DashMap::with_hasher(BuildHasherDefault::default()),
             term2id: DashMap::with_hasher(BuildHasherDefault::default()),
         }
     }
 
-    pub(super) fn encode_graph_name_intern(
+    /// Returns the width of the object ids.
+    pub fn width(&self) -> ObjectIdWidth {
+        self.width
+    }
+
+    pub(crate) fn encode_graph_name_intern(
         &self,
         scalar: GraphNameRef<'_>,
-    ) -> EncodedGraphObjectId {
+    ) -> Result<EncodedGraphObjectId, ObjectIdMappingError> {
         match scalar {
             GraphNameRef::NamedNode(nn) => {
-                EncodedGraphObjectId(self.encode_term_intern(nn))
+                Ok(EncodedGraphObjectId(self.encode_term_intern(nn)?))
             }
             GraphNameRef::BlankNode(bnode) => {
-                EncodedGraphObjectId(self.encode_term_intern(bnode))
+                Ok(EncodedGraphObjectId(self.encode_term_intern(bnode)?))
             }
-            GraphNameRef::DefaultGraph => DEFAULT_GRAPH_ID,
+            GraphNameRef::DefaultGraph => Ok(DEFAULT_GRAPH_ID),
         }
     }
 
-    pub(super) fn encode_term_intern<'term>(
+    pub(crate) fn encode_term_intern<'term>(
         &self,
         scalar: impl Into<TermRef<'term>>,
-    ) -> EncodedObjectId {
+    ) -> Result<EncodedObjectId, ObjectIdMappingError> {
         let scalar = scalar.into();
         let term = self.obtain_encoded_term(scalar);
         self.obtain_object_id(&term)
     }
 
     /// Encodes the entire `quad`.
-    pub(super) fn encode_quad(
+    pub(crate) fn encode_quad(
         &self,
         quad: QuadRef<'_>,
     ) -> DFResult<EncodedQuad<EncodedObjectId>> {
         Ok(EncodedQuad {
-            graph_name: self.encode_graph_name_intern(quad.graph_name).0,
-            subject: self.encode_term_intern(quad.subject),
-            predicate: self.encode_term_intern(quad.predicate),
-            object: self.encode_term_intern(quad.object),
+            graph_name: self.encode_graph_name_intern(quad.graph_name)?.0,
+            subject: self.encode_term_intern(quad.subject)?,
+            predicate: self.encode_term_intern(quad.predicate)?,
+            object: self.encode_term_intern(quad.object)?,
         })
     }
 
@@ -120,7 +139,7 @@ impl MemObjectIdMapping {
     /// # Error
     ///
     /// Returns an error if the object id is unknown.
-    pub(super) fn decode_term(
+    pub(crate) fn decode_term(
         &self,
         object_id: EncodedObjectId,
     ) -> Result<Term, ObjectIdMappingError> {
@@ -136,7 +155,7 @@ impl MemObjectIdMapping {
     ///
     /// Returns an error if the object id is unknown or if `object_id` referred to the default
     /// graph or a literal.
-    pub(super) fn decode_named_graph(
+    pub(crate) fn decode_named_graph(
         &self,
         term: EncodedObjectId,
     ) -> Result<NamedOrBlankNode, ObjectIdMappingError> {
@@ -208,7 +227,7 @@ impl MemObjectIdMapping {
         }
     }
 
-    pub(super) fn try_get_encoded_object_id_from_term<'term>(
+    pub(crate) fn try_get_encoded_object_id_from_term<'term>(
         &self,
         encoded_term: impl Into<TermRef<'term>>,
     ) -> Option<EncodedObjectId> {
@@ -217,7 +236,7 @@ impl MemObjectIdMapping {
             .and_then(|term| self.try_get_encoded_object_id(&term))
     }
 
-    pub(super) fn try_get_encoded_object_id_from_graph_name(
+    pub(crate) fn try_get_encoded_object_id_from_graph_name(
         &self,
         encoded_term: GraphNameRef<'_>,
     ) -> Option<EncodedGraphObjectId> {
@@ -259,22 +278,81 @@ impl MemObjectIdMapping {
         })
     }
 
-    pub(super) fn obtain_object_id(&self, encoded_term: &EncodedTerm) -> EncodedObjectId {
-        let found = self.term2id.get(encoded_term);
-        match found {
-            None => {
-                let next_id = self.next_id.fetch_add(1, Ordering::Relaxed);
-                let object_id = EncodedObjectId::from(next_id);
+    /// Returns the object id of `encoded_term`, allocating a new one if the term is unknown.
+    ///
+    /// The term is interned while holding the lock of its shard in `term2id`. Hence, threads that
+    /// concurrently intern the same term obtain the same object id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the term is unknown and all object ids of the width have been allocated.
+    pub(super) fn obtain_object_id(
+        &self,
+        encoded_term: &EncodedTerm,
+    ) -> Result<EncodedObjectId, ObjectIdMappingError> {
+        if let Some(entry) = self.term2id.get(encoded_term) {
+            return Ok(*entry);
+        }
+
+        match self.term2id.entry(encoded_term.clone()) {
+            Entry::Occupied(entry) => Ok(*entry.get()),
+            Entry::Vacant(entry) => {
+                let object_id =
+                    EncodedObjectId::from(self.next_id.fetch_add(1, Ordering::Relaxed));
+                if object_id > self.width.max() {
+                    return Err(ObjectIdMappingError::ObjectIdsExhausted);
+                }
+
                 let encoded_typed_value = EncodedTypedValue::from(encoded_term);
                 self.id2term
                     .insert(object_id, (encoded_term.clone(), encoded_typed_value));
-                self.term2id.insert(encoded_term.clone(), object_id);
-                object_id
+                Ok(*entry.insert(object_id))
             }
-            Some(entry) => *entry,
         }
     }
 
+    /// Returns all object ids of the mapping in ascending order.
+    pub(crate) fn object_ids(&self) -> Vec<EncodedObjectId> {
+        let mut object_ids = self.id2term.iter().map(|entry| *entry.key()).collect_vec();
+        object_ids.sort_unstable();
+        object_ids
+    }
+
+    /// Registers `term` with the given `object_id`.
+    ///
+    /// This is used for restoring a persisted mapping such that the object ids remain stable.
+    /// Object ids that are allocated afterward are larger than `object_id`.
+    ///
+    /// Returns false if `object_id` or `term` is already mapped to a different value or if
+    /// `object_id` does not fit into the width of the mapping.
+    pub(crate) fn insert_with_object_id(
+        &self,
+        object_id: EncodedObjectId,
+        term: TermRef<'_>,
+    ) -> bool {
+        if object_id == DEFAULT_GRAPH_ID.0 || object_id > self.width.max() {
+            return false;
+        }
+
+        let encoded_term = self.obtain_encoded_term(term);
+        match (
+            self.try_get_encoded_object_id(&encoded_term),
+            self.try_get_encoded_term_from_object_id(object_id),
+        ) {
+            (Some(existing), _) => return existing == object_id,
+            (None, Some(_)) => return false,
+            (None, None) => {}
+        }
+
+        let encoded_typed_value = EncodedTypedValue::from(&encoded_term);
+        self.id2term
+            .insert(object_id, (encoded_term.clone(), encoded_typed_value));
+        self.term2id.insert(encoded_term, object_id);
+        self.next_id
+            .fetch_max(object_id.as_u64().saturating_add(1), Ordering::Relaxed);
+        true
+    }
+
     fn intern_str(&self, value: &str) -> Arc<str> {
         let found = self.str_interning.get(value);
         match found {
@@ -290,7 +368,7 @@ impl MemObjectIdMapping {
 
 impl ObjectIdMapping for MemObjectIdMapping {
     fn object_id_size(&self) -> ObjectIdSize {
-        ObjectIdSize::try_from(4).expect("4 is valid")
+        self.width.object_id_size()
     }
 
     fn try_get_object_id(
@@ -302,38 +380,35 @@ impl ObjectIdMapping for MemObjectIdMapping {
             .ok()
             .and_then(|term| self.try_get_encoded_term(term))
             .and_then(|term| self.try_get_encoded_object_id(&term))
-            .map(|oid| oid.as_object_id());
+            .and_then(|oid| oid.as_object_id(self.width));
         Ok(result)
     }
 
     fn encode_array(
         &self,
         array: &PlainTermArray,
-    ) -> Result<UInt32Array, ObjectIdMappingError> {
+    ) -> Result<ArrayRef, ObjectIdMappingError> {
         let terms = DefaultPlainTermDecoder::decode_terms(array);
 
         // TODO: without alloc/Arc copy
-        let mut result = UInt32Builder::new();
-        for term in terms {
-            match term {
+        let object_ids = terms
+            .map(|term| match term {
                 Ok(term) => {
                     let encoded_term = self.obtain_encoded_term(term);
-                    let object_id = self.obtain_object_id(&encoded_term);
-                    result.append_value(object_id.as_u32())
+                    self.obtain_object_id(&encoded_term).map(Some)
                 }
-                Err(_) => result.append_null(),
-            }
-        }
+                Err(_) => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(result.finish())
+        Ok(self.width.create_array(object_ids))
     }
 
     fn decode_array(
         &self,
-        array: &UInt32Array,
+        array: &dyn Array,
     ) -> Result<PlainTermArray, ObjectIdMappingError> {
-        let terms = array.iter().map(|oid| {
-            let oid = oid.map(EncodedObjectId::from);
+        let terms = object_ids_of_array(array)?.into_iter().map(|oid| {
             oid.map(|oid| {
                 self.try_get_encoded_term_from_object_id(oid)
                     .expect("Missing EncodedObjectId")
@@ -376,10 +451,9 @@ impl ObjectIdMapping for MemObjectIdMapping {
     fn decode_array_to_typed_value(
         &self,
         encoding: &TypedValueEncodingRef,
-        array: &UInt32Array,
+        array: &dyn Array,
     ) -> Result<TypedValueArray, ObjectIdMappingError> {
-        let typed_values = array.iter().map(|oid| {
-            let oid = oid.map(EncodedObjectId::from);
+        let typed_values = object_ids_of_array(array)?.into_iter().map(|oid| {
             oid.map(|oid| {
                 self.try_get_encoded_typed_value_from_object_id(oid)
                     .expect("Missing EncodedObjectId")
@@ -406,6 +480,7 @@ impl ObjectIdMapping for MemObjectIdMapping {
 mod tests {
     use super::*;
     use datafusion::arrow::array::AsArray;
+    use datafusion::arrow::datatypes::{DataType, UInt32Type};
     use rdf_fusion_encoding::EncodingArray;
     use rdf_fusion_encoding::plain_term::PlainTermArrayElementBuilder;
     use rdf_fusion_model::vocab::xsd;
@@ -426,7 +501,7 @@ mod tests {
         let plain_term_array = builder.finish();
 
         let object_id_array = mapping.encode_array(&plain_term_array)?;
-        let decoded_plain_term_array = mapping.decode_array(&object_id_array)?;
+        let decoded_plain_term_array = mapping.decode_array(object_id_array.as_ref())?;
 
         assert_eq!(
             plain_term_array.array().len(),
@@ -455,6 +530,7 @@ mod tests {
         let plain_term_array = builder.finish();
 
         let object_id_array = mapping.encode_array(&plain_term_array)?;
+        let object_id_array = object_id_array.as_primitive::<UInt32Type>();
 
         let id1 = object_id_array.value(0);
         let id2 = object_id_array.value(1);
@@ -469,6 +545,7 @@ mod tests {
         builder2.append_named_node(nn1);
         let plain_term_array2 = builder2.finish();
         let object_id_array2 = mapping.encode_array(&plain_term_array2)?;
+        let object_id_array2 = object_id_array2.as_primitive::<UInt32Type>();
 
         let id4 = object_id_array2.value(0);
         let id5 = object_id_array2.value(1);
@@ -499,6 +576,7 @@ mod tests {
         builder.append_blank_node(BlankNodeRef::new_unchecked("b1"));
         let plain_term_array = builder.finish();
         let object_id_array = mapping.encode_array(&plain_term_array)?;
+        let object_id_array = object_id_array.as_primitive::<UInt32Type>();
 
         // After encoding, should be Some
         let object_id1 = mapping.try_get_object_id(&term1)?;
@@ -524,4 +602,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_encode_decode_roundtrip_with_8_byte_ids() -> DFResult<()> {
+        let mapping = MemObjectIdMapping::new_with_width(ObjectIdWidth::U64);
+        assert_eq!(i32::from(mapping.object_id_size()), 8);
+
+        let mut builder = PlainTermArrayElementBuilder::new(2);
+        builder.append_named_node(NamedNodeRef::new_unchecked("http://example.com/a"));
+        builder.append_null();
+        let plain_term_array = builder.finish();
+
+        let object_id_array = mapping.encode_array(&plain_term_array)?;
+        assert_eq!(object_id_array.data_type(), &DataType::UInt64);
+
+        let decoded_plain_term_array = mapping.decode_array(object_id_array.as_ref())?;
+        assert_eq!(
+            plain_term_array.array().as_struct(),
+            decoded_plain_term_array.array().as_struct()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocating_beyond_width_fails() {
+        let max_id = EncodedObjectId::from(u32::MAX);
+        let term = NamedNodeRef::new_unchecked("http://example.com/a");
+        let other_term = NamedNodeRef::new_unchecked("http://example.com/b");
+
+        let mapping = MemObjectIdMapping::new();
+        assert!(mapping.insert_with_object_id(max_id, term.into()));
+        assert!(matches!(
+            mapping.encode_term_intern(other_term),
+            Err(ObjectIdMappingError::ObjectIdsExhausted)
+        ));
+        assert!(!mapping.insert_with_object_id(
+            EncodedObjectId::from(u64::from(u32::MAX) + 1),
+            other_term.into()
+        ));
+
+        let mapping = MemObjectIdMapping::new_with_width(ObjectIdWidth::U64);
+        assert!(mapping.insert_with_object_id(max_id, term.into()));
+        assert_eq!(
+            mapping.encode_term_intern(other_term).unwrap(),
+            EncodedObjectId::from(u64::from(u32::MAX) + 1)
+        );
+    }
 }
@@ -0,0 +1,265 @@
+use crate::index::EncodedQuad;
+use crate::memory::MemObjectIdMapping;
+use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedObjectId, object_ids_of_array};
+use crate::memory::persistence::MemStoragePersistenceError;
+use crate::memory::storage::MemQuadStorageSnapshot;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
+use datafusion::common::DataFusionError;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet};
+use futures::StreamExt;
+use rdf_fusion_encoding::object_id::ObjectIdMapping;
+use rdf_fusion_encoding::plain_term::decoders::DefaultPlainTermDecoder;
+use rdf_fusion_encoding::plain_term::{PlainTermArray, PlainTermEncoding};
+use rdf_fusion_encoding::{EncodingArray, TermDecoder};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The file that holds the mapping between object ids and terms.
+const TERMS_FILE: &str = "terms.parquet";
+/// The file that holds the quads as object ids.
+const QUADS_FILE: &str = "quads.parquet";
+/// The file that holds the object ids of the named graphs.
+const NAMED_GRAPHS_FILE: &str = "named_graphs.parquet";
+
+/// The number of terms that are written in a single record batch.
+const TERMS_BATCH_SIZE: usize = 8192;
+
+/// The content of a dump that has been read with [read_parquet_dump].
+pub(crate) struct ParquetDump {
+    /// The persisted quads.
+    pub quads: Vec<EncodedQuad<EncodedObjectId>>,
+    /// The persisted named graphs.
+    pub named_graphs: Vec<EncodedObjectId>,
+}
+
+/// Writes the terms of `mapping` and the quads and named graphs of `snapshot` into the directory
+/// at `path`.
+///
+/// The quads are written as object ids. Together with the persisted terms, this allows restoring
+/// a storage with the same object ids.
+pub(crate) async fn write_parquet_dump(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+    snapshot: &MemQuadStorageSnapshot,
+) -> Result<(), MemStoragePersistenceError> {
+    std::fs::create_dir_all(path)?;
+    write_terms(&path.join(TERMS_FILE), mapping)?;
+    write_quads(&path.join(QUADS_FILE), snapshot).await?;
+    write_named_graphs(&path.join(NAMED_GRAPHS_FILE), mapping, snapshot)?;
+    Ok(())
+}
+
+/// Reads a dump that has been written with [write_parquet_dump] from the directory at `path`.
+///
+/// The persisted terms are registered in `mapping` with their original object ids. The returned
+/// quads and named graphs refer to these object ids.
+pub(crate) fn read_parquet_dump(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+) -> Result<ParquetDump, MemStoragePersistenceError> {
+    read_terms(&path.join(TERMS_FILE), mapping)?;
+    let quads = read_quads(&path.join(QUADS_FILE), mapping)?;
+    let named_graphs = read_named_graphs(&path.join(NAMED_GRAPHS_FILE), mapping)?;
+    Ok(ParquetDump {
+        quads,
+        named_graphs,
+    })
+}
+
+/// Writes all object ids of `mapping` together with their term in the plain term encoding.
+fn write_terms(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+) -> Result<(), MemStoragePersistenceError> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("object_id", mapping.width().data_type(), false),
+        Field::new("term", PlainTermEncoding::data_type(), false),
+    ]));
+
+    let mut writer =
+        ArrowWriter::try_new(File::create(path)?, Arc::clone(&schema), None)?;
+    for chunk in mapping.object_ids().chunks(TERMS_BATCH_SIZE) {
+        let object_ids = mapping
+            .width()
+            .create_array(chunk.iter().copied().map(Some));
+        let terms = mapping
+            .decode_array(object_ids.as_ref())
+            .map_err(DataFusionError::from)?;
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![object_ids, terms.into_array_ref()],
+        )?;
+        writer.write(&batch)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Writes all quads of `snapshot` as object ids.
+async fn write_quads(
+    path: &Path,
+    snapshot: &MemQuadStorageSnapshot,
+) -> Result<(), MemStoragePersistenceError> {
+    let execution_plan_metrics = ExecutionPlanMetricsSet::new();
+    let baseline_metrics = BaselineMetrics::new(&execution_plan_metrics, 0);
+    let mut quads = snapshot
+        .stream_quads()
+        .await?
+        .create_stream(baseline_metrics);
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, quads.schema(), None)?;
+    while let Some(batch) = quads.next().await {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Writes the object ids of the named graphs in `snapshot`.
+fn write_named_graphs(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+    snapshot: &MemQuadStorageSnapshot,
+) -> Result<(), MemStoragePersistenceError> {
+    let schema: SchemaRef = Arc::new(Schema::new(vec![Field::new(
+        "graph",
+        mapping.width().data_type(),
+        false,
+    )]));
+    let named_graphs = mapping
+        .width()
+        .create_array(snapshot.encoded_named_graphs().iter().copied().map(Some));
+    let batch = RecordBatch::try_new(Arc::clone(&schema), vec![named_graphs])?;
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Registers the persisted terms with their object ids in `mapping`.
+fn read_terms(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+) -> Result<(), MemStoragePersistenceError> {
+    for batch in read_batches(path)? {
+        let batch = batch?;
+        let object_ids = object_id_column(&batch, 0)?;
+        let terms = PlainTermArray::try_from(Arc::clone(batch.column(1)))?;
+
+        let terms = DefaultPlainTermDecoder::decode_terms(&terms);
+        for (object_id, term) in object_ids.into_iter().zip(terms) {
+            let (Some(object_id), Ok(term)) = (object_id, term) else {
+                return Err(invalid_data("Every object id must have a term."));
+            };
+
+            if !mapping.insert_with_object_id(object_id, term) {
+                return Err(MemStoragePersistenceError::InvalidMemQuadStorage(format!(
+                    "Object id {object_id} or term {term} is already mapped differently."
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the persisted quads. All object ids must be known to `mapping`.
+fn read_quads(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+) -> Result<Vec<EncodedQuad<EncodedObjectId>>, MemStoragePersistenceError> {
+    let mut result = Vec::new();
+    for batch in read_batches(path)? {
+        let batch = batch?;
+        let graph_names = object_id_column(&batch, 0)?;
+        let subjects = object_id_column(&batch, 1)?;
+        let predicates = object_id_column(&batch, 2)?;
+        let objects = object_id_column(&batch, 3)?;
+
+        for i in 0..batch.num_rows() {
+            // The default graph is represented as null in the quad stream.
+            let graph_name = match graph_names[i] {
+                None => DEFAULT_GRAPH_ID.0,
+                Some(_) => known_object_id(mapping, &graph_names, i)?,
+            };
+            result.push(EncodedQuad {
+                graph_name,
+                subject: known_object_id(mapping, &subjects, i)?,
+                predicate: known_object_id(mapping, &predicates, i)?,
+                object: known_object_id(mapping, &objects, i)?,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Reads the object ids of the persisted named graphs.
+fn read_named_graphs(
+    path: &Path,
+    mapping: &MemObjectIdMapping,
+) -> Result<Vec<EncodedObjectId>, MemStoragePersistenceError> {
+    let mut result = Vec::new();
+    for batch in read_batches(path)? {
+        let batch = batch?;
+        let named_graphs = object_id_column(&batch, 0)?;
+        for i in 0..batch.num_rows() {
+            result.push(known_object_id(mapping, &named_graphs, i)?);
+        }
+    }
+    Ok(result)
+}
+
+/// Returns an iterator over the record batches of the Parquet file at `path`.
+fn read_batches(
+    path: &Path,
+) -> Result<
+    impl Iterator<Item = Result<RecordBatch, datafusion::arrow::error::ArrowError>>,
+    MemStoragePersistenceError,
+> {
+    Ok(ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?)
+}
+
+/// Returns the column at `index` of `batch` as object ids.
+///
+/// Both 4-byte and 8-byte object ids are accepted. Ids that do not fit into the width of the
+/// mapping are unknown to the mapping and rejected while reading.
+fn object_id_column(
+    batch: &RecordBatch,
+    index: usize,
+) -> Result<Vec<Option<EncodedObjectId>>, MemStoragePersistenceError> {
+    batch
+        .columns()
+        .get(index)
+        .and_then(|column| object_ids_of_array(column.as_ref()).ok())
+        .ok_or_else(|| invalid_data("Expected a column with object ids."))
+}
+
+/// Returns the object id at `index` of `object_ids` if it is known to `mapping`.
+fn known_object_id(
+    mapping: &MemObjectIdMapping,
+    object_ids: &[Option<EncodedObjectId>],
+    index: usize,
+) -> Result<EncodedObjectId, MemStoragePersistenceError> {
+    let Some(object_id) = object_ids[index] else {
+        return Err(invalid_data("Unexpected missing object id."));
+    };
+
+    if mapping
+        .try_get_encoded_term_from_object_id(object_id)
+        .is_none()
+    {
+        return Err(invalid_data(&format!("Unknown object id {object_id}.")));
+    }
+    Ok(object_id)
+}
+
+fn invalid_data(message: &str) -> MemStoragePersistenceError {
+    MemStoragePersistenceError::InvalidData(message.to_owned())
+}
@@ -1,13 +1,17 @@
+mod dump;
 mod parquet;
 
 use crate::memory::storage::MemQuadStorageSnapshot;
 use async_trait::async_trait;
+use datafusion::arrow::error::ArrowError;
 use datafusion::common::DataFusionError;
 use datafusion::parquet::errors::ParquetError;
 use rdf_fusion_encoding::QuadStorageEncoding;
+use rdf_fusion_model::StorageError;
 use std::io::Write;
 use thiserror::Error;
 
+pub(crate) use dump::{read_parquet_dump, write_parquet_dump};
 pub use parquet::ParquetMemQuadStoragePersistence;
 
 /// Errors that can occur when persisting the in-memory storage.
@@ -22,6 +26,10 @@ pub enum MemStoragePersistenceError {
     QueryError(#[from] DataFusionError),
     #[error("Error while writing files: {0}")]
     DataFileError(Box<dyn std::error::Error + Send + Sync>),
+    #[error("The persisted storage is invalid: {0}")]
+    InvalidData(String),
+    #[error("Could not insert into the storage: {0}")]
+    StorageError(#[from] StorageError),
 }
 
 impl From<ParquetError> for MemStoragePersistenceError {
@@ -30,6 +38,18 @@ impl From<ParquetError> for MemStoragePersistenceError {
     }
 }
 
+impl From<ArrowError> for MemStoragePersistenceError {
+    fn from(err: ArrowError) -> Self {
+        MemStoragePersistenceError::DataFileError(Box::new(err))
+    }
+}
+
+impl From<std::io::Error> for MemStoragePersistenceError {
+    fn from(err: std::io::Error) -> Self {
+        MemStoragePersistenceError::DataFileError(Box::new(err))
+    }
+}
+
 /// Options for persisting the in-memory storage.
 #[derive(Default)]
 pub struct MemQuadPersistenceOptions {
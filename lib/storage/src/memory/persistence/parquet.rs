@@ -256,7 +256,7 @@ mod tests {
         )]));
 
         let array = Arc::new(Int32Array::from(vec![1, 2, 3]));
-        let batch = RecordBatch::try_new(schema.clone(), vec![array]).unwrap();
+        let batch = RecordBatch::try_new(Arc::clone(&schema), vec![array]).unwrap();
         let input_stream: SendableRecordBatchStream = Box::pin(MyOnceStream::new(batch));
 
         let col_expr: PhysicalExprRef = Arc::new(Column::new("col1", 0));
@@ -266,7 +266,7 @@ mod tests {
         let new_schema =
             Arc::new(Schema::new(vec![Field::new("col1", DataType::Utf8, false)]));
         let mut stream = QuadEncodingChangeStream {
-            schema: new_schema.clone(),
+            schema: Arc::clone(&new_schema),
             inner: input_stream,
             exprs: vec![cast_expr],
         };
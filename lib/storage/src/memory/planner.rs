@@ -1,5 +1,6 @@
 use crate::memory::storage::{
     MemQuadPatternDataSource, MemQuadStorageSnapshot, PlanPatternScanResult,
+    ScanOrderHint,
 };
 use async_trait::async_trait;
 use datafusion::datasource::source::DataSourceExec;
@@ -10,6 +11,7 @@ use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
 use rdf_fusion_logical::quad_pattern::QuadPatternNode;
+use rdf_fusion_model::{NamedNodePattern, TermPattern};
 use std::sync::Arc;
 
 /// Plans [QuadPatternNode]s with a [MemQuadStore].
@@ -45,7 +47,9 @@ impl ExtensionPlanner for MemQuadStorePlanner {
                     node.pattern().clone(),
                     node.blank_node_mode(),
                 )
-                .await?;
+                .await?
+                .with_order_hint(scan_order_hint(node))
+                .with_fetch(node.limit());
             let schema = Arc::clone(node.schema().inner());
 
             match plan {
@@ -63,3 +67,25 @@ impl ExtensionPlanner for MemQuadStorePlanner {
         }
     }
 }
+
+/// Returns the [ScanOrderHint] for the order hint of `node`.
+///
+/// If the hinted variable appears multiple times in the pattern, the first position is used.
+fn scan_order_hint(node: &QuadPatternNode) -> ScanOrderHint {
+    let Some(variable) = node.order_hint() else {
+        return ScanOrderHint::Any;
+    };
+
+    let pattern = node.pattern();
+    let is_variable = |term: &TermPattern| matches!(term, TermPattern::Variable(v) if v.as_ref() == variable);
+    if is_variable(&pattern.subject) {
+        ScanOrderHint::Subject
+    } else if matches!(&pattern.predicate, NamedNodePattern::Variable(v) if v.as_ref() == variable)
+    {
+        ScanOrderHint::Predicate
+    } else if is_variable(&pattern.object) {
+        ScanOrderHint::Object
+    } else {
+        ScanOrderHint::Any
+    }
+}
@@ -1,22 +1,41 @@
-use crate::index::{IndexComponents, IndexPermutations};
+use crate::index::{EncodedQuad, IndexBuildProgress, IndexComponents, IndexPermutations};
 use crate::memory::MemObjectIdMapping;
+use crate::memory::object_id::EncodedObjectId;
+use crate::memory::persistence::{
+    MemStoragePersistenceError, read_parquet_dump, write_parquet_dump,
+};
 use crate::memory::planner::MemQuadStorePlanner;
-use crate::memory::storage::quad_index::{MemIndexConfiguration, MemQuadIndex};
+use crate::memory::storage::quad_index::{
+    MemIndexConfiguration, MemNamedGraphStorage, MemQuadIndex, NamedGraphStorageKind,
+};
 use crate::memory::storage::snapshot::MemQuadStorageSnapshot;
 use async_trait::async_trait;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::common::runtime::SpawnedTask;
+use datafusion::execution::SendableRecordBatchStream;
 use datafusion::physical_planner::ExtensionPlanner;
 use rdf_fusion_encoding::QuadStorageEncoding;
-use rdf_fusion_encoding::object_id::{ObjectIdEncodingRef, ObjectIdMapping};
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdEncodingRef, ObjectIdMapping};
+use rdf_fusion_encoding::plain_term::PlainTermQuadBatch;
 use rdf_fusion_extensions::RdfFusionContextView;
-use rdf_fusion_extensions::storage::QuadStorage;
+use rdf_fusion_extensions::storage::{
+    QuadPosition, QuadStorage, QuadStorageLookups, StorageInfo, StorageOptimizationReport,
+};
 use rdf_fusion_model::DFResult;
 use rdf_fusion_model::StorageError;
 use rdf_fusion_model::{
-    GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad, QuadRef,
+    GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad,
+    QuadRef, Term, TermRef,
 };
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, RwLockMappedWriteGuard, RwLockWriteGuard};
+
+/// The number of quads that [MemQuadStorage::bulk_load] inserts into an index before reporting
+/// its progress.
+const BULK_LOAD_CHUNK_SIZE: usize = 100_000;
 
 /// A memory-based quad storage.
 pub struct MemQuadStorage {
@@ -24,8 +43,11 @@ pub struct MemQuadStorage {
     object_id_encoding: ObjectIdEncodingRef,
     /// Holds the mapping between terms and object ids.
     object_id_mapping: Arc<MemObjectIdMapping>,
-    /// The index set
-    indexes: Arc<RwLock<IndexPermutations<MemQuadIndex>>>,
+    /// The current version of the index set. Snapshots hold older versions (see
+    /// [MemQuadStorageSnapshot]).
+    indexes: RwLock<Arc<IndexPermutations<MemQuadIndex>>>,
+    /// The batch size of the indexes.
+    batch_size: usize,
 }
 
 impl MemQuadStorage {
@@ -33,11 +55,54 @@ impl MemQuadStorage {
     ///
     /// This method expects that the given `object_id_mapping` is the same as the mapping in
     /// `object_id_encoding`. We are planning on removing this restriction in the future.
+    ///
+    /// The named graphs are held in a hash set. See [Self::new_with_named_graph_storage] for
+    /// choosing a different data structure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object id width of `object_id_encoding` does not match the width
+    /// of `object_id_mapping`.
     pub fn new(
         object_id_mapping: Arc<MemObjectIdMapping>,
         object_id_encoding: ObjectIdEncodingRef,
         batch_size: usize,
-    ) -> Self {
+    ) -> Result<Self, StorageError> {
+        Self::new_with_named_graph_storage(
+            object_id_mapping,
+            object_id_encoding,
+            batch_size,
+            NamedGraphStorageKind::default(),
+        )
+    }
+
+    /// Creates a new [MemQuadStorage] that holds its named graphs in the data structure given by
+    /// `named_graph_storage`.
+    ///
+    /// Use [NamedGraphStorageKind::Sorted] if the named graphs should be enumerated in a stable
+    /// order and [NamedGraphStorageKind::Hash] for faster membership checks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object id width of `object_id_encoding` does not match the width
+    /// of `object_id_mapping`.
+    pub fn new_with_named_graph_storage(
+        object_id_mapping: Arc<MemObjectIdMapping>,
+        object_id_encoding: ObjectIdEncodingRef,
+        batch_size: usize,
+        named_graph_storage: NamedGraphStorageKind,
+    ) -> Result<Self, StorageError> {
+        if object_id_encoding.object_id_size() != object_id_mapping.object_id_size() {
+            return Err(StorageError::Other(
+                format!(
+                    "The object id encoding ({}) does not match the width of the object id mapping ({}).",
+                    object_id_encoding.object_id_size(),
+                    object_id_mapping.object_id_size()
+                )
+                .into(),
+            ));
+        }
+
         let components = [
             IndexComponents::GSPO,
             IndexComponents::GPOS,
@@ -53,24 +118,208 @@ impl MemQuadStorage {
                 })
             })
             .collect();
-        Self {
-            indexes: Arc::new(RwLock::new(IndexPermutations::new(
-                HashSet::new(),
+        Ok(Self {
+            indexes: RwLock::new(Arc::new(IndexPermutations::new(
+                MemNamedGraphStorage::new(named_graph_storage),
                 indexes,
             ))),
             object_id_mapping,
             object_id_encoding,
-        }
+            batch_size,
+        })
     }
 
     /// Creates a snapshot of this storage.
+    ///
+    /// The snapshot references the current version of the indexes and does not block writers.
+    /// See [MemQuadStorageSnapshot] for the implications of long-lived snapshots.
     pub async fn snapshot(&self) -> MemQuadStorageSnapshot {
         MemQuadStorageSnapshot::new(
             self.encoding(),
             Arc::clone(&self.object_id_mapping),
-            Arc::new(Arc::clone(&self.indexes).read_owned().await),
+            Arc::clone(&*self.indexes.read().await),
+        )
+    }
+
+    /// Removes duplicates from `quads` and encodes the remaining quads.
+    fn encode_distinct_quads(
+        &self,
+        quads: Vec<Quad>,
+    ) -> DFResult<(Vec<Quad>, Vec<EncodedQuad<EncodedObjectId>>)> {
+        let mut seen = HashSet::new();
+        let quads = quads
+            .into_iter()
+            .filter(|quad| seen.insert(quad.clone()))
+            .collect::<Vec<_>>();
+        let encoded = quads
+            .iter()
+            .map(|q| self.object_id_mapping.encode_quad(q.as_ref()))
+            .collect::<DFResult<Vec<_>>>()?;
+        Ok((quads, encoded))
+    }
+
+    /// Acquires exclusive access to the current version of the indexes.
+    ///
+    /// If a snapshot still references this version, the indexes are copied before they are
+    /// modified (see [MemQuadStorageSnapshot]).
+    async fn indexes_mut(
+        &self,
+    ) -> RwLockMappedWriteGuard<'_, IndexPermutations<MemQuadIndex>> {
+        RwLockWriteGuard::map(self.indexes.write().await, Arc::make_mut)
+    }
+
+    /// Creates an additional index that only holds the quads with the given `predicate`.
+    ///
+    /// Queries that bind the predicate to `predicate` (e.g., `?s rdf:type ?class`) can then scan
+    /// this smaller index instead of the general index permutations. The index is ordered by
+    /// graph, predicate, object, and subject and is populated with the existing quads.
+    ///
+    /// Returns false if such an index already exists.
+    pub async fn create_predicate_index(
+        &self,
+        predicate: NamedNodeRef<'_>,
+    ) -> Result<bool, StorageError> {
+        let predicate = self
+            .object_id_mapping
+            .encode_term_intern(predicate)
+            .map_err(StorageError::from)?;
+        let index = MemQuadIndex::new_specialized(
+            MemIndexConfiguration {
+                object_id_encoding: Arc::clone(&self.object_id_encoding),
+                batch_size: self.batch_size,
+                components: IndexComponents::GPOS,
+            },
+            predicate,
+        );
+        Ok(self.indexes_mut().await.add_specialized_index(index))
+    }
+
+    /// Defers building the index permutation with the given `name` (e.g., `GOSP`) until a query
+    /// requires it for the first time.
+    ///
+    /// Deferred indexes are not maintained while loading data, which speeds up bulk loads if most
+    /// queries only use a few pattern shapes. Once a query chooses the index, it is built
+    /// transparently from the quads of another index and maintained from there on. The current
+    /// content of the index is dropped.
+    ///
+    /// Returns false if there is no index with this name or if it is the last index that is not
+    /// deferred.
+    pub async fn defer_index(&self, name: &str) -> Result<bool, StorageError> {
+        let components = [
+            IndexComponents::GSPO,
+            IndexComponents::GPOS,
+            IndexComponents::GOSP,
+        ]
+        .into_iter()
+        .find(|components| components.to_string() == name);
+        let Some(components) = components else {
+            return Ok(false);
+        };
+        Ok(self.indexes_mut().await.defer_index(components))
+    }
+
+    /// Inserts `quads` into the storage while reporting the progress of building the indexes.
+    ///
+    /// The indexes are built one after another and `progress` is called after each chunk of
+    /// quads that has been inserted into an index. Deferred indexes (see [Self::defer_index]) that have not been
+    /// built yet are skipped. Returns the number of inserted quads.
+    pub async fn bulk_load(
+        &self,
+        quads: Vec<Quad>,
+        mut progress: impl FnMut(BulkLoadProgress),
+    ) -> Result<usize, StorageError> {
+        let encoded = quads
+            .iter()
+            .map(|q| self.object_id_mapping.encode_quad(q.as_ref()))
+            .collect::<DFResult<Vec<_>>>()?;
+        self.indexes_mut().await.insert_with_progress(
+            encoded.as_ref(),
+            BULK_LOAD_CHUNK_SIZE,
+            |index_progress| progress(self.bulk_load_progress(index_progress)),
         )
     }
+
+    /// Writes the entire storage into the directory at `path`. The directory is created if it
+    /// does not exist.
+    ///
+    /// In contrast to the files written by
+    /// [ParquetMemQuadStoragePersistence](crate::memory::ParquetMemQuadStoragePersistence), the
+    /// dump also contains the mapping between terms and object ids. This allows restoring an
+    /// equivalent storage with [Self::load_from_parquet].
+    pub async fn dump_to_parquet(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), MemStoragePersistenceError> {
+        let snapshot = self.snapshot().await;
+        write_parquet_dump(path.as_ref(), &self.object_id_mapping, &snapshot).await
+    }
+
+    /// Loads a dump that has been written with [Self::dump_to_parquet] into this storage and
+    /// returns the number of loaded quads.
+    ///
+    /// The terms retain their persisted object ids. The quads are inserted into all index
+    /// permutations of this storage, including specialized predicate indexes. Deferred indexes
+    /// are built once a query requires them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage is not empty or if the object id mapping of the storage
+    /// already maps one of the persisted object ids or terms differently.
+    pub async fn load_from_parquet(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<usize, MemStoragePersistenceError> {
+        let mut indexes = self.indexes_mut().await;
+        if indexes.len() > 0 || !indexes.named_graphs().is_empty() {
+            return Err(MemStoragePersistenceError::InvalidMemQuadStorage(
+                "Dumps can only be loaded into an empty storage.".to_owned(),
+            ));
+        }
+
+        let dump = read_parquet_dump(path.as_ref(), &self.object_id_mapping)?;
+        for named_graph in dump.named_graphs {
+            indexes.insert_named_graph(named_graph);
+        }
+        Ok(indexes.insert(&dump.quads)?)
+    }
+
+    /// Converts the progress of the [IndexPermutations] into a [BulkLoadProgress].
+    fn bulk_load_progress(
+        &self,
+        progress: IndexBuildProgress<EncodedObjectId>,
+    ) -> BulkLoadProgress {
+        let predicate = progress.index.predicate().map(|predicate| {
+            match self.object_id_mapping.decode_term(*predicate) {
+                Ok(Term::NamedNode(predicate)) => predicate,
+                _ => unreachable!("Specialized indexes are created for predicates"),
+            }
+        });
+        BulkLoadProgress {
+            index: progress.index.components().to_string(),
+            predicate,
+            inserted_quads: progress.inserted_quads,
+            total_quads: progress.total_quads,
+            completed_indexes: progress.completed_indexes,
+            total_indexes: progress.total_indexes,
+        }
+    }
+}
+
+/// The progress of a [MemQuadStorage::bulk_load].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkLoadProgress {
+    /// The name of the index that is currently built (e.g., `GPOS`).
+    pub index: String,
+    /// The predicate if the index only holds the quads with this predicate.
+    pub predicate: Option<NamedNode>,
+    /// The number of quads that have been inserted into the current index.
+    pub inserted_quads: usize,
+    /// The number of quads that are inserted into the current index.
+    pub total_quads: usize,
+    /// The number of indexes that are completely built.
+    pub completed_indexes: usize,
+    /// The number of indexes that are built during the bulk load.
+    pub total_indexes: usize,
 }
 
 #[async_trait]
@@ -96,23 +345,99 @@ impl QuadStorage for MemQuadStorage {
         let encoded = quads
             .iter()
             .map(|q| self.object_id_mapping.encode_quad(q.as_ref()))
-            .collect::<DFResult<Vec<_>>>()
-            .expect("TODO");
-        self.indexes.write().await.insert(encoded.as_ref())
+            .collect::<DFResult<Vec<_>>>()?;
+        self.indexes_mut().await.insert(encoded.as_ref())
+    }
+
+    async fn extend_parallel(
+        &self,
+        quads: Vec<Quad>,
+        parallelism: NonZeroUsize,
+    ) -> Result<usize, StorageError> {
+        // The object ids are interned concurrently, as the mapping is backed by sharded maps.
+        // Only the insertion into the indexes requires exclusive access. The quads are encoded on
+        // the blocking thread pool, such that the runtime can continue driving other tasks.
+        let quad_count = quads.len();
+        let chunk_size = quad_count.div_ceil(parallelism.get()).max(1);
+        let mut quads = quads.into_iter();
+        let mut tasks = Vec::new();
+        loop {
+            let chunk = quads.by_ref().take(chunk_size).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break;
+            }
+            let object_id_mapping = Arc::clone(&self.object_id_mapping);
+            tasks.push(SpawnedTask::spawn_blocking(move || {
+                chunk
+                    .iter()
+                    .map(|q| object_id_mapping.encode_quad(q.as_ref()))
+                    .collect::<DFResult<Vec<_>>>()
+            }));
+        }
+
+        let mut encoded = Vec::with_capacity(quad_count);
+        for task in tasks {
+            let chunk = task
+                .join_unwind()
+                .await
+                .map_err(|err| StorageError::Other(Box::new(err)))??;
+            encoded.extend(chunk);
+        }
+        self.indexes_mut().await.insert(encoded.as_ref())
+    }
+
+    async fn extend_from_batch(&self, batch: RecordBatch) -> Result<usize, StorageError> {
+        let batch = PlainTermQuadBatch::try_new(&batch)?;
+        let encoded = batch
+            .quads()
+            .map(|quad| quad.and_then(|quad| self.object_id_mapping.encode_quad(quad)))
+            .collect::<DFResult<Vec<_>>>()?;
+        self.indexes_mut().await.insert(encoded.as_ref())
     }
 
     async fn remove(&self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
-        let encoded = self.object_id_mapping.encode_quad(quad).expect("TODO");
-        let count = self.indexes.write().await.remove(&[encoded]);
+        let encoded = self.object_id_mapping.encode_quad(quad)?;
+        let count = self.indexes_mut().await.remove(&[encoded]);
         Ok(count > 0)
     }
 
+    async fn insert_quads(&self, quads: Vec<Quad>) -> Result<Vec<Quad>, StorageError> {
+        let (quads, encoded) = self.encode_distinct_quads(quads)?;
+        let mut indexes = self.indexes_mut().await;
+        let (inserted, encoded): (Vec<_>, Vec<_>) = quads
+            .into_iter()
+            .zip(encoded)
+            .filter(|(_, encoded)| !indexes.contains(encoded))
+            .unzip();
+        if !encoded.is_empty() {
+            indexes.insert(&encoded)?;
+        }
+        Ok(inserted)
+    }
+
+    async fn remove_quads(&self, quads: Vec<Quad>) -> Result<Vec<Quad>, StorageError> {
+        let (quads, encoded) = self.encode_distinct_quads(quads)?;
+        let mut indexes = self.indexes_mut().await;
+        let (removed, encoded): (Vec<_>, Vec<_>) = quads
+            .into_iter()
+            .zip(encoded)
+            .filter(|(_, encoded)| indexes.contains(encoded))
+            .unzip();
+        if !encoded.is_empty() {
+            indexes.remove(&encoded);
+        }
+        Ok(removed)
+    }
+
     async fn insert_named_graph<'a>(
         &self,
         graph_name: NamedOrBlankNodeRef<'a>,
     ) -> Result<bool, StorageError> {
-        let encoded = self.object_id_mapping.encode_term_intern(graph_name);
-        Ok(self.indexes.write().await.insert_named_graph(encoded))
+        let encoded = self
+            .object_id_mapping
+            .encode_term_intern(graph_name)
+            .map_err(StorageError::from)?;
+        Ok(self.indexes_mut().await.insert_named_graph(encoded))
     }
 
     async fn named_graphs(&self) -> Result<Vec<NamedOrBlankNode>, StorageError> {
@@ -127,7 +452,7 @@ impl QuadStorage for MemQuadStorage {
     }
 
     async fn clear(&self) -> Result<(), StorageError> {
-        self.indexes.write().await.clear();
+        self.indexes_mut().await.clear();
         Ok(())
     }
 
@@ -141,7 +466,7 @@ impl QuadStorage for MemQuadStorage {
         else {
             return Ok(());
         };
-        self.indexes.write().await.clear_graph(&encoded.0);
+        self.indexes_mut().await.clear_graph(&encoded.0);
         Ok(())
     }
 
@@ -156,18 +481,126 @@ impl QuadStorage for MemQuadStorage {
             return Ok(false);
         };
 
-        Ok(self.indexes.write().await.drop_named_graph(&encoded))
+        Ok(self.indexes_mut().await.drop_named_graph(&encoded))
     }
 
     async fn len(&self) -> Result<usize, StorageError> {
         Ok(self.snapshot().await.len())
     }
 
-    async fn optimize(&self) -> Result<(), StorageError> {
-        Ok(())
+    fn lookups(&self) -> Option<&dyn QuadStorageLookups> {
+        Some(self)
+    }
+
+    async fn snapshot(&self) -> Result<Option<Arc<dyn QuadStorage>>, StorageError> {
+        Ok(Some(Arc::new(MemQuadStorage::snapshot(self).await)))
+    }
+
+    /// Rebuilds all index permutations such that their row groups are compact again.
+    ///
+    /// The compacted indexes are built from the current version without holding a lock, allowing
+    /// concurrent queries and writers to proceed. Afterward, the new indexes are swapped in. If the
+    /// storage was modified in the meantime, the compaction is repeated while holding the write
+    /// lock.
+    async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError> {
+        let indexes = Arc::clone(&*self.indexes.read().await);
+        let (compacted, version) = (indexes.compacted(), indexes.version());
+
+        let mut indexes = self.indexes.write().await;
+        let compacted = if indexes.version() == version {
+            compacted
+        } else {
+            indexes.compacted()
+        };
+
+        let reclaimed_bytes = indexes
+            .memory_size()
+            .saturating_sub(compacted.memory_size());
+        *indexes = Arc::new(compacted);
+
+        Ok(StorageOptimizationReport { reclaimed_bytes })
     }
 
     async fn validate(&self) -> Result<(), StorageError> {
         Ok(())
     }
+
+    async fn storage_info(&self) -> Result<Option<StorageInfo>, StorageError> {
+        Ok(Some(self.snapshot().await.storage_info()))
+    }
+}
+
+#[async_trait]
+impl QuadStorageLookups for MemQuadStorage {
+    async fn count_matching(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<usize, StorageError> {
+        let count = self
+            .snapshot()
+            .await
+            .count_matching(graph_name, subject, predicate, object)?;
+        Ok(count)
+    }
+
+    async fn predicates_for(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: NamedOrBlankNodeRef<'_>,
+    ) -> Result<Vec<NamedNode>, StorageError> {
+        let predicates = self.snapshot().await.predicates_for(graph_name, subject)?;
+        Ok(predicates)
+    }
+
+    async fn distinct_objects_of(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        predicate: NamedNodeRef<'_>,
+    ) -> Result<Vec<Term>, StorageError> {
+        let objects = self
+            .snapshot()
+            .await
+            .distinct_objects_of(graph_name, predicate)?;
+        Ok(objects)
+    }
+
+    async fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> Result<Vec<(Term, usize)>, StorageError> {
+        let terms = self.snapshot().await.top_terms(position, k)?;
+        Ok(terms)
+    }
+
+    async fn quads_for_object_id_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+    ) -> Result<Vec<Quad>, StorageError> {
+        let quads = self
+            .snapshot()
+            .await
+            .quads_for_object_id_pattern(graph_name, subject, predicate, object)?;
+        Ok(quads)
+    }
+
+    async fn quads_for_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<SendableRecordBatchStream, StorageError> {
+        let stream = self
+            .snapshot()
+            .await
+            .quads_for_pattern(graph_name, subject, predicate, object);
+        Ok(stream)
+    }
 }
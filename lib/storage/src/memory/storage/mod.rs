@@ -8,18 +8,23 @@ mod scan_instructions;
 mod snapshot;
 mod stream;
 
-pub use mem_storage::MemQuadStorage;
+pub use mem_storage::{BulkLoadProgress, MemQuadStorage};
 pub use pattern_data_source::MemQuadPatternDataSource;
+pub use quad_index::NamedGraphStorageKind;
+pub use scan::ScanOrderHint;
 pub use snapshot::{MemQuadStorageSnapshot, PlanPatternScanResult};
 
 #[cfg(test)]
 mod tests {
     use crate::index::{
-        EncodedQuad, IndexComponents, IndexPermutations, IndexQuad, QuadIndex,
+        EncodedQuad, IndexComponent, IndexComponents, IndexId, IndexPermutations,
+        IndexQuad, QuadIndex,
     };
     use crate::memory::MemObjectIdMapping;
     use crate::memory::object_id::EncodedObjectId;
-    use crate::memory::storage::quad_index::{MemIndexConfiguration, MemQuadIndex};
+    use crate::memory::storage::quad_index::{
+        MemIndexConfiguration, MemNamedGraphStorage, MemQuadIndex,
+    };
     use crate::memory::storage::scan::MemQuadIndexScanRecordBatchIterator;
     use crate::memory::storage::scan_instructions::{
         MemIndexScanInstruction, MemIndexScanInstructions, MemIndexScanPredicate,
@@ -28,9 +33,7 @@ mod tests {
     use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema};
     use insta::assert_debug_snapshot;
     use rdf_fusion_encoding::object_id::ObjectIdEncoding;
-    use std::collections::HashSet;
     use std::sync::Arc;
-    use tokio::sync::RwLock;
 
     #[tokio::test]
     async fn insert_and_scan_triple() {
@@ -265,7 +268,7 @@ mod tests {
             MemIndexScanInstructions::new_gspo([
                 MemIndexScanInstruction::Scan(
                     Arc::new("a".to_owned()),
-                    Some(MemIndexScanPredicate::In([eid(1), eid(3)].into()).into()),
+                    Some(MemIndexScanPredicate::In([eid(1), eid(3)].into())),
                 ),
                 scan("b"),
                 scan("c"),
@@ -299,6 +302,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn scan_stops_after_fetch() {
+        let mut index = create_index_with_batch_size(10);
+        let mut quads = Vec::new();
+        for i in 0..25 {
+            quads.push(IndexQuad([eid(1), eid(2), eid(3), eid(i + 1)]))
+        }
+        index.insert(quads);
+
+        let instructions = MemIndexScanInstructions::new_gspo([
+            traverse(1),
+            traverse(2),
+            traverse(3),
+            scan("d"),
+        ]);
+        for (fetch, expected_batch_sizes) in [
+            (0, vec![]),
+            (5, vec![5]),
+            (13, vec![10, 3]),
+            (30, vec![10, 10, 5]),
+        ] {
+            let batch_sizes = index
+                .scan_quads(instructions.clone())
+                .with_fetch(Some(fetch))
+                .map(|arr| arr.unwrap().num_rows)
+                .collect::<Vec<_>>();
+            assert_eq!(batch_sizes, expected_batch_sizes);
+        }
+    }
+
     #[tokio::test]
     async fn scan_multi_level_batches_coalesce_results() {
         let mut index = create_index_with_batch_size(10);
@@ -435,6 +468,51 @@ mod tests {
         assert_eq!(a.try_and_with(&b), None);
     }
 
+    #[test]
+    fn try_or_with_false_returns_other() {
+        let a = MemIndexScanPredicate::False;
+        let b = MemIndexScanPredicate::Between(eid(2), eid(3));
+        assert_eq!(a.try_or_with(&b), Some(b.clone()));
+        assert_eq!(b.try_or_with(&a), Some(b));
+    }
+
+    #[test]
+    fn try_or_with_in_and_in_unites_sets() {
+        let a = MemIndexScanPredicate::In([eid(1), eid(2)].into());
+        let b = MemIndexScanPredicate::In([eid(2), eid(5)].into());
+        assert_eq!(
+            a.try_or_with(&b),
+            Some(MemIndexScanPredicate::In([eid(1), eid(2), eid(5)].into()))
+        );
+    }
+
+    #[test]
+    fn try_or_with_in_and_between_covers_both() {
+        let a = MemIndexScanPredicate::In([eid(1), eid(7)].into());
+        let b = MemIndexScanPredicate::Between(eid(3), eid(5));
+        assert_eq!(
+            a.try_or_with(&b),
+            Some(MemIndexScanPredicate::Between(eid(1), eid(7)))
+        );
+    }
+
+    #[test]
+    fn try_or_with_between_and_between_disjoint_covers_gap() {
+        let a = MemIndexScanPredicate::Between(eid(1), eid(2));
+        let b = MemIndexScanPredicate::Between(eid(5), eid(6));
+        assert_eq!(
+            a.try_or_with(&b),
+            Some(MemIndexScanPredicate::Between(eid(1), eid(6)))
+        );
+    }
+
+    #[test]
+    fn try_or_with_incompatible_returns_none() {
+        let a = MemIndexScanPredicate::In([eid(1)].into());
+        let b = MemIndexScanPredicate::EqualTo(Arc::new("x".to_string()));
+        assert_eq!(a.try_or_with(&b), None);
+    }
+
     #[test]
     fn choose_index_all_bound() {
         let set = create_storage();
@@ -448,7 +526,7 @@ mod tests {
 
         let result = set.choose_index(&pattern);
 
-        assert_eq!(result, IndexComponents::GSPO);
+        assert_eq!(result.components(), IndexComponents::GSPO);
     }
 
     #[test]
@@ -463,7 +541,7 @@ mod tests {
         ]);
 
         let result = set.choose_index(&pattern);
-        assert_eq!(result, IndexComponents::GOSP);
+        assert_eq!(result.components(), IndexComponents::GOSP);
     }
 
     #[test]
@@ -479,21 +557,264 @@ mod tests {
 
         let result = set.choose_index(&pattern);
 
-        assert_eq!(result, IndexComponents::GPOS);
+        assert_eq!(result.components(), IndexComponents::GPOS);
+    }
+
+    #[test]
+    fn choose_index_prefers_specialized_predicate_index() {
+        let mut set = create_storage();
+        set.add_specialized_index(create_specialized_index(3));
+
+        let pattern = MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(0),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            traverse_and_filter(3),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]);
+
+        let result = set.choose_index(&pattern);
+
+        assert_eq!(result.components(), IndexComponents::GPOS);
+        assert_eq!(result.predicate(), Some(&eid(3)));
+    }
+
+    #[test]
+    fn choose_index_falls_back_to_general_index_for_other_predicates() {
+        let mut set = create_storage();
+        set.add_specialized_index(create_specialized_index(3));
+
+        let pattern = MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(0),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            traverse_and_filter(4),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]);
+
+        let result = set.choose_index(&pattern);
+
+        assert_eq!(result.components(), IndexComponents::GPOS);
+        assert_eq!(result.predicate(), None);
+    }
+
+    #[test]
+    fn choose_index_with_order_selects_ordered_index() {
+        let set = create_storage();
+
+        let pattern = MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(0),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            traverse_and_filter(2),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]);
+
+        let result = set
+            .choose_index_with_order(&pattern, IndexComponent::Subject)
+            .unwrap();
+
+        assert_eq!(result.components(), IndexComponents::GSPO);
+    }
+
+    #[test]
+    fn choose_index_with_order_no_index_if_graph_is_not_fixed() {
+        let set = create_storage();
+
+        let pattern = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::Scan(Arc::new("graph".to_string()), None),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            traverse_and_filter(2),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]);
+
+        let result = set.choose_index_with_order(&pattern, IndexComponent::Subject);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn specialized_index_only_holds_quads_with_predicate() {
+        let mut set = create_storage();
+        set.insert(&[quad(0, 1, 3, 4), quad(0, 1, 5, 6)]).unwrap();
+        set.add_specialized_index(create_specialized_index(3));
+        set.insert(&[quad(0, 2, 3, 4), quad(0, 2, 5, 6)]).unwrap();
+        set.remove(&[quad(0, 1, 3, 4)]);
+
+        let specialized = set
+            .find_index(IndexId::new(IndexComponents::GPOS, Some(eid(3))))
+            .unwrap();
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(
+            specialized.quads(),
+            vec![IndexQuad([eid(0), eid(3), eid(4), eid(2)])]
+        );
+    }
+
+    #[test]
+    fn deferred_index_is_built_on_first_use() {
+        let mut set = create_storage();
+        assert!(set.defer_index(IndexComponents::GPOS));
+        set.insert(&[quad(0, 1, 3, 4), quad(0, 2, 5, 6)]).unwrap();
+
+        let id = IndexId::general(IndexComponents::GPOS);
+        assert!(!set.is_built(id));
+
+        let pattern = MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(0),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            traverse_and_filter(3),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]);
+        assert_eq!(set.choose_index(&pattern), id);
+
+        let index = set.find_index(id).unwrap();
+        assert_eq!(
+            index.quads(),
+            vec![
+                IndexQuad([eid(0), eid(3), eid(4), eid(1)]),
+                IndexQuad([eid(0), eid(5), eid(6), eid(2)])
+            ]
+        );
+        assert!(set.is_built(id));
+    }
+
+    #[test]
+    fn deferred_index_is_maintained_once_built() {
+        let mut set = create_storage();
+        assert!(set.defer_index(IndexComponents::GPOS));
+        set.insert(&[quad(0, 1, 3, 4)]).unwrap();
+
+        let id = IndexId::general(IndexComponents::GPOS);
+        set.find_index(id).unwrap();
+        set.insert(&[quad(0, 2, 5, 6)]).unwrap();
+        set.remove(&[quad(0, 1, 3, 4)]);
+
+        assert_eq!(
+            set.find_index(id).unwrap().quads(),
+            vec![IndexQuad([eid(0), eid(5), eid(6), eid(2)])]
+        );
+    }
+
+    #[test]
+    fn last_general_index_cannot_be_deferred() {
+        let mut set = create_storage();
+        assert!(set.defer_index(IndexComponents::GPOS));
+        assert!(set.defer_index(IndexComponents::GOSP));
+        assert!(!set.defer_index(IndexComponents::GSPO));
+        assert!(!set.defer_index(IndexComponents::GPOS));
+    }
+
+    #[test]
+    fn insert_with_progress_reports_chunks_of_built_indexes() {
+        let mut set = create_storage();
+        assert!(set.defer_index(IndexComponents::GOSP));
+
+        let mut progress = Vec::new();
+        let count = set
+            .insert_with_progress(
+                &[quad(0, 1, 3, 4), quad(0, 2, 5, 6), quad(0, 3, 5, 7)],
+                2,
+                |p| {
+                    progress.push((
+                        p.index.components(),
+                        p.inserted_quads,
+                        p.completed_indexes,
+                    ))
+                },
+            )
+            .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            progress,
+            vec![
+                (IndexComponents::GSPO, 2, 0),
+                (IndexComponents::GSPO, 3, 1),
+                (IndexComponents::GPOS, 2, 1),
+                (IndexComponents::GPOS, 3, 2),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn scan_multiple_predicates_in_single_pass() {
+        let mut set = create_storage();
+        set.insert(&[
+            quad(1, 1, 5, 10),
+            quad(1, 2, 4, 11),
+            quad(1, 3, 3, 12),
+            quad(1, 4, 5, 13),
+            quad(1, 5, 3, 14),
+        ])
+        .unwrap();
+
+        let pattern = Box::new(MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(1),
+            MemIndexScanInstruction::Scan(Arc::new("subject".to_string()), None),
+            MemIndexScanInstruction::Scan(
+                Arc::new("predicate".to_string()),
+                Some(MemIndexScanPredicate::In([eid(3), eid(5)].into())),
+            ),
+            MemIndexScanInstruction::Scan(Arc::new("object".to_string()), None),
+        ]));
+
+        let schema = Arc::new(Schema::new(Fields::from(vec![
+            Field::new("subject", DataType::UInt32, false),
+            Field::new("predicate", DataType::UInt32, false),
+            Field::new("object", DataType::UInt32, false),
+        ])));
+
+        let set = Arc::new(set);
+        let index = set.choose_index(&pattern);
+        let mut scan = MemQuadIndexScanRecordBatchIterator::new(
+            schema,
+            set,
+            index,
+            None,
+            *pattern,
+            vec![],
+            None,
+        );
+
+        let batch = scan.next().unwrap().unwrap();
+        assert!(scan.next().is_none());
+        assert_eq!(index.components(), IndexComponents::GPOS);
+        assert_debug_snapshot!(batch.columns(), @r"
+        [
+            PrimitiveArray<UInt32>
+            [
+              3,
+              5,
+              1,
+              4,
+            ],
+            PrimitiveArray<UInt32>
+            [
+              3,
+              3,
+              5,
+              5,
+            ],
+            PrimitiveArray<UInt32>
+            [
+              12,
+              14,
+              10,
+              13,
+            ],
+        ]
+        ");
     }
 
     #[tokio::test]
     async fn scan_gpos_subject_and_object() {
-        let set = RwLock::new(create_storage());
-        set.write()
-            .await
-            .insert(&[EncodedQuad {
-                graph_name: EncodedObjectId::from(1),
-                subject: EncodedObjectId::from(2),
-                predicate: EncodedObjectId::from(3),
-                object: EncodedObjectId::from(4),
-            }])
-            .unwrap();
+        let mut set = create_storage();
+        set.insert(&[EncodedQuad {
+            graph_name: EncodedObjectId::from(1u32),
+            subject: EncodedObjectId::from(2u32),
+            predicate: EncodedObjectId::from(3u32),
+            object: EncodedObjectId::from(4u32),
+        }])
+        .unwrap();
 
         let pattern = Box::new(MemIndexScanInstructions::new_gspo([
             traverse_and_filter(1),
@@ -507,19 +828,21 @@ mod tests {
             Field::new("object", DataType::UInt32, false),
         ])));
 
-        let set_lock = Arc::new(Arc::new(set).read_owned().await);
-        let configuration = set_lock.choose_index(&pattern);
+        let set = Arc::new(set);
+        let configuration = set.choose_index(&pattern);
 
         let mut scan = MemQuadIndexScanRecordBatchIterator::new(
             schema,
-            set_lock,
+            set,
             configuration,
+            None,
             *pattern,
             vec![],
+            None,
         );
 
         let batch = scan.next().unwrap().unwrap();
-        assert_eq!(configuration, IndexComponents::GPOS);
+        assert_eq!(configuration.components(), IndexComponents::GPOS);
         assert_debug_snapshot!(batch, @r#"
         RecordBatch {
             schema: Schema {
@@ -550,6 +873,58 @@ mod tests {
         "#);
     }
 
+    #[tokio::test]
+    async fn scan_predicate_between_bound_subject_and_object() {
+        let mut set = create_storage();
+        set.insert(&[
+            quad(0, 1, 2, 3),
+            quad(0, 1, 4, 3),
+            quad(0, 1, 5, 6),
+            quad(0, 7, 8, 3),
+        ])
+        .unwrap();
+
+        // Only the predicate is unbound (e.g., `<s> ?p <o>`).
+        let pattern = Box::new(MemIndexScanInstructions::new_gspo([
+            traverse_and_filter(0),
+            traverse_and_filter(1),
+            MemIndexScanInstruction::Scan(Arc::new("predicate".to_string()), None),
+            traverse_and_filter(3),
+        ]));
+
+        let schema = Arc::new(Schema::new(Fields::from(vec![Field::new(
+            "predicate",
+            DataType::UInt32,
+            false,
+        )])));
+
+        let set = Arc::new(set);
+        let index = set.choose_index(&pattern);
+        let mut scan = MemQuadIndexScanRecordBatchIterator::new(
+            schema,
+            set,
+            index,
+            None,
+            *pattern,
+            vec![],
+            None,
+        );
+
+        // GOSP binds the graph, object, and subject such that only the predicates are scanned.
+        let batch = scan.next().unwrap().unwrap();
+        assert!(scan.next().is_none());
+        assert_eq!(index.components(), IndexComponents::GOSP);
+        assert_debug_snapshot!(batch.columns(), @r"
+        [
+            PrimitiveArray<UInt32>
+            [
+              2,
+              4,
+            ],
+        ]
+        ");
+    }
+
     fn create_storage() -> IndexPermutations<MemQuadIndex> {
         let mapping = Arc::new(MemObjectIdMapping::new());
         let encoding = Arc::new(ObjectIdEncoding::new(mapping));
@@ -568,13 +943,24 @@ mod tests {
                 })
             })
             .collect();
-        IndexPermutations::new(HashSet::new(), indexes)
+        IndexPermutations::new(MemNamedGraphStorage::default(), indexes)
+    }
+
+    fn create_specialized_index(predicate: u32) -> MemQuadIndex {
+        let mapping = Arc::new(MemObjectIdMapping::new());
+        let encoding = Arc::new(ObjectIdEncoding::new(mapping));
+        let configuration = MemIndexConfiguration {
+            object_id_encoding: encoding,
+            batch_size: 100,
+            components: IndexComponents::GPOS,
+        };
+        MemQuadIndex::new_specialized(configuration, eid(predicate))
     }
 
     fn traverse_and_filter(id: u32) -> MemIndexScanInstruction {
-        MemIndexScanInstruction::Traverse(Some(
-            MemIndexScanPredicate::In([eid(id)].into()).into(),
-        ))
+        MemIndexScanInstruction::Traverse(Some(MemIndexScanPredicate::In(
+            [eid(id)].into(),
+        )))
     }
 
     fn create_index() -> MemQuadIndex {
@@ -593,9 +979,9 @@ mod tests {
     }
 
     fn traverse(id: u32) -> MemIndexScanInstruction {
-        MemIndexScanInstruction::Traverse(Some(
-            MemIndexScanPredicate::In([EncodedObjectId::from(id)].into()).into(),
-        ))
+        MemIndexScanInstruction::Traverse(Some(MemIndexScanPredicate::In(
+            [EncodedObjectId::from(id)].into(),
+        )))
     }
 
     fn scan(name: impl Into<String>) -> MemIndexScanInstruction {
@@ -606,6 +992,20 @@ mod tests {
         EncodedObjectId::from(id)
     }
 
+    fn quad(
+        graph: u32,
+        subject: u32,
+        predicate: u32,
+        object: u32,
+    ) -> EncodedQuad<EncodedObjectId> {
+        EncodedQuad {
+            graph_name: eid(graph),
+            subject: eid(subject),
+            predicate: eid(predicate),
+            object: eid(object),
+        }
+    }
+
     fn run_non_matching_test(
         index: MemQuadIndex,
         instructions: MemIndexScanInstructions,
@@ -1,13 +1,18 @@
 use crate::memory::storage::predicate_pushdown::MemStoragePredicateExpr;
 use crate::memory::storage::scan::PlannedPatternScan;
 use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::stats::Precision;
 use datafusion::common::{Statistics, exec_err};
 use datafusion::config::ConfigOptions;
 use datafusion::datasource::source::DataSource;
 use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_expr::expressions::Column;
 use datafusion::physical_expr::projection::ProjectionExprs;
-use datafusion::physical_expr::{EquivalenceProperties, Partitioning, PhysicalExpr};
+use datafusion::physical_expr::{
+    EquivalenceProperties, Partitioning, PhysicalExpr, PhysicalSortExpr,
+};
 use datafusion::physical_plan::DisplayFormatType;
+use datafusion::physical_plan::EmptyRecordBatchStream;
 use datafusion::physical_plan::execution_plan::SchedulingType;
 use datafusion::physical_plan::filter_pushdown::{FilterPushdownPropagation, PushedDown};
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet};
@@ -36,6 +41,26 @@ impl MemQuadPatternDataSource {
             metrics: ExecutionPlanMetricsSet::default(),
         }
     }
+
+    /// Returns the statistics of the data source.
+    ///
+    /// The number of rows is estimated with [PlannedPatternScan::estimate_num_rows]. This allows
+    /// DataFusion to order joins by the size of their inputs (e.g., choosing the smaller input as
+    /// the build side of a hash join). The total byte size is derived from the number of rows if
+    /// every column has a fixed size (e.g., object ids).
+    fn compute_statistics(&self) -> Statistics {
+        let num_rows = self.planned_scan.estimate_num_rows();
+        let row_size = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| field.data_type().primitive_width())
+            .sum::<Option<usize>>()
+            .map_or(Precision::Absent, Precision::Exact);
+        Statistics::new_unknown(&self.schema)
+            .with_num_rows(num_rows)
+            .with_total_byte_size(num_rows.multiply(&row_size))
+    }
 }
 
 impl DataSource for MemQuadPatternDataSource {
@@ -48,6 +73,13 @@ impl DataSource for MemQuadPatternDataSource {
             return exec_err!("Only partition 0 is supported for now.");
         }
 
+        // Contradicting filters were pushed into the scan. Don't bother scanning the index.
+        if self.planned_scan.is_unsatisfiable() {
+            return Ok(Box::pin(EmptyRecordBatchStream::new(Arc::clone(
+                &self.schema,
+            ))));
+        }
+
         let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
         let result = self.planned_scan.clone().create_stream(baseline_metrics);
         if result.schema() != self.schema {
@@ -70,7 +102,15 @@ impl DataSource for MemQuadPatternDataSource {
     }
 
     fn eq_properties(&self) -> EquivalenceProperties {
-        EquivalenceProperties::new(Arc::clone(&self.schema))
+        let mut eq_properties = EquivalenceProperties::new(Arc::clone(&self.schema));
+        let sort_column = self
+            .planned_scan
+            .sorted_by()
+            .and_then(|variable| Column::new_with_schema(variable, &self.schema).ok());
+        if let Some(column) = sort_column {
+            eq_properties.add_ordering([PhysicalSortExpr::new_default(Arc::new(column))]);
+        }
+        eq_properties
     }
 
     fn scheduling_type(&self) -> SchedulingType {
@@ -78,19 +118,22 @@ impl DataSource for MemQuadPatternDataSource {
     }
 
     fn partition_statistics(&self, _partition: Option<usize>) -> DFResult<Statistics> {
-        Ok(Statistics::new_unknown(&self.schema))
+        Ok(self.compute_statistics())
     }
 
     fn statistics(&self) -> DFResult<Statistics> {
-        Ok(Statistics::new_unknown(&self.schema))
+        Ok(self.compute_statistics())
     }
 
-    fn with_fetch(&self, _limit: Option<usize>) -> Option<Arc<dyn DataSource>> {
-        None
+    fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn DataSource>> {
+        Some(Arc::new(MemQuadPatternDataSource::new(
+            Arc::clone(&self.schema),
+            self.planned_scan.clone().with_fetch(limit),
+        )))
     }
 
     fn fetch(&self) -> Option<usize> {
-        None
+        self.planned_scan.fetch()
     }
 
     fn metrics(&self) -> ExecutionPlanMetricsSet {
@@ -166,15 +209,18 @@ fn apply_pushdown_filters(
 
 #[cfg(test)]
 mod test {
-    use crate::memory::storage::MemQuadPatternDataSource;
     use crate::memory::storage::snapshot::PlanPatternScanResult;
+    use crate::memory::storage::{MemQuadPatternDataSource, ScanOrderHint};
     use crate::memory::{MemObjectIdMapping, MemQuadStorage};
     use datafusion::arrow::datatypes::{DataType, Field, Schema};
     use datafusion::catalog::memory::DataSourceExec;
+    use datafusion::common::stats::Precision;
     use datafusion::config::ConfigOptions;
     use datafusion::datasource::source::DataSource;
+    use datafusion::execution::TaskContext;
     use datafusion::logical_expr::Operator;
-    use datafusion::physical_expr::expressions::{BinaryExpr, Column, Literal};
+    use datafusion::physical_expr::expressions::{BinaryExpr, Column, Literal, in_list};
+    use datafusion::physical_plan::common::collect;
     use datafusion::physical_plan::filter_pushdown::{
         FilterPushdownPropagation, PushedDown,
     };
@@ -213,7 +259,7 @@ mod test {
         assert!(result.updated_node.is_some());
         assert_snapshot!(
             format_quad_pattern(result.updated_node.unwrap()),
-            @"DataSourceExec: [GPOS] subject=?subject, predicate=<http://example.com/test>, object=?object, additional_filters=[object in (2..4294967295)]"
+            @"DataSourceExec: [GPOS] subject=?subject, predicate=<http://example.com/test>, object=?object, additional_filters=[object in (2..18446744073709551615)]"
         )
     }
 
@@ -233,6 +279,68 @@ mod test {
         )
     }
 
+    #[tokio::test]
+    async fn test_filter_pushdown_binds_variable_in_list() {
+        let exec = create_test_pattern().await;
+        let filter_expr = in_list(
+            Arc::new(Column::new("object", 1)),
+            vec![
+                Arc::new(Literal::new(ScalarValue::UInt32(Some(7)))),
+                Arc::new(Literal::new(ScalarValue::UInt32(Some(3)))),
+            ],
+            &false,
+            &exec.schema,
+        )
+        .unwrap();
+        let result = execute_filter_pushdown(exec, filter_expr);
+
+        assert!(result.filters.iter().all(|f| matches!(f, PushedDown::Yes)));
+        assert!(result.updated_node.is_some());
+        assert_snapshot!(
+            format_quad_pattern(result.updated_node.unwrap()),
+            @"DataSourceExec: [GPOS] subject=?subject, predicate=<http://example.com/test>, object=?object, additional_filters=[object in (3, 7)]"
+        )
+    }
+
+    #[tokio::test]
+    async fn test_filter_pushdown_detects_conflicting_filters() {
+        let exec = create_test_pattern().await;
+        let eq_1 = create_object_filter_expr(Operator::Eq, 1);
+        let eq_2 = create_object_filter_expr(Operator::Eq, 2);
+        let result = exec
+            .try_pushdown_filters(vec![eq_1, eq_2], &ConfigOptions::default())
+            .unwrap();
+
+        assert!(result.filters.iter().all(|f| matches!(f, PushedDown::Yes)));
+        let updated_node = result.updated_node.unwrap();
+        assert_eq!(
+            updated_node.partition_statistics(None).unwrap().num_rows,
+            Precision::Exact(0)
+        );
+        let stream = updated_node
+            .open(0, Arc::new(TaskContext::default()))
+            .unwrap();
+        assert!(collect(stream).await.unwrap().is_empty());
+        assert_snapshot!(
+            format_quad_pattern(updated_node),
+            @"DataSourceExec: [GPOS] subject=?subject, predicate=<http://example.com/test>, object=?object, additional_filters=[object false]"
+        )
+    }
+
+    #[tokio::test]
+    async fn test_order_hint_provides_output_ordering() {
+        let exec = create_test_pattern().await;
+        assert!(exec.eq_properties().output_ordering().is_none());
+
+        let ordered = MemQuadPatternDataSource::new(
+            Arc::clone(&exec.schema),
+            exec.planned_scan.with_order_hint(ScanOrderHint::Object),
+        );
+
+        let ordering = ordered.eq_properties().output_ordering().unwrap();
+        assert_eq!(ordering.to_string(), "object@1 ASC");
+    }
+
     /// Creates a new [MemQuadPatternDataSource] for the pattern (?subject <...> ?object) and no graph
     /// variable.
     async fn create_test_pattern() -> MemQuadPatternDataSource {
@@ -249,14 +357,16 @@ mod test {
         };
 
         let object_id_mapping = Arc::new(MemObjectIdMapping::default());
-        object_id_mapping.encode_term_intern(TermRef::NamedNode(
-            NamedNodeRef::new_unchecked("http://example.com/test"),
-        ));
+        object_id_mapping
+            .encode_term_intern(TermRef::NamedNode(NamedNodeRef::new_unchecked(
+                "http://example.com/test",
+            )))
+            .unwrap();
 
         let encoding = Arc::new(ObjectIdEncoding::new(
             Arc::clone(&object_id_mapping) as Arc<dyn ObjectIdMapping>
         ));
-        let index = MemQuadStorage::new(object_id_mapping, encoding, 10);
+        let index = MemQuadStorage::new(object_id_mapping, encoding, 10).unwrap();
         let planned_scan = index
             .snapshot()
             .await
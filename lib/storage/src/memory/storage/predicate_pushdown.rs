@@ -6,7 +6,7 @@ use datafusion::common::{ScalarValue, exec_datafusion_err, exec_err};
 use datafusion::logical_expr::Operator;
 use datafusion::physical_expr::PhysicalExpr;
 use datafusion::physical_expr::expressions::{
-    BinaryExpr, Column, DynamicFilterPhysicalExpr, Literal,
+    BinaryExpr, CaseExpr, Column, DynamicFilterPhysicalExpr, InListExpr, Literal,
 };
 use rdf_fusion_model::DFResult;
 use std::any::Any;
@@ -70,6 +70,8 @@ pub enum MemStoragePredicateExpr {
     Binary(Arc<str>, PredicateExprOperator, EncodedObjectId),
     /// Checks that a column is between two object ids.
     Between(Arc<str>, EncodedObjectId, EncodedObjectId),
+    /// Checks that a column is equal to one of the object ids.
+    In(Arc<str>, BTreeSet<EncodedObjectId>),
     /// Holds a dynamic filter that will be evaluated during query execution.
     Dynamic(Arc<DynamicFilterPhysicalExpr>),
 }
@@ -108,6 +110,7 @@ impl MemStoragePredicateExpr {
             Self::Column(column) => Some(column.as_ref()),
             Self::Binary(column, _, _) => Some(column.as_ref()),
             Self::Between(column, _, _) => Some(column.as_ref()),
+            Self::In(column, _) => Some(column.as_ref()),
             _ => None,
         }
     }
@@ -141,6 +144,11 @@ impl MemStoragePredicateExpr {
                 PredicateExprOperator::Eq => In(BTreeSet::from([*value])),
             }),
             MemStoragePredicateExpr::Between(_, from, to) => Some(Between(*from, *to)),
+            MemStoragePredicateExpr::In(_, ids) => Some(if ids.is_empty() {
+                False
+            } else {
+                In(ids.clone())
+            }),
 
             // For dynamic expressions, we use the current snapshot.
             MemStoragePredicateExpr::Dynamic(dynamic_expr) => dynamic_expr
@@ -172,6 +180,9 @@ pub fn try_rewrite_datafusion_expr(
             ScalarValue::UInt32(Some(value)) => {
                 Some(MemStoragePredicateExpr::ObjectId((*value).into()))
             }
+            ScalarValue::UInt64(Some(value)) => {
+                Some(MemStoragePredicateExpr::ObjectId((*value).into()))
+            }
             ScalarValue::Boolean(Some(true)) => Some(MemStoragePredicateExpr::True),
             _ => None,
         };
@@ -204,13 +215,49 @@ pub fn try_rewrite_datafusion_expr(
                 Some(MemStoragePredicateExpr::Binary(column, op, literal))
             }
             Operator::And => try_rewrite_and_expr(binary),
+            Operator::Or => try_rewrite_union([binary.left(), binary.right()]),
             _ => return None,
         };
     }
 
+    if let Some(in_list) = expr.as_any().downcast_ref::<InListExpr>() {
+        return try_rewrite_in_list_expr(in_list);
+    }
+
+    if let Some(case) = expr.as_any().downcast_ref::<CaseExpr>() {
+        return try_rewrite_case_expr(case);
+    }
+
     None
 }
 
+/// Rewrites an `IN` expression with a list of object ids into a [MemStoragePredicateExpr].
+///
+/// This allows scanning multiple values of a single quad component (e.g., multiple predicates) in
+/// one pass over the index, instead of executing a separate scan for each value.
+fn try_rewrite_in_list_expr(in_list: &InListExpr) -> Option<MemStoragePredicateExpr> {
+    if in_list.negated() {
+        return None;
+    }
+
+    let MemStoragePredicateExpr::Column(column) =
+        try_rewrite_datafusion_expr(in_list.expr())?
+    else {
+        return None;
+    };
+
+    let ids = in_list
+        .list()
+        .iter()
+        .map(|item| match try_rewrite_datafusion_expr(item)? {
+            MemStoragePredicateExpr::ObjectId(id) => Some(id),
+            _ => None,
+        })
+        .collect::<Option<BTreeSet<_>>>()?;
+
+    Some(MemStoragePredicateExpr::In(column, ids))
+}
+
 /// Rewrites a logical and expression into a [MemStoragePredicateExpr].
 ///
 /// If only one side of the and can be rewritten, the other side will be ignored. This approximates
@@ -225,11 +272,17 @@ fn try_rewrite_and_expr(binary: &BinaryExpr) -> Option<MemStoragePredicateExpr>
         (Some(left), None) => Some(left),
         (None, Some(right)) => Some(right),
         (Some(left), Some(right)) => {
-            let MemStoragePredicateExpr::Binary(lhs_column, _, _) = &left else {
-                return None;
+            let lhs_column = match &left {
+                MemStoragePredicateExpr::Binary(column, _, _)
+                | MemStoragePredicateExpr::Between(column, _, _)
+                | MemStoragePredicateExpr::In(column, _) => Arc::clone(column),
+                _ => return None,
             };
-            let MemStoragePredicateExpr::Binary(rhs_column, _, _) = &right else {
-                return None;
+            let rhs_column = match &right {
+                MemStoragePredicateExpr::Binary(column, _, _)
+                | MemStoragePredicateExpr::Between(column, _, _)
+                | MemStoragePredicateExpr::In(column, _) => Arc::clone(column),
+                _ => return None,
             };
 
             if lhs_column != rhs_column {
@@ -239,15 +292,81 @@ fn try_rewrite_and_expr(binary: &BinaryExpr) -> Option<MemStoragePredicateExpr>
             let left = left.to_scan_predicate().ok().flatten()?;
             let right = right.to_scan_predicate().ok().flatten()?;
             match left.try_and_with(&right)? {
-                MemIndexScanPredicate::Between(from, to) => Some(
-                    MemStoragePredicateExpr::Between(Arc::clone(lhs_column), from, to),
-                ),
+                MemIndexScanPredicate::Between(from, to) => {
+                    Some(MemStoragePredicateExpr::Between(lhs_column, from, to))
+                }
+                MemIndexScanPredicate::In(ids) => {
+                    Some(MemStoragePredicateExpr::In(lhs_column, ids))
+                }
                 _ => None,
             }
         }
     }
 }
 
+/// Rewrites a `CASE` expression into a [MemStoragePredicateExpr] that covers all branches.
+///
+/// Hash joins in partitioned mode produce such expressions as dynamic filters. Each branch holds
+/// the bounds of the build side of a single partition and the branch is chosen based on the hash
+/// of the join key. As we cannot evaluate the hash during the index scan, we approximate the
+/// filter with the union of all branches. Branches that are `false` (e.g., empty partitions) do
+/// not contribute to the union.
+fn try_rewrite_case_expr(case: &CaseExpr) -> Option<MemStoragePredicateExpr> {
+    let branches = case
+        .when_then_expr()
+        .iter()
+        .map(|(_, then)| then)
+        .chain(case.else_expr());
+    try_rewrite_union(branches)
+}
+
+/// Rewrites the logical or of all `exprs` into a [MemStoragePredicateExpr].
+///
+/// Contrary to a logical and, all operands must be supported and refer to the same column, as
+/// ignoring an operand could exclude matching object ids. The result may be an
+/// over-approximation (see [MemIndexScanPredicate::try_or_with]).
+fn try_rewrite_union<'a>(
+    exprs: impl IntoIterator<Item = &'a Arc<dyn PhysicalExpr>>,
+) -> Option<MemStoragePredicateExpr> {
+    let mut column: Option<Arc<str>> = None;
+    let mut result = MemIndexScanPredicate::False;
+    for expr in exprs {
+        if is_false_literal(expr) {
+            continue;
+        }
+
+        let expr = try_rewrite_datafusion_expr(expr)?;
+        let expr_column: Arc<str> = expr.column()?.into();
+        match &column {
+            None => column = Some(expr_column),
+            Some(column) if *column != expr_column => return None,
+            Some(_) => {}
+        }
+
+        let predicate = expr.to_scan_predicate().ok()??;
+        result = result.try_or_with(&predicate)?;
+    }
+
+    let column = column?;
+    match result {
+        MemIndexScanPredicate::False => {
+            Some(MemStoragePredicateExpr::In(column, BTreeSet::new()))
+        }
+        MemIndexScanPredicate::In(ids) => Some(MemStoragePredicateExpr::In(column, ids)),
+        MemIndexScanPredicate::Between(from, to) => {
+            Some(MemStoragePredicateExpr::Between(column, from, to))
+        }
+        MemIndexScanPredicate::EqualTo(_) => None,
+    }
+}
+
+/// Returns true if `expr` is the literal `false`.
+fn is_false_literal(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.as_any()
+        .downcast_ref::<Literal>()
+        .is_some_and(|lit| lit.value() == &ScalarValue::Boolean(Some(false)))
+}
+
 /// A Wrapper around a [DynamicFilterPhysicalExpr] that implements [MemIndexScanPredicateSource].
 #[derive(Debug)]
 pub struct DynamicFilterScanPredicateSource(Arc<DynamicFilterPhysicalExpr>);
@@ -296,6 +415,8 @@ impl Display for DynamicFilterScanPredicateSource {
 mod tests {
     use super::*;
     use MemIndexScanPredicate::*;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_expr::expressions::{case, in_list};
 
     #[test]
     fn test_column_predicate() {
@@ -361,8 +482,8 @@ mod tests {
         };
 
         assert_eq!(column.as_ref(), "subject");
-        assert_eq!(from.as_u32(), 124);
-        assert_eq!(to.as_u32(), 456);
+        assert_eq!(from.as_u64(), 124);
+        assert_eq!(to.as_u64(), 456);
     }
 
     #[test]
@@ -382,7 +503,164 @@ mod tests {
 
         let result = try_rewrite_datafusion_expr(&expr);
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_in_list_predicate() {
+        let expr = in_list_expr("predicate", &[3, 1, 2], false);
+
+        let result = try_rewrite_datafusion_expr(&expr).unwrap();
+        let MemStoragePredicateExpr::In(column, ids) = result else {
+            panic!("Unexpected expr.")
+        };
+
+        assert_eq!(column.as_ref(), "predicate");
+        assert_eq!(
+            ids.iter().map(|id| id.as_u64()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_negated_in_list_unsupported() {
+        let expr = in_list_expr("predicate", &[1, 2], true);
+
+        let result = try_rewrite_datafusion_expr(&expr);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_in_list_and_between_predicate() {
+        let in_expr = in_list_expr("predicate", &[1, 5, 10], false);
+        let lt_expr = Arc::new(BinaryExpr::new(
+            column_expr("predicate"),
+            Operator::Lt,
+            literal_uint(10),
+        )) as Arc<dyn PhysicalExpr>;
+        let expr = Arc::new(BinaryExpr::new(in_expr, Operator::And, lt_expr))
+            as Arc<dyn PhysicalExpr>;
+
+        let result = try_rewrite_datafusion_expr(&expr).unwrap();
+
+        assert_eq!(
+            result.to_scan_predicate().unwrap(),
+            Some(In(BTreeSet::from([
+                EncodedObjectId::from(1u32),
+                EncodedObjectId::from(5u32)
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_range_and_in_list_predicate() {
+        let range_expr = between_expr("subject", 5, 10);
+        let in_expr = in_list_expr("subject", &[5, 7, 10], false);
+        let expr = Arc::new(BinaryExpr::new(range_expr, Operator::And, in_expr))
+            as Arc<dyn PhysicalExpr>;
+
+        let result = try_rewrite_datafusion_expr(&expr).unwrap();
+
+        assert_eq!(
+            result.to_scan_predicate().unwrap(),
+            Some(In(BTreeSet::from([
+                EncodedObjectId::from(5u32),
+                EncodedObjectId::from(7u32),
+                EncodedObjectId::from(10u32)
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_or_predicate_covers_both_ranges() {
+        let expr = Arc::new(BinaryExpr::new(
+            between_expr("subject", 10, 20),
+            Operator::Or,
+            between_expr("subject", 40, 50),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let result = try_rewrite_datafusion_expr(&expr).unwrap();
+
+        assert_eq!(result.column(), Some("subject"));
+        assert_eq!(
+            result.to_scan_predicate().unwrap(),
+            Some(Between(
+                EncodedObjectId::from(10u32),
+                EncodedObjectId::from(50u32)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_or_predicate_with_unsupported_operand() {
+        let unsupported = Arc::new(BinaryExpr::new(
+            column_expr("subject"),
+            Operator::Plus,
+            literal_uint(1),
+        )) as Arc<dyn PhysicalExpr>;
+        let expr = Arc::new(BinaryExpr::new(
+            between_expr("subject", 10, 20),
+            Operator::Or,
+            unsupported,
+        )) as Arc<dyn PhysicalExpr>;
+
+        let result = try_rewrite_datafusion_expr(&expr);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_or_predicate_wrong_column_name() {
+        let expr = Arc::new(BinaryExpr::new(
+            between_expr("subject", 10, 20),
+            Operator::Or,
+            between_expr("object", 40, 50),
+        )) as Arc<dyn PhysicalExpr>;
+
+        let result = try_rewrite_datafusion_expr(&expr);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_partitioned_hash_join_filter_covers_all_partitions() {
+        // Mirrors the dynamic filter of a hash join in partitioned mode.
+        let expr = case(
+            Some(column_expr("partition")),
+            vec![
+                (literal_uint(0), between_expr("subject", 30, 40)),
+                (literal_uint(1), literal_bool(false)),
+                (literal_uint(2), in_list_expr("subject", &[5, 35], false)),
+            ],
+            Some(literal_bool(false)),
+        )
+        .unwrap();
+
+        let result = try_rewrite_datafusion_expr(&expr).unwrap();
+
+        assert_eq!(result.column(), Some("subject"));
+        assert_eq!(
+            result.to_scan_predicate().unwrap(),
+            Some(Between(
+                EncodedObjectId::from(5u32),
+                EncodedObjectId::from(40u32)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_partitioned_hash_join_filter_without_bounds_is_false() {
+        let expr = case(
+            Some(column_expr("partition")),
+            vec![(literal_uint(0), literal_bool(false))],
+            Some(literal_bool(false)),
+        )
+        .unwrap();
+
+        let result = try_rewrite_datafusion_expr(&expr);
+
+        assert!(result.is_none());
     }
 
     #[test]
@@ -405,7 +683,7 @@ mod tests {
 
     #[test]
     fn test_to_scan_predicate_eq_produces_in_predicate() {
-        let obj_id = EncodedObjectId::from(100);
+        let obj_id = EncodedObjectId::from(100u32);
         let expr = MemStoragePredicateExpr::Binary(
             Arc::from("col"),
             PredicateExprOperator::Eq,
@@ -420,7 +698,7 @@ mod tests {
 
     #[test]
     fn test_to_scan_predicate_gt_produces_between() {
-        let obj_id = EncodedObjectId::from(100);
+        let obj_id = EncodedObjectId::from(100u32);
         let expr = MemStoragePredicateExpr::Binary(
             Arc::from("col"),
             PredicateExprOperator::Gt,
@@ -429,13 +707,13 @@ mod tests {
 
         assert_eq!(
             expr.to_scan_predicate().unwrap(),
-            Some(Between(EncodedObjectId::from(101), EncodedObjectId::MAX))
+            Some(Between(EncodedObjectId::from(101u32), EncodedObjectId::MAX))
         );
     }
 
     #[test]
     fn test_to_scan_predicate_geq_produces_between() {
-        let obj_id = EncodedObjectId::from(100);
+        let obj_id = EncodedObjectId::from(100u32);
         let expr = MemStoragePredicateExpr::Binary(
             Arc::from("col"),
             PredicateExprOperator::GtEq,
@@ -444,13 +722,13 @@ mod tests {
 
         assert_eq!(
             expr.to_scan_predicate().unwrap(),
-            Some(Between(EncodedObjectId::from(100), EncodedObjectId::MAX))
+            Some(Between(EncodedObjectId::from(100u32), EncodedObjectId::MAX))
         );
     }
 
     #[test]
     fn test_to_scan_predicate_lt_produces_between() {
-        let value = EncodedObjectId::from(100);
+        let value = EncodedObjectId::from(100u32);
         let expr = MemStoragePredicateExpr::Binary(
             Arc::from("col"),
             PredicateExprOperator::Lt,
@@ -459,13 +737,13 @@ mod tests {
 
         assert_eq!(
             expr.to_scan_predicate().unwrap(),
-            Some(Between(EncodedObjectId::MIN, EncodedObjectId::from(99)))
+            Some(Between(EncodedObjectId::MIN, EncodedObjectId::from(99u32)))
         );
     }
 
     #[test]
     fn test_to_scan_predicate_leq_produces_between() {
-        let value = EncodedObjectId::from(100);
+        let value = EncodedObjectId::from(100u32);
         let expr = MemStoragePredicateExpr::Binary(
             Arc::from("col"),
             PredicateExprOperator::LtEq,
@@ -474,15 +752,15 @@ mod tests {
 
         assert_eq!(
             expr.to_scan_predicate().unwrap(),
-            Some(Between(EncodedObjectId::MIN, EncodedObjectId::from(100)))
+            Some(Between(EncodedObjectId::MIN, EncodedObjectId::from(100u32)))
         );
     }
 
     #[test]
     fn test_to_scan_predicate_between_expr_produces_between() {
         use crate::memory::object_id::EncodedObjectId;
-        let from = EncodedObjectId::from(10);
-        let to = EncodedObjectId::from(20);
+        let from = EncodedObjectId::from(10u32);
+        let to = EncodedObjectId::from(20u32);
         let expr = MemStoragePredicateExpr::Between(Arc::from("col"), from, to);
 
         assert_eq!(expr.to_scan_predicate().unwrap(), Some(Between(from, to)));
@@ -524,7 +802,7 @@ mod tests {
     #[test]
     fn test_to_scan_predicate_objectid_is_err() {
         use crate::memory::object_id::EncodedObjectId;
-        let expr = MemStoragePredicateExpr::ObjectId(EncodedObjectId::from(1));
+        let expr = MemStoragePredicateExpr::ObjectId(EncodedObjectId::from(1u32));
         let result = expr.to_scan_predicate();
         assert!(result.is_err());
     }
@@ -537,6 +815,26 @@ mod tests {
         Arc::new(Literal::new(ScalarValue::UInt32(Some(value))))
     }
 
+    fn in_list_expr(name: &str, values: &[u32], negated: bool) -> Arc<dyn PhysicalExpr> {
+        let schema = Schema::new(vec![Field::new(name, DataType::UInt32, false)]);
+        let list = values.iter().map(|value| literal_uint(*value)).collect();
+        in_list(column_expr(name), list, &negated, &schema).unwrap()
+    }
+
+    fn between_expr(name: &str, from: u32, to: u32) -> Arc<dyn PhysicalExpr> {
+        let gt_eq_expr = Arc::new(BinaryExpr::new(
+            column_expr(name),
+            Operator::GtEq,
+            literal_uint(from),
+        )) as Arc<dyn PhysicalExpr>;
+        let lt_eq_expr = Arc::new(BinaryExpr::new(
+            column_expr(name),
+            Operator::LtEq,
+            literal_uint(to),
+        )) as Arc<dyn PhysicalExpr>;
+        Arc::new(BinaryExpr::new(gt_eq_expr, Operator::And, lt_eq_expr))
+    }
+
     fn literal_bool(value: bool) -> Arc<dyn PhysicalExpr> {
         Arc::new(Literal::new(ScalarValue::Boolean(Some(value))))
     }
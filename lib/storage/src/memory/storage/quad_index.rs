@@ -1,12 +1,13 @@
 use crate::index::{
     EncodedTerm, IndexComponents, IndexQuad, NamedGraphStorage, QuadIndex,
 };
-use crate::memory::object_id::EncodedObjectId;
+use crate::memory::object_id::{EncodedObjectId, ObjectIdWidth};
 use crate::memory::storage::quad_index_data::MemIndexData;
 use crate::memory::storage::scan::{DirectIndexRef, MemQuadIndexScanIterator};
 use crate::memory::storage::scan_instructions::{
     MemIndexPruningPredicate, MemIndexPruningPredicates, MemIndexScanInstructions,
 };
+use itertools::Either;
 use rdf_fusion_encoding::object_id::ObjectIdEncodingRef;
 use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Display, Formatter};
@@ -23,6 +24,14 @@ pub struct MemIndexConfiguration {
     pub components: IndexComponents,
 }
 
+impl MemIndexConfiguration {
+    /// Returns the width of the object ids in the index.
+    pub fn width(&self) -> ObjectIdWidth {
+        ObjectIdWidth::try_from(self.object_id_encoding.object_id_size())
+            .expect("The object id encoding only supports 4-byte and 8-byte ids")
+    }
+}
+
 impl Display for MemIndexConfiguration {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.components)
@@ -47,12 +56,14 @@ impl Display for MemIndexConfiguration {
 /// ```
 ///
 /// The physical representation of the index in detaield in [MemIndexData].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MemQuadIndex {
     /// The index content.
     data: MemIndexData,
     /// The configuration of the index.
     configuration: MemIndexConfiguration,
+    /// If set, the index only holds quads with this predicate.
+    specialized_predicate: Option<EncodedObjectId>,
 }
 
 impl MemQuadIndex {
@@ -65,8 +76,27 @@ impl MemQuadIndex {
             .position(|c| c.gspo_index() == 0)
             .expect("There has to be a graph name");
         Self {
-            data: MemIndexData::new(configuration.batch_size, nullable_position),
+            data: MemIndexData::new(
+                configuration.batch_size,
+                nullable_position,
+                configuration.width(),
+            ),
             configuration,
+            specialized_predicate: None,
+        }
+    }
+
+    /// Creates a new [MemQuadIndex] that only holds the quads with the given `predicate`.
+    ///
+    /// See [IndexPermutations](crate::index::IndexPermutations) on how specialized indexes are
+    /// used.
+    pub fn new_specialized(
+        configuration: MemIndexConfiguration,
+        predicate: EncodedObjectId,
+    ) -> Self {
+        Self {
+            specialized_predicate: Some(predicate),
+            ..Self::new(configuration)
         }
     }
 
@@ -86,17 +116,25 @@ impl MemQuadIndex {
 
 impl QuadIndex for MemQuadIndex {
     type Term = EncodedObjectId;
-    type NamedGraphStorage = HashSet<EncodedObjectId>;
+    type NamedGraphStorage = MemNamedGraphStorage;
     type ScanInstructions = MemIndexScanInstructions;
 
     fn components(&self) -> IndexComponents {
         self.configuration.components
     }
 
+    fn specialized_predicate(&self) -> Option<EncodedObjectId> {
+        self.specialized_predicate
+    }
+
     fn len(&self) -> usize {
         self.data.len()
     }
 
+    fn memory_size(&self) -> usize {
+        self.data.memory_size()
+    }
+
     fn compute_scan_score(&self, instructions: &Self::ScanInstructions) -> usize {
         let pruning_predicates = MemIndexPruningPredicates::from(instructions);
         let mut score = 0;
@@ -129,6 +167,10 @@ impl QuadIndex for MemQuadIndex {
         score
     }
 
+    fn contains(&self, quad: &IndexQuad<EncodedObjectId>) -> bool {
+        self.data.contains(quad)
+    }
+
     fn insert(
         &mut self,
         quads: impl IntoIterator<Item = IndexQuad<EncodedObjectId>>,
@@ -159,6 +201,7 @@ impl QuadIndex for MemQuadIndex {
         self.data = MemIndexData::new(
             self.configuration.batch_size,
             self.data.nullable_position(),
+            self.configuration.width(),
         );
     }
 
@@ -166,28 +209,99 @@ impl QuadIndex for MemQuadIndex {
         let index = self.data.nullable_position();
         self.data.clear_all_with_value_in_column(graph_name, index);
     }
+
+    fn quads(&self) -> Vec<IndexQuad<EncodedObjectId>> {
+        self.data.quads()
+    }
+
+    fn compacted(&self) -> Self {
+        Self {
+            data: self.data.compacted(),
+            configuration: self.configuration.clone(),
+            specialized_predicate: self.specialized_predicate,
+        }
+    }
+}
+
+/// Selects the data structure that holds the named graphs of a
+/// [MemQuadStorage](crate::memory::MemQuadStorage).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NamedGraphStorageKind {
+    /// Stores the named graphs in a hash set. Provides fast membership checks, while the named
+    /// graphs are enumerated in an arbitrary order.
+    #[default]
+    Hash,
+    /// Stores the named graphs in a sorted set. The named graphs are enumerated in the order of
+    /// their object ids (i.e., the order in which the graph names were first encoded), which is
+    /// stable across calls.
+    Sorted,
+}
+
+/// Holds the named graphs of the in-memory storage. See [NamedGraphStorageKind].
+#[derive(Debug, Clone)]
+pub enum MemNamedGraphStorage {
+    /// Backed by a hash set.
+    Hash(HashSet<EncodedObjectId>),
+    /// Backed by a sorted set.
+    Sorted(BTreeSet<EncodedObjectId>),
 }
 
-impl NamedGraphStorage for HashSet<EncodedObjectId> {
+impl MemNamedGraphStorage {
+    /// Creates a new empty [MemNamedGraphStorage] of the given `kind`.
+    pub fn new(kind: NamedGraphStorageKind) -> Self {
+        match kind {
+            NamedGraphStorageKind::Hash => Self::Hash(HashSet::new()),
+            NamedGraphStorageKind::Sorted => Self::Sorted(BTreeSet::new()),
+        }
+    }
+
+    /// Returns the [NamedGraphStorageKind] of this storage.
+    pub fn kind(&self) -> NamedGraphStorageKind {
+        match self {
+            Self::Hash(_) => NamedGraphStorageKind::Hash,
+            Self::Sorted(_) => NamedGraphStorageKind::Sorted,
+        }
+    }
+}
+
+impl Default for MemNamedGraphStorage {
+    fn default() -> Self {
+        Self::new(NamedGraphStorageKind::default())
+    }
+}
+
+impl NamedGraphStorage for MemNamedGraphStorage {
     type Term = EncodedObjectId;
 
     fn contains(&self, graph_name: &Self::Term) -> bool {
-        self.contains(graph_name)
+        match self {
+            Self::Hash(set) => set.contains(graph_name),
+            Self::Sorted(set) => set.contains(graph_name),
+        }
     }
 
     fn iter(&self) -> impl Iterator<Item = Self::Term> {
-        self.iter().copied()
+        match self {
+            Self::Hash(set) => Either::Left(set.iter().copied()),
+            Self::Sorted(set) => Either::Right(set.iter().copied()),
+        }
     }
 
     fn insert(&mut self, graph_name: Self::Term) -> bool {
         if graph_name.is_default_graph() {
             return false;
         }
-        self.insert(graph_name)
+        match self {
+            Self::Hash(set) => set.insert(graph_name),
+            Self::Sorted(set) => set.insert(graph_name),
+        }
     }
 
     fn remove(&mut self, graph_name: &Self::Term) -> bool {
-        self.remove(graph_name)
+        match self {
+            Self::Hash(set) => set.remove(graph_name),
+            Self::Sorted(set) => set.remove(graph_name),
+        }
     }
 }
 
@@ -209,9 +323,9 @@ mod tests {
         let eq = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Scan(
                 Arc::new("g".to_string()),
-                Some(
-                    MemIndexScanPredicate::In([EncodedObjectId::from(10)].into()).into(),
-                ),
+                Some(MemIndexScanPredicate::In(
+                    [EncodedObjectId::from(10u32)].into(),
+                )),
             ),
             MemIndexScanInstruction::Traverse(None),
             MemIndexScanInstruction::Traverse(None),
@@ -242,9 +356,9 @@ mod tests {
             MemIndexScanInstruction::Traverse(None),
             MemIndexScanInstruction::Scan(
                 Arc::new("g".to_string()),
-                Some(
-                    MemIndexScanPredicate::In([EncodedObjectId::from(10)].into()).into(),
-                ),
+                Some(MemIndexScanPredicate::In(
+                    [EncodedObjectId::from(10u32)].into(),
+                )),
             ),
             MemIndexScanInstruction::Traverse(None),
             MemIndexScanInstruction::Traverse(None),
@@ -272,15 +386,15 @@ mod tests {
         let instructions_eq = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Scan(
                 Arc::new("g".to_string()),
-                Some(
-                    MemIndexScanPredicate::In([EncodedObjectId::from(10)].into()).into(),
-                ),
+                Some(MemIndexScanPredicate::In(
+                    [EncodedObjectId::from(10u32)].into(),
+                )),
             ),
             MemIndexScanInstruction::Scan(
                 Arc::new("s".to_string()),
-                Some(
-                    MemIndexScanPredicate::In([EncodedObjectId::from(10)].into()).into(),
-                ),
+                Some(MemIndexScanPredicate::In(
+                    [EncodedObjectId::from(10u32)].into(),
+                )),
             ),
             MemIndexScanInstruction::Scan(Arc::new("p".to_string()), None),
             MemIndexScanInstruction::Scan(Arc::new("o".to_string()), None),
@@ -289,17 +403,14 @@ mod tests {
         let instructions_mixed = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Scan(
                 Arc::new("g".to_string()),
-                Some(MemIndexScanPredicate::EqualTo(Arc::new("x".to_string())).into()),
+                Some(MemIndexScanPredicate::EqualTo(Arc::new("x".to_string()))),
             ),
             MemIndexScanInstruction::Scan(
                 Arc::new("s".to_string()),
-                Some(
-                    MemIndexScanPredicate::Between(
-                        EncodedObjectId::from(1),
-                        EncodedObjectId::from(10),
-                    )
-                    .into(),
-                ),
+                Some(MemIndexScanPredicate::Between(
+                    EncodedObjectId::from(1u32),
+                    EncodedObjectId::from(10u32),
+                )),
             ),
             MemIndexScanInstruction::Scan(Arc::new("p".to_string()), None),
             MemIndexScanInstruction::Scan(Arc::new("o".to_string()), None),
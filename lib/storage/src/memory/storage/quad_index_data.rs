@@ -1,18 +1,19 @@
 use crate::index::IndexQuad;
-use crate::memory::object_id::EncodedObjectId;
+use crate::memory::object_id::{EncodedObjectId, ObjectIdWidth};
 use crate::memory::storage::scan_instructions::{
     MemIndexPruningPredicate, MemIndexPruningPredicates, MemIndexScanInstructions,
     MemIndexScanPredicate,
 };
-use datafusion::arrow::array::{Array, UInt32Array};
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::datatypes::{UInt32Type, UInt64Type};
 use itertools::Itertools;
 use std::collections::BTreeSet;
-use std::sync::Arc;
 
 /// Contains the data of a [MemQuadIndex](super::MemQuadIndex). This is the physical layout of the
 /// index. Analogous to the [MemQuadIndex](super::MemQuadIndex), a single [MemIndexData] represents
 /// exactly one permutation of the quad components. Furthermore, all RDF terms are represented as
-/// [EncodedObjectId]s.
+/// [EncodedObjectId]s. The columns hold `UInt32` or `UInt64` arrays, depending on the
+/// [ObjectIdWidth] of the index.
 ///
 /// The physical layout of the [MemIndexData] is inspired by [Apache Parquet](https://parquet.apache.org/).
 /// Therefore, we also adopt its terminology for row groups and column chunks.
@@ -53,12 +54,14 @@ use std::sync::Arc;
 /// - relatively quickly check whether a quad is contained in the index
 /// - efficiently scan the index for predicates that select a slice of the index (see [MemQuadIndexScanIterator](super::MemQuadIndexScanIterator)
 ///   for further details)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct MemIndexData {
     /// Indicates which column is allowed to contain nullable data (i.e., the graph name)
     nullable_position: usize,
     /// The target row group size
     row_group_size: usize,
+    /// The width of the object ids in the column chunks.
+    width: ObjectIdWidth,
     /// The vector of [MemRowGroup].
     row_groups: Vec<MemRowGroup>,
 }
@@ -84,10 +87,15 @@ pub(super) struct RowGroupPruningResult {
 
 impl MemIndexData {
     /// Creates a new [IndexColumn].
-    pub fn new(batch_size: usize, nullable_position: usize) -> Self {
+    pub fn new(
+        batch_size: usize,
+        nullable_position: usize,
+        width: ObjectIdWidth,
+    ) -> Self {
         Self {
             nullable_position,
             row_group_size: batch_size,
+            width,
             row_groups: Vec::new(),
         }
     }
@@ -105,6 +113,42 @@ impl MemIndexData {
             .sum()
     }
 
+    /// Returns the approximate number of bytes that are used by the row groups of this index.
+    pub fn memory_size(&self) -> usize {
+        self.row_groups
+            .iter()
+            .map(|row_group| row_group.memory_size())
+            .sum()
+    }
+
+    /// Returns all quads in this index in sorted order.
+    pub fn quads(&self) -> Vec<IndexQuad<EncodedObjectId>> {
+        self.row_groups
+            .iter()
+            .flat_map(|row_group| row_group.quads())
+            .collect()
+    }
+
+    /// Creates a compacted copy of this index.
+    ///
+    /// Inserting into and removing from an index can leave row groups that are larger or smaller
+    /// than [Self::row_group_size]. The compacted copy re-partitions all quads into row groups that
+    /// hold exactly [Self::row_group_size] quads (except for the last one).
+    pub fn compacted(&self) -> Self {
+        let quads = self.quads();
+        let row_groups = quads
+            .chunks(self.row_group_size)
+            .map(|chunk| MemRowGroup::new(chunk.iter().collect(), self.width))
+            .collect();
+
+        Self {
+            nullable_position: self.nullable_position,
+            row_group_size: self.row_group_size,
+            width: self.width,
+            row_groups,
+        }
+    }
+
     /// Finds the range of this index that these instructions could match.
     ///
     /// This handles two tasks.
@@ -283,6 +327,18 @@ impl MemIndexData {
         }
     }
 
+    /// Returns whether `quad` is contained in the index.
+    pub fn contains(&self, quad: &IndexQuad<EncodedObjectId>) -> bool {
+        for row_group in &self.row_groups {
+            match row_group.find(quad) {
+                QuadFindResult::Contained => return true,
+                QuadFindResult::Before | QuadFindResult::NotContained => return false,
+                QuadFindResult::After => {}
+            }
+        }
+        false
+    }
+
     /// Insert `to_insert` into the index.
     pub fn insert(&mut self, to_insert: &BTreeSet<IndexQuad<EncodedObjectId>>) -> usize {
         let mut count = 0;
@@ -323,7 +379,7 @@ impl MemIndexData {
 
         for chunk in to_insert.chunks(self.row_group_size).into_iter() {
             let chunk = chunk.collect::<Vec<_>>();
-            let new_row_group = MemRowGroup::new(chunk);
+            let new_row_group = MemRowGroup::new(chunk, self.width);
             count += new_row_group.len();
             self.row_groups.push(new_row_group);
         }
@@ -429,18 +485,18 @@ impl MemRowGroup {
     /// Creates a new [MemRowGroup] with the provided `quads`.
     ///
     /// Assumes that `quads` is sorted.
-    pub fn new(quads: Vec<&IndexQuad<EncodedObjectId>>) -> Self {
+    pub fn new(quads: Vec<&IndexQuad<EncodedObjectId>>, width: ObjectIdWidth) -> Self {
         let column_chunks: [MemColumnChunk; 4] = (0..4)
             .map(|idx| {
                 quads
                     .iter()
                     .map(|quad| {
-                        let v = quad.0[idx].as_u32();
-                        (v != 0).then_some(v)
+                        let v = quad.0[idx];
+                        (v != EncodedObjectId::MIN).then_some(v)
                     })
                     .collect::<Vec<_>>()
             })
-            .map(MemColumnChunk::new)
+            .map(|data| MemColumnChunk::new(data, width))
             .collect::<Vec<_>>()
             .try_into()
             .expect("Should yield 4 columns");
@@ -453,6 +509,11 @@ impl MemRowGroup {
         self.column_chunks[3].len()
     }
 
+    /// The width of the object ids in this row group.
+    fn width(&self) -> ObjectIdWidth {
+        self.column_chunks[3].width()
+    }
+
     /// Inserts the given quads into this [MemRowGroup].
     ///
     /// This method may assume the following:
@@ -461,7 +522,7 @@ impl MemRowGroup {
         let mut new_quads = self.quads();
         new_quads.append(&mut quads);
 
-        let new_data = Self::new(new_quads.iter().collect());
+        let new_data = Self::new(new_quads.iter().collect(), self.width());
         self.column_chunks = new_data.column_chunks;
     }
 
@@ -472,7 +533,7 @@ impl MemRowGroup {
     pub fn remove(&mut self, quads: BTreeSet<IndexQuad<EncodedObjectId>>) {
         let new_quads = self.quads();
         let difference = new_quads.difference(&quads);
-        let new_data = Self::new(difference.collect());
+        let new_data = Self::new(difference.collect(), self.width());
         self.column_chunks = new_data.column_chunks;
     }
 
@@ -516,18 +577,22 @@ impl MemRowGroup {
         QuadFindResult::Contained
     }
 
+    /// Returns the number of bytes that are used by the column chunks of this row group.
+    pub fn memory_size(&self) -> usize {
+        self.column_chunks
+            .iter()
+            .map(|chunk| chunk.data.get_array_memory_size())
+            .sum()
+    }
+
     /// Returns a [BTreeSet] of all quads in this [MemRowGroup].
     fn quads(&self) -> BTreeSet<IndexQuad<EncodedObjectId>> {
-        let n = self.len();
-        (0..n)
-            .map(|i| {
-                IndexQuad([
-                    self.column_chunks[0].data.value(i).into(),
-                    self.column_chunks[1].data.value(i).into(),
-                    self.column_chunks[2].data.value(i).into(),
-                    self.column_chunks[3].data.value(i).into(),
-                ])
-            })
+        let [graphs, subjects, predicates, objects] = self
+            .column_chunks
+            .each_ref()
+            .map(MemColumnChunk::object_ids);
+        (0..self.len())
+            .map(|i| IndexQuad([graphs[i], subjects[i], predicates[i], objects[i]]))
             .collect()
     }
 
@@ -547,7 +612,7 @@ impl MemRowGroup {
     }
 
     /// Returns the [MemColumnChunk] arrays of this row group.
-    pub fn into_arrays(self) -> [Arc<UInt32Array>; 4] {
+    pub fn into_arrays(self) -> [ArrayRef; 4] {
         self.column_chunks.map(|c| c.data)
     }
 }
@@ -571,14 +636,15 @@ pub(super) enum FindRangeResult {
 
 #[derive(Debug, Clone)]
 pub(super) struct MemColumnChunk {
-    data: Arc<UInt32Array>,
+    /// The object ids as `UInt32` or `UInt64` array. Null values represent the default graph.
+    data: ArrayRef,
 }
 
 impl MemColumnChunk {
-    /// Creates a new [MemColumnChunk].
-    pub fn new(data: Vec<Option<u32>>) -> Self {
+    /// Creates a new [MemColumnChunk] that holds `data` with the given `width`.
+    pub fn new(data: Vec<Option<EncodedObjectId>>, width: ObjectIdWidth) -> Self {
         Self {
-            data: Arc::new(UInt32Array::from(data)),
+            data: width.create_array(data),
         }
     }
 
@@ -587,6 +653,31 @@ impl MemColumnChunk {
         self.data.len()
     }
 
+    /// The width of the object ids in this column chunk.
+    pub fn width(&self) -> ObjectIdWidth {
+        ObjectIdWidth::try_from(self.data.data_type()).expect("Checked in constructor")
+    }
+
+    /// Returns all object ids in this column chunk. Null values are returned as the default graph.
+    fn object_ids(&self) -> Vec<EncodedObjectId> {
+        match self.width() {
+            ObjectIdWidth::U32 => self
+                .data
+                .as_primitive::<UInt32Type>()
+                .values()
+                .iter()
+                .map(|v| EncodedObjectId::from(*v))
+                .collect(),
+            ObjectIdWidth::U64 => self
+                .data
+                .as_primitive::<UInt64Type>()
+                .values()
+                .iter()
+                .map(|v| EncodedObjectId::from(*v))
+                .collect(),
+        }
+    }
+
     /// Checks whether the given `value` is part of this [MemColumnChunk].
     ///
     /// See [FindRangeResult] for a list of possible results.
@@ -602,51 +693,21 @@ impl MemColumnChunk {
         from: EncodedObjectId,
         to: EncodedObjectId,
     ) -> FindRangeResult {
-        let values = self.data.values();
-
-        // Fast path for before check
-        if to.as_u32() < *values.first().expect("Chunks are never empty") {
-            return FindRangeResult::Before;
-        }
-
-        // Fast path for after check
-        if values.last().expect("Chunks are never empty") < &from.as_u32() {
-            return FindRangeResult::After;
-        }
-
-        // Fast path for null handling
         let null_count = self.data.null_count();
-        if from.as_u32() == 0 && to.as_u32() == 0 {
-            if null_count == 0 {
-                return FindRangeResult::Before;
-            }
-            return FindRangeResult::Contained(0, null_count);
+        match self.width() {
+            ObjectIdWidth::U32 => find_range_between(
+                self.data.as_primitive::<UInt32Type>().values(),
+                null_count,
+                from.as_u64(),
+                to.as_u64(),
+            ),
+            ObjectIdWidth::U64 => find_range_between(
+                self.data.as_primitive::<UInt64Type>().values(),
+                null_count,
+                from.as_u64(),
+                to.as_u64(),
+            ),
         }
-
-        let values = self.data.values();
-        let find_result = values.iter().position(|v| *v >= from.as_u32());
-        let count_first_larger_value = match find_result {
-            None => unreachable!("Should have been caught by fast path"),
-            Some(position) => {
-                if position == 0 && values[0] > to.as_u32() {
-                    unreachable!("Should have been caught by fast path");
-                } else if values[position] > to.as_u32() {
-                    return FindRangeResult::NotContained(position);
-                } else {
-                    position
-                }
-            }
-        };
-
-        let contained_count = values[count_first_larger_value..]
-            .iter()
-            .take_while(|v| **v <= to.as_u32())
-            .count();
-
-        FindRangeResult::Contained(
-            count_first_larger_value,
-            count_first_larger_value + contained_count,
-        )
     }
 
     /// Returns a new [MemColumnChunk] starting at index `from` and ending at index `to`
@@ -657,11 +718,65 @@ impl MemColumnChunk {
         debug_assert!(from < to, "From must be smaller than to");
         let len = to - from;
         Self {
-            data: Arc::new(self.data.slice(from, len)),
+            data: self.data.slice(from, len),
         }
     }
 }
 
+/// Checks whether any of the sorted `values` fall between `from` and `to`. The first
+/// `null_count` values are nulls that represent the id `0`.
+///
+/// See [FindRangeResult] for a list of possible results.
+fn find_range_between<T: Copy + Into<u64>>(
+    values: &[T],
+    null_count: usize,
+    from: u64,
+    to: u64,
+) -> FindRangeResult {
+    let value = |idx: usize| -> u64 { values[idx].into() };
+
+    // Fast path for before check
+    if to < (*values.first().expect("Chunks are never empty")).into() {
+        return FindRangeResult::Before;
+    }
+
+    // Fast path for after check
+    if (*values.last().expect("Chunks are never empty")).into() < from {
+        return FindRangeResult::After;
+    }
+
+    // Fast path for null handling
+    if from == 0 && to == 0 {
+        if null_count == 0 {
+            return FindRangeResult::Before;
+        }
+        return FindRangeResult::Contained(0, null_count);
+    }
+
+    let find_result = (0..values.len()).find(|idx| value(*idx) >= from);
+    let count_first_larger_value = match find_result {
+        None => unreachable!("Should have been caught by fast path"),
+        Some(position) => {
+            if position == 0 && value(0) > to {
+                unreachable!("Should have been caught by fast path");
+            } else if value(position) > to {
+                return FindRangeResult::NotContained(position);
+            } else {
+                position
+            }
+        }
+    };
+
+    let contained_count = (count_first_larger_value..values.len())
+        .take_while(|idx| value(*idx) <= to)
+        .count();
+
+    FindRangeResult::Contained(
+        count_first_larger_value,
+        count_first_larger_value + contained_count,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -674,34 +789,38 @@ mod tests {
 
     #[test]
     fn test_memcolumnchunk_slice_simple() {
-        let chunk = MemColumnChunk::new(vec![Some(10), Some(20), Some(30), Some(40)]);
-        assert_eq!(chunk.slice(1, 3).data.values(), &[20, 30]);
+        let chunk = column_chunk(vec![Some(10), Some(20), Some(30), Some(40)]);
+        assert_eq!(
+            chunk.slice(1, 3).data.as_primitive::<UInt32Type>().values(),
+            &[20, 30]
+        );
     }
 
     #[test]
     fn test_memcolumnchunk_slice_of_slice() {
-        let chunk = MemColumnChunk::new(vec![
-            Some(5),
-            Some(6),
-            Some(7),
-            Some(8),
-            Some(9),
-            Some(10),
-        ]);
+        let chunk =
+            column_chunk(vec![Some(5), Some(6), Some(7), Some(8), Some(9), Some(10)]);
         let mid_slice = chunk.slice(1, 5); // [6, 7, 8, 9]
-        assert_eq!(mid_slice.slice(1, 3).data.values(), &[7, 8]);
+        assert_eq!(
+            mid_slice
+                .slice(1, 3)
+                .data
+                .as_primitive::<UInt32Type>()
+                .values(),
+            &[7, 8]
+        );
     }
 
     #[test]
     fn test_empty_indexdata() {
-        let index = MemIndexData::new(2, 0);
+        let index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         assert_eq!(index.len(), 0);
         assert_eq!(index.row_groups.len(), 0);
     }
 
     #[test]
     fn test_insert_and_len_single_row_group() {
-        let mut index = MemIndexData::new(4, 0);
+        let mut index = MemIndexData::new(4, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3]);
         index.insert(&items);
 
@@ -712,7 +831,7 @@ mod tests {
 
     #[test]
     fn test_insert_and_len_multiple_row_groups() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([10, 20, 30, 40, 50]);
         index.insert(&items);
 
@@ -723,9 +842,22 @@ mod tests {
         assert_eq!(index.row_groups[2].len(), 1);
     }
 
+    #[test]
+    fn test_contains_across_row_groups() {
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
+        index.insert(&quad_set([10, 20, 30, 40, 50]));
+
+        for quad in quad_set([10, 30, 50]) {
+            assert!(index.contains(&quad));
+        }
+        for quad in quad_set([5, 25, 60]) {
+            assert!(!index.contains(&quad));
+        }
+    }
+
     #[test]
     fn test_insert_empty_set_no_effect() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([]);
         index.insert(&items);
 
@@ -735,7 +867,7 @@ mod tests {
 
     #[test]
     fn test_inserting_multiple_batches_and_content() {
-        let mut index = MemIndexData::new(3, 0);
+        let mut index = MemIndexData::new(3, 0, ObjectIdWidth::U32);
         let items = quad_set([11, 12, 13, 14, 15, 16]);
         index.insert(&items);
 
@@ -744,9 +876,32 @@ mod tests {
         assert_eq!(index.row_groups[1].len(), 3);
     }
 
+    #[test]
+    fn test_compacted_repartitions_row_groups() {
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
+        index.insert(&quad_set([10, 20, 30, 40, 50, 60]));
+        index.remove(&quad_set([20, 30, 50]));
+        assert_eq!(index.row_groups.len(), 3);
+
+        let compacted = index.compacted();
+
+        assert_eq!(compacted.len(), 3);
+        assert_eq!(compacted.row_groups.len(), 2);
+        assert_eq!(compacted.row_groups[0].len(), 2);
+        assert_eq!(compacted.row_groups[1].len(), 1);
+        assert_eq!(
+            compacted
+                .row_groups
+                .iter()
+                .flat_map(|row_group| row_group.quads())
+                .collect::<BTreeSet<_>>(),
+            quad_set([10, 40, 60])
+        );
+    }
+
     #[test]
     fn test_inserting_duplicate_quads() {
-        let mut index = MemIndexData::new(3, 0);
+        let mut index = MemIndexData::new(3, 0, ObjectIdWidth::U32);
         let mut items = quad_set([1, 2, 3]);
         index.insert(&items);
         assert_eq!(index.len(), 3);
@@ -760,7 +915,7 @@ mod tests {
 
     #[test]
     fn test_nullable_indexdata_insert() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([0, 1, 2]);
         index.insert(&items);
     }
@@ -768,12 +923,12 @@ mod tests {
     #[test]
     fn test_memrowgroup_insert_to_empty() {
         let quads: Vec<IndexQuad<EncodedObjectId>> =
-            [10, 20, 30].into_iter().map(|i| quad(i)).collect();
-        let mut group = MemRowGroup::new(vec![]);
+            [10, 20, 30].into_iter().map(quad).collect();
+        let mut group = MemRowGroup::new(vec![], ObjectIdWidth::U32);
 
         group.insert(quads.into_iter().collect());
         let arrays = group.clone().into_arrays();
-        let values = arrays[0].values();
+        let values = arrays[0].as_primitive::<UInt32Type>().values();
         assert_eq!(values, &[10, 20, 30]);
     }
 
@@ -781,14 +936,14 @@ mod tests {
     fn test_memrowgroup_insert_appends_to_existing() {
         // Insert after initial values, nothing overlaps
         let initial: Vec<IndexQuad<EncodedObjectId>> =
-            [10, 20].into_iter().map(|i| quad(i)).collect();
-        let mut group = MemRowGroup::new(initial.iter().collect());
+            [10, 20].into_iter().map(quad).collect();
+        let mut group = MemRowGroup::new(initial.iter().collect(), ObjectIdWidth::U32);
         let new_quads: Vec<IndexQuad<EncodedObjectId>> =
-            [30, 40].into_iter().map(|i| quad(i)).collect();
+            [30, 40].into_iter().map(quad).collect();
 
         group.insert(new_quads.into_iter().collect());
         let arrays = group.clone().into_arrays();
-        let values = arrays[0].values();
+        let values = arrays[0].as_primitive::<UInt32Type>().values();
         assert_eq!(values, &[10, 20, 30, 40]);
     }
 
@@ -796,34 +951,51 @@ mod tests {
     fn test_memrowgroup_insert_inserts_in_middle() {
         // Insert in the middle
         let initial: Vec<IndexQuad<EncodedObjectId>> =
-            [10, 30].into_iter().map(|i| quad(i)).collect();
-        let mut group = MemRowGroup::new(initial.iter().collect());
+            [10, 30].into_iter().map(quad).collect();
+        let mut group = MemRowGroup::new(initial.iter().collect(), ObjectIdWidth::U32);
         let new_quads: Vec<IndexQuad<EncodedObjectId>> =
-            [20].into_iter().map(|i| quad(i)).collect();
+            [20].into_iter().map(quad).collect();
 
         group.insert(new_quads.into_iter().collect());
         let arrays = group.clone().into_arrays();
-        let values = arrays[0].values();
+        let values = arrays[0].as_primitive::<UInt32Type>().values();
         assert_eq!(values, &[10, 20, 30]);
     }
 
+    #[test]
+    fn test_memrowgroup_with_8_byte_ids() {
+        let large = u64::from(u32::MAX) + 1;
+        let quads: Vec<IndexQuad<EncodedObjectId>> = [1, large]
+            .into_iter()
+            .map(|id| IndexQuad([EncodedObjectId::from(id); 4]))
+            .collect();
+        let group = MemRowGroup::new(quads.iter().collect(), ObjectIdWidth::U64);
+
+        assert_eq!(
+            group.find(&IndexQuad([EncodedObjectId::from(large); 4])),
+            QuadFindResult::Contained
+        );
+        let arrays = group.into_arrays();
+        assert_eq!(arrays[0].as_primitive::<UInt64Type>().values(), &[1, large]);
+    }
+
     #[test]
     fn test_memrowgroup_insert_with_nulls() {
         let initial: Vec<IndexQuad<EncodedObjectId>> =
-            [0, 2].into_iter().map(|i| quad(i)).collect();
-        let mut group = MemRowGroup::new(initial.iter().collect());
+            [0, 2].into_iter().map(quad).collect();
+        let mut group = MemRowGroup::new(initial.iter().collect(), ObjectIdWidth::U32);
 
         let new_quads: Vec<IndexQuad<EncodedObjectId>> =
-            [1].into_iter().map(|i| quad(i)).collect();
+            [1].into_iter().map(quad).collect();
         group.insert(new_quads.into_iter().collect());
 
         let arrays = group.clone().into_arrays();
-        assert_eq!(arrays[0].values(), &[0, 1, 2]);
+        assert_eq!(arrays[0].as_primitive::<UInt32Type>().values(), &[0, 1, 2]);
     }
 
     #[test]
     fn test_prune_empty_index() {
-        let index = MemIndexData::new(2, 0);
+        let index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let instructions = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Traverse(None),
             MemIndexScanInstruction::Traverse(None),
@@ -838,7 +1010,7 @@ mod tests {
 
     #[test]
     fn test_prune_no_filter_returns_all_groups() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3, 4]);
         index.insert(&items);
 
@@ -855,7 +1027,7 @@ mod tests {
 
     #[test]
     fn test_prune_filter_single_quad_present() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([10, 20, 30, 40]);
         index.insert(&items);
 
@@ -916,7 +1088,7 @@ mod tests {
     /// It is important that the algorithm stops after the 2nd group (the partial match).
     #[test]
     fn test_prune_filter_partial_match_breaks_early() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -967,7 +1139,7 @@ mod tests {
 
     #[test]
     fn test_prune_filter_breaks_early_on_last_row() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1005,7 +1177,7 @@ mod tests {
 
     #[test]
     fn test_prune_filter_multi_row_groups() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1031,13 +1203,13 @@ mod tests {
 
         let instructions = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Traverse(Some(MemIndexScanPredicate::In(
-                [EncodedObjectId::from(0)].into(),
+                [EncodedObjectId::from(0u32)].into(),
             ))),
             MemIndexScanInstruction::Traverse(Some(MemIndexScanPredicate::In(
-                [EncodedObjectId::from(11)].into(),
+                [EncodedObjectId::from(11u32)].into(),
             ))),
             MemIndexScanInstruction::Traverse(Some(MemIndexScanPredicate::In(
-                [EncodedObjectId::from(10)].into(),
+                [EncodedObjectId::from(10u32)].into(),
             ))),
             MemIndexScanInstruction::Traverse(None),
         ]);
@@ -1052,7 +1224,7 @@ mod tests {
 
     #[test]
     fn test_prune_multiple_filters_start_fixed() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1081,7 +1253,7 @@ mod tests {
 
     #[test]
     fn test_prune_multiple_filters_end_fixed() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1110,7 +1282,7 @@ mod tests {
 
     #[test]
     fn test_prune_filter_single_quad_absent() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3, 4]);
         index.insert(&items);
 
@@ -1129,13 +1301,13 @@ mod tests {
 
     #[test]
     fn test_prune_filter_between_removes_current_instructions_but_retains_rest() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3, 4]);
         index.insert(&items);
 
         let predicate = MemIndexScanPredicate::Between(
-            EncodedObjectId::from(1),
-            EncodedObjectId::from(2),
+            EncodedObjectId::from(1u32),
+            EncodedObjectId::from(2u32),
         );
         let instructions = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Traverse(Some(predicate.clone())),
@@ -1155,13 +1327,13 @@ mod tests {
     #[test]
     fn test_prune_filter_between_with_single_element_also_prunes_following_instructions()
     {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3, 4]);
         index.insert(&items);
 
         let predicate = MemIndexScanPredicate::Between(
-            EncodedObjectId::from(1),
-            EncodedObjectId::from(1),
+            EncodedObjectId::from(1u32),
+            EncodedObjectId::from(1u32),
         );
         let instructions = MemIndexScanInstructions::new_gspo([
             MemIndexScanInstruction::Traverse(Some(predicate.clone())),
@@ -1180,7 +1352,7 @@ mod tests {
 
     #[test]
     fn test_prune_relevant_row_groups_in_predicate_multiple_values_no_pruning() {
-        let mut index = MemIndexData::new(2, 0);
+        let mut index = MemIndexData::new(2, 0, ObjectIdWidth::U32);
         let items = quad_set([1, 2, 3, 4]);
         index.insert(&items);
 
@@ -1200,7 +1372,7 @@ mod tests {
 
     #[test]
     fn test_find_range_all_nulls() {
-        let chunk = MemColumnChunk::new(vec![None, None, None]);
+        let chunk = column_chunk(vec![None, None, None]);
         let value = EncodedObjectId::from(0u32);
         let result = chunk.find_range(value);
         assert_eq!(result, FindRangeResult::Contained(0, 3));
@@ -1208,7 +1380,7 @@ mod tests {
 
     #[test]
     fn test_find_range_nulls_before_data() {
-        let chunk = MemColumnChunk::new(vec![None, None, Some(3), Some(5), Some(7)]);
+        let chunk = column_chunk(vec![None, None, Some(3), Some(5), Some(7)]);
         let result_null = chunk.find_range(EncodedObjectId::from(0u32));
         assert_eq!(result_null, FindRangeResult::Contained(0, 2));
 
@@ -1218,21 +1390,21 @@ mod tests {
 
     #[test]
     fn test_find_range_value_present_single() {
-        let chunk = MemColumnChunk::new(vec![Some(2), Some(4), Some(6)]);
+        let chunk = column_chunk(vec![Some(2), Some(4), Some(6)]);
         let result = chunk.find_range(EncodedObjectId::from(4u32));
         assert_eq!(result, FindRangeResult::Contained(1, 2));
     }
 
     #[test]
     fn test_find_range_value_present_multiple() {
-        let chunk = MemColumnChunk::new(vec![Some(4), Some(4), Some(4), Some(5)]);
+        let chunk = column_chunk(vec![Some(4), Some(4), Some(4), Some(5)]);
         let result = chunk.find_range(EncodedObjectId::from(4u32));
         assert_eq!(result, FindRangeResult::Contained(0, 3));
     }
 
     #[test]
     fn test_find_range_value_not_present_between() {
-        let chunk = MemColumnChunk::new(vec![Some(1), Some(3), Some(5), Some(7)]);
+        let chunk = column_chunk(vec![Some(1), Some(3), Some(5), Some(7)]);
         let result = chunk.find_range(EncodedObjectId::from(4u32));
         // 4 is not present, but would fall between 3 (idx 1) and 5 (idx 2)
         assert_eq!(result, FindRangeResult::NotContained(2));
@@ -1240,7 +1412,7 @@ mod tests {
 
     #[test]
     fn test_find_range_value_too_small_and_too_big() {
-        let chunk = MemColumnChunk::new(vec![Some(10), Some(20), Some(30)]);
+        let chunk = column_chunk(vec![Some(10), Some(20), Some(30)]);
         // Value smaller than any element
         let result_before = chunk.find_range(EncodedObjectId::from(2u32));
         assert_eq!(result_before, FindRangeResult::Before);
@@ -1252,7 +1424,7 @@ mod tests {
 
     #[test]
     fn test_find_quad_contained_complex() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1266,10 +1438,10 @@ mod tests {
         );
 
         let result = index.row_groups[0].find(&IndexQuad([
-            EncodedObjectId::from(1),
-            EncodedObjectId::from(5),
-            EncodedObjectId::from(3),
-            EncodedObjectId::from(4),
+            EncodedObjectId::from(1u32),
+            EncodedObjectId::from(5u32),
+            EncodedObjectId::from(3u32),
+            EncodedObjectId::from(4u32),
         ]));
 
         assert_eq!(QuadFindResult::Contained, result);
@@ -1277,7 +1449,7 @@ mod tests {
 
     #[test]
     fn test_clear_all_with_value_in_column_removes_only_matching() {
-        let mut index = MemIndexData::new(5, 0);
+        let mut index = MemIndexData::new(5, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1290,17 +1462,21 @@ mod tests {
             .collect(),
         );
 
-        index.clear_all_with_value_in_column(EncodedObjectId::from(100), 1);
+        index.clear_all_with_value_in_column(EncodedObjectId::from(100u32), 1);
 
         assert_eq!(index.len(), 2);
-        for value in index.row_groups[0].column_chunks[1].data.values() {
+        for value in index.row_groups[0].column_chunks[1]
+            .data
+            .as_primitive::<UInt32Type>()
+            .values()
+        {
             assert_ne!(*value, 100);
         }
     }
 
     #[test]
     fn test_clear_all_with_value_in_column_no_match_leaves_index_unchanged() {
-        let mut index = MemIndexData::new(3, 0);
+        let mut index = MemIndexData::new(3, 0, ObjectIdWidth::U32);
 
         index.insert(
             &[
@@ -1311,7 +1487,7 @@ mod tests {
             .collect(),
         );
 
-        index.clear_all_with_value_in_column(EncodedObjectId::from(99), 0);
+        index.clear_all_with_value_in_column(EncodedObjectId::from(99u32), 0);
 
         assert_eq!(index.len(), 2);
     }
@@ -1348,4 +1524,15 @@ mod tests {
     ) -> BTreeSet<IndexQuad<EncodedObjectId>> {
         vals.into_iter().map(quad).collect()
     }
+
+    /// Creates a [MemColumnChunk] with 4-byte object ids.
+    fn column_chunk(values: Vec<Option<u32>>) -> MemColumnChunk {
+        MemColumnChunk::new(
+            values
+                .into_iter()
+                .map(|v| v.map(EncodedObjectId::from))
+                .collect(),
+            ObjectIdWidth::U32,
+        )
+    }
 }
@@ -1,4 +1,7 @@
-use crate::index::{IndexComponents, IndexPermutations, QuadIndex, ScanInstructions};
+use crate::index::{
+    IndexComponent, IndexId, IndexPermutations, QuadIndex, ScanInstructions,
+};
+use crate::memory::object_id::{EncodedObjectId, ObjectIdWidth};
 use crate::memory::storage::predicate_pushdown::{
     DynamicFilterScanPredicateSource, MemStoragePredicateExpr,
 };
@@ -10,21 +13,25 @@ use crate::memory::storage::scan_instructions::{
 };
 use crate::memory::storage::stream::MemIndexScanStream;
 use datafusion::arrow::array::{
-    Array, BooleanArray, RecordBatch, RecordBatchOptions, UInt32Array,
+    Array, ArrayRef, BooleanArray, RecordBatch, RecordBatchOptions,
 };
 use datafusion::arrow::compute::kernels::cmp::{eq, gt_eq, lt_eq};
-use datafusion::arrow::compute::{and, filter, or};
+use datafusion::arrow::compute::{
+    and, concat_batches, filter, or, sort_to_indices, take_record_batch,
+};
 use datafusion::arrow::datatypes::{Schema, SchemaRef};
-use datafusion::common::ScalarValue;
+use datafusion::common::exec_datafusion_err;
+use datafusion::common::stats::Precision;
 use datafusion::execution::SendableRecordBatchStream;
 use datafusion::physical_plan::coop::cooperative;
 use datafusion::physical_plan::metrics::BaselineMetrics;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::TryStreamExt;
 use itertools::{Itertools, repeat_n};
 use rdf_fusion_model::{DFResult, TriplePattern, Variable};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
-use tokio::sync::OwnedRwLockReadGuard;
 
 /// The results emitted by the [MemQuadIndexScanIterator].
 pub struct QuadIndexBatch {
@@ -38,6 +45,21 @@ pub struct QuadIndexBatch {
 pub struct MemQuadIndexScanIterator<TIndexRef: IndexRef> {
     /// A reference to the index.
     state: ScanState<TIndexRef>,
+    /// The number of rows that the iterator may still produce. [None] if the number of rows is
+    /// not limited.
+    remaining: Option<usize>,
+}
+
+impl<TIndexRef: IndexRef> MemQuadIndexScanIterator<TIndexRef> {
+    /// Limits the number of rows that the iterator produces to `fetch`.
+    ///
+    /// Once the limit is reached, the iterator finishes without scanning the remaining row groups.
+    pub fn with_fetch(self, fetch: Option<usize>) -> Self {
+        Self {
+            remaining: fetch,
+            ..self
+        }
+    }
 }
 
 impl<'index> MemQuadIndexScanIterator<DirectIndexRef<'index>> {
@@ -52,24 +74,29 @@ impl<'index> MemQuadIndexScanIterator<DirectIndexRef<'index>> {
                 instructions,
                 Vec::new(),
             ),
+            remaining: None,
         }
     }
 }
 
 impl MemQuadIndexScanIterator<IndexRefInSet> {
     /// Creates a new [MemQuadIndexScanIterator].
+    ///
+    /// If `order` is set, the scan only switches to other indexes that preserve this order.
     pub fn new_from_index_set(
-        index_set: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
-        index: IndexComponents,
+        index_set: Arc<IndexPermutations<MemQuadIndex>>,
+        index: IndexId<EncodedObjectId>,
+        order: Option<IndexComponent>,
         instructions: MemIndexScanInstructions,
         dynamic_filters: Vec<Arc<dyn MemIndexScanPredicateSource>>,
     ) -> Self {
         Self {
             state: ScanState::CollectRelevantRowGroups(
-                IndexRefInSet(index_set, index),
+                IndexRefInSet(index_set, index, order),
                 instructions,
                 dynamic_filters,
             ),
+            remaining: None,
         }
     }
 }
@@ -102,6 +129,10 @@ impl<TIndexRef: IndexRef> Iterator for MemQuadIndexScanIterator<TIndexRef> {
     type Item = DFResult<QuadIndexBatch>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            self.state = ScanState::Finished;
+        }
+
         loop {
             match &mut self.state {
                 ScanState::CollectRelevantRowGroups(
@@ -149,10 +180,7 @@ impl<TIndexRef: IndexRef> Iterator for MemQuadIndexScanIterator<TIndexRef> {
                                 .zip(instructions.iter())
                                 .filter_map(|(data, instruction)| match instruction {
                                     Some(MemIndexScanInstruction::Scan(name, _)) => {
-                                        Some((
-                                            name.as_str().to_owned(),
-                                            Arc::clone(data) as Arc<dyn Array>,
-                                        ))
+                                        Some((name.as_str().to_owned(), Arc::clone(data)))
                                     }
                                     _ => None,
                                 })
@@ -163,10 +191,10 @@ impl<TIndexRef: IndexRef> Iterator for MemQuadIndexScanIterator<TIndexRef> {
                                 self.state = ScanState::Finished;
                             }
 
-                            return Some(Ok(QuadIndexBatch {
+                            return Some(Ok(self.apply_fetch(QuadIndexBatch {
                                 num_rows: batch_size,
                                 columns,
-                            }));
+                            })));
                         }
                         Some(selection_vector) => {
                             let columns = batch
@@ -197,10 +225,10 @@ impl<TIndexRef: IndexRef> Iterator for MemQuadIndexScanIterator<TIndexRef> {
                                 continue;
                             }
 
-                            return Some(Ok(QuadIndexBatch {
+                            return Some(Ok(self.apply_fetch(QuadIndexBatch {
                                 num_rows: selection_vector.true_count(),
                                 columns,
-                            }));
+                            })));
                         }
                     }
                 }
@@ -261,8 +289,34 @@ fn combine_instructions_with_dynamic_filters(
 }
 
 impl<TIndexRef: IndexRef> MemQuadIndexScanIterator<TIndexRef> {
+    /// Truncates `batch` to the remaining number of rows. If the limit is reached, the iterator
+    /// finishes.
+    fn apply_fetch(&mut self, batch: QuadIndexBatch) -> QuadIndexBatch {
+        let Some(remaining) = &mut self.remaining else {
+            return batch;
+        };
+
+        let num_rows = batch.num_rows.min(*remaining);
+        *remaining -= num_rows;
+        if *remaining == 0 {
+            self.state = ScanState::Finished;
+        }
+
+        if num_rows == batch.num_rows {
+            return batch;
+        }
+        QuadIndexBatch {
+            num_rows,
+            columns: batch
+                .columns
+                .into_iter()
+                .map(|(name, data)| (name, data.slice(0, num_rows)))
+                .collect(),
+        }
+    }
+
     fn compute_selection_vector(
-        data: &[Arc<UInt32Array>; 4],
+        data: &[ArrayRef; 4],
         instructions: &[Option<MemIndexScanInstruction>; 4],
     ) -> Option<BooleanArray> {
         data.iter()
@@ -290,52 +344,57 @@ impl<TIndexRef: IndexRef> MemQuadIndexScanIterator<TIndexRef> {
     /// over the array and consulting the set. In the future, one could check the size of the
     /// set and switch to the different strategy for large predicates.
     fn apply_predicate(
-        all_data: &[Arc<UInt32Array>; 4],
+        all_data: &[ArrayRef; 4],
         instructions: &[Option<MemIndexScanInstruction>; 4],
-        data: &UInt32Array,
+        data: &ArrayRef,
         predicate: &MemIndexScanPredicate,
     ) -> Option<BooleanArray> {
+        let width = ObjectIdWidth::try_from(data.data_type())
+            .expect("Index columns hold object ids");
+        let scalar = |id: EncodedObjectId| {
+            width
+                .scalar_value(id)
+                .expect("Id fits into the width")
+                .to_scalar()
+                .expect("Object ids can be converted to a Scalar")
+        };
+        let none_matches = || repeat_n(Some(false), data.len()).collect();
+
         match predicate {
-            MemIndexScanPredicate::In(ids) => ids
-                .iter()
-                .map(|id| {
-                    eq(
-                        data,
-                        &ScalarValue::UInt32(Some(id.as_u32())).to_scalar().unwrap(),
-                    )
-                    .expect("Array length must match, Data Types match")
-                })
-                .reduce(|lhs, rhs| or(&lhs, &rhs).expect("Array length must match")),
+            MemIndexScanPredicate::In(ids) => {
+                // Ids that do not fit into the width of the index cannot match.
+                let ids = ids.iter().filter(|id| **id <= width.max()).collect_vec();
+                if ids.is_empty() {
+                    return Some(none_matches());
+                }
+                ids.into_iter()
+                    .map(|id| {
+                        eq(data, &scalar(*id))
+                            .expect("Array length must match, Data Types match")
+                    })
+                    .reduce(|lhs, rhs| or(&lhs, &rhs).expect("Array length must match"))
+            }
             MemIndexScanPredicate::EqualTo(name) => {
                 let index = instructions.iter().position(|i| match i {
                     Some(MemIndexScanInstruction::Scan(var, _)) => var == name,
                     _ => false,
                 })?;
                 Some(
-                    eq(all_data[index].as_ref(), data)
+                    eq(&all_data[index], data)
                         .expect("Array length must match, Data Types match"),
                 )
             }
             MemIndexScanPredicate::Between(from, to) => {
-                let ge = gt_eq(
-                    data,
-                    &ScalarValue::UInt32(Some(from.as_u32()))
-                        .to_scalar()
-                        .expect("UInt32 can be converted to a Scalar"),
-                )
-                .expect("gt_eq supports UInt32");
-                let le = lt_eq(
-                    data,
-                    &ScalarValue::UInt32(Some(to.as_u32()))
-                        .to_scalar()
-                        .expect("UInt32 can be converted to a Scalar"),
-                )
-                .expect("lt_eq supports UInt32");
+                // Bounds beyond the width of the index (e.g., EncodedObjectId::MAX) are clamped.
+                let to = (*to).min(width.max());
+                if *from > to {
+                    return Some(none_matches());
+                }
+                let ge = gt_eq(data, &scalar(*from)).expect("gt_eq supports object ids");
+                let le = lt_eq(data, &scalar(to)).expect("lt_eq supports object ids");
                 Some(and(&ge, &le).expect("Inputs are bools and of same length"))
             }
-            MemIndexScanPredicate::False => {
-                Some(repeat_n(Some(false), data.len()).collect())
-            }
+            MemIndexScanPredicate::False => Some(none_matches()),
         }
     }
 }
@@ -352,18 +411,22 @@ impl MemQuadIndexScanRecordBatchIterator {
     /// Creates a new [MemQuadIndexScanRecordBatchIterator].
     pub fn new(
         schema: SchemaRef,
-        index_set: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
-        index: IndexComponents,
+        index_set: Arc<IndexPermutations<MemQuadIndex>>,
+        index: IndexId<EncodedObjectId>,
+        order: Option<IndexComponent>,
         instructions: MemIndexScanInstructions,
         dynamic_filters: Vec<Arc<dyn MemIndexScanPredicateSource>>,
+        fetch: Option<usize>,
     ) -> Self {
-        let instructions = instructions.reorder(index);
+        let instructions = instructions.reorder(index.components());
         let iterator = MemQuadIndexScanIterator::new_from_index_set(
             index_set,
             index,
+            order,
             instructions.clone(),
             dynamic_filters,
-        );
+        )
+        .with_fetch(fetch);
         MemQuadIndexScanRecordBatchIterator {
             schema,
             inner: iterator,
@@ -415,10 +478,10 @@ fn reorder_result(
 pub struct PlannedPatternScan {
     /// The result schema.
     schema: SchemaRef,
-    /// Holds a read lock on the index set.
-    index_set: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
+    /// The version of the index set that is scanned.
+    index_set: Arc<IndexPermutations<MemQuadIndex>>,
     /// Which index to scan.
-    index: IndexComponents,
+    index: IndexId<EncodedObjectId>,
     /// The instructions to scan the index.
     instructions: Box<MemIndexScanInstructions>,
     /// The graph variable. Used for printing the query plan.
@@ -427,14 +490,18 @@ pub struct PlannedPatternScan {
     pattern: Box<TriplePattern>,
     /// A list of dynamic filters that are applied to the scan.
     dynamic_filters: Vec<Arc<dyn MemIndexScanPredicateSource>>,
+    /// The requested order of the results.
+    order_hint: ScanOrderHint,
+    /// The maximum number of rows that the scan produces.
+    fetch: Option<usize>,
 }
 
 impl PlannedPatternScan {
     /// Creates a new [PlannedPatternScan].
     pub fn new(
         schema: SchemaRef,
-        index_set: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
-        index: IndexComponents,
+        index_set: Arc<IndexPermutations<MemQuadIndex>>,
+        index: IndexId<EncodedObjectId>,
         instructions: Box<MemIndexScanInstructions>,
         graph_variable: Option<Variable>,
         pattern: Box<TriplePattern>,
@@ -447,6 +514,8 @@ impl PlannedPatternScan {
             graph_variable,
             pattern,
             dynamic_filters: vec![],
+            order_hint: ScanOrderHint::Any,
+            fetch: None,
         }
     }
 
@@ -460,8 +529,8 @@ impl PlannedPatternScan {
         self.pattern.as_ref()
     }
 
-    /// Returns a reference to the [IndexComponents] that is used to scan the index.
-    pub fn selected_index(&self) -> &IndexComponents {
+    /// Returns a reference to the [IndexId] of the index that is used to scan.
+    pub fn selected_index(&self) -> &IndexId<EncodedObjectId> {
         &self.index
     }
 
@@ -479,27 +548,164 @@ impl PlannedPatternScan {
         })
     }
 
+    /// Returns true if the scan provably produces no results (see
+    /// [MemIndexScanInstructions::is_unsatisfiable]). Dynamic filters are not considered, as they
+    /// are only known during execution.
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.instructions.is_unsatisfiable()
+    }
+
+    /// Estimates the number of rows that the scan produces.
+    ///
+    /// As the index is fully materialized, the quads that match the fixed prefix of the selected
+    /// index can be counted cheaply by pruning its row groups (see
+    /// [MemIndexData::prune_relevant_row_groups](super::quad_index_data::MemIndexData::prune_relevant_row_groups)).
+    /// For example, the count for an unbound pattern is the number of quads in the active graph.
+    /// The count is exact if the pruning applies all predicates of the scan. Otherwise, it is an
+    /// upper bound and returned as [Precision::Inexact]. Dynamic filters are not considered, as
+    /// they are only known during execution.
+    pub fn estimate_num_rows(&self) -> Precision<usize> {
+        if self.is_unsatisfiable() {
+            return Precision::Exact(0);
+        }
+
+        let Some(index) = self.index_set.find_index(self.index) else {
+            return Precision::Absent;
+        };
+        let instructions = self.instructions.reorder(self.index.components());
+        let pruning_result = index.data().prune_relevant_row_groups(&instructions);
+        let count = pruning_result
+            .row_groups
+            .iter()
+            .map(MemRowGroup::len)
+            .sum::<usize>();
+        let count = self.fetch.map_or(count, |fetch| count.min(fetch));
+
+        let remaining_instructions =
+            pruning_result.new_instructions.unwrap_or(instructions);
+        let is_exact = self.dynamic_filters.is_empty()
+            && remaining_instructions
+                .inner()
+                .iter()
+                .all(|instruction| instruction.predicate().is_none());
+        if is_exact {
+            Precision::Exact(count)
+        } else {
+            Precision::Inexact(count)
+        }
+    }
+
+    /// Returns the requested order of the results.
+    pub fn order_hint(&self) -> ScanOrderHint {
+        self.order_hint
+    }
+
+    /// Requests that the scan produces its results in the order given by `order_hint`.
+    ///
+    /// The scan prefers an index permutation that already provides the requested order. If no
+    /// such index exists for the pattern, the results are sorted explicitly once the scan is
+    /// executed. Note that this requires buffering all results of the scan.
+    pub fn with_order_hint(self, order_hint: ScanOrderHint) -> Self {
+        let index =
+            choose_index_for_order(&self.index_set, &self.instructions, order_hint);
+        Self {
+            index,
+            order_hint,
+            ..self
+        }
+    }
+
+    /// Returns the variable that the results of the scan are sorted by, if any.
+    ///
+    /// This is the variable of the requested [ScanOrderHint]. The results are either sorted
+    /// because the selected index provides the order or because they are sorted explicitly. If
+    /// the requested component is fixed by the pattern, the results are not sorted by any variable.
+    pub fn sorted_by(&self) -> Option<&str> {
+        let order = self.order_hint.component()?;
+        match self.instructions.instruction_for_component(order) {
+            MemIndexScanInstruction::Scan(variable, _) => Some(variable.as_str()),
+            MemIndexScanInstruction::Traverse(_) => None,
+        }
+    }
+
+    /// Returns the maximum number of rows that the scan produces.
+    pub fn fetch(&self) -> Option<usize> {
+        self.fetch
+    }
+
+    /// Limits the number of rows that the scan produces to `fetch`.
+    ///
+    /// Once the limit is reached, the scan stops without looking at the remaining quads. If the
+    /// results must be sorted explicitly, the limit is applied after sorting.
+    pub fn with_fetch(self, fetch: Option<usize>) -> Self {
+        Self { fetch, ..self }
+    }
+
     /// Chooses the new index to scan based on the current instructions.
     pub fn try_find_better_index(self) -> DFResult<Self> {
-        let index = self.index_set.choose_index(&self.instructions);
+        let index =
+            choose_index_for_order(&self.index_set, &self.instructions, self.order_hint);
         Ok(Self { index, ..self })
     }
 
+    /// Returns the variable that the results must be sorted by explicitly.
+    ///
+    /// This is the case if the selected index does not provide the requested order.
+    fn explicit_sort_variable(&self) -> Option<Arc<String>> {
+        let order = self.order_hint.component()?;
+        if self
+            .instructions
+            .is_ordered_by(self.index.components(), order)
+        {
+            return None;
+        }
+
+        match self.instructions.instruction_for_component(order) {
+            MemIndexScanInstruction::Scan(variable, _) => Some(Arc::clone(variable)),
+            MemIndexScanInstruction::Traverse(_) => None,
+        }
+    }
+
     /// Executes the pattern scan and return the [SendableRecordBatchStream] that implements the
     /// scan. The resulting stream will be cooperative.
+    ///
+    /// If the selected index does not provide the requested [ScanOrderHint], the stream sorts
+    /// the results explicitly.
     pub fn create_stream(self, metrics: BaselineMetrics) -> SendableRecordBatchStream {
+        let sort_variable = self.explicit_sort_variable();
+        // Only preserve the order while switching indexes if the index provides the order.
+        let order = match sort_variable {
+            None => self.order_hint.component(),
+            Some(_) => None,
+        };
+
+        // If the results are sorted explicitly, all results must be scanned before the limit
+        // can be applied.
+        let scan_fetch = match sort_variable {
+            None => self.fetch,
+            Some(_) => None,
+        };
+
+        let schema = Arc::clone(&self.schema);
         let iterator = MemQuadIndexScanRecordBatchIterator::new(
             Arc::clone(&self.schema),
             self.index_set,
             self.index,
+            order,
             *self.instructions,
             self.dynamic_filters,
+            scan_fetch,
         );
-        Box::pin(cooperative(MemIndexScanStream::new(
+        let stream = Box::pin(cooperative(MemIndexScanStream::new(
             self.schema,
             iterator,
             metrics,
-        )))
+        )));
+
+        match sort_variable {
+            None => stream,
+            Some(variable) => sort_stream(schema, stream, variable, self.fetch),
+        }
     }
 
     /// Returns a new [PlannedPatternScan] with the given `filter`.
@@ -542,10 +748,89 @@ impl Display for PlannedPatternScan {
             )?;
         }
 
+        if let Some(order) = self.order_hint.component() {
+            write!(f, ", order={order}")?;
+            if self.explicit_sort_variable().is_some() {
+                write!(f, " (explicit sort)")?;
+            }
+        }
+
+        if let Some(fetch) = self.fetch {
+            write!(f, ", fetch={fetch}")?;
+        }
+
         Ok(())
     }
 }
 
+/// A hint for the order in which a [PlannedPatternScan] should produce its results.
+///
+/// Orders refer to the encoded object ids of the terms. As a result, the results are grouped by
+/// the requested component but not sorted by the lexical value of the terms. The order is only
+/// guaranteed within a single graph, unless the graph is fixed by the pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScanOrderHint {
+    /// The results can be produced in any order. The scan uses the best index for the pattern.
+    #[default]
+    Any,
+    /// The results should be ordered by the subject.
+    Subject,
+    /// The results should be ordered by the predicate.
+    Predicate,
+    /// The results should be ordered by the object.
+    Object,
+}
+
+impl ScanOrderHint {
+    /// Returns the [IndexComponent] that the results should be ordered by.
+    fn component(self) -> Option<IndexComponent> {
+        match self {
+            ScanOrderHint::Any => None,
+            ScanOrderHint::Subject => Some(IndexComponent::Subject),
+            ScanOrderHint::Predicate => Some(IndexComponent::Predicate),
+            ScanOrderHint::Object => Some(IndexComponent::Object),
+        }
+    }
+}
+
+/// Chooses the index for the `instructions`, preferring indexes that provide the order requested
+/// by `order_hint`.
+fn choose_index_for_order(
+    index_set: &IndexPermutations<MemQuadIndex>,
+    instructions: &MemIndexScanInstructions,
+    order_hint: ScanOrderHint,
+) -> IndexId<EncodedObjectId> {
+    order_hint
+        .component()
+        .and_then(|order| index_set.choose_index_with_order(instructions, order))
+        .unwrap_or_else(|| index_set.choose_index(instructions))
+}
+
+/// Sorts the entire `stream` by the column of the given `variable`.
+///
+/// This must buffer all results of the stream before emitting a single, sorted batch. If `fetch`
+/// is given, the batch only contains the first `fetch` rows.
+fn sort_stream(
+    schema: SchemaRef,
+    stream: SendableRecordBatchStream,
+    variable: Arc<String>,
+    fetch: Option<usize>,
+) -> SendableRecordBatchStream {
+    let sorted = futures::stream::once({
+        let schema = Arc::clone(&schema);
+        async move {
+            let batches = stream.try_collect::<Vec<_>>().await?;
+            let batch = concat_batches(&schema, &batches)?;
+            let column = batch.column_by_name(&variable).ok_or_else(|| {
+                exec_datafusion_err!("Could not find column for sorting: {variable}")
+            })?;
+            let indices = sort_to_indices(column, None, fetch)?;
+            Ok(take_record_batch(&batch, &indices)?)
+        }
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, sorted))
+}
+
 /// A reference to a [MemQuadIndex].
 pub trait IndexRef {
     /// Returns a reference to the index.
@@ -558,11 +843,14 @@ pub trait IndexRef {
     ) -> Option<Arc<dyn IndexRef>>;
 }
 
-/// Reference to an index in a locked [IndexSet] with its [IndexComponents]. The
-/// [IndexComponents] uniquely identifier an index within an [IndexSet].
+/// Reference to an index in a version of an [IndexSet] with its [IndexId]. The [IndexId] uniquely
+/// identifies an index within an [IndexSet].
+///
+/// If an order is given, only indexes that preserve this order are chosen as better indexes.
 pub struct IndexRefInSet(
-    Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
-    IndexComponents,
+    Arc<IndexPermutations<MemQuadIndex>>,
+    IndexId<EncodedObjectId>,
+    Option<IndexComponent>,
 );
 
 impl IndexRef for IndexRefInSet {
@@ -574,12 +862,15 @@ impl IndexRef for IndexRefInSet {
         &self,
         instructions: &MemIndexScanInstructions,
     ) -> Option<Arc<dyn IndexRef>> {
-        let index = self.0.choose_index(instructions);
+        let index = match self.2 {
+            None => self.0.choose_index(instructions),
+            Some(order) => self.0.choose_index_with_order(instructions, order)?,
+        };
 
         if index == self.1 {
             None
         } else {
-            Some(Arc::new(IndexRefInSet(Arc::clone(&self.0), index)))
+            Some(Arc::new(IndexRefInSet(Arc::clone(&self.0), index, self.2)))
         }
     }
 }
@@ -603,15 +894,16 @@ impl IndexRef for DirectIndexRef<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::index::{EncodedQuad, IndexComponents};
+    use crate::index::{EncodedQuad, IndexComponents, IndexId};
     use crate::memory::MemObjectIdMapping;
     use crate::memory::object_id::EncodedObjectId;
     use crate::memory::storage::predicate_pushdown::MemStoragePredicateExpr;
-    use crate::memory::storage::quad_index::MemIndexConfiguration;
+    use crate::memory::storage::quad_index::{
+        MemIndexConfiguration, MemNamedGraphStorage,
+    };
     use rdf_fusion_encoding::object_id::{ObjectIdEncoding, ObjectIdMapping};
-    use std::collections::{BTreeSet, HashSet};
+    use std::collections::BTreeSet;
     use std::sync::Mutex;
-    use tokio::sync::RwLock;
 
     #[tokio::test]
     async fn test_dynamic_filters() {
@@ -625,7 +917,8 @@ mod tests {
             batch_size: 100,
             components: IndexComponents::GSPO,
         });
-        let mut index = IndexPermutations::new(HashSet::new(), vec![index]);
+        let mut index =
+            IndexPermutations::new(MemNamedGraphStorage::default(), vec![index]);
 
         index
             .insert(&[
@@ -635,7 +928,7 @@ mod tests {
                 quad(0, 3, 10, 100),
             ])
             .unwrap();
-        let index = Arc::new(RwLock::new(index));
+        let index = Arc::new(index);
 
         let dynamic_filter = MockDynamicFilter::new(MemStoragePredicateExpr::Between(
             Arc::from("subject"),
@@ -660,8 +953,9 @@ mod tests {
 
         // Create iterator with the dynamic filter
         let mut iterator = MemQuadIndexScanIterator::new_from_index_set(
-            Arc::new(index.read_owned().await),
-            IndexComponents::GSPO,
+            Arc::clone(&index),
+            IndexId::general(IndexComponents::GSPO),
+            None,
             instructions.clone(),
             vec![Arc::clone(&dynamic_filter) as Arc<dyn MemIndexScanPredicateSource>],
         );
@@ -688,11 +982,13 @@ mod tests {
             batch_size: 100,
             components: IndexComponents::GOSP,
         });
-        let mut index =
-            IndexPermutations::new(HashSet::new(), vec![gspo_index, gosp_index]);
+        let mut index = IndexPermutations::new(
+            MemNamedGraphStorage::default(),
+            vec![gspo_index, gosp_index],
+        );
 
         index.insert(&[quad(0, 1, 10, 100)]).unwrap();
-        let index = Arc::new(RwLock::new(index));
+        let index = Arc::new(index);
 
         // Create a dynamic filter that starts with "True" (matches everything)
         let dynamic_filter = MockDynamicFilter::new(MemStoragePredicateExpr::Between(
@@ -713,7 +1009,11 @@ mod tests {
 
         // Create iterator with the dynamic filter
         let pruning_result = collect_relevant_row_groups(
-            &IndexRefInSet(Arc::new(index.read_owned().await), IndexComponents::GSPO),
+            &IndexRefInSet(
+                Arc::clone(&index),
+                IndexId::general(IndexComponents::GSPO),
+                None,
+            ),
             &instructions,
             &[Arc::clone(&dynamic_filter) as Arc<dyn MemIndexScanPredicateSource>],
         )
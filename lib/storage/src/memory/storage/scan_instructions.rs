@@ -1,4 +1,4 @@
-use crate::index::{IndexComponents, ScanInstructions};
+use crate::index::{IndexComponent, IndexComponents, ScanInstructions};
 use crate::memory::encoding::{EncodedActiveGraph, EncodedTermPattern};
 use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedObjectId};
 use crate::memory::storage::predicate_pushdown::MemStoragePredicateExpr;
@@ -94,6 +94,29 @@ impl MemIndexScanInstructions {
         Self(self.0, new_instructions)
     }
 
+    /// Returns the [MemIndexScanInstruction] for the given `component`.
+    pub fn instruction_for_component(
+        &self,
+        component: IndexComponent,
+    ) -> &MemIndexScanInstruction {
+        let index = self
+            .0
+            .inner()
+            .iter()
+            .position(|c| *c == component)
+            .expect("All components must exist");
+        &self.1[index]
+    }
+
+    /// Returns true if the instructions provably match no quads. This is the case if any
+    /// instruction has a [MemIndexScanPredicate::False] predicate (e.g., after intersecting
+    /// disjoint sets of object ids).
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.1
+            .iter()
+            .any(|i| matches!(i.predicate(), Some(MemIndexScanPredicate::False)))
+    }
+
     /// Applies a new `predicate` expression to the instructions.
     ///
     /// This will find the corresponding "scan" instruction that scans the column of the `predicate` and logically
@@ -134,6 +157,8 @@ impl MemIndexScanInstructions {
 }
 
 impl ScanInstructions for MemIndexScanInstructions {
+    type Term = EncodedObjectId;
+
     fn reorder(&self, components: IndexComponents) -> Self {
         let mut reordered = Vec::new();
         for component in components.inner() {
@@ -150,6 +175,28 @@ impl ScanInstructions for MemIndexScanInstructions {
             reordered.try_into().expect("Should yield 4 instructions"),
         )
     }
+
+    fn bound_predicate(&self) -> Option<EncodedObjectId> {
+        match self
+            .instruction_for_component(IndexComponent::Predicate)
+            .predicate()
+        {
+            Some(MemIndexScanPredicate::In(set)) if set.len() == 1 => {
+                set.first().copied()
+            }
+            _ => None,
+        }
+    }
+
+    fn is_fixed(&self, component: IndexComponent) -> bool {
+        match self.instruction_for_component(component).predicate() {
+            Some(MemIndexScanPredicate::In(set)) => set.len() == 1,
+            Some(MemIndexScanPredicate::Between(from, to)) => from == to,
+            // An empty scan is trivially ordered.
+            Some(MemIndexScanPredicate::False) => true,
+            _ => false,
+        }
+    }
 }
 
 /// A predicate for filtering object ids.
@@ -208,6 +255,39 @@ impl MemIndexScanPredicate {
         };
         Some(result)
     }
+
+    /// Combines this predicate with `other` using a logical or.
+    ///
+    /// The result may be an over-approximation. For example, the union of two disjoint ranges is
+    /// represented by a single range that covers both. This is sufficient for pruning, as the
+    /// predicate never excludes an object id that matches one of the inputs.
+    pub fn try_or_with(
+        &self,
+        other: &MemIndexScanPredicate,
+    ) -> Option<MemIndexScanPredicate> {
+        use MemIndexScanPredicate::*;
+        let result = match (self, other) {
+            // False is the neutral element.
+            (predicate, False) | (False, predicate) => predicate.clone(),
+
+            // Unite the sets.
+            (In(a), In(b)) => In(a.union(b).cloned().collect()),
+
+            // Compute the range that covers both predicates.
+            (In(a), Between(f, t)) | (Between(f, t), In(a)) => {
+                let from = a.first().map_or(*f, |first| (*first).min(*f));
+                let to = a.last().map_or(*t, |last| (*last).max(*t));
+                Between(from, to)
+            }
+            (Between(from_1, to_1), Between(from_2, to_2)) => {
+                Between((*from_1).min(*from_2), (*to_1).max(*to_2))
+            }
+
+            // Otherwise, return None to indicate that the predicates cannot be combined.
+            _ => return None,
+        };
+        Some(result)
+    }
 }
 
 impl Display for MemIndexScanPredicate {
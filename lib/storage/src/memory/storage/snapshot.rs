@@ -1,40 +1,68 @@
-use crate::index::IndexPermutations;
+use crate::index::{IndexComponent, IndexPermutations, QuadIndex, ScanInstructions};
 use crate::memory::MemObjectIdMapping;
 use crate::memory::encoding::{
     EncodedActiveGraph, EncodedTermPattern, EncodedTriplePattern,
 };
+use crate::memory::object_id::{EncodedObjectId, object_ids_of_array};
+use crate::memory::planner::MemQuadStorePlanner;
 use crate::memory::storage::quad_index::MemQuadIndex;
-use crate::memory::storage::scan::PlannedPatternScan;
+use crate::memory::storage::scan::{
+    MemQuadIndexScanIterator, PlannedPatternScan, ScanOrderHint,
+};
 use crate::memory::storage::scan_instructions::{
-    MemIndexScanInstruction, MemIndexScanInstructions,
+    MemIndexScanInstruction, MemIndexScanInstructions, MemIndexScanPredicate,
 };
+use async_trait::async_trait;
+use datafusion::arrow::array::{RecordBatch, RecordBatchOptions};
 use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::stats::Precision;
+use datafusion::common::{internal_datafusion_err, internal_err};
 use datafusion::execution::SendableRecordBatchStream;
 use datafusion::physical_plan::EmptyRecordBatchStream;
 use datafusion::physical_plan::metrics::BaselineMetrics;
-use rdf_fusion_encoding::QuadStorageEncoding;
-use rdf_fusion_encoding::object_id::UnknownObjectIdError;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_planner::ExtensionPlanner;
+use rdf_fusion_encoding::object_id::{ObjectId, ObjectIdMapping, UnknownObjectIdError};
+use rdf_fusion_encoding::{EncodingArray, QuadStorageEncoding};
+use rdf_fusion_extensions::RdfFusionContextView;
+use rdf_fusion_extensions::storage::{
+    IndexInfo, QuadPosition, QuadStorage, QuadStorageLookups, StorageInfo,
+    StorageOptimizationReport,
+};
 use rdf_fusion_logical::ActiveGraph;
 use rdf_fusion_logical::patterns::compute_schema_for_triple_pattern;
 use rdf_fusion_model::quads::{COL_GRAPH, COL_OBJECT, COL_PREDICATE, COL_SUBJECT};
-use rdf_fusion_model::{BlankNodeMatchingMode, DFResult, NamedNodePattern};
 use rdf_fusion_model::{
-    NamedOrBlankNode, NamedOrBlankNodeRef, TermPattern, TriplePattern, Variable,
+    BlankNodeMatchingMode, DFResult, NamedNodePattern, Quad, QuadRef, StorageError,
+};
+use rdf_fusion_model::{
+    GraphName, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Term, TermPattern, TermRef, TriplePattern, Variable,
 };
+use std::collections::BTreeSet;
 use std::sync::Arc;
-use tokio::sync::OwnedRwLockReadGuard;
 
 /// Provides a snapshot view on the storage. Other transactions can read and write to the storage
 /// without changing the view of the snapshot.
+///
+/// The snapshot also implements [QuadStorage], allowing to evaluate queries against it. In this
+/// case, all write operations return an error.
+///
+/// # Resource Usage
+///
+/// A snapshot references the version of the indexes that was current when it was created. Writers
+/// do not wait for snapshots. Instead, the first modification after creating a snapshot copies the
+/// indexes (copy-on-write) and modifies the copy. The row groups of the indexes are shared between
+/// the versions, such that this copy mostly duplicates the bookkeeping of the indexes (e.g., the
+/// term frequencies). A version is released once the last snapshot that references it is dropped.
 #[derive(Debug, Clone)]
 pub struct MemQuadStorageSnapshot {
     /// The encoding of the storage.
     encoding: QuadStorageEncoding,
     /// Object id mapping
     object_id_mapping: Arc<MemObjectIdMapping>,
-    /// Holds a read lock on the index set. Holding the lock prevents concurrent modifications to
-    /// the index.
-    index_permutations: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
+    /// The version of the index set that is observed by the snapshot.
+    index_permutations: Arc<IndexPermutations<MemQuadIndex>>,
 }
 
 /// The result of a [MemQuadStorageSnapshot::plan_pattern_evaluation].
@@ -58,6 +86,40 @@ impl PlanPatternScanResult {
             }
         }
     }
+
+    /// Estimates the number of rows that the result contains.
+    ///
+    /// See [PlannedPatternScan::estimate_num_rows] for details.
+    pub fn estimate_num_rows(&self) -> Precision<usize> {
+        match self {
+            PlanPatternScanResult::Empty(_) => Precision::Exact(0),
+            PlanPatternScanResult::PatternScan(scan) => scan.estimate_num_rows(),
+        }
+    }
+
+    /// Requests that the results are produced in the order given by `order_hint`.
+    ///
+    /// See [PlannedPatternScan::with_order_hint] for details.
+    pub fn with_order_hint(self, order_hint: ScanOrderHint) -> Self {
+        match self {
+            PlanPatternScanResult::Empty(schema) => PlanPatternScanResult::Empty(schema),
+            PlanPatternScanResult::PatternScan(scan) => {
+                PlanPatternScanResult::PatternScan(scan.with_order_hint(order_hint))
+            }
+        }
+    }
+
+    /// Limits the number of rows that the scan produces to `fetch`.
+    ///
+    /// See [PlannedPatternScan::with_fetch] for details.
+    pub fn with_fetch(self, fetch: Option<usize>) -> Self {
+        match self {
+            PlanPatternScanResult::Empty(schema) => PlanPatternScanResult::Empty(schema),
+            PlanPatternScanResult::PatternScan(scan) => {
+                PlanPatternScanResult::PatternScan(scan.with_fetch(fetch))
+            }
+        }
+    }
 }
 
 impl MemQuadStorageSnapshot {
@@ -65,7 +127,7 @@ impl MemQuadStorageSnapshot {
     pub fn new(
         encoding: QuadStorageEncoding,
         object_id_mapping: Arc<MemObjectIdMapping>,
-        index_set: Arc<OwnedRwLockReadGuard<IndexPermutations<MemQuadIndex>>>,
+        index_set: Arc<IndexPermutations<MemQuadIndex>>,
     ) -> Self {
         Self {
             encoding,
@@ -119,6 +181,10 @@ impl MemQuadStorageSnapshot {
             MemIndexScanInstruction::from(enc_pattern.object.clone()),
         ]);
 
+        if scan_instructions.is_unsatisfiable() {
+            return Ok(PlanPatternScanResult::Empty(schema));
+        }
+
         let index = self.index_permutations.choose_index(&scan_instructions);
         Ok(PlanPatternScanResult::PatternScan(PlannedPatternScan::new(
             schema,
@@ -130,6 +196,29 @@ impl MemQuadStorageSnapshot {
         )))
     }
 
+    /// Estimates the number of solutions of the given pattern.
+    ///
+    /// The estimate is based on the index that would be chosen for scanning the pattern (see
+    /// [PlannedPatternScan::estimate_num_rows]). This is used by the query optimizer, for
+    /// example, to order joins by the size of their inputs.
+    pub async fn estimate_pattern_cardinality(
+        &self,
+        active_graph: ActiveGraph,
+        graph_variable: Option<Variable>,
+        pattern: TriplePattern,
+        blank_node_mode: BlankNodeMatchingMode,
+    ) -> DFResult<Precision<usize>> {
+        let result = self
+            .plan_pattern_evaluation(
+                active_graph,
+                graph_variable,
+                pattern,
+                blank_node_mode,
+            )
+            .await?;
+        Ok(result.estimate_num_rows())
+    }
+
     /// Returns a [PlanPatternScanResult] that extracts all quads from the storage from an arbitrary
     /// index.
     pub async fn stream_quads(&self) -> DFResult<PlanPatternScanResult> {
@@ -209,11 +298,445 @@ impl MemQuadStorageSnapshot {
         })
     }
 
+    /// Returns the number of quads that match the given pattern. Unbound components match any
+    /// term.
+    ///
+    /// The quads are counted by scanning the best index for the pattern without materializing
+    /// any terms.
+    pub fn count_matching(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> DFResult<usize> {
+        let active_graph = match graph_name {
+            None => EncodedActiveGraph::AllGraphs,
+            Some(GraphNameRef::DefaultGraph) => EncodedActiveGraph::DefaultGraph,
+            Some(graph_name) => match self
+                .object_id_mapping
+                .try_get_encoded_object_id_from_graph_name(graph_name)
+            {
+                None => return Ok(0),
+                Some(object_id) => EncodedActiveGraph::Union(vec![object_id]),
+            },
+        };
+
+        let (Some(subject), Some(predicate), Some(object)) = (
+            self.count_instruction(subject.map(Into::into)),
+            self.count_instruction(predicate.map(Into::into)),
+            self.count_instruction(object),
+        ) else {
+            // A single unknown term causes the result to be empty.
+            return Ok(0);
+        };
+
+        // Only traverse instructions are used. Hence, the scan does not project any columns.
+        let instructions = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::from_active_graph(&active_graph, None),
+            subject,
+            predicate,
+            object,
+        ]);
+        let index = self.index_permutations.choose_index(&instructions);
+        let instructions = instructions.reorder(index.components());
+        MemQuadIndexScanIterator::new_from_index_set(
+            Arc::clone(&self.index_permutations),
+            index,
+            None,
+            instructions,
+            Vec::new(),
+        )
+        .map(|batch| batch.map(|batch| batch.num_rows))
+        .sum()
+    }
+
+    /// Returns the distinct predicates of the quads with the given `subject`.
+    ///
+    /// The predicates are obtained by scanning an index with the subject as a leading component.
+    /// Only the predicate column is projected and the predicates are deduplicated on their object
+    /// ids. Hence, only the distinct predicates are decoded.
+    pub fn predicates_for(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: NamedOrBlankNodeRef<'_>,
+    ) -> DFResult<Vec<NamedNode>> {
+        let Some(active_graph) = self.encoded_active_graph(graph_name) else {
+            return Ok(Vec::new());
+        };
+        let Some(subject) = self.count_instruction(Some(subject.into())) else {
+            return Ok(Vec::new());
+        };
+
+        let instructions = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::from_active_graph(&active_graph, None),
+            subject,
+            MemIndexScanInstruction::scan(COL_PREDICATE.to_owned()),
+            MemIndexScanInstruction::traverse(),
+        ]);
+        let index = self.index_permutations.choose_index(&instructions);
+        let instructions = instructions.reorder(index.components());
+        let mut predicates = BTreeSet::new();
+        for batch in MemQuadIndexScanIterator::new_from_index_set(
+            Arc::clone(&self.index_permutations),
+            index,
+            None,
+            instructions,
+            Vec::new(),
+        ) {
+            let batch = batch?;
+            let column = batch
+                .columns
+                .get(COL_PREDICATE)
+                .ok_or_else(|| internal_datafusion_err!("Missing predicate column"))?;
+            predicates
+                .extend(object_ids_of_array(column.as_ref())?.into_iter().flatten());
+        }
+
+        predicates
+            .into_iter()
+            .map(
+                |object_id| match self.object_id_mapping.decode_term(object_id) {
+                    Ok(Term::NamedNode(predicate)) => Ok(predicate),
+                    _ => internal_err!("Index contains an invalid predicate."),
+                },
+            )
+            .collect()
+    }
+
+    /// Returns the distinct objects of the quads with the given `predicate`. If `graph_name` is
+    /// [None], quads in all graphs are considered.
+    ///
+    /// The scan uses an index in which the predicate precedes the object such that only the range
+    /// of the predicate is visited. The objects are deduplicated on their object ids and only the
+    /// distinct objects are decoded.
+    pub fn distinct_objects_of(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        predicate: NamedNodeRef<'_>,
+    ) -> DFResult<Vec<Term>> {
+        let Some(active_graph) = self.encoded_active_graph(graph_name) else {
+            return Ok(Vec::new());
+        };
+        let Some(predicate) = self.count_instruction(Some(predicate.into())) else {
+            return Ok(Vec::new());
+        };
+
+        let instructions = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::from_active_graph(&active_graph, None),
+            MemIndexScanInstruction::traverse(),
+            predicate,
+            MemIndexScanInstruction::scan(COL_OBJECT.to_owned()),
+        ]);
+        let index = self.index_permutations.choose_index(&instructions);
+        let instructions = instructions.reorder(index.components());
+        let mut objects = BTreeSet::new();
+        for batch in MemQuadIndexScanIterator::new_from_index_set(
+            Arc::clone(&self.index_permutations),
+            index,
+            None,
+            instructions,
+            Vec::new(),
+        ) {
+            let batch = batch?;
+            let column = batch
+                .columns
+                .get(COL_OBJECT)
+                .ok_or_else(|| internal_datafusion_err!("Missing object column"))?;
+            objects.extend(object_ids_of_array(column.as_ref())?.into_iter().flatten());
+        }
+
+        objects
+            .into_iter()
+            .map(|object_id| Ok(self.object_id_mapping.decode_term(object_id)?))
+            .collect()
+    }
+
+    /// Returns the `k` most frequent terms in the given `position`, together with the number of
+    /// quads that reference them.
+    ///
+    /// The frequencies are maintained by the index permutations. Hence, only the returned terms
+    /// are decoded.
+    pub fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> DFResult<Vec<(Term, usize)>> {
+        let component = match position {
+            QuadPosition::GraphName => IndexComponent::GraphName,
+            QuadPosition::Subject => IndexComponent::Subject,
+            QuadPosition::Predicate => IndexComponent::Predicate,
+            QuadPosition::Object => IndexComponent::Object,
+        };
+        self.index_permutations
+            .term_frequencies()
+            .top_k(component, k)
+            .into_iter()
+            .map(|(object_id, count)| {
+                Ok((self.object_id_mapping.decode_term(object_id)?, count))
+            })
+            .collect()
+    }
+
+    /// Returns the quads that match the given pattern of object ids. Unbound components match
+    /// any term. If `graph_name` is [None], quads in all graphs are returned.
+    ///
+    /// Contrary to the other lookups, the bound components are not encoded. Object ids that are
+    /// unknown to the storage do not match any quad.
+    pub fn quads_for_object_id_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+    ) -> DFResult<Vec<Quad>> {
+        let Some(active_graph) = self.encoded_active_graph(graph_name) else {
+            return Ok(Vec::new());
+        };
+        let (Some(subject), Some(predicate), Some(object)) = (
+            object_id_scan_instruction(COL_SUBJECT, subject),
+            object_id_scan_instruction(COL_PREDICATE, predicate),
+            object_id_scan_instruction(COL_OBJECT, object),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        let instructions = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::from_active_graph(
+                &active_graph,
+                Some(&Variable::new_unchecked(COL_GRAPH)),
+            ),
+            subject,
+            predicate,
+            object,
+        ]);
+        let index = self.index_permutations.choose_index(&instructions);
+        let instructions = instructions.reorder(index.components());
+
+        let mut quads = Vec::new();
+        for batch in MemQuadIndexScanIterator::new_from_index_set(
+            Arc::clone(&self.index_permutations),
+            index,
+            None,
+            instructions,
+            Vec::new(),
+        ) {
+            let batch = batch?;
+            let column = |name: &str| {
+                let column = batch
+                    .columns
+                    .get(name)
+                    .ok_or_else(|| internal_datafusion_err!("Missing {name} column"))?;
+                DFResult::Ok(object_ids_of_array(column.as_ref())?)
+            };
+            let graph_names = column(COL_GRAPH)?;
+            let subjects = column(COL_SUBJECT)?;
+            let predicates = column(COL_PREDICATE)?;
+            let objects = column(COL_OBJECT)?;
+
+            for i in 0..batch.num_rows {
+                quads.push(self.decode_quad([
+                    graph_names[i],
+                    subjects[i],
+                    predicates[i],
+                    objects[i],
+                ])?);
+            }
+        }
+        Ok(quads)
+    }
+
+    /// Returns a stream of the quads that match the given pattern. Unbound components match any
+    /// term. If `graph_name` is [None], quads in all graphs are returned.
+    ///
+    /// The quads are obtained by scanning the best index for the pattern. The scanned object ids
+    /// are decoded batch-wise into the quad schema of [QuadStorageEncoding::PlainTerm].
+    pub fn quads_for_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> SendableRecordBatchStream {
+        let schema = Arc::clone(QuadStorageEncoding::PlainTerm.quad_schema().inner());
+        let empty = || -> SendableRecordBatchStream {
+            Box::pin(EmptyRecordBatchStream::new(Arc::clone(&schema)))
+        };
+
+        let Some(active_graph) = self.encoded_active_graph(graph_name) else {
+            return empty();
+        };
+        let (Some(subject), Some(predicate), Some(object)) = (
+            self.scan_instruction(COL_SUBJECT, subject.map(Into::into)),
+            self.scan_instruction(COL_PREDICATE, predicate.map(Into::into)),
+            self.scan_instruction(COL_OBJECT, object),
+        ) else {
+            // A single unknown term causes the result to be empty.
+            return empty();
+        };
+
+        let instructions = MemIndexScanInstructions::new_gspo([
+            MemIndexScanInstruction::from_active_graph(
+                &active_graph,
+                Some(&Variable::new_unchecked(COL_GRAPH)),
+            ),
+            subject,
+            predicate,
+            object,
+        ]);
+        let index = self.index_permutations.choose_index(&instructions);
+        let instructions = instructions.reorder(index.components());
+        let iterator = MemQuadIndexScanIterator::new_from_index_set(
+            Arc::clone(&self.index_permutations),
+            index,
+            None,
+            instructions,
+            Vec::new(),
+        );
+
+        let object_id_mapping = Arc::clone(&self.object_id_mapping);
+        let batch_schema = Arc::clone(&schema);
+        let batches = iterator.map(move |batch| {
+            let batch = batch?;
+            let columns = [COL_GRAPH, COL_SUBJECT, COL_PREDICATE, COL_OBJECT]
+                .into_iter()
+                .map(|name| {
+                    let column = batch.columns.get(name).ok_or_else(|| {
+                        internal_datafusion_err!("Missing {name} column")
+                    })?;
+                    let terms = object_id_mapping.decode_array(column.as_ref())?;
+                    Ok(terms.into_array_ref())
+                })
+                .collect::<DFResult<Vec<_>>>()?;
+            Ok(RecordBatch::try_new_with_options(
+                Arc::clone(&batch_schema),
+                columns,
+                &RecordBatchOptions::new().with_row_count(Some(batch.num_rows)),
+            )?)
+        });
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches),
+        ))
+    }
+
+    /// Decodes a quad of object ids in the GSPO order. A missing graph name represents the
+    /// default graph.
+    fn decode_quad(
+        &self,
+        [graph_name, subject, predicate, object]: [Option<EncodedObjectId>; 4],
+    ) -> DFResult<Quad> {
+        let graph_name = match graph_name {
+            None => GraphName::DefaultGraph,
+            Some(graph_name) => self
+                .object_id_mapping
+                .decode_named_graph(graph_name)?
+                .into(),
+        };
+        let (Some(subject), Some(predicate), Some(object)) = (subject, predicate, object)
+        else {
+            return internal_err!("Index contains a missing object id.");
+        };
+        let subject = match self.object_id_mapping.decode_term(subject)? {
+            Term::NamedNode(node) => NamedOrBlankNode::NamedNode(node),
+            Term::BlankNode(node) => NamedOrBlankNode::BlankNode(node),
+            Term::Literal(_) => {
+                return internal_err!("Index contains an invalid subject.");
+            }
+        };
+        let Term::NamedNode(predicate) = self.object_id_mapping.decode_term(predicate)?
+        else {
+            return internal_err!("Index contains an invalid predicate.");
+        };
+        let object = self.object_id_mapping.decode_term(object)?;
+        Ok(Quad::new(subject, predicate, object, graph_name))
+    }
+
+    /// Returns the [EncodedActiveGraph] for `graph_name`. Returns [None] if `graph_name` is not
+    /// known to the storage, in which case no quads can match.
+    fn encoded_active_graph(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+    ) -> Option<EncodedActiveGraph> {
+        match graph_name {
+            None => Some(EncodedActiveGraph::AllGraphs),
+            Some(GraphNameRef::DefaultGraph) => Some(EncodedActiveGraph::DefaultGraph),
+            Some(graph_name) => self
+                .object_id_mapping
+                .try_get_encoded_object_id_from_graph_name(graph_name)
+                .map(|object_id| EncodedActiveGraph::Union(vec![object_id])),
+        }
+    }
+
+    /// Returns the [MemIndexScanInstruction] for counting the quads that match `term`. Returns
+    /// [None] if `term` is not known to the storage.
+    fn count_instruction(
+        &self,
+        term: Option<TermRef<'_>>,
+    ) -> Option<MemIndexScanInstruction> {
+        let Some(term) = term else {
+            return Some(MemIndexScanInstruction::traverse());
+        };
+
+        let object_id = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_term(term)?;
+        Some(MemIndexScanInstruction::traverse_with_predicate(
+            MemIndexScanPredicate::In(BTreeSet::from([object_id])),
+        ))
+    }
+
+    /// Returns the [MemIndexScanInstruction] that binds `column` and, if `term` is given,
+    /// restricts it to `term`. Returns [None] if `term` is not known to the storage.
+    fn scan_instruction(
+        &self,
+        column: &str,
+        term: Option<TermRef<'_>>,
+    ) -> Option<MemIndexScanInstruction> {
+        let column = column.to_owned();
+        let Some(term) = term else {
+            return Some(MemIndexScanInstruction::scan(column));
+        };
+
+        let object_id = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_term(term)?;
+        Some(MemIndexScanInstruction::scan_with_predicate(
+            column,
+            MemIndexScanPredicate::In(BTreeSet::from([object_id])),
+        ))
+    }
+
     /// Returns the number of quads in the storage.
     pub fn len(&self) -> usize {
         self.index_permutations.as_ref().len()
     }
 
+    /// Returns statistics on the index permutations of the storage.
+    pub fn storage_info(&self) -> StorageInfo {
+        let indexes = self
+            .index_permutations
+            .indexes()
+            .map(|index| IndexInfo {
+                name: index.components().to_string(),
+                predicate: index.specialized_predicate().map(|predicate| {
+                    match self.object_id_mapping.decode_term(predicate) {
+                        Ok(Term::NamedNode(predicate)) => predicate,
+                        _ => {
+                            unreachable!("Specialized indexes are created for predicates")
+                        }
+                    }
+                }),
+                quad_count: index.len(),
+                memory_size: index.memory_size(),
+            })
+            .collect();
+        StorageInfo {
+            quad_count: self.len(),
+            indexes,
+        }
+    }
+
     /// Returns the number of quads in the storage.
     pub fn named_graphs(&self) -> Vec<NamedOrBlankNode> {
         self.index_permutations
@@ -225,6 +748,11 @@ impl MemQuadStorageSnapshot {
             .expect("Index only contains valid named graphs")
     }
 
+    /// Returns the object ids of the named graphs in the storage.
+    pub(crate) fn encoded_named_graphs(&self) -> Vec<EncodedObjectId> {
+        self.index_permutations.as_ref().named_graphs()
+    }
+
     /// Returns whether the storage contains the named graph `graph_name`.
     pub fn contains_named_graph(&self, graph_name: NamedOrBlankNodeRef<'_>) -> bool {
         let Some(object_id) = self
@@ -274,3 +802,193 @@ fn encode_term_pattern(
         }
     })
 }
+
+/// Returns the [MemIndexScanInstruction] that binds `column` and, if `object_id` is given,
+/// restricts it to `object_id`. Returns [None] if `object_id` is not a valid object id of the
+/// storage.
+fn object_id_scan_instruction(
+    column: &str,
+    object_id: Option<&ObjectId>,
+) -> Option<MemIndexScanInstruction> {
+    let column = column.to_owned();
+    let Some(object_id) = object_id else {
+        return Some(MemIndexScanInstruction::scan(column));
+    };
+
+    let object_id = EncodedObjectId::try_from(object_id).ok()?;
+    Some(MemIndexScanInstruction::scan_with_predicate(
+        column,
+        MemIndexScanPredicate::In(BTreeSet::from([object_id])),
+    ))
+}
+
+#[async_trait]
+impl QuadStorage for MemQuadStorageSnapshot {
+    fn encoding(&self) -> QuadStorageEncoding {
+        self.encoding.clone()
+    }
+
+    fn object_id_mapping(&self) -> Option<Arc<dyn ObjectIdMapping>> {
+        Some(Arc::clone(&self.object_id_mapping) as Arc<dyn ObjectIdMapping>)
+    }
+
+    async fn planners(
+        &self,
+        _context: &RdfFusionContextView,
+    ) -> Vec<Arc<dyn ExtensionPlanner + Send + Sync>> {
+        vec![Arc::new(MemQuadStorePlanner::new(self.clone()))]
+    }
+
+    async fn extend(&self, _quads: Vec<Quad>) -> Result<usize, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn extend_from_batch(
+        &self,
+        _batch: RecordBatch,
+    ) -> Result<usize, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn remove(&self, _quad: QuadRef<'_>) -> Result<bool, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn insert_named_graph<'a>(
+        &self,
+        _graph_name: NamedOrBlankNodeRef<'a>,
+    ) -> Result<bool, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn named_graphs(&self) -> Result<Vec<NamedOrBlankNode>, StorageError> {
+        Ok(MemQuadStorageSnapshot::named_graphs(self))
+    }
+
+    async fn contains_named_graph<'a>(
+        &self,
+        graph_name: NamedOrBlankNodeRef<'a>,
+    ) -> Result<bool, StorageError> {
+        Ok(MemQuadStorageSnapshot::contains_named_graph(
+            self, graph_name,
+        ))
+    }
+
+    async fn clear(&self) -> Result<(), StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn clear_graph<'a>(
+        &self,
+        _graph_name: GraphNameRef<'a>,
+    ) -> Result<(), StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn drop_named_graph(
+        &self,
+        _graph_name: NamedOrBlankNodeRef<'_>,
+    ) -> Result<bool, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn len(&self) -> Result<usize, StorageError> {
+        Ok(MemQuadStorageSnapshot::len(self))
+    }
+
+    fn lookups(&self) -> Option<&dyn QuadStorageLookups> {
+        Some(self)
+    }
+
+    async fn snapshot(&self) -> Result<Option<Arc<dyn QuadStorage>>, StorageError> {
+        Ok(Some(Arc::new(self.clone())))
+    }
+
+    async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError> {
+        Err(read_only_error())
+    }
+
+    async fn validate(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn storage_info(&self) -> Result<Option<StorageInfo>, StorageError> {
+        Ok(Some(MemQuadStorageSnapshot::storage_info(self)))
+    }
+}
+
+/// Returns the error for write operations on a [MemQuadStorageSnapshot].
+fn read_only_error() -> StorageError {
+    StorageError::Other("Cannot modify a read-only snapshot of the storage.".into())
+}
+
+#[async_trait]
+impl QuadStorageLookups for MemQuadStorageSnapshot {
+    async fn count_matching(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<usize, StorageError> {
+        let count = MemQuadStorageSnapshot::count_matching(
+            self, graph_name, subject, predicate, object,
+        )?;
+        Ok(count)
+    }
+
+    async fn predicates_for(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: NamedOrBlankNodeRef<'_>,
+    ) -> Result<Vec<NamedNode>, StorageError> {
+        let predicates =
+            MemQuadStorageSnapshot::predicates_for(self, graph_name, subject)?;
+        Ok(predicates)
+    }
+
+    async fn distinct_objects_of(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        predicate: NamedNodeRef<'_>,
+    ) -> Result<Vec<Term>, StorageError> {
+        let objects =
+            MemQuadStorageSnapshot::distinct_objects_of(self, graph_name, predicate)?;
+        Ok(objects)
+    }
+
+    async fn top_terms(
+        &self,
+        position: QuadPosition,
+        k: usize,
+    ) -> Result<Vec<(Term, usize)>, StorageError> {
+        let terms = MemQuadStorageSnapshot::top_terms(self, position, k)?;
+        Ok(terms)
+    }
+
+    async fn quads_for_object_id_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<&ObjectId>,
+        predicate: Option<&ObjectId>,
+        object: Option<&ObjectId>,
+    ) -> Result<Vec<Quad>, StorageError> {
+        let quads = MemQuadStorageSnapshot::quads_for_object_id_pattern(
+            self, graph_name, subject, predicate, object,
+        )?;
+        Ok(quads)
+    }
+
+    async fn quads_for_pattern(
+        &self,
+        graph_name: Option<GraphNameRef<'_>>,
+        subject: Option<NamedOrBlankNodeRef<'_>>,
+        predicate: Option<NamedNodeRef<'_>>,
+        object: Option<TermRef<'_>>,
+    ) -> Result<SendableRecordBatchStream, StorageError> {
+        let stream = MemQuadStorageSnapshot::quads_for_pattern(
+            self, graph_name, subject, predicate, object,
+        );
+        Ok(stream)
+    }
+}
@@ -2,7 +2,7 @@ use crate::memory::storage::scan::MemQuadIndexScanRecordBatchIterator;
 use datafusion::arrow::array::RecordBatch;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::execution::RecordBatchStream;
-use datafusion::physical_plan::metrics::BaselineMetrics;
+use datafusion::physical_plan::metrics::{BaselineMetrics, RecordOutput};
 use futures::Stream;
 use rdf_fusion_model::DFResult;
 use std::pin::Pin;
@@ -50,10 +50,7 @@ impl Stream for MemIndexScanStream {
 
         if let Some(batch) = batch {
             match batch {
-                Ok(batch) => {
-                    metrics.record_output(batch.num_rows());
-                    Poll::Ready(Some(Ok(batch)))
-                }
+                Ok(batch) => Poll::Ready(Some(Ok(batch.record_output(&metrics)))),
                 Err(err) => Poll::Ready(Some(Err(err))),
             }
         } else {
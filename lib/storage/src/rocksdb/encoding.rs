@@ -0,0 +1,197 @@
+use crate::index::{EncodedQuad, IndexComponent, IndexComponents};
+use crate::memory::object_id::EncodedObjectId;
+use rdf_fusion_model::{BlankNode, CorruptionError, Literal, NamedNode, Term, TermRef};
+
+/// The column family that maps object ids to their serialized term.
+pub(super) const ID2TERM_CF: &str = "id2term";
+/// The column family that holds the object ids of the named graphs.
+pub(super) const GRAPHS_CF: &str = "graphs";
+/// The index permutations that are maintained by the storage.
+pub(super) const INDEXES: [IndexComponents; 3] = [
+    IndexComponents::GSPO,
+    IndexComponents::GPOS,
+    IndexComponents::GOSP,
+];
+
+/// The number of bytes of an encoded object id. The storage uses 4-byte object ids.
+pub(super) const OBJECT_ID_LEN: usize = size_of::<u32>();
+/// The number of bytes of a key in an index column family.
+pub(super) const INDEX_KEY_LEN: usize = 4 * OBJECT_ID_LEN;
+
+const NAMED_NODE_TAG: u8 = 0;
+const BLANK_NODE_TAG: u8 = 1;
+const TYPED_LITERAL_TAG: u8 = 2;
+const LANG_STRING_TAG: u8 = 3;
+
+/// Returns the name of the column family that holds the index with the given `components`.
+pub(super) fn index_cf_name(components: IndexComponents) -> String {
+    components.to_string().to_lowercase()
+}
+
+/// Returns the names of all column families of the storage.
+pub(super) fn column_family_names() -> Vec<String> {
+    [ID2TERM_CF.to_owned(), GRAPHS_CF.to_owned()]
+        .into_iter()
+        .chain(INDEXES.into_iter().map(index_cf_name))
+        .collect()
+}
+
+/// Encodes the `object_id` such that the byte-wise order of the keys matches the order of the
+/// object ids.
+///
+/// The object id mapping of the storage only allocates 4-byte ids. Larger ids (e.g.,
+/// [EncodedObjectId::MAX] as the upper bound of a range) are clamped to the largest 4-byte id.
+pub(super) fn encode_object_id(object_id: EncodedObjectId) -> [u8; OBJECT_ID_LEN] {
+    u32::try_from(object_id.as_u64())
+        .unwrap_or(u32::MAX)
+        .to_be_bytes()
+}
+
+/// Decodes an object id that has been encoded with [encode_object_id].
+pub(super) fn decode_object_id(bytes: &[u8]) -> Result<EncodedObjectId, CorruptionError> {
+    let bytes = <[u8; OBJECT_ID_LEN]>::try_from(bytes)
+        .map_err(|_| CorruptionError::msg("Invalid length of an object id."))?;
+    Ok(EncodedObjectId::from(u32::from_be_bytes(bytes)))
+}
+
+/// Encodes the key of `quad` in the index with the given `components`.
+pub(super) fn encode_index_key(
+    quad: &EncodedQuad<EncodedObjectId>,
+    components: IndexComponents,
+) -> [u8; INDEX_KEY_LEN] {
+    let mut key = [0; INDEX_KEY_LEN];
+    for (i, component) in components.inner().iter().enumerate() {
+        let object_id = match component {
+            IndexComponent::GraphName => quad.graph_name,
+            IndexComponent::Subject => quad.subject,
+            IndexComponent::Predicate => quad.predicate,
+            IndexComponent::Object => quad.object,
+        };
+        key[i * OBJECT_ID_LEN..(i + 1) * OBJECT_ID_LEN]
+            .copy_from_slice(&encode_object_id(object_id));
+    }
+    key
+}
+
+/// Decodes a key of an index column family into the object ids of the quad components. The
+/// object ids are returned in the order of the index.
+pub(super) fn decode_index_key(
+    key: &[u8],
+) -> Result<[EncodedObjectId; 4], CorruptionError> {
+    if key.len() != INDEX_KEY_LEN {
+        return Err(CorruptionError::msg("Invalid length of an index key."));
+    }
+
+    let mut result = [EncodedObjectId::MIN; 4];
+    for (i, chunk) in key.chunks_exact(OBJECT_ID_LEN).enumerate() {
+        result[i] = decode_object_id(chunk)?;
+    }
+    Ok(result)
+}
+
+/// Serializes `term` for the [ID2TERM_CF] column family.
+///
+/// The first byte identifies the kind of the term. Literals store the length of their lexical
+/// value such that the value can be separated from the datatype or language tag.
+pub(super) fn encode_term(term: TermRef<'_>) -> Vec<u8> {
+    match term {
+        TermRef::NamedNode(nn) => tagged(NAMED_NODE_TAG, nn.as_str(), None),
+        TermRef::BlankNode(bnode) => tagged(BLANK_NODE_TAG, bnode.as_str(), None),
+        TermRef::Literal(lit) => match lit.language() {
+            Some(language) => tagged(LANG_STRING_TAG, lit.value(), Some(language)),
+            None => tagged(
+                TYPED_LITERAL_TAG,
+                lit.value(),
+                Some(lit.datatype().as_str()),
+            ),
+        },
+    }
+}
+
+/// Deserializes a term that has been serialized with [encode_term].
+pub(super) fn decode_term(bytes: &[u8]) -> Result<Term, CorruptionError> {
+    let Some((tag, rest)) = bytes.split_first() else {
+        return Err(CorruptionError::msg("Empty term."));
+    };
+
+    Ok(match *tag {
+        NAMED_NODE_TAG => NamedNode::new_unchecked(decode_str(rest)?).into(),
+        BLANK_NODE_TAG => BlankNode::new_unchecked(decode_str(rest)?).into(),
+        TYPED_LITERAL_TAG => {
+            let (value, datatype) = split_literal(rest)?;
+            Literal::new_typed_literal(value, NamedNode::new_unchecked(datatype)).into()
+        }
+        LANG_STRING_TAG => {
+            let (value, language) = split_literal(rest)?;
+            Literal::new_language_tagged_literal_unchecked(value, language).into()
+        }
+        _ => return Err(CorruptionError::msg(format!("Unknown term tag {tag}."))),
+    })
+}
+
+fn tagged(tag: u8, value: &str, suffix: Option<&str>) -> Vec<u8> {
+    let mut result = vec![tag];
+    if let Some(suffix) = suffix {
+        let len = u32::try_from(value.len()).expect("Lexical values are below 4 GiB");
+        result.extend_from_slice(&len.to_be_bytes());
+        result.extend_from_slice(value.as_bytes());
+        result.extend_from_slice(suffix.as_bytes());
+    } else {
+        result.extend_from_slice(value.as_bytes());
+    }
+    result
+}
+
+fn split_literal(bytes: &[u8]) -> Result<(&str, &str), CorruptionError> {
+    let invalid = || CorruptionError::msg("Invalid literal.");
+    let (len, rest) = bytes.split_at_checked(4).ok_or_else(invalid)?;
+    let len = u32::from_be_bytes(len.try_into().map_err(|_| invalid())?) as usize;
+    let (value, suffix) = rest.split_at_checked(len).ok_or_else(invalid)?;
+    Ok((decode_str(value)?, decode_str(suffix)?))
+}
+
+fn decode_str(bytes: &[u8]) -> Result<&str, CorruptionError> {
+    std::str::from_utf8(bytes).map_err(CorruptionError::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdf_fusion_model::vocab::xsd;
+    use rdf_fusion_model::{BlankNodeRef, LiteralRef, NamedNodeRef};
+
+    #[test]
+    fn test_term_roundtrip() {
+        let terms = [
+            TermRef::from(NamedNodeRef::new_unchecked("http://example.com/a")),
+            TermRef::from(BlankNodeRef::new_unchecked("b1")),
+            TermRef::from(LiteralRef::new_typed_literal("1", xsd::INTEGER)),
+            TermRef::from(LiteralRef::new_language_tagged_literal_unchecked(
+                "hello", "en",
+            )),
+        ];
+
+        for term in terms {
+            let decoded = decode_term(&encode_term(term)).unwrap();
+            assert_eq!(decoded.as_ref(), term);
+        }
+    }
+
+    #[test]
+    fn test_index_keys_are_ordered_like_object_ids() {
+        let quad = |subject: u32| EncodedQuad {
+            graph_name: EncodedObjectId::from(0u32),
+            subject: EncodedObjectId::from(subject),
+            predicate: EncodedObjectId::from(2u32),
+            object: EncodedObjectId::from(3u32),
+        };
+
+        let smaller = encode_index_key(&quad(255), IndexComponents::GSPO);
+        let larger = encode_index_key(&quad(256), IndexComponents::GSPO);
+        assert!(smaller < larger);
+        assert_eq!(
+            decode_index_key(&larger).unwrap()[1],
+            EncodedObjectId::from(256u32)
+        );
+    }
+}
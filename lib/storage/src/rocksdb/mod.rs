@@ -0,0 +1,15 @@
+//! A persistent quad storage that is backed by [RocksDB](https://rocksdb.org/).
+//!
+//! The storage maintains the GSPO, GPOS, and GOSP permutations of the quads. Each permutation is
+//! held in a column family whose keys are the big-endian object ids of the quad components in the
+//! order of the permutation. As a result, the keys of a column family are sorted like the quads in
+//! the corresponding in-memory index, and scanning a quad pattern is a range iteration over a key
+//! prefix. The mapping between terms and object ids is persisted in a separate column family.
+//!
+//! This module is only available with the `rocksdb` feature.
+mod encoding;
+mod planner;
+mod scan;
+mod storage;
+
+pub use storage::RocksDbQuadStorage;
@@ -0,0 +1,175 @@
+use crate::memory::MemObjectIdMapping;
+use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedObjectId};
+use crate::rocksdb::scan::{
+    RocksDbQuadScan, RocksDbQuadScanPartition, RocksDbScanInstruction,
+    RocksDbScanPredicate,
+};
+use async_trait::async_trait;
+use datafusion::error::Result as DFResult;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_expr::LexOrdering;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::streaming::StreamingTableExec;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+use rdf_fusion_logical::ActiveGraph;
+use rdf_fusion_logical::quad_pattern::QuadPatternNode;
+use rdf_fusion_model::{BlankNodeMatchingMode, TermPattern, TermRef};
+use rocksdb::DB;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::OwnedRwLockReadGuard;
+
+/// Plans [QuadPatternNode]s with a [RocksDbQuadStorage](super::RocksDbQuadStorage).
+pub struct RocksDbQuadStorePlanner {
+    /// Holds a read lock on the database such that all patterns observe the same state.
+    db: Arc<OwnedRwLockReadGuard<DB>>,
+    /// Used for encoding the terms of the patterns.
+    object_id_mapping: Arc<MemObjectIdMapping>,
+    /// The batch size of the scans.
+    batch_size: usize,
+}
+
+impl RocksDbQuadStorePlanner {
+    /// Creates a new [RocksDbQuadStorePlanner].
+    pub fn new(
+        db: Arc<OwnedRwLockReadGuard<DB>>,
+        object_id_mapping: Arc<MemObjectIdMapping>,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            db,
+            object_id_mapping,
+            batch_size,
+        }
+    }
+
+    /// Creates the [RocksDbScanInstruction]s (in GSPO order) for the given `node`.
+    ///
+    /// Returns [None] if a term of the pattern is unknown. In this case, no quad can match.
+    fn scan_instructions(
+        &self,
+        node: &QuadPatternNode,
+    ) -> Option<[RocksDbScanInstruction; 4]> {
+        let graph = node
+            .graph_variable()
+            .map(|variable| RocksDbScanInstruction::scan(variable.as_str()))
+            .unwrap_or_default()
+            .with_predicate(self.active_graph_predicate(node.active_graph())?);
+
+        let pattern = node.pattern();
+        Some([
+            graph,
+            self.term_pattern_instruction(&pattern.subject, node.blank_node_mode())?,
+            self.term_pattern_instruction(
+                &pattern.predicate.clone().into(),
+                node.blank_node_mode(),
+            )?,
+            self.term_pattern_instruction(&pattern.object, node.blank_node_mode())?,
+        ])
+    }
+
+    /// Returns the predicate on the graph name for the `active_graph`.
+    ///
+    /// Returns [None] if none of the graphs is known.
+    fn active_graph_predicate(
+        &self,
+        active_graph: &ActiveGraph,
+    ) -> Option<Option<RocksDbScanPredicate>> {
+        Some(match active_graph {
+            ActiveGraph::DefaultGraph => {
+                Some(RocksDbScanPredicate::In(BTreeSet::from([
+                    DEFAULT_GRAPH_ID.0
+                ])))
+            }
+            ActiveGraph::AllGraphs => None,
+            ActiveGraph::Union(graphs) => {
+                let object_ids = graphs
+                    .iter()
+                    .filter_map(|g| {
+                        self.object_id_mapping
+                            .try_get_encoded_object_id_from_graph_name(g.as_ref())
+                    })
+                    .map(|g| g.0)
+                    .collect::<BTreeSet<_>>();
+                if object_ids.is_empty() {
+                    return None;
+                }
+                Some(RocksDbScanPredicate::In(object_ids))
+            }
+            ActiveGraph::AnyNamedGraph => Some(RocksDbScanPredicate::Between(
+                DEFAULT_GRAPH_ID
+                    .0
+                    .next()
+                    .expect("Default graph is the smallest id"),
+                EncodedObjectId::MAX,
+            )),
+        })
+    }
+
+    /// Returns the [RocksDbScanInstruction] for the given `pattern`.
+    ///
+    /// Returns [None] if the term of the pattern is unknown.
+    fn term_pattern_instruction(
+        &self,
+        pattern: &TermPattern,
+        blank_node_mode: BlankNodeMatchingMode,
+    ) -> Option<RocksDbScanInstruction> {
+        let term = match pattern {
+            TermPattern::Variable(variable) => {
+                return Some(RocksDbScanInstruction::scan(variable.as_str()));
+            }
+            TermPattern::BlankNode(bnode)
+                if blank_node_mode == BlankNodeMatchingMode::Variable =>
+            {
+                return Some(RocksDbScanInstruction::scan(bnode.as_str()));
+            }
+            TermPattern::NamedNode(nn) => TermRef::from(nn.as_ref()),
+            TermPattern::BlankNode(bnode) => TermRef::from(bnode.as_ref()),
+            TermPattern::Literal(lit) => TermRef::from(lit.as_ref()),
+        };
+
+        let object_id = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_term(term)?;
+        Some(RocksDbScanInstruction::filter(RocksDbScanPredicate::In(
+            BTreeSet::from([object_id]),
+        )))
+    }
+}
+
+#[async_trait]
+impl ExtensionPlanner for RocksDbQuadStorePlanner {
+    /// Converts a logical [QuadPatternNode] into a scan of the best suited index.
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        _physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _session_state: &SessionState,
+    ) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+        let Some(node) = node.as_any().downcast_ref::<QuadPatternNode>() else {
+            return Ok(None);
+        };
+
+        let schema = Arc::clone(node.schema().inner());
+        let scan = self.scan_instructions(node).and_then(|instructions| {
+            RocksDbQuadScan::try_new(Arc::clone(&schema), instructions, self.batch_size)
+        });
+        let Some(scan) = scan else {
+            return Ok(Some(Arc::new(EmptyExec::new(schema))));
+        };
+
+        let partition = RocksDbQuadScanPartition::new(Arc::clone(&self.db), scan);
+        Ok(Some(Arc::new(StreamingTableExec::try_new(
+            schema,
+            vec![Arc::new(partition)],
+            None,
+            Vec::<LexOrdering>::new(),
+            false,
+            None,
+        )?)))
+    }
+}
@@ -0,0 +1,468 @@
+use crate::index::{IndexComponent, IndexComponents};
+use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedObjectId};
+use crate::rocksdb::encoding::{
+    INDEXES, decode_index_key, encode_object_id, index_cf_name,
+};
+use datafusion::arrow::array::{
+    ArrayRef, RecordBatch, RecordBatchOptions, UInt32Builder,
+};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::{DataFusionError, exec_datafusion_err};
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use datafusion::physical_plan::streaming::PartitionStream;
+use futures::Stream;
+use rdf_fusion_model::DFResult;
+use rocksdb::DB;
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::OwnedRwLockReadGuard;
+
+/// Restricts the object ids of a quad component during a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RocksDbScanPredicate {
+    /// The object id must be one of the given object ids.
+    In(BTreeSet<EncodedObjectId>),
+    /// The object id must be within the given (inclusive) range.
+    Between(EncodedObjectId, EncodedObjectId),
+}
+
+impl RocksDbScanPredicate {
+    /// Returns true if `object_id` fulfills the predicate.
+    pub fn evaluate(&self, object_id: EncodedObjectId) -> bool {
+        match self {
+            RocksDbScanPredicate::In(object_ids) => object_ids.contains(&object_id),
+            RocksDbScanPredicate::Between(from, to) => {
+                *from <= object_id && object_id <= *to
+            }
+        }
+    }
+
+    /// Returns the smallest and the largest object id that can fulfill the predicate. Returns
+    /// [None] if no object id can fulfill the predicate.
+    pub fn bounds(&self) -> Option<(EncodedObjectId, EncodedObjectId)> {
+        match self {
+            RocksDbScanPredicate::In(object_ids) => {
+                Some((*object_ids.first()?, *object_ids.last()?))
+            }
+            RocksDbScanPredicate::Between(from, to) => {
+                (from <= to).then_some((*from, *to))
+            }
+        }
+    }
+
+    /// Returns the object id if only a single object id fulfills the predicate.
+    fn single(&self) -> Option<EncodedObjectId> {
+        match self.bounds()? {
+            (from, to) if from == to => Some(from),
+            _ => None,
+        }
+    }
+}
+
+/// Describes how a single quad component is handled during a scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RocksDbScanInstruction {
+    /// The variable that the component is bound to. Components that are bound to the same
+    /// variable must have the same object id.
+    pub variable: Option<String>,
+    /// The predicate that the object ids of the component must fulfill.
+    pub predicate: Option<RocksDbScanPredicate>,
+}
+
+impl RocksDbScanInstruction {
+    /// Creates a new [RocksDbScanInstruction] that binds the component to `variable`.
+    pub fn scan(variable: impl Into<String>) -> Self {
+        Self {
+            variable: Some(variable.into()),
+            predicate: None,
+        }
+    }
+
+    /// Creates a new [RocksDbScanInstruction] that only retains components that fulfill
+    /// `predicate`.
+    pub fn filter(predicate: RocksDbScanPredicate) -> Self {
+        Self {
+            variable: None,
+            predicate: Some(predicate),
+        }
+    }
+
+    /// Returns the instruction with the additional `predicate`.
+    pub fn with_predicate(self, predicate: Option<RocksDbScanPredicate>) -> Self {
+        Self { predicate, ..self }
+    }
+
+    /// Returns true if no object id can fulfill the instruction.
+    fn is_unsatisfiable(&self) -> bool {
+        self.predicate
+            .as_ref()
+            .is_some_and(|predicate| predicate.bounds().is_none())
+    }
+}
+
+/// A scan over one index permutation of a [RocksDbQuadStorage](super::RocksDbQuadStorage).
+///
+/// The scan chooses the index whose key prefix is bound by the most components. The bound prefix
+/// and the range of the first component that is not bound (e.g., from a
+/// [RocksDbScanPredicate::Between]) determine the key range of the scan. Hence, the scan seeks to
+/// the first key within the range and stops once it leaves the range. All remaining predicates
+/// are evaluated on the scanned keys.
+#[derive(Debug, Clone)]
+pub struct RocksDbQuadScan {
+    /// The schema of the produced record batches.
+    schema: SchemaRef,
+    /// The components of the scanned index.
+    components: IndexComponents,
+    /// The instructions in the order of the scanned index.
+    instructions: [RocksDbScanInstruction; 4],
+    /// For each column of the schema, the position of the component in the index.
+    projection: Vec<usize>,
+    /// The first key of the scan.
+    lower: Vec<u8>,
+    /// The (inclusive) upper bound on the key prefix of the scanned keys.
+    upper: Vec<u8>,
+    /// The maximum number of rows of a single record batch.
+    batch_size: usize,
+}
+
+impl RocksDbQuadScan {
+    /// Creates a new [RocksDbQuadScan] for the given `instructions`, which are given in the GSPO
+    /// order. Each variable in `instructions` must have a field in `schema`.
+    ///
+    /// Returns [None] if no quad can match the instructions.
+    pub fn try_new(
+        schema: SchemaRef,
+        instructions: [RocksDbScanInstruction; 4],
+        batch_size: usize,
+    ) -> Option<Self> {
+        if instructions
+            .iter()
+            .any(RocksDbScanInstruction::is_unsatisfiable)
+        {
+            return None;
+        }
+
+        let (components, instructions) = INDEXES
+            .into_iter()
+            .map(|components| {
+                let reordered = (*components.inner())
+                    .map(|component| instructions[component.gspo_index()].clone());
+                (components, reordered)
+            })
+            .rev() // Prefer GSPO (max by uses the last on equality)
+            .max_by_key(|(_, instructions)| scan_score(instructions))
+            .expect("At least one index");
+
+        let projection = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                instructions
+                    .iter()
+                    .position(|i| i.variable.as_deref() == Some(field.name().as_str()))
+                    .expect("Every field must be bound to a component")
+            })
+            .collect();
+
+        let mut lower = Vec::new();
+        let mut upper = Vec::new();
+        for instruction in &instructions {
+            let Some((from, to)) =
+                instruction.predicate.as_ref().and_then(|p| p.bounds())
+            else {
+                break;
+            };
+            lower.extend_from_slice(&encode_object_id(from));
+            upper.extend_from_slice(&encode_object_id(to));
+            if from != to {
+                break;
+            }
+        }
+
+        Some(Self {
+            schema,
+            components,
+            instructions,
+            projection,
+            lower,
+            upper,
+            batch_size: batch_size.max(1),
+        })
+    }
+
+    /// Scans the index in `db` and returns all matching rows.
+    pub fn collect(&self, db: &DB) -> DFResult<Vec<RecordBatch>> {
+        let mut result = Vec::new();
+        let mut next_key = Some(self.lower.clone());
+        while let Some(start) = next_key {
+            let (batch, next) = self.read_batch(db, &start)?;
+            if batch.num_rows() > 0 {
+                result.push(batch);
+            }
+            next_key = next;
+        }
+        Ok(result)
+    }
+
+    /// Reads the matching rows starting at the key `start`.
+    ///
+    /// Returns the next key if the batch is full. A new iterator is created for every batch such
+    /// that no iterator must be held across polls of the stream.
+    fn read_batch(
+        &self,
+        db: &DB,
+        start: &[u8],
+    ) -> DFResult<(RecordBatch, Option<Vec<u8>>)> {
+        let cf_name = index_cf_name(self.components);
+        let cf = db
+            .cf_handle(&cf_name)
+            .ok_or_else(|| exec_datafusion_err!("Missing column family {cf_name}."))?;
+
+        let mut builders = self
+            .projection
+            .iter()
+            .map(|_| UInt32Builder::with_capacity(self.batch_size))
+            .collect::<Vec<_>>();
+        let mut num_rows = 0;
+        let mut next_key = None;
+
+        let mut iter = db.raw_iterator_cf(cf);
+        iter.seek(start);
+        while let Some(key) = iter.key() {
+            if key[..self.upper.len()] > self.upper[..] {
+                break;
+            }
+            if num_rows == self.batch_size {
+                next_key = Some(key.to_vec());
+                break;
+            }
+
+            let object_ids = decode_index_key(key)
+                .map_err(|err| DataFusionError::External(Box::new(err)))?;
+            if self.matches(&object_ids) {
+                for (builder, position) in builders.iter_mut().zip(&self.projection) {
+                    let object_id = object_ids[*position];
+                    let is_graph =
+                        self.components.inner()[*position] == IndexComponent::GraphName;
+                    if is_graph && object_id == DEFAULT_GRAPH_ID.0 {
+                        builder.append_null();
+                    } else {
+                        builder.append_value(
+                            u32::try_from(object_id.as_u64())
+                                .expect("Keys hold 4-byte object ids"),
+                        );
+                    }
+                }
+                num_rows += 1;
+            }
+            iter.next();
+        }
+        iter.status()
+            .map_err(|err| DataFusionError::External(Box::new(err)))?;
+
+        let columns = builders
+            .into_iter()
+            .map(|mut builder| Arc::new(builder.finish()) as ArrayRef)
+            .collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        let batch = RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &options,
+        )?;
+        Ok((batch, next_key))
+    }
+
+    /// Returns true if the `object_ids` (in the order of the index) match the instructions.
+    fn matches(&self, object_ids: &[EncodedObjectId; 4]) -> bool {
+        self.instructions
+            .iter()
+            .enumerate()
+            .all(|(i, instruction)| {
+                let fulfills_predicate = instruction
+                    .predicate
+                    .as_ref()
+                    .is_none_or(|predicate| predicate.evaluate(object_ids[i]));
+                let equals_previous =
+                    instruction.variable.as_ref().is_none_or(|variable| {
+                        self.instructions[..i]
+                            .iter()
+                            .zip(object_ids)
+                            .filter(|(other, _)| {
+                                other.variable.as_ref() == Some(variable)
+                            })
+                            .all(|(_, object_id)| *object_id == object_ids[i])
+                    });
+                fulfills_predicate && equals_previous
+            })
+    }
+}
+
+/// Computes how well an index with the (reordered) `instructions` is suited for a scan.
+///
+/// The score is the number of leading components that are bound to a single object id. A range
+/// on the next component increases the score, as the scan can seek to the start of the range.
+fn scan_score(instructions: &[RocksDbScanInstruction; 4]) -> usize {
+    let mut score = 0;
+    for instruction in instructions {
+        let predicate = instruction.predicate.as_ref();
+        if predicate.and_then(RocksDbScanPredicate::single).is_some() {
+            score += 2;
+        } else {
+            if predicate.and_then(RocksDbScanPredicate::bounds).is_some() {
+                score += 1;
+            }
+            break;
+        }
+    }
+    score
+}
+
+/// A [PartitionStream] that executes a [RocksDbQuadScan].
+pub(super) struct RocksDbQuadScanPartition {
+    /// Holds a read lock on the database such that the scan observes a consistent state.
+    db: Arc<OwnedRwLockReadGuard<DB>>,
+    /// The scan to execute.
+    scan: RocksDbQuadScan,
+}
+
+impl RocksDbQuadScanPartition {
+    /// Creates a new [RocksDbQuadScanPartition].
+    pub(super) fn new(db: Arc<OwnedRwLockReadGuard<DB>>, scan: RocksDbQuadScan) -> Self {
+        Self { db, scan }
+    }
+}
+
+impl Debug for RocksDbQuadScanPartition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RocksDbQuadScanPartition")
+            .field("scan", &self.scan)
+            .finish()
+    }
+}
+
+impl PartitionStream for RocksDbQuadScanPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.scan.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        Box::pin(RocksDbQuadScanStream {
+            db: Arc::clone(&self.db),
+            next_key: Some(self.scan.lower.clone()),
+            scan: self.scan.clone(),
+        })
+    }
+}
+
+/// A stream that reads the results of a [RocksDbQuadScan] batch by batch.
+struct RocksDbQuadScanStream {
+    /// Holds a read lock on the database.
+    db: Arc<OwnedRwLockReadGuard<DB>>,
+    /// The scan to execute.
+    scan: RocksDbQuadScan,
+    /// The key where the next batch starts. [None] if the scan is exhausted.
+    next_key: Option<Vec<u8>>,
+}
+
+impl Stream for RocksDbQuadScanStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let Some(start) = self.next_key.take() else {
+            return Poll::Ready(None);
+        };
+
+        match self.scan.read_batch(&self.db, &start) {
+            Ok((batch, next_key)) => {
+                self.next_key = next_key;
+                if batch.num_rows() == 0 && self.next_key.is_none() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Ok(batch)))
+                }
+            }
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+impl RecordBatchStream for RocksDbQuadScanStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.scan.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+
+    #[test]
+    fn test_between_on_graph_seeks_to_range() {
+        let schema = Arc::new(Schema::new(vec![Field::new("g", DataType::UInt32, true)]));
+        let graph = RocksDbScanInstruction::scan("g").with_predicate(Some(
+            RocksDbScanPredicate::Between(
+                EncodedObjectId::from(1u32),
+                EncodedObjectId::MAX,
+            ),
+        ));
+        let instructions = [
+            graph,
+            RocksDbScanInstruction::default(),
+            RocksDbScanInstruction::default(),
+            RocksDbScanInstruction::default(),
+        ];
+
+        let scan = RocksDbQuadScan::try_new(schema, instructions, 10).unwrap();
+
+        assert_eq!(scan.components, IndexComponents::GSPO);
+        assert_eq!(scan.lower, 1u32.to_be_bytes());
+        assert_eq!(scan.upper, u32::MAX.to_be_bytes());
+    }
+
+    #[test]
+    fn test_bound_object_chooses_gosp() {
+        let object =
+            RocksDbScanInstruction::filter(RocksDbScanPredicate::In(BTreeSet::from([
+                EncodedObjectId::from(5u32),
+            ])));
+        let graph =
+            RocksDbScanInstruction::filter(RocksDbScanPredicate::In(BTreeSet::from([
+                EncodedObjectId::from(0u32),
+            ])));
+        let instructions = [
+            graph,
+            RocksDbScanInstruction::default(),
+            RocksDbScanInstruction::default(),
+            object,
+        ];
+
+        let scan = RocksDbQuadScan::try_new(Arc::new(Schema::empty()), instructions, 10)
+            .unwrap();
+
+        assert_eq!(scan.components, IndexComponents::GOSP);
+        assert_eq!(scan.lower, [0, 0, 0, 0, 0, 0, 0, 5]);
+        assert_eq!(scan.upper, scan.lower);
+    }
+
+    #[test]
+    fn test_empty_predicate_is_unsatisfiable() {
+        let instructions = [
+            RocksDbScanInstruction::filter(RocksDbScanPredicate::In(BTreeSet::new())),
+            RocksDbScanInstruction::default(),
+            RocksDbScanInstruction::default(),
+            RocksDbScanInstruction::default(),
+        ];
+
+        let scan = RocksDbQuadScan::try_new(Arc::new(Schema::empty()), instructions, 10);
+
+        assert!(scan.is_none());
+    }
+}
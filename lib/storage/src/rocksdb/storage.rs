@@ -0,0 +1,406 @@
+use crate::index::{EncodedQuad, IndexComponents};
+use crate::memory::MemObjectIdMapping;
+use crate::memory::object_id::{DEFAULT_GRAPH_ID, EncodedObjectId};
+use crate::rocksdb::encoding::{
+    GRAPHS_CF, ID2TERM_CF, INDEXES, column_family_names, decode_object_id, decode_term,
+    encode_index_key, encode_object_id, encode_term, index_cf_name,
+};
+use crate::rocksdb::planner::RocksDbQuadStorePlanner;
+use crate::rocksdb::scan::RocksDbQuadScan;
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::physical_planner::ExtensionPlanner;
+use rdf_fusion_encoding::QuadStorageEncoding;
+use rdf_fusion_encoding::object_id::{
+    ObjectIdEncoding, ObjectIdEncodingRef, ObjectIdMapping,
+};
+use rdf_fusion_extensions::RdfFusionContextView;
+use rdf_fusion_extensions::storage::{QuadStorage, StorageOptimizationReport};
+use rdf_fusion_model::{
+    CorruptionError, DFResult, GraphNameRef, NamedOrBlankNode, NamedOrBlankNodeRef, Quad,
+    QuadRef, StorageError,
+};
+use rocksdb::{ColumnFamily, DB, IteratorMode, Options, WriteBatch};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// A persistent quad storage that is backed by RocksDB.
+///
+/// See the [module documentation](super) for the layout of the database.
+///
+/// # Object IDs
+///
+/// A term is persisted together with its object id once a quad or a named graph refers to it.
+/// When the storage is opened, the persisted terms are loaded into a [MemObjectIdMapping] that is
+/// used for encoding and decoding terms. Hence, the terms (but not the quads) must fit into
+/// memory.
+///
+/// # Consistency
+///
+/// Like in the [MemQuadStorage](crate::memory::MemQuadStorage), a query holds a read lock on the
+/// database until its plan is dropped. Writers wait until all running queries have finished.
+pub struct RocksDbQuadStorage {
+    /// The database.
+    db: Arc<RwLock<DB>>,
+    /// The object id encoding.
+    object_id_encoding: ObjectIdEncodingRef,
+    /// Holds the mapping between terms and object ids.
+    object_id_mapping: Arc<MemObjectIdMapping>,
+    /// The batch size of the scans.
+    batch_size: usize,
+}
+
+impl RocksDbQuadStorage {
+    /// Opens the storage at `path`. The database is created if it does not exist.
+    pub fn open(path: impl AsRef<Path>, batch_size: usize) -> Result<Self, StorageError> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let db =
+            DB::open_cf(&options, path, column_family_names()).map_err(rocksdb_error)?;
+
+        let object_id_mapping = Arc::new(MemObjectIdMapping::new());
+        load_object_id_mapping(&db, &object_id_mapping)?;
+        let object_id_encoding = Arc::new(ObjectIdEncoding::new(Arc::clone(
+            &object_id_mapping,
+        )
+            as Arc<dyn ObjectIdMapping>));
+
+        Ok(Self {
+            db: Arc::new(RwLock::new(db)),
+            object_id_encoding,
+            object_id_mapping,
+            batch_size,
+        })
+    }
+
+    /// Inserts the encoded `quads` and the terms they refer to. Returns the number of quads that
+    /// did not exist before.
+    async fn insert_encoded(
+        &self,
+        quads: &[EncodedQuad<EncodedObjectId>],
+    ) -> Result<usize, StorageError> {
+        let db = self.db.write().await;
+        let gspo = column_family(&db, &index_cf_name(IndexComponents::GSPO))?;
+        let graphs = column_family(&db, GRAPHS_CF)?;
+
+        let mut batch = WriteBatch::default();
+        let mut seen = HashSet::new();
+        let mut terms = HashSet::new();
+        let mut inserted = 0;
+        for quad in quads {
+            let key = encode_index_key(quad, IndexComponents::GSPO);
+            if !seen.insert(key) || contains_key(&db, gspo, &key)? {
+                continue;
+            }
+            inserted += 1;
+
+            for components in INDEXES {
+                let cf = column_family(&db, &index_cf_name(components))?;
+                batch.put_cf(cf, encode_index_key(quad, components), []);
+            }
+            terms.extend([quad.subject, quad.predicate, quad.object]);
+            if quad.graph_name != DEFAULT_GRAPH_ID.0 {
+                terms.insert(quad.graph_name);
+                batch.put_cf(graphs, encode_object_id(quad.graph_name), []);
+            }
+        }
+        self.put_terms(&db, &mut batch, terms)?;
+
+        db.write(batch).map_err(rocksdb_error)?;
+        Ok(inserted)
+    }
+
+    /// Adds the terms of the given `object_ids` to the `batch`.
+    fn put_terms(
+        &self,
+        db: &DB,
+        batch: &mut WriteBatch,
+        object_ids: impl IntoIterator<Item = EncodedObjectId>,
+    ) -> Result<(), StorageError> {
+        let id2term = column_family(db, ID2TERM_CF)?;
+        for object_id in object_ids {
+            let key = encode_object_id(object_id);
+            if !contains_key(db, id2term, &key)? {
+                let term = self.object_id_mapping.decode_term(object_id)?;
+                batch.put_cf(id2term, key, encode_term(term.as_ref()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes all keys of the index column families that start with `graph_name`.
+    fn delete_graph(
+        db: &DB,
+        batch: &mut WriteBatch,
+        graph_name: EncodedObjectId,
+    ) -> Result<(), StorageError> {
+        let prefix = encode_object_id(graph_name);
+        for components in INDEXES {
+            let cf = column_family(db, &index_cf_name(components))?;
+            let mut iter = db.raw_iterator_cf(cf);
+            iter.seek(prefix);
+            while let Some(key) = iter.key() {
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                batch.delete_cf(cf, key);
+                iter.next();
+            }
+            iter.status().map_err(rocksdb_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QuadStorage for RocksDbQuadStorage {
+    fn encoding(&self) -> QuadStorageEncoding {
+        QuadStorageEncoding::ObjectId(Arc::clone(&self.object_id_encoding))
+    }
+
+    fn object_id_mapping(&self) -> Option<Arc<dyn ObjectIdMapping>> {
+        Some(Arc::clone(&self.object_id_mapping) as Arc<dyn ObjectIdMapping>)
+    }
+
+    async fn planners(
+        &self,
+        _context: &RdfFusionContextView,
+    ) -> Vec<Arc<dyn ExtensionPlanner + Send + Sync>> {
+        let db = Arc::new(Arc::clone(&self.db).read_owned().await);
+        vec![Arc::new(RocksDbQuadStorePlanner::new(
+            db,
+            Arc::clone(&self.object_id_mapping),
+            self.batch_size,
+        ))]
+    }
+
+    async fn extend(&self, quads: Vec<Quad>) -> Result<usize, StorageError> {
+        let encoded = quads
+            .iter()
+            .map(|q| self.object_id_mapping.encode_quad(q.as_ref()))
+            .collect::<DFResult<Vec<_>>>()?;
+        self.insert_encoded(&encoded).await
+    }
+
+    async fn remove(&self, quad: QuadRef<'_>) -> Result<bool, StorageError> {
+        let mapping = &self.object_id_mapping;
+        let (Some(graph_name), Some(subject), Some(predicate), Some(object)) = (
+            mapping.try_get_encoded_object_id_from_graph_name(quad.graph_name),
+            mapping.try_get_encoded_object_id_from_term(quad.subject),
+            mapping.try_get_encoded_object_id_from_term(quad.predicate),
+            mapping.try_get_encoded_object_id_from_term(quad.object),
+        ) else {
+            return Ok(false);
+        };
+        let quad = EncodedQuad {
+            graph_name: graph_name.0,
+            subject,
+            predicate,
+            object,
+        };
+
+        let db = self.db.write().await;
+        let gspo = column_family(&db, &index_cf_name(IndexComponents::GSPO))?;
+        if !contains_key(&db, gspo, &encode_index_key(&quad, IndexComponents::GSPO))? {
+            return Ok(false);
+        }
+
+        let mut batch = WriteBatch::default();
+        for components in INDEXES {
+            let cf = column_family(&db, &index_cf_name(components))?;
+            batch.delete_cf(cf, encode_index_key(&quad, components));
+        }
+        db.write(batch).map_err(rocksdb_error)?;
+        Ok(true)
+    }
+
+    async fn insert_named_graph<'a>(
+        &self,
+        graph_name: NamedOrBlankNodeRef<'a>,
+    ) -> Result<bool, StorageError> {
+        let encoded = self
+            .object_id_mapping
+            .encode_term_intern(graph_name)
+            .map_err(StorageError::from)?;
+
+        let db = self.db.write().await;
+        let graphs = column_family(&db, GRAPHS_CF)?;
+        let key = encode_object_id(encoded);
+        if contains_key(&db, graphs, &key)? {
+            return Ok(false);
+        }
+
+        let mut batch = WriteBatch::default();
+        batch.put_cf(graphs, key, []);
+        self.put_terms(&db, &mut batch, [encoded])?;
+        db.write(batch).map_err(rocksdb_error)?;
+        Ok(true)
+    }
+
+    async fn named_graphs(&self) -> Result<Vec<NamedOrBlankNode>, StorageError> {
+        let db = self.db.read().await;
+        let graphs = column_family(&db, GRAPHS_CF)?;
+        db.iterator_cf(graphs, IteratorMode::Start)
+            .map(|entry| {
+                let (key, _) = entry.map_err(rocksdb_error)?;
+                let object_id = decode_object_id(&key)?;
+                Ok(self.object_id_mapping.decode_named_graph(object_id)?)
+            })
+            .collect()
+    }
+
+    async fn contains_named_graph<'a>(
+        &self,
+        graph_name: NamedOrBlankNodeRef<'a>,
+    ) -> Result<bool, StorageError> {
+        let Some(encoded) = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_term(graph_name)
+        else {
+            return Ok(false);
+        };
+
+        let db = self.db.read().await;
+        let graphs = column_family(&db, GRAPHS_CF)?;
+        contains_key(&db, graphs, &encode_object_id(encoded))
+    }
+
+    async fn clear(&self) -> Result<(), StorageError> {
+        let db = self.db.write().await;
+        let mut batch = WriteBatch::default();
+        for components in INDEXES {
+            let cf = column_family(&db, &index_cf_name(components))?;
+            for entry in db.iterator_cf(cf, IteratorMode::Start) {
+                let (key, _) = entry.map_err(rocksdb_error)?;
+                batch.delete_cf(cf, key);
+            }
+        }
+        db.write(batch).map_err(rocksdb_error)
+    }
+
+    async fn clear_graph<'a>(
+        &self,
+        graph_name: GraphNameRef<'a>,
+    ) -> Result<(), StorageError> {
+        let Some(encoded) = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_graph_name(graph_name)
+        else {
+            return Ok(());
+        };
+
+        let db = self.db.write().await;
+        let mut batch = WriteBatch::default();
+        Self::delete_graph(&db, &mut batch, encoded.0)?;
+        db.write(batch).map_err(rocksdb_error)
+    }
+
+    async fn drop_named_graph(
+        &self,
+        graph_name: NamedOrBlankNodeRef<'_>,
+    ) -> Result<bool, StorageError> {
+        let Some(encoded) = self
+            .object_id_mapping
+            .try_get_encoded_object_id_from_term(graph_name)
+        else {
+            return Ok(false);
+        };
+
+        let db = self.db.write().await;
+        let graphs = column_family(&db, GRAPHS_CF)?;
+        let key = encode_object_id(encoded);
+        let existed = contains_key(&db, graphs, &key)?;
+
+        let mut batch = WriteBatch::default();
+        Self::delete_graph(&db, &mut batch, encoded)?;
+        batch.delete_cf(graphs, key);
+        db.write(batch).map_err(rocksdb_error)?;
+        Ok(existed)
+    }
+
+    async fn len(&self) -> Result<usize, StorageError> {
+        let db = self.db.read().await;
+        count_quads(&db, self.batch_size)
+    }
+
+    /// Compacts all column families of the database.
+    async fn optimize(&self) -> Result<StorageOptimizationReport, StorageError> {
+        let db = self.db.read().await;
+        for name in column_family_names() {
+            let cf = column_family(&db, &name)?;
+            db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
+        Ok(StorageOptimizationReport::default())
+    }
+
+    /// Validates that all index permutations contain the same number of quads.
+    async fn validate(&self) -> Result<(), StorageError> {
+        let db = self.db.read().await;
+        let expected = count_quads(&db, self.batch_size)?;
+        for components in INDEXES {
+            let cf = column_family(&db, &index_cf_name(components))?;
+            let count = db.iterator_cf(cf, IteratorMode::Start).count();
+            if count != expected {
+                return Err(CorruptionError::msg(format!(
+                    "The index {components} contains {count} quads instead of {expected}."
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Registers all persisted terms with their object ids in `mapping`.
+fn load_object_id_mapping(
+    db: &DB,
+    mapping: &MemObjectIdMapping,
+) -> Result<(), StorageError> {
+    let id2term = column_family(db, ID2TERM_CF)?;
+    for entry in db.iterator_cf(id2term, IteratorMode::Start) {
+        let (key, value) = entry.map_err(rocksdb_error)?;
+        let object_id = decode_object_id(&key)?;
+        let term = decode_term(&value)?;
+        if !mapping.insert_with_object_id(object_id, term.as_ref()) {
+            return Err(CorruptionError::msg(format!(
+                "The term {term} is persisted with multiple object ids."
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Counts the quads by scanning the GSPO index without projecting any columns.
+fn count_quads(db: &DB, batch_size: usize) -> Result<usize, StorageError> {
+    let scan = RocksDbQuadScan::try_new(
+        Arc::new(Schema::empty()),
+        Default::default(),
+        batch_size,
+    )
+    .expect("Scan without predicates is satisfiable");
+    let count = scan.collect(db)?.iter().map(|batch| batch.num_rows()).sum();
+    Ok(count)
+}
+
+/// Returns the column family with the given `name`.
+fn column_family<'db>(
+    db: &'db DB,
+    name: &str,
+) -> Result<&'db ColumnFamily, StorageError> {
+    db.cf_handle(name).ok_or_else(|| {
+        CorruptionError::msg(format!("Missing column family {name}.")).into()
+    })
+}
+
+/// Returns whether the column family `cf` contains `key`.
+fn contains_key(db: &DB, cf: &ColumnFamily, key: &[u8]) -> Result<bool, StorageError> {
+    Ok(db.get_pinned_cf(cf, key).map_err(rocksdb_error)?.is_some())
+}
+
+fn rocksdb_error(error: rocksdb::Error) -> StorageError {
+    StorageError::Other(Box::new(error))
+}
@@ -1,6 +1,8 @@
 use rdf_fusion_model::{GraphName, Literal, NamedNode, NamedOrBlankNode, Quad, Term};
 
 mod memory;
+#[cfg(feature = "rocksdb")]
+mod rocksdb;
 
 fn example_quad() -> Quad {
     Quad::new(
@@ -1,18 +1,35 @@
 use crate::{example_quad, example_quad_in_graph};
+use datafusion::arrow::array::{
+    ArrayRef, AsArray, RecordBatch, UInt32Array, UInt64Array,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, UInt32Type};
+use datafusion::common::ScalarValue;
+use datafusion::common::stats::Precision;
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet};
 use futures::StreamExt;
 use insta::assert_debug_snapshot;
-use rdf_fusion_encoding::object_id::{ObjectIdEncoding, ObjectIdMapping};
-use rdf_fusion_extensions::storage::QuadStorage;
+use rdf_fusion_encoding::object_id::{
+    ObjectId, ObjectIdEncoding, ObjectIdMapping, ObjectIdScalar, ObjectIdSize,
+};
+use rdf_fusion_encoding::plain_term::PlainTermArrayElementBuilder;
+use rdf_fusion_encoding::plain_term::decoders::DefaultPlainTermDecoder;
+use rdf_fusion_encoding::{
+    EncodingArray, QuadStorageEncoding, TermDecoder, TermEncoding,
+};
+use rdf_fusion_extensions::storage::{QuadPosition, QuadStorage, QuadStorageLookups};
 use rdf_fusion_logical::ActiveGraph;
 use rdf_fusion_model::BlankNodeMatchingMode;
 use rdf_fusion_model::{
-    GraphNameRef, NamedNode, NamedNodePattern, NamedOrBlankNode, TermPattern,
-    TriplePattern, Variable,
+    GraphName, GraphNameRef, Literal, NamedNode, NamedNodePattern, NamedNodeRef,
+    NamedOrBlankNode, NamedOrBlankNodeRef, Quad, Term, TermPattern, TriplePattern,
+    Variable,
 };
-use rdf_fusion_storage::memory::{MemObjectIdMapping, MemQuadStorage};
+use rdf_fusion_storage::memory::{
+    MemObjectIdMapping, MemQuadStorage, NamedGraphStorageKind, ObjectIdWidth,
+    ScanOrderHint,
+};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
-use tokio;
 
 #[tokio::test]
 async fn insert_quad() {
@@ -126,6 +143,39 @@ async fn insert_duplicate_quads_in_same_operation_quads() {
     assert_eq!(inserted, 1);
 }
 
+#[tokio::test]
+async fn extend_parallel_encodes_shared_terms_consistently() {
+    let storage = create_storage();
+    let predicate = NamedNode::new_unchecked("http://example.com/predicate");
+    let quads = (0..1_000)
+        .map(|i| {
+            Quad::new(
+                NamedNode::new_unchecked(format!("http://example.com/s{i}")),
+                predicate.clone(),
+                Literal::new_simple_literal((i % 10).to_string()),
+                GraphName::DefaultGraph,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let inserted = storage
+        .extend_parallel(quads, NonZeroUsize::new(8).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(inserted, 1_000);
+    assert_eq!(
+        count_matching_quads(&storage, None, None, Some(predicate.as_ref())).await,
+        1_000
+    );
+    let object = Literal::new_simple_literal("3");
+    let count = storage
+        .count_matching(None, None, None, Some(object.as_ref().into()))
+        .await
+        .unwrap();
+    assert_eq!(count, 100);
+}
+
 #[tokio::test]
 async fn named_graph_insertion_and_query() {
     let storage = create_storage();
@@ -156,6 +206,36 @@ async fn remove_quad() {
     assert_eq!(len, 0);
 }
 
+#[tokio::test]
+async fn insert_quads_returns_new_quads() {
+    let storage = create_storage();
+    let other = example_quad_in_graph("http://example.com/g");
+    storage.extend(vec![example_quad()]).await.unwrap();
+
+    let inserted = storage
+        .insert_quads(vec![example_quad(), other.clone(), other.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(inserted, vec![other]);
+    assert_eq!(storage.len().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn remove_quads_returns_removed_quads() {
+    let storage = create_storage();
+    let other = example_quad_in_graph("http://example.com/g");
+    storage.extend(vec![example_quad()]).await.unwrap();
+
+    let removed = storage
+        .remove_quads(vec![example_quad(), example_quad(), other])
+        .await
+        .unwrap();
+
+    assert_eq!(removed, vec![example_quad()]);
+    assert_eq!(storage.len().await.unwrap(), 0);
+}
+
 #[tokio::test]
 async fn clear_graph() {
     let storage = create_storage();
@@ -203,6 +283,58 @@ async fn remove_named_graph() {
     assert!(!exists);
 }
 
+#[tokio::test]
+async fn sorted_named_graphs_are_enumerated_in_encoding_order() {
+    let storage = create_storage_with_named_graphs(NamedGraphStorageKind::Sorted);
+    let graphs = (0..20)
+        .rev()
+        .map(|i| {
+            NamedOrBlankNode::NamedNode(
+                NamedNode::new(format!("http://example.com/graph{i}")).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    for graph in &graphs {
+        storage.insert_named_graph(graph.as_ref()).await.unwrap();
+    }
+
+    assert_eq!(storage.named_graphs().await.unwrap(), graphs);
+    assert!(
+        storage
+            .contains_named_graph(graphs[3].as_ref())
+            .await
+            .unwrap()
+    );
+
+    storage.drop_named_graph(graphs[3].as_ref()).await.unwrap();
+    let mut expected = graphs.clone();
+    expected.remove(3);
+    assert_eq!(storage.named_graphs().await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn hash_named_graphs_contain_all_graphs() {
+    let storage = create_storage_with_named_graphs(NamedGraphStorageKind::Hash);
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad_in_graph("http://example.com/g1"),
+            example_quad_in_graph("http://example.com/g2"),
+        ])
+        .await
+        .unwrap();
+
+    let mut graphs = storage.named_graphs().await.unwrap();
+    graphs.sort_by_key(ToString::to_string);
+    assert_eq!(
+        graphs,
+        vec![
+            NamedOrBlankNode::NamedNode(NamedNode::new("http://example.com/g1").unwrap()),
+            NamedOrBlankNode::NamedNode(NamedNode::new("http://example.com/g2").unwrap()),
+        ]
+    );
+}
+
 #[tokio::test]
 async fn clear_all() {
     let storage = create_storage();
@@ -238,6 +370,693 @@ async fn snapshot_consistency() {
     assert_eq!(snapshot.len(), 1);
 }
 
+#[tokio::test]
+async fn optimize_retains_quads_and_reclaims_memory() {
+    let storage = create_storage();
+    let quads = (0..100)
+        .map(|i| example_quad_in_graph(&format!("http://example.com/g{i}")))
+        .collect::<Vec<_>>();
+    storage.extend(quads.clone()).await.unwrap();
+    for quad in quads.iter().skip(1).step_by(2) {
+        storage.remove(quad.as_ref()).await.unwrap();
+    }
+
+    let report = storage.optimize().await.unwrap();
+
+    assert!(report.reclaimed_bytes > 0);
+    assert_eq!(storage.len().await.unwrap(), 50);
+    let graphs = storage.named_graphs().await.unwrap();
+    assert_eq!(graphs.len(), 100);
+}
+
+#[tokio::test]
+async fn predicate_index_answers_pattern_with_bound_predicate() {
+    let storage = create_storage();
+    storage.extend(vec![example_quad()]).await.unwrap();
+
+    let predicate = NamedNode::new("http://example.com/predicate").unwrap();
+    let created = storage
+        .create_predicate_index(predicate.as_ref())
+        .await
+        .unwrap();
+    assert!(created);
+    let created_again = storage
+        .create_predicate_index(predicate.as_ref())
+        .await
+        .unwrap();
+    assert!(!created_again);
+
+    let ep_metrics = ExecutionPlanMetricsSet::default();
+    let metrics = BaselineMetrics::new(&ep_metrics, 0);
+    let batch = storage
+        .snapshot()
+        .await
+        .plan_pattern_evaluation(
+            ActiveGraph::DefaultGraph,
+            None,
+            TriplePattern {
+                subject: TermPattern::Variable(Variable::new_unchecked("s")),
+                predicate: NamedNodePattern::NamedNode(predicate),
+                object: TermPattern::Variable(Variable::new_unchecked("o")),
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+        .await
+        .unwrap()
+        .create_stream(metrics)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(batch.num_rows(), 1);
+}
+
+#[tokio::test]
+async fn pattern_with_only_predicate_unbound_returns_matching_predicates() {
+    let storage = create_storage();
+    let node = |name: &str| NamedNode::new(format!("http://example.com/{name}")).unwrap();
+    let quad = |subject: &str, predicate: &str, object: &str| {
+        Quad::new(
+            node(subject),
+            node(predicate),
+            node(object),
+            GraphName::DefaultGraph,
+        )
+    };
+    storage
+        .extend(vec![
+            quad("s", "p1", "o"),
+            quad("s", "p2", "o"),
+            quad("s", "p3", "other"),
+            quad("other", "p4", "o"),
+        ])
+        .await
+        .unwrap();
+
+    let ep_metrics = ExecutionPlanMetricsSet::default();
+    let metrics = BaselineMetrics::new(&ep_metrics, 0);
+    let batch = storage
+        .snapshot()
+        .await
+        .plan_pattern_evaluation(
+            ActiveGraph::DefaultGraph,
+            None,
+            TriplePattern {
+                subject: TermPattern::NamedNode(node("s")),
+                predicate: NamedNodePattern::Variable(Variable::new_unchecked("p")),
+                object: TermPattern::NamedNode(node("o")),
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+        .await
+        .unwrap()
+        .create_stream(metrics)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(batch.num_columns(), 1);
+    assert_eq!(batch.num_rows(), 2);
+}
+
+#[tokio::test]
+async fn graph_variable_is_bound_to_source_graph() {
+    let mapping = Arc::new(MemObjectIdMapping::new());
+    let encoding = Arc::new(ObjectIdEncoding::new(
+        Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
+    ));
+    let storage = MemQuadStorage::new(Arc::clone(&mapping), encoding, 10).unwrap();
+    let quad = |graph: GraphName, subject: &str| {
+        Quad::new(
+            NamedNode::new(format!("http://example.com/{subject}")).unwrap(),
+            NamedNode::new("http://example.com/predicate").unwrap(),
+            Literal::new_simple_literal("value"),
+            graph,
+        )
+    };
+    let graph = |name: &str| {
+        GraphName::NamedNode(
+            NamedNode::new(format!("http://example.com/{name}")).unwrap(),
+        )
+    };
+    storage
+        .extend(vec![
+            quad(GraphName::DefaultGraph, "s0"),
+            quad(graph("g1"), "s1"),
+            quad(graph("g2"), "s2"),
+            quad(graph("g2"), "s3"),
+        ])
+        .await
+        .unwrap();
+
+    let ep_metrics = ExecutionPlanMetricsSet::default();
+    let metrics = BaselineMetrics::new(&ep_metrics, 0);
+    let batch = storage
+        .snapshot()
+        .await
+        .plan_pattern_evaluation(
+            ActiveGraph::AnyNamedGraph,
+            Some(Variable::new_unchecked("g")),
+            TriplePattern {
+                subject: TermPattern::Variable(Variable::new_unchecked("s")),
+                predicate: NamedNodePattern::Variable(Variable::new_unchecked("p")),
+                object: TermPattern::Variable(Variable::new_unchecked("o")),
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+        .await
+        .unwrap()
+        .create_stream(metrics)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let decode_column = |name: &str| {
+        let ids = batch
+            .column_by_name(name)
+            .unwrap()
+            .as_primitive::<UInt32Type>();
+        let terms = mapping.decode_array(ids).unwrap();
+        DefaultPlainTermDecoder::decode_terms(&terms)
+            .map(|term| term.unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let mut graphs_and_subjects = decode_column("g")
+        .into_iter()
+        .zip(decode_column("s"))
+        .collect::<Vec<_>>();
+    graphs_and_subjects.sort();
+    assert_eq!(
+        graphs_and_subjects,
+        vec![
+            (
+                "<http://example.com/g1>".to_owned(),
+                "<http://example.com/s1>".to_owned()
+            ),
+            (
+                "<http://example.com/g2>".to_owned(),
+                "<http://example.com/s2>".to_owned()
+            ),
+            (
+                "<http://example.com/g2>".to_owned(),
+                "<http://example.com/s3>".to_owned()
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn order_hint_sorts_results_across_graphs() {
+    let storage = create_storage();
+    let quad = |graph: &str, subject: &str| {
+        Quad::new(
+            NamedNode::new(format!("http://example.com/{subject}")).unwrap(),
+            NamedNode::new("http://example.com/predicate").unwrap(),
+            Literal::new_simple_literal("value"),
+            NamedNode::new(format!("http://example.com/{graph}")).unwrap(),
+        )
+    };
+    storage
+        .extend(vec![
+            quad("g1", "s3"),
+            quad("g2", "s1"),
+            quad("g1", "s4"),
+            quad("g2", "s2"),
+        ])
+        .await
+        .unwrap();
+
+    let ep_metrics = ExecutionPlanMetricsSet::default();
+    let metrics = BaselineMetrics::new(&ep_metrics, 0);
+    let batch = storage
+        .snapshot()
+        .await
+        .plan_pattern_evaluation(
+            ActiveGraph::AnyNamedGraph,
+            Some(Variable::new_unchecked("g")),
+            TriplePattern {
+                subject: TermPattern::Variable(Variable::new_unchecked("s")),
+                predicate: NamedNodePattern::Variable(Variable::new_unchecked("p")),
+                object: TermPattern::Variable(Variable::new_unchecked("o")),
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+        .await
+        .unwrap()
+        .with_order_hint(ScanOrderHint::Subject)
+        .create_stream(metrics)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let subjects = batch
+        .column_by_name("s")
+        .unwrap()
+        .as_primitive::<UInt32Type>()
+        .values()
+        .to_vec();
+    let mut sorted_subjects = subjects.clone();
+    sorted_subjects.sort();
+    assert_eq!(subjects.len(), 4);
+    assert_eq!(subjects, sorted_subjects);
+}
+
+#[tokio::test]
+async fn extend_from_batch() {
+    let storage = create_storage();
+    let ex = NamedNodeRef::new_unchecked("http://example.com/ex");
+    let graph = NamedNodeRef::new_unchecked("http://example.com/graph");
+
+    let mut graphs = PlainTermArrayElementBuilder::default();
+    graphs.append_null();
+    graphs.append_named_node(graph);
+    let mut terms = PlainTermArrayElementBuilder::default();
+    terms.append_named_node(ex);
+    terms.append_named_node(ex);
+    let terms = terms.finish().into_array_ref();
+    let batch = RecordBatch::try_new(
+        Arc::clone(QuadStorageEncoding::PlainTerm.quad_schema().inner()),
+        vec![
+            graphs.finish().into_array_ref(),
+            Arc::clone(&terms),
+            Arc::clone(&terms),
+            terms,
+        ],
+    )
+    .unwrap();
+
+    let inserted = storage.extend_from_batch(batch).await.unwrap();
+
+    assert_eq!(inserted, 2);
+    assert_eq!(storage.len().await.unwrap(), 2);
+    let graphs = storage.named_graphs().await.unwrap();
+    assert_eq!(graphs, vec![NamedOrBlankNode::from(graph.into_owned())]);
+}
+
+#[tokio::test]
+async fn extend_from_batch_with_wrong_schema() {
+    let storage = create_storage();
+    let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, false)]));
+
+    let result = storage
+        .extend_from_batch(RecordBatch::new_empty(schema))
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn count_matching() {
+    let storage = create_storage();
+    let other_subject = Quad::new(
+        NamedNode::new_unchecked("http://example.com/other"),
+        NamedNode::new_unchecked("http://example.com/predicate"),
+        Literal::new_simple_literal("value"),
+        GraphName::DefaultGraph,
+    );
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad_in_graph("http://example.com/g1"),
+            example_quad_in_graph("http://example.com/g2"),
+            other_subject,
+        ])
+        .await
+        .unwrap();
+
+    let subject = NamedNodeRef::new_unchecked("http://example.com/subject");
+    let predicate = NamedNodeRef::new_unchecked("http://example.com/predicate");
+    let graph = NamedNodeRef::new_unchecked("http://example.com/g1");
+    let unknown = NamedNodeRef::new_unchecked("http://example.com/unknown");
+    let count = |graph_name, subject, predicate| {
+        count_matching_quads(&storage, graph_name, subject, predicate)
+    };
+
+    assert_eq!(count(None, None, None).await, 4);
+    assert_eq!(count(None, Some(subject.into()), None).await, 3);
+    assert_eq!(count(None, None, Some(predicate)).await, 4);
+    assert_eq!(
+        count(Some(GraphNameRef::DefaultGraph), Some(subject.into()), None).await,
+        1
+    );
+    assert_eq!(count(Some(graph.into()), None, None).await, 1);
+    assert_eq!(count(None, Some(unknown.into()), None).await, 0);
+    assert_eq!(count(Some(unknown.into()), None, None).await, 0);
+}
+
+async fn count_matching_quads(
+    storage: &MemQuadStorage,
+    graph_name: Option<GraphNameRef<'_>>,
+    subject: Option<NamedOrBlankNodeRef<'_>>,
+    predicate: Option<NamedNodeRef<'_>>,
+) -> usize {
+    storage
+        .count_matching(graph_name, subject, predicate, None)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn estimate_pattern_cardinality() {
+    let storage = create_storage();
+    let p1 = NamedNode::new_unchecked("http://example.com/p1");
+    let p2 = NamedNode::new_unchecked("http://example.com/p2");
+    let quads = (0..20)
+        .map(|i| (i, &p1))
+        .chain((0..5).map(|i| (i, &p2)))
+        .map(|(i, predicate)| {
+            Quad::new(
+                NamedNode::new_unchecked(format!("http://example.com/s{i}")),
+                predicate.clone(),
+                Literal::new_simple_literal(format!("value {i}")),
+                GraphName::DefaultGraph,
+            )
+        })
+        .chain([example_quad_in_graph("http://example.com/g1")])
+        .collect::<Vec<_>>();
+    storage.extend(quads).await.unwrap();
+
+    let snapshot = storage.snapshot().await;
+    let variable = |name| TermPattern::Variable(Variable::new_unchecked(name));
+    let estimate = |active_graph, predicate: NamedNodePattern, object| {
+        snapshot.estimate_pattern_cardinality(
+            active_graph,
+            None,
+            TriplePattern {
+                subject: variable("s"),
+                predicate,
+                object,
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+    };
+    let p = NamedNodePattern::Variable(Variable::new_unchecked("p"));
+
+    // Unbound patterns return the number of quads in the active graph.
+    assert_eq!(
+        estimate(ActiveGraph::DefaultGraph, p.clone(), variable("o"))
+            .await
+            .unwrap(),
+        Precision::Exact(25)
+    );
+    assert_eq!(
+        estimate(ActiveGraph::AllGraphs, p.clone(), variable("o"))
+            .await
+            .unwrap(),
+        Precision::Exact(26)
+    );
+
+    // Bound predicates are counted with an index that has the predicate as a prefix.
+    assert_eq!(
+        estimate(ActiveGraph::DefaultGraph, p1.clone().into(), variable("o"))
+            .await
+            .unwrap(),
+        Precision::Exact(20)
+    );
+    assert_eq!(
+        estimate(ActiveGraph::DefaultGraph, p2.into(), variable("o"))
+            .await
+            .unwrap(),
+        Precision::Exact(5)
+    );
+
+    // Predicates that cannot be answered by the index prefix yield an upper bound.
+    assert_eq!(
+        estimate(ActiveGraph::DefaultGraph, p1.into(), variable("s"))
+            .await
+            .unwrap(),
+        Precision::Inexact(20)
+    );
+
+    // Unknown terms cannot match any quad.
+    let unknown = NamedNode::new_unchecked("http://example.com/unknown");
+    assert_eq!(
+        estimate(ActiveGraph::DefaultGraph, unknown.into(), variable("o"))
+            .await
+            .unwrap(),
+        Precision::Exact(0)
+    );
+}
+
+#[tokio::test]
+async fn predicates_for_subject() {
+    let storage = create_storage();
+    let other_predicate = Quad::new(
+        NamedNode::new_unchecked("http://example.com/subject"),
+        NamedNode::new_unchecked("http://example.com/other"),
+        Literal::new_simple_literal("value"),
+        GraphName::NamedNode(NamedNode::new_unchecked("http://example.com/g1")),
+    );
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad_in_graph("http://example.com/g1"),
+            example_quad_in_graph("http://example.com/g2"),
+            other_predicate,
+        ])
+        .await
+        .unwrap();
+
+    let subject = NamedNodeRef::new_unchecked("http://example.com/subject");
+    let predicate = NamedNode::new_unchecked("http://example.com/predicate");
+    let other = NamedNode::new_unchecked("http://example.com/other");
+    let graph = NamedNodeRef::new_unchecked("http://example.com/g1");
+    let unknown = NamedNodeRef::new_unchecked("http://example.com/unknown");
+    let predicates = |graph_name, subject| predicates_for(&storage, graph_name, subject);
+
+    assert_eq!(
+        predicates(None, subject.into()).await,
+        vec![other.clone(), predicate.clone()]
+    );
+    assert_eq!(
+        predicates(Some(GraphNameRef::DefaultGraph), subject.into()).await,
+        vec![predicate.clone()]
+    );
+    assert_eq!(
+        predicates(Some(graph.into()), subject.into()).await,
+        vec![other, predicate]
+    );
+    assert!(predicates(None, unknown.into()).await.is_empty());
+    assert!(
+        predicates(Some(unknown.into()), subject.into())
+            .await
+            .is_empty()
+    );
+}
+
+async fn predicates_for(
+    storage: &MemQuadStorage,
+    graph_name: Option<GraphNameRef<'_>>,
+    subject: NamedOrBlankNodeRef<'_>,
+) -> Vec<NamedNode> {
+    let mut predicates = storage.predicates_for(graph_name, subject).await.unwrap();
+    predicates.sort();
+    predicates
+}
+
+#[tokio::test]
+async fn top_terms_follow_modifications() {
+    let storage = create_storage();
+    let g1 = "http://example.com/g1";
+    let other_predicate = Quad::new(
+        NamedNode::new_unchecked("http://example.com/subject"),
+        NamedNode::new_unchecked("http://example.com/other"),
+        Literal::new_simple_literal("value"),
+        GraphName::DefaultGraph,
+    );
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad(),
+            example_quad_in_graph(g1),
+            other_predicate.clone(),
+        ])
+        .await
+        .unwrap();
+    // Duplicates must not be counted twice.
+    storage.extend(vec![example_quad()]).await.unwrap();
+
+    let predicate = Term::from(NamedNode::new_unchecked("http://example.com/predicate"));
+    let other = Term::from(NamedNode::new_unchecked("http://example.com/other"));
+    let graph = Term::from(NamedNode::new_unchecked(g1));
+    assert_eq!(
+        top_terms(&storage, QuadPosition::Predicate, 10).await,
+        vec![(predicate.clone(), 2), (other.clone(), 1)]
+    );
+    assert_eq!(
+        top_terms(&storage, QuadPosition::Predicate, 1).await,
+        vec![(predicate.clone(), 2)]
+    );
+    assert_eq!(
+        top_terms(&storage, QuadPosition::GraphName, 10).await,
+        vec![(graph, 1)]
+    );
+
+    // Removing a quad that does not exist must not change the frequencies.
+    storage.remove(other_predicate.as_ref()).await.unwrap();
+    storage.remove(other_predicate.as_ref()).await.unwrap();
+    assert_eq!(
+        top_terms(&storage, QuadPosition::Predicate, 10).await,
+        vec![(predicate.clone(), 2)]
+    );
+
+    storage
+        .clear_graph(GraphNameRef::NamedNode(NamedNodeRef::new_unchecked(g1)))
+        .await
+        .unwrap();
+    assert_eq!(
+        top_terms(&storage, QuadPosition::Predicate, 10).await,
+        vec![(predicate, 1)]
+    );
+    assert!(
+        top_terms(&storage, QuadPosition::GraphName, 10)
+            .await
+            .is_empty()
+    );
+
+    storage.clear().await.unwrap();
+    assert!(
+        top_terms(&storage, QuadPosition::Subject, 10)
+            .await
+            .is_empty()
+    );
+}
+
+async fn top_terms(
+    storage: &MemQuadStorage,
+    position: QuadPosition,
+    k: usize,
+) -> Vec<(Term, usize)> {
+    storage.top_terms(position, k).await.unwrap()
+}
+
+#[tokio::test]
+async fn storage_info_lists_index_permutations() {
+    let storage = create_storage();
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad_in_graph("http://example.com/g"),
+        ])
+        .await
+        .unwrap();
+    let predicate = NamedNode::new("http://example.com/predicate").unwrap();
+    storage
+        .create_predicate_index(predicate.as_ref())
+        .await
+        .unwrap();
+
+    let info = storage.storage_info().await.unwrap().unwrap();
+
+    assert_eq!(info.quad_count, 2);
+    let indexes = info
+        .indexes
+        .iter()
+        .map(|index| {
+            (
+                index.name.as_str(),
+                index.predicate.as_ref(),
+                index.quad_count,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        indexes,
+        vec![
+            ("GSPO", None, 2),
+            ("GPOS", None, 2),
+            ("GOSP", None, 2),
+            ("GPOS", Some(&predicate), 2),
+        ]
+    );
+    assert!(info.indexes.iter().all(|index| index.memory_size > 0));
+    assert_eq!(
+        info.memory_size(),
+        info.indexes
+            .iter()
+            .map(|index| index.memory_size)
+            .sum::<usize>()
+    );
+}
+
+#[test]
+fn object_id_encoding_uses_width_of_mapping() {
+    let encoding = create_object_id_encoding();
+
+    assert_eq!(i32::from(encoding.object_id_size()), 4);
+    assert_eq!(encoding.data_type(), &DataType::UInt32);
+}
+
+#[test]
+fn object_id_encoding_accepts_matching_widths() {
+    let encoding = create_object_id_encoding();
+
+    let array = Arc::new(UInt32Array::from(vec![1, 2])) as ArrayRef;
+    assert!(encoding.try_new_array(array).is_ok());
+    assert!(
+        encoding
+            .try_new_scalar(ScalarValue::UInt32(Some(1)))
+            .is_ok()
+    );
+
+    let object_id = ObjectId::try_new([0, 0, 0, 1]).unwrap();
+    assert!(ObjectIdScalar::from_object_id(Arc::clone(&encoding), object_id).is_ok());
+}
+
+#[test]
+fn object_id_encoding_rejects_mismatched_widths() {
+    let encoding = create_object_id_encoding();
+
+    let array = Arc::new(UInt64Array::from(vec![1, 2])) as ArrayRef;
+    assert!(encoding.try_new_array(array).is_err());
+    assert!(
+        encoding
+            .try_new_scalar(ScalarValue::UInt64(Some(1)))
+            .is_err()
+    );
+
+    let object_id = ObjectId::try_new([0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+    assert!(ObjectIdScalar::from_object_id(Arc::clone(&encoding), object_id).is_err());
+}
+
+#[test]
+fn object_id_size_maps_to_data_type() {
+    let size = ObjectIdSize::try_from(4).unwrap();
+    assert_eq!(size.data_type(), Some(DataType::UInt32));
+
+    let size = ObjectIdSize::try_from(8).unwrap();
+    assert_eq!(size.data_type(), Some(DataType::UInt64));
+}
+
+#[test]
+fn object_id_size_without_index_support_has_no_data_type() {
+    let size = ObjectIdSize::try_from(16).unwrap();
+    assert_eq!(size.data_type(), None);
+}
+
+#[tokio::test]
+async fn insert_then_scan_with_4_byte_ids() {
+    assert_insert_then_scan(ObjectIdWidth::U32, &DataType::UInt32).await;
+}
+
+#[tokio::test]
+async fn insert_then_scan_with_8_byte_ids() {
+    assert_insert_then_scan(ObjectIdWidth::U64, &DataType::UInt64).await;
+}
+
+#[test]
+fn storage_rejects_encoding_with_mismatched_width() {
+    let mapping = Arc::new(MemObjectIdMapping::new_with_width(ObjectIdWidth::U64));
+    let encoding = create_object_id_encoding();
+
+    let result = MemQuadStorage::new(mapping, encoding, 10);
+    assert!(result.is_err());
+}
+
 #[tokio::test]
 async fn validate_storage() {
     let storage = create_storage();
@@ -256,5 +1075,98 @@ fn create_storage() -> MemQuadStorage {
     let encoding = Arc::new(ObjectIdEncoding::new(
         Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
     ));
-    MemQuadStorage::new(mapping, encoding, 10)
+    MemQuadStorage::new(mapping, encoding, 10).unwrap()
+}
+
+fn create_storage_with_named_graphs(kind: NamedGraphStorageKind) -> MemQuadStorage {
+    let mapping = Arc::new(MemObjectIdMapping::new());
+    let encoding = Arc::new(ObjectIdEncoding::new(
+        Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
+    ));
+    MemQuadStorage::new_with_named_graph_storage(mapping, encoding, 10, kind).unwrap()
+}
+
+/// Inserts quads into a storage with object ids of `width`, scans them with a bound subject and
+/// checks that the scanned columns use `data_type` and decode to the inserted terms.
+async fn assert_insert_then_scan(width: ObjectIdWidth, data_type: &DataType) {
+    let mapping = Arc::new(MemObjectIdMapping::new_with_width(width));
+    let encoding = Arc::new(ObjectIdEncoding::new(
+        Arc::clone(&mapping) as Arc<dyn ObjectIdMapping>
+    ));
+    let storage = MemQuadStorage::new(Arc::clone(&mapping), encoding, 10).unwrap();
+    let quad = |subject: &str, object: i32, graph: GraphName| {
+        Quad::new(
+            NamedNode::new(format!("http://example.com/{subject}")).unwrap(),
+            NamedNode::new("http://example.com/predicate").unwrap(),
+            Literal::from(object),
+            graph,
+        )
+    };
+    let inserted = storage
+        .extend(vec![
+            quad("s0", 1, GraphName::DefaultGraph),
+            quad(
+                "s0",
+                2,
+                GraphName::NamedNode(NamedNode::new("http://example.com/g").unwrap()),
+            ),
+            quad("s1", 3, GraphName::DefaultGraph),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(inserted, 3);
+
+    let ep_metrics = ExecutionPlanMetricsSet::default();
+    let metrics = BaselineMetrics::new(&ep_metrics, 0);
+    let batch = storage
+        .snapshot()
+        .await
+        .plan_pattern_evaluation(
+            ActiveGraph::AllGraphs,
+            Some(Variable::new_unchecked("g")),
+            TriplePattern {
+                subject: TermPattern::NamedNode(
+                    NamedNode::new("http://example.com/s0").unwrap(),
+                ),
+                predicate: NamedNodePattern::Variable(Variable::new_unchecked("p")),
+                object: TermPattern::Variable(Variable::new_unchecked("o")),
+            },
+            BlankNodeMatchingMode::Filter,
+        )
+        .await
+        .unwrap()
+        .create_stream(metrics)
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(batch.num_rows(), 2);
+    assert!(
+        batch
+            .schema()
+            .fields()
+            .iter()
+            .all(|field| field.data_type() == data_type)
+    );
+
+    let objects = mapping
+        .decode_array(batch.column_by_name("o").unwrap().as_ref())
+        .unwrap();
+    let mut objects = DefaultPlainTermDecoder::decode_terms(&objects)
+        .map(|term| term.unwrap().to_string())
+        .collect::<Vec<_>>();
+    objects.sort();
+    assert_eq!(
+        objects,
+        vec![
+            "\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"2\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+        ]
+    );
+}
+
+fn create_object_id_encoding() -> Arc<ObjectIdEncoding> {
+    let mapping = Arc::new(MemObjectIdMapping::new());
+    Arc::new(ObjectIdEncoding::new(mapping as Arc<dyn ObjectIdMapping>))
 }
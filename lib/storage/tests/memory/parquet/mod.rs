@@ -3,12 +3,16 @@ use crate::{example_quad, example_quad_in_graph};
 use datafusion::config::ConfigOptions;
 use insta::assert_binary_snapshot;
 use rdf_fusion_encoding::QuadStorageEncoding;
+use rdf_fusion_encoding::object_id::ObjectId;
+use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
 use rdf_fusion_extensions::storage::QuadStorage;
+use rdf_fusion_model::{NamedNode, TermRef};
 use rdf_fusion_storage::memory::{
-    MemQuadPersistenceOptions, MemQuadStoragePersistence,
+    MemQuadPersistenceOptions, MemQuadStorage, MemQuadStoragePersistence,
     ParquetMemQuadStoragePersistence,
 };
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
 /// A simple writer that is backed by a [`Vec<u8>`]. This implements interior mutability to allow
@@ -65,3 +69,71 @@ async fn test_object_id_storage_export_parquet_as_plain_term_encoding() {
         writer.inner.read().expect("poison").to_vec()
     );
 }
+
+#[tokio::test]
+async fn test_dump_and_load_parquet_retains_object_ids() {
+    let path = dump_directory("retains_object_ids");
+    let storage = create_storage();
+    let empty_graph = NamedNode::new("http://example.com/empty").unwrap();
+    storage
+        .extend(vec![
+            example_quad(),
+            example_quad_in_graph("http://example.com/g"),
+        ])
+        .await
+        .unwrap();
+    storage
+        .insert_named_graph(empty_graph.as_ref().into())
+        .await
+        .unwrap();
+    storage.dump_to_parquet(&path).await.unwrap();
+
+    let loaded = create_storage();
+    let count = loaded.load_from_parquet(&path).await.unwrap();
+    std::fs::remove_dir_all(&path).unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(loaded.len().await.unwrap(), 2);
+    assert!(
+        loaded
+            .contains_named_graph(empty_graph.as_ref().into())
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        object_id(&storage, example_quad().object.as_ref()),
+        object_id(&loaded, example_quad().object.as_ref())
+    );
+    assert_eq!(
+        object_id(&storage, example_quad().subject.as_ref().into()),
+        object_id(&loaded, example_quad().subject.as_ref().into())
+    );
+}
+
+#[tokio::test]
+async fn test_load_parquet_into_non_empty_storage_fails() {
+    let path = dump_directory("non_empty_storage");
+    let storage = create_storage();
+    storage.extend(vec![example_quad()]).await.unwrap();
+    storage.dump_to_parquet(&path).await.unwrap();
+
+    let result = storage.load_from_parquet(&path).await;
+    std::fs::remove_dir_all(&path).unwrap();
+
+    assert!(result.is_err());
+}
+
+/// Returns a fresh directory for writing a dump.
+fn dump_directory(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rdf-fusion-dump-{name}-{}", std::process::id()))
+}
+
+/// Returns the object id of `term` in `storage`.
+fn object_id(storage: &MemQuadStorage, term: TermRef<'_>) -> Option<ObjectId> {
+    let scalar = PLAIN_TERM_ENCODING.encode_term(Ok(term)).unwrap();
+    storage
+        .object_id_mapping()
+        .unwrap()
+        .try_get_object_id(&scalar)
+        .unwrap()
+}
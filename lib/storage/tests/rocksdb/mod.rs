@@ -0,0 +1,196 @@
+use crate::{example_quad, example_quad_in_graph};
+use rdf_fusion_encoding::object_id::ObjectId;
+use rdf_fusion_encoding::plain_term::PLAIN_TERM_ENCODING;
+use rdf_fusion_extensions::storage::QuadStorage;
+use rdf_fusion_model::{GraphNameRef, NamedNode, NamedOrBlankNode, TermRef};
+use rdf_fusion_storage::rocksdb::RocksDbQuadStorage;
+use std::path::{Path, PathBuf};
+
+#[tokio::test]
+async fn insert_quad() {
+    let directory = TestDirectory::new("insert_quad");
+    let storage = create_storage(directory.path());
+
+    let inserted = storage.extend(vec![example_quad()]).await.unwrap();
+    assert_eq!(inserted, 1);
+
+    let len = storage.len().await.unwrap();
+    assert_eq!(len, 1);
+}
+
+#[tokio::test]
+async fn insert_duplicate_quads_no_effect() {
+    let directory = TestDirectory::new("insert_duplicate_quads_no_effect");
+    let storage = create_storage(directory.path());
+
+    storage.extend(vec![example_quad()]).await.unwrap();
+
+    let inserted = storage
+        .extend(vec![example_quad(), example_quad()])
+        .await
+        .unwrap();
+    assert_eq!(inserted, 0); // duplicate
+}
+
+#[tokio::test]
+async fn named_graph_insertion_and_query() {
+    let directory = TestDirectory::new("named_graph_insertion_and_query");
+    let storage = create_storage(directory.path());
+    let graph =
+        NamedOrBlankNode::NamedNode(NamedNode::new("http://example.com/graph").unwrap());
+
+    let inserted = storage.insert_named_graph(graph.as_ref()).await.unwrap();
+    assert!(inserted);
+
+    let exists = storage.contains_named_graph(graph.as_ref()).await.unwrap();
+    assert!(exists);
+
+    let graphs = storage.named_graphs().await.unwrap();
+    assert_eq!(graphs, vec![graph]);
+}
+
+#[tokio::test]
+async fn remove_quad() {
+    let directory = TestDirectory::new("remove_quad");
+    let storage = create_storage(directory.path());
+    let quad = example_quad_in_graph("http://example.com/g");
+
+    storage.extend(vec![quad.clone()]).await.unwrap();
+    let removed = storage.remove(quad.as_ref()).await.unwrap();
+    assert!(removed);
+
+    let len = storage.len().await.unwrap();
+    assert_eq!(len, 0);
+    storage.validate().await.unwrap();
+}
+
+#[tokio::test]
+async fn clear_graph() {
+    let directory = TestDirectory::new("clear_graph");
+    let storage = create_storage(directory.path());
+
+    let g1 = "http://example.com/g1";
+    let g2 = "http://example.com/g2";
+
+    storage
+        .extend(vec![example_quad_in_graph(g1), example_quad_in_graph(g2)])
+        .await
+        .unwrap();
+
+    storage
+        .clear_graph(GraphNameRef::NamedNode(
+            NamedNode::new(g1).unwrap().as_ref(),
+        ))
+        .await
+        .unwrap();
+
+    let len = storage.len().await.unwrap();
+    assert_eq!(len, 1);
+    storage.validate().await.unwrap();
+}
+
+#[tokio::test]
+async fn remove_named_graph() {
+    let directory = TestDirectory::new("remove_named_graph");
+    let storage = create_storage(directory.path());
+    let graph =
+        NamedOrBlankNode::NamedNode(NamedNode::new("http://example.com/graph").unwrap());
+
+    storage
+        .extend(vec![example_quad_in_graph("http://example.com/graph")])
+        .await
+        .unwrap();
+    let removed = storage.drop_named_graph(graph.as_ref()).await.unwrap();
+    assert!(removed);
+
+    let exists = storage.contains_named_graph(graph.as_ref()).await.unwrap();
+    assert!(!exists);
+    assert_eq!(storage.len().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn clear_all() {
+    let directory = TestDirectory::new("clear_all");
+    let storage = create_storage(directory.path());
+    storage
+        .extend(vec![
+            example_quad(), // default graph
+            example_quad_in_graph("http://example.com/g1"),
+            example_quad_in_graph("http://example.com/g2"),
+        ])
+        .await
+        .unwrap();
+
+    storage.clear().await.unwrap();
+    let len = storage.len().await.unwrap();
+    assert_eq!(len, 0);
+    assert_eq!(storage.named_graphs().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn reopen_retains_quads_and_object_ids() {
+    let directory = TestDirectory::new("reopen_retains_quads_and_object_ids");
+    let quad = example_quad_in_graph("http://example.com/g");
+
+    let object_id = {
+        let storage = create_storage(directory.path());
+        storage.extend(vec![quad.clone()]).await.unwrap();
+        object_id(&storage, quad.subject.as_ref().into())
+    };
+
+    let storage = create_storage(directory.path());
+    assert_eq!(storage.len().await.unwrap(), 1);
+    assert!(object_id.is_some());
+    assert_eq!(object_id(&storage, quad.subject.as_ref().into()), object_id);
+
+    let removed = storage.remove(quad.as_ref()).await.unwrap();
+    assert!(removed);
+}
+
+#[tokio::test]
+async fn validate_storage() {
+    let directory = TestDirectory::new("validate_storage");
+    let storage = create_storage(directory.path());
+    storage
+        .extend(vec![example_quad(), example_quad_in_graph("http://g")])
+        .await
+        .unwrap();
+
+    storage.optimize().await.unwrap();
+    storage.validate().await.unwrap();
+}
+
+fn object_id(storage: &RocksDbQuadStorage, term: TermRef<'_>) -> Option<ObjectId> {
+    let scalar = PLAIN_TERM_ENCODING.encode_term(Ok(term)).unwrap();
+    storage
+        .object_id_mapping()
+        .unwrap()
+        .try_get_object_id(&scalar)
+        .unwrap()
+}
+
+fn create_storage(path: &Path) -> RocksDbQuadStorage {
+    RocksDbQuadStorage::open(path, 10).unwrap()
+}
+
+/// A directory for a database that is removed once the test finishes.
+struct TestDirectory(PathBuf);
+
+impl TestDirectory {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir()
+            .join(format!("rdf-fusion-rocksdb-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        Self(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
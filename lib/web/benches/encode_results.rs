@@ -4,7 +4,7 @@ use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::prelude::SessionConfig;
 use rdf_fusion::model::{GraphName, NamedNode, NamedOrBlankNode, Quad, Term};
 use rdf_fusion::store::Store;
-use rdf_fusion_web::{AppState, create_router};
+use rdf_fusion_web::{AppState, DEFAULT_MAX_GET_QUERY_LENGTH, create_router};
 use std::sync::Arc;
 use tokio::runtime::Builder;
 
@@ -24,6 +24,11 @@ fn encode_solution(criterion: &mut Criterion) {
         store: Arc::new(store),
         read_only: false,
         union_default_graph: false,
+        admin: false,
+        max_get_query_length: DEFAULT_MAX_GET_QUERY_LENGTH,
+        max_update_size: None,
+        query_limiter: None,
+        load_directories: Arc::new([]),
     };
 
     let app = create_router(app_state);
@@ -44,9 +49,9 @@ fn encode_solution(criterion: &mut Criterion) {
 
 fn generate_quads(count: usize) -> impl Iterator<Item = Quad> {
     (0..count).map(|i| {
-        let subject = format!("http://example.com/subject{}", i);
-        let predicate = format!("http://example.com/predicate{}", i);
-        let object = format!("http://example.com/object{}", i);
+        let subject = format!("http://example.com/subject{i}");
+        let predicate = format!("http://example.com/predicate{i}");
+        let object = format!("http://example.com/object{i}");
         Quad::new(
             NamedOrBlankNode::NamedNode(NamedNode::new_unchecked(subject)),
             NamedNode::new_unchecked(predicate),
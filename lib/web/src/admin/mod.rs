@@ -0,0 +1,74 @@
+use crate::AppState;
+use crate::error::RdfFusionServerError;
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use rdf_fusion::api::storage::{IndexInfo, StorageInfo};
+use serde::Serialize;
+
+/// Creates the routes for administrative endpoints.
+///
+/// These endpoints expose internals of the store and are only available if the server has been
+/// started with admin endpoints enabled.
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new().route("/storage", get(handle_storage_get))
+}
+
+/// Returns diagnostic information on the index permutations of the storage.
+async fn handle_storage_get(
+    State(state): State<AppState>,
+) -> Result<Json<StorageInfoResponse>, RdfFusionServerError> {
+    let info = state
+        .store
+        .storage_info()
+        .await
+        .map_err(|error| RdfFusionServerError::Internal(error.into()))?
+        .ok_or_else(|| {
+            RdfFusionServerError::NotSupported(
+                "The storage does not provide diagnostic information.".to_owned(),
+            )
+        })?;
+    Ok(Json(StorageInfoResponse::from(info)))
+}
+
+/// The JSON representation of [StorageInfo].
+#[derive(Debug, Serialize)]
+struct StorageInfoResponse {
+    quad_count: usize,
+    memory_size: usize,
+    indexes: Vec<IndexInfoResponse>,
+}
+
+/// The JSON representation of [IndexInfo].
+#[derive(Debug, Serialize)]
+struct IndexInfoResponse {
+    name: String,
+    predicate: Option<String>,
+    quad_count: usize,
+    memory_size: usize,
+}
+
+impl From<StorageInfo> for StorageInfoResponse {
+    fn from(info: StorageInfo) -> Self {
+        Self {
+            quad_count: info.quad_count,
+            memory_size: info.memory_size(),
+            indexes: info
+                .indexes
+                .into_iter()
+                .map(IndexInfoResponse::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<IndexInfo> for IndexInfoResponse {
+    fn from(info: IndexInfo) -> Self {
+        Self {
+            name: info.name,
+            predicate: info.predicate.map(|predicate| predicate.into_string()),
+            quad_count: info.quad_count,
+            memory_size: info.memory_size,
+        }
+    }
+}
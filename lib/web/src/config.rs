@@ -1,10 +1,21 @@
 use rdf_fusion::store::Store;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::time::Duration;
 
-#[allow(unused, reason = "Not yet implemented")]
-pub const MAX_SPARQL_BODY_SIZE: u64 = 1024 * 1024 * 128; // 128MB
+/// The default maximum size (in bytes) of a request body that is sent to the update endpoint.
+pub const DEFAULT_MAX_UPDATE_SIZE: usize = 1024 * 1024 * 128; // 128MB
 #[allow(unused, reason = "Not yet implemented")]
 pub const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+/// The default time that in-flight requests are given to finish during a graceful shutdown.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+/// The default maximum length (in bytes) of a query that is sent via `GET`.
+///
+/// Many clients, proxies, and servers limit the length of URLs to a few kilobytes.
+pub const DEFAULT_MAX_GET_QUERY_LENGTH: usize = 8 * 1024;
+/// The default time that a query waits for a free slot if the number of concurrent queries is
+/// limited (see [ExcessQueryBehavior::Queue]).
+pub const DEFAULT_QUERY_QUEUE_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Holds the configuration for a RDF Fusion web server.
 pub struct ServerConfig {
@@ -18,4 +29,50 @@ pub struct ServerConfig {
     pub cors: bool,
     /// Whether, by default, queries match against all graphs.
     pub union_default_graph: bool,
+    /// Whether the administrative endpoints under `/admin` (e.g., storage diagnostics) are
+    /// available. These endpoints expose internals of the store and should only be enabled for
+    /// trusted clients.
+    pub admin: bool,
+    /// How long in-flight requests are allowed to run after a shutdown signal has been received.
+    ///
+    /// Once this timeout expires, the server stops without waiting for the remaining requests.
+    pub shutdown_timeout: Duration,
+    /// The maximum length (in bytes) of a query that is sent via `GET`.
+    ///
+    /// Longer queries are rejected with `414 URI Too Long` and must be sent via `POST`.
+    pub max_get_query_length: usize,
+    /// The maximum size (in bytes) of a request body that is sent to the update endpoint.
+    ///
+    /// Larger updates are rejected with `413 Payload Too Large`. [None] disables the limit. The
+    /// size of other request bodies (e.g., RDF data sent to the data endpoint) is not limited.
+    pub max_update_size: Option<usize>,
+    /// Limits the number of queries that are evaluated concurrently. [None] disables the limit.
+    pub query_concurrency_limit: Option<QueryConcurrencyLimit>,
+    /// The directories from which SPARQL updates may `LOAD` files.
+    ///
+    /// `LOAD` reads files from the machine that runs the server. Hence, clients can read any file
+    /// within these directories. An empty list rejects loading any file.
+    pub load_directories: Vec<PathBuf>,
+}
+
+/// Limits the number of queries that a server evaluates concurrently.
+///
+/// A query occupies one of the slots until its results have been sent to the client. Requests
+/// for the service description and updates are not limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryConcurrencyLimit {
+    /// The maximum number of queries that are evaluated at the same time.
+    pub max_concurrent_queries: NonZeroUsize,
+    /// What happens with queries that arrive while all slots are occupied.
+    pub excess_queries: ExcessQueryBehavior,
+}
+
+/// Defines how a server handles queries that exceed the [QueryConcurrencyLimit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExcessQueryBehavior {
+    /// The query waits for a free slot. If no slot becomes available within `timeout`, the query
+    /// is rejected with `503 Service Unavailable`.
+    Queue { timeout: Duration },
+    /// The query is immediately rejected with `503 Service Unavailable`.
+    Reject,
 }
@@ -7,10 +7,20 @@ use axum::{
 pub enum RdfFusionServerError {
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("URI too long: {0}")]
+    UriTooLong(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
     #[error("Content Negotiation Failed: {0}")]
     ContentNegotiation(String),
     #[error("Server is read-only")]
     ReadOnly,
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
     #[error("Internal server error: {0}")]
     Internal(anyhow::Error),
 }
@@ -19,6 +29,13 @@ impl IntoResponse for RdfFusionServerError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             RdfFusionServerError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            RdfFusionServerError::UriTooLong(msg) => (StatusCode::URI_TOO_LONG, msg),
+            RdfFusionServerError::PayloadTooLarge(msg) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, msg)
+            }
+            RdfFusionServerError::UnsupportedMediaType(msg) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg)
+            }
             RdfFusionServerError::ContentNegotiation(msg) => {
                 (StatusCode::NOT_ACCEPTABLE, msg)
             }
@@ -26,6 +43,10 @@ impl IntoResponse for RdfFusionServerError {
                 StatusCode::FORBIDDEN,
                 "Server is in read-only mode".to_owned(),
             ),
+            RdfFusionServerError::NotSupported(msg) => (StatusCode::NOT_IMPLEMENTED, msg),
+            RdfFusionServerError::ServiceUnavailable(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, msg)
+            }
             RdfFusionServerError::Internal(e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
             }
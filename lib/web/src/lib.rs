@@ -10,26 +10,38 @@
 
 use axum::body::Body;
 use axum::extract::DefaultBodyLimit;
-use axum::http::Request;
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderName, Request};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::{Router, middleware, routing::get};
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use tower_http::trace::{HttpMakeClassifier, TraceLayer};
-use tracing::{Level, error};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use tower_http::request_id::{
+    MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer,
+};
+use tower_http::trace::{HttpMakeClassifier, MakeSpan, TraceLayer};
+use tracing::{Level, Span, error, info, warn};
 
+mod admin;
 mod app;
 mod config;
 mod error;
 mod repositories;
 mod state;
 
+use crate::admin::create_admin_routes;
 use crate::app::create_app_routes;
 use crate::repositories::create_repositories_routes;
-pub use config::ServerConfig;
-pub use state::AppState;
+pub use config::{
+    DEFAULT_MAX_GET_QUERY_LENGTH, DEFAULT_MAX_UPDATE_SIZE, DEFAULT_QUERY_QUEUE_TIMEOUT,
+    DEFAULT_SHUTDOWN_TIMEOUT, ExcessQueryBehavior, QueryConcurrencyLimit, ServerConfig,
+};
+pub use state::{AppState, QueryLimiter};
 
 // TODO: proper logging
 #[allow(clippy::print_stdout)]
@@ -40,6 +52,11 @@ pub async fn serve(config: ServerConfig) -> anyhow::Result<()> {
         store: Arc::new(config.store),
         read_only: config.read_only,
         union_default_graph: config.union_default_graph,
+        admin: config.admin,
+        max_get_query_length: config.max_get_query_length,
+        max_update_size: config.max_update_size,
+        query_limiter: config.query_concurrency_limit.map(QueryLimiter::new),
+        load_directories: config.load_directories.into(),
     };
     let app = create_router(app_state);
 
@@ -52,49 +69,179 @@ pub async fn serve(config: ServerConfig) -> anyhow::Result<()> {
 
     println!("Listening on {addr}");
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    Ok(axum::serve(listener, app).await?)
+    let listener = TcpListener::bind(addr).await?;
+    run_until_shutdown(listener, app, shutdown_signal(), config.shutdown_timeout).await
+}
+
+/// Serves `app` until `signal` completes.
+///
+/// Once `signal` completes, the server stops accepting new connections and waits for the
+/// in-flight requests to finish. If they do not finish within `shutdown_timeout`, the server
+/// stops anyway and the remaining requests are aborted.
+async fn run_until_shutdown(
+    listener: TcpListener,
+    app: Router,
+    signal: impl Future<Output = ()> + Send + 'static,
+    shutdown_timeout: Duration,
+) -> anyhow::Result<()> {
+    let shutdown_started = Arc::new(Notify::new());
+    let server = axum::serve(listener, app).with_graceful_shutdown({
+        let shutdown_started = Arc::clone(&shutdown_started);
+        async move {
+            signal.await;
+            info!(
+                "Shutting down, waiting up to {shutdown_timeout:?} for in-flight requests"
+            );
+            shutdown_started.notify_one();
+        }
+    });
+
+    let drain_timeout = async {
+        shutdown_started.notified().await;
+        tokio::time::sleep(shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => Ok(result?),
+        () = drain_timeout => {
+            warn!("In-flight requests did not finish within {shutdown_timeout:?}, aborting them");
+            Ok(())
+        }
+    }
+}
+
+/// Completes once the process receives SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            error!("Failed to install the Ctrl+C handler: {error}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                error!("Failed to install the SIGTERM handler: {error}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {},
+        () = terminate => {},
+    }
 }
 
 pub fn create_router(app_state: AppState) -> Router {
-    Router::new()
+    let router = Router::new()
         .route("/", get(|| async { Redirect::permanent("/app") }))
         .nest("/app", create_app_routes())
-        .nest("/repositories", create_repositories_routes())
+        .nest(
+            "/repositories",
+            create_repositories_routes(app_state.max_update_size),
+        );
+    let router = if app_state.admin {
+        router.nest("/admin", create_admin_routes())
+    } else {
+        router
+    };
+
+    router
         .with_state(app_state)
         .layer(DefaultBodyLimit::disable())
         .layer(create_tracing_layer())
         .layer(middleware::from_fn(log_error_responses))
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
 }
 
+/// The header that holds the id of a request.
+///
+/// If a client does not provide a request id, a new one is generated. The id is always returned
+/// in the response.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
 /// Creates the tracing (logging) layer for the web application.
-fn create_tracing_layer() -> TraceLayer<HttpMakeClassifier> {
+fn create_tracing_layer() -> TraceLayer<HttpMakeClassifier, RequestSpan> {
     TraceLayer::new_for_http()
-        .make_span_with(tower_http::trace::DefaultMakeSpan::new().level(Level::INFO))
+        .make_span_with(RequestSpan)
         .on_request(tower_http::trace::DefaultOnRequest::new().level(Level::DEBUG))
         .on_response(tower_http::trace::DefaultOnResponse::new().level(Level::DEBUG))
         .on_failure(tower_http::trace::DefaultOnFailure::new().level(Level::ERROR))
 }
 
+/// Creates the span of a request. The span includes the id of the request.
+#[derive(Debug, Clone, Copy)]
+struct RequestSpan;
+
+impl<B> MakeSpan<B> for RequestSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        let request_id = request_id(request).unwrap_or_default();
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %request.uri(),
+            version = ?request.version(),
+            request_id = %request_id,
+        )
+    }
+}
+
+/// Returns the id of the request, if available.
+fn request_id<B>(request: &Request<B>) -> Option<String> {
+    request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
 /// Logs the body of an error response.
+///
+/// The id of the request is included in the log message and appended to plain-text error
+/// responses such that client reports can be correlated with the server logs.
 pub async fn log_error_responses(req: Request<Body>, next: Next) -> impl IntoResponse {
+    let request_id = request_id(&req).unwrap_or_default();
     let response = next.run(req).await;
     let status = response.status();
 
     if status.is_client_error() || status.is_server_error() {
-        let (parts, body) = response.into_parts();
+        let (mut parts, body) = response.into_parts();
         let body_result = axum::body::to_bytes(body, usize::MAX).await;
 
         match body_result {
             Ok(bytes) => {
                 let body_text = String::from_utf8_lossy(bytes.as_ref());
-                error!("Error response {}: {}", status, body_text);
-                Response::from_parts(parts, Body::from(bytes))
+                error!(
+                    "Error response {} (request id: {}): {}",
+                    status, request_id, body_text
+                );
+
+                let is_plain_text = parts
+                    .headers
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_none_or(|value| value.starts_with("text/plain"));
+                if is_plain_text && !request_id.is_empty() {
+                    let body = format!("{body_text}\nRequest ID: {request_id}");
+                    parts.headers.remove(CONTENT_LENGTH);
+                    Response::from_parts(parts, Body::from(body))
+                } else {
+                    Response::from_parts(parts, Body::from(bytes))
+                }
             }
             Err(error) => {
                 error!(
-                    "Error response {}: <Could not read body>, {}",
-                    status, error
+                    "Error response {} (request id: {}): <Could not read body>, {}",
+                    status, request_id, error
                 );
                 Response::from_parts(parts, Body::empty())
             }
@@ -103,3 +250,733 @@ pub async fn log_error_responses(req: Request<Body>, next: Next) -> impl IntoRes
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use axum::http::header::ACCEPT;
+    use axum::http::{HeaderValue, StatusCode};
+    use axum_test::TestServer;
+    use rdf_fusion::io::RdfFormat;
+    use rdf_fusion::model::{GraphName, Literal, NamedNode, Quad};
+    use rdf_fusion::store::Store;
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::num::NonZeroUsize;
+
+    #[tokio::test]
+    async fn test_shutdown_without_in_flight_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let app = Router::new().route("/", get(|| async { "ok" }));
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            run_until_shutdown(listener, app, async {}, Duration::from_secs(60)),
+        )
+        .await;
+
+        assert!(result.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_requests_exceeding_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/", get(std::future::pending::<()>));
+
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            stream
+        });
+        let signal = tokio::time::sleep(Duration::from_millis(200));
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            run_until_shutdown(listener, app, signal, Duration::from_millis(100)),
+        )
+        .await;
+
+        assert!(result.unwrap().is_ok());
+        drop(client.join().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_error_response_contains_given_request_id() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT * WHERE {")
+            .add_header(REQUEST_ID_HEADER, HeaderValue::from_static("my-request"))
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.header(REQUEST_ID_HEADER), "my-request");
+        assert!(response.text().ends_with("Request ID: my-request"));
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT * WHERE {")
+            .expect_failure()
+            .await;
+
+        let request_id = response.header(REQUEST_ID_HEADER);
+        let request_id = request_id.to_str().unwrap();
+        assert!(!request_id.is_empty());
+        assert!(
+            response
+                .text()
+                .ends_with(&format!("Request ID: {request_id}"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_json_ld_data() {
+        let store = Arc::new(Store::default());
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::clone(&store),
+            read_only: false,
+            union_default_graph: false,
+            admin: false,
+            max_get_query_length: DEFAULT_MAX_GET_QUERY_LENGTH,
+            max_update_size: None,
+            query_limiter: None,
+            load_directories: Arc::new([]),
+        }))
+        .unwrap();
+
+        server
+            .post("/repositories/default/data")
+            .bytes(Bytes::from_static(
+                br#"{
+                    "@context": { "name": "http://schema.org/name" },
+                    "@id": "http://example.com/paris",
+                    "name": "Paris"
+                }"#,
+            ))
+            .content_type("application/ld+json")
+            .expect_success()
+            .await;
+
+        assert_eq!(store.len().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_select_json_lines() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT ?x WHERE { VALUES ?x { 1 \"a\" } }")
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+
+        assert_eq!(response.header(CONTENT_TYPE), "application/x-ndjson");
+        assert_eq!(
+            response.text(),
+            "{\"x\":{\"type\":\"literal\",\"value\":\"1\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#integer\"}}\n\
+            {\"x\":{\"type\":\"literal\",\"value\":\"a\"}}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_csv() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param(
+                "query",
+                "SELECT ?x ?y WHERE { VALUES (?x ?y) { (<http://example/x> \"a\\\"b\") (UNDEF 1) } }",
+            )
+            .add_header(ACCEPT, HeaderValue::from_static("text/csv"))
+            .expect_success()
+            .await;
+
+        assert_eq!(response.header(CONTENT_TYPE), "text/csv; charset=utf-8");
+        assert_eq!(
+            response.text(),
+            "x,y\r\nhttp://example/x,\"a\"\"b\"\r\n,1\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_tsv() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param(
+                "query",
+                "SELECT ?x ?y WHERE { VALUES (?x ?y) { (<http://example/x> \"a\"@en) (UNDEF \"2011-01-10\"^^<http://www.w3.org/2001/XMLSchema#date>) } }",
+            )
+            .add_header(
+                ACCEPT,
+                HeaderValue::from_static("text/tab-separated-values"),
+            )
+            .expect_success()
+            .await;
+
+        assert_eq!(
+            response.header(CONTENT_TYPE),
+            "text/tab-separated-values; charset=utf-8"
+        );
+        assert_eq!(
+            response.text(),
+            "?x\t?y\n<http://example/x>\t\"a\"@en\n\t\"2011-01-10\"^^<http://www.w3.org/2001/XMLSchema#date>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_large_result_tsv() {
+        let store = Arc::new(many_quads_store(10_000).await);
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::clone(&store),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT ?s WHERE { ?s ?p ?o }")
+            .add_header(ACCEPT, HeaderValue::from_static("text/tsv"))
+            .expect_success()
+            .await;
+
+        assert_eq!(
+            response.header(CONTENT_TYPE),
+            "text/tab-separated-values; charset=utf-8"
+        );
+        let text = response.text();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("?s"));
+        assert_eq!(lines.count(), 10_000);
+    }
+
+    #[tokio::test]
+    async fn test_select_large_result_xml() {
+        let store = Arc::new(many_quads_store(10_000).await);
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::clone(&store),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT ?s WHERE { ?s ?p ?o }")
+            .add_header(
+                ACCEPT,
+                HeaderValue::from_static("application/sparql-results+xml"),
+            )
+            .expect_success()
+            .await;
+
+        assert_eq!(
+            response.header(CONTENT_TYPE),
+            "application/sparql-results+xml"
+        );
+        let text = response.text();
+        assert_eq!(text.matches("<sparql ").count(), 1);
+        assert_eq!(text.matches("<result>").count(), 10_000);
+        assert!(text.trim_end().ends_with("</sparql>"));
+    }
+
+    #[tokio::test]
+    async fn test_long_get_query_is_rejected() {
+        let server = TestServer::new(create_router(AppState {
+            max_get_query_length: 16,
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", SELECT_QUERY)
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::URI_TOO_LONG);
+        assert!(response.text().contains("via POST"));
+    }
+
+    #[tokio::test]
+    async fn test_excess_query_is_rejected() {
+        let query_limiter = QueryLimiter::new(QueryConcurrencyLimit {
+            max_concurrent_queries: NonZeroUsize::MIN,
+            excess_queries: ExcessQueryBehavior::Reject,
+        });
+        let server = TestServer::new(create_router(AppState {
+            query_limiter: Some(query_limiter.clone()),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let permit = query_limiter.acquire().await.unwrap();
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", SELECT_QUERY)
+            .expect_failure()
+            .await;
+        assert_eq!(response.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+
+        drop(permit);
+        server
+            .get("/repositories/default/query")
+            .add_query_param("query", SELECT_QUERY)
+            .expect_success()
+            .await;
+        // The slot of the previous query has been freed.
+        drop(query_limiter.acquire().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_excess_query_is_queued() {
+        let query_limiter = QueryLimiter::new(QueryConcurrencyLimit {
+            max_concurrent_queries: NonZeroUsize::MIN,
+            excess_queries: ExcessQueryBehavior::Queue {
+                timeout: Duration::from_millis(100),
+            },
+        });
+        let server = TestServer::new(create_router(AppState {
+            query_limiter: Some(query_limiter.clone()),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        // The query times out while waiting for the slot.
+        let permit = query_limiter.acquire().await.unwrap();
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", SELECT_QUERY)
+            .expect_failure()
+            .await;
+        assert_eq!(response.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // The query starts once the slot is freed.
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(permit);
+        });
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", SELECT_QUERY)
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+        assert_eq!(response.text(), SELECT_QUERY_RESULT);
+        release.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_query() {
+        let server = TestServer::new(create_router(AppState {
+            max_get_query_length: 16,
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .post("/repositories/default/query")
+            .text(SELECT_QUERY)
+            .content_type("application/sparql-query")
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+
+        assert_eq!(response.text(), SELECT_QUERY_RESULT);
+    }
+
+    #[tokio::test]
+    async fn test_post_query_form() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .post("/repositories/default/query")
+            .form(&[("query", SELECT_QUERY)])
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+
+        assert_eq!(response.text(), SELECT_QUERY_RESULT);
+    }
+
+    #[tokio::test]
+    async fn test_post_query_unsupported_content_type() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .post("/repositories/default/query")
+            .text(SELECT_QUERY)
+            .content_type("text/plain")
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[tokio::test]
+    async fn test_update_sent_to_query_endpoint_is_rejected() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        for update in [
+            "INSERT DATA { <http://example.com/s> <http://example.com/p> 1 }",
+            "PREFIX ex: <http://example.com/> # comment\n delete where { ?s ex:p ?o }",
+            "CLEAR DEFAULT; LOAD",
+        ] {
+            let response = server
+                .post("/repositories/default/query")
+                .text(update)
+                .content_type("application/sparql-query")
+                .expect_failure()
+                .await;
+
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+            assert!(response.text().contains("update endpoint"), "{update}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_with_update_keywords_as_variables() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param(
+                "query",
+                "SELECT ?insert ?delete WHERE { VALUES ?insert { 1 } }",
+            )
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT ?insert WHERE {")
+            .expect_failure()
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        assert!(!response.text().contains("update endpoint"));
+    }
+
+    #[tokio::test]
+    async fn test_post_update() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .post("/repositories/default/update")
+            .text(INSERT_UPDATE)
+            .content_type("application/sparql-update")
+            .expect_success()
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", "SELECT ?o WHERE { ?s ?p ?o }")
+            .add_header(ACCEPT, HeaderValue::from_static("application/x-ndjson"))
+            .expect_success()
+            .await;
+        assert_eq!(
+            response.text(),
+            "{\"o\":{\"type\":\"uri\",\"value\":\"http://example.com/o\"}}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_update_form() {
+        let store = Arc::new(Store::default());
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::clone(&store),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        server
+            .post("/repositories/default/update")
+            .form(&[("update", INSERT_UPDATE)])
+            .expect_success()
+            .await;
+
+        assert_eq!(store.len().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_post_update_read_only() {
+        let store = Arc::new(Store::default());
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::clone(&store),
+            read_only: true,
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .post("/repositories/default/update")
+            .text(INSERT_UPDATE)
+            .content_type("application/sparql-update")
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        assert!(store.is_empty().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_post_update_invalid_syntax() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .post("/repositories/default/update")
+            .text("INSERT DATA {")
+            .content_type("application/sparql-update")
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_post_update_exceeding_body_limit() {
+        let server = TestServer::new(create_router(AppState {
+            max_update_size: Some(16),
+            ..test_app_state()
+        }))
+        .unwrap();
+
+        let response = server
+            .post("/repositories/default/update")
+            .text(INSERT_UPDATE)
+            .content_type("application/sparql-update")
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_update_load_of_file_is_rejected_by_default() {
+        let state = test_app_state();
+        let store = Arc::clone(&state.store);
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        let response = server
+            .post("/repositories/default/update")
+            .text("LOAD <file:///etc/passwd>")
+            .content_type("application/sparql-update")
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        assert!(store.is_empty().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_update_load_of_file_outside_of_load_directories() {
+        let directory = std::env::temp_dir()
+            .join(format!("rdf-fusion-web-load-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file = directory.join("data.nt");
+        std::fs::write(
+            &file,
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n",
+        )
+        .unwrap();
+
+        let state = AppState {
+            load_directories: Arc::new([directory.clone()]),
+            ..test_app_state()
+        };
+        let store = Arc::clone(&state.store);
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        for source in [
+            "file:///etc/passwd".to_owned(),
+            format!("file://{}/../../etc/passwd", directory.display()),
+            format!("file://{}/missing.nt", directory.display()),
+        ] {
+            let response = server
+                .post("/repositories/default/update")
+                .text(format!("LOAD <{source}>"))
+                .content_type("application/sparql-update")
+                .expect_failure()
+                .await;
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+            assert!(!response.text().contains("No such file"), "{source}");
+        }
+
+        server
+            .post("/repositories/default/update")
+            .text(format!("LOAD <file://{}>", file.display()))
+            .content_type("application/sparql-update")
+            .expect_success()
+            .await;
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(store.len().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_post_data_is_not_limited_by_update_size() {
+        let state = AppState {
+            max_update_size: Some(16),
+            ..test_app_state()
+        };
+        let store = Arc::clone(&state.store);
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        server
+            .post("/repositories/default/data")
+            .text(
+                "<http://example.com/s> <http://example.com/p> <http://example.com/o> .",
+            )
+            .content_type("application/n-triples")
+            .expect_success()
+            .await;
+
+        assert_eq!(store.len().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_construct_prefers_highest_quality_format() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", CONSTRUCT_QUERY)
+            .add_header(
+                ACCEPT,
+                HeaderValue::from_static("text/turtle;q=0.9, application/n-quads;q=1.0"),
+            )
+            .expect_success()
+            .await;
+
+        assert_eq!(response.header(CONTENT_TYPE), "application/n-quads");
+        assert_eq!(
+            response.text(),
+            "<http://example.com/s> <http://example.com/p> <http://example.com/o> .\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_construct_turtle() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", CONSTRUCT_QUERY)
+            .add_header(
+                ACCEPT,
+                HeaderValue::from_static("application/n-quads;q=0.5, text/turtle"),
+            )
+            .expect_success()
+            .await;
+
+        assert_eq!(response.header(CONTENT_TYPE), "text/turtle");
+        assert!(response.text().contains("<http://example.com/s>"));
+    }
+
+    #[tokio::test]
+    async fn test_construct_unsupported_format() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server
+            .get("/repositories/default/query")
+            .add_query_param("query", CONSTRUCT_QUERY)
+            .add_header(ACCEPT, HeaderValue::from_static("image/png"))
+            .expect_failure()
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[tokio::test]
+    async fn test_admin_storage_info() {
+        let store = Store::default();
+        store
+            .load_from_reader(
+                RdfFormat::NTriples,
+                b"<http://example.com/s> <http://example.com/p> <http://example.com/o> ."
+                    .as_slice(),
+            )
+            .await
+            .unwrap();
+        let server = TestServer::new(create_router(AppState {
+            store: Arc::new(store),
+            read_only: false,
+            union_default_graph: false,
+            admin: true,
+            max_get_query_length: DEFAULT_MAX_GET_QUERY_LENGTH,
+            max_update_size: None,
+            query_limiter: None,
+            load_directories: Arc::new([]),
+        }))
+        .unwrap();
+
+        let response = server.get("/admin/storage").expect_success().await;
+
+        assert_eq!(response.header(CONTENT_TYPE), "application/json");
+        let text = response.text();
+        assert!(text.starts_with("{\"quad_count\":1,\"memory_size\":"));
+        assert!(text.contains("{\"name\":\"GSPO\",\"predicate\":null,\"quad_count\":1,"));
+    }
+
+    #[tokio::test]
+    async fn test_admin_endpoints_disabled_by_default() {
+        let server = TestServer::new(create_router(test_app_state())).unwrap();
+
+        let response = server.get("/admin/storage").expect_failure().await;
+
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    const SELECT_QUERY: &str = "SELECT ?x WHERE { VALUES ?x { \"a\" } }";
+
+    const SELECT_QUERY_RESULT: &str = "{\"x\":{\"type\":\"literal\",\"value\":\"a\"}}\n";
+
+    const INSERT_UPDATE: &str = "INSERT DATA { \
+        <http://example.com/s> <http://example.com/p> <http://example.com/o> }";
+
+    const CONSTRUCT_QUERY: &str = "CONSTRUCT { <http://example.com/s> <http://example.com/p> ?o } \
+        WHERE { VALUES ?o { <http://example.com/o> } }";
+
+    async fn many_quads_store(count: usize) -> Store {
+        let store = Store::default();
+        store
+            .extend((0..count).map(|i| {
+                Quad::new(
+                    NamedNode::new_unchecked(format!("http://example.com/s{i}")),
+                    NamedNode::new_unchecked("http://example.com/p"),
+                    Literal::from(i64::try_from(i).unwrap()),
+                    GraphName::DefaultGraph,
+                )
+            }))
+            .await
+            .unwrap();
+        store
+    }
+
+    fn test_app_state() -> AppState {
+        AppState {
+            store: Arc::new(Store::default()),
+            read_only: false,
+            union_default_graph: false,
+            admin: false,
+            max_get_query_length: DEFAULT_MAX_GET_QUERY_LENGTH,
+            max_update_size: None,
+            query_limiter: None,
+            load_directories: Arc::new([]),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::AppState;
 use crate::error::RdfFusionServerError;
+use crate::repositories::query::SolutionsFormat;
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use headers::HeaderMapExt;
@@ -19,20 +20,31 @@ impl FromRequestParts<AppState> for RdfFormat {
         parts: &mut Parts,
         _state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        static MEDIA_TYPES: [MediaType<'_>; 8] = [
-            MediaType::new(TEXT, PLAIN),
+        // The canonical media types of all supported formats come first, followed by
+        // aliases that are also understood by `RdfFormat::from_media_type`.
+        static MEDIA_TYPES: [MediaType<'_>; 12] = [
             MediaType::new(APPLICATION, N_QUADS),
             MediaType::new(APPLICATION, N_TRIPLES),
             MediaType::new(APPLICATION, TRIG),
-            MediaType::new(APPLICATION, TURTLE),
-            MediaType::new(APPLICATION, N3),
-            MediaType::new(APPLICATION, XML),
+            MediaType::new(TEXT, TURTLE),
+            MediaType::new(TEXT, N3),
             MediaType::from_parts(
                 APPLICATION,
                 Name::new_unchecked("rdf"),
-                Some(Name::new_unchecked("xml")),
+                Some(XML),
+                &[],
+            ),
+            MediaType::from_parts(
+                APPLICATION,
+                Name::new_unchecked("ld"),
+                Some(JSON),
                 &[],
             ),
+            MediaType::new(TEXT, PLAIN),
+            MediaType::new(APPLICATION, TURTLE),
+            MediaType::new(APPLICATION, Name::new_unchecked("x-turtle")),
+            MediaType::new(APPLICATION, Name::new_unchecked("x-trig")),
+            MediaType::new(APPLICATION, XML),
         ];
         static DEFAULT_MEDIA_TYPE: MediaType<'_> = MediaType::new(APPLICATION, N_QUADS);
 
@@ -41,7 +53,7 @@ impl FromRequestParts<AppState> for RdfFormat {
             accept,
             &MEDIA_TYPES,
             &DEFAULT_MEDIA_TYPE,
-            "application/turtle",
+            "text/turtle or application/n-quads",
         )?;
 
         RdfFormat::from_media_type(media_type.to_string().as_str()).ok_or(
@@ -53,16 +65,17 @@ impl FromRequestParts<AppState> for RdfFormat {
 }
 
 /// Handles the content-negotiation for requests that return query results.
-impl FromRequestParts<AppState> for QueryResultsFormat {
+impl FromRequestParts<AppState> for SolutionsFormat {
     type Rejection = RdfFusionServerError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         _state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        static MEDIA_TYPES: [MediaType<'_>; 8] = [
+        static MEDIA_TYPES: [MediaType<'_>; 11] = [
             MediaType::new(TEXT, PLAIN),
             MediaType::new(TEXT, CSV),
+            MediaType::new(TEXT, Name::new_unchecked("tab-separated-values")),
             MediaType::new(TEXT, Name::new_unchecked("tsv")),
             MediaType::new(APPLICATION, JSON),
             MediaType::from_parts(
@@ -79,6 +92,8 @@ impl FromRequestParts<AppState> for QueryResultsFormat {
             ),
             MediaType::new(APPLICATION, Name::new_unchecked("tab-separated-values")),
             MediaType::new(APPLICATION, XML),
+            MediaType::new(APPLICATION, Name::new_unchecked("x-ndjson")),
+            MediaType::new(APPLICATION, Name::new_unchecked("jsonl")),
         ];
         static DEFAULT_MEDIA_TYPE: MediaType<'_> = MediaType::new(APPLICATION, JSON);
 
@@ -90,11 +105,17 @@ impl FromRequestParts<AppState> for QueryResultsFormat {
             "application/sparql-results+json or text/tsv",
         )?;
 
-        QueryResultsFormat::from_media_type(media_type.to_string().as_str()).ok_or(
-            RdfFusionServerError::BadRequest(format!(
+        if media_type.ty == APPLICATION
+            && matches!(media_type.subty.as_str(), "x-ndjson" | "jsonl")
+        {
+            return Ok(SolutionsFormat::JsonLines);
+        }
+
+        QueryResultsFormat::from_media_type(media_type.to_string().as_str())
+            .map(SolutionsFormat::QueryResults)
+            .ok_or(RdfFusionServerError::BadRequest(format!(
                 "Could not convert negotiated media type '{media_type}' to internal representation."
-            )),
-        )
+            )))
     }
 }
 
@@ -172,4 +193,27 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_content_negotiation_respects_quality_values() {
+        static MEDIA_TYPES: [MediaType<'_>; 2] = [
+            MediaType::new(TEXT, TURTLE),
+            MediaType::new(APPLICATION, N_QUADS),
+        ];
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "accept",
+            HeaderValue::from_static("text/turtle;q=0.9, application/n-quads;q=1.0"),
+        );
+        let accept = headers.typed_get::<Accept>();
+
+        let result = content_negotiation(
+            accept,
+            &MEDIA_TYPES,
+            &DEFAULT_MEDIA_TYPE,
+            "application/n-quads",
+        );
+
+        assert_eq!(result.unwrap(), MediaType::new(APPLICATION, N_QUADS));
+    }
 }
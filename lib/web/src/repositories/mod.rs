@@ -1,7 +1,9 @@
 use crate::AppState;
 use crate::repositories::data::handle_data_post;
-use crate::repositories::query::handle_query_get;
+use crate::repositories::query::{handle_query_get, handle_query_post};
+use crate::repositories::update::handle_update_post;
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::routing::{get, post};
 
 mod content_negotiation;
@@ -9,9 +11,24 @@ mod data;
 mod query;
 mod service_description;
 mod sparql_query_params;
+mod update;
+
+/// Creates the routes of the repositories. Only the body of updates is limited to
+/// `max_update_size` bytes.
+pub fn create_repositories_routes(max_update_size: Option<usize>) -> Router<AppState> {
+    let update_body_limit = match max_update_size {
+        None => DefaultBodyLimit::disable(),
+        Some(limit) => DefaultBodyLimit::max(limit),
+    };
 
-pub fn create_repositories_routes() -> Router<AppState> {
     Router::new()
-        .route("/default/query", get(handle_query_get))
+        .route(
+            "/default/query",
+            get(handle_query_get).post(handle_query_post),
+        )
+        .route(
+            "/default/update",
+            post(handle_update_post).layer(update_body_limit),
+        )
         .route("/default/data", post(handle_data_post))
 }
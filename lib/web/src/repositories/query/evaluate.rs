@@ -1,9 +1,9 @@
 use crate::error::RdfFusionServerError;
 use crate::repositories::query::HandleQueryResponse;
+use crate::repositories::query::SolutionsFormat;
 use crate::repositories::query::results::serialize_query_result;
 use crate::repositories::sparql_query_params::SparqlQueryParams;
 use anyhow::anyhow;
-use rdf_fusion::execution::results::QueryResultsFormat;
 use rdf_fusion::execution::sparql::Query;
 use rdf_fusion::io::RdfFormat;
 use rdf_fusion::model::{GraphName, IriParseError, NamedNode, NamedOrBlankNode};
@@ -15,10 +15,18 @@ pub async fn evaluate_sparql_query(
     params: &SparqlQueryParams,
     query: &str,
     rdf_format: Result<RdfFormat, RdfFusionServerError>,
-    query_format: Result<QueryResultsFormat, RdfFusionServerError>,
+    query_format: Result<SolutionsFormat, RdfFusionServerError>,
 ) -> Result<HandleQueryResponse, RdfFusionServerError> {
-    let mut query = Query::parse(query, Some(params.base_uri.as_str()))
-        .map_err(|e| RdfFusionServerError::BadRequest(e.to_string()))?;
+    let mut query = Query::parse(query, Some(params.base_uri.as_str())).map_err(|e| {
+        if looks_like_update(query) {
+            RdfFusionServerError::BadRequest(format!(
+                "The query endpoint does not accept SPARQL updates. Send updates via POST to \
+                the update endpoint (/repositories/default/update) instead. Parser error: {e}"
+            ))
+        } else {
+            RdfFusionServerError::BadRequest(e.to_string())
+        }
+    })?;
 
     if params.default_graph_as_union {
         query.dataset_mut().set_default_graph_as_union()
@@ -46,7 +54,52 @@ pub async fn evaluate_sparql_query(
         .query_opt(query, params.to_query_options())
         .await
         .map_err(|e| RdfFusionServerError::Internal(anyhow!(e)))?;
+    // Content negotiation errors are only raised once the kind of result is known.
     serialize_query_result(query_result, rdf_format, query_format)
         .await
-        .map_err(|e| RdfFusionServerError::Internal(anyhow!(e)))
+        .map_err(|e| {
+            e.downcast::<RdfFusionServerError>()
+                .unwrap_or_else(RdfFusionServerError::Internal)
+        })
+}
+
+/// The keywords that start an update operation.
+const UPDATE_KEYWORDS: [&str; 10] = [
+    "INSERT", "DELETE", "WITH", "LOAD", "CLEAR", "CREATE", "DROP", "COPY", "MOVE", "ADD",
+];
+
+/// Checks whether `query`, which could not be parsed as a query, is a (possibly malformed)
+/// SPARQL update.
+///
+/// The first keyword after the prologue (`BASE` and `PREFIX` declarations) and comments is
+/// compared with the keywords that start an update operation. As this function is only called
+/// for strings that are not valid queries, variables named like these keywords (e.g.,
+/// `?insert`) cannot cause a valid query to be rejected.
+fn looks_like_update(query: &str) -> bool {
+    let mut rest = query;
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with('#') {
+            rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+            continue;
+        }
+
+        let keyword_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let keyword = &rest[..keyword_len];
+        if keyword.eq_ignore_ascii_case("BASE") || keyword.eq_ignore_ascii_case("PREFIX")
+        {
+            // Skip the declaration up to the end of its IRI.
+            let Some((_, after_iri)) = rest.split_once('>') else {
+                return false;
+            };
+            rest = after_iri;
+            continue;
+        }
+
+        return UPDATE_KEYWORDS
+            .iter()
+            .any(|update_keyword| keyword.eq_ignore_ascii_case(update_keyword));
+    }
 }
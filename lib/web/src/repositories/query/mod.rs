@@ -9,15 +9,22 @@ use crate::repositories::service_description::{
     EndpointKind, generate_service_description,
 };
 use crate::repositories::sparql_query_params::SparqlQueryParams;
-use axum::extract::State;
-use rdf_fusion::execution::results::QueryResultsFormat;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use futures::StreamExt;
 use rdf_fusion::io::RdfFormat;
 
+pub use results::SolutionsFormat;
+
+/// Evaluates a query that is sent via `GET` (`?query=...`).
+///
+/// Queries that are longer than [AppState::max_get_query_length] are rejected with
+/// `414 URI Too Long`. Such queries must be sent via `POST` (see [handle_query_post]).
 pub async fn handle_query_get(
     State(state): State<AppState>,
     query_params: SparqlQueryParams,
     rdf_format: Result<RdfFormat, RdfFusionServerError>,
-    query_format: Result<QueryResultsFormat, RdfFusionServerError>,
+    query_format: Result<SolutionsFormat, RdfFusionServerError>,
 ) -> Result<HandleQueryResponse, RdfFusionServerError> {
     let Some(query) = &query_params.query else {
         return Ok(generate_service_description(
@@ -37,6 +44,85 @@ pub async fn handle_query_get(
         .into());
     }
 
-    evaluate_sparql_query(&state.store, &query_params, query, rdf_format, query_format)
+    if query.len() > state.max_get_query_length {
+        return Err(RdfFusionServerError::UriTooLong(format!(
+            "The query has {} bytes, which exceeds the maximum of {} bytes for GET requests. \
+            Send long queries via POST, either as the body with the content type \
+            application/sparql-query or as the query parameter of an \
+            application/x-www-form-urlencoded form.",
+            query.len(),
+            state.max_get_query_length
+        )));
+    }
+
+    evaluate_limited_sparql_query(&state, &query_params, query, rdf_format, query_format)
         .await
 }
+
+/// Evaluates a query that is sent via `POST`.
+///
+/// The query is either the body of the request (`application/sparql-query`) or the `query`
+/// parameter of a form (`application/x-www-form-urlencoded`). Unlike queries sent via `GET`, the
+/// length of the query is not limited.
+pub async fn handle_query_post(
+    State(state): State<AppState>,
+    rdf_format: Result<RdfFormat, RdfFusionServerError>,
+    query_format: Result<SolutionsFormat, RdfFusionServerError>,
+    request: Request,
+) -> Result<HandleQueryResponse, RdfFusionServerError> {
+    let query_params = SparqlQueryParams::from_post_request(request, &state).await?;
+    let Some(query) = &query_params.query else {
+        return Err(RdfFusionServerError::BadRequest(
+            "Missing query parameter.".to_owned(),
+        ));
+    };
+
+    evaluate_limited_sparql_query(&state, &query_params, query, rdf_format, query_format)
+        .await
+}
+
+/// Evaluates a query while respecting the concurrency limit of the server (see
+/// [AppState::query_limiter]).
+///
+/// The slot of the query is only freed once the (streamed) results have been sent or the client
+/// has disconnected.
+async fn evaluate_limited_sparql_query(
+    state: &AppState,
+    query_params: &SparqlQueryParams,
+    query: &str,
+    rdf_format: Result<RdfFormat, RdfFusionServerError>,
+    query_format: Result<SolutionsFormat, RdfFusionServerError>,
+) -> Result<HandleQueryResponse, RdfFusionServerError> {
+    let Some(query_limiter) = &state.query_limiter else {
+        return evaluate_sparql_query(
+            &state.store,
+            query_params,
+            query,
+            rdf_format,
+            query_format,
+        )
+        .await;
+    };
+
+    let permit = query_limiter.acquire().await?;
+    let response = evaluate_sparql_query(
+        &state.store,
+        query_params,
+        query,
+        rdf_format,
+        query_format,
+    )
+    .await?;
+    Ok(match response {
+        HandleQueryResponse::QueryResults(response) => {
+            HandleQueryResponse::QueryResults(response.map(|body| {
+                // The permit is dropped together with the body stream.
+                Body::from_stream(body.into_data_stream().map(move |chunk| {
+                    let _permit = &permit;
+                    chunk
+                }))
+            }))
+        }
+        response @ HandleQueryResponse::ServiceDescription(_) => response,
+    })
+}
@@ -4,35 +4,46 @@ use crate::error::RdfFusionServerError;
 use crate::repositories::query::results::solutions::serialize_solutions;
 use crate::repositories::service_description::ServiceDescription;
 use anyhow::Context;
+use axum::body::Body;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use futures::StreamExt;
 use rdf_fusion::execution::results::{
-    QueryResults, QueryResultsFormat, QueryResultsSerializer,
+    JSON_LINES_MEDIA_TYPE, QueryResults, QueryResultsFormat, QueryResultsSerializer,
+    json_lines_stream,
 };
 use rdf_fusion::io::{RdfFormat, RdfSerializer};
 
 pub async fn serialize_query_result(
     query_result: QueryResults,
     rdf_format: Result<RdfFormat, RdfFusionServerError>,
-    query_format: Result<QueryResultsFormat, RdfFusionServerError>,
+    query_format: Result<SolutionsFormat, RdfFusionServerError>,
 ) -> anyhow::Result<HandleQueryResponse> {
     let response = match query_result {
-        QueryResults::Solutions(solutions) => {
-            let format = query_format?;
-            let result = serialize_solutions(solutions, format).await?;
-            Response::builder()
+        QueryResults::Solutions(solutions) => match query_format? {
+            SolutionsFormat::QueryResults(format) => {
+                let chunks = serialize_solutions(solutions, format)?;
+                Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", format.media_type())
+                    .body(Body::from_stream(chunks))
+                    .context("Could not build response")
+            }
+            SolutionsFormat::JsonLines => Response::builder()
                 .status(StatusCode::OK)
-                .header("Content-Type", format.media_type())
-                .body(result.into())
-                .context("Could not build response")
-        }
+                .header("Content-Type", JSON_LINES_MEDIA_TYPE)
+                .body(Body::from_stream(json_lines_stream(solutions)))
+                .context("Could not build response"),
+        },
         QueryResults::Boolean(result) => {
             let format = query_format?;
 
             let mut buffer = Vec::new();
-            QueryResultsSerializer::from_format(format)
+            QueryResultsSerializer::from_format(format.query_results_format())
                 .serialize_boolean_to_writer(&mut buffer, result)?;
+            if format == SolutionsFormat::JsonLines {
+                buffer.push(b'\n');
+            }
 
             Response::builder()
                 .header("Content-Type", format.media_type())
@@ -65,6 +76,36 @@ pub async fn serialize_query_result(
     Ok(HandleQueryResponse::from(response))
 }
 
+/// The format of the results of a `SELECT` or `ASK` query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionsFormat {
+    /// One of the standardized SPARQL query results formats.
+    QueryResults(QueryResultsFormat),
+    /// A [JSON Lines](https://jsonlines.org/) document with one solution per line.
+    ///
+    /// The solutions are streamed to the client. The result of an `ASK` query is a single line
+    /// that holds the SPARQL JSON result.
+    JsonLines,
+}
+
+impl SolutionsFormat {
+    /// Returns the media type of the format.
+    pub fn media_type(self) -> &'static str {
+        match self {
+            SolutionsFormat::QueryResults(format) => format.media_type(),
+            SolutionsFormat::JsonLines => JSON_LINES_MEDIA_TYPE,
+        }
+    }
+
+    /// Returns the [QueryResultsFormat] that is used for serializing boolean results.
+    fn query_results_format(self) -> QueryResultsFormat {
+        match self {
+            SolutionsFormat::QueryResults(format) => format,
+            SolutionsFormat::JsonLines => QueryResultsFormat::Json,
+        }
+    }
+}
+
 /// Holds any of the possible responses from a query request.
 pub enum HandleQueryResponse {
     ServiceDescription(ServiceDescription),
@@ -1,6 +1,7 @@
 use anyhow::Context;
 use datafusion::arrow::array::RecordBatch;
-use futures::StreamExt;
+use datafusion::execution::SendableRecordBatchStream;
+use futures::{Stream, StreamExt};
 use rdf_fusion::encoding::plain_term::decoders::DefaultPlainTermDecoder;
 use rdf_fusion::encoding::plain_term::{PLAIN_TERM_ENCODING, PlainTermArray};
 use rdf_fusion::encoding::{TermDecoder, TermEncoding};
@@ -10,33 +11,98 @@ use rdf_fusion::execution::results::{
 };
 use rdf_fusion::execution::sparql::error::QueryEvaluationError;
 use rdf_fusion::model::{TermRef, ThinResult, Variable};
-use std::sync::Arc;
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-/// Serializes `solutions` according to `format`.
-pub async fn serialize_solutions(
+/// Serializes `solutions` according to `format` into a stream of chunks.
+///
+/// Each chunk holds the serialized solutions of a single record batch. The first chunk also
+/// contains the header of the format and the last chunk its footer. A record batch is only
+/// pulled from `solutions` once the previous chunk has been consumed. As a result, a slow client
+/// slows down the query evaluation instead of causing the results to be buffered in memory.
+pub fn serialize_solutions(
     solutions: QuerySolutionStream,
     format: QueryResultsFormat,
-) -> anyhow::Result<Vec<u8>> {
+) -> anyhow::Result<impl Stream<Item = anyhow::Result<Vec<u8>>> + Send + 'static> {
     let variables = solutions.variables().to_vec();
-    let mut buffer = Vec::new();
+    let buffer = SharedBuffer::default();
+    let serializer = QueryResultsSerializer::from_format(format)
+        .serialize_solutions_to_writer(buffer.clone(), variables.clone())?;
+
+    let state = SerializationState {
+        batches: solutions.into_record_batch_stream()?,
+        variables,
+        serializer: Some(serializer),
+        buffer,
+    };
+    Ok(futures::stream::try_unfold(state, next_chunk))
+}
+
+/// The state of the stream returned by [serialize_solutions].
+struct SerializationState {
+    /// The solutions that are yet to be serialized.
+    batches: SendableRecordBatchStream,
+    /// The variables of the solutions.
+    variables: Vec<Variable>,
+    /// The serializer. [None] once the serializer has been finished.
+    serializer: Option<WriterSolutionsSerializer<SharedBuffer>>,
+    /// The buffer that the serializer writes into.
+    buffer: SharedBuffer,
+}
+
+/// Serializes the next record batch (or the footer) and returns the written bytes.
+async fn next_chunk(
+    mut state: SerializationState,
+) -> anyhow::Result<Option<(Vec<u8>, SerializationState)>> {
+    let Some(serializer) = state.serializer.as_mut() else {
+        return Ok(None);
+    };
+
+    match state.batches.next().await {
+        Some(batch) => handle_record_batch(serializer, &state.variables, batch?)?,
+        None => {
+            if let Some(serializer) = state.serializer.take() {
+                serializer
+                    .finish()
+                    .context("Could not finalize serializer")?;
+            }
+        }
+    }
 
-    let mut serializer = QueryResultsSerializer::from_format(format)
-        .serialize_solutions_to_writer(&mut buffer, variables.clone())?;
+    let chunk = state.buffer.take();
+    Ok(Some((chunk, state)))
+}
+
+/// A [Write] implementation whose contents can be taken while the writer is owned by a
+/// serializer.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Takes the bytes that have been written since the last call.
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.lock())
+    }
 
-    let mut solutions = solutions.into_record_batch_stream()?;
-    while let Some(solutions) = solutions.next().await {
-        let solutions = solutions?;
-        handle_record_batch(&mut serializer, &variables, solutions)?;
+    fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+        // The buffer cannot be left in an inconsistent state, so a poisoned lock is fine.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
     }
 
-    serializer
-        .finish()
-        .context("Could not finalize serializer")?;
-    Ok(buffer)
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 fn handle_record_batch(
-    serializer: &mut WriterSolutionsSerializer<&mut Vec<u8>>,
+    serializer: &mut WriterSolutionsSerializer<SharedBuffer>,
     variables: &[Variable],
     record_batch: RecordBatch,
 ) -> anyhow::Result<()> {
@@ -90,7 +156,7 @@ fn get_term_iterators(
 
 /// Serializes a single solution into the `serialiter`.
 fn serialize_solution<'terms>(
-    serializer: &mut WriterSolutionsSerializer<&mut Vec<u8>>,
+    serializer: &mut WriterSolutionsSerializer<SharedBuffer>,
     variables: &[Variable],
     iterators: &mut Vec<impl Iterator<Item = ThinResult<TermRef<'terms>>>>,
 ) -> anyhow::Result<()> {
@@ -1,7 +1,8 @@
 use crate::AppState;
 use crate::error::RdfFusionServerError;
 use axum::RequestPartsExt;
-use axum::extract::{FromRequestParts, Query};
+use axum::extract::{Form, FromRequest, FromRequestParts, Query, Request};
+use axum::http::header::CONTENT_TYPE;
 use axum::http::request::Parts;
 use rdf_fusion::execution::sparql::QueryOptions;
 use serde::Deserialize;
@@ -34,21 +35,54 @@ impl SparqlQueryParams {
     pub fn to_query_options(&self) -> QueryOptions {
         QueryOptions::default()
     }
-}
-
-impl FromRequestParts<AppState> for SparqlQueryParams {
-    type Rejection = RdfFusionServerError;
 
-    async fn from_request_parts(
-        parts: &mut Parts,
+    /// Extracts the parameters of a query that is sent via `POST`.
+    ///
+    /// As defined by the SPARQL protocol, the query is either the body of the request
+    /// (`application/sparql-query`) or a parameter of a form
+    /// (`application/x-www-form-urlencoded`). In the former case, the remaining parameters are
+    /// taken from the URL.
+    pub async fn from_post_request(
+        request: Request,
         state: &AppState,
-    ) -> Result<Self, Self::Rejection> {
-        let raw_params = parts
-            .extract::<Query<SparqlQueryParamsRaw>>()
-            .await
-            .map_err(|e| RdfFusionServerError::BadRequest(e.to_string()))?
-            .0;
+    ) -> Result<Self, RdfFusionServerError> {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
 
+        match content_type.as_str() {
+            "application/sparql-query" => {
+                let (mut parts, body) = request.into_parts();
+                let mut params = Self::from_request_parts(&mut parts, state).await?;
+                let query = String::from_request(Request::from_parts(parts, body), state)
+                    .await
+                    .map_err(|e| RdfFusionServerError::BadRequest(e.to_string()))?;
+                params.query = Some(query);
+                Ok(params)
+            }
+            "application/x-www-form-urlencoded" => {
+                let raw_params = Form::<SparqlQueryParamsRaw>::from_request(request, state)
+                    .await
+                    .map_err(|e| RdfFusionServerError::BadRequest(e.to_string()))?
+                    .0;
+                Self::from_raw(raw_params, state)
+            }
+            _ => Err(RdfFusionServerError::UnsupportedMediaType(
+                "Queries sent via POST must use the content type application/sparql-query or application/x-www-form-urlencoded".to_owned(),
+            )),
+        }
+    }
+
+    /// Validates the `raw_params` and applies the defaults of the server.
+    fn from_raw(
+        raw_params: SparqlQueryParamsRaw,
+        state: &AppState,
+    ) -> Result<Self, RdfFusionServerError> {
         let use_default_graph_as_union = raw_params
             .using_union_graph
             .unwrap_or(state.union_default_graph);
@@ -61,13 +95,28 @@ impl FromRequestParts<AppState> for SparqlQueryParams {
             ));
         }
 
-        let result = SparqlQueryParams {
+        Ok(SparqlQueryParams {
             query: raw_params.query,
             base_uri: "http://localhost:7878".to_owned(), // TODO
             named_graph_uris: raw_params.using_named_graph_uri,
             default_graph_uris: raw_params.using_graph_uri,
             default_graph_as_union: use_default_graph_as_union,
-        };
-        Ok(result)
+        })
+    }
+}
+
+impl FromRequestParts<AppState> for SparqlQueryParams {
+    type Rejection = RdfFusionServerError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let raw_params = parts
+            .extract::<Query<SparqlQueryParamsRaw>>()
+            .await
+            .map_err(|e| RdfFusionServerError::BadRequest(e.to_string()))?
+            .0;
+        Self::from_raw(raw_params, state)
     }
 }
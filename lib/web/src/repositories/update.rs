@@ -0,0 +1,104 @@
+use crate::AppState;
+use crate::error::RdfFusionServerError;
+use anyhow::anyhow;
+use axum::body::Body;
+use axum::extract::{Form, FromRequest, Request, State};
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::Response;
+use rdf_fusion::execution::sparql::UpdateOptions;
+use rdf_fusion::execution::sparql::error::QueryEvaluationError;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct SparqlUpdateParamsRaw {
+    #[serde(default)]
+    update: Option<String>,
+}
+
+/// Executes an update that is sent via `POST`.
+///
+/// As defined by the SPARQL protocol, the update is either the body of the request
+/// (`application/sparql-update`) or a parameter of a form
+/// (`application/x-www-form-urlencoded`). Updates are rejected with `403 Forbidden` if the
+/// server is read-only. `LOAD` may only read files from [AppState::load_directories].
+pub async fn handle_update_post(
+    State(state): State<AppState>,
+    request: Request,
+) -> Result<Response, RdfFusionServerError> {
+    if state.read_only {
+        return Err(RdfFusionServerError::ReadOnly);
+    }
+
+    let content_type = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    let update = match content_type.as_str() {
+        "application/sparql-update" => String::from_request(request, &state)
+            .await
+            .map_err(|e| body_rejection(e.status(), e.body_text()))?,
+        "application/x-www-form-urlencoded" => {
+            Form::<SparqlUpdateParamsRaw>::from_request(request, &state)
+                .await
+                .map_err(|e| body_rejection(e.status(), e.body_text()))?
+                .0
+                .update
+                .ok_or_else(|| {
+                    RdfFusionServerError::BadRequest(
+                        "The update parameter is missing".to_owned(),
+                    )
+                })?
+        }
+        _ => return Err(RdfFusionServerError::UnsupportedMediaType(
+            "Updates sent via POST must use the content type application/sparql-update or application/x-www-form-urlencoded".to_owned(),
+        )),
+    };
+
+    let options = UpdateOptions {
+        load_directories: state.load_directories.to_vec(),
+        ..UpdateOptions::default()
+    };
+    state
+        .store
+        .update_opt(update.as_str(), options)
+        .await
+        .map_err(|error| match error {
+            QueryEvaluationError::Parsing(err) => {
+                RdfFusionServerError::BadRequest(err.to_string())
+            }
+            QueryEvaluationError::GraphAlreadyExists(_)
+            | QueryEvaluationError::GraphDoesNotExist(_)
+            | QueryEvaluationError::LoadNotAllowed(_)
+            | QueryEvaluationError::NotImplemented(_) => {
+                RdfFusionServerError::BadRequest(error.to_string())
+            }
+            // The details of these errors may reveal information about the file system.
+            QueryEvaluationError::GraphParsing(_)
+            | QueryEvaluationError::UnsupportedContentType(_) => {
+                RdfFusionServerError::BadRequest(
+                    "The source of LOAD could not be loaded".to_owned(),
+                )
+            }
+            error => RdfFusionServerError::Internal(anyhow!(error)),
+        })?;
+
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap())
+}
+
+/// Maps the rejection of extracting the request body to an error.
+fn body_rejection(status: StatusCode, message: String) -> RdfFusionServerError {
+    if status == StatusCode::PAYLOAD_TOO_LARGE {
+        RdfFusionServerError::PayloadTooLarge(message)
+    } else {
+        RdfFusionServerError::BadRequest(message)
+    }
+}
@@ -1,10 +1,74 @@
+use crate::config::{ExcessQueryBehavior, QueryConcurrencyLimit};
+use crate::error::RdfFusionServerError;
 use rdf_fusion::store::Store;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 #[derive(Clone)]
 pub struct AppState {
     pub store: Arc<Store>,
-    #[allow(unused, reason = "Not yet implemented")]
+    /// Whether updates are rejected with `403 Forbidden`.
     pub read_only: bool,
     pub union_default_graph: bool,
+    /// Whether the administrative endpoints under `/admin` are available.
+    pub admin: bool,
+    /// The maximum length (in bytes) of a query that is sent via `GET`.
+    pub max_get_query_length: usize,
+    /// The maximum size (in bytes) of an update request body. [None] disables the limit.
+    pub max_update_size: Option<usize>,
+    /// Limits the number of concurrently evaluated queries. [None] disables the limit.
+    pub query_limiter: Option<QueryLimiter>,
+    /// The directories from which updates may `LOAD` files.
+    pub load_directories: Arc<[PathBuf]>,
+}
+
+/// Enforces a [QueryConcurrencyLimit] with a semaphore that is shared by all requests.
+#[derive(Clone)]
+pub struct QueryLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: QueryConcurrencyLimit,
+}
+
+impl QueryLimiter {
+    /// Creates a new [QueryLimiter] that enforces `limit`.
+    pub fn new(limit: QueryConcurrencyLimit) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit.max_concurrent_queries.get())),
+            limit,
+        }
+    }
+
+    /// Acquires a slot for evaluating a query. The slot is freed once the permit is dropped.
+    ///
+    /// Returns [RdfFusionServerError::ServiceUnavailable] if no slot is available, either
+    /// immediately or after waiting for the configured queue timeout.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, RdfFusionServerError> {
+        let max_concurrent_queries = self.limit.max_concurrent_queries;
+        match self.limit.excess_queries {
+            ExcessQueryBehavior::Reject => Arc::clone(&self.semaphore)
+                .try_acquire_owned()
+                .map_err(|_| {
+                    RdfFusionServerError::ServiceUnavailable(format!(
+                        "The server is already evaluating the maximum of \
+                        {max_concurrent_queries} concurrent queries. Retry later."
+                    ))
+                }),
+            ExcessQueryBehavior::Queue { timeout } => {
+                let permit = tokio::time::timeout(
+                    timeout,
+                    Arc::clone(&self.semaphore).acquire_owned(),
+                )
+                .await
+                .map_err(|_| {
+                    RdfFusionServerError::ServiceUnavailable(format!(
+                        "The query could not be started within {timeout:?} as the \
+                                server is already evaluating the maximum of \
+                                {max_concurrent_queries} concurrent queries. Retry later."
+                    ))
+                })?;
+                permit.map_err(|e| RdfFusionServerError::Internal(e.into()))
+            }
+        }
+    }
 }
@@ -465,7 +465,7 @@ impl StaticQueryResults {
                         Ok((bindings, index))
                     })
                     .collect::<Result<Vec<_>>>()?;
-                solutions.sort_by(|(_, index_a), (_, index_b)| index_a.cmp(index_b));
+                solutions.sort_by_key(|(_, index_a)| *index_a);
 
                 let ordered = solutions.iter().all(|(_, index)| index.is_some());
 